@@ -1,9 +1,9 @@
 use crate::elements::{
-    Component, Contract, Event, EventField, FuncInput, FuncOutput, FuncType, Function, JsonAbi,
-    ModuleAbis,
+    Component, Contract, Entry, Error, Event, EventField, FuncInput, FuncOutput, FuncType,
+    Function, JsonAbi, ModuleAbis,
 };
 use crate::AbiError;
-use fe_analyzer::namespace::items::{ContractId, FunctionId, ModuleId};
+use fe_analyzer::namespace::items::{ContractId, FunctionId, Item, ModuleId, StructId, TypeDef};
 use fe_analyzer::namespace::types;
 use fe_analyzer::AnalyzerDb;
 
@@ -28,41 +28,117 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> Result<ModuleAbis, AbiEr
 fn contract_def(db: &dyn AnalyzerDb, contract: ContractId) -> Contract {
     let events = contract
         .events(db)
-        .iter()
-        .map(|(name, eventid)| {
-            let attributes = eventid.typ(db);
-            Event {
-                name: name.to_string(),
-                typ: "event".to_string(),
+        .values()
+        .map(|event| {
+            let attributes = event.typ(db);
+            let entry = Entry::Event(Event {
+                anonymous: false,
                 fields: attributes
                     .fields
                     .iter()
                     .map(|field| {
                         let typ = field.typ.clone().expect("event field type error");
                         EventField {
+                            components: components(db, &typ),
+                            indexed: field.is_indexed,
+                            internal_type: internal_type(&typ),
                             name: field.name.to_string(),
                             typ: typ.abi_json_name(),
-                            indexed: field.is_indexed,
-                            components: components(db, &typ),
                         }
                     })
                     .collect(),
-                anonymous: false,
-            }
+                name: event.name(db).to_string(),
+                typ: "event".to_string(),
+            });
+            (event.data(db).ast.span.start, entry)
         })
-        .collect();
+        .collect::<Vec<_>>();
 
-    let mut functions = contract
+    let functions = contract
         .public_functions(db)
         .iter()
-        .map(|(name, func)| function_def(db, name, *func, FuncType::Function))
+        .map(|(name, func)| (*func, function_def(db, name, *func, FuncType::Function)))
+        .chain(contract.init_function(db).map(|init_fn| {
+            (
+                init_fn,
+                function_def(db, "", init_fn, FuncType::Constructor),
+            )
+        }))
+        .chain(contract.fallback_function(db).map(|fallback_fn| {
+            (
+                fallback_fn,
+                function_def(db, "", fallback_fn, FuncType::Fallback),
+            )
+        }))
+        .chain(contract.receive_function(db).map(|receive_fn| {
+            (
+                receive_fn,
+                function_def(db, "", receive_fn, FuncType::Receive),
+            )
+        }))
+        .map(|(func, function)| (func.span(db).start, Entry::Function(function)))
         .collect::<Vec<_>>();
 
+    let mut entries = [events, functions].concat();
+    entries.sort_by_key(|(position, _)| *position);
+    if contract.is_multicall(db) {
+        entries.push((contract.span(db).end, Entry::Function(multicall_def())));
+    }
+
+    Contract {
+        entries: contract_errors(db, contract)
+            .into_iter()
+            .map(|error| Entry::Error(error_def(db, error)))
+            .chain(entries.into_iter().map(|(_, entry)| entry))
+            .collect(),
+    }
+}
+
+/// The custom errors that the contract's code constructs, in the order that
+/// they're declared. Errors are declared outside of contracts, so they come
+/// before the contract's own entries.
+fn contract_errors(db: &dyn AnalyzerDb, contract: ContractId) -> Vec<StructId> {
+    let mut graphs = vec![
+        contract.dependency_graph(db),
+        contract.runtime_dependency_graph(db),
+    ];
     if let Some(init_fn) = contract.init_function(db) {
-        functions.push(function_def(db, "", init_fn, FuncType::Constructor));
+        graphs.push(init_fn.dependency_graph(db));
     }
 
-    Contract { events, functions }
+    let mut errors = graphs
+        .iter()
+        .flat_map(|graph| graph.nodes())
+        .filter_map(|item| match item {
+            Item::Type(TypeDef::Struct(id)) if id.is_error(db) => Some(id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    errors.sort_by_key(|id| (id.module(db), id.span(db).start));
+    errors.dedup();
+    errors
+}
+
+fn error_def(db: &dyn AnalyzerDb, error: StructId) -> Error {
+    let inputs = error
+        .fields(db)
+        .iter()
+        .map(|(name, field)| {
+            let typ = field.typ(db).expect("error field type error");
+            FuncInput {
+                components: components(db, &typ),
+                internal_type: internal_type(&typ),
+                name: name.to_string(),
+                typ: typ.abi_json_name(),
+            }
+        })
+        .collect();
+
+    Error {
+        inputs,
+        name: error.name(db).to_string(),
+        typ: "error".to_string(),
+    }
 }
 
 fn function_def(db: &dyn AnalyzerDb, name: &str, fn_id: FunctionId, typ: FuncType) -> Function {
@@ -74,9 +150,10 @@ fn function_def(db: &dyn AnalyzerDb, name: &str, fn_id: FunctionId, typ: FuncTyp
             let typ = param.typ.clone().expect("function parameter type error");
 
             FuncInput {
+                components: components(db, &typ),
+                internal_type: internal_type(&typ),
                 name: param.name.to_string(),
                 typ: typ.abi_json_name(),
-                components: components(db, &typ),
             }
         })
         .collect();
@@ -85,18 +162,74 @@ fn function_def(db: &dyn AnalyzerDb, name: &str, fn_id: FunctionId, typ: FuncTyp
     let outputs = if return_type.is_unit() {
         vec![]
     } else {
+        // Multiple named return values are returned as a tuple, so they name
+        // its components.
+        let return_names = fn_id.return_names(db);
+        let mut components = components(db, &return_type);
+        let name = if return_names.len() > 1 {
+            for (component, name) in components.iter_mut().zip(&return_names) {
+                component.name = name.to_string();
+            }
+            String::new()
+        } else {
+            return_names
+                .first()
+                .map_or_else(String::new, |name| name.to_string())
+        };
+
         vec![FuncOutput {
-            name: "".to_string(),
+            components,
+            internal_type: internal_type(&return_type),
+            name,
             typ: return_type.abi_json_name(),
-            components: components(db, &return_type),
         }]
     };
 
     Function {
-        name: name.to_string(),
-        typ,
         inputs,
+        name: name.to_string(),
         outputs,
+        typ,
+    }
+}
+
+/// The generated `multicall(bytes[] data) returns (bytes[])` function of a
+/// `multicall` contract.
+fn multicall_def() -> Function {
+    Function {
+        inputs: vec![FuncInput {
+            components: vec![],
+            internal_type: "bytes[]".to_string(),
+            name: "data".to_string(),
+            typ: "bytes[]".to_string(),
+        }],
+        name: "multicall".to_string(),
+        outputs: vec![FuncOutput {
+            components: vec![],
+            internal_type: "bytes[]".to_string(),
+            name: String::new(),
+            typ: "bytes[]".to_string(),
+        }],
+        typ: FuncType::Function,
+    }
+}
+
+/// The Fe name of a type, for the `internalType` field. Struct, enum and
+/// contract names are prefixed the same way as in solc's output.
+fn internal_type(typ: &types::FixedSize) -> String {
+    match typ {
+        types::FixedSize::Struct(types::Struct { name, .. }) => format!("struct {}", name),
+        types::FixedSize::Enum(types::Enum { name, .. }) => format!("enum {}", name),
+        types::FixedSize::Contract(types::Contract { name, .. }) => format!("contract {}", name),
+        _ => typ.to_string(),
+    }
+}
+
+fn component(name: String, typ: &types::FixedSize) -> Component {
+    Component {
+        internal_type: internal_type(typ),
+        name,
+        typ: typ.abi_json_name(),
     }
 }
 
@@ -105,22 +238,27 @@ fn components(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> Vec<Component> {
         types::FixedSize::Struct(types::Struct { id, .. }) => id
             .fields(db)
             .iter()
-            .map(|(name, field_id)| Component {
-                name: name.to_string(),
-                typ: field_id
-                    .typ(db)
-                    .expect("struct field type error")
-                    .abi_json_name(),
+            .map(|(name, field_id)| {
+                component(
+                    name.to_string(),
+                    &field_id.typ(db).expect("struct field type error"),
+                )
             })
             .collect(),
         types::FixedSize::Tuple(types::Tuple { items }) => items
             .iter()
             .enumerate()
-            .map(|(index, item)| Component {
-                name: format!("item{}", index),
-                typ: item.abi_json_name(),
-            })
+            .map(|(index, item)| component(format!("item{}", index), item))
             .collect(),
+        types::FixedSize::Option(types::FeOption { inner }) => vec![
+            component("item0".to_string(), &types::Base::Bool.into()),
+            component("item1".to_string(), &(*inner).into()),
+        ],
+        types::FixedSize::Result(types::FeResult { ok, err }) => vec![
+            component("item0".to_string(), &types::Base::Bool.into()),
+            component("item1".to_string(), &(*ok).into()),
+            component("item2".to_string(), &(*err).into()),
+        ],
         _ => vec![],
     }
 }
@@ -128,6 +266,7 @@ fn components(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> Vec<Component> {
 #[cfg(test)]
 mod tests {
     use crate::builder;
+    use crate::elements::{Entry, FuncType};
     use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
     use fe_analyzer::{AnalyzerDb, TestDb};
     use fe_common::files::SourceFileId;
@@ -173,19 +312,169 @@ contract Foo:
         let abis = builder::module(&db, module_id).expect("unable to build ABI");
 
         if let Some(abi) = abis.get("Foo") {
-            // event
-            assert_eq!(abi.events[0].name, "Food");
+            // entries are in declaration order
+            assert!(matches!(&abi.entries[0], Entry::Event(event) if event.name == "Food"));
+            assert_eq!(abi.events().next().unwrap().fields[0].internal_type, "u256");
             // function count
-            assert_eq!(abi.functions.len(), 2);
+            let functions = abi.functions().collect::<Vec<_>>();
+            assert_eq!(functions.len(), 2);
+            // __init__
+            assert_eq!(functions[0].name, "");
+            assert_eq!(functions[0].inputs[0].typ, "address",);
             // bar
-            assert_eq!(abi.functions[0].name, "bar",);
-            assert_eq!(abi.functions[0].inputs[0].typ, "uint256",);
-            assert_eq!(abi.functions[0].outputs[0].typ, "uint256[10]",);
-            // __init__ always comes after normal functions
-            assert_eq!(abi.functions[1].name, "");
-            assert_eq!(abi.functions[1].inputs[0].typ, "address",);
+            assert_eq!(functions[1].name, "bar",);
+            assert_eq!(functions[1].inputs[0].typ, "uint256",);
+            assert_eq!(functions[1].outputs[0].typ, "uint256[10]",);
+            assert_eq!(functions[1].outputs[0].internal_type, "Array<u256, 10>",);
         } else {
             panic!("contract \"Foo\" not found in module")
         }
     }
+
+    #[test]
+    fn named_outputs_abi() {
+        let contract = r#"
+contract Vault:
+  pub fn withdraw(amount: u256) -> (sent: u256, ok: bool):
+    sent = amount
+    ok = true
+  pub fn total() -> (supply: u256):
+    supply = 10"#;
+
+        let ast = parse_code_chunk(parse_module, contract)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let abis = builder::module(&db, module_id).expect("unable to build ABI");
+
+        let functions = abis["Vault"].functions().collect::<Vec<_>>();
+        // withdraw
+        let output = &functions[0].outputs[0];
+        assert_eq!(output.name, "");
+        assert_eq!(output.typ, "tuple");
+        let names = output
+            .components
+            .iter()
+            .map(|component| component.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["sent", "ok"]);
+        // total
+        assert_eq!(functions[1].outputs[0].name, "supply");
+        assert_eq!(functions[1].outputs[0].typ, "uint256");
+    }
+
+    #[test]
+    fn errors_abi() {
+        let contract = r#"
+error Unused()
+error InsufficientBalance(available: u256, required: u256)
+
+contract Vault:
+  balance: u256
+  pub fn withdraw(self, amount: u256):
+    if amount > self.balance:
+      revert InsufficientBalance(available=self.balance, required=amount)
+    self.balance -= amount"#;
+
+        let ast = parse_code_chunk(parse_module, contract)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let abis = builder::module(&db, module_id).expect("unable to build ABI");
+
+        // only the errors that the contract uses are listed, before its functions
+        let abi = &abis["Vault"];
+        assert!(
+            matches!(&abi.entries[0], Entry::Error(error) if error.name == "InsufficientBalance")
+        );
+        let errors = abi.errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].typ, "error");
+        let inputs = errors[0]
+            .inputs
+            .iter()
+            .map(|input| (input.name.as_str(), input.typ.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inputs,
+            vec![("available", "uint256"), ("required", "uint256")]
+        );
+    }
+
+    #[test]
+    fn fallback_receive_abi() {
+        let contract = r#"
+contract Wallet:
+  pub fn receive(self):
+    pass
+  pub fn deposit(self):
+    pass
+  pub fn fallback(self):
+    pass"#;
+
+        let ast = parse_code_chunk(parse_module, contract)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let abis = builder::module(&db, module_id).expect("unable to build ABI");
+
+        // `receive` and `fallback` have no selector, so they're listed by type
+        let functions = abis["Wallet"]
+            .functions()
+            .map(|function| (function.name.as_str(), function.typ.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                ("", FuncType::Receive),
+                ("deposit", FuncType::Function),
+                ("", FuncType::Fallback),
+            ]
+        );
+    }
 }
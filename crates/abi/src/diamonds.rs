@@ -0,0 +1,158 @@
+use crate::builder;
+use crate::elements::{FuncType, Function};
+use crate::AbiError;
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::AnalyzerDb;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+/// The selector tables of each diamond in a Fe module, by the name of the
+/// diamond's storage layout struct.
+pub type ModuleSelectorTables = IndexMap<String, SelectorTable>;
+
+/// The public functions of all facets of a diamond, and the facet that
+/// implements each of them. A diamond dispatcher routes calls by this table.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct SelectorTable {
+    /// The facets of the diamond, in declaration order.
+    pub facets: Vec<String>,
+    /// The storage layout struct shared by the facets.
+    pub layout: String,
+    /// The public functions of the facets.
+    pub selectors: Vec<Selector>,
+}
+
+/// A single entry of a selector table.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Selector {
+    /// The facet that implements the function.
+    pub facet: String,
+    /// The function selector, e.g. `0xd09de08a`.
+    pub selector: String,
+    /// The function signature, e.g. `increment()`.
+    pub signature: String,
+}
+
+impl SelectorTable {
+    /// Serialize the selector table into JSON.
+    pub fn json(&self, prettify: bool) -> Result<String, AbiError> {
+        match prettify {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        }
+        .map_err(|_| AbiError::SerializationFailed)
+    }
+}
+
+/// Builds the selector table of each diamond in the module.
+pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> Result<ModuleSelectorTables, AbiError> {
+    let abis = builder::module(db, module)?;
+
+    Ok(module
+        .diamonds(db)
+        .iter()
+        .map(|(layout, facets)| {
+            let facets = facets
+                .iter()
+                .map(|facet| facet.name(db).to_string())
+                .collect::<Vec<_>>();
+            let selectors = facets
+                .iter()
+                .flat_map(|facet| {
+                    abis[facet]
+                        .functions()
+                        .filter(|function| function.typ == FuncType::Function)
+                        .map(move |function| selector(facet, function))
+                })
+                .collect();
+
+            let table = SelectorTable {
+                facets,
+                layout: layout.name(db).to_string(),
+                selectors,
+            };
+            (table.layout.clone(), table)
+        })
+        .collect())
+}
+
+fn selector(facet: &str, function: &Function) -> Selector {
+    Selector {
+        facet: facet.to_string(),
+        selector: function.selector(),
+        signature: function.signature(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diamonds::{self, Selector};
+    use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
+    use fe_analyzer::{AnalyzerDb, TestDb};
+    use fe_common::files::SourceFileId;
+    use fe_parser::{grammar::module::parse_module, parse_code_chunk};
+    use std::rc::Rc;
+
+    #[test]
+    fn selector_table() {
+        let src = r#"
+struct AppStorage:
+  pub admin: address
+  pub count: u256
+
+contract Ownership for AppStorage:
+  admin: address
+  pub fn owner(self) -> address:
+    return self.admin
+
+contract Counter for AppStorage:
+  admin: address
+  count: u256
+  pub fn increment(self):
+    self.count += 1
+
+contract Standalone:
+  pub fn foo():
+    pass"#;
+
+        let ast = parse_code_chunk(parse_module, src)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let tables = diamonds::module(&db, module_id).expect("unable to build selector tables");
+
+        assert_eq!(tables.keys().collect::<Vec<_>>(), vec!["AppStorage"]);
+        let table = &tables["AppStorage"];
+        assert_eq!(table.facets, vec!["Ownership", "Counter"]);
+        assert_eq!(
+            table.selectors,
+            vec![
+                Selector {
+                    facet: "Ownership".to_string(),
+                    selector: "0x8da5cb5b".to_string(),
+                    signature: "owner()".to_string(),
+                },
+                Selector {
+                    facet: "Counter".to_string(),
+                    selector: "0xd09de08a".to_string(),
+                    signature: "increment()".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -1,19 +1,22 @@
 use crate::errors::AbiError;
-use fe_analyzer::namespace::types::{Array, Base, FeString, FixedSize, Integer, Struct, Tuple};
-use serde::ser::SerializeSeq;
-use serde::{Serialize, Serializer};
-use std::collections::HashMap;
+use crate::utils::func_selector;
+use fe_analyzer::namespace::types::{
+    Array, Base, FeOption, FeResult, FeString, FixedSize, Integer, Struct, Tuple,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
 
-/// The ABIs for each contract in a Fe module.
-pub type ModuleAbis = HashMap<String, Contract>;
+/// The ABIs for each contract in a Fe module, in declaration order.
+pub type ModuleAbis = IndexMap<String, Contract>;
 
-/// All public interfaces of a Fe contract.
-#[derive(Debug, PartialEq, Clone)]
+// The fields of the JSON elements below are declared in alphabetical order, so
+// that the keys of the serialized ABI are sorted like in solc's output.
+
+/// All public interfaces of a Fe contract, in declaration order.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+#[serde(transparent)]
 pub struct Contract {
-    /// All events defined in a contract.
-    pub events: Vec<Event>,
-    /// All public functions defined in a contract.
-    pub functions: Vec<Function>,
+    pub entries: Vec<Entry>,
 }
 
 impl Default for Contract {
@@ -24,10 +27,31 @@ impl Default for Contract {
 
 impl Contract {
     pub fn new() -> Self {
-        Self {
-            events: vec![],
-            functions: vec![],
-        }
+        Self { entries: vec![] }
+    }
+
+    /// All events defined in the contract.
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Event(event) => Some(event),
+            Entry::Function(_) | Entry::Error(_) => None,
+        })
+    }
+
+    /// All public functions defined in the contract, including the constructor.
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Function(function) => Some(function),
+            Entry::Event(_) | Entry::Error(_) => None,
+        })
+    }
+
+    /// All custom errors that the contract can revert with.
+    pub fn errors(&self) -> impl Iterator<Item = &Error> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Error(error) => Some(error),
+            Entry::Event(_) | Entry::Function(_) => None,
+        })
     }
 }
 
@@ -42,23 +66,13 @@ impl Contract {
     }
 }
 
-impl Serialize for Contract {
-    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
-    where
-        S: Serializer,
-    {
-        let mut seq = serializer.serialize_seq(None)?;
-
-        for event in self.events.iter() {
-            seq.serialize_element(event)?;
-        }
-
-        for function in self.functions.iter() {
-            seq.serialize_element(function)?;
-        }
-
-        seq.end()
-    }
+/// A single event, function or error interface of a contract.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum Entry {
+    Event(Event),
+    Function(Function),
+    Error(Error),
 }
 
 /// Single component of a tuple.
@@ -84,7 +98,10 @@ impl JsonAbi for FixedSize {
             FixedSize::Tuple(tuple) => tuple.abi_json_name(),
             FixedSize::String(string) => string.abi_json_name(),
             FixedSize::Contract(_) => "address".to_string(),
+            FixedSize::Enum(_) => "uint8".to_string(),
             FixedSize::Struct(val) => val.abi_json_name(),
+            FixedSize::Option(option) => option.abi_json_name(),
+            FixedSize::Result(result) => result.abi_json_name(),
         }
     }
 }
@@ -106,6 +123,7 @@ impl JsonAbi for Base {
             Base::Numeric(Integer::I8) => "int8".to_string(),
             Base::Address => "address".to_string(),
             Base::Bool => "bool".to_string(),
+            Base::FixedBytes(size) => format!("bytes{}", size),
             Base::Unit => panic!("unit type is not abi encodable"),
         }
     }
@@ -133,6 +151,18 @@ impl JsonAbi for Tuple {
     }
 }
 
+impl JsonAbi for FeOption {
+    fn abi_json_name(&self) -> String {
+        "tuple".to_string()
+    }
+}
+
+impl JsonAbi for FeResult {
+    fn abi_json_name(&self) -> String {
+        "tuple".to_string()
+    }
+}
+
 impl JsonAbi for FeString {
     fn abi_json_name(&self) -> String {
         "string".to_string()
@@ -142,52 +172,103 @@ impl JsonAbi for FeString {
 /// An event interface.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Event {
+    /// True if the event was declared as anonymous.
+    pub anonymous: bool,
+    /// All event fields.
+    #[serde(rename = "inputs")]
+    pub fields: Vec<EventField>,
     /// The event's name.
     pub name: String,
     /// The type of an event (Always "event").
     #[serde(rename = "type")]
     pub typ: String,
-    /// All event fields.
-    #[serde(rename = "inputs")]
-    pub fields: Vec<EventField>,
-    /// True if the event was declared as anonymous.
-    pub anonymous: bool,
 }
 
 /// A single event field.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct EventField {
+    /// Components of a tuple. This field is excluded if there are no
+    /// components.
+    #[serde(skip_serializing_if = "should_skip_components")]
+    pub components: Vec<Component>,
+    /// True if the field is part of the log’s topics, false if it is one of the
+    /// log’s data segment.
+    pub indexed: bool,
+    /// The Fe type of the field (e.g. u256, Array<u8, 100>, struct Foo,...)
+    #[serde(rename = "internalType")]
+    pub internal_type: String,
     /// The event field's name.
     pub name: String,
     /// The type of an event (e.g. u256, address, bytes100,...)
     #[serde(rename = "type")]
     pub typ: String,
-    /// True if the field is part of the log’s topics, false if it is one of the
-    /// log’s data segment.
-    pub indexed: bool,
-    /// Components of a tuple. This field is excluded if there are no
-    /// components.
-    #[serde(skip_serializing_if = "should_skip_components")]
-    pub components: Vec<Component>,
+}
+
+/// A custom error interface.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Error {
+    /// All error fields.
+    pub inputs: Vec<FuncInput>,
+    /// The error's name.
+    pub name: String,
+    /// The type of an error (Always "error").
+    #[serde(rename = "type")]
+    pub typ: String,
 }
 
 /// A function interface.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Function {
+    /// All function inputs.
+    pub inputs: Vec<FuncInput>,
     /// The function's name.
     pub name: String,
+    /// All function outputs.
+    pub outputs: Vec<FuncOutput>,
     /// The type of a function (Function, Constructor, Receive, and Fallback)
     #[serde(rename = "type")]
     pub typ: FuncType,
-    /// All function inputs.
-    pub inputs: Vec<FuncInput>,
-    /// All function outputs.
-    pub outputs: Vec<FuncOutput>,
+}
+
+impl Function {
+    /// The signature of the function, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.selector_params().join(","))
+    }
+
+    /// The 4 byte selector of the function, e.g. `0xa9059cbb`.
+    pub fn selector(&self) -> String {
+        func_selector(&self.name, &self.selector_params())
+    }
+
+    fn selector_params(&self) -> Vec<String> {
+        self.inputs.iter().map(selector_type).collect()
+    }
+}
+
+/// The type of an input as it appears in a function signature, where tuples
+/// are written out as their component types, e.g. `(uint256,bool)`.
+fn selector_type(input: &FuncInput) -> String {
+    match input.typ.strip_prefix("tuple") {
+        Some(suffix) => format!(
+            "({}){}",
+            input
+                .components
+                .iter()
+                .map(|component| component.typ.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            suffix
+        ),
+        None => input.typ.clone(),
+    }
 }
 
 /// Component of an ABI tuple.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Component {
+    #[serde(rename = "internalType")]
+    pub internal_type: String,
     pub name: String,
     #[serde(rename = "type")]
     pub typ: String,
@@ -200,6 +281,7 @@ impl From<AbiComponent> for Component {
         }
 
         Self {
+            internal_type: component.typ.clone(),
             name: component.name,
             typ: component.typ,
         }
@@ -209,29 +291,35 @@ impl From<AbiComponent> for Component {
 /// A single function input.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct FuncInput {
+    /// Components of a tuple. This field is excluded if there are no
+    /// components.
+    #[serde(skip_serializing_if = "should_skip_components")]
+    pub components: Vec<Component>,
+    /// The input's Fe type.
+    #[serde(rename = "internalType")]
+    pub internal_type: String,
     /// The input's name.
     pub name: String,
     /// The input's type.
     #[serde(rename = "type")]
     pub typ: String,
-    /// Components of a tuple. This field is excluded if there are no
-    /// components.
-    #[serde(skip_serializing_if = "should_skip_components")]
-    pub components: Vec<Component>,
 }
 
 /// A single function output.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct FuncOutput {
+    /// Components of a tuple. This field is excluded if there are no
+    /// components.
+    #[serde(skip_serializing_if = "should_skip_components")]
+    pub components: Vec<Component>,
+    /// The output's Fe type.
+    #[serde(rename = "internalType")]
+    pub internal_type: String,
     /// The output's name.
     pub name: String,
     /// The output's type.
     #[serde(rename = "type")]
     pub typ: String,
-    /// Components of a tuple. This field is excluded if there are no
-    /// components.
-    #[serde(skip_serializing_if = "should_skip_components")]
-    pub components: Vec<Component>,
 }
 
 fn should_skip_components(components: &[Component]) -> bool {
@@ -239,7 +327,6 @@ fn should_skip_components(components: &[Component]) -> bool {
 }
 
 /// The type of a public function.
-#[allow(dead_code)]
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum FuncType {
@@ -262,58 +349,66 @@ pub enum StateMutability {
 
 #[cfg(test)]
 mod tests {
-    use crate::elements::{Contract, Event, EventField, FuncInput, FuncOutput, FuncType, Function};
+    use crate::elements::{
+        Contract, Entry, Event, EventField, FuncInput, FuncOutput, FuncType, Function,
+    };
 
     #[test]
     fn contract_json() {
         let contract = Contract {
-            events: vec![Event {
-                name: "event_name".to_string(),
-                typ: "event".to_string(),
-                fields: vec![EventField {
-                    name: "input_name".to_string(),
-                    typ: "uint256".to_string(),
-                    indexed: true,
-                    components: vec![],
-                }],
-                anonymous: false,
-            }],
-            functions: vec![Function {
-                name: "function_name".to_string(),
-                typ: FuncType::Function,
-                inputs: vec![FuncInput {
-                    name: "input_name".to_string(),
-                    typ: "address".to_string(),
-                    components: vec![],
-                }],
-                outputs: vec![FuncOutput {
-                    name: "output_name".to_string(),
-                    typ: "uint256".to_string(),
-                    components: vec![],
-                }],
-            }],
+            entries: vec![
+                Entry::Event(Event {
+                    anonymous: false,
+                    fields: vec![EventField {
+                        components: vec![],
+                        indexed: true,
+                        internal_type: "u256".to_string(),
+                        name: "input_name".to_string(),
+                        typ: "uint256".to_string(),
+                    }],
+                    name: "event_name".to_string(),
+                    typ: "event".to_string(),
+                }),
+                Entry::Function(Function {
+                    inputs: vec![FuncInput {
+                        components: vec![],
+                        internal_type: "address".to_string(),
+                        name: "input_name".to_string(),
+                        typ: "address".to_string(),
+                    }],
+                    name: "function_name".to_string(),
+                    outputs: vec![FuncOutput {
+                        components: vec![],
+                        internal_type: "u256".to_string(),
+                        name: "output_name".to_string(),
+                        typ: "uint256".to_string(),
+                    }],
+                    typ: FuncType::Function,
+                }),
+            ],
         };
 
         assert_eq!(
             contract.json(false).unwrap(),
             r#"[
                 {
-                    "name":"event_name",
-                    "type":"event",
+                    "anonymous":false,
                     "inputs":[
                         {
+                            "indexed":true,
+                            "internalType":"u256",
                             "name":"input_name",
-                            "type":"uint256",
-                            "indexed":true
+                            "type":"uint256"
                         }
                     ],
-                    "anonymous":false
+                    "name":"event_name",
+                    "type":"event"
                 },
                 {
+                    "inputs":[{"internalType":"address","name":"input_name","type":"address"}],
                     "name":"function_name",
-                    "type":"function",
-                    "inputs":[{"name":"input_name","type":"address"}],
-                    "outputs":[{"name":"output_name","type":"uint256"}]
+                    "outputs":[{"internalType":"u256","name":"output_name","type":"uint256"}],
+                    "type":"function"
                 }
             ]"#
             .split_whitespace()
@@ -2,4 +2,6 @@
 pub enum AbiError {
     DuplicateContractDefinition(String),
     SerializationFailed,
+    /// A type that can't be expressed in the requested output.
+    UnsupportedType(String),
 }
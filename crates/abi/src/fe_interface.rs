@@ -0,0 +1,252 @@
+use fe_analyzer::namespace::items::{ContractId, ModuleId, TypeDef};
+use fe_analyzer::namespace::types::{Enum, FixedSize, Struct};
+use fe_analyzer::AnalyzerDb;
+use indexmap::IndexMap;
+use std::fmt::Write;
+
+/// The interfaces of the contracts in a module, by contract name.
+pub type ModuleInterfaces = IndexMap<String, String>;
+
+/// Generates an interface of each contract in the module, in Fe. An interface
+/// is a contract with the events and public functions of the original, whose
+/// functions only `revert`, and the structs and enums that they use. Other
+/// projects can call a deployed contract through it, eg.
+/// `Token(address).transfer(to, 10)`, without its source.
+pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ModuleInterfaces {
+    module
+        .all_contracts(db)
+        .iter()
+        .map(|contract| {
+            (
+                contract.name(db).to_string(),
+                contract_interface(db, *contract),
+            )
+        })
+        .collect()
+}
+
+fn contract_interface(db: &dyn AnalyzerDb, contract: ContractId) -> String {
+    let mut types = vec![];
+    let mut members = vec![];
+
+    for event in contract.events(db).values() {
+        let mut member = format!("    event {}:\n", event.name(db));
+        for field in &event.typ(db).fields {
+            let typ = field.typ.clone().expect("event field type error");
+            let idx = if field.is_indexed { "idx " } else { "" };
+            let _ = writeln!(
+                member,
+                "        {}{}: {}",
+                idx,
+                field.name,
+                type_name(db, &typ, &mut types)
+            );
+        }
+        members.push((event.data(db).ast.span.start, member));
+    }
+
+    for (name, function) in contract.public_functions(db).iter() {
+        let sig = function.signature(db);
+        let mut params = vec![];
+        if function.takes_self(db) {
+            params.push("self".to_string());
+        }
+        for param in &sig.params {
+            let typ = param.typ.clone().expect("function parameter type error");
+            params.push(format!(
+                "{}: {}",
+                param.name,
+                type_name(db, &typ, &mut types)
+            ));
+        }
+        let return_type = sig.return_type.clone().expect("function return type error");
+        let returns = if return_type.is_unit() {
+            String::new()
+        } else {
+            format!(" -> {}", type_name(db, &return_type, &mut types))
+        };
+        let member = format!(
+            "    pub fn {}({}){}:\n        revert\n",
+            name,
+            params.join(", "),
+            returns
+        );
+        members.push((function.span(db).start, member));
+    }
+    members.sort_by_key(|(position, _)| *position);
+
+    let name = contract.name(db);
+    let mut source = format!(
+        "# The interface of the contract `{}`, generated by the Fe compiler. Its\n# functions are run by the deployed contract.\n\n",
+        name
+    );
+    // The fields of a struct may add more types, which are declared after
+    // it.
+    let mut index = 0;
+    while index < types.len() {
+        match types[index] {
+            TypeDef::Struct(id) if id.is_error(db) => {
+                let fields = id
+                    .fields(db)
+                    .iter()
+                    .map(|(field_name, field)| {
+                        let typ = field.typ(db).expect("error field type error");
+                        format!("{}: {}", field_name, type_name(db, &typ, &mut types))
+                    })
+                    .collect::<Vec<_>>();
+                let _ = writeln!(source, "error {}({})", id.name(db), fields.join(", "));
+            }
+            TypeDef::Struct(id) => {
+                let _ = writeln!(source, "struct {}:", id.name(db));
+                for (field_name, field) in id.fields(db).iter() {
+                    let typ = field.typ(db).expect("struct field type error");
+                    let _ = writeln!(
+                        source,
+                        "    pub {}: {}",
+                        field_name,
+                        type_name(db, &typ, &mut types)
+                    );
+                }
+            }
+            TypeDef::Enum(id) => {
+                let variants = id.variants(db).keys().cloned().collect::<Vec<_>>();
+                let _ = writeln!(source, "enum {}: {}", id.name(db), variants.join(", "));
+            }
+            _ => unreachable!("only structs and enums are declared"),
+        }
+        source += "\n";
+        index += 1;
+    }
+    let _ = writeln!(source, "contract {}:", name);
+    let members = members
+        .into_iter()
+        .map(|(_, member)| member)
+        .collect::<Vec<_>>();
+    if members.is_empty() {
+        source += "    pass\n";
+    }
+    source += &members.join("\n");
+    source
+}
+
+/// The Fe name of a type, adding the structs and enums it uses to `types`.
+/// Contract types are replaced with `address`, which has the same ABI, so that
+/// the interface doesn't need their interfaces too.
+fn type_name(db: &dyn AnalyzerDb, typ: &FixedSize, types: &mut Vec<TypeDef>) -> String {
+    let declared = match typ {
+        FixedSize::Contract(_) => return "address".to_string(),
+        FixedSize::Struct(Struct { id, .. }) => TypeDef::Struct(*id),
+        FixedSize::Enum(Enum { id, .. }) => TypeDef::Enum(*id),
+        FixedSize::Tuple(tuple) => {
+            let items = tuple
+                .items
+                .iter()
+                .map(|item| type_name(db, item, types))
+                .collect::<Vec<_>>();
+            return format!("({})", items.join(", "));
+        }
+        _ => return typ.to_string(),
+    };
+    if !types.contains(&declared) {
+        types.push(declared);
+    }
+    typ.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fe_interface;
+    use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
+    use fe_analyzer::{AnalyzerDb, TestDb};
+    use fe_common::files::SourceFileId;
+    use fe_parser::{grammar::module::parse_module, parse_code_chunk};
+    use std::rc::Rc;
+
+    fn interfaces(src: &str) -> fe_interface::ModuleInterfaces {
+        let ast = parse_code_chunk(parse_module, src)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        fe_interface::module(&db, module_id)
+    }
+
+    #[test]
+    fn contract_interfaces() {
+        let generated = interfaces(
+            r#"
+struct Point:
+  pub x: u256
+  pub y: u256
+
+struct Unused:
+  pub z: u256
+
+contract Other:
+  pub fn ping():
+    pass
+
+contract Shapes:
+  points: Map<address, Point>
+
+  event Moved:
+    idx owner: address
+    to: Point
+
+  pub fn __init__(self):
+    pass
+
+  pub fn origin() -> Point:
+    return Point(x=0, y=0)
+
+  fn private_helper():
+    pass
+
+  pub fn bounds(self, points: Array<u256, 4>) -> (u256, bool):
+    return (points[0], true)
+
+  pub fn reset(self, other: Other):
+    pass"#,
+        );
+
+        let expected = "# The interface of the contract `Shapes`, generated by the Fe compiler. Its
+# functions are run by the deployed contract.
+
+struct Point:
+    pub x: u256
+    pub y: u256
+
+contract Shapes:
+    event Moved:
+        idx owner: address
+        to: Point
+
+    pub fn origin() -> Point:
+        revert
+
+    pub fn bounds(self, points: Array<u256, 4>) -> (u256, bool):
+        revert
+
+    pub fn reset(self, other: address):
+        revert
+";
+        assert_eq!(generated["Shapes"], expected);
+
+        // The interface is valid Fe with the same interface.
+        assert_eq!(interfaces(expected)["Shapes"], expected);
+    }
+}
@@ -1,29 +1,60 @@
 //! Fe to ABI builder.
 
+use crate::elements::ModuleAbis;
 use fe_analyzer::namespace::items::ModuleId;
 use fe_analyzer::AnalyzerDb;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 mod builder;
 pub mod utils;
 
+/// Checks of contract ABIs against standard interfaces like ERC-20.
+pub mod standards;
+
 /// Elements used to define contract ABIs.
 pub mod elements;
 
+/// Selector tables of diamonds, whose facets share a storage layout.
+pub mod diamonds;
+
+/// Solidity interfaces of contract ABIs.
+pub mod solidity;
+
+/// Interfaces of contracts in Fe, for calling them from other projects.
+pub mod fe_interface;
+
 mod errors;
 pub use errors::AbiError;
 
-/// A mapping of contract names and their ABIs.
-pub type NamedAbis = HashMap<ContractName, JsonAbi>;
+/// A mapping of contract names and their ABIs, in declaration order.
+pub type NamedAbis = IndexMap<ContractName, JsonAbi>;
 /// The ABI of a contract as a string.
 pub type JsonAbi = String;
 /// The name of a Fe contract.
 pub type ContractName = String;
 
+/// Builds the ABI elements of each contract in the module.
+pub fn contracts(db: &dyn AnalyzerDb, module: ModuleId) -> Result<ModuleAbis, AbiError> {
+    builder::module(db, module)
+}
+
+/// Builds the selector table of each diamond in the module.
+pub fn diamonds(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+) -> Result<diamonds::ModuleSelectorTables, AbiError> {
+    diamonds::module(db, module)
+}
+
+/// Generates the interface of each contract in the module, in Fe.
+pub fn fe_interfaces(db: &dyn AnalyzerDb, module: ModuleId) -> fe_interface::ModuleInterfaces {
+    fe_interface::module(db, module)
+}
+
 /// Builds ABIs for each contract in the module.
 pub fn build(db: &dyn AnalyzerDb, module: ModuleId) -> Result<NamedAbis, AbiError> {
     builder::module(db, module)?
-        .drain()
+        .into_iter()
         .map(|(name, abi)| abi.json(true).map(|json| (name, json)))
         .collect::<Result<NamedAbis, _>>()
 }
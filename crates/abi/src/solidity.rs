@@ -0,0 +1,245 @@
+use crate::elements::{Component, Contract, FuncType};
+use crate::AbiError;
+use std::fmt::Write;
+
+/// The Solidity pragma of generated interfaces. Older versions can't return
+/// structs without the ABI coder v2 pragma.
+const PRAGMA: &str = "pragma solidity >=0.8.0;";
+
+/// Custom errors were added in Solidity 0.8.4.
+const ERRORS_PRAGMA: &str = "pragma solidity >=0.8.4;";
+
+/// Generates a Solidity file with an `interface` that matches the public
+/// functions, events and errors of a contract's ABI, so that Solidity
+/// contracts can call it.
+///
+/// The constructor is left out, as interfaces can't declare one. Tuple types,
+/// which are Fe structs, tuples, options and results, are declared as structs
+/// in the interface. Fe structs keep their names, and the other tuples are
+/// named `Tuple0`, `Tuple1` and so on.
+pub fn interface(name: &str, contract: &Contract) -> Result<String, AbiError> {
+    let mut structs = Structs::default();
+    let mut members = vec![];
+
+    for error in contract.errors() {
+        let fields = error
+            .inputs
+            .iter()
+            .map(|input| {
+                let typ = structs.typ(&input.typ, &input.internal_type, &input.components)?;
+                Ok(format!("{} {}", typ, input.name))
+            })
+            .collect::<Result<Vec<_>, AbiError>>()?;
+        members.push(format!("error {}({});", error.name, fields.join(", ")));
+    }
+
+    for event in contract.events() {
+        let fields = event
+            .fields
+            .iter()
+            .map(|field| {
+                let typ = structs.typ(&field.typ, &field.internal_type, &field.components)?;
+                let indexed = if field.indexed { " indexed" } else { "" };
+                Ok(format!("{}{} {}", typ, indexed, field.name))
+            })
+            .collect::<Result<Vec<_>, AbiError>>()?;
+        members.push(format!("event {}({});", event.name, fields.join(", ")));
+    }
+
+    for function in contract
+        .functions()
+        .filter(|function| function.typ == FuncType::Function)
+    {
+        let inputs = function
+            .inputs
+            .iter()
+            .map(|input| {
+                let typ = structs.typ(&input.typ, &input.internal_type, &input.components)?;
+                Ok(param(&input.typ, typ, "calldata", &input.name))
+            })
+            .collect::<Result<Vec<_>, AbiError>>()?;
+        let outputs = function
+            .outputs
+            .iter()
+            .map(|output| {
+                let typ = structs.typ(&output.typ, &output.internal_type, &output.components)?;
+                Ok(param(&output.typ, typ, "memory", &output.name))
+            })
+            .collect::<Result<Vec<_>, AbiError>>()?;
+
+        let mut member = format!("function {}({}) external", function.name, inputs.join(", "));
+        if !outputs.is_empty() {
+            member += &format!(" returns ({})", outputs.join(", "));
+        }
+        members.push(member + ";");
+    }
+
+    let pragma = if contract.errors().next().is_some() {
+        ERRORS_PRAGMA
+    } else {
+        PRAGMA
+    };
+    let mut source = format!(
+        "// The interface of the Fe contract `{}`, generated by the Fe compiler.\n{}\n\ninterface {} {{\n",
+        name, pragma, name
+    );
+    for declared in &structs.declared {
+        let _ = writeln!(source, "    struct {} {{", declared.name);
+        for field in &declared.fields {
+            let _ = writeln!(source, "        {} {};", field.typ, field.name);
+        }
+        source += "    }\n\n";
+    }
+    for member in members {
+        let _ = writeln!(source, "    {}", member);
+    }
+    source += "}\n";
+    Ok(source)
+}
+
+/// The structs that tuple types are declared as, in order of first use.
+#[derive(Default)]
+struct Structs {
+    declared: Vec<DeclaredStruct>,
+    tuple_count: usize,
+}
+
+struct DeclaredStruct {
+    name: String,
+    internal_type: String,
+    fields: Vec<Component>,
+}
+
+impl Structs {
+    /// The Solidity type of an ABI type, declaring a struct for it if it's a
+    /// tuple.
+    fn typ(
+        &mut self,
+        typ: &str,
+        internal_type: &str,
+        components: &[Component],
+    ) -> Result<String, AbiError> {
+        if typ != "tuple" {
+            return Ok(typ.to_string());
+        }
+        if let Some(component) = components.iter().find(|component| component.typ == "tuple") {
+            return Err(AbiError::UnsupportedType(format!(
+                "`{}` has a nested tuple `{}`",
+                internal_type, component.name
+            )));
+        }
+
+        if let Some(declared) = self.declared.iter().find(|declared| {
+            declared.internal_type == internal_type && declared.fields.as_slice() == components
+        }) {
+            return Ok(declared.name.clone());
+        }
+        let name = match internal_type.strip_prefix("struct ") {
+            Some(name) => name.to_string(),
+            None => {
+                self.tuple_count += 1;
+                format!("Tuple{}", self.tuple_count - 1)
+            }
+        };
+        self.declared.push(DeclaredStruct {
+            name: name.clone(),
+            internal_type: internal_type.to_string(),
+            fields: components.to_vec(),
+        });
+        Ok(name)
+    }
+}
+
+/// A function parameter of the Solidity type `typ`, with a data location if
+/// its ABI type `abi_typ` is a reference type.
+fn param(abi_typ: &str, typ: String, location: &str, name: &str) -> String {
+    let is_reference = matches!(abi_typ, "string" | "bytes" | "tuple") || abi_typ.ends_with(']');
+    let mut param = typ;
+    if is_reference {
+        param = format!("{} {}", param, location);
+    }
+    if !name.is_empty() {
+        param = format!("{} {}", param, name);
+    }
+    param
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder;
+    use crate::solidity::interface;
+    use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
+    use fe_analyzer::{AnalyzerDb, TestDb};
+    use fe_common::files::SourceFileId;
+    use fe_parser::{grammar::module::parse_module, parse_code_chunk};
+    use std::rc::Rc;
+
+    #[test]
+    fn solidity_interface() {
+        let contract = r#"
+struct Point:
+  pub x: u256
+  pub y: u256
+
+contract Shapes:
+  event Moved:
+    idx owner: address
+    to: Point
+  pub fn __init__(owner: address):
+    pass
+  pub fn origin() -> Point:
+    return Point(x=0, y=0)
+  pub fn name(id: u256) -> String<10>:
+    return "shape"
+  pub fn bounds(points: Array<u256, 4>) -> (u256, bool):
+    return (points[0], true)
+  pub fn reset():
+    pass"#;
+
+        let ast = parse_code_chunk(parse_module, contract)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let abis = builder::module(&db, module_id).expect("unable to build ABI");
+
+        assert_eq!(
+            interface("Shapes", &abis["Shapes"]).unwrap(),
+            "// The interface of the Fe contract `Shapes`, generated by the Fe compiler.
+pragma solidity >=0.8.0;
+
+interface Shapes {
+    struct Point {
+        uint256 x;
+        uint256 y;
+    }
+
+    struct Tuple0 {
+        uint256 item0;
+        bool item1;
+    }
+
+    event Moved(address indexed owner, Point to);
+    function origin() external returns (Point memory);
+    function name(uint256 id) external returns (string memory);
+    function bounds(uint256[4] calldata points) external returns (Tuple0 memory);
+    function reset() external;
+}
+"
+        );
+    }
+}
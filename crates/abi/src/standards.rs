@@ -0,0 +1,398 @@
+use crate::elements::{Contract, Event, FuncType, Function};
+use crate::utils::func_selector;
+use std::fmt;
+use std::str::FromStr;
+
+/// A standard contract interface that a contract ABI can be checked against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Standard {
+    Erc20,
+    Erc721,
+    Erc1155,
+}
+
+impl FromStr for Standard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "").as_str() {
+            "erc20" => Ok(Standard::Erc20),
+            "erc721" => Ok(Standard::Erc721),
+            "erc1155" => Ok(Standard::Erc1155),
+            _ => Err(format!("unknown standard `{}`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Standard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Standard::Erc20 => "ERC-20",
+            Standard::Erc721 => "ERC-721",
+            Standard::Erc1155 => "ERC-1155",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A function of a standard interface.
+struct FunctionSig {
+    name: &'static str,
+    inputs: &'static [&'static str],
+    outputs: &'static [&'static str],
+}
+
+/// An event of a standard interface. Each field is a type and whether it is
+/// indexed.
+struct EventSig {
+    name: &'static str,
+    fields: &'static [(&'static str, bool)],
+}
+
+macro_rules! func {
+    ($name:literal ($($input:literal),*) -> ($($output:literal),*)) => {
+        FunctionSig {
+            name: $name,
+            inputs: &[$($input),*],
+            outputs: &[$($output),*],
+        }
+    };
+}
+
+macro_rules! event {
+    ($name:literal ($($typ:literal $indexed:literal),*)) => {
+        EventSig {
+            name: $name,
+            fields: &[$(($typ, $indexed)),*],
+        }
+    };
+}
+
+const ERC20_FUNCTIONS: &[FunctionSig] = &[
+    func!("totalSupply"() -> ("uint256")),
+    func!("balanceOf"("address") -> ("uint256")),
+    func!("transfer"("address", "uint256") -> ("bool")),
+    func!("transferFrom"("address", "address", "uint256") -> ("bool")),
+    func!("approve"("address", "uint256") -> ("bool")),
+    func!("allowance"("address", "address") -> ("uint256")),
+];
+
+const ERC20_EVENTS: &[EventSig] = &[
+    event!("Transfer"("address" true, "address" true, "uint256" false)),
+    event!("Approval"("address" true, "address" true, "uint256" false)),
+];
+
+const ERC721_FUNCTIONS: &[FunctionSig] = &[
+    func!("balanceOf"("address") -> ("uint256")),
+    func!("ownerOf"("uint256") -> ("address")),
+    func!("safeTransferFrom"("address", "address", "uint256", "bytes") -> ()),
+    func!("safeTransferFrom"("address", "address", "uint256") -> ()),
+    func!("transferFrom"("address", "address", "uint256") -> ()),
+    func!("approve"("address", "uint256") -> ()),
+    func!("setApprovalForAll"("address", "bool") -> ()),
+    func!("getApproved"("uint256") -> ("address")),
+    func!("isApprovedForAll"("address", "address") -> ("bool")),
+];
+
+const ERC721_EVENTS: &[EventSig] = &[
+    event!("Transfer"("address" true, "address" true, "uint256" true)),
+    event!("Approval"("address" true, "address" true, "uint256" true)),
+    event!("ApprovalForAll"("address" true, "address" true, "bool" false)),
+];
+
+const ERC1155_FUNCTIONS: &[FunctionSig] = &[
+    func!("safeTransferFrom"("address", "address", "uint256", "uint256", "bytes") -> ()),
+    func!("safeBatchTransferFrom"(
+        "address",
+        "address",
+        "uint256[]",
+        "uint256[]",
+        "bytes"
+    ) -> ()),
+    func!("balanceOf"("address", "uint256") -> ("uint256")),
+    func!("balanceOfBatch"("address[]", "uint256[]") -> ("uint256[]")),
+    func!("setApprovalForAll"("address", "bool") -> ()),
+    func!("isApprovedForAll"("address", "address") -> ("bool")),
+];
+
+const ERC1155_EVENTS: &[EventSig] = &[
+    event!("TransferSingle"(
+        "address" true,
+        "address" true,
+        "address" true,
+        "uint256" false,
+        "uint256" false
+    )),
+    event!("TransferBatch"(
+        "address" true,
+        "address" true,
+        "address" true,
+        "uint256[]" false,
+        "uint256[]" false
+    )),
+    event!("ApprovalForAll"("address" true, "address" true, "bool" false)),
+    event!("URI"("string" false, "uint256" true)),
+];
+
+impl Standard {
+    fn functions(self) -> &'static [FunctionSig] {
+        match self {
+            Standard::Erc20 => ERC20_FUNCTIONS,
+            Standard::Erc721 => ERC721_FUNCTIONS,
+            Standard::Erc1155 => ERC1155_FUNCTIONS,
+        }
+    }
+
+    fn events(self) -> &'static [EventSig] {
+        match self {
+            Standard::Erc20 => ERC20_EVENTS,
+            Standard::Erc721 => ERC721_EVENTS,
+            Standard::Erc1155 => ERC1155_EVENTS,
+        }
+    }
+}
+
+/// A difference between a contract's ABI and a standard interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The contract has no function with the expected name.
+    MissingFunction { expected: String, selector: String },
+    /// The contract has a function with the expected name, but a different
+    /// signature.
+    FunctionSignature { expected: String, actual: String },
+    /// The contract has no event with the expected name.
+    MissingEvent { expected: String },
+    /// The contract has an event with the expected name, but different fields.
+    EventSignature { expected: String, actual: String },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::MissingFunction { expected, selector } => {
+                write!(f, "missing function `{}` (selector {})", expected, selector)
+            }
+            Mismatch::FunctionSignature { expected, actual } => {
+                write!(f, "function `{}` should be `{}`", actual, expected)
+            }
+            Mismatch::MissingEvent { expected } => write!(f, "missing event `{}`", expected),
+            Mismatch::EventSignature { expected, actual } => {
+                write!(f, "event `{}` should be `{}`", actual, expected)
+            }
+        }
+    }
+}
+
+/// Compares the public functions and events of a contract against a standard
+/// interface. Functions are matched by selector and must also have the
+/// expected return types; events are matched by topic and must index the
+/// expected fields.
+///
+/// State mutability is not checked, since Fe ABIs don't specify it yet.
+pub fn check(contract: &Contract, standard: Standard) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+
+    let functions = contract
+        .functions()
+        .filter(|function| function.typ == FuncType::Function)
+        .collect::<Vec<_>>();
+    let expected_signatures = standard
+        .functions()
+        .iter()
+        .map(expected_function_signature)
+        .collect::<Vec<_>>();
+
+    for (expected, expected_signature) in standard.functions().iter().zip(&expected_signatures) {
+        let same_name = functions
+            .iter()
+            .filter(|function| function.name == expected.name)
+            .map(|function| function_signature(function))
+            .collect::<Vec<_>>();
+
+        if same_name.contains(expected_signature) {
+            continue;
+        }
+        // A function that matches another overload of the standard isn't a
+        // mismatch of this one.
+        match same_name
+            .into_iter()
+            .find(|actual| !expected_signatures.contains(actual))
+        {
+            Some(actual) => mismatches.push(Mismatch::FunctionSignature {
+                expected: expected_signature.clone(),
+                actual,
+            }),
+            None => mismatches.push(Mismatch::MissingFunction {
+                expected: expected_signature.clone(),
+                selector: func_selector(
+                    expected.name,
+                    &expected
+                        .inputs
+                        .iter()
+                        .map(|input| input.to_string())
+                        .collect::<Vec<_>>(),
+                ),
+            }),
+        }
+    }
+
+    let events = contract.events().collect::<Vec<_>>();
+    for expected in standard.events() {
+        let expected_signature = expected_event_signature(expected);
+        match events.iter().find(|event| event.name == expected.name) {
+            Some(event) => {
+                let actual = event_signature(event);
+                if actual != expected_signature {
+                    mismatches.push(Mismatch::EventSignature {
+                        expected: expected_signature,
+                        actual,
+                    })
+                }
+            }
+            None => mismatches.push(Mismatch::MissingEvent {
+                expected: expected_signature,
+            }),
+        }
+    }
+
+    mismatches
+}
+
+fn format_function(name: &str, inputs: &[&str], outputs: &[&str]) -> String {
+    if outputs.is_empty() {
+        format!("{}({})", name, inputs.join(","))
+    } else {
+        format!(
+            "{}({}) returns ({})",
+            name,
+            inputs.join(","),
+            outputs.join(",")
+        )
+    }
+}
+
+fn expected_function_signature(function: &FunctionSig) -> String {
+    format_function(function.name, function.inputs, function.outputs)
+}
+
+fn function_signature(function: &Function) -> String {
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|input| input.typ.as_str())
+        .collect::<Vec<_>>();
+    let outputs = function
+        .outputs
+        .iter()
+        .map(|output| output.typ.as_str())
+        .collect::<Vec<_>>();
+    format_function(&function.name, &inputs, &outputs)
+}
+
+fn format_event<'a>(name: &str, fields: impl Iterator<Item = (&'a str, bool)>) -> String {
+    let fields = fields
+        .map(|(typ, indexed)| {
+            if indexed {
+                format!("{} indexed", typ)
+            } else {
+                typ.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("{}({})", name, fields.join(","))
+}
+
+fn expected_event_signature(event: &EventSig) -> String {
+    format_event(event.name, event.fields.iter().copied())
+}
+
+fn event_signature(event: &Event) -> String {
+    format_event(
+        &event.name,
+        event
+            .fields
+            .iter()
+            .map(|field| (field.typ.as_str(), field.indexed)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder;
+    use crate::standards::{check, Mismatch, Standard};
+    use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
+    use fe_analyzer::{AnalyzerDb, TestDb};
+    use fe_common::files::SourceFileId;
+    use fe_parser::{grammar::module::parse_module, parse_code_chunk};
+    use std::rc::Rc;
+
+    #[test]
+    fn check_erc20() {
+        let contract = r#"
+contract Token:
+  event Transfer:
+    idx from: address
+    to: address
+    value: u256
+  event Approval:
+    idx owner: address
+    idx spender: address
+    value: u256
+  pub fn totalSupply() -> u256:
+    return 0
+  pub fn balanceOf(account: address) -> u256:
+    return 0
+  pub fn transfer(to: address, value: u256) -> bool:
+    return true
+  pub fn transferFrom(sender: address, to: address, value: u256) -> bool:
+    return true
+  pub fn approve(spender: address, value: u256):
+    pass"#;
+
+        let ast = parse_code_chunk(parse_module, contract)
+            .expect("unable to build module AST")
+            .kind;
+        let db = TestDb::default();
+
+        let global = Global::default();
+        let global_id = db.intern_global(Rc::new(global));
+
+        let module = Module {
+            name: "test_module".into(),
+            context: ModuleContext::Global(global_id),
+            file_content: ModuleFileContent::File {
+                file: SourceFileId(0),
+            },
+            ast,
+        };
+        let module_id = db.intern_module(Rc::new(module));
+
+        fe_analyzer::analyze_module(&db, module_id).expect("failed to analyze source");
+        let abis = builder::module(&db, module_id).expect("unable to build ABI");
+
+        assert_eq!(
+            check(&abis["Token"], Standard::Erc20),
+            vec![
+                Mismatch::FunctionSignature {
+                    expected: "approve(address,uint256) returns (bool)".to_string(),
+                    actual: "approve(address,uint256)".to_string(),
+                },
+                Mismatch::MissingFunction {
+                    expected: "allowance(address,address) returns (uint256)".to_string(),
+                    selector: "0xdd62ed3e".to_string(),
+                },
+                Mismatch::EventSignature {
+                    expected: "Transfer(address indexed,address indexed,uint256)".to_string(),
+                    actual: "Transfer(address indexed,address,uint256)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_standard() {
+        assert_eq!("erc20".parse(), Ok(Standard::Erc20));
+        assert_eq!("ERC-721".parse(), Ok(Standard::Erc721));
+        assert!("erc777".parse::<Standard>().is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Interfaces generated from the JSON ABI of a deployed contract, which is
+//! imported with `use abi "./erc20_abi.json" as ERC20`. The import is replaced
+//! with an `interface` of the functions in the ABI when the module is loaded,
+//! so the rest of the analyzer only sees an ordinary interface.
+
+use fe_common::diagnostics::{Diagnostic, Label, Severity};
+use fe_common::files::{FileStore, SourceFile};
+use fe_parser::ast;
+use fe_parser::node::{Node, Span};
+use serde_json::Value;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use vec1::Vec1;
+
+use crate::errors;
+
+/// The Fe integer types, which have the same names as the ABI types with `u`
+/// for `uint` and `i` for `int`.
+const INTEGER_SIZES: &[usize] = &[8, 16, 32, 64, 128, 256];
+
+/// Replaces each `use abi` statement of `ast`, which was parsed from `file`,
+/// with the interface generated from the imported ABI. Returns an error for
+/// each ABI that couldn't be read, in which case the interface is empty, and a
+/// warning for each function that was left out of an interface.
+pub fn expand_abi_imports(
+    files: &FileStore,
+    file: &SourceFile,
+    ast: &mut ast::Module,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for stmt in ast.body.iter_mut() {
+        if let ast::ModuleStmt::AbiImport(import) = stmt {
+            let interface = abi_interface(files, file, import, &mut diagnostics);
+            *stmt = ast::ModuleStmt::Contract(interface);
+        }
+    }
+    diagnostics
+}
+
+fn abi_interface(
+    files: &FileStore,
+    file: &SourceFile,
+    import: &Node<ast::AbiImport>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Node<ast::Contract> {
+    let functions = match load_abi(files, file, import) {
+        Ok(entries) => interface_functions(&entries, import, diagnostics),
+        Err(diagnostic) => {
+            diagnostics.push(diagnostic);
+            vec![]
+        }
+    };
+
+    Node::new(
+        ast::Contract {
+            name: import.kind.name.clone(),
+            fields: vec![],
+            body: functions
+                .into_iter()
+                .map(ast::ContractStmt::Function)
+                .collect(),
+            pub_qual: None,
+            layout: None,
+            upgradeable_qual: None,
+            multicall_qual: None,
+            ownable_qual: None,
+            pausable_qual: None,
+            roles_qual: None,
+            is_interface: true,
+        },
+        import.span,
+    )
+}
+
+/// Reads the entries of the imported ABI, which is either a JSON array or the
+/// build artifact of a contract with the array in its `abi` field.
+fn load_abi(
+    files: &FileStore,
+    file: &SourceFile,
+    import: &Node<ast::AbiImport>,
+) -> Result<Vec<Value>, Diagnostic> {
+    let path_span = import.kind.path.span;
+    let path = abi_path(&file.name, &import.kind.path.kind);
+    let content = files.read_file(&path).map_err(|err| {
+        errors::error(
+            format!("unable to read the ABI file `{}`", path.display()),
+            path_span,
+            format!("{}", err),
+        )
+    })?;
+    let json: Value = serde_json::from_str(&content).map_err(|err| {
+        errors::error(
+            format!("the ABI file `{}` isn't valid JSON", path.display()),
+            path_span,
+            format!("{}", err),
+        )
+    })?;
+
+    match json {
+        Value::Array(entries) => Ok(entries),
+        Value::Object(mut artifact) => match artifact.remove("abi") {
+            Some(Value::Array(entries)) => Ok(entries),
+            _ => Err(not_an_abi(&path, path_span)),
+        },
+        _ => Err(not_an_abi(&path, path_span)),
+    }
+}
+
+fn not_an_abi(path: &Path, span: Span) -> Diagnostic {
+    errors::fancy_error(
+        format!("the file `{}` isn't a JSON ABI", path.display()),
+        vec![Label::primary(span, "expected a JSON array")],
+        vec!["Note: an ABI is a JSON array of functions and events, or a build artifact with the array in its `abi` field".into()],
+    )
+}
+
+/// The path of an imported ABI, relative to the directory of the importing
+/// file, without `.` components.
+fn abi_path(file_name: &str, path: &str) -> PathBuf {
+    Path::new(file_name)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// The interface functions of the `function` entries of an ABI. The events,
+/// errors and constructor of the contract aren't part of its interface.
+/// Functions with an ABI type that has no Fe equivalent, and overloads of a
+/// function that's already in the interface, are left out with a warning.
+fn interface_functions(
+    entries: &[Value],
+    import: &Node<ast::AbiImport>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Node<ast::Function>> {
+    let span = import.span;
+    let path_span = import.kind.path.span;
+    let interface_name = &import.kind.name.kind;
+    let mut names = HashSet::new();
+    let mut functions = vec![];
+
+    for entry in entries {
+        // The type of a function may be omitted.
+        let entry_type = entry.get("type").and_then(Value::as_str);
+        if !matches!(entry_type, None | Some("function")) {
+            continue;
+        }
+        let name = match entry.get("name").and_then(Value::as_str) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        match interface_function(entry, name, span) {
+            Ok(_) if names.contains(name) => diagnostics.push(left_out_warning(
+                interface_name,
+                name,
+                path_span,
+                "Note: Fe has no overloaded functions, so only the first function with this name is in the interface".into(),
+            )),
+            Ok(function) => {
+                names.insert(name.to_string());
+                functions.push(function);
+            }
+            Err(abi_type) => diagnostics.push(left_out_warning(
+                interface_name,
+                name,
+                path_span,
+                format!("Note: the ABI type `{}` has no Fe equivalent", abi_type),
+            )),
+        }
+    }
+    functions
+}
+
+fn left_out_warning(interface_name: &str, name: &str, span: Span, note: String) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        code: None,
+        message: format!(
+            "the function `{}` isn't part of the interface `{}`",
+            name, interface_name
+        ),
+        labels: vec![Label::primary(span, "the function is declared in this ABI")],
+        notes: vec![note],
+    }
+}
+
+/// The interface function of an ABI entry, or the first ABI type of its
+/// inputs and outputs that has no Fe equivalent.
+fn interface_function(
+    entry: &Value,
+    name: &str,
+    span: Span,
+) -> Result<Node<ast::Function>, String> {
+    let mut args = vec![Node::new(ast::FunctionArg::Zelf, span)];
+    for (index, input) in params(entry, "inputs").iter().enumerate() {
+        let param_name = match input.get("name").and_then(Value::as_str) {
+            Some(param_name) if !param_name.is_empty() => SmolStr::new(param_name),
+            _ => SmolStr::new(format!("arg{}", index)),
+        };
+        args.push(Node::new(
+            ast::FunctionArg::Regular(ast::RegularFunctionArg {
+                name: Node::new(param_name, span),
+                typ: param_type(input, span)?,
+            }),
+            span,
+        ));
+    }
+
+    let outputs = params(entry, "outputs")
+        .iter()
+        .map(|output| param_type(output, span))
+        .collect::<Result<Vec<_>, _>>()?;
+    let return_type = match outputs.len() {
+        0 => None,
+        1 => outputs.into_iter().next(),
+        _ => Some(Node::new(
+            ast::TypeDesc::Tuple {
+                items: Vec1::try_from_vec(outputs).expect("tuple without items"),
+            },
+            span,
+        )),
+    };
+
+    Ok(Node::new(
+        ast::Function {
+            pub_: Some(span),
+            const_: None,
+            unsafe_: None,
+            test: None,
+            only_role: None,
+            name: Node::new(name.into(), span),
+            generic_params: vec![],
+            args,
+            return_type,
+            return_names: vec![],
+            body: vec![],
+        },
+        span,
+    ))
+}
+
+fn params<'a>(entry: &'a Value, field: &str) -> &'a [Value] {
+    entry
+        .get(field)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn param_type(param: &Value, span: Span) -> Result<Node<ast::TypeDesc>, String> {
+    let abi_type = param.get("type").and_then(Value::as_str).unwrap_or("");
+    type_desc(abi_type, span).ok_or_else(|| abi_type.to_string())
+}
+
+/// The Fe type of an ABI type, if it has one.
+fn type_desc(abi_type: &str, span: Span) -> Option<Node<ast::TypeDesc>> {
+    let base = |name: &str| {
+        Some(Node::new(
+            ast::TypeDesc::Base {
+                base: SmolStr::new(name),
+            },
+            span,
+        ))
+    };
+
+    // A fixed size array, e.g. `uint256[4]`.
+    if let Some(item_type) = abi_type.strip_suffix(']') {
+        let (item_type, size) = item_type.rsplit_once('[')?;
+        let size = size.parse::<usize>().ok()?;
+        return Some(Node::new(
+            ast::TypeDesc::Generic {
+                base: Node::new("Array".into(), span),
+                args: Node::new(
+                    vec![
+                        ast::GenericArg::TypeDesc(type_desc(item_type, span)?),
+                        ast::GenericArg::Int(Node::new(size, span)),
+                    ],
+                    span,
+                ),
+            },
+            span,
+        ));
+    }
+
+    match abi_type {
+        "address" | "bool" => base(abi_type),
+        "uint" => base("u256"),
+        "int" => base("i256"),
+        _ => {
+            let (prefix, size) = if let Some(size) = abi_type.strip_prefix("uint") {
+                ("u", size)
+            } else {
+                ("i", abi_type.strip_prefix("int")?)
+            };
+            let size = size.parse::<usize>().ok()?;
+            if INTEGER_SIZES.contains(&size) {
+                base(&format!("{}{}", prefix, size))
+            } else {
+                None
+            }
+        }
+    }
+}
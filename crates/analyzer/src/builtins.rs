@@ -1,4 +1,5 @@
-use crate::namespace::types::Base;
+use crate::namespace::types::{Array, Base, FixedSize, Integer, Type};
+use crate::AnalyzerDb;
 use strum::{AsRefStr, EnumIter, EnumString};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, AsRefStr)]
@@ -7,6 +8,27 @@ pub enum ValueMethod {
     Clone,
     ToMem,
     AbiEncode,
+    IsSome,
+    IsNone,
+    Unwrap,
+    UnwrapOr,
+    IsOk,
+    IsErr,
+    DivFloor,
+    DivCeil,
+    DivRound,
+    Contains,
+    IndexOf,
+    Fill,
+    CopyFrom,
+    Sort,
+    BinarySearch,
+    Hash,
+    Push,
+    Pop,
+    Len,
+    Concat,
+    Slice,
 }
 
 #[derive(
@@ -18,6 +40,62 @@ pub enum GlobalFunction {
     SendValue,
     Balance,
     BalanceOf,
+    Proportion,
+    ProportionCeil,
+    ProportionRound,
+    Bps,
+    BpsCeil,
+    BpsRound,
+    Some,
+    None,
+    Ok,
+    Err,
+    RawSload,
+    RawSstore,
+    RawCall,
+    Delegatecall,
+    Staticcall,
+    Dbg,
+    /// `dbg(value)` with the location of `value` in its source file, which
+    /// the `RecordDbg` lowering plugin turns each `dbg` call into.
+    #[strum(serialize = "__dbg_record")]
+    DbgRecord,
+}
+
+impl GlobalFunction {
+    /// Whether the function can only be called in an `unsafe` function or block.
+    /// A `delegatecall` runs the code of another contract on this contract's
+    /// storage, so it's as unsafe as `raw_sstore`.
+    pub fn is_unsafe(&self) -> bool {
+        matches!(
+            self,
+            GlobalFunction::RawSload | GlobalFunction::RawSstore | GlobalFunction::Delegatecall
+        )
+    }
+}
+
+/// The size of the byte array that a low-level call copies its return data to,
+/// given the type of its result: a `(bool, Array<u8, N>)` tuple, or a struct
+/// whose fields are a `bool` followed by an `Array<u8, N>`, which is what the
+/// tuple is lowered to. `None` if the type is neither.
+pub fn call_return_data_size(db: &dyn AnalyzerDb, typ: &Type) -> Option<usize> {
+    let items = match typ {
+        Type::Tuple(tuple) => tuple.items.to_vec(),
+        Type::Struct(struct_) => struct_
+            .id
+            .fields(db)
+            .values()
+            .map(|field| field.typ(db).ok())
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+    match items.as_slice() {
+        [FixedSize::Base(Base::Bool), FixedSize::Array(Array {
+            size,
+            inner: Base::Numeric(Integer::U8),
+        })] => Some(*size),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, AsRefStr)]
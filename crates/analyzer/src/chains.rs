@@ -0,0 +1,131 @@
+//! Chain profiles: the differences between Ethereum and the L2 chains that a
+//! contract can be built for. Some chains don't support every opcode, and
+//! some give the execution environment a different meaning, e.g.
+//! `block.number` on Arbitrum is a block number of L1.
+
+use crate::builtins::Intrinsic;
+use crate::context::CallType;
+use crate::errors;
+use crate::lints::{self, Lint};
+use crate::namespace::items::ModuleId;
+use crate::references::module_functions;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::{Diagnostic, Label};
+
+/// The chain that a contract is built for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChainProfile {
+    Ethereum,
+    Arbitrum,
+    Optimism,
+    /// Polygon zkEVM.
+    ZkEvm,
+}
+
+impl ChainProfile {
+    pub const ALL: [ChainProfile; 4] = [
+        ChainProfile::Ethereum,
+        ChainProfile::Arbitrum,
+        ChainProfile::Optimism,
+        ChainProfile::ZkEvm,
+    ];
+
+    /// The profile with the given name, e.g. `arbitrum`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        ChainProfile::ALL
+            .iter()
+            .copied()
+            .find(|chain| chain.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ChainProfile::Ethereum => "ethereum",
+            ChainProfile::Arbitrum => "arbitrum",
+            ChainProfile::Optimism => "optimism",
+            ChainProfile::ZkEvm => "zkevm",
+        }
+    }
+
+    /// Whether the chain supports `PUSH0`, which solc emits for the Shanghai
+    /// EVM and later.
+    pub fn supports_push0(self) -> bool {
+        self == ChainProfile::Ethereum
+    }
+
+    /// Why the chain doesn't support the opcode of an intrinsic, if it doesn't.
+    fn unsupported_intrinsic(self, intrinsic: Intrinsic) -> Option<&'static str> {
+        match (self, intrinsic) {
+            (ChainProfile::ZkEvm, Intrinsic::__selfdestruct) => {
+                Some("`SELFDESTRUCT` is replaced by `SENDALL`, which doesn't remove the contract")
+            }
+            _ => None,
+        }
+    }
+
+    /// The reads of the execution environment that mean something else on
+    /// the chain, and what they mean.
+    fn env_read_note(self, path: &str) -> Option<&'static str> {
+        match (self, path) {
+            (ChainProfile::Arbitrum, "block.number") => {
+                Some("On Arbitrum, `block.number` is an approximate block number of L1, which can stay the same for several L2 blocks.")
+            }
+            (ChainProfile::Arbitrum, "block.difficulty") => {
+                Some("On Arbitrum, `block.difficulty` is always 1.")
+            }
+            (ChainProfile::Arbitrum, "block.coinbase") => {
+                Some("On Arbitrum, `block.coinbase` is a fixed address of the sequencer rather than the block producer.")
+            }
+            (ChainProfile::Optimism, "block.coinbase") => {
+                Some("On Optimism, `block.coinbase` is the address of the sequencer's fee vault rather than the block producer.")
+            }
+            (ChainProfile::Optimism, "block.difficulty") => {
+                Some("On Optimism, `block.difficulty` is the `PREVRANDAO` of an earlier L1 block, which the sequencer knows in advance.")
+            }
+            (ChainProfile::ZkEvm, "block.difficulty") => {
+                Some("On Polygon zkEVM, `block.difficulty` is always 0.")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks the functions of the module against the chain profile. Opcodes that
+/// the chain doesn't support are errors, and reads of the environment that
+/// mean something else on the chain are `chain-semantics` warnings.
+pub fn check(db: &dyn AnalyzerDb, module: ModuleId, chain: ChainProfile) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for function in module_functions(db, module) {
+        let body = function.body(db);
+
+        for (id, call) in body.calls.iter() {
+            if let CallType::Intrinsic(intrinsic) = call {
+                if let (Some(reason), Some(span)) =
+                    (chain.unsupported_intrinsic(*intrinsic), body.spans.get(id))
+                {
+                    diagnostics.push(errors::fancy_error(
+                        &format!(
+                            "`{}` isn't supported on {}",
+                            intrinsic.as_ref(),
+                            chain.name()
+                        ),
+                        vec![Label::primary(*span, reason)],
+                        vec![],
+                    ));
+                }
+            }
+        }
+
+        for (id, path) in body.env_reads.iter() {
+            if let (Some(note), Some(span)) = (chain.env_read_note(path), body.spans.get(id)) {
+                diagnostics.push(lints::warning(
+                    Lint::ChainSemantics,
+                    format!("`{}` has a different meaning on {}", path, chain.name()),
+                    vec![Label::primary(*span, "")],
+                    vec![format!("Note: {}", note)],
+                ));
+            }
+        }
+    }
+    diagnostics
+}
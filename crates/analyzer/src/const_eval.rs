@@ -0,0 +1,661 @@
+//! Compile-time evaluation of `const` functions.
+//!
+//! The evaluator interprets the analyzed AST of `const` functions directly,
+//! using the types recorded in their [`FunctionBody`] to detect overflows the
+//! same way the checked arithmetic of the compiled code would. Anything outside
+//! of the subset of the language that is allowed in `const` functions
+//! evaluates to [`ConstEvalErrorKind::NotConstant`].
+
+use crate::builtins::GlobalFunction;
+use crate::context::{CallType, FunctionBody};
+use crate::errors::{ConstEvalError, ConstEvalErrorKind};
+use crate::namespace::items::{Class, FunctionId, Item, ModuleConstantId, ModuleId, TypeDef};
+use crate::namespace::types::{Base, FixedSize, Integer, Type};
+use crate::AnalyzerDb;
+use fe_common::numeric;
+use fe_common::utils::keccak;
+use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use smol_str::SmolStr;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The maximum number of statements and expressions evaluated for a single
+/// constant, so that loops that never terminate can't hang the compiler.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// The maximum depth of nested `const fn` calls and constant references.
+const CALL_DEPTH_LIMIT: usize = 128;
+
+/// A value computed at compile time.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstValue {
+    Int(BigInt),
+    Bool(bool),
+    Str(SmolStr),
+    Unit,
+}
+
+/// Evaluates a constant expression: a literal, a constant, arithmetic on
+/// constant expressions, a call to a `const` function with constant arguments,
+/// or `keccak256` of a string.
+///
+/// Names are resolved the same way they are in a function defined in `class`,
+/// or directly in `module` if there is no class.
+pub fn eval_const_expr(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    class: Option<Class>,
+    expr: &Node<ast::Expr>,
+) -> Result<ConstValue, ConstEvalError> {
+    Evaluator::new(db).const_expr(module, class, None, expr)
+}
+
+/// Evaluates the value of a module or contract constant.
+pub fn eval_constant(
+    db: &dyn AnalyzerDb,
+    constant: ModuleConstantId,
+) -> Result<ConstValue, ConstEvalError> {
+    let span = constant.data(db).ast.kind.value.span;
+    Evaluator::new(db).constant(constant, span)
+}
+
+/// Evaluates a call of the `const` function `function` with the given
+/// arguments. `span` is the span of the call expression.
+pub fn eval_call(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    args: Vec<ConstValue>,
+    span: Span,
+) -> Result<ConstValue, ConstEvalError> {
+    Evaluator::new(db).call(function, args, span)
+}
+
+struct Evaluator<'a> {
+    db: &'a dyn AnalyzerDb,
+    steps: usize,
+    depth: usize,
+    /// The module constants that are currently being evaluated, innermost last.
+    constants: Vec<ModuleConstantId>,
+}
+
+/// The state of a `const` function call that is being evaluated.
+struct Frame {
+    function: FunctionId,
+    body: Rc<FunctionBody>,
+    locals: HashMap<SmolStr, ConstValue>,
+}
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(ConstValue),
+}
+
+fn error<T>(kind: ConstEvalErrorKind, span: Span) -> Result<T, ConstEvalError> {
+    Err(ConstEvalError::new(kind, span))
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(db: &'a dyn AnalyzerDb) -> Self {
+        Self {
+            db,
+            steps: 0,
+            depth: 0,
+            constants: vec![],
+        }
+    }
+
+    fn step(&mut self, span: Span) -> Result<(), ConstEvalError> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return error(ConstEvalErrorKind::StepLimitExceeded, span);
+        }
+        Ok(())
+    }
+
+    /// Evaluates an expression outside of a function. `int` is the integer
+    /// type of the expression, whose bounds its arithmetic is checked against.
+    fn const_expr(
+        &mut self,
+        module: ModuleId,
+        class: Option<Class>,
+        int: Option<Integer>,
+        expr: &Node<ast::Expr>,
+    ) -> Result<ConstValue, ConstEvalError> {
+        self.step(expr.span)?;
+        match &expr.kind {
+            ast::Expr::Num(num) => Ok(ConstValue::Int(parse_num(num))),
+            ast::Expr::Bool(val) => Ok(ConstValue::Bool(*val)),
+            ast::Expr::Str(val) => Ok(ConstValue::Str(val.clone())),
+            ast::Expr::Unit => Ok(ConstValue::Unit),
+            ast::Expr::Name(name) => match resolve_name(self.db, module, class, name) {
+                Some(Item::Constant(id)) => self.constant(id, expr.span),
+                _ => error(ConstEvalErrorKind::NotConstant, expr.span),
+            },
+            ast::Expr::Path(path) => match module.resolve_path_internal(self.db, path).value {
+                Some(Item::Constant(id)) => self.constant(id, expr.span),
+                _ => error(ConstEvalErrorKind::NotConstant, expr.span),
+            },
+            ast::Expr::BinOperation { left, op, right } => {
+                let lhs = as_int(self.const_expr(module, class, int, left)?, left.span)?;
+                let rhs = as_int(self.const_expr(module, class, int, right)?, right.span)?;
+                Ok(ConstValue::Int(bin_op(&op.kind, lhs, rhs, int, expr.span)?))
+            }
+            ast::Expr::UnaryOperation { op, operand } => {
+                let value = self.const_expr(module, class, int, operand)?;
+                match op.kind {
+                    ast::UnaryOperator::Not => Ok(ConstValue::Bool(!as_bool(value, expr.span)?)),
+                    ast::UnaryOperator::USub => {
+                        let value = ConstValue::Int(-as_int(value, expr.span)?);
+                        match int {
+                            Some(int) => check_fits(value, int, expr.span),
+                            None => Ok(value),
+                        }
+                    }
+                    ast::UnaryOperator::Invert => {
+                        let value = as_int(value, expr.span)?;
+                        let inverted = match int {
+                            Some(int) if !int.is_signed() => max_unsigned(int) - value,
+                            _ => -value - 1,
+                        };
+                        Ok(ConstValue::Int(inverted))
+                    }
+                }
+            }
+            ast::Expr::Call { func, args, .. } => {
+                let item = match &func.kind {
+                    ast::Expr::Name(name) => resolve_name(self.db, module, class, name),
+                    ast::Expr::Path(path) => module.resolve_path_internal(self.db, path).value,
+                    _ => None,
+                };
+                if !is_const_callable(self.db, item) {
+                    return error(ConstEvalErrorKind::NotConstant, expr.span);
+                }
+                let args = args
+                    .kind
+                    .iter()
+                    .map(|arg| self.const_expr(module, class, None, &arg.kind.value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call_item(item, args, expr.span)
+            }
+            _ => error(ConstEvalErrorKind::NotConstant, expr.span),
+        }
+    }
+
+    fn constant(
+        &mut self,
+        constant: ModuleConstantId,
+        span: Span,
+    ) -> Result<ConstValue, ConstEvalError> {
+        if let Some(idx) = self.constants.iter().position(|other| *other == constant) {
+            let cycle = self.constants[idx..]
+                .iter()
+                .chain(std::iter::once(&constant))
+                .map(|constant| constant.name(self.db))
+                .collect();
+            return error(ConstEvalErrorKind::RecursiveConstant(cycle), span);
+        }
+        self.depth += 1;
+        if self.depth > CALL_DEPTH_LIMIT {
+            return error(ConstEvalErrorKind::CallDepthExceeded, span);
+        }
+
+        let int = match constant.typ(self.db) {
+            Ok(Type::Base(Base::Numeric(int))) => Some(int),
+            _ => None,
+        };
+        self.constants.push(constant);
+        let data = constant.data(self.db);
+        let class = data.contract.map(Class::Contract);
+        let value = self.const_expr(data.module, class, int, &data.ast.kind.value)?;
+        self.constants.pop();
+        self.depth -= 1;
+
+        match int {
+            Some(int) => check_fits(value, int, span),
+            None => Ok(value),
+        }
+    }
+
+    fn call_item(
+        &mut self,
+        item: Option<Item>,
+        args: Vec<ConstValue>,
+        span: Span,
+    ) -> Result<ConstValue, ConstEvalError> {
+        match (item, args.as_slice()) {
+            (Some(Item::Function(function)), _) => self.call(function, args, span),
+            (Some(Item::Type(TypeDef::Primitive(Base::Numeric(int)))), [value]) => {
+                check_fits(value.clone(), int, span)
+            }
+            (Some(Item::BuiltinFunction(GlobalFunction::Keccak256)), [ConstValue::Str(val)]) => {
+                let hash = keccak::full_as_bytes(val.as_bytes());
+                Ok(ConstValue::Int(BigInt::from_bytes_be(Sign::Plus, &hash)))
+            }
+            _ => error(ConstEvalErrorKind::NotConstant, span),
+        }
+    }
+
+    fn call(
+        &mut self,
+        function: FunctionId,
+        args: Vec<ConstValue>,
+        span: Span,
+    ) -> Result<ConstValue, ConstEvalError> {
+        if !function.is_const(self.db) {
+            return error(ConstEvalErrorKind::NotConstant, span);
+        }
+        self.depth += 1;
+        if self.depth > CALL_DEPTH_LIMIT {
+            return error(ConstEvalErrorKind::CallDepthExceeded, span);
+        }
+
+        let sig = function.signature(self.db);
+        if sig.params.len() != args.len() {
+            return error(ConstEvalErrorKind::NotConstant, span);
+        }
+        let mut locals = HashMap::new();
+        for (param, arg) in sig.params.iter().zip(args) {
+            let arg = match param.typ {
+                Ok(FixedSize::Base(Base::Numeric(int))) => check_fits(arg, int, span)?,
+                _ => arg,
+            };
+            locals.insert(param.name.clone(), arg);
+        }
+
+        let mut frame = Frame {
+            function,
+            body: function.body(self.db),
+            locals,
+        };
+        let value = match self.block(&mut frame, &function.data(self.db).ast.kind.body)? {
+            Flow::Return(value) => value,
+            _ => ConstValue::Unit,
+        };
+        self.depth -= 1;
+
+        match sig.return_type {
+            Ok(FixedSize::Base(Base::Numeric(int))) => check_fits(value, int, span),
+            _ => Ok(value),
+        }
+    }
+
+    fn block(
+        &mut self,
+        frame: &mut Frame,
+        stmts: &[Node<ast::FuncStmt>],
+    ) -> Result<Flow, ConstEvalError> {
+        for stmt in stmts {
+            match self.stmt(frame, stmt)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn stmt(
+        &mut self,
+        frame: &mut Frame,
+        stmt: &Node<ast::FuncStmt>,
+    ) -> Result<Flow, ConstEvalError> {
+        self.step(stmt.span)?;
+        match &stmt.kind {
+            ast::FuncStmt::Return { value } => {
+                let value = match value {
+                    Some(value) => self.expr(frame, value)?,
+                    None => ConstValue::Unit,
+                };
+                Ok(Flow::Return(value))
+            }
+            ast::FuncStmt::VarDecl { target, typ, value } => {
+                let name = match &target.kind {
+                    ast::VarDeclTarget::Name(name) => name,
+                    ast::VarDeclTarget::Tuple(_) => {
+                        return error(ConstEvalErrorKind::NotConstant, target.span)
+                    }
+                };
+                let decl_type = frame.body.var_decl_types.get(&typ.id).cloned();
+                let value = match (value, decl_type) {
+                    (Some(value), Some(FixedSize::Base(Base::Numeric(int)))) => {
+                        let value = self.expr(frame, value)?;
+                        check_fits(value, int, stmt.span)?
+                    }
+                    (Some(value), _) => self.expr(frame, value)?,
+                    (None, Some(FixedSize::Base(Base::Numeric(_)))) => {
+                        ConstValue::Int(BigInt::zero())
+                    }
+                    (None, Some(FixedSize::Base(Base::Bool))) => ConstValue::Bool(false),
+                    (None, _) => return error(ConstEvalErrorKind::NotConstant, stmt.span),
+                };
+                frame.locals.insert(name.clone(), value);
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::Assign { target, value } => {
+                let name = local_name(frame, target)?;
+                let value = self.expr(frame, value)?;
+                let value = match int_type(frame, target) {
+                    Some(int) => check_fits(value, int, stmt.span)?,
+                    None => value,
+                };
+                frame.locals.insert(name, value);
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::AugAssign { target, op, value } => {
+                let name = local_name(frame, target)?;
+                let lhs = as_int(self.expr(frame, target)?, target.span)?;
+                let rhs = as_int(self.expr(frame, value)?, value.span)?;
+                let result = bin_op(&op.kind, lhs, rhs, int_type(frame, target), stmt.span)?;
+                frame.locals.insert(name, ConstValue::Int(result));
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::While { test, body } => {
+                while as_bool(self.expr(frame, test)?, test.span)? {
+                    match self.block(frame, body)? {
+                        Flow::Break => break,
+                        Flow::Next | Flow::Continue => {}
+                        flow @ Flow::Return(_) => return Ok(flow),
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                if as_bool(self.expr(frame, test)?, test.span)? {
+                    self.block(frame, body)
+                } else {
+                    self.block(frame, or_else)
+                }
+            }
+            ast::FuncStmt::Assert { test, .. } => {
+                if as_bool(self.expr(frame, test)?, test.span)? {
+                    Ok(Flow::Next)
+                } else {
+                    error(ConstEvalErrorKind::AssertionFailed, stmt.span)
+                }
+            }
+            ast::FuncStmt::Expr { value } => {
+                self.expr(frame, value)?;
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::Pass => Ok(Flow::Next),
+            ast::FuncStmt::Break => Ok(Flow::Break),
+            ast::FuncStmt::Continue => Ok(Flow::Continue),
+            ast::FuncStmt::Revert { .. } => error(ConstEvalErrorKind::Reverted, stmt.span),
+            ast::FuncStmt::For { .. }
+            | ast::FuncStmt::Emit { .. }
+            | ast::FuncStmt::Unsafe(_)
+            | ast::FuncStmt::Match { .. }
+            | ast::FuncStmt::Yul(_) => error(ConstEvalErrorKind::NotConstant, stmt.span),
+        }
+    }
+
+    fn expr(
+        &mut self,
+        frame: &mut Frame,
+        expr: &Node<ast::Expr>,
+    ) -> Result<ConstValue, ConstEvalError> {
+        self.step(expr.span)?;
+        match &expr.kind {
+            ast::Expr::Num(num) => Ok(ConstValue::Int(parse_num(num))),
+            ast::Expr::Bool(val) => Ok(ConstValue::Bool(*val)),
+            ast::Expr::Str(val) => Ok(ConstValue::Str(val.clone())),
+            ast::Expr::Unit => Ok(ConstValue::Unit),
+            ast::Expr::Name(name) => {
+                if let Some(value) = frame.locals.get(name) {
+                    return Ok(value.clone());
+                }
+                let function = frame.function;
+                match resolve_name(
+                    self.db,
+                    function.module(self.db),
+                    function.class(self.db),
+                    name,
+                ) {
+                    Some(Item::Constant(id)) => self.constant(id, expr.span),
+                    _ => error(ConstEvalErrorKind::NotConstant, expr.span),
+                }
+            }
+            ast::Expr::BinOperation { left, op, right } => {
+                let lhs = as_int(self.expr(frame, left)?, left.span)?;
+                let rhs = as_int(self.expr(frame, right)?, right.span)?;
+                let result = bin_op(&op.kind, lhs, rhs, int_type(frame, expr), expr.span)?;
+                Ok(ConstValue::Int(result))
+            }
+            ast::Expr::UnaryOperation { op, operand } => {
+                let value = self.expr(frame, operand)?;
+                match op.kind {
+                    ast::UnaryOperator::Not => Ok(ConstValue::Bool(!as_bool(value, expr.span)?)),
+                    ast::UnaryOperator::USub => {
+                        let value = ConstValue::Int(-as_int(value, expr.span)?);
+                        match int_type(frame, expr) {
+                            Some(int) => check_fits(value, int, expr.span),
+                            None => Ok(value),
+                        }
+                    }
+                    ast::UnaryOperator::Invert => {
+                        let value = as_int(value, expr.span)?;
+                        let inverted = match int_type(frame, expr) {
+                            Some(int) if !int.is_signed() => max_unsigned(int) - value,
+                            _ => -value - 1,
+                        };
+                        Ok(ConstValue::Int(inverted))
+                    }
+                }
+            }
+            ast::Expr::CompOperation { left, op, right } => {
+                let lhs = self.expr(frame, left)?;
+                let rhs = self.expr(frame, right)?;
+                let ordering = match (&lhs, &rhs) {
+                    (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs.cmp(rhs),
+                    (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs.cmp(rhs),
+                    _ => return error(ConstEvalErrorKind::NotConstant, expr.span),
+                };
+                let result = match op.kind {
+                    ast::CompOperator::Eq => ordering == Ordering::Equal,
+                    ast::CompOperator::NotEq => ordering != Ordering::Equal,
+                    ast::CompOperator::Lt => ordering == Ordering::Less,
+                    ast::CompOperator::LtE => ordering != Ordering::Greater,
+                    ast::CompOperator::Gt => ordering == Ordering::Greater,
+                    ast::CompOperator::GtE => ordering != Ordering::Less,
+                };
+                Ok(ConstValue::Bool(result))
+            }
+            ast::Expr::BoolOperation { left, op, right } => {
+                let lhs = as_bool(self.expr(frame, left)?, left.span)?;
+                let result = match op.kind {
+                    ast::BoolOperator::And if !lhs => false,
+                    ast::BoolOperator::Or if lhs => true,
+                    _ => as_bool(self.expr(frame, right)?, right.span)?,
+                };
+                Ok(ConstValue::Bool(result))
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                if as_bool(self.expr(frame, test)?, test.span)? {
+                    self.expr(frame, if_expr)
+                } else {
+                    self.expr(frame, else_expr)
+                }
+            }
+            ast::Expr::Call { func, args, .. } => {
+                let item = match frame.body.calls.get(&func.id) {
+                    Some(CallType::Pure(function))
+                    | Some(CallType::AssociatedFunction { function, .. }) => {
+                        Some(Item::Function(*function))
+                    }
+                    Some(CallType::TypeConstructor(Type::Base(base))) => {
+                        Some(Item::Type(TypeDef::Primitive(*base)))
+                    }
+                    _ => None,
+                };
+                if !is_const_callable(self.db, item) {
+                    return error(ConstEvalErrorKind::NotConstant, expr.span);
+                }
+                let args = args
+                    .kind
+                    .iter()
+                    .map(|arg| self.expr(frame, &arg.kind.value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call_item(item, args, expr.span)
+            }
+            _ => error(ConstEvalErrorKind::NotConstant, expr.span),
+        }
+    }
+}
+
+fn resolve_name(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    class: Option<Class>,
+    name: &str,
+) -> Option<Item> {
+    match class {
+        Some(Class::Contract(contract)) => contract.resolve_name(db, name),
+        _ => module.resolve_name(db, name),
+    }
+}
+
+fn is_const_callable(db: &dyn AnalyzerDb, item: Option<Item>) -> bool {
+    match item {
+        Some(Item::Function(function)) => function.is_const(db),
+        Some(Item::Type(TypeDef::Primitive(Base::Numeric(_)))) => true,
+        Some(Item::BuiltinFunction(GlobalFunction::Keccak256)) => true,
+        _ => false,
+    }
+}
+
+/// Returns the name of the local that `target` refers to.
+fn local_name(frame: &Frame, target: &Node<ast::Expr>) -> Result<SmolStr, ConstEvalError> {
+    match &target.kind {
+        ast::Expr::Name(name) if frame.locals.contains_key(name) => Ok(name.clone()),
+        _ => error(ConstEvalErrorKind::NotConstant, target.span),
+    }
+}
+
+/// Returns the integer type of `expr`, as recorded by the analyzer.
+fn int_type(frame: &Frame, expr: &Node<ast::Expr>) -> Option<Integer> {
+    match frame.body.expressions.get(&expr.id).map(|attr| &attr.typ) {
+        Some(Type::Base(Base::Numeric(int))) => Some(*int),
+        _ => None,
+    }
+}
+
+fn parse_num(num: &str) -> BigInt {
+    numeric::Literal::new(num)
+        .parse::<BigInt>()
+        .expect("the numeric literal contains a invalid digit")
+}
+
+fn as_int(value: ConstValue, span: Span) -> Result<BigInt, ConstEvalError> {
+    match value {
+        ConstValue::Int(value) => Ok(value),
+        _ => error(ConstEvalErrorKind::NotConstant, span),
+    }
+}
+
+fn as_bool(value: ConstValue, span: Span) -> Result<bool, ConstEvalError> {
+    match value {
+        ConstValue::Bool(value) => Ok(value),
+        _ => error(ConstEvalErrorKind::NotConstant, span),
+    }
+}
+
+fn check_fits(value: ConstValue, int: Integer, span: Span) -> Result<ConstValue, ConstEvalError> {
+    match &value {
+        ConstValue::Int(num) if !int.fits(num.clone()) => error(ConstEvalErrorKind::Overflow, span),
+        _ => Ok(value),
+    }
+}
+
+fn bits(int: Option<Integer>) -> usize {
+    int.map_or(256, |int| int.size() * 8)
+}
+
+fn max_unsigned(int: Integer) -> BigInt {
+    (BigInt::one() << (int.size() * 8)) - 1
+}
+
+/// Truncates `value` to the width of `int`, like the EVM does for shifts.
+fn wrap(value: BigInt, int: Option<Integer>) -> BigInt {
+    let bits = bits(int);
+    let modulus = BigInt::one() << bits;
+    let value = ((value % &modulus) + &modulus) % &modulus;
+    if int.map_or(false, |int| int.is_signed()) && value >= (BigInt::one() << (bits - 1)) {
+        value - modulus
+    } else {
+        value
+    }
+}
+
+fn bin_op(
+    op: &ast::BinOperator,
+    lhs: BigInt,
+    rhs: BigInt,
+    int: Option<Integer>,
+    span: Span,
+) -> Result<BigInt, ConstEvalError> {
+    let result = match op {
+        ast::BinOperator::Add => lhs + rhs,
+        ast::BinOperator::Sub => lhs - rhs,
+        ast::BinOperator::Mult => lhs * rhs,
+        ast::BinOperator::Div | ast::BinOperator::Mod if rhs.is_zero() => {
+            return error(ConstEvalErrorKind::DivisionByZero, span)
+        }
+        ast::BinOperator::Div => lhs / rhs,
+        ast::BinOperator::Mod => lhs % rhs,
+        ast::BinOperator::Pow => pow(lhs, rhs, span)?,
+        ast::BinOperator::LShift | ast::BinOperator::RShift => {
+            let shift = match rhs.to_usize() {
+                Some(shift) if shift < bits(int) => shift,
+                _ if matches!(op, ast::BinOperator::RShift) && lhs.is_negative() => {
+                    return Ok(-BigInt::one())
+                }
+                _ => return Ok(BigInt::zero()),
+            };
+            return Ok(match op {
+                ast::BinOperator::LShift => wrap(lhs << shift, int),
+                _ => lhs >> shift,
+            });
+        }
+        ast::BinOperator::BitOr => lhs | rhs,
+        ast::BinOperator::BitXor => lhs ^ rhs,
+        ast::BinOperator::BitAnd => lhs & rhs,
+    };
+
+    match int {
+        Some(int) if !int.fits(result.clone()) => error(ConstEvalErrorKind::Overflow, span),
+        _ => Ok(result),
+    }
+}
+
+fn pow(base: BigInt, exp: BigInt, span: Span) -> Result<BigInt, ConstEvalError> {
+    if exp.is_negative() {
+        return error(ConstEvalErrorKind::NotConstant, span);
+    }
+    if base.is_zero() || base.is_one() {
+        return Ok(if exp.is_zero() { BigInt::one() } else { base });
+    }
+    if base == -BigInt::one() {
+        let is_odd = (&exp % 2u8).is_one();
+        return Ok(if is_odd { base } else { BigInt::one() });
+    }
+    // The absolute value of `base` is at least 2, so anything above this would
+    // overflow even the 256 bit types.
+    match exp.to_u32() {
+        Some(exp) if exp <= 256 => Ok(base.pow(exp)),
+        _ => error(ConstEvalErrorKind::Overflow, span),
+    }
+}
@@ -1,5 +1,6 @@
 use crate::builtins::{ContractTypeMethod, GlobalFunction, Intrinsic, ValueMethod};
 use crate::errors::{self, CannotMove, TypeError};
+use crate::lints::{self, Lint};
 use crate::namespace::items::{Class, ContractId, DiagnosticSink, EventId, FunctionId, Item};
 use crate::namespace::types::{FixedSize, SelfDecl, Type};
 use crate::AnalyzerDb;
@@ -90,6 +91,11 @@ pub trait AnalyzerContext {
         ))
     }
 
+    /// Emits a warning for the given lint. Warnings don't stop compilation.
+    fn lint(&mut self, lint: Lint, message: &str, labels: Vec<Label>, notes: Vec<String>) {
+        self.add_diagnostic(lints::warning(lint, message, labels, notes))
+    }
+
     fn register_diag(&mut self, diag: Diagnostic) -> DiagnosticVoucher {
         self.add_diagnostic(diag);
         DiagnosticVoucher(PhantomData::default())
@@ -184,10 +190,13 @@ impl Location {
         match typ {
             FixedSize::Base(_) => Location::Value,
             FixedSize::Contract(_) => Location::Value,
+            FixedSize::Enum(_) => Location::Value,
             FixedSize::Array(_) => Location::Memory,
             FixedSize::Tuple(_) => Location::Memory,
             FixedSize::String(_) => Location::Memory,
             FixedSize::Struct(_) => Location::Memory,
+            FixedSize::Option(_) => Location::Memory,
+            FixedSize::Result(_) => Location::Memory,
         }
     }
 }
@@ -200,7 +209,17 @@ pub struct FunctionBody {
 
     // This is the id of the VarDecl TypeDesc node
     pub var_decl_types: IndexMap<NodeId, FixedSize>,
+    // This is the id of the match arm Pattern node
+    pub pattern_types: IndexMap<NodeId, FixedSize>,
     pub calls: IndexMap<NodeId, CallType>,
+    /// Reads of the execution environment, e.g. `msg.sender`, keyed by
+    /// expression id. These values are constant during a call, so repeated
+    /// reads within a function can share a single value.
+    pub env_reads: IndexMap<NodeId, SmolStr>,
+    /// The variable that each name expression refers to, keyed by expression
+    /// id. Variables are identified by the span of their name where they're
+    /// defined.
+    pub variable_refs: IndexMap<NodeId, Span>,
     pub spans: HashMap<NodeId, Span>,
 }
 
@@ -230,7 +249,7 @@ impl ExpressionAttributes {
     /// Adds a move to value, if it is in storage or memory.
     pub fn into_loaded(mut self) -> Result<Self, CannotMove> {
         match self.typ {
-            Type::Base(_) | Type::Contract(_) => {
+            Type::Base(_) | Type::Contract(_) | Type::Enum(_) => {
                 if self.location != Location::Value {
                     self.move_location = Some(Location::Value);
                 }
@@ -327,6 +346,8 @@ impl CallType {
     pub fn is_unsafe(&self, db: &dyn AnalyzerDb) -> bool {
         if let CallType::Intrinsic(_) = self {
             true
+        } else if let CallType::BuiltinFunction(function) = self {
+            function.is_unsafe()
         } else {
             self.function().map(|id| id.is_unsafe(db)).unwrap_or(false)
         }
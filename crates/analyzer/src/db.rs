@@ -1,11 +1,16 @@
+use crate::const_eval::ConstValue;
 use crate::context::{Analysis, FunctionBody};
 use crate::errors::TypeError;
 use crate::namespace::items::{
-    self, ContractFieldId, ContractId, DepGraphWrapper, EventId, FunctionId, GlobalId, IngotId,
-    Item, ModuleConstantId, ModuleId, StructFieldId, StructId, TypeAliasId,
+    self, Class, ContractFieldId, ContractId, DepGraphWrapper, EnumId, EventId, FunctionId,
+    GlobalId, ImplId, IngotId, Item, ModuleConstantId, ModuleId, StructFieldId, StructId, TraitId,
+    TypeAliasId,
 };
 use crate::namespace::types;
+use crate::references::ReferenceIndex;
 use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::Node;
 use indexmap::map::IndexMap;
 use smol_str::SmolStr;
 use std::rc::Rc;
@@ -42,6 +47,8 @@ pub trait AnalyzerDb {
     #[salsa::interned]
     fn intern_struct_field(&self, data: Rc<items::StructField>) -> StructFieldId;
     #[salsa::interned]
+    fn intern_enum(&self, data: Rc<items::Enum>) -> EnumId;
+    #[salsa::interned]
     fn intern_type_alias(&self, data: Rc<items::TypeAlias>) -> TypeAliasId;
     #[salsa::interned]
     fn intern_contract(&self, data: Rc<items::Contract>) -> ContractId;
@@ -51,6 +58,10 @@ pub trait AnalyzerDb {
     fn intern_function(&self, data: Rc<items::Function>) -> FunctionId;
     #[salsa::interned]
     fn intern_event(&self, data: Rc<items::Event>) -> EventId;
+    #[salsa::interned]
+    fn intern_trait(&self, data: Rc<items::Trait>) -> TraitId;
+    #[salsa::interned]
+    fn intern_impl(&self, data: Rc<items::Impl>) -> ImplId;
 
     // Ingot
     #[salsa::invoke(queries::ingots::ingot_all_modules)]
@@ -71,8 +82,17 @@ pub trait AnalyzerDb {
     fn module_item_map(&self, module: ModuleId) -> Analysis<Rc<IndexMap<SmolStr, Item>>>;
     #[salsa::invoke(queries::module::module_contracts)]
     fn module_contracts(&self, module: ModuleId) -> Rc<Vec<ContractId>>;
+    #[salsa::invoke(queries::module::module_diamonds)]
+    fn module_diamonds(
+        &self,
+        module: ModuleId,
+    ) -> Analysis<Rc<IndexMap<StructId, Vec<ContractId>>>>;
     #[salsa::invoke(queries::module::module_structs)]
     fn module_structs(&self, module: ModuleId) -> Rc<Vec<StructId>>;
+    #[salsa::invoke(queries::traits::module_impls)]
+    fn module_impls(&self, module: ModuleId) -> Rc<Vec<ImplId>>;
+    #[salsa::invoke(queries::module::module_generic_instances)]
+    fn module_generic_instances(&self, module: ModuleId) -> Rc<Vec<Item>>;
     #[salsa::invoke(queries::module::module_used_item_map)]
     fn module_used_item_map(
         &self,
@@ -82,6 +102,8 @@ pub trait AnalyzerDb {
     fn module_parent_module(&self, module: ModuleId) -> Option<ModuleId>;
     #[salsa::invoke(queries::module::module_sub_modules)]
     fn module_sub_modules(&self, module: ModuleId) -> Rc<IndexMap<SmolStr, ModuleId>>;
+    #[salsa::invoke(queries::module::module_references)]
+    fn module_references(&self, module: ModuleId) -> Rc<ReferenceIndex>;
 
     // Module Constant
     #[salsa::invoke(queries::module::module_constant_type)]
@@ -89,6 +111,11 @@ pub trait AnalyzerDb {
         &self,
         id: ModuleConstantId,
     ) -> Analysis<Result<types::Type, TypeError>>;
+    #[salsa::invoke(queries::module::module_constant_value)]
+    fn module_constant_value(
+        &self,
+        id: ModuleConstantId,
+    ) -> Analysis<Result<ConstValue, TypeError>>;
 
     // Contract
     #[salsa::invoke(queries::contracts::contract_all_functions)]
@@ -99,8 +126,18 @@ pub trait AnalyzerDb {
     fn contract_public_function_map(&self, id: ContractId) -> Rc<IndexMap<SmolStr, FunctionId>>;
     #[salsa::invoke(queries::contracts::contract_init_function)]
     fn contract_init_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_initialize_function)]
+    fn contract_initialize_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
     #[salsa::invoke(queries::contracts::contract_call_function)]
     fn contract_call_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_fallback_function)]
+    fn contract_fallback_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_receive_function)]
+    fn contract_receive_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_multicall)]
+    fn contract_multicall(&self, id: ContractId) -> Analysis<bool>;
+    #[salsa::invoke(queries::contracts::contract_injected_members)]
+    fn contract_injected_members(&self, id: ContractId) -> Analysis<Rc<items::InjectedMembers>>;
 
     #[salsa::invoke(queries::contracts::contract_all_events)]
     fn contract_all_events(&self, id: ContractId) -> Rc<Vec<EventId>>;
@@ -119,6 +156,14 @@ pub trait AnalyzerDb {
         &self,
         field: ContractFieldId,
     ) -> Analysis<Result<types::Type, TypeError>>;
+    #[salsa::invoke(queries::contracts::contract_constant_map)]
+    fn contract_constant_map(
+        &self,
+        id: ContractId,
+    ) -> Analysis<Rc<IndexMap<SmolStr, ModuleConstantId>>>;
+    #[salsa::invoke(queries::contracts::contract_layout)]
+    fn contract_layout(&self, id: ContractId) -> Analysis<Option<StructId>>;
+
     #[salsa::cycle(queries::contracts::contract_dependency_graph_cycle)]
     #[salsa::invoke(queries::contracts::contract_dependency_graph)]
     fn contract_dependency_graph(&self, id: ContractId) -> DepGraphWrapper;
@@ -143,6 +188,7 @@ pub trait AnalyzerDb {
     #[salsa::invoke(queries::structs::struct_field_map)]
     fn struct_field_map(&self, id: StructId) -> Analysis<Rc<IndexMap<SmolStr, StructFieldId>>>;
     #[salsa::invoke(queries::structs::struct_field_type)]
+    #[salsa::cycle(queries::structs::struct_field_type_cycle)]
     fn struct_field_type(
         &self,
         field: StructFieldId,
@@ -154,6 +200,27 @@ pub trait AnalyzerDb {
     #[salsa::invoke(queries::structs::struct_dependency_graph)]
     fn struct_dependency_graph(&self, id: StructId) -> DepGraphWrapper;
 
+    // Trait
+    #[salsa::invoke(queries::traits::trait_function_map)]
+    fn trait_function_map(
+        &self,
+        id: TraitId,
+    ) -> Analysis<Rc<IndexMap<SmolStr, Node<ast::Function>>>>;
+
+    // Impl
+    #[salsa::invoke(queries::traits::impl_trait)]
+    fn impl_trait(&self, id: ImplId) -> Analysis<Option<TraitId>>;
+    #[salsa::invoke(queries::traits::impl_receiver)]
+    fn impl_receiver(&self, id: ImplId) -> Analysis<Option<Class>>;
+    #[salsa::invoke(queries::traits::impl_all_functions)]
+    fn impl_all_functions(&self, id: ImplId) -> Rc<Vec<Node<ast::Function>>>;
+    #[salsa::invoke(queries::traits::impl_check)]
+    fn impl_check(&self, id: ImplId) -> Analysis<bool>;
+
+    // Enum
+    #[salsa::invoke(queries::enums::enum_variant_map)]
+    fn enum_variant_map(&self, id: EnumId) -> Analysis<Rc<IndexMap<SmolStr, usize>>>;
+
     // Event
     #[salsa::invoke(queries::events::event_type)]
     fn event_type(&self, event: EventId) -> Analysis<Rc<types::Event>>;
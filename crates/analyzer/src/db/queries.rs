@@ -1,7 +1,9 @@
 pub mod contracts;
+pub mod enums;
 pub mod events;
 pub mod functions;
 pub mod ingots;
 pub mod module;
 pub mod structs;
+pub mod traits;
 pub mod types;
@@ -1,34 +1,51 @@
-use crate::context::{AnalyzerContext, NamedThing};
+use crate::builtins::Intrinsic;
+use crate::context::{AnalyzerContext, CallType, NamedThing};
+use crate::db::queries::traits;
 use crate::db::{Analysis, AnalyzerDb};
 use crate::errors;
 use crate::namespace::items::{
     self, ContractFieldId, ContractId, DepGraph, DepGraphWrapper, DepLocality, EventId, FunctionId,
-    Item, TypeDef,
+    Item, ModuleConstantId, StructId, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, Contract, Struct, Type};
 use crate::traversal::types::type_desc;
 use fe_common::diagnostics::Label;
+use fe_common::Span;
 use fe_parser::ast;
+use fe_parser::node::Node;
 use indexmap::map::{Entry, IndexMap};
 use smol_str::SmolStr;
 use std::rc::Rc;
 
+/// The members that the `ownable` qualifier adds to a contract.
+const OWNABLE_MEMBERS: &str = include_str!("contracts/ownable.fe");
+/// The members that the `pausable` qualifier adds to a contract.
+const PAUSABLE_MEMBERS: &str = include_str!("contracts/pausable.fe");
+/// The members that the `roles` qualifier adds to a contract.
+const ROLES_MEMBERS: &str = include_str!("contracts/roles.fe");
+
 /// A `Vec` of every function defined in the contract, including duplicates and the init function.
 pub fn contract_all_functions(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<Vec<FunctionId>> {
     let module = contract.module(db);
     let body = &contract.data(db).ast.kind.body;
+    let injected = contract.injected_members(db);
+    let implemented = traits::implemented_functions(db, module, items::Class::Contract(contract));
     Rc::new(
         body.iter()
+            .chain(injected.body.iter())
             .filter_map(|stmt| match stmt {
                 ast::ContractStmt::Event(_) => None,
-                ast::ContractStmt::Function(node) => {
-                    Some(db.intern_function(Rc::new(items::Function {
-                        ast: node.clone(),
-                        module,
-                        parent: Some(items::Class::Contract(contract)),
-                    })))
-                }
+                ast::ContractStmt::Function(node) => Some(node),
+            })
+            .chain(implemented.iter())
+            .map(|node| {
+                db.intern_function(Rc::new(items::Function {
+                    ast: node.clone(),
+                    module,
+                    parent: Some(items::Class::Contract(contract)),
+                    type_args: vec![],
+                }))
             })
             .collect(),
     )
@@ -101,6 +118,7 @@ pub fn contract_public_function_map(
         contract
             .functions(db)
             .iter()
+            .filter(|(name, _)| *name != "fallback" && *name != "receive")
             .filter_map(|(name, func)| func.is_public(db).then(|| (name.clone(), *func)))
             .collect(),
     )
@@ -158,6 +176,196 @@ pub fn contract_init_function(
     }
 }
 
+/// The `initialize` function of an `upgradeable` contract, which sets up the
+/// contract in place of `__init__`.
+pub fn contract_initialize_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Option<FunctionId>> {
+    let upgradeable_span = match contract.upgradeable_span(db) {
+        Some(span) => span,
+        None => {
+            return Analysis {
+                value: None,
+                diagnostics: Rc::new(vec![]),
+            }
+        }
+    };
+
+    let mut diagnostics = vec![];
+
+    if let Some(init_fn) = contract.init_function(db) {
+        diagnostics.push(errors::fancy_error(
+            &format!(
+                "upgradeable contract `{}` can't have an `__init__` function",
+                contract.name(db),
+            ),
+            vec![
+                Label::primary(init_fn.name_span(db), "`__init__` defined here"),
+                Label::secondary(upgradeable_span, "the contract is upgradeable here"),
+            ],
+            vec![
+                "Note: the constructor of an upgradeable contract runs on the implementation, not on the proxy that holds its storage".into(),
+                "Hint: move the body of `__init__` into `pub fn initialize(self)`".into(),
+            ],
+        ));
+    }
+
+    let initialize_fn = contract.function(db, "initialize");
+    match initialize_fn {
+        Some(id) if !id.is_public(db) || !id.takes_self(db) => {
+            diagnostics.push(errors::fancy_error(
+                "`initialize` function must be public and take `self`",
+                vec![Label::primary(
+                    id.name_span(db),
+                    "`initialize` is called through the proxy",
+                )],
+                vec!["Example: `pub fn initialize(self, owner: address):`".into()],
+            ));
+        }
+        Some(_) => {}
+        None => {
+            diagnostics.push(errors::fancy_error(
+                &format!(
+                    "upgradeable contract `{}` has no `initialize` function",
+                    contract.name(db),
+                ),
+                vec![Label::primary(
+                    contract.name_span(db),
+                    "expected `pub fn initialize(self)`",
+                )],
+                vec![
+                    "Note: an upgradeable contract is set up by `initialize`, in place of `__init__`".into(),
+                    "Example: `pub fn initialize(self, owner: address):`".into(),
+                ],
+            ));
+        }
+    }
+
+    Analysis {
+        value: initialize_fn,
+        diagnostics: Rc::new(diagnostics),
+    }
+}
+
+/// Checks a `multicall` contract, whose generated `multicall` function
+/// `delegatecall`s the contract itself once for each item of the batch. The
+/// value is whether the contract is `multicall`.
+pub fn contract_multicall(db: &dyn AnalyzerDb, contract: ContractId) -> Analysis<bool> {
+    let multicall_span = match contract.multicall_span(db) {
+        Some(span) => span,
+        None => {
+            return Analysis {
+                value: false,
+                diagnostics: Rc::new(vec![]),
+            }
+        }
+    };
+
+    let mut diagnostics = vec![];
+
+    if let Some(id) = contract.function(db, "multicall") {
+        diagnostics.push(errors::fancy_error(
+            &format!(
+                "multicall contract `{}` can't define a `multicall` function",
+                contract.name(db),
+            ),
+            vec![
+                Label::primary(id.name_span(db), "`multicall` defined here"),
+                Label::secondary(multicall_span, "`multicall` is generated because of this"),
+            ],
+            vec![],
+        ));
+    }
+
+    if let Some(call_fn) = contract.call_function(db) {
+        diagnostics.push(errors::fancy_error(
+            &format!(
+                "multicall contract `{}` can't have a `__call__` function",
+                contract.name(db),
+            ),
+            vec![
+                Label::primary(call_fn.name_span(db), "`__call__` defined here"),
+                Label::secondary(multicall_span, "the contract is multicall here"),
+            ],
+            vec!["Note: the `multicall` function is added to the generated ABI dispatcher".into()],
+        ));
+    }
+
+    for (name, id) in contract.public_functions(db).iter() {
+        if let Some(span) = msg_value_read(db, *id, &mut vec![]) {
+            let mut labels = vec![Label::primary(span, "`msg.value` read here")];
+            let def_span = id.data(db).ast.span;
+            if span.file_id != def_span.file_id
+                || span.start < def_span.start
+                || def_span.end < span.end
+            {
+                labels.push(Label::secondary(
+                    id.name_span(db),
+                    format!("`{}` reads it through a function call", name),
+                ));
+            }
+            labels.push(Label::secondary(
+                multicall_span,
+                "the contract is multicall here",
+            ));
+            diagnostics.push(errors::fancy_error(
+                &format!(
+                    "public function `{}` of multicall contract `{}` reads `msg.value`",
+                    name,
+                    contract.name(db),
+                ),
+                labels,
+                vec![
+                    "Note: every call in a batch is a `delegatecall` that sees the `msg.value` of the whole batch, so the value could be counted more than once".into(),
+                ],
+            ));
+        }
+    }
+
+    Analysis {
+        value: true,
+        diagnostics: Rc::new(diagnostics),
+    }
+}
+
+/// The span of a `msg.value` read in `function`, or in a function of the same
+/// module that it calls. External calls are skipped, as they get their own
+/// `msg.value`.
+fn msg_value_read(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    visited: &mut Vec<FunctionId>,
+) -> Option<Span> {
+    if visited.contains(&function) {
+        return None;
+    }
+    visited.push(function);
+
+    let body = function.body(db);
+    let span = |id| body.spans.get(id).copied();
+    if let Some(span) = body
+        .env_reads
+        .iter()
+        .find_map(|(id, path)| (path == "msg.value").then(|| span(id)).flatten())
+    {
+        return Some(span);
+    }
+
+    body.calls.iter().find_map(|(id, call)| match call {
+        CallType::Intrinsic(Intrinsic::__callvalue) => span(id),
+        CallType::External { .. } => None,
+        _ => {
+            let callee = call.function()?;
+            if callee.module(db) == function.module(db) {
+                msg_value_read(db, callee, visited)
+            } else {
+                None
+            }
+        }
+    })
+}
+
 pub fn contract_call_function(
     db: &dyn AnalyzerDb,
     contract: ContractId,
@@ -229,11 +437,200 @@ pub fn contract_call_function(
     }
 }
 
+/// The `fallback` function, which the ABI dispatcher calls when the calldata
+/// doesn't start with the selector of a public function.
+pub fn contract_fallback_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Option<FunctionId>> {
+    dispatcher_function(db, contract, "fallback")
+}
+
+/// The `receive` function, which the ABI dispatcher calls when the calldata
+/// is empty, e.g. for a plain ether transfer.
+pub fn contract_receive_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Option<FunctionId>> {
+    dispatcher_function(db, contract, "receive")
+}
+
+/// Looks up and checks `fallback` or `receive`. The dispatcher calls them
+/// without decoding the calldata or encoding a return value, so they must be
+/// public, and can't take arguments or return anything.
+fn dispatcher_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    name: &str,
+) -> Analysis<Option<FunctionId>> {
+    let function = contract.function(db, name);
+    let mut diagnostics = vec![];
+
+    if let Some(id) = function {
+        let def = &id.data(db).ast.kind;
+        let example = format!("Example: `pub fn {}(self):`", name);
+
+        if !id.is_public(db) {
+            diagnostics.push(errors::fancy_error(
+                &format!("`{}` function is not public", name),
+                vec![Label::primary(
+                    id.name_span(db),
+                    format!("`{}` function must be public", name),
+                )],
+                vec!["Hint: Add the `pub` modifier.".into(), example.clone()],
+            ));
+        }
+
+        let args = def
+            .args
+            .iter()
+            .filter(|arg| matches!(arg.kind, ast::FunctionArg::Regular(_)))
+            .collect::<Vec<_>>();
+        if let Some(first) = args.first() {
+            diagnostics.push(errors::fancy_error(
+                &format!("`{}` function can't take arguments", name),
+                vec![Label::primary(
+                    first.span + args.last().map(|arg| arg.span),
+                    "remove these arguments",
+                )],
+                vec![
+                    format!("Note: `{}` is called with the raw calldata, which isn't decoded into arguments", name),
+                    example.clone(),
+                ],
+            ));
+        }
+
+        if let Some(return_type) = &def.return_type {
+            if return_type.kind != ast::TypeDesc::Unit {
+                diagnostics.push(errors::fancy_error(
+                    &format!("`{}` function has incorrect return type", name),
+                    vec![Label::primary(
+                        return_type.span,
+                        "return type should be `()`",
+                    )],
+                    vec![
+                        "Hint: Remove the return type specification.".into(),
+                        example,
+                    ],
+                ));
+            }
+        }
+    }
+
+    Analysis {
+        value: function,
+        diagnostics: Rc::new(diagnostics),
+    }
+}
+
+/// The members that the `ownable`, `pausable` and `roles` qualifiers add to
+/// the contract, which are parsed from the Fe code in `contracts/ownable.fe`,
+/// `contracts/pausable.fe` and `contracts/roles.fe`, with the span of the
+/// qualifier. A member is left
+/// out, with an error, if the contract declares a member with the same name.
+pub fn contract_injected_members(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Rc<items::InjectedMembers>> {
+    let ast = &contract.data(db).ast.kind;
+    let mut diagnostics = vec![];
+
+    let mut templates = vec![];
+    if let Some(ownable_span) = ast.ownable_qual {
+        templates.push(("ownable", ownable_span, OWNABLE_MEMBERS));
+    }
+    if let Some(pausable_span) = ast.pausable_qual {
+        if ast.ownable_qual.is_some() {
+            templates.push(("pausable", pausable_span, PAUSABLE_MEMBERS));
+        } else {
+            diagnostics.push(errors::fancy_error(
+                &format!("pausable contract `{}` must be ownable", contract.name(db)),
+                vec![Label::primary(pausable_span, "`pausable` requires `ownable`")],
+                vec![format!(
+                    "Hint: Only the owner can pause the contract. Add the `ownable` qualifier, e.g. `ownable pausable contract {}:`",
+                    contract.name(db),
+                )],
+            ));
+        }
+    }
+
+    if let Some(roles_span) = ast.roles_qual {
+        templates.push(("roles", roles_span, ROLES_MEMBERS));
+    }
+
+    let mut members = items::InjectedMembers::default();
+    for (qualifier, span, src) in templates {
+        let template = match fe_parser::parse_generated(span, src)
+            .expect("invalid contract template")
+            .body
+            .pop()
+        {
+            Some(ast::ModuleStmt::Contract(node)) => node.kind,
+            _ => unreachable!("contract templates define a single contract"),
+        };
+
+        let mut conflicts = |name: &Node<SmolStr>| match declared_member_span(ast, &name.kind) {
+            Some(declared_span) => {
+                diagnostics.push(errors::fancy_error(
+                    &format!(
+                        "`{}` conflicts with a member added by the `{}` qualifier",
+                        name.kind, qualifier
+                    ),
+                    vec![
+                        Label::primary(declared_span, format!("`{}` is declared here", name.kind)),
+                        Label::secondary(span, format!("`{}` adds `{}`", qualifier, name.kind)),
+                    ],
+                    vec![],
+                ));
+                true
+            }
+            None => false,
+        };
+
+        for field in template.fields {
+            if !conflicts(&field.kind.name) {
+                members.fields.push(field);
+            }
+        }
+        for stmt in template.body {
+            let name = match &stmt {
+                ast::ContractStmt::Event(node) => &node.kind.name,
+                ast::ContractStmt::Function(node) => &node.kind.name,
+            };
+            if !conflicts(name) {
+                members.body.push(stmt);
+            }
+        }
+    }
+
+    Analysis {
+        value: Rc::new(members),
+        diagnostics: Rc::new(diagnostics),
+    }
+}
+
+/// The span of the name of the field, event or function named `name` that is
+/// declared in the contract definition.
+fn declared_member_span(contract: &ast::Contract, name: &str) -> Option<Span> {
+    contract
+        .fields
+        .iter()
+        .map(|field| &field.kind.name)
+        .chain(contract.body.iter().map(|stmt| match stmt {
+            ast::ContractStmt::Event(node) => &node.kind.name,
+            ast::ContractStmt::Function(node) => &node.kind.name,
+        }))
+        .find(|member| member.kind == name)
+        .map(|member| member.span)
+}
+
 /// A `Vec` of all events defined within the contract, including those with duplicate names.
 pub fn contract_all_events(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<Vec<EventId>> {
     let body = &contract.data(db).ast.kind.body;
+    let injected = contract.injected_members(db);
     Rc::new(
         body.iter()
+            .chain(injected.body.iter())
             .filter_map(|stmt| match stmt {
                 ast::ContractStmt::Function(_) => None,
                 ast::ContractStmt::Event(node) => Some(db.intern_event(Rc::new(items::Event {
@@ -279,12 +676,15 @@ pub fn contract_event_map(
 
 /// All field ids, including those with duplicate names
 pub fn contract_all_fields(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<Vec<ContractFieldId>> {
+    let injected = contract.injected_members(db);
     let fields = contract
         .data(db)
         .ast
         .kind
         .fields
         .iter()
+        .chain(injected.fields.iter())
+        .filter(|node| !node.kind.is_const)
         .map(|node| {
             db.intern_contract_field(Rc::new(items::ContractField {
                 ast: node.clone(),
@@ -339,9 +739,6 @@ pub fn contract_field_type(
     if node.kind.is_pub {
         scope.not_yet_implemented("contract `pub` fields", node.span);
     }
-    if node.kind.is_const {
-        scope.not_yet_implemented("contract `const` fields", node.span);
-    }
     if let Some(value_node) = &node.kind.value {
         scope.not_yet_implemented("contract field initial value assignment", value_node.span);
     }
@@ -359,6 +756,209 @@ pub fn contract_field_type(
     }
 }
 
+/// The `const` fields of a contract. They're analyzed and inlined like module
+/// constants, and take up no storage.
+pub fn contract_constant_map(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Rc<IndexMap<SmolStr, ModuleConstantId>>> {
+    let mut scope = ItemScope::new(db, contract.module(db));
+    let mut map = IndexMap::<SmolStr, ModuleConstantId>::new();
+
+    let contract_name = contract.name(db);
+    let fields = contract.fields(db);
+    for node in contract
+        .data(db)
+        .ast
+        .kind
+        .fields
+        .iter()
+        .filter(|node| node.kind.is_const)
+    {
+        if node.kind.is_pub {
+            scope.not_yet_implemented("contract `pub const` fields", node.span);
+        }
+        let value = match &node.kind.value {
+            Some(value) => value,
+            None => {
+                scope.fancy_error(
+                    &format!("missing value of constant `{}`", node.name()),
+                    vec![Label::primary(
+                        node.span,
+                        "a `const` field must have a value",
+                    )],
+                    vec![format!(
+                        "Example: `const {}: {} = 10`",
+                        node.name(),
+                        node.kind.typ.kind
+                    )],
+                );
+                continue;
+            }
+        };
+
+        if let Some(field) = fields.get(node.name()) {
+            scope.duplicate_name_error(
+                &format!("duplicate field names in `contract {}`", contract_name),
+                node.name(),
+                field.data(db).ast.span,
+                node.span,
+            );
+            continue;
+        }
+        match map.entry(node.name().into()) {
+            Entry::Occupied(entry) => {
+                scope.duplicate_name_error(
+                    &format!("duplicate field names in `contract {}`", contract_name),
+                    entry.key(),
+                    entry.get().span(db),
+                    node.span,
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(db.intern_module_const(Rc::new(items::ModuleConstant {
+                    ast: Node::new(
+                        ast::ConstantDecl {
+                            name: node.kind.name.clone(),
+                            typ: node.kind.typ.clone(),
+                            value: value.clone(),
+                        },
+                        node.span,
+                    ),
+                    module: contract.module(db),
+                    contract: Some(contract),
+                })));
+            }
+        }
+    }
+
+    Analysis {
+        value: Rc::new(map),
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
+/// The storage layout struct of a diamond facet, i.e. `AppStorage` in
+/// `contract Counter for AppStorage:`. The fields of a facet must be a prefix
+/// of the fields of its layout, so that all facets of a diamond agree on which
+/// storage slot holds which field.
+pub fn contract_layout(db: &dyn AnalyzerDb, contract: ContractId) -> Analysis<Option<StructId>> {
+    let layout = match &contract.data(db).ast.kind.layout {
+        Some(layout) => layout,
+        None => {
+            return Analysis {
+                value: None,
+                diagnostics: Rc::new(vec![]),
+            }
+        }
+    };
+    let mut scope = ItemScope::new(db, contract.module(db));
+
+    let id = match scope.resolve_name(&layout.kind) {
+        Some(NamedThing::Item(Item::Type(TypeDef::Struct(id)))) => id,
+        Some(named_thing) => {
+            let mut labels = vec![Label::primary(
+                layout.span,
+                format!("`{}` is used here as a storage layout", layout.kind),
+            )];
+            if let Some(def_span) = named_thing.name_span(db) {
+                labels.push(Label::secondary(
+                    def_span,
+                    format!(
+                        "`{}` is defined here as a {}",
+                        layout.kind,
+                        named_thing.item_kind_display_name()
+                    ),
+                ));
+            }
+            scope.fancy_error(
+                &format!("`{}` is not a struct", layout.kind),
+                labels,
+                vec!["Note: the storage layout of a facet must be a struct".into()],
+            );
+            return Analysis {
+                value: None,
+                diagnostics: Rc::new(scope.diagnostics),
+            };
+        }
+        None => {
+            scope.error(
+                "undefined type",
+                layout.span,
+                &format!("`{}` has not been defined", layout.kind),
+            );
+            return Analysis {
+                value: None,
+                diagnostics: Rc::new(scope.diagnostics),
+            };
+        }
+    };
+
+    let layout_name = id.name(db);
+    let layout_fields = id.fields(db);
+    let note = format!(
+        "Note: the fields of a facet must match the first fields of `{}`, in the same order",
+        layout_name
+    );
+    for (index, field) in contract.fields(db).values().enumerate() {
+        let node = &field.data(db).ast;
+        match layout_fields.get_index(index) {
+            Some((name, layout_field)) => {
+                let types_match = match (field.typ(db), layout_field.typ(db)) {
+                    (Ok(typ), Ok(layout_typ)) => typ == Type::from(layout_typ),
+                    _ => true,
+                };
+                if name != node.name() || !types_match {
+                    scope.fancy_error(
+                        &format!(
+                            "contract field `{}` doesn't match the storage layout `{}`",
+                            node.name(),
+                            layout_name
+                        ),
+                        vec![
+                            Label::primary(
+                                node.span,
+                                format!(
+                                    "expected `{}: {}`",
+                                    name,
+                                    layout_field.data(db).ast.kind.typ.kind
+                                ),
+                            ),
+                            Label::secondary(
+                                layout_field.span(db),
+                                format!("`{}` has `{}` here", layout_name, name),
+                            ),
+                        ],
+                        vec![note.clone()],
+                    );
+                }
+            }
+            None => {
+                scope.fancy_error(
+                    &format!(
+                        "contract field `{}` isn't part of the storage layout `{}`",
+                        node.name(),
+                        layout_name
+                    ),
+                    vec![
+                        Label::primary(node.span, "this field isn't in the layout"),
+                        Label::secondary(
+                            id.name_span(db),
+                            format!("`{}` is defined here", layout_name),
+                        ),
+                    ],
+                    vec![note.clone()],
+                );
+            }
+        }
+    }
+
+    Analysis {
+        value: Some(id),
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
 pub fn contract_dependency_graph(db: &dyn AnalyzerDb, contract: ContractId) -> DepGraphWrapper {
     // A contract depends on the types of its fields, and the things those types depend on.
     // Note that this *does not* include the contract's public function graph.
@@ -412,7 +1012,10 @@ pub fn contract_runtime_dependency_graph(
     let pub_fns = contract
         .public_functions(db)
         .values()
-        .map(|fun| (root, Item::Function(*fun), DepLocality::Local))
+        .copied()
+        .chain(contract.fallback_function(db))
+        .chain(contract.receive_function(db))
+        .map(|fun| (root, Item::Function(fun), DepLocality::Local))
         .collect::<Vec<_>>();
 
     let mut graph = DepGraph::from_edges(pub_fns.iter());
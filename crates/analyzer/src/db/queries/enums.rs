@@ -0,0 +1,64 @@
+use crate::context::AnalyzerContext;
+use crate::db::Analysis;
+use crate::namespace::items::EnumId;
+use crate::namespace::scopes::ItemScope;
+use crate::namespace::types::MAX_ENUM_VARIANTS;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
+use indexmap::map::{Entry, IndexMap};
+use smol_str::SmolStr;
+use std::rc::Rc;
+
+/// The index of each variant of an enum, which is the value that encodes it.
+pub fn enum_variant_map(
+    db: &dyn AnalyzerDb,
+    enum_: EnumId,
+) -> Analysis<Rc<IndexMap<SmolStr, usize>>> {
+    let mut scope = ItemScope::new(db, enum_.module(db));
+    let mut variants = IndexMap::<SmolStr, usize>::new();
+
+    let enum_data = enum_.data(db);
+    let enum_name = enum_.name(db);
+    for node in &enum_data.ast.kind.variants {
+        let index = variants.len();
+        match variants.entry(node.kind.clone()) {
+            Entry::Occupied(entry) => {
+                let first = enum_data
+                    .ast
+                    .kind
+                    .variants
+                    .iter()
+                    .find(|variant| &variant.kind == entry.key())
+                    .expect("missing first variant");
+                scope.duplicate_name_error(
+                    &format!("duplicate variant names in `enum {}`", enum_name),
+                    entry.key(),
+                    first.span,
+                    node.span,
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            }
+        }
+    }
+
+    if variants.len() > MAX_ENUM_VARIANTS {
+        scope.fancy_error(
+            &format!("`enum {}` has too many variants", enum_name),
+            vec![Label::primary(
+                enum_.name_span(db),
+                format!("this enum has {} variants", variants.len()),
+            )],
+            vec![format!(
+                "Note: an enum can have at most {} variants, because its values are encoded as `u8`",
+                MAX_ENUM_VARIANTS
+            )],
+        );
+    }
+
+    Analysis {
+        value: Rc::new(variants),
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
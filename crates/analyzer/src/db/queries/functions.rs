@@ -5,9 +5,9 @@ use crate::namespace::items::{
     Class, DepGraph, DepGraphWrapper, DepLocality, FunctionId, Item, TypeDef,
 };
 use crate::namespace::scopes::{BlockScope, BlockScopeType, FunctionScope, ItemScope};
-use crate::namespace::types::{self, Contract, FixedSize, SelfDecl, Struct, Type};
+use crate::namespace::types::{self, Base, Contract, FixedSize, SelfDecl, Struct, Type};
 use crate::traversal::functions::traverse_statements;
-use crate::traversal::types::type_desc;
+use crate::traversal::types::{type_desc, validate_generic_params};
 use fe_common::diagnostics::Label;
 use fe_parser::ast;
 use fe_parser::node::Node;
@@ -24,7 +24,7 @@ pub fn function_signature(
     let node = &function.data(db).ast;
     let def = &node.kind;
 
-    let mut scope = ItemScope::new(db, function.module(db));
+    let mut scope = ItemScope::with_type_params(db, function.module(db), function.type_params(db));
     let fn_parent = function.class(db);
 
     if_chain! {
@@ -38,6 +38,34 @@ pub fn function_signature(
         }
     }
 
+    if_chain! {
+        if let Some(const_span) = function.const_span(db);
+        if let Some(unsafe_span) = function.unsafe_span(db);
+        then {
+            scope.error("`const` functions can't be unsafe",
+                        const_span + unsafe_span,
+                        "a function can be either `const` or `unsafe`, but not both");
+        }
+    }
+
+    if let Some(first) = def.generic_params.first() {
+        if function.type_args(db).is_empty() {
+            if fn_parent.is_some() {
+                scope.error(
+                    "type parameters can only be declared by module-level functions",
+                    first.span + def.generic_params.last(),
+                    "not allowed in contract or struct functions",
+                );
+            }
+            validate_generic_params(
+                &mut scope,
+                function.module(db),
+                &def.name.kind,
+                &def.generic_params,
+            );
+        }
+    }
+
     let mut self_decl = None;
     let mut names = HashMap::new();
     let params = def
@@ -46,6 +74,13 @@ pub fn function_signature(
         .enumerate()
         .filter_map(|(index, arg)| match &arg.kind {
             ast::FunctionArg::Zelf => {
+                if function.is_const(db) {
+                    scope.error(
+                        "`const` functions can't take `self`",
+                        arg.span,
+                        "`self` is not known at compile time",
+                    );
+                }
                 if fn_parent.is_none() {
                     scope.error(
                         "`self` can only be used in contract or struct functions",
@@ -77,6 +112,16 @@ pub fn function_signature(
                     ))),
                 });
 
+                if let Ok(typ) = &typ {
+                    if function.is_const(db) && !is_const_fn_type(typ) {
+                        scope.error(
+                            "`const` function parameters must be numeric or boolean",
+                            typ_node.span,
+                            &format!("this has type `{}`", typ),
+                        );
+                    }
+                }
+
                 if let Some(named_item) = scope.resolve_name(&name.kind) {
                     scope.name_conflict_error(
                         "function parameter",
@@ -141,6 +186,106 @@ pub fn function_signature(
         })
         .unwrap_or_else(|| Ok(FixedSize::unit()));
 
+    if_chain! {
+        if let Some(const_span) = function.const_span(db);
+        if let Some(first_name) = def.return_names.first();
+        then {
+            scope.error("`const` functions can't have named return values",
+                        const_span + first_name.span,
+                        "a `const` function must return its value with `return`");
+        }
+    }
+
+    if_chain! {
+        if let Some(Class::Contract(contract)) = fn_parent;
+        if contract.is_interface(db);
+        if self_decl.is_none();
+        then {
+            scope.fancy_error(
+                "interface functions must take `self`",
+                vec![Label::primary(def.name.span, "this function doesn't take `self`")],
+                vec![format!(
+                    "Note: the functions of an interface are called on a deployed contract, e.g. `{}(address).{}(...)`",
+                    contract.name(db),
+                    def.name.kind
+                )],
+            );
+        }
+    }
+
+    if let Some(role) = &def.only_role {
+        match fn_parent {
+            Some(Class::Contract(contract)) if !contract.has_roles(db) => {
+                scope.fancy_error(
+                    &format!(
+                        "`only_role` requires contract `{}` to have the `roles` qualifier",
+                        contract.name(db)
+                    ),
+                    vec![Label::primary(
+                        role.span,
+                        "the roles of the caller can't be checked",
+                    )],
+                    vec![format!(
+                        "Hint: Add the `roles` qualifier, e.g. `roles contract {}:`",
+                        contract.name(db)
+                    )],
+                );
+            }
+            _ if self_decl.is_none() => {
+                scope.fancy_error(
+                    "`only_role` functions must take `self`",
+                    vec![Label::primary(
+                        def.name.span,
+                        "this function doesn't take `self`",
+                    )],
+                    vec!["Note: the roles of the caller are kept in the contract's storage".into()],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(test_span) = def.test {
+        if def.pub_.is_none() {
+            scope.fancy_error(
+                "`test` functions must be public",
+                vec![Label::primary(
+                    test_span + def.name.span,
+                    "this function isn't public",
+                )],
+                vec![format!(
+                    "Hint: `fe test` calls tests through the contract's ABI. Make the function public, e.g. `test pub fn {}`",
+                    def.name.kind
+                )],
+            );
+        }
+        if let Some(arg) = def
+            .args
+            .iter()
+            .find(|arg| !matches!(arg.kind, ast::FunctionArg::Zelf))
+        {
+            scope.error(
+                "`test` functions can't take arguments",
+                arg.span,
+                "`fe test` has no value to pass for this argument",
+            );
+        }
+    }
+
+    if let Ok(typ) = &return_type {
+        if function.is_const(db) && !is_const_fn_type(typ) {
+            let span = def
+                .return_type
+                .as_ref()
+                .map_or(def.name.span, |type_node| type_node.span);
+            scope.error(
+                "`const` functions must return a numeric or boolean value",
+                span,
+                &format!("this function returns `{}`", typ),
+            );
+        }
+    }
+
     Analysis {
         value: Rc::new(types::FunctionSignature {
             self_decl,
@@ -160,8 +305,14 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
     // so no scanning is necessary.
     // If the return type is anything else, we need to ensure that all code paths
     // return or revert.
+    // A function with named return values implicitly returns them at the end.
+    // The functions of an interface have no body.
     if let Ok(return_type) = &function.signature(db).return_type {
-        if !return_type.is_unit() && !all_paths_return_or_revert(&def.body) {
+        if !return_type.is_unit()
+            && !function.has_named_outputs(db)
+            && !function.is_interface_function(db)
+            && !all_paths_return_or_revert(&def.body)
+        {
             scope.fancy_error(
                 "function body is missing a return or revert statement",
                 vec![
@@ -188,17 +339,172 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
         },
     );
 
+    // Named return values are declared as variables, which are initialized
+    // to their default values.
+    for (name, typ) in def.return_names.iter().zip(function.named_output_types(db)) {
+        let _ = block_scope.add_var(&name.kind, typ, name.span);
+    }
+
     // If `traverse_statements` fails, we can be confident that a diagnostic
     // has been emitted, either while analyzing this fn body or while analyzing
     // a type or fn used in this fn body, because of the `DiagnosticVoucher`
     // system. (See the definition of `FatalError`)
     let _ = traverse_statements(&mut block_scope, &def.body);
+
+    if function.is_const(db) {
+        let body = scope.body.borrow().clone();
+        check_const_fn_stmts(&mut scope, &body, &def.body);
+    }
+
     Analysis {
         value: Rc::new(scope.body.into_inner()),
         diagnostics: Rc::new(scope.diagnostics.into_inner()),
     }
 }
 
+/// Returns `true` if values of the given type can be passed to, declared in,
+/// and returned from `const` functions.
+fn is_const_fn_type(typ: &FixedSize) -> bool {
+    matches!(typ, FixedSize::Base(Base::Numeric(_) | Base::Bool))
+}
+
+/// Check that the body of a `const` function only uses the subset of the
+/// language that can be evaluated at compile time.
+fn check_const_fn_stmts(
+    scope: &mut FunctionScope,
+    body: &FunctionBody,
+    stmts: &[Node<ast::FuncStmt>],
+) {
+    for stmt in stmts {
+        let not_allowed = match &stmt.kind {
+            ast::FuncStmt::Return { value } => {
+                if let Some(value) = value {
+                    check_const_fn_expr(scope, body, value);
+                }
+                None
+            }
+            ast::FuncStmt::VarDecl { target, typ, value } => {
+                let is_allowed = matches!(target.kind, ast::VarDeclTarget::Name(_))
+                    && body
+                        .var_decl_types
+                        .get(&typ.id)
+                        .map_or(true, is_const_fn_type);
+                if !is_allowed {
+                    scope.error(
+                        "`const` functions can only declare numeric or boolean variables",
+                        target.span + typ.span,
+                        "this can't be declared in a `const` function",
+                    );
+                }
+                if let Some(value) = value {
+                    check_const_fn_expr(scope, body, value);
+                }
+                None
+            }
+            ast::FuncStmt::Assign { target, value }
+            | ast::FuncStmt::AugAssign { target, value, .. } => {
+                if !matches!(target.kind, ast::Expr::Name(_)) {
+                    scope.error(
+                        "`const` functions can only assign to local variables",
+                        target.span,
+                        "can't be evaluated at compile time",
+                    );
+                }
+                check_const_fn_expr(scope, body, value);
+                None
+            }
+            ast::FuncStmt::While { test, body: stmts } => {
+                check_const_fn_expr(scope, body, test);
+                check_const_fn_stmts(scope, body, stmts);
+                None
+            }
+            ast::FuncStmt::If {
+                test,
+                body: stmts,
+                or_else,
+            } => {
+                check_const_fn_expr(scope, body, test);
+                check_const_fn_stmts(scope, body, stmts);
+                check_const_fn_stmts(scope, body, or_else);
+                None
+            }
+            ast::FuncStmt::Assert { test, .. } => {
+                check_const_fn_expr(scope, body, test);
+                None
+            }
+            ast::FuncStmt::Expr { value } => {
+                check_const_fn_expr(scope, body, value);
+                None
+            }
+            ast::FuncStmt::Pass
+            | ast::FuncStmt::Break
+            | ast::FuncStmt::Continue
+            | ast::FuncStmt::Revert { error: None } => None,
+            ast::FuncStmt::Revert { error: Some(_) } => Some("`revert` with an error value is"),
+            ast::FuncStmt::For { .. } => Some("`for` loops are"),
+            ast::FuncStmt::Emit { .. } => Some("`emit` statements are"),
+            ast::FuncStmt::Unsafe(_) => Some("`unsafe` blocks are"),
+            ast::FuncStmt::Match { .. } => Some("`match` statements are"),
+            ast::FuncStmt::Yul(_) => Some("inline Yul blocks are"),
+        };
+
+        if let Some(description) = not_allowed {
+            scope.error(
+                &format!("{} not allowed in `const` functions", description),
+                stmt.span,
+                "can't be evaluated at compile time",
+            );
+        }
+    }
+}
+
+fn check_const_fn_expr(scope: &mut FunctionScope, body: &FunctionBody, expr: &Node<ast::Expr>) {
+    match &expr.kind {
+        ast::Expr::Num(_) | ast::Expr::Bool(_) | ast::Expr::Name(_) => {}
+        ast::Expr::BinOperation { left, right, .. }
+        | ast::Expr::CompOperation { left, right, .. }
+        | ast::Expr::BoolOperation { left, right, .. } => {
+            check_const_fn_expr(scope, body, left);
+            check_const_fn_expr(scope, body, right);
+        }
+        ast::Expr::UnaryOperation { operand, .. } => check_const_fn_expr(scope, body, operand),
+        ast::Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            check_const_fn_expr(scope, body, if_expr);
+            check_const_fn_expr(scope, body, test);
+            check_const_fn_expr(scope, body, else_expr);
+        }
+        ast::Expr::Call { func, args, .. } => {
+            match body.calls.get(&func.id) {
+                Some(CallType::Pure(function) | CallType::AssociatedFunction { function, .. })
+                    if function.is_const(scope.db()) => {}
+                Some(CallType::TypeConstructor(Type::Base(Base::Numeric(_)))) | None => {}
+                Some(call_type) => {
+                    let fn_name = call_type.function_name(scope.db());
+                    scope.error(
+                        "`const` functions can only call other `const` functions",
+                        func.span,
+                        &format!("`{}` is not a `const` function", fn_name),
+                    );
+                }
+            }
+            for arg in &args.kind {
+                check_const_fn_expr(scope, body, &arg.kind.value);
+            }
+        }
+        _ => {
+            scope.error(
+                "expression not allowed in `const` functions",
+                expr.span,
+                "can't be evaluated at compile time",
+            );
+        }
+    }
+}
+
 fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
     for statement in block.iter().rev() {
         match &statement.kind {
@@ -220,6 +526,16 @@ fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
                     return true;
                 }
             }
+            // The analyzer rejects a `match` that doesn't cover every value,
+            // so one of the arms always runs.
+            ast::FuncStmt::Match { arms, .. } => {
+                if arms
+                    .iter()
+                    .all(|arm| all_paths_return_or_revert(&arm.kind.body))
+                {
+                    return true;
+                }
+            }
             _ => {}
         }
     }
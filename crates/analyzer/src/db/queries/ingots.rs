@@ -84,6 +84,7 @@ pub fn ingot_main_module(db: &dyn AnalyzerDb, ingot_id: IngotId) -> Analysis<Opt
             if main_id.is_none() {
                 vec![Diagnostic {
                     severity: Severity::Error,
+                    code: None,
                     message: format!(
                         "The ingot named \"{}\" is missing a main module. \
                             \nPlease add a `src/main.fe` file to the base directory.",
@@ -112,6 +113,7 @@ pub fn ingot_lib_module(db: &dyn AnalyzerDb, ingot_id: IngotId) -> Analysis<Opti
             if lib_id.is_none() {
                 vec![Diagnostic {
                     severity: Severity::Error,
+                    code: None,
                     message: format!(
                         "The ingot named \"{}\" is missing a lib module. \
                             \nPlease add a `src/lib.fe` file to the base directory.",
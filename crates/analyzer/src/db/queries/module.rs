@@ -1,12 +1,15 @@
-use crate::context::{Analysis, AnalyzerContext};
+use crate::builtins::GlobalFunction;
+use crate::const_eval::{self, ConstValue};
+use crate::context::{Analysis, AnalyzerContext, CallType};
 use crate::db::AnalyzerDb;
 use crate::errors::{self, TypeError};
 use crate::namespace::items::{
-    Contract, ContractId, Function, Item, ModuleConstant, ModuleConstantId, ModuleContext,
-    ModuleFileContent, ModuleId, Struct, StructId, TypeAlias, TypeDef,
+    Contract, ContractId, Enum, Function, FunctionId, Item, ModuleConstant, ModuleConstantId,
+    ModuleContext, ModuleFileContent, ModuleId, Struct, StructId, Trait, TypeAlias, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, Type};
+use crate::references::ReferenceIndex;
 use crate::traversal::types::type_desc;
 use fe_common::diagnostics::Label;
 use fe_common::Span;
@@ -42,12 +45,20 @@ pub fn module_all_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<Item>>
                 Rc::new(Struct {
                     ast: node.clone(),
                     module,
+                    type_args: vec![],
                 }),
             )))),
+            ast::ModuleStmt::Enum(node) => {
+                Some(Item::Type(TypeDef::Enum(db.intern_enum(Rc::new(Enum {
+                    ast: node.clone(),
+                    module,
+                })))))
+            }
             ast::ModuleStmt::Constant(node) => Some(Item::Constant(db.intern_module_const(
                 Rc::new(ModuleConstant {
                     ast: *node.clone(),
                     module,
+                    contract: None,
                 }),
             ))),
             ast::ModuleStmt::Function(node) => {
@@ -55,10 +66,20 @@ pub fn module_all_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<Item>>
                     ast: node.clone(),
                     module,
                     parent: None,
+                    type_args: vec![],
                 }))))
             }
+            ast::ModuleStmt::Trait(node) => Some(Item::Trait(db.intern_trait(Rc::new(Trait {
+                ast: node.clone(),
+                module,
+            })))),
             ast::ModuleStmt::Pragma(_) => None,
             ast::ModuleStmt::Use(_) => None,
+            // The functions of a trait implementation belong to its struct or
+            // contract.
+            ast::ModuleStmt::Impl(_) => None,
+            // Replaced with the generated interface when the module is loaded.
+            ast::ModuleStmt::AbiImport(_) => None,
             ast::ModuleStmt::Event(_) => todo!(),
         })
         .collect();
@@ -170,13 +191,83 @@ pub fn module_contracts(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<Contrac
             .all_items(db)
             .iter()
             .filter_map(|item| match item {
-                Item::Type(TypeDef::Contract(id)) => Some(*id),
+                Item::Type(TypeDef::Contract(id)) if !id.is_interface(db) => Some(*id),
                 _ => None,
             })
             .collect(),
     )
 }
 
+/// Groups the facets of the module by their storage layout struct, and reports
+/// public functions of different facets of the same diamond that have the same
+/// name and parameter types, and thus the same selector.
+pub fn module_diamonds(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+) -> Analysis<Rc<IndexMap<StructId, Vec<ContractId>>>> {
+    let mut diamonds = IndexMap::<StructId, Vec<ContractId>>::new();
+    for contract in module.all_contracts(db).iter() {
+        if let Some(layout) = contract.layout(db) {
+            diamonds.entry(layout).or_default().push(*contract);
+        }
+    }
+
+    let mut scope = ItemScope::new(db, module);
+    for (layout, facets) in diamonds.iter() {
+        let mut selectors =
+            IndexMap::<(SmolStr, Vec<types::FixedSize>), (ContractId, FunctionId)>::new();
+        for facet in facets {
+            for (name, function) in facet.public_functions(db).iter() {
+                let params = match function
+                    .signature(db)
+                    .params
+                    .iter()
+                    .map(|param| param.typ.clone())
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(params) => params,
+                    Err(_) => continue,
+                };
+
+                match selectors.entry((name.clone(), params)) {
+                    Entry::Occupied(entry) => {
+                        let (other_facet, other_function) = entry.get();
+                        scope.fancy_error(
+                            &format!(
+                                "selector clash between facets `{}` and `{}`",
+                                other_facet.name(db),
+                                facet.name(db)
+                            ),
+                            vec![
+                                Label::primary(
+                                    function.name_span(db),
+                                    format!("`{}` defines `{}` here", facet.name(db), name),
+                                ),
+                                Label::secondary(
+                                    other_function.name_span(db),
+                                    format!("`{}` defines `{}` here", other_facet.name(db), name),
+                                ),
+                            ],
+                            vec![format!(
+                                "Note: the diamond of `{}` dispatches each selector to a single facet",
+                                layout.name(db)
+                            )],
+                        );
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert((*facet, *function));
+                    }
+                }
+            }
+        }
+    }
+
+    Analysis {
+        value: Rc::new(diamonds),
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
 pub fn module_structs(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<StructId>> {
     Rc::new(
         module
@@ -197,6 +288,120 @@ pub fn module_structs(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<StructId>
     )
 }
 
+/// The instances of the generic functions and structs of the module that are
+/// used in its ingot. These are found by following the calls and types of the
+/// non-generic functions, structs and contracts of the ingot, and of the
+/// instances themselves.
+pub fn module_generic_instances(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<Item>> {
+    let modules = match module.context(db) {
+        ModuleContext::Ingot(ingot) => ingot.all_modules(db).to_vec(),
+        ModuleContext::Global(_) => vec![module],
+    };
+
+    let mut pending_functions = vec![];
+    let mut pending_types = vec![];
+    for item in modules
+        .iter()
+        .flat_map(|module| module.all_items(db).to_vec())
+    {
+        match item {
+            Item::Function(function) if !function.is_generic(db) => {
+                pending_functions.push(function)
+            }
+            Item::Type(TypeDef::Struct(struct_)) if !struct_.is_generic(db) => {
+                pending_types.push(Type::Struct(types::Struct::from_id(struct_, db)))
+            }
+            Item::Type(TypeDef::Contract(contract)) => {
+                pending_functions.extend(
+                    db.contract_all_functions(contract)
+                        .iter()
+                        .filter(|function| !function.is_generic(db)),
+                );
+                for field in db.contract_all_fields(contract).iter() {
+                    pending_types.extend(db.contract_field_type(*field).value);
+                }
+            }
+            Item::Type(TypeDef::Alias(alias)) => pending_types.extend(alias.typ(db)),
+            _ => {}
+        }
+    }
+
+    let mut seen_functions = HashSet::new();
+    let mut seen_structs = HashSet::new();
+    let mut instances = vec![];
+    while !pending_functions.is_empty() || !pending_types.is_empty() {
+        for typ in std::mem::take(&mut pending_types) {
+            for struct_ in contained_structs(&typ) {
+                if struct_.is_generic(db) || !seen_structs.insert(struct_) {
+                    continue;
+                }
+                if !struct_.type_args(db).is_empty() {
+                    instances.push(Item::Type(TypeDef::Struct(struct_)));
+                }
+                for field in struct_.fields(db).values() {
+                    pending_types.extend(field.typ(db).map(Type::from));
+                }
+                pending_functions.extend(db.struct_all_functions(struct_).iter().copied());
+            }
+        }
+
+        for function in std::mem::take(&mut pending_functions) {
+            if !seen_functions.insert(function) {
+                continue;
+            }
+            if !function.type_args(db).is_empty() {
+                instances.push(Item::Function(function));
+            }
+
+            let sig = function.signature(db);
+            for param in sig.params.iter() {
+                pending_types.extend(param.typ.clone().map(Type::from));
+            }
+            pending_types.extend(sig.return_type.clone().map(Type::from));
+
+            let body = db.function_body(function).value;
+            pending_types.extend(body.expressions.values().map(|attrs| attrs.typ.clone()));
+            pending_types.extend(
+                body.var_decl_types
+                    .values()
+                    .chain(body.pattern_types.values())
+                    .map(|typ| Type::from(typ.clone())),
+            );
+            for call in body.calls.values() {
+                match call {
+                    CallType::TypeConstructor(typ) => pending_types.push(typ.clone()),
+                    _ => pending_functions.extend(call.function().filter(|f| !f.is_generic(db))),
+                }
+            }
+        }
+    }
+
+    Rc::new(
+        instances
+            .into_iter()
+            .filter(|item| match item {
+                Item::Function(function) => function.module(db) == module,
+                Item::Type(TypeDef::Struct(struct_)) => struct_.module(db) == module,
+                _ => false,
+            })
+            .collect(),
+    )
+}
+
+/// The structs that a value of the given type contains or refers to.
+fn contained_structs(typ: &Type) -> Vec<StructId> {
+    match typ {
+        Type::Struct(struct_) => vec![struct_.id],
+        Type::Map(map) => contained_structs(&map.value),
+        Type::Tuple(tuple) => tuple
+            .items
+            .iter()
+            .flat_map(|item| contained_structs(&item.clone().into()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
 pub fn module_constant_type(
     db: &dyn AnalyzerDb,
     constant: ModuleConstantId,
@@ -221,6 +426,118 @@ pub fn module_constant_type(
     }
 }
 
+/// Evaluate the value of a module or contract constant. The value must be a
+/// literal, a constant expression (arithmetic on literals and other
+/// constants), a call to a `const` function with constant arguments, or
+/// `keccak256` of a string literal.
+pub fn module_constant_value(
+    db: &dyn AnalyzerDb,
+    constant: ModuleConstantId,
+) -> Analysis<Result<ConstValue, TypeError>> {
+    let data = constant.data(db);
+    let mut scope = ItemScope::new(db, data.module);
+    let value_node = &data.ast.kind.value;
+
+    let evaluate = |scope: &mut ItemScope| {
+        const_eval::eval_constant(db, constant).map_err(|err| {
+            let mut labels = vec![Label::primary(err.span, err.kind.to_string())];
+            if err.span != value_node.span {
+                let context = if matches!(value_node.kind, ast::Expr::Call { .. }) {
+                    "while evaluating this call"
+                } else {
+                    "while evaluating this expression"
+                };
+                labels.push(Label::secondary(value_node.span, context));
+            }
+            TypeError::new(scope.fancy_error("failed to evaluate constant", labels, vec![]))
+        })
+    };
+
+    let value = match &value_node.kind {
+        ast::Expr::Bool(_) | ast::Expr::Num(_) | ast::Expr::Str(_) | ast::Expr::Unit => {
+            const_eval::eval_const_expr(db, data.module, None, value_node).map_err(|err| {
+                TypeError::new(scope.error(
+                    "failed to evaluate constant",
+                    err.span,
+                    &err.kind.to_string(),
+                ))
+            })
+        }
+        ast::Expr::Name(_)
+        | ast::Expr::Path(_)
+        | ast::Expr::BinOperation { .. }
+        | ast::Expr::UnaryOperation { .. } => {
+            evaluate(&mut scope).and_then(|value| match (&value, constant.typ(db)) {
+                (ConstValue::Int(_), Ok(Type::Base(types::Base::Numeric(_))))
+                | (ConstValue::Int(_), Ok(Type::Base(types::Base::Address)))
+                | (ConstValue::Bool(_), Ok(Type::Base(types::Base::Bool)))
+                | (ConstValue::Unit, Ok(Type::Base(types::Base::Unit)))
+                | (_, Err(_)) => Ok(value),
+                (_, Ok(expected)) => Err(TypeError::new(scope.error(
+                    "mismatched types",
+                    value_node.span,
+                    &format!("this doesn't evaluate to a value of type `{}`", expected),
+                ))),
+            })
+        }
+        ast::Expr::Call { func, args, .. } => {
+            let callee = match &func.kind {
+                ast::Expr::Name(name) => constant.resolve_name(db, name),
+                ast::Expr::Path(path) => data.module.resolve_path_internal(db, path).value,
+                _ => None,
+            };
+            match callee {
+                Some(Item::Function(function)) if function.is_const(db) => {
+                    let return_type = function.signature(db).return_type.clone();
+                    match (constant.typ(db), return_type) {
+                        (Ok(expected), Ok(actual)) if expected != Type::from(actual.clone()) => {
+                            Err(TypeError::new(scope.type_error(
+                                "mismatched types",
+                                value_node.span,
+                                &expected,
+                                &actual,
+                            )))
+                        }
+                        _ => evaluate(&mut scope),
+                    }
+                }
+                // `keccak256` of a string literal, e.g. a role identifier
+                Some(Item::BuiltinFunction(GlobalFunction::Keccak256)) => match args.kind.as_slice() {
+                    [arg] if matches!(arg.kind.value.kind, ast::Expr::Str(_)) => {
+                        evaluate(&mut scope)
+                    }
+                    _ => Err(TypeError::new(scope.fancy_error(
+                        "`keccak256` in a constant value expects a string literal",
+                        vec![Label::primary(value_node.span, "expected a string literal argument")],
+                        vec!["Example: `const MINTER_ROLE: u256 = keccak256(\"MINTER\")`".into()],
+                    ))),
+                },
+                _ => Err(TypeError::new(scope.fancy_error(
+                    "only `const` functions can be called in constant values",
+                    vec![Label::primary(
+                        func.span,
+                        format!("`{}` is not a `const` function", func.kind),
+                    )],
+                    vec![format!(
+                        "Hint: use `const fn {}(..)` to allow the function to be evaluated at compile time",
+                        func.kind
+                    )],
+                ))),
+            }
+        }
+        _ => Err(TypeError::new(scope.error(
+            "unsupported expression in constant value",
+            value_node.span,
+            "this can't be evaluated at compile time",
+        ))),
+    };
+
+    Analysis {
+        value,
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
 pub fn module_used_item_map(
     db: &dyn AnalyzerDb,
     module: ModuleId,
@@ -341,3 +658,7 @@ pub fn module_sub_modules(
         ModuleContext::Global(_) => Rc::new(indexmap! {}),
     }
 }
+
+pub fn module_references(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<ReferenceIndex> {
+    Rc::new(ReferenceIndex::build(db, module))
+}
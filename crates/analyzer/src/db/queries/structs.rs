@@ -1,15 +1,17 @@
 use crate::builtins;
-use crate::context::AnalyzerContext;
+use crate::context::{AnalyzerContext, TempContext};
+use crate::db::queries::traits;
 use crate::db::Analysis;
 use crate::errors::TypeError;
 use crate::namespace::items::{
-    self, DepGraph, DepGraphWrapper, DepLocality, Function, FunctionId, Item, StructField,
+    self, Class, DepGraph, DepGraphWrapper, DepLocality, Function, FunctionId, Item, StructField,
     StructFieldId, StructId, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, Contract, FixedSize, Struct};
-use crate::traversal::types::type_desc;
+use crate::traversal::types::{type_desc, validate_generic_params};
 use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
 use fe_parser::ast;
 use indexmap::map::{Entry, IndexMap};
 use smol_str::SmolStr;
@@ -49,6 +51,15 @@ pub fn struct_field_map(
     let mut fields = IndexMap::<SmolStr, StructFieldId>::new();
 
     let struct_name = struct_.name(db);
+    if struct_.is_generic(db) {
+        validate_generic_params(
+            &mut scope,
+            struct_.module(db),
+            &struct_name,
+            &struct_.generic_params(db),
+        );
+    }
+
     for field in db.struct_all_fields(struct_).iter() {
         let node = &field.data(db).ast;
 
@@ -79,7 +90,11 @@ pub fn struct_field_type(
 ) -> Analysis<Result<types::FixedSize, TypeError>> {
     let field_data = field.data(db);
 
-    let mut scope = ItemScope::new(db, field_data.parent.module(db));
+    let mut scope = ItemScope::with_type_params(
+        db,
+        field_data.parent.module(db),
+        field_data.parent.type_params(db),
+    );
 
     let ast::Field {
         is_pub: _,
@@ -104,7 +119,17 @@ pub fn struct_field_type(
                 );
                 Ok(FixedSize::Contract(contract))
             }
-            Ok(typ) => Ok(typ),
+            Ok(typ) => {
+                // Resolve the field types of the structs contained in this field.
+                // If this field's struct contains itself, this forms a query cycle,
+                // which is reported by `struct_field_type_cycle`.
+                for id in contained_structs(&typ) {
+                    for field in id.fields(db).values() {
+                        let _ = field.typ(db);
+                    }
+                }
+                Ok(typ)
+            }
             Err(_) => Err(TypeError::new(scope.error(
                 "struct field type must have a fixed size",
                 field_data.ast.span,
@@ -120,18 +145,126 @@ pub fn struct_field_type(
     }
 }
 
+pub fn struct_field_type_cycle(
+    db: &dyn AnalyzerDb,
+    _cycle: &[String],
+    field: &StructFieldId,
+) -> Analysis<Result<types::FixedSize, TypeError>> {
+    let field_data = field.data(db);
+    let struct_name = field_data.parent.name(db);
+
+    let notes = match struct_cycle(db, *field) {
+        Some(cycle) => vec![format!(
+            "Note: the cycle is {}",
+            cycle
+                .iter()
+                .map(|id| format!("`{}`", id.name(db)))
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        )],
+        None => vec![],
+    };
+
+    let mut context = TempContext::default();
+    let err = Err(TypeError::new(context.fancy_error(
+        "recursive struct definition",
+        vec![Label::primary(
+            field_data.ast.span,
+            format!("`{}` contains itself through this field", struct_name),
+        )],
+        notes,
+    )));
+
+    Analysis {
+        value: err,
+        diagnostics: Rc::new(context.diagnostics),
+    }
+}
+
+/// The structs that a value of the given type contains directly.
+fn contained_structs(typ: &FixedSize) -> Vec<StructId> {
+    match typ {
+        FixedSize::Struct(Struct { id, .. }) => vec![*id],
+        FixedSize::Tuple(tuple) => tuple.items.iter().flat_map(contained_structs).collect(),
+        _ => vec![],
+    }
+}
+
+/// Finds the structs that lead from the struct that `field` belongs to, through
+/// `field`, back to the same struct, eg. `[A, B, A]`.
+///
+/// The field types are resolved without the `struct_field_type` query, because
+/// this is used while that query is in a cycle.
+fn struct_cycle(db: &dyn AnalyzerDb, field: StructFieldId) -> Option<Vec<StructId>> {
+    fn field_structs(db: &dyn AnalyzerDb, field: StructFieldId) -> Vec<StructId> {
+        let field_data = field.data(db);
+        let mut scope = ItemScope::with_type_params(
+            db,
+            field_data.parent.module(db),
+            field_data.parent.type_params(db),
+        );
+        match type_desc(&mut scope, &field_data.ast.kind.typ).map(FixedSize::try_from) {
+            Ok(Ok(typ)) => contained_structs(&typ),
+            _ => vec![],
+        }
+    }
+
+    fn find_path(
+        db: &dyn AnalyzerDb,
+        from: StructId,
+        target: StructId,
+        path: &mut Vec<StructId>,
+    ) -> bool {
+        if from == target {
+            return true;
+        }
+        if path.contains(&from) {
+            return false;
+        }
+        path.push(from);
+        for field in from.fields(db).values() {
+            for id in field_structs(db, *field) {
+                if find_path(db, id, target, path) {
+                    return true;
+                }
+            }
+        }
+        path.pop();
+        false
+    }
+
+    let start = field.data(db).parent;
+    let mut path = vec![start];
+    for id in field_structs(db, field) {
+        if find_path(db, id, start, &mut path) {
+            path.push(start);
+            return Some(path);
+        }
+    }
+    None
+}
+
 pub fn struct_all_functions(db: &dyn AnalyzerDb, struct_: StructId) -> Rc<Vec<FunctionId>> {
     let struct_data = struct_.data(db);
+    // Traits are implemented for a generic struct itself, and their functions
+    // are added to each of its instances.
+    let implemented = traits::implemented_functions(
+        db,
+        struct_data.module,
+        Class::Struct(struct_.instantiate(db, vec![])),
+    );
     let fields = struct_data
         .ast
         .kind
         .functions
         .iter()
+        .chain(implemented.iter())
         .map(|node| {
             db.intern_function(Rc::new(Function {
                 ast: node.clone(),
                 module: struct_data.module,
                 parent: Some(items::Class::Struct(struct_)),
+                type_args: vec![],
             }))
         })
         .collect();
@@ -210,7 +343,6 @@ pub fn struct_dependency_graph(db: &dyn AnalyzerDb, struct_: StructId) -> DepGra
                 Item::Type(TypeDef::Contract(id)),
                 DepLocality::External,
             )),
-            // Not possible yet, but it will be soon
             FixedSize::Struct(Struct { id, .. }) => {
                 Some((root, Item::Type(TypeDef::Struct(id)), DepLocality::Local))
             }
@@ -0,0 +1,327 @@
+use crate::context::{Analysis, AnalyzerContext};
+use crate::namespace::items::{Class, Function, Impl, ImplId, Item, ModuleId, TraitId, TypeDef};
+use crate::namespace::scopes::ItemScope;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use indexmap::map::{Entry, IndexMap};
+use smol_str::SmolStr;
+use std::rc::Rc;
+
+pub fn trait_function_map(
+    db: &dyn AnalyzerDb,
+    trait_: TraitId,
+) -> Analysis<Rc<IndexMap<SmolStr, Node<ast::Function>>>> {
+    let mut scope = ItemScope::new(db, trait_.module(db));
+    let mut map = IndexMap::<SmolStr, Node<ast::Function>>::new();
+
+    for func in trait_.data(db).ast.kind.functions.iter() {
+        match map.entry(func.name().into()) {
+            Entry::Occupied(entry) => {
+                scope.duplicate_name_error(
+                    &format!("duplicate function names in `trait {}`", trait_.name(db)),
+                    entry.key(),
+                    entry.get().span,
+                    func.span,
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(func.clone());
+            }
+        }
+    }
+
+    Analysis {
+        value: Rc::new(map),
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
+pub fn module_impls(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<ImplId>> {
+    Rc::new(
+        module
+            .data(db)
+            .ast
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                ast::ModuleStmt::Impl(node) => Some(db.intern_impl(Rc::new(Impl {
+                    ast: node.clone(),
+                    module,
+                }))),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+pub fn impl_trait(db: &dyn AnalyzerDb, impl_: ImplId) -> Analysis<Option<TraitId>> {
+    let module = impl_.module(db);
+    let mut scope = ItemScope::new(db, module);
+    let name = &impl_.data(db).ast.kind.trait_name;
+
+    let trait_ = match module.resolve_name(db, &name.kind) {
+        Some(Item::Trait(id)) => Some(id),
+        Some(item) => {
+            scope.error(
+                &format!("`{}` is not a trait", name.kind),
+                name.span,
+                &format!("`{}` is a {}", name.kind, item.item_kind_display_name()),
+            );
+            None
+        }
+        None => {
+            scope.error(
+                "undefined trait",
+                name.span,
+                &format!("`{}` has not been defined", name.kind),
+            );
+            None
+        }
+    };
+
+    Analysis {
+        value: trait_,
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
+pub fn impl_receiver(db: &dyn AnalyzerDb, impl_: ImplId) -> Analysis<Option<Class>> {
+    let module = impl_.module(db);
+    let mut scope = ItemScope::new(db, module);
+    let name = &impl_.data(db).ast.kind.receiver;
+
+    let receiver = match module.resolve_name(db, &name.kind) {
+        Some(Item::Type(TypeDef::Contract(id))) if id.is_interface(db) => {
+            scope.error(
+                "can't implement a trait for an interface",
+                name.span,
+                &format!("`{}` is an interface", name.kind),
+            );
+            None
+        }
+        Some(Item::Type(TypeDef::Struct(id))) if id.module(db) == module => Some(Class::Struct(id)),
+        Some(Item::Type(TypeDef::Contract(id))) if id.module(db) == module => {
+            Some(Class::Contract(id))
+        }
+        Some(Item::Type(TypeDef::Struct(_) | TypeDef::Contract(_))) => {
+            scope.fancy_error(
+                &format!("`{}` is defined in another module", name.kind),
+                vec![Label::primary(
+                    name.span,
+                    format!("`{}` is imported", name.kind),
+                )],
+                vec!["Note: a trait can only be implemented in the module that defines the struct or contract".into()],
+            );
+            None
+        }
+        Some(item) => {
+            scope.fancy_error(
+                &format!("can't implement a trait for `{}`", name.kind),
+                vec![Label::primary(
+                    name.span,
+                    format!("`{}` is a {}", name.kind, item.item_kind_display_name()),
+                )],
+                vec!["Note: only structs and contracts can implement traits".into()],
+            );
+            None
+        }
+        None => {
+            scope.error(
+                "undefined type",
+                name.span,
+                &format!("`{}` has not been defined", name.kind),
+            );
+            None
+        }
+    };
+
+    Analysis {
+        value: receiver,
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
+/// The functions that the implementation defines, followed by the default
+/// functions of the trait that it doesn't define. Default functions are
+/// checked and compiled once for each implementation, in its module.
+pub fn impl_all_functions(db: &dyn AnalyzerDb, impl_: ImplId) -> Rc<Vec<Node<ast::Function>>> {
+    let defined = &impl_.data(db).ast.kind.functions;
+    let mut functions = defined.clone();
+    if let Some(trait_) = impl_.trait_(db) {
+        functions.extend(
+            trait_
+                .functions(db)
+                .values()
+                .filter(|func| {
+                    !func.kind.body.is_empty()
+                        && !defined.iter().any(|def| def.name() == func.name())
+                })
+                .cloned(),
+        );
+    }
+    Rc::new(functions)
+}
+
+/// Checks that the implementation defines each function of the trait without
+/// a default, and that the functions it defines are declared by the trait,
+/// with the same signature.
+pub fn impl_check(db: &dyn AnalyzerDb, impl_: ImplId) -> Analysis<bool> {
+    let module = impl_.module(db);
+    let mut scope = ItemScope::new(db, module);
+    let (trait_, receiver) = match (impl_.trait_(db), impl_.receiver(db)) {
+        (Some(trait_), Some(receiver)) => (trait_, receiver),
+        _ => {
+            return Analysis {
+                value: false,
+                diagnostics: Rc::new(vec![]),
+            }
+        }
+    };
+
+    let ast = &impl_.data(db).ast.kind;
+    let trait_name = trait_.name(db);
+    let trait_functions = trait_.functions(db);
+    let mut complete = true;
+
+    // The declared functions are resolved as functions of the receiver, so that
+    // their signatures can be compared with those of the defined functions.
+    let function_id = |node: &Node<ast::Function>| {
+        db.intern_function(Rc::new(Function {
+            ast: node.clone(),
+            module,
+            parent: Some(receiver),
+            type_args: vec![],
+        }))
+    };
+
+    for func in ast.functions.iter() {
+        let declared = match trait_functions.get(func.name()) {
+            Some(declared) => declared,
+            None => {
+                scope.fancy_error(
+                    &format!(
+                        "`{}` is not a function of trait `{}`",
+                        func.name(),
+                        trait_name
+                    ),
+                    vec![
+                        Label::primary(
+                            func.kind.name.span,
+                            format!("not declared by `{}`", trait_name),
+                        ),
+                        Label::secondary(
+                            trait_.name_span(db),
+                            format!("`{}` is defined here", trait_name),
+                        ),
+                    ],
+                    vec![format!(
+                        "Hint: to add `{}` to `{}` only, define it in `{} {}`",
+                        func.name(),
+                        receiver.name(db),
+                        receiver.kind(),
+                        receiver.name(db),
+                    )],
+                );
+                complete = false;
+                continue;
+            }
+        };
+
+        let declared_sig = db.function_signature(function_id(declared));
+        scope
+            .diagnostics
+            .extend(declared_sig.diagnostics.iter().cloned());
+        let (declared_sig, defined_sig) = (
+            declared_sig.value,
+            db.function_signature(function_id(func)).value,
+        );
+        let resolved = [&declared_sig, &defined_sig]
+            .iter()
+            .all(|sig| sig.return_type.is_ok() && sig.params.iter().all(|param| param.typ.is_ok()));
+        if resolved && (declared_sig != defined_sig || declared.kind.is_pub() != func.kind.is_pub())
+        {
+            scope.fancy_error(
+                &format!(
+                    "`{}` doesn't match its declaration in trait `{}`",
+                    func.name(),
+                    trait_name
+                ),
+                vec![
+                    Label::primary(func.kind.name.span, "signature differs from the trait"),
+                    Label::secondary(declared.kind.name.span, "declared here"),
+                ],
+                vec![
+                    format!("Note: `{}` declares `{}`", trait_name, signature(declared)),
+                    format!("Note: the implementation defines `{}`", signature(func)),
+                ],
+            );
+            complete = false;
+        }
+    }
+
+    let missing = trait_functions
+        .values()
+        .filter(|func| {
+            func.kind.body.is_empty() && !ast.functions.iter().any(|def| def.name() == func.name())
+        })
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let names = missing
+            .iter()
+            .map(|func| format!("`{}`", func.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut labels = vec![Label::primary(
+            ast.trait_name.span + ast.receiver.span,
+            format!("missing {}", names),
+        )];
+        labels.extend(missing.iter().map(|func| {
+            Label::secondary(
+                func.kind.name.span,
+                format!("`{}` is declared here", func.name()),
+            )
+        }));
+        scope.fancy_error(
+            &format!(
+                "`{}` doesn't define all functions of trait `{}`",
+                receiver.name(db),
+                trait_name
+            ),
+            labels,
+            missing
+                .iter()
+                .map(|func| format!("Hint: define `{}`", signature(func)))
+                .collect(),
+        );
+        complete = false;
+    }
+
+    Analysis {
+        value: complete,
+        diagnostics: Rc::new(scope.diagnostics),
+    }
+}
+
+/// The functions that trait implementations in the module of a struct or
+/// contract add to it.
+pub fn implemented_functions(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    receiver: Class,
+) -> Vec<Node<ast::Function>> {
+    module
+        .impls(db)
+        .iter()
+        .filter(|impl_| impl_.receiver(db) == Some(receiver))
+        .flat_map(|impl_| impl_.functions(db).to_vec())
+        .collect()
+}
+
+/// The signature of a function, without its body, e.g. `fn id(self) -> u256`.
+fn signature(func: &Node<ast::Function>) -> String {
+    let mut sig = func.kind.clone();
+    sig.body.clear();
+    sig.to_string()
+}
@@ -3,6 +3,7 @@
 use crate::context::{DiagnosticVoucher, NamedThing};
 use fe_common::diagnostics::{Diagnostic, Label, Severity};
 use fe_common::Span;
+use smol_str::SmolStr;
 use std::fmt::Display;
 
 /// Error indicating that a type is invalid.
@@ -77,6 +78,57 @@ pub enum BinaryOperationError {
     NotEqualAndUnsigned,
 }
 
+/// Error indicating that an expression couldn't be evaluated at compile time
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstEvalError {
+    pub kind: ConstEvalErrorKind,
+    /// The span of the expression or statement that caused the error.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConstEvalErrorKind {
+    /// The value depends on something that is only known at runtime.
+    NotConstant,
+    Overflow,
+    DivisionByZero,
+    AssertionFailed,
+    Reverted,
+    StepLimitExceeded,
+    CallDepthExceeded,
+    /// A constant refers to itself. Holds the names of the constants in the
+    /// cycle, starting and ending with the same constant.
+    RecursiveConstant(Vec<SmolStr>),
+}
+
+impl ConstEvalError {
+    pub fn new(kind: ConstEvalErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl Display for ConstEvalErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstEvalErrorKind::NotConstant => write!(f, "value is not known at compile time"),
+            ConstEvalErrorKind::Overflow => write!(f, "arithmetic overflow"),
+            ConstEvalErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ConstEvalErrorKind::AssertionFailed => write!(f, "assertion failed"),
+            ConstEvalErrorKind::Reverted => write!(f, "evaluation reverted"),
+            ConstEvalErrorKind::StepLimitExceeded => write!(f, "evaluation step limit exceeded"),
+            ConstEvalErrorKind::CallDepthExceeded => write!(f, "call depth limit exceeded"),
+            ConstEvalErrorKind::RecursiveConstant(cycle) => {
+                let cycle = cycle
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                write!(f, "constant depends on itself: {}", cycle)
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AnalyzerError(pub Vec<Diagnostic>);
 
@@ -97,6 +149,7 @@ pub fn fancy_error(
 ) -> Diagnostic {
     Diagnostic {
         severity: Severity::Error,
+        code: None,
         message: message.into(),
         labels,
         notes,
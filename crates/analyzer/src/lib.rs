@@ -4,35 +4,50 @@
 //! any semantic errors within a given AST and produces a `Context` instance
 //! that can be used to query contextual information attributed to AST nodes.
 
+mod abi_import;
 pub mod builtins;
+pub mod chains;
+pub mod const_eval;
 pub mod constants;
 pub mod context;
 pub mod db;
 pub mod errors;
+pub mod lints;
 pub mod namespace;
 mod operations;
+pub mod references;
+pub mod rename;
 mod traversal;
 
 use crate::namespace::items::{IngotId, ModuleId};
 pub use db::{AnalyzerDb, TestDb};
-use fe_common::diagnostics::Diagnostic;
+use fe_common::diagnostics::{Diagnostic, Severity};
 
-pub fn analyze_ingot(db: &dyn AnalyzerDb, ingot_id: IngotId) -> Result<(), Vec<Diagnostic>> {
-    let diagnostics = ingot_id.diagnostics(db);
-
-    if diagnostics.is_empty() {
-        Ok(())
-    } else {
-        Err(diagnostics)
-    }
+/// Analyzes the ingot. Returns its warnings if there are no errors, otherwise
+/// returns all of its diagnostics.
+pub fn analyze_ingot(
+    db: &dyn AnalyzerDb,
+    ingot_id: IngotId,
+) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    split_warnings(ingot_id.diagnostics(db))
 }
 
-pub fn analyze_module(db: &dyn AnalyzerDb, module_id: ModuleId) -> Result<(), Vec<Diagnostic>> {
-    let diagnostics = module_id.diagnostics(db);
+/// Analyzes the module. Returns its warnings if there are no errors, otherwise
+/// returns all of its diagnostics.
+pub fn analyze_module(
+    db: &dyn AnalyzerDb,
+    module_id: ModuleId,
+) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    split_warnings(module_id.diagnostics(db))
+}
 
-    if diagnostics.is_empty() {
-        Ok(())
-    } else {
+fn split_warnings(diagnostics: Vec<Diagnostic>) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
         Err(diagnostics)
+    } else {
+        Ok(diagnostics)
     }
 }
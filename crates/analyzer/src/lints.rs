@@ -0,0 +1,408 @@
+//! Lints: warnings about code that compiles, but is likely to be wrong.
+
+use crate::builtins::GlobalFunction;
+use crate::context::{AnalyzerContext, CallType};
+use crate::namespace::items::ModuleId;
+use crate::namespace::scopes::BlockScope;
+use crate::references::SymbolKind;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::{Diagnostic, Label, LabelStyle, Severity};
+use fe_common::files::{FileStore, SourceFile, SourceFileId};
+use fe_common::numeric;
+use fe_parser::ast as fe;
+use fe_parser::lexer::{Lexer, TokenKind};
+use fe_parser::node::Node;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A kind of warning. Each lint has an id, which is shown with its warnings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// `==` or `!=` comparison of an account balance.
+    StrictBalanceEquality,
+    /// `==` or `!=` comparison of `block.timestamp`.
+    TimestampEquality,
+    /// Modulo of a value derived from `block` attributes.
+    BlockRandomness,
+    /// A local variable that's never used.
+    UnusedVariable,
+    /// A private contract or struct function that's never called.
+    UnusedFunction,
+    /// A read of the execution environment that means something else on the
+    /// chain that the contract is built for.
+    ChainSemantics,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::StrictBalanceEquality,
+        Lint::TimestampEquality,
+        Lint::BlockRandomness,
+        Lint::UnusedVariable,
+        Lint::UnusedFunction,
+        Lint::ChainSemantics,
+    ];
+
+    /// The lint with the given id, e.g. `unused-variable`.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Lint::ALL.iter().copied().find(|lint| lint.id() == id)
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Lint::StrictBalanceEquality => "strict-balance-equality",
+            Lint::TimestampEquality => "timestamp-equality",
+            Lint::BlockRandomness => "block-randomness",
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedFunction => "unused-function",
+            Lint::ChainSemantics => "chain-semantics",
+        }
+    }
+}
+
+/// How the warnings of a lint are reported, as set in the `[lints]` table of
+/// a project manifest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The warnings are left out.
+    Allow,
+    Warn,
+    /// The warnings are errors, which fail the compilation.
+    Deny,
+}
+
+impl LintLevel {
+    /// The level with the given name: `allow`, `warn` or `deny`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Creates a warning diagnostic for the given lint.
+pub fn warning(
+    lint: Lint,
+    message: impl Into<String>,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        code: Some(lint.id().to_string()),
+        message: message.into(),
+        labels,
+        notes,
+    }
+}
+
+/// Checks a comparison for `==` or `!=` on balances and timestamps. The
+/// operands must already be analyzed.
+pub fn check_comparison(scope: &mut BlockScope, exp: &Node<fe::Expr>) {
+    let (left, right) = match &exp.kind {
+        fe::Expr::CompOperation { left, op, right }
+            if matches!(op.kind, fe::CompOperator::Eq | fe::CompOperator::NotEq) =>
+        {
+            (left, right)
+        }
+        _ => return,
+    };
+
+    if is_balance_call(scope, left) || is_balance_call(scope, right) {
+        scope.lint(
+            Lint::StrictBalanceEquality,
+            "strict equality comparison of a balance",
+            vec![Label::primary(exp.span, "this may never be true")],
+            vec!["Note: Anyone can send ether to an account, e.g. with `selfdestruct`, so its exact balance can't be relied on. Compare with `>=` or `<=` instead.".into()],
+        );
+    }
+
+    if is_block_field(left, Some("timestamp")) || is_block_field(right, Some("timestamp")) {
+        scope.lint(
+            Lint::TimestampEquality,
+            "equality comparison of `block.timestamp`",
+            vec![Label::primary(exp.span, "this is unlikely to ever be true")],
+            vec!["Note: Block timestamps are chosen by block producers and rarely match an exact value. Compare with `<` or `>` instead.".into()],
+        );
+    }
+}
+
+/// Checks a modulo operation for "randomness" derived from `block` attributes.
+/// Modulo by a power of two truncates a value and isn't reported.
+pub fn check_modulo(scope: &mut BlockScope, exp: &Node<fe::Expr>) {
+    let (left, right) = match &exp.kind {
+        fe::Expr::BinOperation { left, op, right } if op.kind == fe::BinOperator::Mod => {
+            (left, right)
+        }
+        _ => return,
+    };
+
+    if reads_block(left) && !is_power_of_two(right) {
+        scope.lint(
+            Lint::BlockRandomness,
+            "`block` attributes used as a source of randomness",
+            vec![Label::primary(left.span, "this can be predicted or influenced")],
+            vec!["Note: Block producers choose the values of `block` attributes, so they can predict the result, or pick a favorable one.".into()],
+        );
+    }
+}
+
+/// Checks the module for variables and private functions that are never used.
+/// These warnings are collected from the module's reference index, so unlike
+/// the other lints they aren't part of the module's diagnostics; the compiler
+/// driver reports them along with the analyzer's warnings.
+///
+/// Variables whose names start with `_` aren't reported.
+pub fn check_unused(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<Diagnostic> {
+    let index = db.module_references(module);
+    let mut warnings = vec![];
+    for symbol in index.symbols.iter() {
+        if !symbol.references.is_empty() {
+            continue;
+        }
+        match symbol.kind {
+            SymbolKind::Variable if !symbol.name.starts_with('_') => warnings.push(warning(
+                Lint::UnusedVariable,
+                format!("unused variable `{}`", symbol.name),
+                vec![Label::primary(symbol.definition, "this is never used")],
+                vec![format!(
+                    "Hint: If this is intentional, rename it to `_{}`.",
+                    symbol.name
+                )],
+            )),
+            SymbolKind::Function
+                if symbol.function.class(db).is_some()
+                    && !symbol.function.is_public(db)
+                    && !symbol.name.starts_with("__") =>
+            {
+                warnings.push(warning(
+                    Lint::UnusedFunction,
+                    format!("function `{}` is never called", symbol.name),
+                    vec![Label::primary(symbol.definition, "this is never called")],
+                    vec![],
+                ))
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+/// Applies the levels of lints to their warnings. The warnings of allowed
+/// lints are left out, and those of denied lints become errors. Lints without
+/// a level, and other diagnostics, are left as they are.
+pub fn apply_levels(
+    diagnostics: Vec<Diagnostic>,
+    levels: &IndexMap<Lint, LintLevel>,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let lint = diagnostic.code.as_deref().and_then(Lint::from_id);
+            match lint.and_then(|lint| levels.get(&lint)) {
+                Some(LintLevel::Allow) => None,
+                Some(LintLevel::Deny) => {
+                    diagnostic.severity = Severity::Error;
+                    diagnostic.notes.push(format!(
+                        "Note: the lint `{}` is set to `deny`",
+                        lint.expect("level without a lint").id()
+                    ));
+                    Some(diagnostic)
+                }
+                Some(LintLevel::Warn) | None => Some(diagnostic),
+            }
+        })
+        .collect()
+}
+
+/// Removes the warnings of lints that are allowed by an `#allow(<lint>, ...)`
+/// comment. A comment on a line of its own allows the lints on the next line
+/// of code and on the lines indented under it, e.g. in the body of a
+/// function, and a comment after code allows them on its line and the lines
+/// indented under it. A warning is added for each unknown lint in a comment.
+pub fn remove_allowed(files: &FileStore, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let mut file_ids = files.all_files();
+    file_ids.sort();
+    let mut unknown = vec![];
+    let comments = file_ids
+        .into_iter()
+        .filter_map(|id| files.get_file(id))
+        .filter(|file| file.content.contains("#allow("))
+        .map(|file| (file.id, allow_comments(file, &mut unknown)))
+        .collect::<HashMap<_, _>>();
+
+    let mut diagnostics = diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let lint = match diagnostic.code.as_deref().and_then(Lint::from_id) {
+                Some(lint) => lint,
+                None => return true,
+            };
+            let span = match diagnostic
+                .labels
+                .iter()
+                .find(|label| label.style == LabelStyle::Primary)
+            {
+                Some(label) => label.span,
+                None => return true,
+            };
+            !comments.get(&span.file_id).map_or(false, |comments| {
+                comments.iter().any(|comment| {
+                    comment.lints.contains(&lint) && comment.range.contains(&span.start)
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    diagnostics.extend(unknown);
+    diagnostics
+}
+
+/// An `#allow(<lint>, ...)` comment, which allows the lints in the source code
+/// in `range`.
+struct AllowComment {
+    lints: Vec<Lint>,
+    range: Range<usize>,
+}
+
+/// The `#allow` comments of a file. The unknown lints that they name are added
+/// to `unknown` as warnings.
+fn allow_comments(file: &SourceFile, unknown: &mut Vec<Diagnostic>) -> Vec<AllowComment> {
+    let src = file.content.as_str();
+    let mut lines = vec![];
+    let mut start = 0;
+    for line in src.split_inclusive('\n') {
+        lines.push(start..start + line.len());
+        start += line.len();
+    }
+    let is_code = |line: &Range<usize>| {
+        let text = src[line.clone()].trim();
+        !text.is_empty() && !text.starts_with('#')
+    };
+    let indent = |line: &Range<usize>| {
+        let text = &src[line.clone()];
+        text.len() - text.trim_start().len()
+    };
+
+    let mut comments = vec![];
+    for token in Lexer::with_trivia(file.id, src) {
+        let ids = match token.kind {
+            TokenKind::Comment => token
+                .text
+                .strip_prefix("#allow(")
+                .and_then(|rest| rest.trim_end().strip_suffix(')')),
+            _ => None,
+        };
+        let ids = match ids {
+            Some(ids) => ids,
+            None => continue,
+        };
+
+        let mut lints = vec![];
+        for id in ids.split(',').map(str::trim) {
+            match Lint::from_id(id) {
+                Some(lint) => lints.push(lint),
+                None => unknown.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: None,
+                    message: format!("unknown lint `{}`", id),
+                    labels: vec![Label::primary(token.span, "this lint doesn't exist")],
+                    notes: vec![format!(
+                        "Note: the lints are {}",
+                        Lint::ALL
+                            .iter()
+                            .map(|lint| format!("`{}`", lint.id()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )],
+                }),
+            }
+        }
+
+        let line = file.line_index(token.span.start);
+        let first = if src[lines[line].start..token.span.start].trim().is_empty() {
+            match (line + 1..lines.len()).find(|index| is_code(&lines[*index])) {
+                Some(first) => first,
+                None => continue,
+            }
+        } else {
+            line
+        };
+        let mut last = first;
+        for index in first + 1..lines.len() {
+            if !is_code(&lines[index]) {
+                continue;
+            }
+            if indent(&lines[index]) <= indent(&lines[first]) {
+                break;
+            }
+            last = index;
+        }
+        comments.push(AllowComment {
+            lints,
+            range: lines[first].start..lines[last].end,
+        });
+    }
+    comments
+}
+
+fn is_balance_call(scope: &BlockScope, exp: &Node<fe::Expr>) -> bool {
+    if let fe::Expr::Call { func, .. } = &exp.kind {
+        matches!(
+            scope.root.body.borrow().calls.get(&func.id),
+            Some(CallType::BuiltinFunction(
+                GlobalFunction::Balance | GlobalFunction::BalanceOf
+            ))
+        )
+    } else {
+        false
+    }
+}
+
+/// Whether the expression is `block.<field>`, or any `block` attribute if
+/// `field` is `None`.
+fn is_block_field(exp: &Node<fe::Expr>, field: Option<&str>) -> bool {
+    match &exp.kind {
+        fe::Expr::Attribute { value, attr } => {
+            matches!(&value.kind, fe::Expr::Name(name) if name == "block")
+                && field.map_or(true, |field| attr.kind == field)
+        }
+        _ => false,
+    }
+}
+
+/// Whether any part of the expression reads a `block` attribute.
+fn reads_block(exp: &Node<fe::Expr>) -> bool {
+    if is_block_field(exp, None) {
+        return true;
+    }
+    match &exp.kind {
+        fe::Expr::Call { args, .. } => args.kind.iter().any(|arg| reads_block(&arg.kind.value)),
+        fe::Expr::BinOperation { left, right, .. } => reads_block(left) || reads_block(right),
+        fe::Expr::UnaryOperation { operand, .. } => reads_block(operand),
+        fe::Expr::Ternary {
+            if_expr, else_expr, ..
+        } => reads_block(if_expr) || reads_block(else_expr),
+        _ => false,
+    }
+}
+
+/// Whether the expression is a literal power of two, like `256` or `2**32`.
+fn is_power_of_two(exp: &Node<fe::Expr>) -> bool {
+    match &exp.kind {
+        fe::Expr::Num(num) => match numeric::Literal::new(num).parse::<BigInt>() {
+            Ok(num) => num > BigInt::zero() && (&num & (&num - BigInt::one())).is_zero(),
+            Err(_) => false,
+        },
+        fe::Expr::BinOperation { left, op, right } if op.kind == fe::BinOperator::Pow => {
+            matches!(&left.kind, fe::Expr::Num(num) if num == "2")
+                && matches!(&right.kind, fe::Expr::Num(_))
+        }
+        _ => false,
+    }
+}
@@ -1,4 +1,6 @@
+use crate::abi_import::expand_abi_imports;
 use crate::builtins;
+use crate::const_eval::ConstValue;
 use crate::context;
 use crate::context::Analysis;
 use crate::errors::{self, TypeError};
@@ -9,12 +11,11 @@ use crate::AnalyzerDb;
 use fe_common::diagnostics::Diagnostic;
 use fe_common::files::{FileStore, SourceFile, SourceFileId};
 use fe_parser::ast;
-use fe_parser::ast::Expr;
 use fe_parser::node::{Node, Span};
 use indexmap::indexmap;
 use indexmap::IndexMap;
 use smol_str::SmolStr;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::ops::Deref;
 use std::path::Path;
 use std::rc::Rc;
@@ -36,6 +37,7 @@ pub enum Item {
     Event(EventId),
     Function(FunctionId),
     Constant(ModuleConstantId),
+    Trait(TraitId),
     // Needed until we can represent keccak256 as a FunctionId.
     // We can't represent keccak256's arg type yet.
     BuiltinFunction(builtins::GlobalFunction),
@@ -56,6 +58,7 @@ impl Item {
             Item::Intrinsic(id) => id.as_ref().into(),
             Item::Object(id) => id.as_ref().into(),
             Item::Constant(id) => id.name(db),
+            Item::Trait(id) => id.name(db),
             Item::Ingot(id) => id.name(db),
             Item::Module(id) => id.name(db),
         }
@@ -71,6 +74,7 @@ impl Item {
             Item::Intrinsic(_) => None,
             Item::Object(_) => None,
             Item::Constant(id) => Some(id.name_span(db)),
+            Item::Trait(id) => Some(id.name_span(db)),
             Item::Ingot(_) => None,
             Item::Module(_) => None,
         }
@@ -87,6 +91,7 @@ impl Item {
             Item::Intrinsic(_) => true,
             Item::Object(_) => true,
             Item::Constant(_) => false,
+            Item::Trait(_) => false,
             Item::Ingot(_) => false,
             Item::Module(_) => false,
         }
@@ -106,6 +111,7 @@ impl Item {
             Item::Intrinsic(_) => "intrinsic function",
             Item::Object(_) => "object",
             Item::Constant(_) => "constant",
+            Item::Trait(_) => "trait",
             Item::Ingot(_) => "ingot",
             Item::Module(_) => "module",
         }
@@ -115,11 +121,15 @@ impl Item {
         match self {
             Item::Ingot(ingot) => ingot.items(db),
             Item::Module(module) => module.items(db),
+            // The variants of an enum are values, and are resolved by the
+            // expressions that use them.
+            Item::Type(TypeDef::Enum(_)) => Rc::new(indexmap! {}),
             Item::Type(_) => todo!("cannot access items in types yet"),
             Item::GenericType(_)
             | Item::Event(_)
             | Item::Function(_)
             | Item::Constant(_)
+            | Item::Trait(_)
             | Item::BuiltinFunction(_)
             | Item::Intrinsic(_)
             | Item::Object(_) => Rc::new(indexmap! {}),
@@ -136,6 +146,7 @@ impl Item {
             Item::Intrinsic(_) => None,
             Item::Object(_) => None,
             Item::Constant(id) => Some(id.parent(db)),
+            Item::Trait(id) => Some(id.parent(db)),
             Item::Ingot(_) => None,
             Item::Module(id) => id.parent(db),
         }
@@ -212,6 +223,7 @@ impl Item {
             Item::Function(id) => id.sink_diagnostics(db, sink),
             Item::BuiltinFunction(_) | Item::Intrinsic(_) | Item::Object(_) => {}
             Item::Constant(id) => id.sink_diagnostics(db, sink),
+            Item::Trait(id) => id.sink_diagnostics(db, sink),
             Item::Ingot(id) => id.sink_diagnostics(db, sink),
             Item::Module(id) => id.sink_diagnostics(db, sink),
         }
@@ -230,6 +242,10 @@ pub fn std_prelude_items() -> IndexMap<SmolStr, Item> {
             Item::Type(TypeDef::Primitive(types::Base::Numeric(typ))),
         )
     }));
+    items.extend((1..=types::MAX_FIXED_BYTES_SIZE).map(|size| {
+        let typ = types::Base::FixedBytes(size);
+        (typ.name(), Item::Type(TypeDef::Primitive(typ)))
+    }));
     items.extend(types::GenericType::iter().map(|typ| (typ.name(), Item::GenericType(typ))));
     items.extend(
         builtins::GlobalFunction::iter()
@@ -337,8 +353,9 @@ impl Ingot {
                 .filter_map(|file_id| {
                     let file = files.get_file(*file_id).expect("missing file for ID");
                     match fe_parser::parse_file(*file_id, &file.content) {
-                        Ok((ast, parser_diagnostics)) => {
+                        Ok((mut ast, parser_diagnostics)) => {
                             diagnostics.extend(parser_diagnostics);
+                            diagnostics.extend(expand_abi_imports(files, file, &mut ast));
                             Some((*file_id, (file.to_owned(), ast)))
                         }
                         Err(diagnostics) => {
@@ -478,8 +495,9 @@ impl Module {
             .to_string_lossy()
             .to_string();
 
-        let (ast, parser_diagnostics) = fe_parser::parse_file(file_id, &file.content)?;
+        let (mut ast, parser_diagnostics) = fe_parser::parse_file(file_id, &file.content)?;
         diagnostics.extend(parser_diagnostics);
+        diagnostics.extend(expand_abi_imports(files, file, &mut ast));
 
         let module = Module {
             name: name.into(),
@@ -789,7 +807,8 @@ impl ModuleId {
         db.module_parent_module(*self)
     }
 
-    /// All contracts, including duplicates
+    /// All contracts, including duplicates, except for interfaces, which
+    /// aren't compiled.
     pub fn all_contracts(&self, db: &dyn AnalyzerDb) -> Rc<Vec<ContractId>> {
         db.module_contracts(*self)
     }
@@ -826,6 +845,23 @@ impl ModuleId {
         db.module_structs(*self)
     }
 
+    /// The instances of the generic functions and structs of the module that
+    /// are used in its ingot, e.g. `max<u8>` for `fn max<T: Numeric>(..)`.
+    pub fn generic_instances(&self, db: &dyn AnalyzerDb) -> Rc<Vec<Item>> {
+        db.module_generic_instances(*self)
+    }
+
+    /// The trait implementations of the module, e.g. `impl Describe for Point:`.
+    pub fn impls(&self, db: &dyn AnalyzerDb) -> Rc<Vec<ImplId>> {
+        db.module_impls(*self)
+    }
+
+    /// The diamonds of the module: each storage layout struct that is used by
+    /// facets, and its facets in declaration order.
+    pub fn diamonds(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<StructId, Vec<ContractId>>> {
+        db.module_diamonds(*self).value
+    }
+
     pub fn diagnostics(&self, db: &dyn AnalyzerDb) -> Vec<Diagnostic> {
         let mut diagnostics = vec![];
         self.sink_diagnostics(db, &mut diagnostics);
@@ -845,10 +881,43 @@ impl ModuleId {
         // duplicate item name errors
         sink.push_all(db.module_item_map(*self).diagnostics.iter());
 
+        // selector clashes between facets of a diamond
+        sink.push_all(db.module_diamonds(*self).diagnostics.iter());
+
         // errors for each item
+        let mut item_diagnostics = vec![];
         self.all_items(db)
+            .iter()
+            .for_each(|id| id.sink_diagnostics(db, &mut item_diagnostics));
+        sink.push_all(item_diagnostics.iter());
+
+        // missing, unknown and mismatched functions of trait implementations
+        self.impls(db)
             .iter()
             .for_each(|id| id.sink_diagnostics(db, sink));
+
+        // errors in the instances of generic functions and structs, except for
+        // those that were already reported for the generic items themselves
+        let mut instance_diagnostics = vec![];
+        for item in self.generic_instances(db).iter() {
+            match item {
+                Item::Type(TypeDef::Struct(struct_)) => {
+                    db.struct_all_fields(*struct_)
+                        .iter()
+                        .for_each(|id| id.sink_diagnostics(db, &mut instance_diagnostics));
+                    db.struct_all_functions(*struct_)
+                        .iter()
+                        .for_each(|id| id.sink_diagnostics(db, &mut instance_diagnostics));
+                }
+                _ => item.sink_diagnostics(db, &mut instance_diagnostics),
+            }
+        }
+        let mut seen = item_diagnostics.into_iter().collect::<HashSet<_>>();
+        for diag in instance_diagnostics {
+            if seen.insert(diag.clone()) {
+                sink.push(&diag);
+            }
+        }
     }
 }
 
@@ -856,6 +925,9 @@ impl ModuleId {
 pub struct ModuleConstant {
     pub ast: Node<ast::ConstantDecl>,
     pub module: ModuleId,
+    /// The contract that the constant is declared in, if it's a `const` field
+    /// of a contract rather than a module constant.
+    pub contract: Option<ContractId>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -889,8 +961,29 @@ impl ModuleConstantId {
         self.data(db).ast.kind.value.kind.clone()
     }
 
+    /// The value of the constant, computed at compile time.
+    pub fn const_value(&self, db: &dyn AnalyzerDb) -> Result<ConstValue, TypeError> {
+        db.module_constant_value(*self).value
+    }
+
+    pub fn contract(&self, db: &dyn AnalyzerDb) -> Option<ContractId> {
+        self.data(db).contract
+    }
+
+    /// Resolves a name used in the constant's value, the same way it's
+    /// resolved in a function of the constant's contract or module.
+    pub fn resolve_name(&self, db: &dyn AnalyzerDb, name: &str) -> Option<Item> {
+        match self.contract(db) {
+            Some(contract) => contract.resolve_name(db, name),
+            None => self.data(db).module.resolve_name(db, name),
+        }
+    }
+
     pub fn parent(&self, db: &dyn AnalyzerDb) -> Item {
-        Item::Module(self.data(db).module)
+        match self.contract(db) {
+            Some(contract) => Item::Type(TypeDef::Contract(contract)),
+            None => Item::Module(self.data(db).module),
+        }
     }
 
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
@@ -898,17 +991,10 @@ impl ModuleConstantId {
             .diagnostics
             .iter()
             .for_each(|d| sink.push(d));
-
-        if !matches!(
-            self.value(db),
-            Expr::Bool(_) | Expr::Num(_) | Expr::Str(_) | Expr::Unit
-        ) {
-            sink.push(&errors::error(
-                "non-literal expressions not yet supported for constants",
-                self.data(db).ast.kind.value.span,
-                "not a literal",
-            ))
-        }
+        db.module_constant_value(*self)
+            .diagnostics
+            .iter()
+            .for_each(|d| sink.push(d));
     }
 }
 
@@ -916,14 +1002,28 @@ impl ModuleConstantId {
 pub enum TypeDef {
     Alias(TypeAliasId),
     Struct(StructId),
+    Enum(EnumId),
     Contract(ContractId),
     Primitive(types::Base),
 }
 impl TypeDef {
+    /// The type definition of a type that has a name, which is a primitive
+    /// type, struct, enum or contract. Only these can be type arguments.
+    pub fn from_type(typ: &types::Type) -> Option<Self> {
+        match typ {
+            types::Type::Base(base) => Some(TypeDef::Primitive(*base)),
+            types::Type::Struct(struct_) => Some(TypeDef::Struct(struct_.id)),
+            types::Type::Enum(enum_) => Some(TypeDef::Enum(enum_.id)),
+            types::Type::Contract(contract) => Some(TypeDef::Contract(contract.id)),
+            _ => None,
+        }
+    }
+
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
         match self {
             TypeDef::Alias(id) => id.name(db),
             TypeDef::Struct(id) => id.name(db),
+            TypeDef::Enum(id) => id.name(db),
             TypeDef::Contract(id) => id.name(db),
             TypeDef::Primitive(typ) => typ.name(),
         }
@@ -933,6 +1033,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => Some(id.name_span(db)),
             TypeDef::Struct(id) => Some(id.name_span(db)),
+            TypeDef::Enum(id) => Some(id.name_span(db)),
             TypeDef::Contract(id) => Some(id.name_span(db)),
             TypeDef::Primitive(_) => None,
         }
@@ -946,6 +1047,7 @@ impl TypeDef {
                 name: id.name(db),
                 field_count: id.fields(db).len(), // for the EvmSized trait
             })),
+            TypeDef::Enum(id) => Ok(types::Type::Enum(id.typ(db))),
             TypeDef::Contract(id) => Ok(types::Type::Contract(types::Contract {
                 id: *id,
                 name: id.name(db),
@@ -958,6 +1060,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => Some(id.parent(db)),
             TypeDef::Struct(id) => Some(id.parent(db)),
+            TypeDef::Enum(id) => Some(id.parent(db)),
             TypeDef::Contract(id) => Some(id.parent(db)),
             TypeDef::Primitive(_) => None,
         }
@@ -967,6 +1070,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => id.sink_diagnostics(db, sink),
             TypeDef::Struct(id) => id.sink_diagnostics(db, sink),
+            TypeDef::Enum(id) => id.sink_diagnostics(db, sink),
             TypeDef::Contract(id) => id.sink_diagnostics(db, sink),
             TypeDef::Primitive(_) => {}
         }
@@ -1017,6 +1121,14 @@ pub struct Contract {
     pub module: ModuleId,
 }
 
+/// The fields, events and functions that qualifiers like `ownable` add to a
+/// contract.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct InjectedMembers {
+    pub fields: Vec<Node<ast::Field>>,
+    pub body: Vec<ast::ContractStmt>,
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 pub struct ContractId(pub(crate) u32);
 impl_intern_key!(ContractId);
@@ -1052,11 +1164,27 @@ impl ContractId {
         Some((field.typ(db), index))
     }
 
+    /// The storage layout struct, if the contract is a diamond facet.
+    pub fn layout(&self, db: &dyn AnalyzerDb) -> Option<StructId> {
+        db.contract_layout(*self).value
+    }
+
+    /// The `const` fields of the contract, which are constants rather than
+    /// storage fields.
+    pub fn constants(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, ModuleConstantId>> {
+        db.contract_constant_map(*self).value
+    }
+
+    pub fn constant(&self, db: &dyn AnalyzerDb, name: &str) -> Option<ModuleConstantId> {
+        self.constants(db).get(name).copied()
+    }
+
     pub fn resolve_name(&self, db: &dyn AnalyzerDb, name: &str) -> Option<Item> {
         self.function(db, name)
             .filter(|f| !f.takes_self(db))
             .map(Item::Function)
             .or_else(|| self.event(db, name).map(Item::Event))
+            .or_else(|| self.constant(db, name).map(Item::Constant))
             .or_else(|| self.module(db).resolve_name(db, name))
     }
 
@@ -1068,6 +1196,69 @@ impl ContractId {
         db.contract_call_function(*self).value
     }
 
+    /// The function that the dispatcher calls when no selector matches.
+    pub fn fallback_function(&self, db: &dyn AnalyzerDb) -> Option<FunctionId> {
+        db.contract_fallback_function(*self).value
+    }
+
+    /// The function that the dispatcher calls when the calldata is empty.
+    pub fn receive_function(&self, db: &dyn AnalyzerDb) -> Option<FunctionId> {
+        db.contract_receive_function(*self).value
+    }
+
+    /// The `initialize` function, if the contract is `upgradeable`.
+    pub fn initialize_function(&self, db: &dyn AnalyzerDb) -> Option<FunctionId> {
+        db.contract_initialize_function(*self).value
+    }
+
+    pub fn is_upgradeable(&self, db: &dyn AnalyzerDb) -> bool {
+        self.upgradeable_span(db).is_some()
+    }
+    pub fn upgradeable_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.upgradeable_qual
+    }
+
+    /// Whether the contract gets a generated `multicall` function.
+    pub fn is_multicall(&self, db: &dyn AnalyzerDb) -> bool {
+        self.multicall_span(db).is_some()
+    }
+    pub fn multicall_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.multicall_qual
+    }
+
+    /// Whether the contract has an owner, who is set when it's deployed.
+    pub fn is_ownable(&self, db: &dyn AnalyzerDb) -> bool {
+        self.ownable_span(db).is_some()
+    }
+    pub fn ownable_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.ownable_qual
+    }
+    pub fn pausable_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.pausable_qual
+    }
+
+    /// Whether the contract grants roles to accounts, which `only_role`
+    /// functions check.
+    pub fn has_roles(&self, db: &dyn AnalyzerDb) -> bool {
+        self.roles_span(db).is_some()
+    }
+    pub fn roles_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.roles_qual
+    }
+
+    /// Whether the contract is an interface, which only declares the public
+    /// functions of a contract that's deployed elsewhere. Interfaces aren't
+    /// compiled.
+    pub fn is_interface(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.is_interface
+    }
+
+    /// The members that the contract's qualifiers add to it, which are
+    /// included in its fields, events and functions.
+    pub fn injected_members(&self, db: &dyn AnalyzerDb) -> Rc<InjectedMembers> {
+        db.contract_injected_members(*self).value
+    }
+
     /// User functions, public and not. Excludes `__init__` and `__call__`.
     pub fn functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, FunctionId>> {
         db.contract_function_map(*self).value
@@ -1079,7 +1270,7 @@ impl ContractId {
         self.functions(db).get(name).copied()
     }
 
-    /// Excludes `__init__` and `__call__`.
+    /// Excludes `__init__`, `__call__`, `fallback` and `receive`.
     pub fn public_functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, FunctionId>> {
         db.contract_public_function_map(*self)
     }
@@ -1123,6 +1314,13 @@ impl ContractId {
         db.contract_all_fields(*self)
             .iter()
             .for_each(|field| field.sink_diagnostics(db, sink));
+        db.contract_layout(*self).sink_diagnostics(sink);
+
+        // constants
+        db.contract_constant_map(*self).sink_diagnostics(sink);
+        self.constants(db)
+            .values()
+            .for_each(|constant| constant.sink_diagnostics(db, sink));
 
         // events
         db.contract_event_map(*self).sink_diagnostics(sink);
@@ -1132,7 +1330,13 @@ impl ContractId {
 
         // functions
         db.contract_init_function(*self).sink_diagnostics(sink);
+        db.contract_initialize_function(*self)
+            .sink_diagnostics(sink);
         db.contract_call_function(*self).sink_diagnostics(sink);
+        db.contract_fallback_function(*self).sink_diagnostics(sink);
+        db.contract_receive_function(*self).sink_diagnostics(sink);
+        db.contract_multicall(*self).sink_diagnostics(sink);
+        db.contract_injected_members(*self).sink_diagnostics(sink);
         db.contract_function_map(*self).sink_diagnostics(sink);
         db.contract_all_functions(*self)
             .iter()
@@ -1169,6 +1373,10 @@ pub struct Function {
     pub ast: Node<ast::Function>,
     pub module: ModuleId,
     pub parent: Option<Class>,
+    /// The types that the type parameters of a generic function stand for in
+    /// this instance of it. Empty for other functions, and for the generic
+    /// function itself.
+    pub type_args: Vec<TypeDef>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -1187,6 +1395,61 @@ impl FunctionId {
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    /// The name of the function, followed by its type arguments if it's an
+    /// instance of a generic function, e.g. `max<u8>`.
+    pub fn display_name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        generic_display_name(db, &self.name(db), &self.data(db).type_args)
+    }
+
+    /// Whether the function is generic and hasn't been instantiated, i.e. it
+    /// declares type parameters, or belongs to a generic struct. The body of a
+    /// generic function is only checked in its instances.
+    pub fn is_generic(&self, db: &dyn AnalyzerDb) -> bool {
+        let data = self.data(db);
+        match data.parent {
+            Some(Class::Struct(struct_)) if struct_.is_generic(db) => true,
+            _ => !data.ast.kind.generic_params.is_empty() && data.type_args.is_empty(),
+        }
+    }
+    /// The type parameters declared by the function, e.g. `<T: Numeric>`.
+    pub fn generic_params(&self, db: &dyn AnalyzerDb) -> Vec<Node<ast::GenericParameter>> {
+        self.data(db).ast.kind.generic_params.clone()
+    }
+    pub fn type_args(&self, db: &dyn AnalyzerDb) -> Vec<TypeDef> {
+        self.data(db).type_args.clone()
+    }
+    /// The type parameters that are in scope in the function, which are its
+    /// own and those of its struct, with the types they stand for.
+    pub fn type_params(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, TypeDef> {
+        let data = self.data(db);
+        let mut params = match data.parent {
+            Some(Class::Struct(struct_)) => struct_.type_params(db),
+            _ => IndexMap::new(),
+        };
+        params.extend(bind_type_params(
+            &data.ast.kind.generic_params,
+            &data.type_args,
+        ));
+        params
+    }
+    /// The instance of a generic function with the given type arguments.
+    /// The type arguments of a function of a generic struct are those of the
+    /// struct, and the function of the struct's instance is returned.
+    pub fn instantiate(&self, db: &dyn AnalyzerDb, type_args: Vec<TypeDef>) -> FunctionId {
+        let data = self.data(db);
+        if let Some(Class::Struct(struct_)) = data.parent {
+            return struct_
+                .instantiate(db, type_args)
+                .function(db, data.ast.name())
+                .expect("missing function of struct instance");
+        }
+        db.intern_function(Rc::new(Function {
+            ast: data.ast.clone(),
+            module: data.module,
+            parent: data.parent,
+            type_args,
+        }))
+    }
 
     // This should probably be scrapped in favor of `parent()`
     pub fn class(&self, db: &dyn AnalyzerDb) -> Option<Class> {
@@ -1203,6 +1466,20 @@ impl FunctionId {
         self.data(db).module
     }
 
+    /// Whether the function was added to its contract by a qualifier like
+    /// `ownable`, rather than written by the user.
+    pub fn is_injected(&self, db: &dyn AnalyzerDb) -> bool {
+        let id = self.data(db).ast.id;
+        match self.class(db) {
+            Some(Class::Contract(contract)) => contract
+                .injected_members(db)
+                .body
+                .iter()
+                .any(|stmt| matches!(stmt, ast::ContractStmt::Function(node) if node.id == id)),
+            _ => false,
+        }
+    }
+
     pub fn takes_self(&self, db: &dyn AnalyzerDb) -> bool {
         self.signature(db).self_decl.is_some()
     }
@@ -1219,21 +1496,61 @@ impl FunctionId {
         }
     }
 
+    /// The names of the function's named return values. Empty if the outputs
+    /// are unnamed.
+    pub fn return_names(&self, db: &dyn AnalyzerDb) -> Vec<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .return_names
+            .iter()
+            .map(|name| name.kind.clone())
+            .collect()
+    }
+    pub fn has_named_outputs(&self, db: &dyn AnalyzerDb) -> bool {
+        !self.data(db).ast.kind.return_names.is_empty()
+    }
+    /// The types of the named return values, in order. Multiple named values
+    /// are returned as a tuple.
+    pub fn named_output_types(&self, db: &dyn AnalyzerDb) -> Vec<types::FixedSize> {
+        let count = self.data(db).ast.kind.return_names.len();
+        match self.signature(db).return_type.clone() {
+            Ok(types::FixedSize::Tuple(tuple)) if count > 1 => tuple.items.to_vec(),
+            Ok(typ) if count > 0 => vec![typ],
+            _ => vec![],
+        }
+    }
+
     pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
         self.pub_span(db).is_some()
     }
+    /// Whether the function is declared by an interface, and so has no body.
+    pub fn is_interface_function(&self, db: &dyn AnalyzerDb) -> bool {
+        matches!(self.class(db), Some(Class::Contract(contract)) if contract.is_interface(db))
+    }
     pub fn is_constructor(&self, db: &dyn AnalyzerDb) -> bool {
         self.name(db) == "__init__"
     }
     pub fn pub_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
         self.data(db).ast.kind.pub_
     }
+    pub fn is_const(&self, db: &dyn AnalyzerDb) -> bool {
+        self.const_span(db).is_some()
+    }
+    pub fn const_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        self.data(db).ast.kind.const_
+    }
     pub fn is_unsafe(&self, db: &dyn AnalyzerDb) -> bool {
         self.unsafe_span(db).is_some()
     }
     pub fn unsafe_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
         self.data(db).ast.kind.unsafe_
     }
+    /// Whether the function has the `test` qualifier, so that `fe test` runs
+    /// it.
+    pub fn is_test(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.test.is_some()
+    }
     pub fn signature(&self, db: &dyn AnalyzerDb) -> Rc<types::FunctionSignature> {
         db.function_signature(*self).value
     }
@@ -1245,7 +1562,11 @@ impl FunctionId {
     }
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
         sink.push_all(db.function_signature(*self).diagnostics.iter());
-        sink.push_all(db.function_body(*self).diagnostics.iter());
+        // The body of a generic function is checked with placeholder types,
+        // so its errors are only reported for the instances.
+        if !self.is_generic(db) {
+            sink.push_all(db.function_body(*self).diagnostics.iter());
+        }
     }
 }
 
@@ -1297,6 +1618,10 @@ pub enum MemberFunction {
 pub struct Struct {
     pub ast: Node<ast::Struct>,
     pub module: ModuleId,
+    /// The types that the type parameters of a generic struct stand for in
+    /// this instance of it. Empty for other structs, and for the generic
+    /// struct itself.
+    pub type_args: Vec<TypeDef>,
 }
 
 #[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -1309,8 +1634,11 @@ impl StructId {
     pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.span
     }
+    /// The name of the struct, followed by its type arguments if it's an
+    /// instance of a generic struct, e.g. `Pair<u8>`.
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        let data = self.data(db);
+        generic_display_name(db, data.ast.name(), &data.type_args)
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
@@ -1318,6 +1646,36 @@ impl StructId {
     pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
         self.data(db).module
     }
+    /// Whether the struct declares type parameters, and isn't an instance.
+    pub fn is_generic(&self, db: &dyn AnalyzerDb) -> bool {
+        let data = self.data(db);
+        !data.ast.kind.generic_params.is_empty() && data.type_args.is_empty()
+    }
+    pub fn generic_params(&self, db: &dyn AnalyzerDb) -> Vec<Node<ast::GenericParameter>> {
+        self.data(db).ast.kind.generic_params.clone()
+    }
+    pub fn type_args(&self, db: &dyn AnalyzerDb) -> Vec<TypeDef> {
+        self.data(db).type_args.clone()
+    }
+    /// The type parameters of the struct, with the types they stand for.
+    pub fn type_params(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, TypeDef> {
+        let data = self.data(db);
+        bind_type_params(&data.ast.kind.generic_params, &data.type_args)
+    }
+    /// The instance of a generic struct with the given type arguments.
+    pub fn instantiate(&self, db: &dyn AnalyzerDb, type_args: Vec<TypeDef>) -> StructId {
+        let data = self.data(db);
+        db.intern_struct(Rc::new(Struct {
+            ast: data.ast.clone(),
+            module: data.module,
+            type_args,
+        }))
+    }
+    /// Returns `true` if the struct was declared as a custom error, e.g.
+    /// `error Unauthorized(caller: address)`.
+    pub fn is_error(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.is_error
+    }
     pub fn typ(&self, db: &dyn AnalyzerDb) -> Rc<types::Struct> {
         db.struct_type(*self)
     }
@@ -1387,6 +1745,132 @@ impl StructId {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Enum {
+    pub ast: Node<ast::Enum>,
+    pub module: ModuleId,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct EnumId(pub(crate) u32);
+impl_intern_key!(EnumId);
+impl EnumId {
+    pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<Enum> {
+        db.lookup_intern_enum(*self)
+    }
+    pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.span
+    }
+    pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        self.data(db).ast.kind.name.kind.clone()
+    }
+    pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.kind.name.span
+    }
+    pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
+        self.data(db).module
+    }
+    pub fn typ(&self, db: &dyn AnalyzerDb) -> types::Enum {
+        types::Enum::from_id(*self, db)
+    }
+
+    /// The index of each variant, which is the value that encodes it.
+    pub fn variants(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, usize>> {
+        db.enum_variant_map(*self).value
+    }
+    pub fn variant_index(&self, db: &dyn AnalyzerDb, name: &str) -> Option<usize> {
+        self.variants(db).get(name).copied()
+    }
+    pub fn parent(&self, db: &dyn AnalyzerDb) -> Item {
+        Item::Module(self.data(db).module)
+    }
+    pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink.push_all(db.enum_variant_map(*self).diagnostics.iter());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Trait {
+    pub ast: Node<ast::Trait>,
+    pub module: ModuleId,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct TraitId(pub(crate) u32);
+impl_intern_key!(TraitId);
+impl TraitId {
+    pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<Trait> {
+        db.lookup_intern_trait(*self)
+    }
+    pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.span
+    }
+    pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        self.data(db).ast.name().into()
+    }
+    pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.kind.name.span
+    }
+    pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
+        self.data(db).module
+    }
+
+    /// The functions of the trait by name. Functions without a body must be
+    /// defined by each implementation.
+    pub fn functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, Node<ast::Function>>> {
+        db.trait_function_map(*self).value
+    }
+    pub fn parent(&self, db: &dyn AnalyzerDb) -> Item {
+        Item::Module(self.data(db).module)
+    }
+    pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink.push_all(db.trait_function_map(*self).diagnostics.iter());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Impl {
+    pub ast: Node<ast::Impl>,
+    pub module: ModuleId,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct ImplId(pub(crate) u32);
+impl_intern_key!(ImplId);
+impl ImplId {
+    pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<Impl> {
+        db.lookup_intern_impl(*self)
+    }
+    pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.span
+    }
+    pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
+        self.data(db).module
+    }
+    /// The name of the struct or contract that implements the trait.
+    pub fn receiver_name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        self.data(db).ast.kind.receiver.kind.clone()
+    }
+    pub fn trait_(&self, db: &dyn AnalyzerDb) -> Option<TraitId> {
+        db.impl_trait(*self).value
+    }
+    pub fn receiver(&self, db: &dyn AnalyzerDb) -> Option<Class> {
+        db.impl_receiver(*self).value
+    }
+
+    /// The functions that the implementation adds to its receiver: the
+    /// functions it defines, and the default functions of the trait that it
+    /// doesn't define.
+    pub fn functions(&self, db: &dyn AnalyzerDb) -> Rc<Vec<Node<ast::Function>>> {
+        db.impl_all_functions(*self)
+    }
+    pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink.push_all(db.impl_trait(*self).diagnostics.iter());
+        sink.push_all(db.impl_receiver(*self).diagnostics.iter());
+        sink.push_all(db.impl_check(*self).diagnostics.iter());
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct StructField {
     pub ast: Node<ast::Field>,
@@ -1452,6 +1936,37 @@ impl EventId {
     }
 }
 
+/// Binds the type parameters of a generic function or struct to its type
+/// arguments. The parameters of a generic function or struct that hasn't been
+/// instantiated stand for `u256`, so that its signature can be checked.
+fn bind_type_params(
+    params: &[Node<ast::GenericParameter>],
+    type_args: &[TypeDef],
+) -> IndexMap<SmolStr, TypeDef> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(index, param)| {
+            let typ = type_args
+                .get(index)
+                .copied()
+                .unwrap_or_else(|| TypeDef::Primitive(types::Base::u256()));
+            (param.kind.name.kind.clone(), typ)
+        })
+        .collect()
+}
+
+fn generic_display_name(db: &dyn AnalyzerDb, name: &str, type_args: &[TypeDef]) -> SmolStr {
+    if type_args.is_empty() {
+        return name.into();
+    }
+    let args = type_args
+        .iter()
+        .map(|typ| typ.name(db).to_string())
+        .collect::<Vec<_>>();
+    format!("{}<{}>", name, args.join(", ")).into()
+}
+
 pub trait DiagnosticSink {
     fn push(&mut self, diag: &Diagnostic);
     fn push_all<'a>(&mut self, iter: impl Iterator<Item = &'a Diagnostic>) {
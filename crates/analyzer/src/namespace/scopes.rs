@@ -3,26 +3,40 @@
 use crate::context::{AnalyzerContext, CallType, ExpressionAttributes, FunctionBody, NamedThing};
 use crate::errors::{AlreadyDefined, TypeError};
 use crate::namespace::items::Item;
-use crate::namespace::items::{Class, EventId, FunctionId, ModuleId};
+use crate::namespace::items::{Class, EventId, FunctionId, ModuleId, TypeDef};
 use crate::namespace::types::FixedSize;
 use crate::AnalyzerDb;
 use fe_common::diagnostics::Diagnostic;
 use fe_common::Span;
 use fe_parser::ast;
 use fe_parser::node::Node;
+use indexmap::IndexMap;
+use smol_str::SmolStr;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 
 pub struct ItemScope<'a> {
     db: &'a dyn AnalyzerDb,
     module: ModuleId,
+    type_params: IndexMap<SmolStr, TypeDef>,
     pub diagnostics: Vec<Diagnostic>,
 }
 impl<'a> ItemScope<'a> {
     pub fn new(db: &'a dyn AnalyzerDb, module: ModuleId) -> Self {
+        Self::with_type_params(db, module, IndexMap::new())
+    }
+
+    /// A scope in which the type parameters of a generic function or struct
+    /// resolve to the given types.
+    pub fn with_type_params(
+        db: &'a dyn AnalyzerDb,
+        module: ModuleId,
+        type_params: IndexMap<SmolStr, TypeDef>,
+    ) -> Self {
         Self {
             db,
             module,
+            type_params,
             diagnostics: vec![],
         }
     }
@@ -33,6 +47,9 @@ impl<'a> AnalyzerContext for ItemScope<'a> {
         self.db
     }
     fn resolve_name(&self, name: &str) -> Option<NamedThing> {
+        if let Some(typ) = self.type_params.get(name) {
+            return Some(NamedThing::Item(Item::Type(*typ)));
+        }
         self.module
             .resolve_name(self.db, name)
             .map(NamedThing::Item)
@@ -123,6 +140,19 @@ impl<'a> FunctionScope<'a> {
             .insert(node.id, typ)
             .expect_none("declaration attributes already exist");
     }
+    /// Attribute contextual information to a `match` pattern node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry already exists for the node id.
+    pub fn add_pattern(&self, node: &Node<ast::Pattern>, typ: FixedSize) {
+        self.add_node(node);
+        self.body
+            .borrow_mut()
+            .pattern_types
+            .insert(node.id, typ)
+            .expect_none("pattern attributes already exist");
+    }
     /// Attribute contextual information to a call expression node.
     /// NOTE: `node` here is actually the `func` node of the `Expr::Call`.
     ///
@@ -140,6 +170,22 @@ impl<'a> FunctionScope<'a> {
             .expect_none("call attributes already exist");
     }
 
+    /// Records that an expression node reads the execution environment, e.g.
+    /// `msg.sender`. `path` is the source form of the read.
+    pub fn add_env_read(&self, node: &Node<ast::Expr>, path: SmolStr) {
+        self.body.borrow_mut().env_reads.insert(node.id, path);
+    }
+
+    /// Records that a name node refers to the variable defined at
+    /// `definition`, the span of the variable's name. The node is usually a
+    /// name expression, but may be a name in an inline Yul block.
+    pub fn add_variable_ref<T>(&self, node: &Node<T>, definition: Span) {
+        self.body
+            .borrow_mut()
+            .variable_refs
+            .insert(node.id, definition);
+    }
+
     fn add_node<T>(&self, node: &Node<T>) {
         self.body.borrow_mut().spans.insert(node.id, node.span);
     }
@@ -187,6 +233,12 @@ impl<'a> AnalyzerContext for FunctionScope<'a> {
                     }
                 })
             })
+            .or_else(|| {
+                self.function
+                    .type_params(self.db)
+                    .get(name)
+                    .map(|typ| NamedThing::Item(Item::Type(*typ)))
+            })
             .or_else(|| {
                 if let Some(Class::Contract(contract)) = self.function.class(self.db) {
                     contract.resolve_name(self.db, name)
@@ -1,5 +1,5 @@
 use crate::errors::{NotFixedSize, TypeError};
-use crate::namespace::items::{Class, ContractId, StructId};
+use crate::namespace::items::{Class, ContractId, EnumId, StructId, TypeDef};
 use crate::AnalyzerDb;
 
 use num_bigint::BigInt;
@@ -7,7 +7,7 @@ use num_traits::ToPrimitive;
 use smol_str::SmolStr;
 use std::fmt;
 use std::str::FromStr;
-use strum::{AsRefStr, EnumIter, EnumString};
+use strum::{AsRefStr, EnumIter, EnumString, IntoEnumIterator};
 use vec1::Vec1;
 
 pub fn u256_min() -> BigInt {
@@ -36,6 +36,8 @@ pub trait SafeNames {
 pub enum Type {
     Base(Base),
     Array(Array),
+    /// A dynamically-sized array, which can only be stored in a contract field.
+    DynamicArray(DynamicArray),
     Map(Map),
     Tuple(Tuple),
     String(FeString),
@@ -45,6 +47,9 @@ pub enum Type {
     /// of `self` within a contract function.
     SelfContract(Contract),
     Struct(Struct),
+    Enum(Enum),
+    Option(FeOption),
+    Result(FeResult),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -55,6 +60,9 @@ pub enum FixedSize {
     String(FeString),
     Contract(Contract),
     Struct(Struct),
+    Enum(Enum),
+    Option(FeOption),
+    Result(FeResult),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -62,15 +70,22 @@ pub enum Base {
     Numeric(Integer),
     Bool,
     Address,
+    /// `bytes1` to `bytes32`, with the given number of bytes. Values are
+    /// stored left-aligned in a word, like in the ABI.
+    FixedBytes(usize),
     Unit,
 }
 
+/// The largest size of a fixed-size bytes type, `bytes32`.
+pub const MAX_FIXED_BYTES_SIZE: usize = 32;
+
 impl Base {
     pub fn name(&self) -> SmolStr {
         match self {
             Base::Numeric(num) => num.as_ref().into(),
             Base::Bool => "bool".into(),
             Base::Address => "address".into(),
+            Base::FixedBytes(size) => format!("bytes{}", size).into(),
             Base::Unit => "()".into(),
         }
     }
@@ -106,6 +121,14 @@ pub struct Array {
     pub inner: Base,
 }
 
+/// An `Array<T>` without a size. Like in Solidity, its length is stored at the
+/// storage word of the array, and its items start at the storage word whose
+/// address is the hash of the address of the length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DynamicArray {
+    pub inner: Base,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Map {
     pub key: Base,
@@ -133,6 +156,27 @@ impl Struct {
     }
 }
 
+/// An enum type. A value is the index of its variant, so it fits in a `u8`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Enum {
+    pub name: SmolStr,
+    pub id: EnumId,
+    pub variant_count: usize,
+}
+impl Enum {
+    pub fn from_id(id: EnumId, db: &dyn AnalyzerDb) -> Self {
+        Self {
+            name: id.name(db),
+            id,
+            variant_count: id.variants(db).len(),
+        }
+    }
+}
+
+/// The largest number of variants of an enum, whose values are encoded as
+/// `u8`s.
+pub const MAX_ENUM_VARIANTS: usize = 256;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Contract {
     pub name: SmolStr,
@@ -152,6 +196,22 @@ pub struct FeString {
     pub max_size: usize,
 }
 
+/// A value that may be absent. Reading a `Map<K, Option<V>>` entry that has
+/// never been written yields `none`, which can be told apart from a stored
+/// zero value because every `Option` carries a presence flag.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub struct FeOption {
+    pub inner: Base,
+}
+
+/// The outcome of an operation that may fail: either an `ok` value or an
+/// `err` value. Errors can be propagated to the caller with the `?` operator.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub struct FeResult {
+    pub ok: Base,
+    pub err: Base,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FunctionSignature {
     pub self_decl: Option<SelfDecl>,
@@ -177,6 +237,8 @@ pub enum GenericType {
     Array,
     String,
     Map,
+    Option,
+    Result,
 }
 
 impl GenericType {
@@ -209,6 +271,20 @@ impl GenericType {
                     kind: GenericParamKind::Int,
                 },
             ],
+            GenericType::Option => vec![GenericParam {
+                name: "inner type".into(),
+                kind: GenericParamKind::PrimitiveType,
+            }],
+            GenericType::Result => vec![
+                GenericParam {
+                    name: "ok type".into(),
+                    kind: GenericParamKind::PrimitiveType,
+                },
+                GenericParam {
+                    name: "error type".into(),
+                    kind: GenericParamKind::PrimitiveType,
+                },
+            ],
         }
     }
 
@@ -233,6 +309,22 @@ impl GenericType {
                     size: *size,
                     inner: element.as_primitive()?,
                 })),
+                [GenericArg::Type(element)] => Some(Type::DynamicArray(DynamicArray {
+                    inner: element.as_primitive()?,
+                })),
+                _ => None,
+            },
+            GenericType::Option => match args {
+                [GenericArg::Type(inner)] => Some(Type::Option(FeOption {
+                    inner: inner.as_primitive()?,
+                })),
+                _ => None,
+            },
+            GenericType::Result => match args {
+                [GenericArg::Type(ok), GenericArg::Type(err)] => Some(Type::Result(FeResult {
+                    ok: ok.as_primitive()?,
+                    err: err.as_primitive()?,
+                })),
                 _ => None,
             },
         }
@@ -260,6 +352,27 @@ pub enum GenericArg {
     Type(Type),
 }
 
+/// A bound on a type parameter of a generic function or struct, e.g. the
+/// `Numeric` in `fn max<T: Numeric>(a: T, b: T) -> T`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+pub enum TypeBound {
+    /// Any integer type.
+    Numeric,
+    /// Any primitive type, i.e. an integer, `bool`, `address` or `bytesN`.
+    Primitive,
+}
+impl TypeBound {
+    /// Returns `true` if the type argument is allowed by the bound.
+    pub fn is_satisfied_by(&self, typ: &TypeDef) -> bool {
+        match self {
+            TypeBound::Numeric => matches!(typ, TypeDef::Primitive(Base::Numeric(_))),
+            TypeBound::Primitive => {
+                matches!(typ, TypeDef::Primitive(base) if *base != Base::Unit)
+            }
+        }
+    }
+}
+
 impl Integer {
     /// Returns `true` if the integer is signed, otherwise `false`
     pub fn is_signed(&self) -> bool {
@@ -291,6 +404,11 @@ impl Integer {
         }
     }
 
+    /// Returns the integer type with the given signedness and size in bytes.
+    pub fn from_parts(signed: bool, size: usize) -> Option<Integer> {
+        Integer::iter().find(|integer| integer.is_signed() == signed && integer.size() == size)
+    }
+
     /// Returns `true` if the integer is at least the same size (or larger) than
     /// `other`
     pub fn can_hold(&self, other: &Integer) -> bool {
@@ -351,12 +469,16 @@ impl Type {
         match self {
             Type::Base(inner) => inner.name(),
             Type::Array(inner) => inner.to_string().into(),
+            Type::DynamicArray(inner) => inner.to_string().into(),
             Type::Map(inner) => inner.to_string().into(),
             Type::Tuple(inner) => inner.to_string().into(),
             Type::String(inner) => inner.to_string().into(),
             Type::Contract(inner) => inner.name.clone(),
             Type::SelfContract(inner) => inner.name.clone(),
             Type::Struct(inner) => inner.name.clone(),
+            Type::Enum(inner) => inner.name.clone(),
+            Type::Option(inner) => inner.to_string().into(),
+            Type::Result(inner) => inner.to_string().into(),
         }
     }
 
@@ -389,6 +511,19 @@ impl Type {
                 0 => Some(Type::Base(array.inner)),
                 _ => None,
             },
+            Type::DynamicArray(array) => match idx {
+                0 => Some(Type::Base(array.inner)),
+                _ => None,
+            },
+            Type::Option(option) => match idx {
+                0 => Some(Type::Base(option.inner)),
+                _ => None,
+            },
+            Type::Result(result) => match idx {
+                0 => Some(Type::Base(result.ok)),
+                1 => Some(Type::Base(result.err)),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -484,6 +619,9 @@ impl From<FixedSize> for Type {
             FixedSize::String(string) => Type::String(string),
             FixedSize::Contract(contract) => Type::Contract(contract),
             FixedSize::Struct(val) => Type::Struct(val),
+            FixedSize::Enum(val) => Type::Enum(val),
+            FixedSize::Option(option) => Type::Option(option),
+            FixedSize::Result(result) => Type::Result(result),
         }
     }
 }
@@ -539,6 +677,9 @@ impl PartialEq<Type> for FixedSize {
             (FixedSize::String(in1), Type::String(in2)) => in1 == in2,
             (FixedSize::Contract(in1), Type::Contract(in2)) => in1 == in2,
             (FixedSize::Struct(in1), Type::Struct(in2)) => in1 == in2,
+            (FixedSize::Enum(in1), Type::Enum(in2)) => in1 == in2,
+            (FixedSize::Option(in1), Type::Option(in2)) => in1 == in2,
+            (FixedSize::Result(in1), Type::Result(in2)) => in1 == in2,
             _ => false,
         }
     }
@@ -566,9 +707,13 @@ impl TryFrom<Type> for FixedSize {
             Type::Tuple(tuple) => Ok(FixedSize::Tuple(tuple)),
             Type::String(string) => Ok(FixedSize::String(string)),
             Type::Struct(val) => Ok(FixedSize::Struct(val)),
+            Type::Enum(val) => Ok(FixedSize::Enum(val)),
+            Type::DynamicArray(_) => Err(NotFixedSize),
             Type::Map(_) => Err(NotFixedSize),
             Type::Contract(contract) => Ok(FixedSize::Contract(contract)),
             Type::SelfContract(_) => Err(NotFixedSize),
+            Type::Option(option) => Ok(FixedSize::Option(option)),
+            Type::Result(result) => Ok(FixedSize::Result(result)),
         }
     }
 }
@@ -588,6 +733,9 @@ impl SafeNames for FixedSize {
             FixedSize::String(string) => string.lower_snake(),
             FixedSize::Contract(contract) => contract.lower_snake(),
             FixedSize::Struct(val) => val.lower_snake(),
+            FixedSize::Enum(val) => val.lower_snake(),
+            FixedSize::Option(option) => option.lower_snake(),
+            FixedSize::Result(result) => result.lower_snake(),
         }
     }
 }
@@ -609,6 +757,7 @@ impl SafeNames for Base {
             Base::Numeric(Integer::I8) => "i8".to_string(),
             Base::Address => "address".to_string(),
             Base::Bool => "bool".to_string(),
+            Base::FixedBytes(size) => format!("bytes{}", size),
             Base::Unit => "unit".to_string(),
         }
     }
@@ -626,6 +775,12 @@ impl SafeNames for Struct {
     }
 }
 
+impl SafeNames for Enum {
+    fn lower_snake(&self) -> String {
+        format!("enum_{}", self.name)
+    }
+}
+
 impl SafeNames for Tuple {
     fn lower_snake(&self) -> String {
         let field_names = self
@@ -656,17 +811,37 @@ impl SafeNames for FeString {
     }
 }
 
+impl SafeNames for FeOption {
+    fn lower_snake(&self) -> String {
+        format!("option_{}", self.inner.lower_snake())
+    }
+}
+
+impl SafeNames for FeResult {
+    fn lower_snake(&self) -> String {
+        format!(
+            "result_{}_{}",
+            self.ok.lower_snake(),
+            self.err.lower_snake()
+        )
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Type::Base(inner) => inner.fmt(f),
             Type::Array(inner) => inner.fmt(f),
+            Type::DynamicArray(inner) => inner.fmt(f),
             Type::Map(inner) => inner.fmt(f),
             Type::Tuple(inner) => inner.fmt(f),
             Type::String(inner) => inner.fmt(f),
             Type::Contract(inner) => inner.fmt(f),
             Type::SelfContract(inner) => inner.fmt(f),
             Type::Struct(inner) => inner.fmt(f),
+            Type::Enum(inner) => inner.fmt(f),
+            Type::Option(inner) => inner.fmt(f),
+            Type::Result(inner) => inner.fmt(f),
         }
     }
 }
@@ -680,6 +855,9 @@ impl fmt::Display for FixedSize {
             FixedSize::String(inner) => inner.fmt(f),
             FixedSize::Contract(inner) => inner.fmt(f),
             FixedSize::Struct(inner) => inner.fmt(f),
+            FixedSize::Enum(inner) => inner.fmt(f),
+            FixedSize::Option(inner) => inner.fmt(f),
+            FixedSize::Result(inner) => inner.fmt(f),
         }
     }
 }
@@ -690,6 +868,7 @@ impl fmt::Display for Base {
             Base::Numeric(int) => return int.fmt(f),
             Base::Bool => "bool",
             Base::Address => "address",
+            Base::FixedBytes(size) => return write!(f, "bytes{}", size),
             Base::Unit => "()",
         };
         write!(f, "{}", name)
@@ -722,6 +901,12 @@ impl fmt::Display for Array {
     }
 }
 
+impl fmt::Display for DynamicArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Array<{}>", self.inner)
+    }
+}
+
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Map<{}, {}>", self.key, self.value)
@@ -746,6 +931,18 @@ impl fmt::Display for FeString {
     }
 }
 
+impl fmt::Display for FeOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Option<{}>", self.inner)
+    }
+}
+
+impl fmt::Display for FeResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Result<{}, {}>", self.ok, self.err)
+    }
+}
+
 impl fmt::Display for Contract {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.name)
@@ -758,6 +955,12 @@ impl fmt::Display for Struct {
     }
 }
 
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 impl FromStr for Base {
     type Err = strum::ParseError;
 
@@ -766,7 +969,12 @@ impl FromStr for Base {
             "bool" => Ok(Base::Bool),
             "address" => Ok(Base::Address),
             "()" => Ok(Base::Unit),
-            _ => Ok(Base::Numeric(Integer::from_str(s)?)),
+            _ => match s.strip_prefix("bytes").map(usize::from_str) {
+                Some(Ok(size)) if (1..=MAX_FIXED_BYTES_SIZE).contains(&size) => {
+                    Ok(Base::FixedBytes(size))
+                }
+                _ => Ok(Base::Numeric(Integer::from_str(s)?)),
+            },
         }
     }
 }
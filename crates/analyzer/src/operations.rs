@@ -1,5 +1,5 @@
 use crate::errors::{BinaryOperationError, IndexingError};
-use crate::namespace::types::{Array, Base, Map, Type, U256};
+use crate::namespace::types::{Array, Base, DynamicArray, Integer, Map, Type, U256};
 
 use fe_parser::ast as fe;
 
@@ -9,13 +9,18 @@ use fe_parser::ast as fe;
 pub fn index(value: Type, index: Type) -> Result<Type, IndexingError> {
     match value {
         Type::Array(array) => index_array(array, index),
+        Type::DynamicArray(array) => index_dynamic_array(array, index),
         Type::Map(map) => index_map(map, index),
+        Type::Base(Base::FixedBytes(_)) => index_fixed_bytes(index),
         Type::Base(_) => Err(IndexingError::NotSubscriptable),
         Type::Tuple(_) => Err(IndexingError::NotSubscriptable),
         Type::String(_) => Err(IndexingError::NotSubscriptable),
         Type::Contract(_) => Err(IndexingError::NotSubscriptable),
         Type::SelfContract(_) => Err(IndexingError::NotSubscriptable),
         Type::Struct(_) => Err(IndexingError::NotSubscriptable),
+        Type::Enum(_) => Err(IndexingError::NotSubscriptable),
+        Type::Option(_) => Err(IndexingError::NotSubscriptable),
+        Type::Result(_) => Err(IndexingError::NotSubscriptable),
     }
 }
 
@@ -27,6 +32,22 @@ fn index_array(array: Array, index: Type) -> Result<Type, IndexingError> {
     Ok(Type::Base(array.inner))
 }
 
+fn index_dynamic_array(array: DynamicArray, index: Type) -> Result<Type, IndexingError> {
+    if index != Type::Base(U256) {
+        return Err(IndexingError::WrongIndexType);
+    }
+
+    Ok(Type::Base(array.inner))
+}
+
+fn index_fixed_bytes(index: Type) -> Result<Type, IndexingError> {
+    if index != Type::Base(U256) {
+        return Err(IndexingError::WrongIndexType);
+    }
+
+    Ok(Type::Base(Base::Numeric(Integer::U8)))
+}
+
 fn index_map(map: Map, index: Type) -> Result<Type, IndexingError> {
     if index != Type::Base(map.key) {
         return Err(IndexingError::WrongIndexType);
@@ -53,18 +74,13 @@ pub fn bin(left: &Type, op: &fe::BinOperator, right: &Type) -> Result<Type, Bina
 
 fn bin_arithmetic(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
     if let (Type::Base(Base::Numeric(left)), Type::Base(Base::Numeric(right))) = (left, right) {
+        // Both operands must have the same type. Fe never promotes integers
+        // implicitly, since a promotion to a larger or signed type changes where
+        // the operation over- or underflows. The error suggests a cast to the
+        // `common_integer` of the operands instead.
         if left == right {
-            // For now, we require that the types be numeric and equal. In the future, we
-            // will want to loosen this up such that arithmetic operations can be performed
-            // on different types.
-            //
-            // The rules should be:
-            // - Any combination of numeric types can be operated on.
-            // - If either number is signed, we return a signed type.
-            // - The larger type is returned.
             Ok(Type::Base(Base::Numeric(left.to_owned())))
         } else {
-            // The types are not equal. Again, there is no need to be this strict.
             Err(BinaryOperationError::TypesNotEqual)
         }
     } else {
@@ -72,6 +88,31 @@ fn bin_arithmetic(left: &Type, right: &Type) -> Result<Type, BinaryOperationErro
     }
 }
 
+/// Finds the smallest integer type that can hold every value of both `left`
+/// and `right`, if there is one.
+///
+/// - If both have the same signedness, it's the larger of the two.
+/// - If only one is signed and it's larger than the unsigned one, it's the
+///   signed one.
+/// - Otherwise, it's the signed type twice the size of the unsigned one, e.g.
+///   `i16` for `u8` and `i8`. There is none if the unsigned one is `u256`.
+pub fn common_integer(left: Integer, right: Integer) -> Option<Integer> {
+    if left.is_signed() == right.is_signed() {
+        return Some(if left.can_hold(&right) { left } else { right });
+    }
+
+    let (signed, unsigned) = if left.is_signed() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    if signed.size() > unsigned.size() {
+        Some(signed)
+    } else {
+        Integer::from_parts(true, unsigned.size() * 2)
+    }
+}
+
 fn bin_pow(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
     if let (Type::Base(Base::Numeric(left)), Type::Base(Base::Numeric(right))) = (left, right) {
         // The exponent is not allowed to be a signed integer. To allow calculations
@@ -93,10 +134,15 @@ fn bin_pow(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
 }
 
 fn bin_bit_shift(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
-    if let (Type::Base(Base::Numeric(left)), Type::Base(Base::Numeric(right))) = (left, right) {
+    // Fixed-size bytes can be shifted like integers. The shift is in bits.
+    if let (
+        Type::Base(left @ (Base::Numeric(_) | Base::FixedBytes(_))),
+        Type::Base(Base::Numeric(right)),
+    ) = (left, right)
+    {
         // The right side must be unsigned.
         if !right.is_signed() {
-            Ok(Type::Base(Base::Numeric(left.to_owned())))
+            Ok(Type::Base(*left))
         } else {
             Err(BinaryOperationError::RightIsSigned)
         }
@@ -106,12 +152,23 @@ fn bin_bit_shift(left: &Type, right: &Type) -> Result<Type, BinaryOperationError
 }
 
 fn bin_bit(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
+    if let (Type::Base(Base::FixedBytes(left)), Type::Base(Base::FixedBytes(right))) = (left, right)
+    {
+        return if left == right {
+            Ok(Type::Base(Base::FixedBytes(*left)))
+        } else {
+            Err(BinaryOperationError::TypesNotEqual)
+        };
+    }
+
     if let (Type::Base(Base::Numeric(left)), Type::Base(Base::Numeric(right))) = (left, right) {
         // We require that both numbers be unsigned and equal in size.
-        if !left.is_signed() && left == right {
+        if left.is_signed() || right.is_signed() {
+            Err(BinaryOperationError::NotEqualAndUnsigned)
+        } else if left == right {
             Ok(Type::Base(Base::Numeric(left.to_owned())))
         } else {
-            Err(BinaryOperationError::NotEqualAndUnsigned)
+            Err(BinaryOperationError::TypesNotEqual)
         }
     } else {
         Err(BinaryOperationError::TypesNotNumeric)
@@ -121,7 +178,7 @@ fn bin_bit(left: &Type, right: &Type) -> Result<Type, BinaryOperationError> {
 #[cfg(test)]
 mod tests {
     use crate::errors::IndexingError;
-    use crate::namespace::types::{Array, Base, Map, Type, U256};
+    use crate::namespace::types::{Array, Base, DynamicArray, Integer, Map, Type, U256};
     use crate::operations;
     use rstest::rstest;
 
@@ -129,6 +186,7 @@ mod tests {
         inner: U256,
         size: 100,
     });
+    const U256_DYNAMIC_ARRAY_TYPE: Type = Type::DynamicArray(DynamicArray { inner: U256 });
     const U256_TYPE: Type = Type::Base(U256);
     const BOOL_TYPE: Type = Type::Base(Base::Bool);
 
@@ -144,7 +202,13 @@ mod tests {
         index,
         expected,
         case(U256_ARRAY_TYPE, U256_TYPE, U256_TYPE),
-        case(u256_bool_map(), U256_TYPE, BOOL_TYPE)
+        case(U256_DYNAMIC_ARRAY_TYPE, U256_TYPE, U256_TYPE),
+        case(u256_bool_map(), U256_TYPE, BOOL_TYPE),
+        case(
+            Type::Base(Base::FixedBytes(4)),
+            U256_TYPE,
+            Type::Base(Base::Numeric(Integer::U8))
+        )
     )]
     fn basic_index(value: Type, index: Type, expected: Type) {
         let actual = operations::index(value, index).expect("failed to get expected type");
@@ -155,6 +219,7 @@ mod tests {
         value,
         index,
         case(U256_ARRAY_TYPE, BOOL_TYPE),
+        case(U256_DYNAMIC_ARRAY_TYPE, BOOL_TYPE),
         case(u256_bool_map(), BOOL_TYPE),
         case(u256_bool_map(), U256_ARRAY_TYPE)
     )]
@@ -162,4 +227,20 @@ mod tests {
         let actual = operations::index(value, index).expect_err("didn't fail");
         assert_eq!(actual, IndexingError::WrongIndexType)
     }
+
+    #[rstest(
+        left,
+        right,
+        expected,
+        case(Integer::U8, Integer::U256, Some(Integer::U256)),
+        case(Integer::I64, Integer::I16, Some(Integer::I64)),
+        case(Integer::I32, Integer::U16, Some(Integer::I32)),
+        case(Integer::U8, Integer::I8, Some(Integer::I16)),
+        case(Integer::U128, Integer::I64, Some(Integer::I256)),
+        case(Integer::I256, Integer::U256, None)
+    )]
+    fn common_integer(left: Integer, right: Integer, expected: Option<Integer>) {
+        assert_eq!(operations::common_integer(left, right), expected);
+        assert_eq!(operations::common_integer(right, left), expected);
+    }
 }
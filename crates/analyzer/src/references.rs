@@ -0,0 +1,522 @@
+//! An index of the variables and functions defined in a module, and the places
+//! they're used.
+//!
+//! The index is built from the name resolution done during analysis, so a name
+//! is only matched with the definition it resolves to. It backs
+//! find-all-references in editors, and the unused-symbol lints.
+
+use crate::context::{CallType, FunctionBody};
+use crate::namespace::items::{FunctionId, Item, ModuleId, TypeDef};
+use crate::namespace::types::Type;
+use crate::AnalyzerDb;
+use fe_common::Span;
+use fe_parser::ast::{
+    CallArg, Expr, FuncStmt, FunctionArg, Pattern, VarDeclTarget, YulExpr, YulStmt,
+};
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A variable defined by `let`, a `for` loop or a `match` pattern.
+    Variable,
+    /// A function parameter, other than `self`.
+    Parameter,
+    /// A named return value.
+    NamedOutput,
+    /// A function, including contract and struct functions.
+    Function,
+}
+
+/// A definition, and the references to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: SmolStr,
+    /// The span of the name where the symbol is defined.
+    pub definition: Span,
+    /// The function the symbol is defined in, or the function itself.
+    pub function: FunctionId,
+    /// The spans of the names that refer to the symbol, in source order.
+    pub references: Vec<Span>,
+}
+
+/// The symbols defined in a module. Only references from within the module
+/// are included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceIndex {
+    pub symbols: Vec<Symbol>,
+}
+
+impl ReferenceIndex {
+    /// Collects the symbols of the module's functions, and their references.
+    pub(crate) fn build(db: &dyn AnalyzerDb, module: ModuleId) -> Self {
+        let functions = module_functions(db, module);
+        let bindings = functions
+            .iter()
+            .map(|function| Bindings::collect(db, *function))
+            .collect::<Vec<_>>();
+
+        let mut symbols = vec![];
+        for (function, function_bindings) in functions.iter().zip(&bindings) {
+            symbols.push(Symbol {
+                kind: SymbolKind::Function,
+                name: function.name(db),
+                definition: function.name_span(db),
+                function: *function,
+                references: bindings
+                    .iter()
+                    .flat_map(|bindings| bindings.calls.iter())
+                    .filter(|call| call.callee == *function)
+                    .map(|call| call.span)
+                    .collect(),
+            });
+
+            for definition in &function_bindings.definitions {
+                let kind = if definition.param.is_some() {
+                    SymbolKind::Parameter
+                } else if definition.output {
+                    SymbolKind::NamedOutput
+                } else {
+                    SymbolKind::Variable
+                };
+                symbols.push(Symbol {
+                    kind,
+                    name: definition.name.clone(),
+                    definition: definition.span,
+                    function: *function,
+                    references: function_bindings
+                        .references
+                        .iter()
+                        .filter(|reference| reference.definition == definition.span)
+                        .map(|reference| reference.span)
+                        .collect(),
+                });
+            }
+        }
+
+        for symbol in &mut symbols {
+            symbol.references.sort_by_key(|span| span.start);
+        }
+        ReferenceIndex { symbols }
+    }
+
+    /// The symbol whose definition, or one of its references, contains `span`.
+    pub fn symbol_at(&self, span: Span) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| {
+            contains(symbol.definition, span)
+                || symbol
+                    .references
+                    .iter()
+                    .any(|reference| contains(*reference, span))
+        })
+    }
+}
+
+/// Finds the symbol whose name contains `span`, either where it's defined or
+/// where it's used. The returned symbol holds every reference to it in the
+/// module.
+pub fn find_references(db: &dyn AnalyzerDb, module: ModuleId, span: Span) -> Option<Symbol> {
+    db.module_references(module).symbol_at(span).cloned()
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.file_id == inner.file_id && outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// All functions defined in the module, including those of its contracts and
+/// structs. Functions that contract qualifiers like `ownable` add aren't
+/// included, as they aren't part of the module's source code.
+pub(crate) fn module_functions(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<FunctionId> {
+    let mut functions = vec![];
+    for item in module.all_items(db).iter() {
+        match item {
+            Item::Function(function) => functions.push(*function),
+            Item::Type(TypeDef::Contract(contract)) => functions.extend(
+                db.contract_all_functions(*contract)
+                    .iter()
+                    .filter(|function| !function.is_injected(db)),
+            ),
+            Item::Type(TypeDef::Struct(struct_)) => {
+                functions.extend(db.struct_all_functions(*struct_).iter())
+            }
+            _ => {}
+        }
+    }
+    functions
+}
+
+/// A variable or function parameter.
+pub(crate) struct Definition {
+    pub name: SmolStr,
+    /// The span of the name where the variable is defined.
+    pub span: Span,
+    pub scope: usize,
+    /// The index of a function parameter, not counting `self`.
+    pub param: Option<usize>,
+    /// Whether the variable is a named return value.
+    pub output: bool,
+    /// Whether the name is also the label of a struct pattern field, as `x`
+    /// in `Point(x)`.
+    pub shorthand: bool,
+}
+
+/// A name expression that refers to a variable.
+pub(crate) struct Reference {
+    pub span: Span,
+    pub definition: Span,
+    /// Whether the name is also the label of a call argument, as `x` in
+    /// `Point(x)`.
+    pub shorthand: bool,
+}
+
+/// A labeled argument of a call to a user-defined function.
+pub(crate) struct ArgLabel {
+    pub callee: FunctionId,
+    pub index: usize,
+    pub name: SmolStr,
+    pub span: Span,
+}
+
+/// A call to a user-defined function. The span is the function name.
+pub(crate) struct FunctionCall {
+    pub callee: FunctionId,
+    pub span: Span,
+}
+
+/// The variables of a function, with the lexical scopes they're defined in,
+/// and the references to them. The scopes match those of the analyzer's
+/// `BlockScope`s.
+#[derive(Default)]
+pub(crate) struct Bindings {
+    /// The parent of each scope. Scope 0 holds the parameters, and scope 1 is
+    /// the function body.
+    pub scopes: Vec<Option<usize>>,
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+    pub labels: Vec<ArgLabel>,
+    pub calls: Vec<FunctionCall>,
+}
+
+impl Bindings {
+    pub fn collect(db: &dyn AnalyzerDb, function: FunctionId) -> Self {
+        let data = function.data(db);
+        let body = function.body(db);
+        let mut collector = Collector {
+            body: &body,
+            bindings: Bindings {
+                scopes: vec![None],
+                ..Bindings::default()
+            },
+        };
+
+        let params = data.ast.kind.args.iter().filter_map(|arg| match &arg.kind {
+            FunctionArg::Regular(arg) => Some(&arg.name),
+            FunctionArg::Zelf => None,
+        });
+        for (index, name) in params.enumerate() {
+            collector.define(&name.kind, name.span, 0, Some(index), false, false);
+        }
+
+        let scope = collector.new_scope(0);
+        for name in &data.ast.kind.return_names {
+            collector.define(&name.kind, name.span, scope, None, true, false);
+        }
+        collector.stmts(&data.ast.kind.body, scope);
+        collector.bindings
+    }
+
+    /// Whether `outer` is `inner` or one of its ancestors.
+    pub fn encloses(&self, outer: usize, inner: usize) -> bool {
+        let mut scope = Some(inner);
+        while let Some(current) = scope {
+            if current == outer {
+                return true;
+            }
+            scope = self.scopes[current];
+        }
+        false
+    }
+}
+
+struct Collector<'a> {
+    body: &'a FunctionBody,
+    bindings: Bindings,
+}
+
+impl Collector<'_> {
+    fn new_scope(&mut self, parent: usize) -> usize {
+        self.bindings.scopes.push(Some(parent));
+        self.bindings.scopes.len() - 1
+    }
+
+    fn define(
+        &mut self,
+        name: &SmolStr,
+        span: Span,
+        scope: usize,
+        param: Option<usize>,
+        output: bool,
+        shorthand: bool,
+    ) {
+        self.bindings.definitions.push(Definition {
+            name: name.clone(),
+            span,
+            scope,
+            param,
+            output,
+            shorthand,
+        })
+    }
+
+    fn stmts(&mut self, stmts: &[Node<FuncStmt>], scope: usize) {
+        for stmt in stmts {
+            self.stmt(stmt, scope)
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<FuncStmt>, scope: usize) {
+        match &stmt.kind {
+            FuncStmt::Return { value } | FuncStmt::Revert { error: value } => {
+                self.opt_expr(value.as_ref())
+            }
+            FuncStmt::VarDecl { target, value, .. } => {
+                self.opt_expr(value.as_ref());
+                self.var_decl_target(target, scope);
+            }
+            FuncStmt::Assign { target, value } | FuncStmt::AugAssign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            FuncStmt::For { target, iter, body } => {
+                self.expr(iter);
+                let inner = self.new_scope(scope);
+                self.define(&target.kind, target.span, inner, None, false, false);
+                self.stmts(body, inner);
+            }
+            FuncStmt::While { test, body } => {
+                self.expr(test);
+                let inner = self.new_scope(scope);
+                self.stmts(body, inner);
+            }
+            FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(test);
+                let inner = self.new_scope(scope);
+                self.stmts(body, inner);
+                let inner = self.new_scope(scope);
+                self.stmts(or_else, inner);
+            }
+            FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                self.opt_expr(msg.as_ref());
+            }
+            FuncStmt::Emit { args, .. } => self.call_args(args, true),
+            FuncStmt::Expr { value } => self.expr(value),
+            FuncStmt::Unsafe(body) => {
+                let inner = self.new_scope(scope);
+                self.stmts(body, inner);
+            }
+            FuncStmt::Match { value, arms } => {
+                self.expr(value);
+                for arm in arms {
+                    let inner = self.new_scope(scope);
+                    self.pattern(&arm.kind.pat, inner);
+                    self.opt_expr(arm.kind.guard.as_ref());
+                    self.stmts(&arm.kind.body, inner);
+                }
+            }
+            FuncStmt::Yul(body) => self.yul_stmts(body),
+            FuncStmt::Pass | FuncStmt::Break | FuncStmt::Continue => {}
+        }
+    }
+
+    /// Inline Yul can only refer to variables defined outside of the block, so
+    /// its own scopes don't need to be tracked.
+    fn yul_stmts(&mut self, stmts: &[Node<YulStmt>]) {
+        for stmt in stmts {
+            match &stmt.kind {
+                YulStmt::Block(body) => self.yul_stmts(body),
+                YulStmt::Let { value, .. } => {
+                    if let Some(value) = value {
+                        self.yul_expr(value)
+                    }
+                }
+                YulStmt::Assign { names, value } => {
+                    for name in names {
+                        self.name(name, false);
+                    }
+                    self.yul_expr(value);
+                }
+                YulStmt::If { test, body } => {
+                    self.yul_expr(test);
+                    self.yul_stmts(body);
+                }
+                YulStmt::Switch { value, cases } => {
+                    self.yul_expr(value);
+                    for case in cases {
+                        self.yul_stmts(&case.kind.body);
+                    }
+                }
+                YulStmt::For {
+                    init,
+                    test,
+                    post,
+                    body,
+                } => {
+                    self.yul_stmts(init);
+                    self.yul_expr(test);
+                    self.yul_stmts(post);
+                    self.yul_stmts(body);
+                }
+                YulStmt::Expr(value) => self.yul_expr(value),
+                YulStmt::Break | YulStmt::Continue => {}
+            }
+        }
+    }
+
+    fn yul_expr(&mut self, expr: &Node<YulExpr>) {
+        match &expr.kind {
+            YulExpr::Name(_) => self.name(expr, false),
+            YulExpr::Call { args, .. } => {
+                for arg in &args.kind {
+                    self.yul_expr(arg)
+                }
+            }
+            YulExpr::Literal(_) => {}
+        }
+    }
+
+    fn var_decl_target(&mut self, target: &Node<VarDeclTarget>, scope: usize) {
+        match &target.kind {
+            VarDeclTarget::Name(name) => self.define(name, target.span, scope, None, false, false),
+            VarDeclTarget::Tuple(items) => {
+                for item in items {
+                    self.var_decl_target(item, scope)
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, pat: &Node<Pattern>, scope: usize) {
+        match &pat.kind {
+            Pattern::Wildcard | Pattern::Path(_) => {}
+            Pattern::Binding(name) => self.define(name, pat.span, scope, None, false, false),
+            Pattern::Literal(value) => self.expr(value),
+            Pattern::Tuple(items) => {
+                for item in items {
+                    self.pattern(item, scope)
+                }
+            }
+            Pattern::Struct { fields, .. } => {
+                for field in fields {
+                    match &field.kind.pat.kind {
+                        // `Point(x)` is shorthand for `Point(x=x)`
+                        Pattern::Binding(name) if field.kind.label.span == field.kind.pat.span => {
+                            self.define(name, field.kind.pat.span, scope, None, false, true)
+                        }
+                        _ => self.pattern(&field.kind.pat, scope),
+                    }
+                }
+            }
+        }
+    }
+
+    fn opt_expr(&mut self, expr: Option<&Node<Expr>>) {
+        if let Some(expr) = expr {
+            self.expr(expr)
+        }
+    }
+
+    fn expr(&mut self, expr: &Node<Expr>) {
+        match &expr.kind {
+            Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                self.expr(if_expr);
+                self.expr(test);
+                self.expr(else_expr);
+            }
+            Expr::BoolOperation { left, right, .. }
+            | Expr::BinOperation { left, right, .. }
+            | Expr::CompOperation { left, right, .. }
+            | Expr::Subscript {
+                value: left,
+                index: right,
+            } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::UnaryOperation { operand: value, .. }
+            | Expr::Attribute { value, .. }
+            | Expr::Try { value } => self.expr(value),
+            Expr::Slice { value, start, end } => {
+                self.expr(value);
+                self.opt_expr(start.as_deref());
+                self.opt_expr(end.as_deref());
+            }
+            Expr::Call { func, args, .. } => {
+                self.expr(func);
+                let call = self.body.calls.get(&func.id);
+                if let Some(callee) = call.and_then(CallType::function) {
+                    let span = match &func.kind {
+                        Expr::Attribute { attr, .. } => attr.span,
+                        _ => func.span,
+                    };
+                    self.bindings.calls.push(FunctionCall { callee, span });
+                    for (index, arg) in args.kind.iter().enumerate() {
+                        if let Some(label) = &arg.kind.label {
+                            self.bindings.labels.push(ArgLabel {
+                                callee,
+                                index,
+                                name: label.kind.clone(),
+                                span: label.span,
+                            })
+                        }
+                    }
+                }
+                let labels_required =
+                    matches!(call, Some(CallType::TypeConstructor(Type::Struct(_))));
+                self.call_args(args, labels_required);
+            }
+            Expr::List { elts } | Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            Expr::Name(_) => self.name(expr, false),
+            Expr::Bool(_) | Expr::Path(_) | Expr::Num(_) | Expr::Str(_) | Expr::Unit => {}
+        }
+    }
+
+    /// `labels_required` is true for the arguments of struct constructors and
+    /// `emit` statements, which may only be unlabeled if they're a variable
+    /// with the same name as the field.
+    fn call_args(&mut self, args: &Node<Vec<Node<CallArg>>>, labels_required: bool) {
+        for arg in &args.kind {
+            let value = &arg.kind.value;
+            match value.kind {
+                Expr::Name(_) if labels_required && arg.kind.label.is_none() => {
+                    self.name(value, true)
+                }
+                _ => self.expr(value),
+            }
+        }
+    }
+
+    /// Records a reference, if `node` is a name that refers to a variable.
+    fn name<T>(&mut self, node: &Node<T>, shorthand: bool) {
+        if let Some(definition) = self.body.variable_refs.get(&node.id) {
+            self.bindings.references.push(Reference {
+                span: node.span,
+                definition: *definition,
+                shorthand,
+            })
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Renaming of local variables and function parameters.
+//!
+//! References are found with the analyzer's name resolution, rather than by
+//! matching text, so variables of the same name in other scopes are left
+//! alone. This is the basis of rename support in editors; a language server
+//! can map the returned edits directly to a `WorkspaceEdit`.
+
+use crate::namespace::items::{Class, ModuleId};
+use crate::references::{module_functions, Bindings};
+use crate::AnalyzerDb;
+use fe_common::files::SourceFileId;
+use fe_common::Span;
+use fe_parser::lexer::{Lexer, TokenKind};
+use std::fmt;
+
+/// A replacement of the source text in `span`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// The span isn't within the name of a variable or function parameter
+    /// defined in the module.
+    NotAVariable,
+    /// The new name isn't a valid identifier, e.g. because it's a keyword.
+    InvalidName(String),
+    /// The new name is already taken by a variable in the same, an enclosing or
+    /// a nested scope, or by an item that's visible in the function. Fe doesn't
+    /// allow shadowing, so the renamed variable would clash with it. The span
+    /// is the name of the existing definition, which is `None` for built-ins.
+    Conflict { name: String, span: Option<Span> },
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::NotAVariable => {
+                write!(f, "only variables and function parameters can be renamed")
+            }
+            RenameError::InvalidName(name) => write!(f, "`{}` is not a valid name", name),
+            RenameError::Conflict { name, .. } => write!(f, "`{}` is already defined", name),
+        }
+    }
+}
+
+/// Computes the edits that rename the variable or function parameter whose
+/// name contains `definition` to `new_name`.
+///
+/// This renames the definition and every reference to it. Renaming a
+/// parameter also renames the argument labels of the calls to its function
+/// in `module`. Unlabeled arguments of struct constructors and `emit`
+/// statements that rely on the variable name matching the field name get an
+/// explicit label instead, e.g. `Point(x)` becomes `Point(x=new)`.
+pub fn rename(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    definition: Span,
+    new_name: &str,
+) -> Result<Vec<TextEdit>, RenameError> {
+    if !is_identifier(new_name) {
+        return Err(RenameError::InvalidName(new_name.to_string()));
+    }
+
+    let functions = module_functions(db, module)
+        .into_iter()
+        .map(|function| (function, Bindings::collect(db, function)))
+        .collect::<Vec<_>>();
+
+    let (function, bindings, target) = functions
+        .iter()
+        .find_map(|(function, bindings)| {
+            let target = bindings.definitions.iter().find(|def| {
+                def.span.file_id == definition.file_id
+                    && def.span.start <= definition.start
+                    && definition.end <= def.span.end
+            })?;
+            Some((*function, bindings, target))
+        })
+        .ok_or(RenameError::NotAVariable)?;
+
+    if let Some(other) = bindings.definitions.iter().find(|other| {
+        other.name == new_name
+            && other.span != target.span
+            && (bindings.encloses(other.scope, target.scope)
+                || bindings.encloses(target.scope, other.scope))
+    }) {
+        return Err(RenameError::Conflict {
+            name: new_name.to_string(),
+            span: Some(other.span),
+        });
+    }
+
+    let item = match function.class(db) {
+        Some(Class::Contract(contract)) => contract.resolve_name(db, new_name),
+        _ => function.module(db).resolve_name(db, new_name),
+    };
+    if let Some(item) = item {
+        return Err(RenameError::Conflict {
+            name: new_name.to_string(),
+            span: item.name_span(db),
+        });
+    }
+
+    let labeled = |span| TextEdit {
+        span,
+        new_text: format!("{}={}", target.name, new_name),
+    };
+    let renamed = |span| TextEdit {
+        span,
+        new_text: new_name.to_string(),
+    };
+
+    let mut edits = vec![if target.shorthand {
+        labeled(target.span)
+    } else {
+        renamed(target.span)
+    }];
+    edits.extend(
+        bindings
+            .references
+            .iter()
+            .filter(|reference| reference.definition == target.span)
+            .map(|reference| {
+                if reference.shorthand {
+                    labeled(reference.span)
+                } else {
+                    renamed(reference.span)
+                }
+            }),
+    );
+    if let Some(param) = target.param {
+        edits.extend(
+            functions
+                .iter()
+                .flat_map(|(_, bindings)| bindings.labels.iter())
+                .filter(|label| {
+                    label.callee == function && label.index == param && label.name == target.name
+                })
+                .map(|label| renamed(label.span)),
+        );
+    }
+
+    edits.sort_by_key(|edit| edit.span.start);
+    Ok(edits)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut tokens = Lexer::new(SourceFileId::default(), name);
+    matches!(
+        (tokens.next(), tokens.next()),
+        (Some(token), None) if token.kind == TokenKind::Name && token.text == name
+    )
+}
@@ -1,6 +1,7 @@
 use crate::context::{AnalyzerContext, Location};
 use crate::errors::FatalError;
 use crate::namespace::scopes::BlockScope;
+use crate::namespace::types::{Base, Type};
 use crate::operations;
 use crate::traversal::expressions;
 use crate::traversal::utils::add_bin_operations_errors;
@@ -14,6 +15,13 @@ use fe_parser::node::Node;
 pub fn assign(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::Assign { target, value } = &stmt.kind {
         let target_attributes = expressions::expr(scope, target, None)?;
+        if let Type::DynamicArray(_) = target_attributes.typ {
+            return Err(FatalError::new(scope.fancy_error(
+                "dynamically-sized arrays can't be assigned",
+                vec![Label::primary(target.span, "this array is in storage")],
+                vec!["Hint: items can be added with `push` and removed with `pop`".into()],
+            )));
+        }
 
         let value_attributes = expressions::expr(scope, value, Some(&target_attributes.typ))?;
         check_assign_target(scope, target)?;
@@ -69,7 +77,26 @@ pub fn check_assign_target(
     use fe::Expr::*;
     match &expr.kind {
         Attribute { .. } => Ok(()),
-        Subscript { .. } => Ok(()),
+        Subscript { value, .. } => {
+            let value_type = scope
+                .root
+                .body
+                .borrow()
+                .expressions
+                .get(&value.id)
+                .map(|attributes| attributes.typ.clone());
+            if let Some(typ @ Type::Base(Base::FixedBytes(_))) = value_type {
+                return Err(FatalError::new(scope.fancy_error(
+                    "invalid assignment target",
+                    vec![Label::primary(expr.span, "")],
+                    vec![format!(
+                        "The bytes of a `{}` value can't be assigned individually. Build a new value instead, e.g. with `{}(..)`.",
+                        typ, typ
+                    )],
+                )));
+            }
+            Ok(())
+        }
         Tuple { elts } => {
             for elt in elts {
                 check_assign_target(scope, elt)?;
@@ -88,19 +115,20 @@ pub fn check_assign_target(
 /// Gather context information for assignments and check for type errors.
 pub fn aug_assign(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::AugAssign { target, op, value } = &stmt.kind {
-        check_assign_target(scope, target)?;
         let target_attributes = expressions::expr(scope, target, None)?;
+        check_assign_target(scope, target)?;
         let value_attributes = expressions::expr(scope, value, Some(&target_attributes.typ))?;
 
         if let Err(err) = operations::bin(&target_attributes.typ, &op.kind, &value_attributes.typ) {
             add_bin_operations_errors(
                 scope,
-                &op.kind,
-                target.span,
+                op,
+                target,
                 &target_attributes.typ,
-                value.span,
+                value,
                 &value_attributes.typ,
                 err,
+                true,
             );
         }
         return Ok(());
@@ -1,4 +1,4 @@
-use crate::context::{AnalyzerContext, DiagnosticVoucher};
+use crate::context::{AnalyzerContext, DiagnosticVoucher, ExpressionAttributes};
 use crate::errors::{FatalError, TypeError};
 use crate::namespace::scopes::BlockScope;
 use crate::namespace::types::{EventField, FixedSize, FunctionParam};
@@ -57,10 +57,25 @@ pub fn validate_named_args(
     args: &Node<Vec<Node<fe::CallArg>>>,
     params: &[impl LabeledParameter],
     label_policy: LabelPolicy,
+) -> Result<(), FatalError> {
+    validate_named_args_with_attrs(scope, name, name_span, args, params, label_policy, &[])
+}
+
+/// Like [`validate_named_args`], but the arguments for which attributes are
+/// given have already been analyzed, e.g. to infer the type arguments of a
+/// call to a generic function.
+pub fn validate_named_args_with_attrs(
+    scope: &mut BlockScope,
+    name: &str,
+    name_span: Span,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    label_policy: LabelPolicy,
+    evaluated: &[Option<ExpressionAttributes>],
 ) -> Result<(), FatalError> {
     validate_arg_count(scope, name, name_span, args, params.len(), "argument");
     validate_arg_labels(scope, args, params, label_policy);
-    validate_arg_types(scope, name, args, params)?;
+    validate_arg_types_with_attrs(scope, name, args, params, evaluated)?;
     Ok(())
 }
 
@@ -185,10 +200,23 @@ pub fn validate_arg_types(
     name: &str,
     args: &Node<Vec<Node<fe::CallArg>>>,
     params: &[impl LabeledParameter],
+) -> Result<(), FatalError> {
+    validate_arg_types_with_attrs(scope, name, args, params, &[])
+}
+
+fn validate_arg_types_with_attrs(
+    scope: &mut BlockScope,
+    name: &str,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    evaluated: &[Option<ExpressionAttributes>],
 ) -> Result<(), FatalError> {
     for (index, (param, arg)) in params.iter().zip(args.kind.iter()).enumerate() {
         let param_type = param.typ()?;
-        let val_attrs = assignable_expr(scope, &arg.kind.value, Some(&param_type.clone().into()))?;
+        let val_attrs = match evaluated.get(index) {
+            Some(Some(attrs)) => attrs.clone(),
+            _ => assignable_expr(scope, &arg.kind.value, Some(&param_type.clone().into()))?,
+        };
         if param_type != val_attrs.typ {
             let msg = if let Some(label) = param.label() {
                 format!("incorrect type for `{}` argument `{}`", name, label)
@@ -1,7 +1,7 @@
 use crate::context::AnalyzerContext;
 use crate::errors::FatalError;
 use crate::namespace::scopes::BlockScope;
-use crate::namespace::types::FixedSize;
+use crate::namespace::types::{FixedSize, Type};
 use crate::traversal::{expressions, types};
 use fe_common::diagnostics::Label;
 use fe_common::utils::humanize::pluralize_conditionally;
@@ -11,16 +11,25 @@ use fe_parser::node::Node;
 /// Gather context information for var declarations and check for type errors.
 pub fn var_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::VarDecl { target, typ, value } = &stmt.kind {
-        let declared_type = match FixedSize::try_from(types::type_desc(scope, typ)?) {
-            Ok(typ) => typ,
-            Err(_) => {
-                // If this conversion fails, the type must be a map (for now at least)
+        let declared_type = match types::type_desc(scope, typ)? {
+            Type::DynamicArray(_) => {
                 return Err(FatalError::new(scope.error(
                     "invalid variable type",
                     typ.span,
-                    "`Map` type can only be used as a contract field",
+                    "dynamically-sized arrays can only be used as a contract field",
                 )));
             }
+            declared_type => match FixedSize::try_from(declared_type) {
+                Ok(typ) => typ,
+                Err(_) => {
+                    // If this conversion fails, the type must be a map (for now at least)
+                    return Err(FatalError::new(scope.error(
+                        "invalid variable type",
+                        typ.span,
+                        "`Map` type can only be used as a contract field",
+                    )));
+                }
+            },
         };
 
         if let Some(value) = value {
@@ -1,24 +1,32 @@
 use crate::builtins::{
-    BlockField, ChainField, ContractSelfField, ContractTypeMethod, GlobalFunction, GlobalObject,
-    Intrinsic, MsgField, TxField, ValueMethod,
+    self, BlockField, ChainField, ContractSelfField, ContractTypeMethod, GlobalFunction,
+    GlobalObject, Intrinsic, MsgField, TxField, ValueMethod,
 };
+use crate::const_eval::{self, ConstValue};
 use crate::context::{AnalyzerContext, CallType, ExpressionAttributes, Location, NamedThing};
 use crate::errors::{FatalError, IndexingError, NotFixedSize};
-use crate::namespace::items::{Class, FunctionId, Item};
+use crate::lints;
+use crate::namespace::items::{Class, FunctionId, Item, StructId, TypeDef};
 use crate::namespace::scopes::{BlockScope, BlockScopeType};
 use crate::namespace::types::{
-    Array, Base, Contract, FeString, Integer, Struct, Tuple, Type, TypeDowncast, U256,
+    Array, Base, Contract, FeOption, FeResult, FeString, FixedSize, Integer, Struct, Tuple, Type,
+    TypeDowncast, U256,
 };
 use crate::operations;
-use crate::traversal::call_args::{validate_arg_count, validate_named_args, LabelPolicy};
-use crate::traversal::types::apply_generic_type_args;
-use crate::traversal::utils::{add_bin_operations_errors, types_to_fixed_sizes};
+use crate::traversal::call_args::{
+    validate_arg_count, validate_arg_labels, validate_arg_types, validate_named_args,
+    validate_named_args_with_attrs, LabelPolicy,
+};
+use crate::traversal::types::{apply_generic_type_args, check_type_bound, resolve_type_args};
+use crate::traversal::utils::{add_bin_operations_errors, operand_cast_hint, types_to_fixed_sizes};
+use crate::AnalyzerDb;
 use fe_common::diagnostics::Label;
 use fe_common::{numeric, Span};
 use fe_parser::ast as fe;
 use fe_parser::ast::UnaryOperator;
 use fe_parser::node::Node;
 use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 use smol_str::SmolStr;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
@@ -36,6 +44,8 @@ pub fn expr(
         fe::Expr::Num(_) => Ok(expr_num(scope, exp, expected_type.as_int())),
         fe::Expr::Bool(_) => expr_bool(exp),
         fe::Expr::Subscript { .. } => expr_subscript(scope, exp),
+        fe::Expr::Slice { .. } => expr_slice(scope, exp),
+        fe::Expr::Try { .. } => expr_try(scope, exp),
         fe::Expr::Attribute { .. } => expr_attribute(scope, exp),
         fe::Expr::Ternary { .. } => expr_ternary(scope, exp),
         fe::Expr::BoolOperation { .. } => expr_bool_operation(scope, exp),
@@ -46,7 +56,7 @@ pub fn expr(
             func,
             generic_args,
             args,
-        } => expr_call(scope, func, generic_args, args),
+        } => expr_call(scope, func, generic_args, args, expected_type),
         fe::Expr::List { elts } => expr_list(scope, elts, expected_type.as_array()),
         fe::Expr::Tuple { .. } => expr_tuple(scope, exp, expected_type.as_tuple()),
         fe::Expr::Str(_) => expr_str(scope, exp),
@@ -147,6 +157,16 @@ pub fn value_expr(
     expected_type: Option<&Type>,
 ) -> Result<ExpressionAttributes, FatalError> {
     let original_attributes = expr(scope, exp, expected_type)?;
+    load_expr(scope, exp, original_attributes)
+}
+
+/// Moves the value of an expression whose attributes were already gathered
+/// onto the stack.
+fn load_expr(
+    scope: &mut BlockScope,
+    exp: &Node<fe::Expr>,
+    original_attributes: ExpressionAttributes,
+) -> Result<ExpressionAttributes, FatalError> {
     let attributes = original_attributes.clone().into_loaded().map_err(|_| {
         FatalError::new(scope.fancy_error(
             "can't move value onto stack",
@@ -175,12 +195,12 @@ pub fn assignable_expr(
 
     let mut attributes = expr(scope, exp, expected_type)?;
     match &attributes.typ {
-        Base(_) | Contract(_) => {
+        Base(_) | Contract(_) | Enum(_) => {
             if attributes.location != Location::Value {
                 attributes.move_location = Some(Location::Value);
             }
         }
-        Array(_) | Tuple(_) | String(_) | Struct(_) => {
+        Array(_) | Tuple(_) | String(_) | Struct(_) | Option(_) | Result(_) => {
             if attributes.final_location() != Location::Memory {
                 scope.fancy_error(
                     "value must be copied to memory",
@@ -208,6 +228,16 @@ pub fn assignable_expr(
                 "this type can only be used in a contract field",
             )));
         }
+        DynamicArray(_) => {
+            return Err(FatalError::new(scope.fancy_error(
+                "dynamically-sized arrays cannot reside in memory",
+                vec![Label::primary(
+                    exp.span,
+                    "this type can only be used in a contract field",
+                )],
+                vec!["Hint: items can be read with an index, e.g. `self.my_array[0]`".into()],
+            )));
+        }
     };
     scope.root.update_expression(exp, attributes.clone());
 
@@ -278,10 +308,64 @@ fn expr_path(
         _ => unreachable!(),
     };
 
+    if let Some(attributes) = expr_enum_variant(scope, path)? {
+        return Ok(attributes);
+    }
     let named_thing = scope.resolve_path(path);
     expr_named_thing(scope, exp, named_thing, expected_type)
 }
 
+/// Checks a path to an enum variant, eg. `State::Active`. Returns `None` if
+/// the path doesn't start with an enum.
+pub fn expr_enum_variant(
+    scope: &mut BlockScope,
+    path: &fe::Path,
+) -> Result<Option<ExpressionAttributes>, FatalError> {
+    let (variant, prefix) = match path.segments.split_last() {
+        Some((variant, prefix)) if !prefix.is_empty() => (variant, prefix),
+        _ => return Ok(None),
+    };
+    let prefix_item = scope
+        .root
+        .function
+        .module(scope.db())
+        .resolve_path_internal(
+            scope.db(),
+            &fe::Path {
+                segments: prefix.to_vec(),
+            },
+        );
+    let id = match prefix_item.value {
+        Some(Item::Type(TypeDef::Enum(id))) => id,
+        _ => return Ok(None),
+    };
+
+    if id.variant_index(scope.db(), &variant.kind).is_none() {
+        let name = id.name(scope.db());
+        let variants = id
+            .variants(scope.db())
+            .keys()
+            .map(|variant| format!("`{}`", variant))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(FatalError::new(scope.fancy_error(
+            &format!("no variant named `{}` in enum `{}`", variant.kind, name),
+            vec![
+                Label::primary(variant.span, "undefined variant"),
+                Label::secondary(
+                    id.name_span(scope.db()),
+                    format!("`{}` is defined here", name),
+                ),
+            ],
+            vec![format!("Note: the variants of `{}` are {}", name, variants)],
+        )));
+    }
+    Ok(Some(ExpressionAttributes::new(
+        Type::Enum(id.typ(scope.db())),
+        Location::Value,
+    )))
+}
+
 fn expr_named_thing(
     scope: &mut BlockScope,
     exp: &Node<fe::Expr>,
@@ -289,7 +373,8 @@ fn expr_named_thing(
     expected_type: Option<&Type>,
 ) -> Result<ExpressionAttributes, FatalError> {
     match named_thing {
-        Some(NamedThing::Variable { typ, .. }) => {
+        Some(NamedThing::Variable { typ, span, .. }) => {
+            scope.root.add_variable_ref(exp, span);
             let typ = typ?;
             let location = Location::assign_location(&typ);
             Ok(ExpressionAttributes::new(typ.into(), location))
@@ -468,6 +553,17 @@ fn expr_subscript(
 ) -> Result<ExpressionAttributes, FatalError> {
     if let fe::Expr::Subscript { value, index } = &exp.kind {
         let value_attributes = expr(scope, value, None)?;
+        // Unlike arrays and maps, fixed-size bytes are indexed on the stack.
+        let value_attributes = match value_attributes.typ {
+            Type::Base(Base::FixedBytes(_)) => {
+                let loaded = value_attributes
+                    .into_loaded()
+                    .expect("base types can be loaded");
+                scope.root.update_expression(value, loaded.clone());
+                loaded
+            }
+            _ => value_attributes,
+        };
         let index_attributes = value_expr(scope, index, None)?;
 
         // performs type checking
@@ -477,7 +573,9 @@ fn expr_subscript(
                     return Err(FatalError::new(scope.fancy_error(
                         &format!("`{}` type is not subscriptable", value_attributes.typ),
                         vec![Label::primary(value.span, "unsubscriptable type")],
-                        vec!["Note: Only arrays and maps are subscriptable".into()],
+                        vec![
+                            "Note: Only arrays, maps and fixed-size bytes are subscriptable".into(),
+                        ],
                     )));
                 }
                 Err(IndexingError::WrongIndexType) => {
@@ -493,11 +591,12 @@ fn expr_subscript(
                 Ok(val) => val,
             };
 
-        let location = match value_attributes.location {
+        let location = match value_attributes.final_location() {
             Location::Storage { .. } => Location::Storage { nonce: None },
             Location::Memory => Location::Memory,
-            // neither maps or arrays can be stored as values, so this is unreachable
-            Location::Value => unreachable!(),
+            // neither maps or arrays can be stored as values, so this is a byte
+            // of a fixed-size bytes value
+            Location::Value => Location::Value,
         };
 
         return Ok(ExpressionAttributes::new(typ, location));
@@ -506,6 +605,199 @@ fn expr_subscript(
     unreachable!()
 }
 
+fn expr_slice(
+    scope: &mut BlockScope,
+    exp: &Node<fe::Expr>,
+) -> Result<ExpressionAttributes, FatalError> {
+    if let fe::Expr::Slice { value, start, end } = &exp.kind {
+        let value_attributes = expr(scope, value, None)?;
+        let len = match &value_attributes.typ {
+            Type::Array(array) => {
+                if value_attributes.final_location() != Location::Memory {
+                    scope.fancy_error(
+                        "value must be copied to memory",
+                        vec![Label::primary(value.span, "this value is in storage")],
+                        vec!["Hint: values located in storage can be copied to memory using the `to_mem` function.".into(),
+                             "Example: `self.my_array.to_mem()`".into(),
+                        ],
+                    );
+                }
+                array.size
+            }
+            Type::Base(Base::FixedBytes(size)) => {
+                let loaded = value_attributes
+                    .clone()
+                    .into_loaded()
+                    .expect("base types can be loaded");
+                scope.root.update_expression(value, loaded);
+                *size
+            }
+            Type::String(_) => {
+                return Err(FatalError::new(scope.fancy_error(
+                    &format!("`{}` type can't be sliced", value_attributes.typ),
+                    vec![Label::primary(
+                        value.span,
+                        format!("this has type `{}`", value_attributes.typ),
+                    )],
+                    vec!["Hint: a part of a string can be copied with `slice`, e.g. `name.slice(0, 4)`".into()],
+                )));
+            }
+            typ => {
+                return Err(FatalError::new(scope.fancy_error(
+                    &format!("`{}` type can't be sliced", typ),
+                    vec![Label::primary(
+                        value.span,
+                        format!("this has type `{}`", typ),
+                    )],
+                    vec!["Note: Only arrays and fixed-size bytes can be sliced".into()],
+                )));
+            }
+        };
+
+        let start_index = match start {
+            Some(start) => slice_bound(scope, start)?,
+            None => 0,
+        };
+        let end_index = match end {
+            Some(end) => slice_bound(scope, end)?,
+            None => len,
+        };
+
+        if start_index > end_index || end_index > len {
+            return Err(FatalError::new(scope.fancy_error(
+                "slice out of bounds",
+                vec![Label::primary(
+                    exp.span,
+                    format!("this slices from {} to {}", start_index, end_index),
+                )],
+                vec![format!(
+                    "Note: the bounds of a slice of `{}` must satisfy `0 <= start <= end <= {}`",
+                    value_attributes.typ, len
+                )],
+            )));
+        }
+
+        return match value_attributes.typ {
+            Type::Array(array) => Ok(ExpressionAttributes::new(
+                Type::Array(Array {
+                    size: end_index - start_index,
+                    inner: array.inner,
+                }),
+                Location::Memory,
+            )),
+            _ if start_index == end_index => Err(FatalError::new(scope.fancy_error(
+                "empty slice of fixed-size bytes",
+                vec![Label::primary(exp.span, "this slice has no bytes")],
+                vec!["Note: fixed-size bytes types have at least one byte".into()],
+            ))),
+            _ => Ok(ExpressionAttributes::new(
+                Type::Base(Base::FixedBytes(end_index - start_index)),
+                Location::Value,
+            )),
+        };
+    }
+
+    unreachable!()
+}
+
+/// Checks a bound of a slice expression and evaluates it. The size of a slice
+/// is part of its type, so the bounds must be known at compile time.
+fn slice_bound(scope: &mut BlockScope, bound: &Node<fe::Expr>) -> Result<usize, FatalError> {
+    let attributes = value_expr(scope, bound, Some(&Type::Base(U256)))?;
+    if !matches!(attributes.typ, Type::Base(Base::Numeric(integer)) if !integer.is_signed()) {
+        return Err(FatalError::new(scope.type_error(
+            "type mismatch",
+            bound.span,
+            &U256,
+            &attributes.typ,
+        )));
+    }
+
+    let db = scope.db();
+    let function = scope.root.function;
+    let value = const_eval::eval_const_expr(db, function.module(db), function.class(db), bound);
+    match value {
+        Ok(ConstValue::Int(value)) => value.to_usize().ok_or_else(|| {
+            FatalError::new(scope.error(
+                "slice out of bounds",
+                bound.span,
+                "this index is too large",
+            ))
+        }),
+        Ok(_) => unreachable!("unsigned integer expressions evaluate to integers"),
+        Err(err) => Err(FatalError::new(scope.fancy_error(
+            "slice bounds must be constant",
+            vec![Label::primary(err.span, err.kind.to_string())],
+            vec!["Note: the size of a slice is part of its type, so its bounds must be literals, constants or calls of `const` functions".into()],
+        ))),
+    }
+}
+
+fn expr_try(
+    scope: &mut BlockScope,
+    exp: &Node<fe::Expr>,
+) -> Result<ExpressionAttributes, FatalError> {
+    if let fe::Expr::Try { value } = &exp.kind {
+        let value_attributes = assignable_expr(scope, value, None)?;
+        let result = match value_attributes.typ {
+            Type::Result(result) => result,
+            _ => {
+                return Err(FatalError::new(scope.fancy_error(
+                    "the `?` operator can only be applied to values of type `Result`",
+                    vec![Label::primary(
+                        value.span,
+                        format!("this has type `{}`", value_attributes.typ),
+                    )],
+                    vec![],
+                )));
+            }
+        };
+
+        let return_type = scope
+            .root
+            .function
+            .signature(scope.db())
+            .return_type
+            .clone()?;
+        match return_type {
+            FixedSize::Result(FeResult { err, .. }) if err == result.err => {}
+            FixedSize::Result(FeResult { err, .. }) => {
+                scope.fancy_error(
+                    "mismatched error types",
+                    vec![Label::primary(
+                        exp.span,
+                        format!(
+                            "this returns an error of type `{}`, but the function returns errors of type `{}`",
+                            result.err, err
+                        ),
+                    )],
+                    vec![],
+                );
+            }
+            _ => {
+                scope.fancy_error(
+                    "the `?` operator can only be used in functions that return `Result`",
+                    vec![Label::primary(
+                        exp.span,
+                        format!(
+                            "this function returns `{}`, which can't hold an error of type `{}`",
+                            return_type, result.err
+                        ),
+                    )],
+                    vec![],
+                );
+            }
+        }
+
+        return Ok(ExpressionAttributes::new(
+            Type::Base(result.ok),
+            Location::Value,
+        ));
+    }
+
+    unreachable!()
+}
+
 fn expr_attribute(
     scope: &mut BlockScope,
     exp: &Node<fe::Expr>,
@@ -521,9 +813,9 @@ fn expr_attribute(
     // This will all go away when the `Context` struct is ready.
 
     if let fe::Expr::Name(name) = &target.kind {
-        match GlobalObject::from_str(name) {
-            Ok(GlobalObject::Block) => {
-                return match BlockField::from_str(&field.kind) {
+        if let Ok(object) = GlobalObject::from_str(name) {
+            let attributes = match object {
+                GlobalObject::Block => match BlockField::from_str(&field.kind) {
                     Ok(BlockField::Coinbase) => base_type(Base::Address),
                     Ok(BlockField::Difficulty) => base_type(U256),
                     Ok(BlockField::Number) => base_type(U256),
@@ -540,22 +832,16 @@ fn expr_attribute(
                             vec!["Note: Only `coinbase`, `difficulty`, `number` and `timestamp` can be accessed on `block`.".into()],
                         )))
                     }
-                }
-            }
-            Ok(GlobalObject::Chain) => {
-                return match ChainField::from_str(&field.kind) {
+                },
+                GlobalObject::Chain => match ChainField::from_str(&field.kind) {
                     Ok(ChainField::Id) => base_type(U256),
-                    Err(_) => {
-                        Err(FatalError::new(scope.fancy_error(
-                            "Not a chain field",
-                            vec![Label::primary(field.span, "")],
-                            vec!["Note: Only `id` can be accessed on `chain`.".into()],
-                        )))
-                    }
-                }
-            }
-            Ok(GlobalObject::Msg) => {
-                return match MsgField::from_str(&field.kind) {
+                    Err(_) => Err(FatalError::new(scope.fancy_error(
+                        "Not a chain field",
+                        vec![Label::primary(field.span, "")],
+                        vec!["Note: Only `id` can be accessed on `chain`.".into()],
+                    ))),
+                },
+                GlobalObject::Msg => match MsgField::from_str(&field.kind) {
                     Ok(MsgField::Sender) => base_type(Base::Address),
                     Ok(MsgField::Sig) => base_type(U256),
                     Ok(MsgField::Value) => base_type(U256),
@@ -571,25 +857,24 @@ fn expr_attribute(
                             vec!["Note: Only `sender`, `sig` and `value` can be accessed on `msg`.".into()],
                         )))
                     }
-                }
-            }
-            Ok(GlobalObject::Tx) => {
-                return match TxField::from_str(&field.kind) {
+                },
+                GlobalObject::Tx => match TxField::from_str(&field.kind) {
                     Ok(TxField::GasPrice) => base_type(U256),
                     Ok(TxField::Origin) => base_type(Base::Address),
-                    Err(_) => {
-                        Err(FatalError::new(scope.fancy_error(
-                            "Not a `tx` field",
-                            vec![Label::primary(field.span, "")],
-                            vec![
-                                "Note: Only `gas_price` and `origin` can be accessed on `tx`."
-                                    .into(),
-                            ],
-                        )))
-                    }
-                }
-            }
-            Err(_) => {}
+                    Err(_) => Err(FatalError::new(scope.fancy_error(
+                        "Not a `tx` field",
+                        vec![Label::primary(field.span, "")],
+                        vec!["Note: Only `gas_price` and `origin` can be accessed on `tx`.".into()],
+                    ))),
+                },
+            }?;
+
+            // The environment doesn't change during a call, so lowering may
+            // read each of these values once per function.
+            scope
+                .root
+                .add_env_read(exp, format!("{}.{}", name, field.kind).into());
+            return Ok(attributes);
         }
     }
 
@@ -609,11 +894,21 @@ fn expr_attribute(
                     typ?,
                     Location::Storage { nonce: Some(nonce) },
                 )),
-                None => Err(FatalError::new(scope.fancy_error(
-                    &format!("No field `{}` exists on this contract", &field.kind),
-                    vec![Label::primary(field.span, "undefined field")],
-                    vec![],
-                ))),
+                None => {
+                    let notes = if contract.id.constant(scope.db(), &field.kind).is_some() {
+                        vec![format!(
+                            "Hint: `{}` is a constant, which is used without `self.`",
+                            &field.kind
+                        )]
+                    } else {
+                        vec![]
+                    };
+                    Err(FatalError::new(scope.fancy_error(
+                        &format!("No field `{}` exists on this contract", &field.kind),
+                        vec![Label::primary(field.span, "undefined field")],
+                        notes,
+                    )))
+                }
             }
         }
         // If the value is a struct, we return the type of the struct field. The location stays the
@@ -719,16 +1014,18 @@ fn expr_bin_operation(
             Err(err) => {
                 return Err(FatalError::new(add_bin_operations_errors(
                     scope,
-                    &op.kind,
-                    left.span,
+                    op,
+                    left,
                     &left_attributes.typ,
-                    right.span,
+                    right,
                     &right_attributes.typ,
                     err,
+                    false,
                 )));
             }
             Ok(val) => val,
         };
+        lints::check_modulo(scope, exp);
 
         return Ok(ExpressionAttributes::new(typ, Location::Value));
     }
@@ -785,7 +1082,10 @@ fn expr_unary_operation(
                 ))
             }
             UnaryOperator::Invert => {
-                if !matches!(operand_attributes.typ, Type::Base(Base::Numeric(_))) {
+                if !matches!(
+                    operand_attributes.typ,
+                    Type::Base(Base::Numeric(_) | Base::FixedBytes(_))
+                ) {
                     emit_err(scope, "a numeric type")
                 }
 
@@ -805,10 +1105,15 @@ fn expr_call(
     func: &Node<fe::Expr>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    expected_type: Option<&Type>,
 ) -> Result<ExpressionAttributes, FatalError> {
     let (attributes, call_type) = match &func.kind {
-        fe::Expr::Name(name) => expr_call_name(scope, name, func, generic_args, args)?,
-        fe::Expr::Path(path) => expr_call_path(scope, path, func, generic_args, args)?,
+        fe::Expr::Name(name) => {
+            expr_call_name(scope, name, func, generic_args, args, expected_type)?
+        }
+        fe::Expr::Path(path) => {
+            expr_call_path(scope, path, func, generic_args, args, expected_type)?
+        }
         fe::Expr::Attribute { value, attr } => {
             // TODO: err if there are generic args
             expr_call_method(scope, value, attr, generic_args, args)?
@@ -854,6 +1159,7 @@ fn expr_call_name<T: std::fmt::Display>(
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    expected_type: Option<&Type>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     check_for_call_to_special_fns(scope, name, func.span)?;
 
@@ -892,7 +1198,7 @@ fn expr_call_name<T: std::fmt::Display>(
         }
     })?;
 
-    expr_call_named_thing(scope, named_thing, func, generic_args, args)
+    expr_call_named_thing(scope, named_thing, func, generic_args, args, expected_type)
 }
 
 fn expr_call_path<T: std::fmt::Display>(
@@ -901,6 +1207,7 @@ fn expr_call_path<T: std::fmt::Display>(
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    expected_type: Option<&Type>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     let named_thing = scope.resolve_path(path).ok_or_else(|| {
         FatalError::new(scope.error(
@@ -910,7 +1217,7 @@ fn expr_call_path<T: std::fmt::Display>(
         ))
     })?;
 
-    expr_call_named_thing(scope, named_thing, func, generic_args, args)
+    expr_call_named_thing(scope, named_thing, func, generic_args, args, expected_type)
 }
 
 fn expr_call_named_thing<T: std::fmt::Display>(
@@ -919,16 +1226,46 @@ fn expr_call_named_thing<T: std::fmt::Display>(
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    expected_type: Option<&Type>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     match named_thing {
-        NamedThing::Item(Item::BuiltinFunction(function)) => {
-            expr_call_builtin_function(scope, function, func.span, generic_args, args)
-        }
+        NamedThing::Item(Item::BuiltinFunction(function)) => expr_call_builtin_function(
+            scope,
+            function,
+            func.span,
+            generic_args,
+            args,
+            expected_type,
+        ),
         NamedThing::Item(Item::Intrinsic(function)) => {
             expr_call_intrinsic(scope, function, func.span, generic_args, args)
         }
         NamedThing::Item(Item::Function(function)) => {
-            expr_call_pure(scope, function, generic_args, args)
+            expr_call_pure(scope, function, func.span, generic_args, args)
+        }
+        NamedThing::Item(Item::Type(TypeDef::Struct(struct_)))
+            if struct_.is_generic(scope.db()) =>
+        {
+            let db = scope.root.db;
+            let field_descs = struct_
+                .data(db)
+                .ast
+                .kind
+                .fields
+                .iter()
+                .map(|field| field.kind.typ.clone())
+                .collect::<Vec<_>>();
+            let (type_args, evaluated) = generic_call_type_args(
+                scope,
+                &struct_.name(db),
+                func.span,
+                &struct_.generic_params(db),
+                &field_descs,
+                generic_args,
+                args,
+            )?;
+            let instance = Struct::from_id(struct_.instantiate(db, type_args), db);
+            expr_call_struct_constructor(scope, func.span, instance, args, &evaluated)
         }
         NamedThing::Item(Item::Type(id)) => {
             if let Some(args) = generic_args {
@@ -1013,6 +1350,14 @@ fn expr_call_named_thing<T: std::fmt::Display>(
                 func.kind
             ),
         ))),
+        NamedThing::Item(Item::Trait(_)) => Err(FatalError::new(scope.error(
+            &format!("`{}` is not callable", func.kind),
+            func.span,
+            &format!(
+                "`{}` is a trait, and can't be used as a function",
+                func.kind
+            ),
+        ))),
     }
 }
 
@@ -1022,6 +1367,7 @@ fn expr_call_builtin_function(
     name_span: Span,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    expected_type: Option<&Type>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(args) = generic_args {
         scope.error(
@@ -1034,7 +1380,39 @@ fn expr_call_builtin_function(
         );
     }
 
-    let argument_attributes = expr_call_args(scope, args)?;
+    let expected_option = match expected_type {
+        Some(Type::Option(option)) => Some(*option),
+        _ => None,
+    };
+    let expected_result = match expected_type {
+        Some(Type::Result(result)) => Some(*result),
+        _ => None,
+    };
+    let expected_arg_type = match function {
+        GlobalFunction::Some => expected_option.map(|option| Type::Base(option.inner)),
+        GlobalFunction::Ok => expected_result.map(|result| Type::Base(result.ok)),
+        GlobalFunction::Err => expected_result.map(|result| Type::Base(result.err)),
+        _ => None,
+    };
+    let argument_attributes = match function {
+        GlobalFunction::Some | GlobalFunction::Ok | GlobalFunction::Err => args
+            .kind
+            .iter()
+            .map(|arg| value_expr(scope, &arg.kind.value, expected_arg_type.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?,
+        // `dbg` returns its value, so the value has the type that the call is
+        // expected to have.
+        GlobalFunction::Dbg | GlobalFunction::DbgRecord => args
+            .kind
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                let expected = if index == 0 { expected_type } else { None };
+                assignable_expr(scope, &arg.kind.value, expected)
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => expr_call_args(scope, args)?,
+    };
 
     let attrs = match function {
         GlobalFunction::Keccak256 => {
@@ -1139,81 +1517,388 @@ fn expr_call_builtin_function(
 
             ExpressionAttributes::new(Type::unit(), Location::Value)
         }
-    };
-    Ok((attrs, CallType::BuiltinFunction(function)))
-}
-
-fn expr_call_intrinsic(
-    scope: &mut BlockScope,
-    function: Intrinsic,
-    name_span: Span,
-    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
-    args: &Node<Vec<Node<fe::CallArg>>>,
-) -> Result<(ExpressionAttributes, CallType), FatalError> {
-    if let Some(args) = generic_args {
-        scope.error(
-            &format!(
-                "`{}` function does not expect generic arguments",
-                function.as_ref()
-            ),
-            args.span,
-            "unexpected generic argument list",
-        );
-    }
-
-    let argument_attributes = expr_call_args(scope, args)?;
-
-    validate_arg_count(
-        scope,
-        function.as_ref(),
-        name_span,
-        args,
-        function.arg_count(),
-        "arguments",
-    );
-    for (idx, arg_attr) in argument_attributes.iter().enumerate() {
-        if arg_attr.typ != Type::Base(Base::Numeric(Integer::U256)) {
-            scope.type_error(
-                "arguments to intrinsic functions must be u256",
-                args.kind[idx].kind.value.span,
-                &Integer::U256,
-                &arg_attr.typ,
+        GlobalFunction::Proportion
+        | GlobalFunction::ProportionCeil
+        | GlobalFunction::ProportionRound
+        | GlobalFunction::Bps
+        | GlobalFunction::BpsCeil
+        | GlobalFunction::BpsRound => {
+            let param_count = if matches!(
+                function,
+                GlobalFunction::Proportion
+                    | GlobalFunction::ProportionCeil
+                    | GlobalFunction::ProportionRound
+            ) {
+                3
+            } else {
+                2
+            };
+            validate_arg_count(
+                scope,
+                function.as_ref(),
+                name_span,
+                args,
+                param_count,
+                "argument",
             );
-        }
-    }
 
-    Ok((
-        ExpressionAttributes::new(Type::Base(function.return_type()), Location::Value),
-        CallType::Intrinsic(function),
-    ))
-}
+            for (index, (arg, attr)) in args.kind.iter().zip(&argument_attributes).enumerate() {
+                expect_no_label_on_arg(scope, args, index);
+                if attr.typ != Type::Base(U256) {
+                    scope.fancy_error(
+                        &format!(
+                            "`{}` can not be used as an argument to `{}`",
+                            attr.typ,
+                            function.as_ref(),
+                        ),
+                        vec![Label::primary(arg.span, "wrong type")],
+                        vec![format!(
+                            "Note: `{}` expects `u256` arguments",
+                            function.as_ref()
+                        )],
+                    );
+                }
+            }
+            ExpressionAttributes::new(Type::Base(U256), Location::Value)
+        }
+        GlobalFunction::Some => {
+            validate_arg_count(scope, function.as_ref(), name_span, args, 1, "argument");
+            expect_no_label_on_arg(scope, args, 0);
 
-fn expr_call_pure(
-    scope: &mut BlockScope,
-    function: FunctionId,
+            let inner = match argument_attributes.first().map(|attr| &attr.typ) {
+                Some(Type::Base(base)) => *base,
+                Some(arg_typ) => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "`{}` can not be used as an argument to `{}`",
+                            arg_typ,
+                            function.as_ref(),
+                        ),
+                        vec![Label::primary(args.span, "wrong type")],
+                        vec![format!(
+                            "Note: `{}` expects a primitive type argument",
+                            function.as_ref()
+                        )],
+                    )))
+                }
+                None => expected_option.map_or(Base::Unit, |option| option.inner),
+            };
+            ExpressionAttributes::new(Type::Option(FeOption { inner }), Location::Memory)
+        }
+        GlobalFunction::None => {
+            validate_arg_count(scope, function.as_ref(), name_span, args, 0, "argument");
+
+            let option = expected_option.ok_or_else(|| {
+                FatalError::new(scope.fancy_error(
+                    "type annotations needed",
+                    vec![Label::primary(
+                        name_span,
+                        "cannot infer the inner type of this `Option`",
+                    )],
+                    vec![
+                        "Hint: use `none()` where an `Option` type is expected, e.g. `let x: Option<u256> = none()`".into(),
+                    ],
+                ))
+            })?;
+            ExpressionAttributes::new(Type::Option(option), Location::Memory)
+        }
+        GlobalFunction::Ok | GlobalFunction::Err => {
+            validate_arg_count(scope, function.as_ref(), name_span, args, 1, "argument");
+            expect_no_label_on_arg(scope, args, 0);
+
+            let result = expected_result.ok_or_else(|| {
+                FatalError::new(scope.fancy_error(
+                    "type annotations needed",
+                    vec![Label::primary(
+                        name_span,
+                        "cannot infer the types of this `Result`",
+                    )],
+                    vec![format!(
+                        "Hint: use `{}(..)` where a `Result` type is expected, e.g. `let x: Result<u256, u8> = {}(..)`",
+                        function.as_ref(),
+                        function.as_ref()
+                    )],
+                ))
+            })?;
+
+            if let (Some(arg), Some(arg_attributes), Some(expected)) = (
+                args.kind.first(),
+                argument_attributes.first(),
+                &expected_arg_type,
+            ) {
+                if arg_attributes.typ != *expected {
+                    scope.type_error("type mismatch", arg.span, expected, &arg_attributes.typ);
+                }
+            }
+            ExpressionAttributes::new(Type::Result(result), Location::Memory)
+        }
+        GlobalFunction::RawSload | GlobalFunction::RawSstore => {
+            let (param_count, access, typ) = if function == GlobalFunction::RawSload {
+                (1, "reads", Type::Base(U256))
+            } else {
+                (2, "writes", Type::unit())
+            };
+            validate_arg_count(
+                scope,
+                function.as_ref(),
+                name_span,
+                args,
+                param_count,
+                "argument",
+            );
+
+            for (index, (arg, attr)) in args.kind.iter().zip(&argument_attributes).enumerate() {
+                expect_no_label_on_arg(scope, args, index);
+                if attr.typ != Type::Base(U256) {
+                    scope.fancy_error(
+                        &format!(
+                            "`{}` can not be used as an argument to `{}`",
+                            attr.typ,
+                            function.as_ref(),
+                        ),
+                        vec![Label::primary(arg.span, "wrong type")],
+                        vec![format!(
+                            "Note: `{}` expects `u256` arguments",
+                            function.as_ref()
+                        )],
+                    );
+                }
+            }
+
+            let db = scope.db();
+            let in_contract = matches!(scope.root.function.class(db), Some(Class::Contract(_)));
+            if !in_contract || !scope.root.function.takes_self(db) {
+                scope.fancy_error(
+                    &format!(
+                        "`{}` can only be called in contract functions that take `self`",
+                        function.as_ref()
+                    ),
+                    vec![Label::primary(
+                        name_span,
+                        format!("this {} contract storage", access),
+                    )],
+                    vec![format!(
+                        "Note: `{}` {} contract storage, so the function calling it can't be pure",
+                        function.as_ref(),
+                        access
+                    )],
+                );
+            }
+            ExpressionAttributes::new(typ, Location::Value)
+        }
+        GlobalFunction::RawCall | GlobalFunction::Delegatecall | GlobalFunction::Staticcall => {
+            let param_count = if function == GlobalFunction::RawCall {
+                4
+            } else {
+                3
+            };
+            validate_arg_count(
+                scope,
+                function.as_ref(),
+                name_span,
+                args,
+                param_count,
+                "argument",
+            );
+
+            for (index, (arg, attr)) in args.kind.iter().zip(&argument_attributes).enumerate() {
+                expect_no_label_on_arg(scope, args, index);
+                let (valid, expected) = match index {
+                    0 => (
+                        attr.typ == Type::Base(Base::Address),
+                        "an `address` as first argument",
+                    ),
+                    1 => (
+                        matches!(
+                            attr.typ,
+                            Type::Array(Array {
+                                inner: Base::Numeric(Integer::U8),
+                                ..
+                            })
+                        ),
+                        "a byte array as second argument",
+                    ),
+                    2 => (
+                        attr.typ == Type::Base(U256),
+                        "a `u256` gas limit as third argument",
+                    ),
+                    _ => (
+                        attr.typ == Type::Base(U256),
+                        "a `u256` value as fourth argument",
+                    ),
+                };
+                if !valid {
+                    scope.fancy_error(
+                        &format!(
+                            "`{}` can not be used as an argument to `{}`",
+                            attr.typ,
+                            function.as_ref(),
+                        ),
+                        vec![Label::primary(arg.span, "wrong type")],
+                        vec![format!(
+                            "Note: `{}` expects {}",
+                            function.as_ref(),
+                            expected
+                        )],
+                    );
+                }
+            }
+
+            // The size of the return data is taken from the expected type.
+            let typ = expected_type
+                .filter(|typ| builtins::call_return_data_size(scope.db(), typ).is_some())
+                .cloned()
+                .ok_or_else(|| {
+                    FatalError::new(scope.fancy_error(
+                        "type annotations needed",
+                        vec![Label::primary(
+                            name_span,
+                            "cannot infer the size of the return data of this call",
+                        )],
+                        vec![format!(
+                            "Hint: use `{}(..)` where a `(bool, Array<u8, N>)` tuple is expected, e.g. `let (success, data): (bool, Array<u8, 32>) = {}(..)`",
+                            function.as_ref(),
+                            function.as_ref()
+                        )],
+                    ))
+                })?;
+            ExpressionAttributes::new(typ, Location::Memory)
+        }
+        GlobalFunction::Dbg | GlobalFunction::DbgRecord => {
+            // The location of a `__dbg_record` call is the file id and the
+            // start and end offsets of the value.
+            let param_count = if function == GlobalFunction::Dbg {
+                1
+            } else {
+                4
+            };
+            validate_arg_count(
+                scope,
+                function.as_ref(),
+                name_span,
+                args,
+                param_count,
+                "argument",
+            );
+            for index in 0..args.kind.len() {
+                expect_no_label_on_arg(scope, args, index);
+            }
+
+            let typ = match argument_attributes.first().map(|attr| &attr.typ) {
+                Some(typ @ Type::Base(Base::Numeric(_) | Base::Bool | Base::Address)) => {
+                    typ.clone()
+                }
+                Some(typ) => {
+                    scope.fancy_error(
+                        &format!(
+                            "`{}` can not be used as an argument to `{}`",
+                            typ,
+                            function.as_ref(),
+                        ),
+                        vec![Label::primary(args.span, "wrong type")],
+                        vec![format!(
+                            "Note: `{}` expects a number, `bool` or `address` argument",
+                            function.as_ref()
+                        )],
+                    );
+                    typ.clone()
+                }
+                None => Type::Base(Base::Unit),
+            };
+            ExpressionAttributes::new(typ, Location::Value)
+        }
+    };
+    Ok((attrs, CallType::BuiltinFunction(function)))
+}
+
+fn expr_call_intrinsic(
+    scope: &mut BlockScope,
+    function: Intrinsic,
+    name_span: Span,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
-    let fn_name = function.name(scope.db());
     if let Some(args) = generic_args {
-        scope.fancy_error(
-            &format!("`{}` function is not generic", fn_name),
-            vec![Label::primary(
-                args.span,
-                "unexpected generic argument list",
-            )],
-            vec![],
+        scope.error(
+            &format!(
+                "`{}` function does not expect generic arguments",
+                function.as_ref()
+            ),
+            args.span,
+            "unexpected generic argument list",
         );
     }
 
-    let sig = function.signature(scope.db());
-    validate_named_args(
+    let argument_attributes = expr_call_args(scope, args)?;
+
+    validate_arg_count(
+        scope,
+        function.as_ref(),
+        name_span,
+        args,
+        function.arg_count(),
+        "arguments",
+    );
+    for (idx, arg_attr) in argument_attributes.iter().enumerate() {
+        if arg_attr.typ != Type::Base(Base::Numeric(Integer::U256)) {
+            scope.type_error(
+                "arguments to intrinsic functions must be u256",
+                args.kind[idx].kind.value.span,
+                &Integer::U256,
+                &arg_attr.typ,
+            );
+        }
+    }
+
+    Ok((
+        ExpressionAttributes::new(Type::Base(function.return_type()), Location::Value),
+        CallType::Intrinsic(function),
+    ))
+}
+
+fn expr_call_pure(
+    scope: &mut BlockScope,
+    function: FunctionId,
+    name_span: Span,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let db = scope.root.db;
+    let fn_name = function.name(db);
+    let (function, evaluated) = if function.is_generic(db) {
+        let (type_args, evaluated) = generic_call_type_args(
+            scope,
+            &fn_name,
+            name_span,
+            &function.generic_params(db),
+            &param_type_descs(db, function),
+            generic_args,
+            args,
+        )?;
+        (function.instantiate(db, type_args), evaluated)
+    } else {
+        if let Some(args) = generic_args {
+            scope.fancy_error(
+                &format!("`{}` function is not generic", fn_name),
+                vec![Label::primary(
+                    args.span,
+                    "unexpected generic argument list",
+                )],
+                vec![],
+            );
+        }
+        (function, vec![])
+    };
+
+    let sig = function.signature(db);
+    validate_named_args_with_attrs(
         scope,
         &fn_name,
-        function.name_span(scope.db()),
+        function.name_span(db),
         args,
         &sig.params,
         LabelPolicy::AllowAnyUnlabeled,
+        &evaluated,
     )?;
 
     let return_type = sig.return_type.clone()?;
@@ -1232,7 +1917,10 @@ fn expr_call_type_constructor(
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     match typ {
         Type::Struct(struct_type) => {
-            return expr_call_struct_constructor(scope, name_span, struct_type, args)
+            return expr_call_struct_constructor(scope, name_span, struct_type, args, &[])
+        }
+        Type::Base(Base::FixedBytes(size)) => {
+            return expr_call_fixed_bytes_constructor(scope, size, name_span, args)
         }
         Type::Base(Base::Bool) => {
             return Err(FatalError::new(scope.error(
@@ -1248,13 +1936,27 @@ fn expr_call_type_constructor(
                 "",
             )))
         }
-        Type::Array(_) => {
+        Type::Array(_) | Type::DynamicArray(_) => {
             return Err(FatalError::new(scope.error(
                 "`Array` type is not callable",
                 name_span,
                 "",
             )))
         }
+        Type::Option(_) => {
+            return Err(FatalError::new(scope.fancy_error(
+                "`Option` type is not callable",
+                vec![Label::primary(name_span, "")],
+                vec!["Hint: use `some(value)` or `none()` to create an `Option`".into()],
+            )))
+        }
+        Type::Result(_) => {
+            return Err(FatalError::new(scope.fancy_error(
+                "`Result` type is not callable",
+                vec![Label::primary(name_span, "")],
+                vec!["Hint: use `ok(value)` or `err(error)` to create a `Result`".into()],
+            )))
+        }
         _ => {}
     }
 
@@ -1292,6 +1994,33 @@ fn expr_call_type_constructor(
                             scope.error("Casting between numeric values can change the sign or size but not both at once", arg.span, &format!("can not cast from `{}` to `{}` in a single step", arg_exp.typ, typ));
                         }
                     }
+                    Type::Base(Base::FixedBytes(size)) => {
+                        if integer.is_signed() || integer.size() < size {
+                            scope.fancy_error(
+                                &format!("can't convert `{}` to `{}`", arg_exp.typ, integer),
+                                vec![Label::primary(
+                                    arg.span,
+                                    format!("this has type `{}`", arg_exp.typ),
+                                )],
+                                vec![format!(
+                                    "Note: `bytes{}` values can only be converted to unsigned integers with at least {} bytes",
+                                    size, size
+                                )],
+                            );
+                        }
+                    }
+                    Type::Enum(_) => {
+                        if *integer == Integer::I8 {
+                            scope.fancy_error(
+                                &format!("can't convert `{}` to `i8`", arg_exp.typ),
+                                vec![Label::primary(
+                                    arg.span,
+                                    format!("this has type `{}`", arg_exp.typ),
+                                )],
+                                vec!["Note: an enum value is a variant index from 0 to 255, which doesn't fit into `i8`".into()],
+                            );
+                        }
+                    }
                     Type::Base(Base::Address) => {
                         if *integer != Integer::U256 {
                             scope.error(
@@ -1328,22 +2057,152 @@ fn expr_call_type_constructor(
             };
             ExpressionAttributes::new(Type::Base(Base::Address), Location::Value)
         }
+        Type::Enum(enum_type) => {
+            if let Some(arg) = args.kind.first() {
+                let arg_attr = assignable_expr(scope, &arg.kind.value, None)?;
+                match (&arg.kind.value.kind, &arg_attr.typ) {
+                    (fe::Expr::Num(num), _) => {
+                        if to_bigint(num) >= BigInt::from(enum_type.variant_count) {
+                            scope.fancy_error(
+                                &format!("literal out of range for `{}`", typ),
+                                vec![Label::primary(
+                                    arg.span,
+                                    "there is no variant with this index",
+                                )],
+                                vec![format!(
+                                    "Note: `{}` has {} variants, with the indices 0 to {}",
+                                    typ,
+                                    enum_type.variant_count,
+                                    enum_type.variant_count.saturating_sub(1)
+                                )],
+                            );
+                        }
+                    }
+                    (_, Type::Base(Base::Numeric(integer))) if !integer.is_signed() => {}
+                    _ => {
+                        scope.fancy_error(
+                            &format!("can't convert `{}` to `{}`", arg_attr.typ, typ),
+                            vec![Label::primary(
+                                arg.span,
+                                format!("this has type `{}`", arg_attr.typ),
+                            )],
+                            vec![format!(
+                                "Note: `{}` values are created from unsigned integers, which are checked to be valid variant indices",
+                                typ
+                            )],
+                        );
+                    }
+                }
+            }
+            ExpressionAttributes::new(typ.clone(), Location::Value)
+        }
         Type::Base(Base::Unit) => unreachable!(), // rejected in expr_call_type
         Type::Base(Base::Bool) => unreachable!(), // handled above
+        Type::Base(Base::FixedBytes(_)) => unreachable!(), // handled above
         Type::Tuple(_) => unreachable!(),         // rejected in expr_call_type
         Type::Struct(_) => unreachable!(),        // handled above
         Type::Map(_) => unreachable!(),           // handled above
         Type::Array(_) => unreachable!(),         // handled above
+        Type::DynamicArray(_) => unreachable!(),  // handled above
+        Type::Option(_) => unreachable!(),        // handled above
+        Type::Result(_) => unreachable!(),        // handled above
         Type::SelfContract(_) => unreachable!(), // unnameable; contract names all become Type::Contract
     };
     Ok((expr_attrs, CallType::TypeConstructor(typ)))
 }
 
+/// Checks a call of a fixed-size bytes type, e.g. `bytes4(x)`.
+///
+/// A single argument can be a numeric literal or an unsigned integer that fits
+/// into the bytes. Otherwise, the arguments are fixed-size bytes values that
+/// are concatenated, and their sizes must add up to the size of the type.
+fn expr_call_fixed_bytes_constructor(
+    scope: &mut BlockScope,
+    size: usize,
+    name_span: Span,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let typ = Type::Base(Base::FixedBytes(size));
+    if args.kind.is_empty() {
+        validate_arg_count(scope, &typ.to_string(), name_span, args, 1, "argument");
+    }
+
+    let mut concatenated_size = 0;
+    let mut arg_sizes = vec![];
+    let mut valid_types = true;
+    for (index, arg) in args.kind.iter().enumerate() {
+        expect_no_label_on_arg(scope, args, index);
+        let arg_attr = assignable_expr(scope, &arg.kind.value, None)?;
+        match (&arg.kind.value.kind, &arg_attr.typ) {
+            (_, Type::Base(Base::FixedBytes(arg_size))) => {
+                arg_sizes.push(*arg_size);
+                concatenated_size += arg_size
+            }
+            (fe::Expr::Num(num), _) if args.kind.len() == 1 => {
+                let num = to_bigint(num);
+                if num >= BigInt::from(1) << (size * 8) {
+                    scope.error(
+                        &format!("literal out of range for `{}`", typ),
+                        arg.span,
+                        &format!("does not fit into type `{}`", typ),
+                    );
+                }
+                concatenated_size = size;
+            }
+            (_, Type::Base(Base::Numeric(integer))) if args.kind.len() == 1 => {
+                if integer.is_signed() || integer.size() > size {
+                    scope.fancy_error(
+                        &format!("can't convert `{}` to `{}`", integer, typ),
+                        vec![Label::primary(arg.span, format!("this has type `{}`", integer))],
+                        vec![format!(
+                            "Note: only unsigned integers with at most {} bytes can be converted to `{}`",
+                            size, typ
+                        )],
+                    );
+                }
+                concatenated_size = size;
+            }
+            _ => {
+                scope.error(
+                    "type mismatch",
+                    arg.span,
+                    &format!(
+                        "expected a fixed-size bytes type or an unsigned integer but was `{}`",
+                        arg_attr.typ
+                    ),
+                );
+                valid_types = false;
+            }
+        }
+    }
+
+    if valid_types && !args.kind.is_empty() && concatenated_size != size {
+        scope.fancy_error(
+            &format!("can't build `{}` from {} bytes", typ, concatenated_size),
+            args.kind
+                .iter()
+                .zip(&arg_sizes)
+                .map(|(arg, arg_size)| Label::secondary(arg.span, format!("{} bytes", arg_size)))
+                .collect(),
+            vec![format!(
+                "Note: the sizes of the concatenated values must add up to {}",
+                size
+            )],
+        );
+    }
+
+    Ok((
+        ExpressionAttributes::new(typ.clone(), Location::Value),
+        CallType::TypeConstructor(typ),
+    ))
+}
+
 fn expr_call_struct_constructor(
     scope: &mut BlockScope,
     name_span: Span,
     struct_: Struct,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    evaluated: &[Option<ExpressionAttributes>],
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     let db = scope.root.db;
 
@@ -1374,13 +2233,14 @@ fn expr_call_struct_constructor(
         .iter()
         .map(|(name, field)| (name.clone(), field.typ(db)))
         .collect::<Vec<_>>();
-    validate_named_args(
+    validate_named_args_with_attrs(
         scope,
         &struct_.name,
         name_span,
         args,
         &fields,
         LabelPolicy::AllowUnlabledIfNameEqual,
+        evaluated,
     )?;
 
     Ok((
@@ -1402,6 +2262,18 @@ fn expr_call_method(
     // All other `NamedThing`s will be handled correctly by `expr()`.
     if let fe::Expr::Name(name) = &target.kind {
         match scope.resolve_name(name) {
+            Some(NamedThing::Item(Item::Type(TypeDef::Struct(struct_))))
+                if struct_.is_generic(scope.db()) =>
+            {
+                return expr_call_generic_struct_attribute(
+                    scope,
+                    struct_,
+                    target.span,
+                    field,
+                    generic_args,
+                    args,
+                )
+            }
             Some(NamedThing::Item(Item::Type(id))) => {
                 return expr_call_type_attribute(
                     scope,
@@ -1410,6 +2282,7 @@ fn expr_call_method(
                     field,
                     generic_args,
                     args,
+                    &[],
                 )
             }
             Some(NamedThing::Item(Item::Object(object))) => {
@@ -1445,6 +2318,7 @@ fn expr_call_method(
     if let Some(class) = target_attributes.typ.as_class() {
         if matches!(class, Class::Contract(_)) {
             check_for_call_to_special_fns(scope, &field.kind, field.span)?;
+            check_for_call_to_dispatcher_fns(scope, &field.kind, field.span)?;
         }
         if let Some(method) = class.function(scope.db(), &field.kind) {
             let is_self = is_self_value(target);
@@ -1558,13 +2432,27 @@ fn expr_call_builtin_value_method(
     method_name: &Node<SmolStr>,
     args: &Node<Vec<Node<fe::CallArg>>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
-    // for now all of these functions expect 0 arguments
+    let arg_count = match method {
+        ValueMethod::UnwrapOr
+        | ValueMethod::DivFloor
+        | ValueMethod::DivCeil
+        | ValueMethod::DivRound
+        | ValueMethod::Contains
+        | ValueMethod::IndexOf
+        | ValueMethod::Fill
+        | ValueMethod::CopyFrom
+        | ValueMethod::BinarySearch
+        | ValueMethod::Push
+        | ValueMethod::Concat => 1,
+        ValueMethod::Slice => 2,
+        _ => 0,
+    };
     validate_arg_count(
         scope,
         &method_name.kind,
         method_name.span,
         args,
-        0,
+        arg_count,
         "argument",
     );
 
@@ -1665,35 +2553,427 @@ fn expr_call_builtin_value_method(
                     );
                 }
 
-                Ok((
-                    ExpressionAttributes::new(
-                        Type::Array(Array {
-                            inner: Base::Numeric(Integer::U8),
-                            size: tuple.items.len() * 32,
-                        }),
-                        Location::Memory,
-                    ),
-                    calltype,
-                ))
+                Ok((
+                    ExpressionAttributes::new(
+                        Type::Array(Array {
+                            inner: Base::Numeric(Integer::U8),
+                            size: tuple.items.len() * 32,
+                        }),
+                        Location::Memory,
+                    ),
+                    calltype,
+                ))
+            }
+            _ => Err(FatalError::new(scope.fancy_error(
+                &format!(
+                    "value of type `{}` does not support `abi_encode()`",
+                    value_attrs.typ
+                ),
+                vec![Label::primary(
+                    value.span,
+                    "this value cannot be encoded using `abi_encode()`",
+                )],
+                vec![
+                    "Hint: struct and tuple values can be encoded.".into(),
+                    "Example: `(42,).abi_encode()`".into(),
+                ],
+            ))),
+        },
+        ValueMethod::IsOk | ValueMethod::IsErr => match &value_attrs.typ {
+            Type::Result(_) => Ok((
+                ExpressionAttributes::new(Type::Base(Base::Bool), Location::Value),
+                calltype,
+            )),
+            _ => Err(FatalError::new(scope.fancy_error(
+                &format!(
+                    "No function `{}` exists on type `{}`",
+                    &method_name.kind, &value_attrs.typ
+                ),
+                vec![Label::primary(method_name.span, "undefined function")],
+                vec![format!(
+                    "Note: `{}` can only be called on `Result` values",
+                    &method_name.kind
+                )],
+            ))),
+        },
+        ValueMethod::IsSome | ValueMethod::IsNone | ValueMethod::Unwrap | ValueMethod::UnwrapOr => {
+            let inner = match &value_attrs.typ {
+                Type::Option(option) => option.inner,
+                Type::Result(result)
+                    if matches!(method, ValueMethod::Unwrap | ValueMethod::UnwrapOr) =>
+                {
+                    result.ok
+                }
+                _ => {
+                    let receivers = match method {
+                        ValueMethod::Unwrap | ValueMethod::UnwrapOr => "`Option` or `Result`",
+                        _ => "`Option`",
+                    };
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on {} values",
+                            &method_name.kind, receivers
+                        )],
+                    )));
+                }
+            };
+
+            let return_type = match method {
+                ValueMethod::IsSome | ValueMethod::IsNone => Type::Base(Base::Bool),
+                ValueMethod::UnwrapOr => {
+                    let inner = Type::Base(inner);
+                    if let Some(arg) = args.kind.first() {
+                        expect_no_label_on_arg(scope, args, 0);
+                        let default = value_expr(scope, &arg.kind.value, Some(&inner))?;
+                        if default.typ != inner {
+                            scope.type_error("type mismatch", arg.span, &inner, &default.typ);
+                        }
+                    }
+                    inner
+                }
+                _ => Type::Base(inner),
+            };
+            Ok((
+                ExpressionAttributes::new(return_type, Location::Value),
+                calltype,
+            ))
+        }
+        ValueMethod::DivFloor | ValueMethod::DivCeil | ValueMethod::DivRound => {
+            let typ = match &value_attrs.typ {
+                Type::Base(Base::Numeric(integer)) => Type::Base(Base::Numeric(*integer)),
+                _ => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on integer values",
+                            &method_name.kind
+                        )],
+                    )));
+                }
+            };
+
+            // The dividend is passed to the runtime function by value.
+            scope.root.update_expression(
+                value,
+                value_attrs
+                    .into_loaded()
+                    .expect("should be able to move integer type to stack"),
+            );
+
+            if let Some(arg) = args.kind.first() {
+                expect_no_label_on_arg(scope, args, 0);
+                let divisor = value_expr(scope, &arg.kind.value, Some(&typ))?;
+                if divisor.typ != typ {
+                    scope.type_error("type mismatch", arg.span, &typ, &divisor.typ);
+                }
+            }
+            Ok((ExpressionAttributes::new(typ, Location::Value), calltype))
+        }
+        ValueMethod::Contains
+        | ValueMethod::IndexOf
+        | ValueMethod::Fill
+        | ValueMethod::CopyFrom
+        | ValueMethod::Sort
+        | ValueMethod::BinarySearch => {
+            let array = match &value_attrs.typ {
+                Type::Array(array) => array.clone(),
+                _ => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on arrays",
+                            &method_name.kind
+                        )],
+                    )));
+                }
+            };
+
+            // `sort` and `binary_search` are only implemented for arrays in memory.
+            if matches!(method, ValueMethod::Sort | ValueMethod::BinarySearch)
+                && value_attrs.final_location() != Location::Memory
+            {
+                scope.fancy_error(
+                    &format!(
+                        "`{}` can only be called on arrays in memory",
+                        &method_name.kind
+                    ),
+                    vec![
+                        Label::primary(value.span, "this value is in storage"),
+                        Label::secondary(
+                            method_name.span,
+                            "hint: copy the array to memory with `.to_mem()`",
+                        ),
+                    ],
+                    vec![],
+                );
+            }
+
+            if let Some(arg) = args.kind.first() {
+                expect_no_label_on_arg(scope, args, 0);
+                if method == ValueMethod::CopyFrom {
+                    // The source is copied item by item, so it must be in memory.
+                    let expected = Type::Array(array);
+                    let source = assignable_expr(scope, &arg.kind.value, Some(&expected))?;
+                    if source.typ != expected {
+                        scope.type_error("type mismatch", arg.span, &expected, &source.typ);
+                    }
+                } else {
+                    let inner = Type::Base(array.inner);
+                    let item = value_expr(scope, &arg.kind.value, Some(&inner))?;
+                    if item.typ != inner {
+                        scope.type_error("type mismatch", arg.span, &inner, &item.typ);
+                    }
+                }
+            }
+
+            let return_type = match method {
+                ValueMethod::Contains => Type::Base(Base::Bool),
+                ValueMethod::IndexOf | ValueMethod::BinarySearch => {
+                    Type::Base(Base::Numeric(Integer::U256))
+                }
+                _ => Type::Base(Base::Unit),
+            };
+            Ok((
+                ExpressionAttributes::new(return_type, Location::Value),
+                calltype,
+            ))
+        }
+        ValueMethod::Push | ValueMethod::Pop => {
+            let array = match &value_attrs.typ {
+                Type::DynamicArray(array) => *array,
+                _ => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on dynamically-sized arrays",
+                            &method_name.kind
+                        )],
+                    )));
+                }
+            };
+
+            if let Some(arg) = args.kind.first() {
+                expect_no_label_on_arg(scope, args, 0);
+                let inner = Type::Base(array.inner);
+                let item = value_expr(scope, &arg.kind.value, Some(&inner))?;
+                if item.typ != inner {
+                    scope.type_error("type mismatch", arg.span, &inner, &item.typ);
+                }
             }
+
+            let return_type = match method {
+                ValueMethod::Push => Type::unit(),
+                _ => Type::Base(array.inner),
+            };
+            Ok((
+                ExpressionAttributes::new(return_type, Location::Value),
+                calltype,
+            ))
+        }
+        ValueMethod::Len => match &value_attrs.typ {
+            Type::DynamicArray(_) | Type::String(_) => Ok((
+                ExpressionAttributes::new(
+                    Type::Base(Base::Numeric(Integer::U256)),
+                    Location::Value,
+                ),
+                calltype,
+            )),
             _ => Err(FatalError::new(scope.fancy_error(
                 &format!(
-                    "value of type `{}` does not support `abi_encode()`",
-                    value_attrs.typ
+                    "No function `{}` exists on type `{}`",
+                    &method_name.kind, &value_attrs.typ
                 ),
-                vec![Label::primary(
-                    value.span,
-                    "this value cannot be encoded using `abi_encode()`",
+                vec![Label::primary(method_name.span, "undefined function")],
+                vec![format!(
+                    "Note: `{}` can only be called on strings and dynamically-sized arrays",
+                    &method_name.kind
                 )],
-                vec![
-                    "Hint: struct and tuple values can be encoded.".into(),
-                    "Example: `(42,).abi_encode()`".into(),
-                ],
             ))),
         },
+        ValueMethod::Concat | ValueMethod::Slice => {
+            let string = match &value_attrs.typ {
+                Type::String(string) => *string,
+                _ => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on strings",
+                            &method_name.kind
+                        )],
+                    )));
+                }
+            };
+
+            // The result is a new string, which is copied from strings in memory.
+            if value_attrs.final_location() != Location::Memory {
+                scope.fancy_error(
+                    &format!(
+                        "`{}` can only be called on strings in memory",
+                        &method_name.kind
+                    ),
+                    vec![
+                        Label::primary(value.span, "this value is in storage"),
+                        Label::secondary(
+                            method_name.span,
+                            "hint: copy the string to memory with `.to_mem()`",
+                        ),
+                    ],
+                    vec![],
+                );
+            }
+
+            let return_type = if method == ValueMethod::Concat {
+                let mut max_size = string.max_size;
+                if let Some(arg) = args.kind.first() {
+                    expect_no_label_on_arg(scope, args, 0);
+                    let other = assignable_expr(scope, &arg.kind.value, None)?;
+                    match other.typ {
+                        Type::String(other) => max_size += other.max_size,
+                        _ => {
+                            scope.fancy_error(
+                                "type mismatch",
+                                vec![Label::primary(
+                                    arg.span,
+                                    format!("this has type `{}`; expected a string", other.typ),
+                                )],
+                                vec![],
+                            );
+                        }
+                    }
+                }
+                FeString { max_size }
+            } else {
+                for (index, arg) in args.kind.iter().enumerate() {
+                    expect_no_label_on_arg(scope, args, index);
+                    let bound = value_expr(scope, &arg.kind.value, Some(&Type::Base(U256)))?;
+                    if bound.typ != Type::Base(U256) {
+                        scope.type_error("type mismatch", arg.span, &U256, &bound.typ);
+                    }
+                }
+                string
+            };
+            Ok((
+                ExpressionAttributes::new(Type::String(return_type), Location::Memory),
+                calltype,
+            ))
+        }
+        ValueMethod::Hash => {
+            let struct_ = match &value_attrs.typ {
+                Type::Struct(struct_) => struct_.clone(),
+                _ => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!(
+                            "No function `{}` exists on type `{}`",
+                            &method_name.kind, &value_attrs.typ
+                        ),
+                        vec![Label::primary(method_name.span, "undefined function")],
+                        vec![format!(
+                            "Note: `{}` can only be called on structs",
+                            &method_name.kind
+                        )],
+                    )));
+                }
+            };
+
+            if value_attrs.final_location() != Location::Memory {
+                scope.fancy_error(
+                    "`hash` can only be called on structs in memory",
+                    vec![
+                        Label::primary(value.span, "this value is in storage"),
+                        Label::secondary(
+                            method_name.span,
+                            "hint: copy the struct to memory with `.to_mem()`",
+                        ),
+                    ],
+                    vec![],
+                );
+            }
+
+            // Only the packed encoding of primitive types is defined.
+            let fields = struct_.id.fields(scope.db());
+            for (name, field) in fields.iter() {
+                match field.typ(scope.db()) {
+                    Ok(FixedSize::Base(_)) | Err(_) => {}
+                    Ok(typ) => {
+                        scope.fancy_error(
+                            &format!("struct `{}` can't be hashed", &struct_.name),
+                            vec![Label::primary(
+                                value.span,
+                                format!("field `{}` has type `{}`", name, typ),
+                            )],
+                            vec!["Note: `hash` can only be called on structs whose fields are numbers, `bool`, `address` or fixed-size bytes".into()],
+                        );
+                        break;
+                    }
+                }
+            }
+
+            Ok((
+                ExpressionAttributes::new(Type::Base(U256), Location::Value),
+                calltype,
+            ))
+        }
     }
 }
 
+/// Analyzes a call to an associated function of a generic struct, e.g.
+/// `Pair.new(1, 2)`. The type arguments of the struct are inferred from the
+/// arguments, or given with the call, e.g. `Pair.new<u8>(1, 2)`.
+fn expr_call_generic_struct_attribute(
+    scope: &mut BlockScope,
+    struct_: StructId,
+    target_span: Span,
+    field: &Node<SmolStr>,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let db = scope.root.db;
+    let struct_name = struct_.name(db);
+    let function = struct_.function(db, &field.kind).ok_or_else(|| {
+        FatalError::new(scope.fancy_error(
+            &format!(
+                "No function `{}` exists on type `{}`",
+                &field.kind, struct_name
+            ),
+            vec![Label::primary(field.span, "undefined function")],
+            vec![],
+        ))
+    })?;
+
+    let (type_args, evaluated) = generic_call_type_args(
+        scope,
+        &struct_name,
+        target_span,
+        &struct_.generic_params(db),
+        &param_type_descs(db, function),
+        generic_args,
+        args,
+    )?;
+    let typ = TypeDef::Struct(struct_.instantiate(db, type_args)).typ(db)?;
+    expr_call_type_attribute(scope, typ, target_span, field, &None, args, &evaluated)
+}
+
 fn expr_call_type_attribute(
     scope: &mut BlockScope,
     typ: Type,
@@ -1701,6 +2981,7 @@ fn expr_call_type_attribute(
     field: &Node<SmolStr>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    evaluated: &[Option<ExpressionAttributes>],
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(generic_args) = generic_args {
         scope.error(
@@ -1710,8 +2991,6 @@ fn expr_call_type_attribute(
         );
     }
 
-    let arg_attributes = expr_call_args(scope, args)?;
-
     if let Some(class) = typ.as_class() {
         let class_name = class.name(scope.db());
 
@@ -1724,33 +3003,85 @@ fn expr_call_type_attribute(
                         vec![Label::primary(field.span, "Contract creation")],
                         vec![format!("Note: Consider using a dedicated factory contract to create instances of `{}`", &class_name)]);
                 }
+                if contract.is_interface(scope.db()) {
+                    scope.fancy_error(
+                        &format!("`{}` is an interface and can't be created", &class_name),
+                        vec![Label::primary(
+                            field.span,
+                            "an interface has no code to deploy",
+                        )],
+                        vec![format!(
+                            "Note: an interface calls a deployed contract, e.g. `{}(address)`",
+                            &class_name
+                        )],
+                    );
+                }
+                // The value (and salt) are followed by the arguments of the
+                // created contract's `__init__` function.
+                let init_params = contract
+                    .init_function(scope.db())
+                    .map(|init| init.signature(scope.db()).params.clone())
+                    .unwrap_or_default();
                 let arg_count = function.arg_count();
-                validate_arg_count(scope, &field.kind, field.span, args, arg_count, "argument");
+                validate_arg_count(
+                    scope,
+                    &field.kind,
+                    field.span,
+                    args,
+                    arg_count + init_params.len(),
+                    "argument",
+                );
 
-                for i in 0..arg_count {
-                    if let Some(attrs) = arg_attributes.get(i) {
-                        if !matches!(&attrs.typ, Type::Base(Base::Numeric(_))) {
-                            scope.fancy_error(
-                                &format!(
-                                    "incorrect type for argument to `{}.{}`",
-                                    &class_name,
-                                    function.as_ref()
-                                ),
-                                vec![Label::primary(
-                                    args.kind[i].span,
-                                    format!("this has type `{}`; expected a number", &attrs.typ),
-                                )],
-                                vec![],
-                            );
-                        }
+                let (builtin_args, init_args) = args.kind.split_at(arg_count.min(args.kind.len()));
+                for arg in builtin_args {
+                    let attrs = assignable_expr(scope, &arg.kind.value, None)?;
+                    if !matches!(&attrs.typ, Type::Base(Base::Numeric(_))) {
+                        scope.fancy_error(
+                            &format!(
+                                "incorrect type for argument to `{}.{}`",
+                                &class_name,
+                                function.as_ref()
+                            ),
+                            vec![Label::primary(
+                                arg.span,
+                                format!("this has type `{}`; expected a number", &attrs.typ),
+                            )],
+                            vec![],
+                        );
                     }
                 }
+                let init_args = Node::new(init_args.to_vec(), args.span);
+                validate_arg_labels(
+                    scope,
+                    &init_args,
+                    &init_params,
+                    LabelPolicy::AllowAnyUnlabeled,
+                );
+                validate_arg_types(
+                    scope,
+                    &format!("{}.{}", &class_name, function.as_ref()),
+                    &init_args,
+                    &init_params,
+                )?;
+                for arg in init_args.kind.iter().skip(init_params.len()) {
+                    assignable_expr(scope, &arg.kind.value, None)?;
+                }
                 return Ok((
                     ExpressionAttributes::new(typ, Location::Value),
                     CallType::BuiltinAssociatedFunction { contract, function },
                 ));
             }
         }
+    }
+
+    for (index, arg) in args.kind.iter().enumerate() {
+        if !matches!(evaluated.get(index), Some(Some(_))) {
+            assignable_expr(scope, &arg.kind.value, None)?;
+        }
+    }
+
+    if let Some(class) = typ.as_class() {
+        let class_name = class.name(scope.db());
 
         if let Some(function) = class.function(scope.db(), &field.kind) {
             if function.takes_self(scope.db()) {
@@ -1821,6 +3152,123 @@ fn expr_call_type_attribute(
     )))
 }
 
+/// The type arguments of a call to a generic function or struct, along with
+/// the attributes of the arguments that were analyzed to infer them.
+///
+/// The type arguments are the generic arguments of the call if it has any.
+/// Otherwise, each one is inferred from the first argument whose declared type
+/// is the type parameter, e.g. `T` in `fn max<T: Numeric>(a: T, b: T) -> T`.
+/// Numeric literals are only used if no other argument has that type, so that
+/// in `max(x, 1)` the literal gets the type of `x`.
+fn generic_call_type_args(
+    scope: &mut BlockScope,
+    name: &str,
+    name_span: Span,
+    generic_params: &[Node<fe::GenericParameter>],
+    param_descs: &[Node<fe::TypeDesc>],
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(Vec<TypeDef>, Vec<Option<ExpressionAttributes>>), FatalError> {
+    let mut evaluated = vec![None; args.kind.len()];
+    if let Some(generic_args) = generic_args {
+        let type_args = resolve_type_args(scope, name, name_span, generic_params, generic_args)?;
+        return Ok((type_args, evaluated));
+    }
+
+    let mut inferred: Vec<Option<TypeDef>> = vec![None; generic_params.len()];
+    for literals in [false, true] {
+        for (index, (desc, arg)) in param_descs.iter().zip(args.kind.iter()).enumerate() {
+            let param_index = match &desc.kind {
+                fe::TypeDesc::Base { base } => generic_params
+                    .iter()
+                    .position(|param| param.kind.name.kind == *base),
+                _ => None,
+            };
+            let param_index = match param_index {
+                Some(param_index) if inferred[param_index].is_none() => param_index,
+                _ => continue,
+            };
+            let value = &arg.kind.value;
+            if is_numeric_literal(value) != literals {
+                continue;
+            }
+
+            let attrs = assignable_expr(scope, value, None)?;
+            let typ = TypeDef::from_type(&attrs.typ).ok_or_else(|| {
+                FatalError::new(scope.fancy_error(
+                    "invalid type argument",
+                    vec![Label::primary(
+                        value.span,
+                        format!("`{}` can't be used as a type argument", attrs.typ),
+                    )],
+                    vec![
+                        "Note: a type argument must be a primitive type, struct, enum or contract"
+                            .into(),
+                    ],
+                ))
+            })?;
+            check_type_bound(scope, &generic_params[param_index].kind, &typ, value.span)?;
+            inferred[param_index] = Some(typ);
+            evaluated[index] = Some(attrs);
+        }
+    }
+
+    let missing = generic_params
+        .iter()
+        .zip(inferred.iter())
+        .filter(|(_, typ)| typ.is_none())
+        .map(|(param, _)| format!("`{}`", param.kind.name.kind))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let params = generic_params
+            .iter()
+            .map(|param| param.kind.name.kind.to_string())
+            .collect::<Vec<_>>();
+        return Err(FatalError::new(scope.fancy_error(
+            &format!("can't infer the type arguments of `{}`", name),
+            vec![Label::primary(
+                name_span,
+                format!(
+                    "the type of {} can't be inferred from the arguments",
+                    missing.join(", ")
+                ),
+            )],
+            vec![format!(
+                "Hint: pass the type arguments explicitly, e.g. `{}<{}>(..)`",
+                name,
+                params.join(", ")
+            )],
+        )));
+    }
+
+    Ok((inferred.into_iter().flatten().collect(), evaluated))
+}
+
+/// The declared types of the parameters of a function, other than `self`.
+fn param_type_descs(db: &dyn AnalyzerDb, function: FunctionId) -> Vec<Node<fe::TypeDesc>> {
+    function
+        .data(db)
+        .ast
+        .kind
+        .args
+        .iter()
+        .filter_map(|arg| match &arg.kind {
+            fe::FunctionArg::Regular(arg) => Some(arg.typ.clone()),
+            fe::FunctionArg::Zelf => None,
+        })
+        .collect()
+}
+
+fn is_numeric_literal(expr: &Node<fe::Expr>) -> bool {
+    match &expr.kind {
+        fe::Expr::Num(_) => true,
+        fe::Expr::UnaryOperation { op, operand } => {
+            op.kind == UnaryOperator::USub && matches!(operand.kind, fe::Expr::Num(_))
+        }
+        _ => false,
+    }
+}
+
 fn expr_call_args(
     scope: &mut BlockScope,
     args: &Node<Vec<Node<fe::CallArg>>>,
@@ -1872,6 +3320,25 @@ fn check_for_call_to_special_fns(
     }
 }
 
+/// `fallback` and `receive` are only called by the ABI dispatcher of their
+/// contract, and have no selector of their own.
+fn check_for_call_to_dispatcher_fns(
+    scope: &mut BlockScope,
+    name: &str,
+    span: Span,
+) -> Result<(), FatalError> {
+    let label = match name {
+        "fallback" => "Note: `fallback` is called when the calldata doesn't match a public function, and can't be called directly.",
+        "receive" => "Note: `receive` is called when the calldata is empty, and can't be called directly.",
+        _ => return Ok(()),
+    };
+    Err(FatalError::new(scope.fancy_error(
+        &format!("`{}()` is not directly callable", name),
+        vec![Label::primary(span, "")],
+        vec![label.into()],
+    )))
+}
+
 fn validate_numeric_literal_fits_type(
     scope: &mut BlockScope,
     num: BigInt,
@@ -1914,8 +3381,13 @@ fn expr_comp_operation(
     exp: &Node<fe::Expr>,
 ) -> Result<ExpressionAttributes, FatalError> {
     if let fe::Expr::CompOperation { left, op, right } = &exp.kind {
+        let left_attr = expr(scope, left, None)?;
+        if let Type::String(_) = left_attr.typ {
+            return expr_comp_strings(scope, exp, left_attr);
+        }
+
         // comparison operands should be moved to the stack
-        let left_attr = value_expr(scope, left, None)?;
+        let left_attr = load_expr(scope, left, left_attr)?;
         let right_attr = value_expr(scope, right, Some(&left_attr.typ))?;
 
         if left_attr.typ != right_attr.typ {
@@ -1928,8 +3400,33 @@ fn expr_comp_operation(
                         format!("this has incompatible type `{}`", right_attr.typ),
                     ),
                 ],
-                vec![],
+                operand_cast_hint(
+                    left,
+                    &left_attr.typ,
+                    right,
+                    &right_attr.typ,
+                    |left, right| fe::Expr::CompOperation {
+                        left: Box::new(left),
+                        op: op.clone(),
+                        right: Box::new(right),
+                    },
+                )
+                .into_iter()
+                .collect(),
+            );
+        } else if matches!(left_attr.typ, Type::Enum(_))
+            && !matches!(op.kind, fe::CompOperator::Eq | fe::CompOperator::NotEq)
+        {
+            scope.fancy_error(
+                &format!("`{}` can't be used on enums", op.kind),
+                vec![Label::primary(
+                    exp.span,
+                    format!("this compares values of `{}`", left_attr.typ),
+                )],
+                vec!["Note: enum values can only be compared with `==` and `!=`".into()],
             );
+        } else {
+            lints::check_comparison(scope, exp);
         }
 
         // for now we assume these are the only possible attributes
@@ -1942,6 +3439,56 @@ fn expr_comp_operation(
     unreachable!()
 }
 
+/// Strings are compared by their content, which is in memory.
+fn expr_comp_strings(
+    scope: &mut BlockScope,
+    exp: &Node<fe::Expr>,
+    left_attr: ExpressionAttributes,
+) -> Result<ExpressionAttributes, FatalError> {
+    if let fe::Expr::CompOperation { left, op, right } = &exp.kind {
+        if left_attr.final_location() != Location::Memory {
+            scope.fancy_error(
+                "value must be copied to memory",
+                vec![Label::primary(left.span, "this value is in storage")],
+                vec!["Hint: values located in storage can be copied to memory using the `to_mem` function.".into(),
+                     "Example: `self.my_string.to_mem()`".into(),
+                ],
+            );
+        }
+        let right_attr = assignable_expr(scope, right, None)?;
+
+        if !matches!(right_attr.typ, Type::String(_)) {
+            scope.fancy_error(
+                &format!("`{}` operands must have the same type", op.kind),
+                vec![
+                    Label::primary(left.span, format!("this has type `{}`", left_attr.typ)),
+                    Label::secondary(
+                        right.span,
+                        format!("this has incompatible type `{}`", right_attr.typ),
+                    ),
+                ],
+                vec![],
+            );
+        } else if !matches!(op.kind, fe::CompOperator::Eq | fe::CompOperator::NotEq) {
+            scope.fancy_error(
+                &format!("`{}` can't be used on strings", op.kind),
+                vec![Label::primary(
+                    exp.span,
+                    format!("this compares `{}` and `{}`", left_attr.typ, right_attr.typ),
+                )],
+                vec!["Note: strings can only be compared with `==` and `!=`".into()],
+            );
+        }
+
+        return Ok(ExpressionAttributes::new(
+            Type::Base(Base::Bool),
+            Location::Value,
+        ));
+    }
+
+    unreachable!()
+}
+
 fn expr_ternary(
     scope: &mut BlockScope,
     exp: &Node<fe::Expr>,
@@ -4,7 +4,8 @@ use crate::namespace::items::Item;
 use crate::namespace::scopes::{BlockScope, BlockScopeType};
 use crate::namespace::types::{Base, FixedSize, Type};
 use crate::traversal::call_args::LabelPolicy;
-use crate::traversal::{assignments, call_args, declarations, expressions};
+use crate::traversal::{assignments, call_args, declarations, expressions, patterns, yul};
+use fe_common::diagnostics::Label;
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 
@@ -29,7 +30,9 @@ fn func_stmt(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fa
         For { .. } => for_loop(scope, stmt),
         While { .. } => while_loop(scope, stmt),
         If { .. } => if_statement(scope, stmt),
+        Match { .. } => match_statement(scope, stmt),
         Unsafe { .. } => unsafe_block(scope, stmt),
+        Yul(_) => yul::yul_block(scope, stmt),
         Assert { .. } => assert(scope, stmt),
         Expr { value } => expressions::expr(scope, value, None).map(|_| ()),
         Pass => Ok(()),
@@ -111,6 +114,65 @@ fn if_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
     }
 }
 
+fn match_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    match &stmt.kind {
+        fe::FuncStmt::Match { value, arms } => {
+            let value_type = expressions::assignable_expr(scope, value, None)?.typ;
+            let value_type = match FixedSize::try_from(value_type) {
+                Ok(typ) => typ,
+                Err(_) => {
+                    return Err(FatalError::new(scope.error(
+                        "invalid `match` value",
+                        value.span,
+                        "`Map` type values can't be matched",
+                    )))
+                }
+            };
+
+            for arm in arms {
+                let mut arm_scope = scope.new_child(BlockScopeType::IfElse);
+                patterns::pattern(&mut arm_scope, &arm.kind.pat, &value_type)?;
+
+                if let Some(guard) = &arm.kind.guard {
+                    let guard_type = expressions::value_expr(&mut arm_scope, guard, None)?.typ;
+                    if guard_type != Type::Base(Base::Bool) {
+                        arm_scope.type_error(
+                            "`match` arm guard is not bool",
+                            guard.span,
+                            &Base::Bool,
+                            &guard_type,
+                        );
+                    }
+                }
+
+                traverse_statements(&mut arm_scope, &arm.kind.body)?;
+            }
+
+            let missing = patterns::missing_patterns(scope.db(), &value_type, arms);
+            if !missing.is_empty() {
+                let missing = missing
+                    .iter()
+                    .map(|pat| format!("`{}`", pat))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                scope.fancy_error(
+                    "non-exhaustive `match`",
+                    vec![Label::primary(
+                        value.span,
+                        format!("not covered: {}", missing),
+                    )],
+                    vec![
+                        "Note: add an arm for each pattern that isn't covered, or a `_` arm that matches any value"
+                            .into(),
+                    ],
+                );
+            }
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
 fn unsafe_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     match &stmt.kind {
         fe::FuncStmt::Unsafe(body) => {
@@ -242,6 +304,10 @@ fn func_return(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
 
         let attributes = match value {
             Some(val) => expressions::assignable_expr(scope, val, Some(&expected_type))?,
+            // A bare `return` returns the named return values, if there are any.
+            None if scope.root.function.has_named_outputs(scope.db()) => {
+                ExpressionAttributes::new(expected_type.clone(), Location::Value)
+            }
             None => ExpressionAttributes::new(Type::unit(), Location::Value),
         };
 
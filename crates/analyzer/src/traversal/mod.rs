@@ -3,6 +3,8 @@ mod call_args;
 mod declarations;
 mod expressions;
 pub mod functions;
+mod patterns;
 pub mod pragma;
 pub mod types;
 mod utils;
+mod yul;
@@ -0,0 +1,184 @@
+use crate::context::AnalyzerContext;
+use crate::errors::FatalError;
+use crate::namespace::scopes::BlockScope;
+use crate::namespace::types::{Base, FixedSize, Type};
+use crate::traversal::expressions;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
+use fe_common::utils::humanize::pluralize_conditionally;
+use fe_parser::ast as fe;
+use fe_parser::node::Node;
+
+/// Check that a `match` arm pattern can match a value of the given type, and
+/// add the names it binds to the scope.
+pub fn pattern(
+    scope: &mut BlockScope,
+    pat: &Node<fe::Pattern>,
+    typ: &FixedSize,
+) -> Result<(), FatalError> {
+    scope.root.add_pattern(pat, typ.clone());
+
+    match &pat.kind {
+        fe::Pattern::Wildcard => Ok(()),
+        fe::Pattern::Binding(name) => {
+            // this logs a message on err, so it's safe to ignore here.
+            let _ = scope.add_var(name, typ.clone(), pat.span);
+            Ok(())
+        }
+        fe::Pattern::Literal(value) => {
+            if !matches!(typ, FixedSize::Base(_)) {
+                return Err(FatalError::new(scope.error(
+                    "invalid literal pattern",
+                    pat.span,
+                    &format!(
+                        "values of type `{}` can't be matched against a literal",
+                        typ
+                    ),
+                )));
+            }
+            let expected = Type::from(typ.clone());
+            let value_type = expressions::value_expr(scope, value, Some(&expected))?.typ;
+            if value_type != expected {
+                scope.type_error("type mismatch", value.span, typ, &value_type);
+            }
+            Ok(())
+        }
+        fe::Pattern::Path(path) => {
+            let path_type = match expressions::expr_enum_variant(scope, path)? {
+                Some(attributes) => attributes.typ,
+                None => {
+                    return Err(FatalError::new(scope.error(
+                        "invalid path pattern",
+                        pat.span,
+                        &format!("`{}` is not an enum variant", path),
+                    )))
+                }
+            };
+            let expected = Type::from(typ.clone());
+            if path_type != expected {
+                scope.type_error("type mismatch", pat.span, typ, &path_type);
+            }
+            Ok(())
+        }
+        fe::Pattern::Tuple(items) => {
+            let items_ty = match typ {
+                FixedSize::Tuple(tuple) => tuple.items.as_vec().clone(),
+                _ => {
+                    return Err(FatalError::new(scope.type_error(
+                        "invalid tuple pattern",
+                        pat.span,
+                        &"a tuple",
+                        typ,
+                    )))
+                }
+            };
+            if items.len() != items_ty.len() {
+                return Err(FatalError::new(scope.fancy_error(
+                    "invalid tuple pattern",
+                    vec![Label::primary(pat.span, "")],
+                    vec![format!(
+                        "Tuple pattern has {} {} but the matched tuple type has {} {}",
+                        items.len(),
+                        pluralize_conditionally("item", items.len()),
+                        items_ty.len(),
+                        pluralize_conditionally("item", items_ty.len()),
+                    )],
+                )));
+            }
+            for (item, item_ty) in items.iter().zip(items_ty.iter()) {
+                pattern(scope, item, item_ty)?;
+            }
+            Ok(())
+        }
+        fe::Pattern::Struct { name, fields } => {
+            let struct_ = match typ {
+                FixedSize::Struct(struct_) if struct_.name == name.kind => struct_.clone(),
+                _ => {
+                    return Err(FatalError::new(scope.type_error(
+                        "invalid struct pattern",
+                        name.span,
+                        typ,
+                        &name.kind,
+                    )))
+                }
+            };
+            for field in fields {
+                let label = &field.kind.label;
+                let struct_field = match struct_.id.field(scope.db(), &label.kind) {
+                    Some(struct_field) => struct_field,
+                    None => {
+                        return Err(FatalError::new(scope.fancy_error(
+                            &format!(
+                                "No field `{}` exists on struct `{}`",
+                                &label.kind, struct_.name
+                            ),
+                            vec![Label::primary(label.span, "undefined field")],
+                            vec![],
+                        )))
+                    }
+                };
+                if !scope.root_item().is_struct(&struct_.id) && !struct_field.is_public(scope.db())
+                {
+                    scope.fancy_error(
+                        &format!(
+                            "Can not access private field `{}` on struct `{}`",
+                            &label.kind, struct_.name
+                        ),
+                        vec![Label::primary(label.span, "private field")],
+                        vec![],
+                    );
+                }
+                let field_type = struct_field.typ(scope.db())?;
+                pattern(scope, &field.kind.pat, &field_type)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Returns the patterns that are needed for the arms to match every value of
+/// the given type, or an empty vec if they already do.
+///
+/// Arms with a guard might not match, so they're ignored. The variants of an
+/// enum and the values of a bool can be listed one by one; any other type
+/// needs an arm that matches everything, like `_`.
+pub fn missing_patterns(
+    db: &dyn AnalyzerDb,
+    typ: &FixedSize,
+    arms: &[Node<fe::MatchArm>],
+) -> Vec<String> {
+    let patterns = arms
+        .iter()
+        .filter(|arm| arm.kind.guard.is_none())
+        .map(|arm| &arm.kind.pat.kind)
+        .collect::<Vec<_>>();
+    if patterns.iter().any(|pat| pat.is_irrefutable()) {
+        return vec![];
+    }
+
+    match typ {
+        FixedSize::Enum(enum_) => enum_
+            .id
+            .variants(db)
+            .keys()
+            .filter(|variant| {
+                !patterns.iter().any(|pat| {
+                    matches!(pat, fe::Pattern::Path(path)
+                        if path.segments.last().map(|segment| &segment.kind) == Some(*variant))
+                })
+            })
+            .map(|variant| format!("{}::{}", enum_.name, variant))
+            .collect(),
+        FixedSize::Base(Base::Bool) => [true, false]
+            .into_iter()
+            .filter(|value| {
+                !patterns.iter().any(|pat| {
+                    matches!(pat, fe::Pattern::Literal(Node { kind: fe::Expr::Bool(literal), .. })
+                        if literal == value)
+                })
+            })
+            .map(|value| value.to_string())
+            .collect(),
+        _ => vec!["_".to_string()],
+    }
+}
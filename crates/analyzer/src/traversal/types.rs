@@ -1,13 +1,19 @@
 use crate::context::{AnalyzerContext, NamedThing};
 use crate::errors::TypeError;
-use crate::namespace::items::Item;
-use crate::namespace::types::{FixedSize, GenericArg, GenericParamKind, GenericType, Tuple, Type};
+use crate::namespace::items::{Item, ModuleId, StructId, TypeDef};
+use crate::namespace::types::{
+    FixedSize, GenericArg, GenericParamKind, GenericType, Tuple, Type, TypeBound,
+};
 use crate::traversal::call_args::validate_arg_count;
 use fe_common::diagnostics::Label;
 use fe_common::utils::humanize::pluralize_conditionally;
 use fe_common::Spanned;
 use fe_parser::ast;
 use fe_parser::node::{Node, Span};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 use vec1::Vec1;
 
 pub fn apply_generic_type_args(
@@ -16,7 +22,7 @@ pub fn apply_generic_type_args(
     name_span: Span,
     args: Option<&Node<Vec<ast::GenericArg>>>,
 ) -> Result<Type, TypeError> {
-    let params = generic.params();
+    let mut params = generic.params();
 
     let args = args.ok_or_else(|| {
         TypeError::new(context.fancy_error(
@@ -37,6 +43,11 @@ pub fn apply_generic_type_args(
         ))
     })?;
 
+    // An `Array` without a size is a dynamically-sized array.
+    if generic == GenericType::Array && args.kind.len() == 1 {
+        params.truncate(1);
+    }
+
     if let Some(diag) = validate_arg_count(
         context,
         &generic.name(),
@@ -108,6 +119,177 @@ fn friendly_generic_arg_example_string(generic: GenericType) -> String {
     format!("Example: `{}<{}>`", generic.name(), example_args.join(", "))
 }
 
+/// Checks the type parameters declared by a generic function or struct. Their
+/// names must be unique and must not clash with the items of the module, and
+/// their bounds must be known.
+pub fn validate_generic_params(
+    context: &mut dyn AnalyzerContext,
+    module: ModuleId,
+    owner_name: &str,
+    params: &[Node<ast::GenericParameter>],
+) {
+    let mut names: HashMap<&SmolStr, Span> = HashMap::new();
+    for param in params {
+        let name = &param.kind.name;
+        if let Some(item) = module.resolve_name(context.db(), &name.kind) {
+            let named_thing = NamedThing::Item(item);
+            let original_span = named_thing.name_span(context.db());
+            context.name_conflict_error(
+                "type parameter",
+                &name.kind,
+                &named_thing,
+                original_span,
+                name.span,
+            );
+        } else if let Some(original) = names.get(&name.kind) {
+            context.duplicate_name_error(
+                &format!("duplicate type parameter names in `{}`", owner_name),
+                &name.kind,
+                *original,
+                name.span,
+            );
+        } else {
+            names.insert(&name.kind, name.span);
+        }
+
+        if let Some(bound) = &param.kind.bound {
+            if TypeBound::from_str(&bound.kind).is_err() {
+                let known = TypeBound::iter()
+                    .map(|bound| format!("`{}`", bound.as_ref()))
+                    .collect::<Vec<_>>();
+                context.fancy_error(
+                    &format!("unknown type bound `{}`", bound.kind),
+                    vec![Label::primary(bound.span, "undefined bound")],
+                    vec![format!("Note: the known bounds are {}", known.join(", "))],
+                );
+            }
+        }
+    }
+}
+
+/// Resolves the type arguments of a generic function or struct, e.g. the `u8`
+/// in `max<u8>(x, y)`, and checks them against the bounds of its type
+/// parameters.
+pub fn resolve_type_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    name_span: Span,
+    params: &[Node<ast::GenericParameter>],
+    args: &Node<Vec<ast::GenericArg>>,
+) -> Result<Vec<TypeDef>, TypeError> {
+    if let Some(diag) = validate_arg_count(
+        context,
+        name,
+        name_span,
+        args,
+        params.len(),
+        "generic argument",
+    ) {
+        return Err(TypeError::new(diag));
+    }
+
+    params
+        .iter()
+        .zip(args.kind.iter())
+        .map(|(param, arg)| match arg {
+            ast::GenericArg::TypeDesc(type_node) => {
+                let typ = type_desc(context, type_node)?;
+                let type_def = TypeDef::from_type(&typ).ok_or_else(|| {
+                    TypeError::new(context.fancy_error(
+                        "invalid type argument",
+                        vec![Label::primary(
+                            type_node.span,
+                            format!("`{}` can't be used as a type argument", typ),
+                        )],
+                        vec![
+                            "Note: a type argument must be a primitive type, struct, enum or contract"
+                                .into(),
+                        ],
+                    ))
+                })?;
+                check_type_bound(context, &param.kind, &type_def, type_node.span)?;
+                Ok(type_def)
+            }
+            ast::GenericArg::Int(_) => Err(TypeError::new(context.fancy_error(
+                &format!("`{}` {} must be a type", name, param.kind.name.kind),
+                vec![Label::primary(arg.span(), "expected a type name")],
+                vec![],
+            ))),
+        })
+        .collect()
+}
+
+/// Checks that a type argument satisfies the bound of its type parameter, if
+/// it has one. Unknown bounds are reported where the parameter is declared.
+pub fn check_type_bound(
+    context: &mut dyn AnalyzerContext,
+    param: &ast::GenericParameter,
+    typ: &TypeDef,
+    span: Span,
+) -> Result<(), TypeError> {
+    let bound = match param
+        .bound
+        .as_ref()
+        .and_then(|bound| TypeBound::from_str(&bound.kind).ok())
+    {
+        Some(bound) => bound,
+        None => return Ok(()),
+    };
+    if bound.is_satisfied_by(typ) {
+        return Ok(());
+    }
+
+    let type_name = typ.name(context.db());
+    Err(TypeError::new(context.error(
+        &format!(
+            "`{}` doesn't satisfy the bound `{}: {}`",
+            type_name,
+            param.name.kind,
+            bound.as_ref()
+        ),
+        span,
+        &format!("expected a `{}` type", bound.as_ref()),
+    )))
+}
+
+/// Applies the type arguments of a generic struct, e.g. `Pair<u8>`, which
+/// yields the instance of the struct for those arguments.
+fn apply_struct_type_args(
+    context: &mut dyn AnalyzerContext,
+    struct_: StructId,
+    name_span: Span,
+    args: Option<&Node<Vec<ast::GenericArg>>>,
+) -> Result<Type, TypeError> {
+    let name = struct_.name(context.db());
+    let params = struct_.generic_params(context.db());
+
+    let args = args.ok_or_else(|| {
+        TypeError::new(context.fancy_error(
+            &format!(
+                "missing generic {} for type `{}`",
+                pluralize_conditionally("argument", params.len()),
+                name
+            ),
+            vec![Label::primary(
+                name_span,
+                &format!(
+                    "expected {} generic {}",
+                    params.len(),
+                    pluralize_conditionally("argument", params.len())
+                ),
+            )],
+            vec![format!(
+                "Example: `{}<{}>`",
+                name,
+                vec!["u256"; params.len()].join(", ")
+            )],
+        ))
+    })?;
+
+    let type_args = resolve_type_args(context, &name, name_span, &params, args)?;
+    TypeDef::Struct(struct_.instantiate(context.db(), type_args)).typ(context.db())
+}
+
 pub fn resolve_concrete_type_name<T: std::fmt::Display>(
     context: &mut dyn AnalyzerContext,
     name: &str,
@@ -135,6 +317,11 @@ pub fn resolve_concrete_type_named_thing<T: std::fmt::Display>(
     generic_args: Option<&Node<Vec<ast::GenericArg>>>,
 ) -> Result<Type, TypeError> {
     match named_thing {
+        Some(NamedThing::Item(Item::Type(TypeDef::Struct(struct_))))
+            if struct_.is_generic(context.db()) =>
+        {
+            apply_struct_type_args(context, struct_, base_desc.span, generic_args)
+        }
         Some(NamedThing::Item(Item::Type(id))) => {
             if let Some(args) = generic_args {
                 context.fancy_error(
@@ -1,27 +1,33 @@
 use fe_common::diagnostics::Label;
-use fe_common::Span;
 
 use crate::context::{AnalyzerContext, DiagnosticVoucher};
 use crate::errors::{BinaryOperationError, NotFixedSize};
-use crate::namespace::types::{FixedSize, Type};
-use std::fmt::Display;
+use crate::namespace::types::{Base, FixedSize, Integer, Type};
+use crate::operations;
+use fe_parser::ast as fe;
+use fe_parser::node::Node;
 
 pub fn types_to_fixed_sizes(sizes: &[Type]) -> Result<Vec<FixedSize>, NotFixedSize> {
     sizes.iter().map(|param| param.clone().try_into()).collect()
 }
 
+/// Reports a [`BinaryOperationError`]. If `assign` is true, the operation is
+/// an augmented assignment to `left`.
+#[allow(clippy::too_many_arguments)]
 pub fn add_bin_operations_errors(
     context: &mut dyn AnalyzerContext,
-    op: &dyn Display,
-    left_span: Span,
+    op: &Node<fe::BinOperator>,
+    left: &Node<fe::Expr>,
     left_type: &Type,
-    right_span: Span,
+    right: &Node<fe::Expr>,
     right_type: &Type,
     error: BinaryOperationError,
+    assign: bool,
 ) -> DiagnosticVoucher {
+    let (left_span, right_span) = (left.span, right.span);
     match error {
         BinaryOperationError::NotEqualAndUnsigned => context.fancy_error(
-            &format!("`{}` operand types must be equal and unsigned", op),
+            &format!("`{}` operand types must be equal and unsigned", op.kind),
             vec![
                 Label::primary(left_span, &format!("this has type `{}`", left_type)),
                 Label::primary(right_span, &format!("this has type `{}`", right_type)),
@@ -31,7 +37,7 @@ pub fn add_bin_operations_errors(
         BinaryOperationError::RightIsSigned => context.fancy_error(
             &format!(
                 "The right hand side of the `{}` operation must be unsigned",
-                op
+                op.kind
             ),
             vec![Label::primary(
                 right_span,
@@ -39,27 +45,54 @@ pub fn add_bin_operations_errors(
             )],
             vec![],
         ),
-        BinaryOperationError::RightTooLarge => context.fancy_error(
-            &format!("incompatible `{}` operand types", op),
-            vec![
-                Label::primary(left_span, &format!("this has type `{}`", left_type)),
-                Label::primary(right_span, &format!("this has type `{}`", right_type)),
-            ],
-            vec![format!(
+        BinaryOperationError::RightTooLarge => {
+            let mut notes = vec![format!(
                 "The type of the right hand side cannot be larger than the left (`{}`)",
                 left_type
-            )],
-        ),
-        BinaryOperationError::TypesNotEqual => context.fancy_error(
-            &format!("`{}` operand types must be equal", op),
-            vec![
-                Label::primary(left_span, &format!("this has type `{}`", left_type)),
-                Label::primary(right_span, &format!("this has type `{}`", right_type)),
-            ],
-            vec![],
-        ),
+            )];
+            // The left hand side of an augmented assignment can't be cast.
+            if let (
+                false,
+                Type::Base(Base::Numeric(left_int)),
+                Type::Base(Base::Numeric(right_int)),
+            ) = (assign, left_type, right_type)
+            {
+                let larger = Integer::from_parts(left_int.is_signed(), right_int.size())
+                    .expect("no integer of the same size");
+                notes.push(format!(
+                    "Hint: cast the left hand side to `{}`: `{}`",
+                    larger,
+                    bin_operation(cast(left, *left_int, larger), op, right.clone())
+                ));
+            }
+            context.fancy_error(
+                &format!("incompatible `{}` operand types", op.kind),
+                vec![
+                    Label::primary(left_span, &format!("this has type `{}`", left_type)),
+                    Label::primary(right_span, &format!("this has type `{}`", right_type)),
+                ],
+                notes,
+            )
+        }
+        BinaryOperationError::TypesNotEqual => {
+            let hint = if assign {
+                assign_cast_hint(op, left, left_type, right, right_type)
+            } else {
+                operand_cast_hint(left, left_type, right, right_type, |left, right| {
+                    bin_operation(left, op, right)
+                })
+            };
+            context.fancy_error(
+                &format!("`{}` operand types must be equal", op.kind),
+                vec![
+                    Label::primary(left_span, &format!("this has type `{}`", left_type)),
+                    Label::primary(right_span, &format!("this has type `{}`", right_type)),
+                ],
+                hint.into_iter().collect(),
+            )
+        }
         BinaryOperationError::TypesNotNumeric => context.fancy_error(
-            &format!("`{}` operands must be numeric", op),
+            &format!("`{}` operands must be numeric", op.kind),
             vec![
                 Label::primary(left_span, &format!("this has type `{}`", left_type)),
                 Label::primary(right_span, &format!("this has type `{}`", right_type)),
@@ -68,3 +101,112 @@ pub fn add_bin_operations_errors(
         ),
     }
 }
+
+/// Suggests casts that give the integer operands of a binary or comparison
+/// operation the same type, i.e. their [`operations::common_integer`].
+/// `rebuild` creates the operation from the cast operands.
+pub fn operand_cast_hint(
+    left: &Node<fe::Expr>,
+    left_type: &Type,
+    right: &Node<fe::Expr>,
+    right_type: &Type,
+    rebuild: impl FnOnce(Node<fe::Expr>, Node<fe::Expr>) -> fe::Expr,
+) -> Option<String> {
+    let (left_int, right_int) = match (left_type, right_type) {
+        (Type::Base(Base::Numeric(left)), Type::Base(Base::Numeric(right))) => (*left, *right),
+        _ => return None,
+    };
+
+    Some(match operations::common_integer(left_int, right_int) {
+        Some(common) => format!(
+            "Hint: integers aren't converted implicitly; cast the operands to `{}`: `{}`",
+            common,
+            rebuild(
+                cast(left, left_int, common),
+                cast(right, right_int, common)
+            )
+        ),
+        None => format!(
+            "Hint: no integer type can hold all values of both `{}` and `{}`; cast one operand, and make sure its values fit the other type",
+            left_int, right_int
+        ),
+    })
+}
+
+/// Suggests a cast of the value of an augmented assignment to the type of
+/// its target, which can't be changed.
+fn assign_cast_hint(
+    op: &Node<fe::BinOperator>,
+    target: &Node<fe::Expr>,
+    target_type: &Type,
+    value: &Node<fe::Expr>,
+    value_type: &Type,
+) -> Option<String> {
+    let (target_int, value_int) = match (target_type, value_type) {
+        (Type::Base(Base::Numeric(target)), Type::Base(Base::Numeric(value))) => (*target, *value),
+        _ => return None,
+    };
+
+    let caveat = if operations::common_integer(target_int, value_int) == Some(target_int) {
+        ""
+    } else {
+        ", which changes values that don't fit"
+    };
+    Some(format!(
+        "Hint: cast the right hand side to `{}`{}: `{} {}= {}`",
+        target_int,
+        caveat,
+        target.kind,
+        op.kind,
+        cast(value, value_int, target_int).kind
+    ))
+}
+
+fn bin_operation(
+    left: Node<fe::Expr>,
+    op: &Node<fe::BinOperator>,
+    right: Node<fe::Expr>,
+) -> fe::Expr {
+    fe::Expr::BinOperation {
+        left: Box::new(left),
+        op: op.clone(),
+        right: Box::new(right),
+    }
+}
+
+/// Wraps `expr` in the casts that convert it from `from` to `to`. A cast can
+/// change either the size or the sign of an integer, so the size is changed
+/// first.
+fn cast(expr: &Node<fe::Expr>, from: Integer, to: Integer) -> Node<fe::Expr> {
+    let resized =
+        Integer::from_parts(from.is_signed(), to.size()).expect("no integer of the same size");
+    let mut expr = expr.clone();
+    if resized != from {
+        expr = type_call(resized, expr);
+    }
+    if to != resized {
+        expr = type_call(to, expr);
+    }
+    expr
+}
+
+fn type_call(typ: Integer, arg: Node<fe::Expr>) -> Node<fe::Expr> {
+    let span = arg.span;
+    Node::new(
+        fe::Expr::Call {
+            func: Box::new(Node::new(fe::Expr::Name(typ.as_ref().into()), span)),
+            generic_args: None,
+            args: Node::new(
+                vec![Node::new(
+                    fe::CallArg {
+                        label: None,
+                        value: arg,
+                    },
+                    span,
+                )],
+                span,
+            ),
+        },
+        span,
+    )
+}
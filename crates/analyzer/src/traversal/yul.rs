@@ -0,0 +1,249 @@
+use crate::builtins::Intrinsic;
+use crate::context::{AnalyzerContext, NamedThing};
+use crate::errors::FatalError;
+use crate::namespace::scopes::{BlockScope, BlockScopeType};
+use crate::namespace::types::Base;
+use crate::traversal::call_args::validate_arg_count;
+use fe_common::diagnostics::Label;
+use fe_parser::ast as fe;
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+use std::str::FromStr;
+
+/// Checks an inline Yul block. Yul has a single type, so only the names, the
+/// function calls and the number of values of each expression are checked.
+pub fn yul_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    match &stmt.kind {
+        fe::FuncStmt::Yul(body) => {
+            if !scope.inherits_type(BlockScopeType::Unsafe) {
+                scope.fancy_error(
+                    "inline Yul can only be used in an unsafe function or block",
+                    vec![Label::primary(stmt.span, "inline Yul")],
+                    vec!["Hint: put this in an `unsafe` block if you're confident that it's safe to use here".into()],
+                );
+            }
+            let mut checker = YulChecker {
+                scope,
+                locals: vec![],
+                loop_depth: 0,
+            };
+            checker.block(body);
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+struct YulChecker<'s, 'a, 'b> {
+    scope: &'s mut BlockScope<'a, 'b>,
+    /// The variables defined in the Yul block, by Yul scope.
+    locals: Vec<Vec<SmolStr>>,
+    loop_depth: usize,
+}
+
+impl YulChecker<'_, '_, '_> {
+    fn block(&mut self, body: &[Node<fe::YulStmt>]) {
+        self.locals.push(vec![]);
+        self.stmts(body);
+        self.locals.pop();
+    }
+
+    fn stmts(&mut self, body: &[Node<fe::YulStmt>]) {
+        for stmt in body {
+            self.stmt(stmt)
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<fe::YulStmt>) {
+        match &stmt.kind {
+            fe::YulStmt::Block(body) => self.block(body),
+            fe::YulStmt::Let { names, value } => {
+                if let Some(value) = value {
+                    self.expr(value, names.len());
+                }
+                for name in names {
+                    self.define(name);
+                }
+            }
+            fe::YulStmt::Assign { names, value } => {
+                self.expr(value, names.len());
+                for name in names {
+                    self.name(name, &name.kind);
+                }
+            }
+            fe::YulStmt::If { test, body } => {
+                self.expr(test, 1);
+                self.block(body);
+            }
+            fe::YulStmt::Switch { value, cases } => {
+                self.expr(value, 1);
+                for case in cases {
+                    self.block(&case.kind.body);
+                }
+            }
+            fe::YulStmt::For {
+                init,
+                test,
+                post,
+                body,
+            } => {
+                // The variables defined in `init` are visible in the rest of
+                // the loop.
+                self.locals.push(vec![]);
+                self.stmts(init);
+                self.expr(test, 1);
+                self.block(post);
+                self.loop_depth += 1;
+                self.block(body);
+                self.loop_depth -= 1;
+                self.locals.pop();
+            }
+            fe::YulStmt::Break | fe::YulStmt::Continue => {
+                if self.loop_depth == 0 {
+                    let word = if matches!(stmt.kind, fe::YulStmt::Break) {
+                        "break"
+                    } else {
+                        "continue"
+                    };
+                    self.scope.error(
+                        &format!("`{}` outside of a Yul `for` loop", word),
+                        stmt.span,
+                        "not in a loop",
+                    );
+                }
+            }
+            fe::YulStmt::Expr(value) => self.expr(value, 0),
+        }
+    }
+
+    /// Checks an expression that should evaluate to `values` values.
+    fn expr(&mut self, expr: &Node<fe::YulExpr>, values: usize) {
+        let found = match &expr.kind {
+            fe::YulExpr::Literal(_) => 1,
+            fe::YulExpr::Name(name) => {
+                self.name(expr, name);
+                1
+            }
+            fe::YulExpr::Call { func, args } => {
+                for arg in &args.kind {
+                    self.expr(arg, 1);
+                }
+                match Intrinsic::from_str(&format!("__{}", func.kind)) {
+                    Ok(intrinsic) => {
+                        validate_arg_count(
+                            &mut *self.scope,
+                            &func.kind,
+                            func.span,
+                            args,
+                            intrinsic.arg_count(),
+                            "argument",
+                        );
+                        if intrinsic.return_type() == Base::Unit {
+                            0
+                        } else {
+                            1
+                        }
+                    }
+                    Err(_) => {
+                        self.scope.error(
+                            &format!("cannot find Yul function `{}`", func.kind),
+                            func.span,
+                            "undefined",
+                        );
+                        return;
+                    }
+                }
+            }
+        };
+
+        if found == values {
+            return;
+        }
+        if values == 0 {
+            self.scope.fancy_error(
+                "unused value in inline Yul",
+                vec![Label::primary(expr.span, "this value is unused")],
+                vec!["Hint: discard the value with `pop`".into()],
+            );
+        } else if found == 0 {
+            self.scope.error(
+                "expected a value in inline Yul",
+                expr.span,
+                "this doesn't return a value",
+            );
+        } else {
+            self.scope.error(
+                &format!("expected {} values in inline Yul, found 1", values),
+                expr.span,
+                "this is a single value",
+            );
+        }
+    }
+
+    fn define(&mut self, name: &Node<SmolStr>) {
+        if Intrinsic::from_str(&format!("__{}", name.kind)).is_ok() {
+            self.scope.error(
+                &format!(
+                    "`{}` is a Yul function, and can't be a variable name",
+                    name.kind
+                ),
+                name.span,
+                "expected a variable name",
+            );
+        } else if self.is_local(&name.kind)
+            || matches!(
+                self.scope.resolve_name(&name.kind),
+                Some(NamedThing::Variable { .. })
+            )
+        {
+            // Yul doesn't allow shadowing, and a Yul variable that shadows a
+            // Fe variable would make the Fe variable unreachable.
+            self.scope.error(
+                &format!("duplicate definition of variable `{}`", name.kind),
+                name.span,
+                "already defined",
+            );
+        } else {
+            self.locals
+                .last_mut()
+                .expect("missing Yul scope")
+                .push(name.kind.clone());
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals.iter().flatten().any(|local| local == name)
+    }
+
+    /// Checks that a name refers to a Yul variable, or to a Fe variable or
+    /// parameter.
+    fn name<T>(&mut self, node: &Node<T>, name: &SmolStr) {
+        if self.is_local(name) {
+            return;
+        }
+        match self.scope.resolve_name(name) {
+            Some(NamedThing::Variable { span, .. }) => self.scope.root.add_variable_ref(node, span),
+            Some(NamedThing::SelfValue { .. }) => {
+                self.scope.error(
+                    "`self` can't be used in inline Yul",
+                    node.span,
+                    "not allowed here",
+                );
+            }
+            Some(NamedThing::Item(_)) => {
+                self.scope.fancy_error(
+                    &format!("`{}` can't be used in inline Yul", name),
+                    vec![Label::primary(node.span, "not a variable")],
+                    vec!["Note: only Fe variables and parameters can be used in inline Yul".into()],
+                );
+            }
+            None => {
+                self.scope.error(
+                    &format!("cannot find value `{}` in this scope", name),
+                    node.span,
+                    "undefined",
+                );
+            }
+        }
+    }
+}
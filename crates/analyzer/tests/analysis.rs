@@ -276,8 +276,12 @@ fn build_snapshot(file_store: &FileStore, module: items::ModuleId, db: &dyn Anal
             Item::Constant(id) => vec![build_display_diagnostic(id.span(db), &id.typ(db).unwrap())],
 
 
+            // Enum variants have no types of their own.
+            Item::Type(TypeDef::Enum(_))
             // Events can't be defined at the module level yet.
-            Item::Event(_)
+            | Item::Event(_)
+            // The functions of a trait are checked in its implementations.
+            | Item::Trait(_)
             // Built-in stuff
             | Item::Type(TypeDef::Primitive(_))
             | Item::GenericType(_)
@@ -296,6 +300,7 @@ fn build_snapshot(file_store: &FileStore, module: items::ModuleId, db: &dyn Anal
 fn new_diagnostic(labels: Vec<Label>) -> Diagnostic {
     Diagnostic {
         severity: Severity::Note,
+        code: None,
         message: String::new(),
         labels: labels.to_vec(),
         notes: vec![],
@@ -408,6 +413,7 @@ fn build_debug_diagnostic<T: Debug>(span: Span, attributes: &T) -> Diagnostic {
     let label = Label::primary(span, format!("attributes hash: {}", hash(attributes)));
     Diagnostic {
         severity: Severity::Note,
+        code: None,
         message: String::new(),
         labels: vec![label],
         notes: vec![format!("{:#?}", attributes)],
@@ -418,6 +424,7 @@ fn build_display_diagnostic<T: Display>(span: Span, attributes: &T) -> Diagnosti
     let label = Label::primary(span, format!("{}", attributes));
     Diagnostic {
         severity: Severity::Note,
+        code: None,
         message: String::new(),
         labels: vec![label],
         notes: vec![],
@@ -25,6 +25,21 @@ fn error_string(path: &str, src: &str) -> String {
     }
 }
 
+/// The diagnostics of loading a module, e.g. of the ABIs that it imports,
+/// which are found before the module is analyzed.
+fn load_error_string(path: &str, src: &str) -> String {
+    let mut files = test_files::new_filestore();
+    let id = files.add_file(path, src);
+    let deps = files.add_included_libraries();
+
+    let db = TestDb::default();
+
+    let diagnostics = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .diagnostics;
+    diagnostics_string(&diagnostics, &files)
+}
+
 fn error_string_ingot(path: &str) -> String {
     let mut files = build_filestore(path);
     let file_ids = files.all_files();
@@ -105,6 +120,25 @@ test_stmt! { array_non_primitive, "let x: Array<(u8, u8), 10>" }
 test_stmt! { array_mixed_types, "let x: Array<u16, 3> = [1, address(0), \"hi\"]" }
 test_stmt! { array_size_mismatch, "let x: Array<u8, 3> = []\nlet y: Array<u8, 3> = [1, 2]" }
 test_stmt! { array_constructor_call, "u8[3]([1, 2, 3])" }
+test_stmt! { slice_non_constant_bound, "let a: Array<u8, 4> = [1, 2, 3, 4]\nlet n: u256 = 1\na[n:]" }
+test_stmt! { slice_out_of_bounds, "let a: Array<u8, 4> = [1, 2, 3, 4]\na[2:5]" }
+test_stmt! { slice_not_sliceable, "let a: u256 = 1\na[0:1]" }
+test_stmt! { slice_empty_fixed_bytes, "let a: bytes4 = bytes4(1)\na[2:2]" }
+test_stmt! { array_method_on_non_array, "let x: u256 = 1\nx.contains(1)" }
+test_stmt! { array_index_of_type_mismatch, "let a: Array<u8, 2> = [1, 2]\na.index_of(true)" }
+test_stmt! { array_copy_from_size_mismatch, "let a: Array<u8, 2> = [1, 2]\nlet b: Array<u8, 3> = [1, 2, 3]\na.copy_from(b)" }
+test_stmt! { array_sort_non_array, "let x: u256 = 1\nx.sort()" }
+test_stmt! { hash_non_struct, "let x: u256 = 1\nx.hash()" }
+test_stmt! { dynamic_array_local, "let x: Array<u256>" }
+test_stmt! { dynamic_array_method_on_non_array, "let x: u256 = 1\nx.push(1)" }
+test_stmt! { raw_sstore_outside_unsafe, "raw_sstore(0, 1)" }
+test_stmt! { raw_call_without_type, "let data: Array<u8, 1> = [0]\nraw_call(address(0), data, 0, 0)" }
+test_stmt! {
+    delegatecall_outside_unsafe,
+    "let data: Array<u8, 1> = [0]\nlet (success, output): (bool, Array<u8, 1>) = delegatecall(address(0), data, 0)"
+}
+test_stmt! { inline_yul_outside_unsafe, "let x: u256 = 1\nyul { x := add(x, 1) }" }
+test_stmt! { inline_yul_errors, "unsafe:\n yul { pop(z) add(1, 2) }" }
 test_stmt! { assert_reason_not_string, "assert true, 1" }
 test_stmt! { assign_int, "5 = 6" }
 test_stmt! { assign_call, "self.f() = 10" }
@@ -114,6 +148,9 @@ test_stmt! { binary_op_add_uints, "let a: u256 = 1\nlet b: u8 = 2\na + b" }
 test_stmt! { binary_op_lshift_bool, "let a: bool = true\nlet b: i256\na << b" }
 test_stmt! { binary_op_lshift_with_int, "let a: u256 = 1\nlet b: i256 = 2\na << b" }
 test_stmt! { binary_op_pow_int, "let a: u256 = 1\nlet b: i256 = 2\na ** b" }
+test_stmt! { binary_op_mixed_sign, "let a: u8 = 1\nlet b: i8 = 2\na * b" }
+test_stmt! { binary_op_no_common_type, "let a: u256 = 1\nlet b: i8 = 2\na - b" }
+test_stmt! { binary_op_bit_and_mixed_widths, "let a: u16 = 1\nlet b: u256 = 2\na & b" }
 test_stmt! { binary_op_boolean_mismatch1, "10 and true" }
 test_stmt! { binary_op_boolean_mismatch2, "false or 1" }
 test_stmt! { binary_op_boolean_mismatch3, "1 or 2" }
@@ -133,6 +170,7 @@ test_stmt! { call_balance_of_without_parameter, "balance_of()" }
 test_stmt! { call_balance_of_with_wrong_type, "balance_of(true)" }
 test_stmt! { call_balance_of_with_2_args, "balance_of(address(0), 2)" }
 test_stmt! { call_balance_of_with_generic_args, "balance_of<10>(address(0))" }
+test_stmt! { call_dbg_with_wrong_type, "dbg(\"hi\")" }
 test_stmt! { call_balance_with_arg, "balance(address(0))" }
 test_stmt! { call_balance_with_generic_args, "balance<10>()" }
 test_stmt! { call_send_value_without_parameter, "send_value()" }
@@ -196,6 +234,8 @@ test_stmt! { shadow_builtin_fn_with_var, "let keccak256: u8 = 10" }
 test_file! { shadow_builtin_type }
 test_file! { shadow_builtin_function }
 test_file! { self_misuse }
+test_stmt! { string_concat_non_string, "\"a\".concat(1)" }
+test_stmt! { string_lt, "\"a\" < \"b\"" }
 test_stmt! { string_capacity_mismatch, "String<3>(\"too long\")" }
 test_stmt! { string_non_int_type_arg, "let x: String<u8>" }
 test_stmt! { string_no_type_arg_list, "let x: String" }
@@ -211,6 +251,9 @@ test_stmt! { ternary_type_mismatch, "10 if 100 else true" }
 test_stmt! { change_sign_and_size_in_cast, "let x: i8\nlet y: u16 = u16(x)" }
 test_stmt! { change_sign_and_type_in_cast, "let x: bool\nlet y: u16 = u16(x)" }
 test_stmt! { type_constructor_arg_count, "let x: u8 = u8(1, 10)" }
+test_stmt! { fixed_bytes_from_large_int, "let a: u64 = 1\nbytes4(a)" }
+test_stmt! { fixed_bytes_to_small_int, "let a: bytes4 = bytes4(1)\nu16(a)" }
+test_stmt! { fixed_bytes_assign_index, "let a: bytes4 = bytes4(1)\na[0] = 2" }
 test_stmt! { unary_minus_on_bool, "let x: bool = true\n-x" }
 test_stmt! { unary_not_on_int, "let x: u256 = 10\nnot x" }
 test_stmt! { undefined_generic_type, "let x: foobar<u256> = 10" }
@@ -228,6 +271,7 @@ test_file! { bad_tuple_attr3 }
 test_file! { call_builtin_object }
 test_file! { call_create_with_wrong_type }
 test_file! { call_create2_with_wrong_type }
+test_file! { call_create_with_wrong_init_args }
 test_file! { call_event_with_wrong_types }
 test_file! { call_undefined_function_on_external_contract }
 test_file! { call_undefined_function_on_memory_struct }
@@ -238,6 +282,8 @@ test_file! { cannot_move2 }
 test_file! { circular_dependency_create }
 test_file! { circular_dependency_create2 }
 test_file! { circular_type_alias }
+test_file! { recursive_constants }
+test_file! { recursive_struct }
 test_file! { duplicate_arg_in_contract_method }
 test_file! { duplicate_contract_in_module }
 test_file! { duplicate_event_in_contract }
@@ -276,7 +322,24 @@ test_file! { module_const_unknown_type }
 test_file! { module_const_non_base_type }
 test_file! { module_const_not_literal }
 test_file! { module_const_call }
+test_file! { const_fn_misuse }
+test_file! { const_keccak_misuse }
+test_file! { contract_const_misuse }
 test_file! { needs_mem_copy }
+test_file! { sort_storage_array }
+test_file! { hash_struct_with_array }
+test_file! { raw_storage_in_pure_fn }
+test_file! { facet_layout_mismatch }
+test_file! { facet_layout_not_struct }
+test_file! { facet_selector_clash }
+test_file! { upgradeable_with_init }
+test_file! { upgradeable_missing_initialize }
+test_file! { multicall_reads_msg_value }
+test_file! { ownable_member_conflict }
+test_file! { pausable_without_ownable }
+test_file! { only_role_without_roles }
+test_file! { test_fn_misuse }
+test_file! { named_outputs_misuse }
 test_file! { not_callable }
 test_file! { not_in_scope }
 test_file! { not_in_scope_2 }
@@ -300,6 +363,7 @@ test_file! { init_wrong_return_type }
 test_file! { init_duplicate_def }
 test_file! { init_call_on_self }
 test_file! { init_call_on_external_contract }
+test_file! { fallback_receive_misuse }
 test_file! { call_wrong_return_type }
 test_file! { call_duplicate_def }
 test_file! { call_call_on_self }
@@ -318,6 +382,28 @@ test_file! { self_not_first }
 test_file! { self_in_standalone_fn }
 test_file! { unsafe_misuse }
 test_file! { unsafe_nesting }
+test_file! { enum_duplicate_variant }
+test_file! { enum_misuse }
+test_file! { match_enum_pattern_misuse }
+test_file! { match_non_exhaustive }
+test_file! { interface_misuse }
+test_file! { generics_misuse }
+test_file! { traits_misuse }
+
+#[test]
+#[wasm_bindgen_test]
+fn abi_import() {
+    let path = "compile_errors/abi_import.fe";
+    let src = test_files::fixture(path);
+    if cfg!(target_arch = "wasm32") {
+        fe_common::assert_snapshot_wasm!(
+            "snapshots/errors__abi_import.snap",
+            load_error_string(path, src)
+        );
+    } else {
+        assert_snapshot!(load_error_string(path, src));
+    }
+}
 
 test_ingot! { bad_ingot }
 test_ingot! { mainless_ingot }
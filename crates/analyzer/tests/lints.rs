@@ -0,0 +1,238 @@
+//! Tests for code that compiles, but should cause warnings
+
+use fe_analyzer::chains::ChainProfile;
+use fe_analyzer::lints::{Lint, LintLevel};
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::TestDb;
+use fe_common::diagnostics::{diagnostics_string, print_diagnostics, Severity};
+use fe_common::files::FileStore;
+use indexmap::indexmap;
+use insta::assert_snapshot;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn analyze(src: &str) -> (Vec<fe_common::diagnostics::Diagnostic>, FileStore) {
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+
+    let db = TestDb::default();
+
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+
+    match fe_analyzer::analyze_module(&db, module_id) {
+        Ok(warnings) => (warnings, files),
+        Err(diags) => {
+            print_diagnostics(&diags, &files);
+            panic!("analysis failed")
+        }
+    }
+}
+
+fn snippet(stmt: &str) -> String {
+    format!(
+        "contract C:\n pub fn f(self):\n  {}",
+        stmt.replace('\n', "\n  ")
+    )
+}
+
+fn warning_string(src: &str) -> String {
+    let (warnings, files) = analyze(src);
+    if warnings.is_empty() {
+        panic!("expected analysis to emit a warning")
+    }
+    diagnostics_string(&warnings, &files)
+}
+
+macro_rules! test_stmt {
+    ($name:ident, $stmt:expr) => {
+        #[test]
+        #[wasm_bindgen_test]
+        fn $name() {
+            let src = snippet($stmt);
+            if cfg!(target_arch = "wasm32") {
+                fe_common::assert_snapshot_wasm!(
+                    concat!("snapshots/lints__", stringify!($name), ".snap"),
+                    warning_string(&src)
+                );
+            } else {
+                assert_snapshot!(warning_string(&src));
+            }
+        }
+    };
+}
+
+test_stmt! { strict_balance_equality, "assert balance() == 0" }
+test_stmt! { timestamp_equality, "assert block.timestamp != 100" }
+test_stmt! { block_randomness, "let winner: u256 = block.number % 10" }
+
+#[test]
+#[wasm_bindgen_test]
+fn power_of_two_modulo() {
+    let (warnings, _) = analyze(&snippet("let time: u256 = block.timestamp % 2**32"));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn unused_symbols() {
+    let src = "contract C:
+    pub fn f(self, unused_param: u256):
+        let used: u256 = 1
+        let unused: u256 = used
+        let _ignored: u256 = 2
+        self.called()
+
+    fn called(self):
+        pass
+
+    fn never_called(self):
+        pass
+";
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+    let db = TestDb::default();
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+    fe_analyzer::analyze_module(&db, module_id).expect("analysis failed");
+
+    let warnings = fe_analyzer::lints::check_unused(&db, module_id)
+        .into_iter()
+        .map(|warning| (warning.code.unwrap(), warning.message))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                "unused-variable".to_string(),
+                "unused variable `unused`".to_string()
+            ),
+            (
+                "unused-function".to_string(),
+                "function `never_called` is never called".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn allow_comments() {
+    let src = "contract C:
+    #allow(unused-variable, unused-function)
+    fn allowed(self):
+        let unused_in_allowed: u256 = 1
+
+    pub fn f(self):
+        let unused_on_line: u256 = 1  #allow(unused-variable)
+        let unused: u256 = 2
+        #allow(unused-varible)
+        let misspelled: u256 = 3
+";
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+    let db = TestDb::default();
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+    fe_analyzer::analyze_module(&db, module_id).expect("analysis failed");
+
+    let warnings = fe_analyzer::lints::remove_allowed(
+        &files,
+        fe_analyzer::lints::check_unused(&db, module_id),
+    )
+    .into_iter()
+    .map(|warning| warning.message)
+    .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            "unused variable `unused`".to_string(),
+            "unused variable `misspelled`".to_string(),
+            "unknown lint `unused-varible`".to_string(),
+        ]
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lint_levels() {
+    let (warnings, _) = analyze(&snippet(
+        "assert block.timestamp != 100\nlet winner: u256 = block.number % 10\nassert balance() == 0",
+    ));
+    let levels = indexmap! {
+        Lint::TimestampEquality => LintLevel::Allow,
+        Lint::BlockRandomness => LintLevel::Deny,
+        Lint::StrictBalanceEquality => LintLevel::Warn,
+    };
+
+    let diagnostics = fe_analyzer::lints::apply_levels(warnings, &levels)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.code.unwrap(), diagnostic.severity))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        diagnostics,
+        vec![
+            ("block-randomness".to_string(), Severity::Error),
+            ("strict-balance-equality".to_string(), Severity::Warning),
+        ]
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn chain_profiles() {
+    let src = "contract C:
+    pub fn f(self) -> u256:
+        unsafe:
+            if block.difficulty == 0:
+                __selfdestruct(0)
+        return block.number
+";
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+    let db = TestDb::default();
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+    fe_analyzer::analyze_module(&db, module_id).expect("analysis failed");
+
+    let diagnostics = |chain| {
+        fe_analyzer::chains::check(&db, module_id, chain)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(diagnostics(ChainProfile::Ethereum), vec![]);
+    assert_eq!(
+        diagnostics(ChainProfile::Arbitrum),
+        vec![
+            (
+                Severity::Warning,
+                "`block.difficulty` has a different meaning on arbitrum".to_string()
+            ),
+            (
+                Severity::Warning,
+                "`block.number` has a different meaning on arbitrum".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        diagnostics(ChainProfile::ZkEvm),
+        vec![
+            (
+                Severity::Error,
+                "`__selfdestruct` isn't supported on zkevm".to_string()
+            ),
+            (
+                Severity::Warning,
+                "`block.difficulty` has a different meaning on zkevm".to_string()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+//! Tests for the reference index
+
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::references::{find_references, SymbolKind};
+use fe_analyzer::TestDb;
+use fe_common::files::FileStore;
+use fe_common::Span;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const SRC: &str = "contract C:
+    pub fn f(self, flag: bool) -> u256:
+        if flag:
+            let x: u256 = self.double(1)
+            return x + x
+        else:
+            let x: u256 = 2
+            return self.double(x)
+
+    fn double(self, value: u256) -> u256:
+        return value * 2
+";
+
+/// Finds the references to the symbol at the `nth` occurrence of `at` in
+/// `src`, and returns the symbol's kind and the text following each reference.
+fn references_at(src: &str, at: &str, nth: usize) -> Option<(SymbolKind, Vec<String>)> {
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+
+    let db = TestDb::default();
+
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+    fe_analyzer::analyze_module(&db, module_id).expect("analysis failed");
+
+    let (start, _) = src.match_indices(at).nth(nth).expect("name not found");
+    let symbol = find_references(&db, module_id, Span::new(id, start, start))?;
+    let references = symbol
+        .references
+        .iter()
+        .map(|span| {
+            assert_eq!(&src[span.start..span.end], symbol.name.as_str());
+            src[span.start..].lines().next().unwrap().to_string()
+        })
+        .collect();
+    Some((symbol.kind, references))
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn variable_references() {
+    // the second `x` in `x + x`; the `x` in the other branch is a different
+    // variable
+    assert_eq!(
+        references_at(SRC, "x + x", 0),
+        Some((SymbolKind::Variable, vec!["x + x".into(), "x".into()]))
+    );
+    assert_eq!(
+        references_at(SRC, "x: u256 = 2", 0),
+        Some((SymbolKind::Variable, vec!["x)".into()]))
+    );
+    assert_eq!(
+        references_at(SRC, "value", 0),
+        Some((SymbolKind::Parameter, vec!["value * 2".into()]))
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn function_references() {
+    assert_eq!(
+        references_at(SRC, "double", 2),
+        Some((
+            SymbolKind::Function,
+            vec!["double(1)".into(), "double(x)".into()]
+        ))
+    );
+    assert_eq!(references_at(SRC, "u256", 0), None);
+}
@@ -0,0 +1,115 @@
+//! Tests for renaming variables and function parameters
+
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::rename::{rename, RenameError};
+use fe_analyzer::TestDb;
+use fe_common::files::FileStore;
+use fe_common::Span;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Renames the variable whose definition starts at the first occurrence of
+/// `at` in `src`, and returns the edited source.
+fn rename_at(src: &str, at: &str, new_name: &str) -> Result<String, RenameError> {
+    let mut files = FileStore::new();
+    let id = files.add_file("[snippet]", src);
+    let deps = files.add_included_libraries();
+
+    let db = TestDb::default();
+
+    let module_id = ModuleId::try_new(&db, &files, id, &deps)
+        .expect("failed to create new module")
+        .value;
+    fe_analyzer::analyze_module(&db, module_id).expect("analysis failed");
+
+    let start = src.find(at).expect("definition not found");
+    let edits = rename(&db, module_id, Span::new(id, start, start), new_name)?;
+
+    let mut renamed = src.to_string();
+    for edit in edits.iter().rev() {
+        renamed.replace_range(edit.span.start..edit.span.end, &edit.new_text);
+    }
+    Ok(renamed)
+}
+
+const BRANCHES: &str = "contract C:
+    pub fn f(self, flag: bool) -> u256:
+        if flag:
+            let x: u256 = 1
+            return x + 1
+        else:
+            let x: u256 = 2
+            return x
+";
+
+#[test]
+#[wasm_bindgen_test]
+fn rename_variable_in_sibling_scope() {
+    pretty_assertions::assert_eq!(
+        rename_at(BRANCHES, "x: u256 = 1", "y").unwrap(),
+        "contract C:
+    pub fn f(self, flag: bool) -> u256:
+        if flag:
+            let y: u256 = 1
+            return y + 1
+        else:
+            let x: u256 = 2
+            return x
+"
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn rename_parameter() {
+    let src = "struct Point:
+    pub x: u256
+    pub y: u256
+
+contract C:
+    fn make(x: u256, y: u256) -> Point:
+        return Point(x, y)
+
+    pub fn f(self) -> u256:
+        let p: Point = make(x=1, y=2)
+        return p.x
+";
+    pretty_assertions::assert_eq!(
+        rename_at(src, "x: u256, y", "a").unwrap(),
+        "struct Point:
+    pub x: u256
+    pub y: u256
+
+contract C:
+    fn make(a: u256, y: u256) -> Point:
+        return Point(x=a, y)
+
+    pub fn f(self) -> u256:
+        let p: Point = make(a=1, y=2)
+        return p.x
+"
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn rename_errors() {
+    let flag = BRANCHES.find("flag").unwrap();
+    match rename_at(BRANCHES, "x: u256 = 1", "flag") {
+        Err(RenameError::Conflict {
+            name,
+            span: Some(span),
+        }) => {
+            assert_eq!(name, "flag");
+            assert_eq!((span.start, span.end), (flag, flag + "flag".len()));
+        }
+        other => panic!("expected a conflict with `flag`, got {:?}", other),
+    }
+    assert_eq!(
+        rename_at(BRANCHES, "x: u256 = 1", "fn"),
+        Err(RenameError::InvalidName("fn".to_string()))
+    );
+    assert_eq!(
+        rename_at(BRANCHES, "f(self", "g"),
+        Err(RenameError::NotAVariable)
+    );
+}
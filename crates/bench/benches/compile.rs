@@ -0,0 +1,91 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use fe_analyzer::namespace::items::ModuleId;
+use fe_common::files::FileStore;
+use fe_yulgen::Db;
+
+fn compile_phases(c: &mut Criterion) {
+    for path in fe_bench::FIXTURES {
+        let src = test_files::fixture(path);
+        let mut files = FileStore::new();
+        let deps = files.add_included_libraries();
+        let file_id = files.add_file(path, src);
+
+        // Each phase is measured with a fresh database, so that salsa can't reuse
+        // results computed by an earlier iteration.
+        let parsed = || {
+            let db = Db::default();
+            let module = ModuleId::try_new(&db, &files, file_id, &deps)
+                .expect("failed to parse fixture")
+                .value;
+            (db, module)
+        };
+        let analyzed = || {
+            let (db, module) = parsed();
+            fe_analyzer::analyze_module(&db, module).expect("failed to analyze fixture");
+            (db, module)
+        };
+        let lowered = || {
+            let (db, module) = analyzed();
+            let lowered = fe_lowering::lower_module(&db, module);
+            fe_analyzer::analyze_module(&db, lowered).expect("failed to analyze lowered fixture");
+            (db, lowered)
+        };
+
+        let mut group = c.benchmark_group(*path);
+        group.bench_function("parse", |b| {
+            b.iter(|| fe_parser::parse_file(file_id, src).expect("failed to parse fixture"))
+        });
+        group.bench_function("analyze", |b| {
+            b.iter_batched(
+                parsed,
+                |(db, module)| {
+                    fe_analyzer::analyze_module(&db, module).expect("failed to analyze fixture");
+                    db
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function("lower", |b| {
+            b.iter_batched(
+                analyzed,
+                |(db, module)| {
+                    let lowered = fe_lowering::lower_module(&db, module);
+                    fe_analyzer::analyze_module(&db, lowered)
+                        .expect("failed to analyze lowered fixture");
+                    db
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function("yulgen", |b| {
+            b.iter_batched(
+                lowered,
+                |(db, lowered)| {
+                    fe_yulgen::compile(&db, lowered);
+                    db
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function("total", |b| {
+            b.iter(|| {
+                fe_driver::compile_module(
+                    &files,
+                    file_id,
+                    &deps,
+                    &fe_driver::CompileOptions {
+                        with_bytecode: false,
+                        with_runtime_bytecode: false,
+                        optimize: false,
+                        ..fe_driver::CompileOptions::default()
+                    },
+                )
+                .expect("failed to compile fixture")
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, compile_phases);
+criterion_main!(benches);
@@ -0,0 +1,182 @@
+//! Reports the gas used to deploy the demo contracts and to call their most
+//! important functions, and the gas used by arithmetic on small integer types.
+//! The numbers only include execution gas; the intrinsic cost of a transaction
+//! isn't counted.
+
+use fe_compiler_test_utils::*;
+
+/// Runs `f`, and returns its result along with the gas it used.
+fn measure<T>(executor: &mut Executor, f: impl FnOnce(&mut Executor) -> T) -> (T, u64) {
+    let before = executor.used_gas();
+    let value = f(executor);
+    (value, executor.used_gas() - before)
+}
+
+fn report(name: &str, gas: u64) {
+    println!("{:<40} {:>10}", name, gas);
+}
+
+fn erc20(executor: &mut Executor) {
+    let bob = address_token("2000000000000000000000000000000000000002");
+
+    let (mut token, gas) = measure(executor, |executor| {
+        deploy_contract(
+            executor,
+            "demos/erc20_token.fe",
+            "ERC20",
+            &[string_token("Fe Coin"), string_token("fe")],
+        )
+    });
+    report("ERC20: deploy", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(executor, "transfer", &[bob.clone(), uint_token(1000)])
+    });
+    report("ERC20: transfer", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(executor, "approve", &[bob.clone(), uint_token(500)])
+    });
+    report("ERC20: approve", gas);
+
+    token.set_caller(bob.clone().into_address().unwrap());
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(
+            executor,
+            "transferFrom",
+            &[address_token(DEFAULT_CALLER), bob.clone(), uint_token(500)],
+        )
+    });
+    report("ERC20: transferFrom", gas);
+}
+
+fn erc721(executor: &mut Executor) {
+    let alice = address_token(DEFAULT_CALLER);
+    let bob = address_token("2000000000000000000000000000000000000002");
+
+    let (mut token, gas) = measure(executor, |executor| {
+        deploy_contract(executor, "demos/erc721_token.fe", "ERC721", &[])
+    });
+    report("ERC721: deploy", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(executor, "mint", &[alice.clone(), uint_token(1)])
+    });
+    report("ERC721: mint", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(executor, "approve", &[bob.clone(), uint_token(1)])
+    });
+    report("ERC721: approve", gas);
+
+    token.set_caller(bob.clone().into_address().unwrap());
+    let (_, gas) = measure(executor, |executor| {
+        token.call_function(
+            executor,
+            "transferFrom",
+            &[alice.clone(), bob.clone(), uint_token(1)],
+        )
+    });
+    report("ERC721: transferFrom", gas);
+}
+
+fn uniswap(executor: &mut Executor) {
+    let alice = address_token(DEFAULT_CALLER);
+
+    let token0 = deploy_contract(
+        executor,
+        "demos/erc20_token.fe",
+        "ERC20",
+        &[string_token("Fe Coin"), string_token("fe")],
+    );
+    let token1 = deploy_contract(
+        executor,
+        "demos/erc20_token.fe",
+        "ERC20",
+        &[string_token("Maker"), string_token("mkr")],
+    );
+    let token0_address = ethabi::Token::Address(token0.address);
+    let token1_address = ethabi::Token::Address(token1.address);
+
+    let (factory, gas) = measure(executor, |executor| {
+        deploy_contract(
+            executor,
+            "demos/uniswap.fe",
+            "UniswapV2Factory",
+            &[address_token("0")],
+        )
+    });
+    report("UniswapV2Factory: deploy", gas);
+
+    let (pair_address, gas) = measure(executor, |executor| {
+        factory
+            .call_function(executor, "create_pair", &[token0_address, token1_address])
+            .expect("factory did not return a pair")
+    });
+    report("UniswapV2Factory: create_pair", gas);
+    let pair = load_contract(
+        pair_address.clone().into_address().expect("not an address"),
+        "demos/uniswap.fe",
+        "UniswapV2Pair",
+    );
+
+    token0.call_function(
+        executor,
+        "transfer",
+        &[
+            pair_address.clone(),
+            uint_token_from_dec_str("200000000000000000000"),
+        ],
+    );
+    token1.call_function(
+        executor,
+        "transfer",
+        &[
+            pair_address.clone(),
+            uint_token_from_dec_str("100000000000000000000"),
+        ],
+    );
+    let (_, gas) = measure(executor, |executor| {
+        pair.call_function(executor, "mint", &[alice.clone()])
+    });
+    report("UniswapV2Pair: mint", gas);
+
+    token1.call_function(executor, "transfer", &[pair_address, uint_token(1000)]);
+    let (_, gas) = measure(executor, |executor| {
+        pair.call_function(
+            executor,
+            "swap",
+            &[uint_token(1993), uint_token(0), alice.clone()],
+        )
+    });
+    report("UniswapV2Pair: swap", gas);
+}
+
+fn small_ints(executor: &mut Executor) {
+    let (harness, gas) = measure(executor, |executor| {
+        deploy_contract(executor, "stress/small_int_math.fe", "SmallIntMath", &[])
+    });
+    report("SmallIntMath: deploy", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        harness.call_function(executor, "checksum", &[uint_token(40)])
+    });
+    report("SmallIntMath: checksum", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        harness.call_function(executor, "walk", &[int_token(100), int_token(-3)])
+    });
+    report("SmallIntMath: walk", gas);
+
+    let (_, gas) = measure(executor, |executor| {
+        harness.call_function(executor, "mix", &[uint_token(300), int_token(-5)])
+    });
+    report("SmallIntMath: mix", gas);
+}
+
+fn main() {
+    println!("{:<40} {:>10}", "", "gas used");
+    for bench in [erc20, erc721, uniswap, small_ints] {
+        with_executor(&|mut executor| bench(&mut executor));
+    }
+}
@@ -0,0 +1,15 @@
+//! Benchmarks for the Fe compiler, run with `cargo bench -p fe-bench`.
+//!
+//! The `compile` benchmark measures the time spent in each phase of the
+//! compiler, and the `gas` benchmark (which requires the `solc-backend`
+//! feature) reports the gas used to deploy the demo contracts and to call their
+//! most important functions, as well as the gas used by checked arithmetic on
+//! small integer types.
+
+/// The fixtures compiled by the `compile` benchmark: an ERC-20 token, an
+/// ERC-721 token, and a Uniswap-style AMM.
+pub const FIXTURES: &[&str] = &[
+    "demos/erc20_token.fe",
+    "demos/erc721_token.fe",
+    "demos/uniswap.fe",
+];
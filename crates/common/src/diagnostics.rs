@@ -1,22 +1,53 @@
+#[cfg(feature = "std")]
 use crate::files::{FileStore, SourceFileId};
 use crate::Span;
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
 pub use codespan_reporting::diagnostic as cs;
+#[cfg(feature = "std")]
 use codespan_reporting::term;
-pub use cs::Severity;
+#[cfg(feature = "std")]
 use term::termcolor::{BufferWriter, ColorChoice};
 
+/// The severity of a [`Diagnostic`], from least to most severe. This mirrors
+/// [`codespan_reporting::diagnostic::Severity`], which isn't available without
+/// the `std` feature.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Severity {
+    Help,
+    Note,
+    Warning,
+    Error,
+    Bug,
+}
+#[cfg(feature = "std")]
+impl From<Severity> for cs::Severity {
+    fn from(other: Severity) -> cs::Severity {
+        match other {
+            Severity::Help => cs::Severity::Help,
+            Severity::Note => cs::Severity::Note,
+            Severity::Warning => cs::Severity::Warning,
+            Severity::Error => cs::Severity::Error,
+            Severity::Bug => cs::Severity::Bug,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Diagnostic {
     pub severity: Severity,
+    /// An identifier for the kind of diagnostic, e.g. the id of a lint.
+    pub code: Option<String>,
     pub message: String,
     pub labels: Vec<Label>,
     pub notes: Vec<String>,
 }
+#[cfg(feature = "std")]
 impl Diagnostic {
     pub fn into_cs(self) -> cs::Diagnostic<SourceFileId> {
         cs::Diagnostic {
-            severity: self.severity,
-            code: None,
+            severity: self.severity.into(),
+            code: self.code,
             message: self.message,
             labels: self
                 .labels
@@ -33,6 +64,7 @@ pub enum LabelStyle {
     Primary,
     Secondary,
 }
+#[cfg(feature = "std")]
 impl From<LabelStyle> for cs::LabelStyle {
     fn from(other: LabelStyle) -> cs::LabelStyle {
         match other {
@@ -70,6 +102,7 @@ impl Label {
     }
 
     /// Convert into a [`codespan_reporting::Diagnostic::Label`]
+    #[cfg(feature = "std")]
     pub fn into_cs_label(self) -> cs::Label<SourceFileId> {
         cs::Label {
             style: self.style.into(),
@@ -81,27 +114,42 @@ impl Label {
 }
 
 /// Print the given diagnostics to stderr.
+#[cfg(feature = "std")]
 pub fn print_diagnostics(diagnostics: &[Diagnostic], files: &FileStore) {
     let writer = BufferWriter::stderr(ColorChoice::Auto);
     let mut buffer = writer.buffer();
     let config = term::Config::default();
 
     for diag in diagnostics {
-        term::emit(&mut buffer, &config, files, &diag.clone().into_cs()).unwrap();
+        term::emit(&mut buffer, &config, files, &char_aligned_cs(diag, files)).unwrap();
     }
     // If we use `writer` here, the output won't be captured by rust's test system.
     eprintln!("{}", std::str::from_utf8(buffer.as_slice()).unwrap());
 }
 
 /// Format the given diagnostics as a string.
+#[cfg(feature = "std")]
 pub fn diagnostics_string(diagnostics: &[Diagnostic], files: &FileStore) -> String {
     let writer = BufferWriter::stderr(ColorChoice::Never);
     let mut buffer = writer.buffer();
     let config = term::Config::default();
 
     for diag in diagnostics {
-        term::emit(&mut buffer, &config, files, &diag.clone().into_cs())
+        term::emit(&mut buffer, &config, files, &char_aligned_cs(diag, files))
             .expect("failed to emit diagnostic");
     }
     std::str::from_utf8(buffer.as_slice()).unwrap().to_string()
 }
+
+/// Converts the diagnostic for `codespan_reporting`, which slices the source
+/// with the spans of the labels and so needs them to fall on characters.
+#[cfg(feature = "std")]
+fn char_aligned_cs(diag: &Diagnostic, files: &FileStore) -> cs::Diagnostic<SourceFileId> {
+    let mut diag = diag.clone();
+    for label in diag.labels.iter_mut() {
+        if let Some(file) = files.get_file(label.span.file_id) {
+            label.span = file.char_aligned_span(label.span);
+        }
+    }
+    diag.into_cs()
+}
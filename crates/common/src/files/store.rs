@@ -0,0 +1,351 @@
+use super::SourceFileId;
+use crate::utils::keccak;
+use crate::Span;
+use codespan_reporting as cs;
+use cs::files::Error as CsError;
+use include_dir::Dir;
+use indexmap::indexmap;
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+#[derive(PartialEq, Clone, Eq, Hash, Debug)]
+pub struct SourceFile {
+    pub id: SourceFileId,
+    pub name: String,
+    pub content: String,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(name: &str, content: &str) -> Self {
+        let hash = keccak::full_as_bytes(content.as_bytes());
+        let line_starts = cs::files::line_starts(content).collect();
+        Self {
+            id: SourceFileId(u128::from_be_bytes(hash[..16].try_into().unwrap())),
+            name: name.to_string(),
+            content: content.to_string(),
+            line_starts,
+        }
+    }
+
+    pub fn line_index(&self, byte_index: usize) -> usize {
+        self.line_starts
+            .binary_search(&byte_index)
+            .unwrap_or_else(|next_line| next_line - 1)
+    }
+
+    pub fn line_span(&self, line_index: usize) -> Option<Span> {
+        let end = if line_index == self.line_starts.len() - 1 {
+            self.content.len()
+        } else {
+            *self.line_starts.get(line_index + 1)?
+        };
+        Some(Span::new(self.id, *self.line_starts.get(line_index)?, end))
+    }
+
+    /// The zero-based line and column of a byte offset, with the column
+    /// counted in `unit`. An offset inside a multi-byte character is the
+    /// position of that character, and an offset past the end of the file is
+    /// the end of the file.
+    pub fn line_column(&self, byte_index: usize, unit: ColumnUnit) -> (usize, usize) {
+        let byte_index = self.floor_char_boundary(byte_index);
+        let line = self.line_index(byte_index);
+        let line_start = self.line_starts[line];
+        let text = &self.content[line_start..byte_index];
+        let column = match unit {
+            ColumnUnit::Byte => text.len(),
+            ColumnUnit::Char => text.chars().count(),
+            ColumnUnit::Utf16 => text.encode_utf16().count(),
+        };
+        (line, column)
+    }
+
+    /// The byte offset of a zero-based line and column, with the column
+    /// counted in `unit`. This is the inverse of [`SourceFile::line_column`].
+    /// A column past the end of the line is the end of the line. Returns
+    /// `None` if there's no such line.
+    pub fn byte_index(&self, line: usize, column: usize, unit: ColumnUnit) -> Option<usize> {
+        let span = self.line_span(line)?;
+        let text = &self.content[span.start..span.end];
+        let mut counted = 0;
+        for (index, char) in text.char_indices() {
+            if counted >= column || char == '\n' {
+                return Some(span.start + index);
+            }
+            counted += match unit {
+                ColumnUnit::Byte => char.len_utf8(),
+                ColumnUnit::Char => 1,
+                ColumnUnit::Utf16 => char.len_utf16(),
+            };
+        }
+        Some(span.end)
+    }
+
+    /// The span widened to the characters that it overlaps, so that it can
+    /// be used to slice the file's contents. Spans of the compiler are always
+    /// aligned, but this keeps a bad span from panicking when it's reported.
+    pub fn char_aligned_span(&self, span: Span) -> Span {
+        let mut end = span.end.min(self.content.len());
+        while !self.content.is_char_boundary(end) {
+            end += 1;
+        }
+        Span::new(span.file_id, self.floor_char_boundary(span.start), end)
+    }
+
+    fn floor_char_boundary(&self, byte_index: usize) -> usize {
+        let mut index = byte_index.min(self.content.len());
+        while !self.content.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    }
+}
+
+/// The unit that the column of a position in a file is counted in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnUnit {
+    Byte,
+    /// Unicode scalar values, i.e. `char`s, which is what diagnostics report.
+    Char,
+    /// UTF-16 code units, which the Language Server Protocol uses.
+    Utf16,
+}
+
+pub trait FileLoader {
+    fn load_file(&self, path: &Path) -> io::Result<String>;
+}
+
+pub struct OsFileLoader;
+
+impl FileLoader for OsFileLoader {
+    fn load_file(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+}
+
+/// The source files of a compilation.
+///
+/// Files are added either with their contents, or by path with
+/// [`FileStore::load_file`], which reads a path once and returns the same file
+/// until the path's contents are replaced. The contents of a path can be given
+/// in memory with [`FileStore::set_overlay`], e.g. the unsaved contents of an
+/// editor's document or the sources of a standard JSON input, and they're used
+/// in place of the loader for the path, whether or not it exists on disk.
+pub struct FileStore {
+    pub files: HashMap<SourceFileId, SourceFile>,
+    /// The files that were loaded by path.
+    paths: HashMap<PathBuf, SourceFileId>,
+    overlays: HashMap<PathBuf, String>,
+    loader: Box<dyn FileLoader>,
+}
+
+impl FileStore {
+    pub fn new() -> Self {
+        Self::with_loader(Box::new(OsFileLoader))
+    }
+
+    pub fn with_loader(loader: Box<dyn FileLoader>) -> Self {
+        Self {
+            files: HashMap::new(),
+            paths: HashMap::new(),
+            overlays: HashMap::new(),
+            loader,
+        }
+    }
+
+    pub fn add_file(&mut self, path: &str, content: &str) -> SourceFileId {
+        let file = SourceFile::new(path, content);
+        let id = file.id;
+        self.files.insert(id, file);
+        id
+    }
+
+    /// Adds an included dir to the file store.
+    pub fn add_included_dir(&mut self, dir: &Dir) -> Vec<SourceFileId> {
+        let mut file_ids = vec![];
+
+        for file in dir.files() {
+            file_ids.push(
+                self.add_file(
+                    file.path()
+                        .to_str()
+                        .expect("cannot convert file path to string"),
+                    file.contents_utf8()
+                        .expect("could not get utf8 encoded file content"),
+                ),
+            );
+        }
+
+        for sub_dir in dir.dirs() {
+            file_ids.extend(self.add_included_dir(sub_dir))
+        }
+
+        file_ids
+    }
+
+    /// Adds the included libraries to the file store and returns a mapping of
+    /// library names to file ids.
+    pub fn add_included_libraries(&mut self) -> IndexMap<SmolStr, Vec<SourceFileId>> {
+        indexmap! {
+            "std".into() => self.add_included_dir(&fe_library::STD)
+        }
+    }
+
+    /// Adds the file at `path`, unless it's already been loaded.
+    pub fn load_file(&mut self, path: &str) -> io::Result<(String, SourceFileId)> {
+        if let Some(file) = self
+            .paths
+            .get(Path::new(path))
+            .and_then(|id| self.files.get(id))
+        {
+            return Ok((file.content.clone(), file.id));
+        }
+        let content = self.read_file(Path::new(path))?;
+        let id = self.add_file(path, &content);
+        self.paths.insert(PathBuf::from(path), id);
+        Ok((content, id))
+    }
+
+    /// Adds a file that only exists in memory. It can be loaded and read by
+    /// its path like a file on disk.
+    pub fn add_virtual_file(&mut self, path: &str, content: &str) -> SourceFileId {
+        self.set_overlay(path, content);
+        self.load_file(path)
+            .expect("virtual files can always be loaded")
+            .1
+    }
+
+    /// Reads the file at `path` without adding it to the store, e.g. the JSON
+    /// ABI that a module imports. The overlay of the path is read if it has
+    /// one, and the loader of the store otherwise.
+    pub fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.overlays.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => self.loader.load_file(path),
+        }
+    }
+
+    /// Sets the contents of `path`, in place of those of the loader. If the
+    /// path has already been loaded, it's loaded again with the new contents.
+    pub fn set_overlay(&mut self, path: impl Into<PathBuf>, content: &str) {
+        let path = path.into();
+        self.paths.remove(&path);
+        self.overlays.insert(path, content.to_string());
+    }
+
+    /// Removes the overlay of `path`, so that it's loaded with the loader
+    /// again. Returns the contents of the overlay.
+    pub fn remove_overlay(&mut self, path: &Path) -> Option<String> {
+        self.paths.remove(path);
+        self.overlays.remove(path)
+    }
+
+    /// The id of the file that was loaded from `path`.
+    pub fn file_id(&self, path: &Path) -> Option<SourceFileId> {
+        self.paths.get(path).copied()
+    }
+
+    pub fn get_file(&self, id: SourceFileId) -> Option<&SourceFile> {
+        self.files.get(&id)
+    }
+
+    pub fn all_files(&self) -> Vec<SourceFileId> {
+        self.files.keys().copied().collect()
+    }
+}
+
+impl<'a> cs::files::Files<'a> for FileStore {
+    type FileId = SourceFileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, id: SourceFileId) -> Result<Self::Name, CsError> {
+        self.get_file(id)
+            .map(|file| file.name.as_str())
+            .ok_or(CsError::FileMissing)
+    }
+
+    fn source(&'a self, id: SourceFileId) -> Result<Self::Source, CsError> {
+        self.get_file(id)
+            .map(|file| file.content.as_str())
+            .ok_or(CsError::FileMissing)
+    }
+
+    fn line_index(&'a self, id: SourceFileId, byte_index: usize) -> Result<usize, CsError> {
+        Ok(self
+            .get_file(id)
+            .ok_or(CsError::FileMissing)?
+            .line_index(byte_index))
+    }
+
+    fn line_range(&'a self, id: SourceFileId, line_index: usize) -> Result<Range<usize>, CsError> {
+        let file = self.get_file(id).ok_or(CsError::FileMissing)?;
+        Ok(file
+            .line_span(line_index)
+            .ok_or(CsError::LineTooLarge {
+                given: line_index,
+                max: file.line_starts.len() - 1,
+            })?
+            .into())
+    }
+}
+
+impl Default for FileStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnUnit, SourceFile};
+    use crate::Span;
+
+    fn file() -> SourceFile {
+        SourceFile::new("test.fe", "x: u8\nlet s = \"héllo 🎉\" # ok\n")
+    }
+
+    #[test]
+    fn line_column_units() {
+        let file = file();
+        // the closing quote, after the two- and four-byte characters
+        assert_eq!(file.line_column(26, ColumnUnit::Byte), (1, 20));
+        assert_eq!(file.line_column(26, ColumnUnit::Char), (1, 16));
+        assert_eq!(file.line_column(26, ColumnUnit::Utf16), (1, 17));
+    }
+
+    #[test]
+    fn line_column_out_of_bounds() {
+        let file = file();
+        // inside `é`
+        assert_eq!(file.line_column(17, ColumnUnit::Char), (1, 10));
+        assert_eq!(file.line_column(100, ColumnUnit::Char), (2, 0));
+    }
+
+    #[test]
+    fn byte_index_units() {
+        let file = file();
+        assert_eq!(file.byte_index(1, 20, ColumnUnit::Byte), Some(26));
+        assert_eq!(file.byte_index(1, 16, ColumnUnit::Char), Some(26));
+        assert_eq!(file.byte_index(1, 17, ColumnUnit::Utf16), Some(26));
+        assert_eq!(file.byte_index(0, 100, ColumnUnit::Char), Some(5));
+        assert_eq!(file.byte_index(3, 0, ColumnUnit::Char), None);
+    }
+
+    #[test]
+    fn char_aligned_span() {
+        let file = file();
+        assert_eq!(
+            file.char_aligned_span(Span::new(file.id, 17, 23)),
+            Span::new(file.id, 16, 26)
+        );
+        assert_eq!(
+            file.char_aligned_span(Span::new(file.id, 15, 18)),
+            Span::new(file.id, 15, 18)
+        );
+    }
+}
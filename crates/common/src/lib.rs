@@ -1,9 +1,22 @@
+//! Types shared by the Fe compiler crates.
+//!
+//! Without the default `std` feature, only [`Span`], [`files::SourceFileId`]
+//! and the [`diagnostics`] types are available, which is enough to run the
+//! parser on `alloc` alone.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod diagnostics;
 pub mod files;
+#[cfg(feature = "std")]
 pub mod numeric;
 mod span;
+#[cfg(feature = "std")]
 pub mod utils;
 pub use span::{Span, Spanned};
+#[cfg(feature = "std")]
 pub mod panic;
 mod upcast;
 pub use upcast::Upcast;
@@ -1,8 +1,8 @@
 use crate::files::SourceFileId;
+use core::cmp;
+use core::fmt::{Debug, Formatter};
+use core::ops::{Add, AddAssign, Range};
 use serde::{Deserialize, Serialize};
-use std::cmp;
-use std::fmt::{Debug, Formatter};
-use std::ops::{Add, AddAssign, Range};
 
 /// An exclusive span of byte offsets in a source file.
 #[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Hash, Eq)]
@@ -55,7 +55,7 @@ impl Span {
 }
 
 impl Debug for Span {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:?}", Range::from(*self))
     }
 }
@@ -68,7 +68,7 @@ impl Add for Span {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        use std::cmp::{max, min};
+        use core::cmp::{max, min};
 
         let file_id = if self.file_id == other.file_id {
             self.file_id
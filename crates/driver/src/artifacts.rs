@@ -0,0 +1,110 @@
+//! The schema version of the JSON artifacts that the compiler emits, and the
+//! loading of artifacts that were written by older versions of the compiler.
+//!
+//! Every JSON object that `fe` writes for other tools has a `schemaVersion`
+//! field: the JSON summary, the diamond selector tables, the standard JSON
+//! output and the diagnostics of `--error-format=json`. The version is bumped
+//! whenever one of these formats changes, and `load` brings an artifact of any
+//! older version up to date, so tools can read artifacts of every compiler
+//! version in the current format.
+//!
+//! Contract ABIs are written in the Ethereum ABI format, which is a JSON
+//! array, and don't have a schema version.
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// The key of the schema version in every artifact.
+pub const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// The schema version of the artifacts that this compiler emits.
+///
+/// Version 0 is the format of the artifacts that were written before they
+/// were versioned, which have no `schemaVersion`.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// The reasons that an artifact can't be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtifactError {
+    InvalidJson(String),
+    /// The artifact isn't a JSON object.
+    NotAnObject,
+    /// The artifact's `schemaVersion` isn't a non-negative integer.
+    InvalidVersion(Value),
+    /// The artifact was written by a newer compiler, whose schema this
+    /// version doesn't know.
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::InvalidJson(err) => write!(f, "invalid JSON: {}", err),
+            ArtifactError::NotAnObject => write!(f, "the artifact isn't a JSON object"),
+            ArtifactError::InvalidVersion(version) => {
+                write!(f, "invalid `{}`: {}", SCHEMA_VERSION_KEY, version)
+            }
+            ArtifactError::UnsupportedVersion(version) => write!(
+                f,
+                "schema version {} is newer than the latest supported version {}",
+                version, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+/// Upgrades an artifact from the version of its index to the next version.
+type Migration = fn(&mut Map<String, Value>);
+
+/// The migrations from each version to the next, starting at version 0.
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [from_unversioned];
+
+/// Version 1 only added the `schemaVersion`, which `migrate` sets.
+fn from_unversioned(_artifact: &mut Map<String, Value>) {}
+
+/// Adds the current schema version to an artifact. Values other than objects
+/// are returned unchanged.
+pub fn versioned(mut artifact: Value) -> Value {
+    if let Value::Object(fields) = &mut artifact {
+        fields.insert(SCHEMA_VERSION_KEY.into(), SCHEMA_VERSION.into());
+    }
+    artifact
+}
+
+/// Returns the schema version of an artifact, which is 0 if it has none.
+pub fn schema_version(artifact: &Value) -> Result<u64, ArtifactError> {
+    let fields = artifact.as_object().ok_or(ArtifactError::NotAnObject)?;
+    match fields.get(SCHEMA_VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| ArtifactError::InvalidVersion(version.clone())),
+    }
+}
+
+/// Upgrades an artifact of any supported version to the current schema.
+pub fn migrate(mut artifact: Value) -> Result<Value, ArtifactError> {
+    let version = schema_version(&artifact)?;
+    if version > SCHEMA_VERSION {
+        return Err(ArtifactError::UnsupportedVersion(version));
+    }
+
+    let fields = artifact
+        .as_object_mut()
+        .expect("schema version of a non-object");
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(fields);
+    }
+    fields.insert(SCHEMA_VERSION_KEY.into(), SCHEMA_VERSION.into());
+    Ok(artifact)
+}
+
+/// Parses a JSON artifact that was written by this or an older version of
+/// the compiler, and upgrades it to the current schema.
+pub fn load(content: &str) -> Result<Value, ArtifactError> {
+    let artifact =
+        serde_json::from_str(content).map_err(|err| ArtifactError::InvalidJson(err.to_string()))?;
+    migrate(artifact)
+}
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag that an embedder of the compiler, like the language server, sets to
+/// abort a compilation whose result it no longer needs, e.g. because the
+/// sources changed again.
+///
+/// The compiler checks the token between its phases and between the modules
+/// and contracts that it compiles, and stops at the next check after the
+/// token is cancelled. Clones of a token share the flag, so one can be kept
+/// by the embedder while another is passed to a compilation on a different
+/// thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the compilations that use this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
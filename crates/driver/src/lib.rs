@@ -1,43 +1,217 @@
+use fe_abi::elements::FuncType;
+pub use fe_analyzer::chains::ChainProfile;
 use fe_analyzer::context::Analysis;
-use fe_analyzer::namespace::items::{IngotId, ModuleId};
-use fe_common::diagnostics::Diagnostic;
+use fe_analyzer::namespace::items::{IngotId, ModuleFileContent, ModuleId};
+pub use fe_analyzer::references::{Symbol, SymbolKind};
+pub use fe_analyzer::rename::{RenameError, TextEdit};
+use fe_common::diagnostics::{Diagnostic, Severity};
 use fe_common::files::{FileStore, SourceFileId};
+use fe_common::Span;
+pub use fe_lowering::plugins::{DebugEvents, RecordDbg};
+pub use fe_lowering::LoweringPlugin;
 use fe_parser::ast::SmolStr;
+#[cfg(feature = "wasm-backend")]
+pub use fe_wasmgen::WasmError;
+#[cfg(feature = "solc-backend")]
+pub use fe_yulc::CodeSize;
 use fe_yulgen::Db;
 use indexmap::IndexMap;
 #[cfg(feature = "solc-backend")]
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Deref;
+use tracing::{debug, info_span};
+
+pub mod artifacts;
+mod cancel;
+
+pub use cancel::CancellationToken;
 
 /// The artifacts of a compiled module.
 pub struct CompiledModule {
     pub src_ast: String,
     pub lowered_ast: String,
+    /// The MIR of the module, or an empty string if it wasn't requested.
+    pub mir: String,
     pub contracts: IndexMap<String, CompiledContract>,
+    /// The contracts of the other source files of an ingot, by the path of
+    /// the file. Empty when a single file is compiled.
+    pub module_contracts: IndexMap<String, IndexMap<String, CompiledContract>>,
+    /// The JSON selector table of each diamond, by the name of its storage
+    /// layout struct.
+    pub diamonds: IndexMap<String, String>,
+    /// Warnings emitted by the analyzer, and by the stack and memory
+    /// estimates of the generated Yul.
+    pub warnings: Vec<Diagnostic>,
 }
 
 /// The artifacts of a compiled contract.
 pub struct CompiledContract {
+    pub abi: fe_abi::elements::Contract,
     pub json_abi: String,
+    /// The interface of the contract in Fe, for calling it from other
+    /// projects.
+    pub fe_interface: String,
+    /// The Yul object that deploys the contract. The runtime object is nested
+    /// inside of it.
     pub yul: String,
+    /// The contract's runtime Yul object on its own.
+    pub runtime_yul: String,
+    /// The Yul object of `yul`, in the form that `solc --strict-assembly`
+    /// reads rather than escaped for solc's JSON input.
+    pub yul_object: String,
+    /// The estimated stack and memory use of each function in the Yul code.
+    pub resources: String,
+    /// The names of the contract's functions with the `test` qualifier, which
+    /// `fe test` calls.
+    pub tests: Vec<String>,
     #[cfg(feature = "solc-backend")]
     pub bytecode: String,
+    #[cfg(feature = "solc-backend")]
+    pub runtime_bytecode: String,
+    /// The contract in the WebAssembly text format, or the reason it can't be
+    /// compiled to WASM. `None` for interfaces, which have no code.
+    #[cfg(feature = "wasm-backend")]
+    pub wasm: Option<Result<String, WasmError>>,
 }
 
+/// The errors that stopped a compilation, and the phase that found them.
 #[derive(Debug)]
-pub struct CompileError(pub Vec<Diagnostic>);
+pub struct CompileError(pub Vec<Diagnostic>, pub CompilePhase);
+
+/// The phase of the compiler that found the errors of a [`CompileError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilePhase {
+    /// The source files couldn't be parsed.
+    Parse,
+    /// The parsed module failed to type check.
+    Analysis,
+    /// The module couldn't be compiled after it was analyzed, eg. because a
+    /// lowering plugin generated invalid code.
+    Codegen,
+    /// The compilation was stopped by its [`CancellationToken`] before it
+    /// finished. There are no errors.
+    Cancelled,
+}
+
+/// The EVM versions that bytecode can be compiled for, from oldest to newest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EvmVersion {
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+}
+
+impl EvmVersion {
+    pub const ALL: [EvmVersion; 11] = [
+        EvmVersion::Homestead,
+        EvmVersion::TangerineWhistle,
+        EvmVersion::SpuriousDragon,
+        EvmVersion::Byzantium,
+        EvmVersion::Constantinople,
+        EvmVersion::Petersburg,
+        EvmVersion::Istanbul,
+        EvmVersion::Berlin,
+        EvmVersion::London,
+        EvmVersion::Paris,
+        EvmVersion::Shanghai,
+    ];
+
+    /// The name of the version in solc and in a project manifest, e.g.
+    /// `london`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EvmVersion::Homestead => "homestead",
+            EvmVersion::TangerineWhistle => "tangerineWhistle",
+            EvmVersion::SpuriousDragon => "spuriousDragon",
+            EvmVersion::Byzantium => "byzantium",
+            EvmVersion::Constantinople => "constantinople",
+            EvmVersion::Petersburg => "petersburg",
+            EvmVersion::Istanbul => "istanbul",
+            EvmVersion::Berlin => "berlin",
+            EvmVersion::London => "london",
+            EvmVersion::Paris => "paris",
+            EvmVersion::Shanghai => "shanghai",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| version.name() == name)
+    }
+
+    /// The newest version that bytecode for the chain can be compiled for, or
+    /// `None` if it supports every version. Chains without `PUSH0` are limited
+    /// to London, the last version that solc compiles without it that every
+    /// such chain supports.
+    pub fn latest_for(chain: ChainProfile) -> Option<Self> {
+        if chain.supports_push0() {
+            None
+        } else {
+            Some(EvmVersion::London)
+        }
+    }
+}
+
+/// The options of [`compile_module`] and [`compile_ingot`].
+#[derive(Clone)]
+pub struct CompileOptions<'a> {
+    /// Whether the Yul of the contracts is compiled to bytecode. Skipping the
+    /// final Yul -> Bytecode pass is useful when debugging invalid Yul code.
+    pub with_bytecode: bool,
+    /// Whether the runtime Yul objects are compiled to bytecode on their own.
+    pub with_runtime_bytecode: bool,
+    /// Whether the MIR of the (main) module is built.
+    pub with_mir: bool,
+    pub optimize: bool,
+    /// The chain profile that the modules are checked against.
+    pub chain: ChainProfile,
+    /// The plugins that transform each lowered module, in order, before Yul
+    /// is generated.
+    pub plugins: &'a [&'a dyn LoweringPlugin],
+    /// Once cancelled, the compilation stops with a
+    /// [`CompilePhase::Cancelled`] error.
+    pub cancel: CancellationToken,
+}
+
+impl Default for CompileOptions<'_> {
+    fn default() -> Self {
+        Self {
+            with_bytecode: true,
+            with_runtime_bytecode: true,
+            with_mir: false,
+            optimize: true,
+            chain: ChainProfile::Ethereum,
+            plugins: &[],
+            cancel: CancellationToken::new(),
+        }
+    }
+}
 
 /// Compiles a single input file.
-///
-/// If `with_bytecode` is set to false, the compiler will skip the final Yul ->
-/// Bytecode pass. This is useful when debugging invalid Yul code.
 pub fn compile_module(
     files: &FileStore,
     file_id: SourceFileId,
     deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
-    _with_bytecode: bool,
-    _optimize: bool,
+    options: &CompileOptions,
 ) -> Result<CompiledModule, CompileError> {
+    let &CompileOptions {
+        with_mir,
+        chain,
+        plugins,
+        ref cancel,
+        ..
+    } = options;
     let mut errors = vec![];
 
     let db = Db::default();
@@ -45,99 +219,99 @@ pub fn compile_module(
     let Analysis {
         value: module_id,
         diagnostics: parser_diagnostics,
-    } = ModuleId::try_new(&db, files, file_id, deps).map_err(CompileError)?;
-    errors.extend(parser_diagnostics.deref().clone());
+    } = info_span!("parse")
+        .in_scope(|| ModuleId::try_new(&db, files, file_id, deps))
+        .map_err(|errors| CompileError(errors, CompilePhase::Parse))?;
+    check_cancelled(cancel)?;
+    // Warnings found while loading the files, e.g. about the functions left
+    // out of an imported ABI, don't stop the compilation.
+    let (load_warnings, parser_errors): (Vec<_>, Vec<_>) = parser_diagnostics
+        .iter()
+        .cloned()
+        .partition(|diagnostic| diagnostic.severity == Severity::Warning);
+    errors.extend(parser_errors);
 
-    match fe_analyzer::analyze_module(&db, module_id) {
-        Ok(_) => {}
-        Err(diagnostics) => {
-            errors.extend(diagnostics.into_iter());
-            return Err(CompileError(errors));
-        }
-    };
+    let mut warnings =
+        match info_span!("analyze").in_scope(|| fe_analyzer::analyze_module(&db, module_id)) {
+            Ok(warnings) => warnings,
+            Err(diagnostics) => {
+                debug!(errors = diagnostics.len(), "analysis failed");
+                // Parse errors are reported with the errors they caused.
+                let phase = if errors.is_empty() {
+                    CompilePhase::Analysis
+                } else {
+                    CompilePhase::Parse
+                };
+                errors.extend(load_warnings);
+                errors.extend(diagnostics.into_iter());
+                return Err(CompileError(errors, phase));
+            }
+        };
+    check_cancelled(cancel)?;
 
     if !errors.is_empty() {
         // There was a non-fatal parser error (eg missing parens in a fn def `fn foo: ...`)
-        return Err(CompileError(errors));
+        return Err(CompileError(errors, CompilePhase::Parse));
     }
+    warnings.extend(load_warnings);
+    warnings.extend(fe_analyzer::lints::check_unused(&db, module_id));
+    check_chain(&db, &[module_id], chain, &mut warnings)?;
 
-    // build abi
-    let json_abis = fe_abi::build(&db, module_id).expect("failed to generate abi");
+    let diamonds = diamond_selector_tables(&db, module_id);
+    check_cancelled(cancel)?;
 
     // lower the AST
-    let lowered_module_id = fe_lowering::lower_module(&db, module_id);
+    let lowered_module_id = info_span!("lower").in_scope(|| {
+        let lowered_module_id = fe_lowering::lower_module(&db, module_id);
+        fe_lowering::plugins::apply(&db, lowered_module_id, plugins)
+    });
     let lowered_ast = format!("{:#?}", &lowered_module_id.ast(&db));
+    check_cancelled(cancel)?;
 
-    fe_analyzer::analyze_module(&db, lowered_module_id).expect("failed to analyze lowered AST");
-
-    // compile to yul
-    let yul_contracts = fe_yulgen::compile(&db, lowered_module_id);
+    if let Err(diagnostics) = info_span!("analyze_lowered")
+        .in_scope(|| fe_analyzer::analyze_module(&db, lowered_module_id))
+    {
+        return Err(plugin_error(files, plugins, diagnostics));
+    }
+    check_public_interface(&db, module_id, lowered_module_id)
+        .map_err(|message| plugin_error(files, plugins, vec![error(message)]))?;
 
-    // compile to bytecode if required
-    #[cfg(feature = "solc-backend")]
-    let bytecode_contracts = if _with_bytecode {
-        match fe_yulc::compile(yul_contracts.clone(), _optimize) {
-            Err(error) => {
-                for error in serde_json::from_str::<Value>(&error.0)
-                    .expect("unable to deserialize json output")["errors"]
-                    .as_array()
-                    .expect("errors not an array")
-                {
-                    eprintln!(
-                        "Error: {}",
-                        error["formattedMessage"]
-                            .as_str()
-                            .expect("error value not a string")
-                            .replace("\\\n", "\n")
-                    )
-                }
-                panic!("Yul compilation failed with the above errors")
-            }
-            Ok(contracts) => contracts,
-        }
+    let mir = if with_mir {
+        info_span!("mir").in_scope(|| fe_mir::print_module(&db, lowered_module_id))
     } else {
-        IndexMap::new()
+        String::new()
     };
+    check_cancelled(cancel)?;
 
-    // combine all of the named contract maps
-    let contracts = json_abis
-        .keys()
-        .map(|name| {
-            (
-                name.to_owned(),
-                CompiledContract {
-                    json_abi: json_abis[name].to_owned(),
-                    yul: yul_contracts[name].to_owned(),
-                    #[cfg(feature = "solc-backend")]
-                    bytecode: if _with_bytecode {
-                        bytecode_contracts[name].to_owned()
-                    } else {
-                        "".to_string()
-                    },
-                },
-            )
-        })
-        .collect::<IndexMap<_, _>>();
+    let contracts = compile_contracts(&db, module_id, lowered_module_id, options, &mut warnings)?;
 
     Ok(CompiledModule {
         src_ast: format!("{:?}", module_id.ast(&db)),
         lowered_ast,
+        mir,
         contracts,
+        module_contracts: IndexMap::new(),
+        diamonds,
+        warnings: fe_analyzer::lints::remove_allowed(files, warnings),
     })
 }
 
-/// Compiles a set of input files.
-///
-/// If `with_bytecode` is set to false, the compiler will skip the final Yul ->
-/// Bytecode pass. This is useful when debugging invalid Yul code.
+/// Compiles a set of input files. The contracts of `main.fe` are returned in
+/// `contracts`, and those of the other files in `module_contracts`.
 pub fn compile_ingot(
     name: &str,
     files: &FileStore,
     file_ids: &[SourceFileId],
     deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
-    _with_bytecode: bool,
-    _optimize: bool,
+    options: &CompileOptions,
 ) -> Result<CompiledModule, CompileError> {
+    let &CompileOptions {
+        with_mir,
+        chain,
+        plugins,
+        ref cancel,
+        ..
+    } = options;
     let mut errors = vec![];
 
     let db = Db::default();
@@ -145,90 +319,649 @@ pub fn compile_ingot(
     let Analysis {
         value: ingot_id,
         diagnostics: parser_diagnostics,
-    } = IngotId::try_new(&db, files, name, file_ids, deps).map_err(CompileError)?;
-    errors.extend(parser_diagnostics.deref().clone());
+    } = info_span!("parse", files = file_ids.len())
+        .in_scope(|| IngotId::try_new(&db, files, name, file_ids, deps))
+        .map_err(|errors| CompileError(errors, CompilePhase::Parse))?;
+    check_cancelled(cancel)?;
+    // Warnings found while loading the files, e.g. about the functions left
+    // out of an imported ABI, don't stop the compilation.
+    let (load_warnings, parser_errors): (Vec<_>, Vec<_>) = parser_diagnostics
+        .iter()
+        .cloned()
+        .partition(|diagnostic| diagnostic.severity == Severity::Warning);
+    errors.extend(parser_errors);
 
-    match fe_analyzer::analyze_ingot(&db, ingot_id) {
-        Ok(_) => {}
-        Err(diagnostics) => {
-            errors.extend(diagnostics.into_iter());
-            return Err(CompileError(errors));
-        }
-    };
+    let mut warnings =
+        match info_span!("analyze").in_scope(|| fe_analyzer::analyze_ingot(&db, ingot_id)) {
+            Ok(warnings) => warnings,
+            Err(diagnostics) => {
+                debug!(errors = diagnostics.len(), "analysis failed");
+                // Parse errors are reported with the errors they caused.
+                let phase = if errors.is_empty() {
+                    CompilePhase::Analysis
+                } else {
+                    CompilePhase::Parse
+                };
+                errors.extend(load_warnings);
+                errors.extend(diagnostics.into_iter());
+                return Err(CompileError(errors, phase));
+            }
+        };
+    check_cancelled(cancel)?;
 
     if !errors.is_empty() {
         // There was a non-fatal parser error (eg missing parens in a fn def `fn foo: ...`)
-        return Err(CompileError(errors));
+        return Err(CompileError(errors, CompilePhase::Parse));
     }
+    warnings.extend(load_warnings);
+    for module in ingot_id.all_modules(&db).iter() {
+        check_cancelled(cancel)?;
+        warnings.extend(fe_analyzer::lints::check_unused(&db, *module));
+    }
+    check_chain(&db, &ingot_id.all_modules(&db), chain, &mut warnings)?;
 
     let module_id = ingot_id.main_module(&db).expect("missing main module");
 
-    // build abi
-    let json_abis = fe_abi::build(&db, module_id).expect("failed to generate abi");
+    let diamonds = diamond_selector_tables(&db, module_id);
     let src_ast = format!("{:#?}", &module_id.ast(&db));
+    check_cancelled(cancel)?;
 
-    let lowered_ingot_id = fe_lowering::lower_ingot(&db, ingot_id);
+    let lowered_ingot_id = info_span!("lower").in_scope(|| {
+        let lowered_ingot_id = fe_lowering::lower_ingot(&db, ingot_id);
+        fe_lowering::plugins::apply_to_ingot(&db, lowered_ingot_id, plugins)
+    });
+    check_cancelled(cancel)?;
 
-    fe_analyzer::analyze_ingot(&db, lowered_ingot_id).expect("failed to analyze lowered AST");
+    if let Err(diagnostics) =
+        info_span!("analyze_lowered").in_scope(|| fe_analyzer::analyze_ingot(&db, lowered_ingot_id))
+    {
+        return Err(plugin_error(files, plugins, diagnostics));
+    }
 
     let lowered_module_id = lowered_ingot_id
         .main_module(&db)
         .expect("missing main module");
+    check_public_interface(&db, module_id, lowered_module_id)
+        .map_err(|message| plugin_error(files, plugins, vec![error(message)]))?;
 
     let lowered_ast = format!("{:#?}", &lowered_module_id.ast(&db));
+    let mir = if with_mir {
+        info_span!("mir").in_scope(|| fe_mir::print_module(&db, lowered_module_id))
+    } else {
+        String::new()
+    };
+    check_cancelled(cancel)?;
+
+    let contracts = compile_contracts(&db, module_id, lowered_module_id, options, &mut warnings)?;
+
+    // The contracts of the other source files are compiled too, so that a
+    // directory of sources can be built at once.
+    let lowered_modules = lowered_ingot_id.all_modules(&db);
+    let mut module_contracts = IndexMap::new();
+    for module in ingot_id.all_modules(&db).iter() {
+        let file_content = module.file_content(&db);
+        if *module == module_id
+            || matches!(file_content, ModuleFileContent::Dir { .. })
+            || module.all_contracts(&db).is_empty()
+        {
+            continue;
+        }
+        let lowered_module = *lowered_modules
+            .iter()
+            .find(|lowered| lowered.file_content(&db) == file_content)
+            .expect("missing lowered module");
+        check_public_interface(&db, *module, lowered_module)
+            .map_err(|message| plugin_error(files, plugins, vec![error(message)]))?;
+
+        module_contracts.insert(
+            module.ingot_path(&db).to_string(),
+            compile_contracts(&db, *module, lowered_module, options, &mut warnings)?,
+        );
+    }
+
+    Ok(CompiledModule {
+        src_ast,
+        lowered_ast,
+        mir,
+        contracts,
+        module_contracts,
+        diamonds,
+        warnings: fe_analyzer::lints::remove_allowed(files, warnings),
+    })
+}
+
+/// Parses and analyzes a single input file without generating code, and
+/// returns its errors and warnings. This is much faster than compiling it,
+/// eg. for checking a file as it's edited. Returns `None` if `cancel` was
+/// cancelled before the check finished.
+pub fn check_module(
+    files: &FileStore,
+    file_id: SourceFileId,
+    deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
+    cancel: &CancellationToken,
+) -> Option<Vec<Diagnostic>> {
+    let db = Db::default();
+
+    let Analysis {
+        value: module_id,
+        diagnostics: parser_diagnostics,
+    } = match ModuleId::try_new(&db, files, file_id, deps) {
+        Ok(analysis) => analysis,
+        Err(diagnostics) => return Some(diagnostics),
+    };
+    let mut diagnostics = parser_diagnostics.deref().clone();
+    check_cancelled(cancel).ok()?;
+
+    match fe_analyzer::analyze_module(&db, module_id) {
+        Ok(warnings) => {
+            check_cancelled(cancel).ok()?;
+            diagnostics.extend(warnings);
+            diagnostics.extend(fe_analyzer::lints::check_unused(&db, module_id));
+        }
+        Err(errors) => diagnostics.extend(errors),
+    }
+    Some(fe_analyzer::lints::remove_allowed(files, diagnostics))
+}
+
+/// Parses and analyzes a set of input files without generating code, and
+/// returns their errors and warnings, or `None` if `cancel` was cancelled
+/// before the check finished.
+pub fn check_ingot(
+    name: &str,
+    files: &FileStore,
+    file_ids: &[SourceFileId],
+    deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
+    cancel: &CancellationToken,
+) -> Option<Vec<Diagnostic>> {
+    let db = Db::default();
+
+    let Analysis {
+        value: ingot_id,
+        diagnostics: parser_diagnostics,
+    } = match IngotId::try_new(&db, files, name, file_ids, deps) {
+        Ok(analysis) => analysis,
+        Err(diagnostics) => return Some(diagnostics),
+    };
+    let mut diagnostics = parser_diagnostics.deref().clone();
+    check_cancelled(cancel).ok()?;
+
+    match fe_analyzer::analyze_ingot(&db, ingot_id) {
+        Ok(warnings) => {
+            diagnostics.extend(warnings);
+            for module in ingot_id.all_modules(&db).iter() {
+                check_cancelled(cancel).ok()?;
+                diagnostics.extend(fe_analyzer::lints::check_unused(&db, *module));
+            }
+        }
+        Err(errors) => diagnostics.extend(errors),
+    }
+    Some(fe_analyzer::lints::remove_allowed(files, diagnostics))
+}
+
+/// Finds the variable, parameter or function whose name contains `span`, and
+/// every reference to it in the module of the span's file. The file is
+/// analyzed on its own, or with the other files of its ingot if `ingot` gives
+/// the ingot's name and files. Returns `None` if there's no symbol at `span`,
+/// or the file doesn't analyze without errors.
+pub fn find_references(
+    files: &FileStore,
+    deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
+    ingot: Option<(&str, &[SourceFileId])>,
+    span: Span,
+) -> Option<Symbol> {
+    let db = Db::default();
+    let module_id = analyzed_module(&db, files, deps, ingot, span.file_id)?;
+    fe_analyzer::references::find_references(&db, module_id, span)
+}
+
+/// Computes the edits that rename the variable or function parameter whose
+/// name contains `span` to `new_name`, within the module of the span's file.
+/// The file is analyzed like in [`find_references`]. Returns `None` if the file
+/// doesn't analyze without errors.
+pub fn rename(
+    files: &FileStore,
+    deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
+    ingot: Option<(&str, &[SourceFileId])>,
+    span: Span,
+    new_name: &str,
+) -> Option<Result<Vec<TextEdit>, RenameError>> {
+    let db = Db::default();
+    let module_id = analyzed_module(&db, files, deps, ingot, span.file_id)?;
+    Some(fe_analyzer::rename::rename(&db, module_id, span, new_name))
+}
+
+/// The module of a file, analyzed on its own or with the rest of its ingot,
+/// or `None` if there were errors.
+fn analyzed_module(
+    db: &Db,
+    files: &FileStore,
+    deps: &IndexMap<SmolStr, Vec<SourceFileId>>,
+    ingot: Option<(&str, &[SourceFileId])>,
+    file_id: SourceFileId,
+) -> Option<ModuleId> {
+    let (module_id, parser_diagnostics) = match ingot {
+        Some((name, file_ids)) => {
+            let analysis = IngotId::try_new(db, files, name, file_ids, deps).ok()?;
+            fe_analyzer::analyze_ingot(db, analysis.value).ok()?;
+            let module_id = analysis
+                .value
+                .all_modules(db)
+                .iter()
+                .copied()
+                .find(|module| {
+                    module.file_content(db) == ModuleFileContent::File { file: file_id }
+                })?;
+            (module_id, analysis.diagnostics)
+        }
+        None => {
+            let analysis = ModuleId::try_new(db, files, file_id, deps).ok()?;
+            fe_analyzer::analyze_module(db, analysis.value).ok()?;
+            (analysis.value, analysis.diagnostics)
+        }
+    };
+    if parser_diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity >= Severity::Error)
+    {
+        return None;
+    }
+    Some(module_id)
+}
+
+/// Generates the ABI, interfaces, Yul and, if requested, bytecode of each
+/// contract in a module, given the module after lowering. The warnings of the
+/// resource estimates are added to `warnings`.
+fn compile_contracts(
+    db: &Db,
+    module_id: ModuleId,
+    lowered_module_id: ModuleId,
+    options: &CompileOptions,
+    warnings: &mut Vec<Diagnostic>,
+) -> Result<IndexMap<String, CompiledContract>, CompileError> {
+    let cancel = &options.cancel;
+    let _span = info_span!("codegen", module = %module_id.name(db)).entered();
+    check_cancelled(cancel)?;
+
+    // build abi
+    let (abis, fe_interfaces) = info_span!("abi").in_scope(|| {
+        let abis = fe_abi::contracts(db, module_id).expect("failed to generate abi");
+        (abis, fe_abi::fe_interfaces(db, module_id))
+    });
+    debug!(contracts = abis.len(), "built the ABIs");
+    check_cancelled(cancel)?;
 
     // compile to yul
-    let yul_contracts = fe_yulgen::compile(&db, lowered_module_id);
+    let (yul_contracts, runtime_yul_contracts, yul_objects) = info_span!("yulgen").in_scope(|| {
+        (
+            fe_yulgen::compile(db, lowered_module_id),
+            fe_yulgen::compile_runtime(db, lowered_module_id),
+            fe_yulgen::compile_objects(db, lowered_module_id),
+        )
+    });
+    let resources = info_span!("estimate_resources")
+        .in_scope(|| fe_yulgen::estimate_resources(db, lowered_module_id));
+    for contract in resources.values() {
+        warnings.extend(contract.warnings());
+    }
+    check_cancelled(cancel)?;
 
     // compile to bytecode if required
     #[cfg(feature = "solc-backend")]
-    let bytecode_contracts = if _with_bytecode {
-        match fe_yulc::compile(yul_contracts.clone(), _optimize) {
-            Err(error) => {
-                for error in serde_json::from_str::<Value>(&error.0)
-                    .expect("unable to deserialize json output")["errors"]
-                    .as_array()
-                    .expect("errors not an array")
-                {
-                    eprintln!(
-                        "Error: {}",
-                        error["formattedMessage"]
-                            .as_str()
-                            .expect("error value not a string")
-                            .replace("\\\n", "\n")
-                    )
-                }
-                panic!("Yul compilation failed with the above errors")
-            }
-            Ok(contracts) => contracts,
-        }
+    let bytecode_contracts = if options.with_bytecode {
+        info_span!("solc").in_scope(|| compile_yul(&yul_contracts, options.optimize, None))
+    } else {
+        IndexMap::new()
+    };
+    #[cfg(feature = "solc-backend")]
+    let runtime_bytecode_contracts = if options.with_runtime_bytecode {
+        info_span!("solc_runtime")
+            .in_scope(|| compile_yul(&runtime_yul_contracts, options.optimize, None))
     } else {
         IndexMap::new()
     };
 
+    #[cfg(feature = "wasm-backend")]
+    let mut wasm_contracts =
+        info_span!("wasmgen").in_scope(|| fe_wasmgen::compile(db, lowered_module_id));
+
+    let mut tests = module_id
+        .all_contracts(db)
+        .iter()
+        .map(|contract| {
+            let names = contract
+                .functions(db)
+                .values()
+                .filter(|function| function.is_test(db))
+                .map(|function| function.name(db).to_string())
+                .collect::<Vec<_>>();
+            (contract.name(db).to_string(), names)
+        })
+        .collect::<IndexMap<_, _>>();
+
+    check_cancelled(cancel)?;
+
     // combine all of the named contract maps
-    let contracts = json_abis
-        .keys()
-        .map(|name| {
-            (
-                name.to_owned(),
-                CompiledContract {
-                    json_abi: json_abis[name].to_owned(),
-                    yul: yul_contracts[name].to_owned(),
-                    #[cfg(feature = "solc-backend")]
-                    bytecode: if _with_bytecode {
-                        bytecode_contracts[name].to_owned()
-                    } else {
-                        "".to_string()
-                    },
+    Ok(abis
+        .into_iter()
+        .map(|(name, abi)| {
+            let json_abi = abi.json(true).expect("failed to serialize abi");
+            let contract = CompiledContract {
+                abi,
+                json_abi,
+                fe_interface: fe_interfaces[&name].to_owned(),
+                yul: yul_contracts[&name].to_owned(),
+                runtime_yul: runtime_yul_contracts[&name].to_owned(),
+                yul_object: yul_objects[&name].to_owned(),
+                resources: resources[&name].to_string(),
+                tests: tests.remove(&name).unwrap_or_default(),
+                #[cfg(feature = "solc-backend")]
+                bytecode: if options.with_bytecode {
+                    bytecode_contracts[&name].to_owned()
+                } else {
+                    "".to_string()
                 },
-            )
+                #[cfg(feature = "solc-backend")]
+                runtime_bytecode: if options.with_runtime_bytecode {
+                    runtime_bytecode_contracts[&name].to_owned()
+                } else {
+                    "".to_string()
+                },
+                #[cfg(feature = "wasm-backend")]
+                wasm: wasm_contracts.remove(&name),
+            };
+            (name, contract)
         })
-        .collect::<IndexMap<_, _>>();
+        .collect())
+}
 
-    Ok(CompiledModule {
-        src_ast,
-        lowered_ast,
-        contracts,
-    })
+/// Stops the compilation with a [`CompilePhase::Cancelled`] error if `cancel`
+/// was cancelled.
+fn check_cancelled(cancel: &CancellationToken) -> Result<(), CompileError> {
+    if cancel.is_cancelled() {
+        debug!("compilation cancelled");
+        return Err(CompileError(vec![], CompilePhase::Cancelled));
+    }
+    Ok(())
+}
+
+/// Checks the modules against a chain profile. Uses of opcodes that the chain
+/// doesn't support are errors, which stop the compilation, and the warnings
+/// are added to `warnings`.
+fn check_chain(
+    db: &Db,
+    modules: &[ModuleId],
+    chain: ChainProfile,
+    warnings: &mut Vec<Diagnostic>,
+) -> Result<(), CompileError> {
+    let (errors, chain_warnings): (Vec<_>, Vec<_>) = modules
+        .iter()
+        .flat_map(|module| fe_analyzer::chains::check(db, *module, chain))
+        .partition(|diagnostic| diagnostic.severity == Severity::Error);
+    if !errors.is_empty() {
+        return Err(CompileError(errors, CompilePhase::Analysis));
+    }
+    warnings.extend(chain_warnings);
+    Ok(())
+}
+
+/// Builds the JSON selector table of each diamond in the module.
+fn diamond_selector_tables(db: &Db, module_id: ModuleId) -> IndexMap<String, String> {
+    fe_abi::diamonds(db, module_id)
+        .expect("failed to generate diamond selector tables")
+        .into_iter()
+        .map(|(layout, table)| {
+            let table =
+                serde_json::to_value(&table).expect("failed to serialize diamond selector table");
+            let json = serde_json::to_string_pretty(&artifacts::versioned(table))
+                .expect("failed to serialize diamond selector table");
+            (layout, json)
+        })
+        .collect()
+}
+
+/// Compiles the Yul of every contract of a compiled module to bytecode again,
+/// with the given optimizer setting and for the given EVM version, and
+/// replaces the contracts' bytecode. The Yul doesn't depend on these
+/// settings, so a module that was analyzed once can be built for several of
+/// them.
+#[cfg(feature = "solc-backend")]
+pub fn compile_bytecode(
+    module: &mut CompiledModule,
+    with_bytecode: bool,
+    with_runtime_bytecode: bool,
+    optimize: bool,
+    evm_version: Option<EvmVersion>,
+) {
+    let _span = info_span!("solc", ?evm_version).entered();
+    for contracts in
+        std::iter::once(&mut module.contracts).chain(module.module_contracts.values_mut())
+    {
+        if with_bytecode {
+            let yul = contracts
+                .iter()
+                .map(|(name, contract)| (name.clone(), contract.yul.clone()))
+                .collect();
+            for (name, bytecode) in compile_yul(&yul, optimize, evm_version) {
+                contracts[&name].bytecode = bytecode;
+            }
+        }
+        if with_runtime_bytecode {
+            let runtime_yul = contracts
+                .iter()
+                .map(|(name, contract)| (name.clone(), contract.runtime_yul.clone()))
+                .collect();
+            for (name, bytecode) in compile_yul(&runtime_yul, optimize, evm_version) {
+                contracts[&name].runtime_bytecode = bytecode;
+            }
+        }
+    }
+}
+
+/// Reports the bytes of a compiled contract's runtime code that each of its
+/// Yul functions takes up. This compiles the runtime Yul again, with the same
+/// `optimize` setting and EVM version as the bytecode, and returns solc's
+/// errors if that fails.
+#[cfg(feature = "solc-backend")]
+pub fn code_size(
+    name: &str,
+    contract: &CompiledContract,
+    optimize: bool,
+    evm_version: Option<EvmVersion>,
+) -> Result<CodeSize, CompileError> {
+    fe_yulc::code_size(
+        name,
+        &contract.runtime_yul,
+        optimize,
+        evm_version.map(EvmVersion::name),
+    )
+    .map_err(|err| CompileError(solc_errors(&err.0), CompilePhase::Codegen))
+}
+
+/// The errors in solc's JSON output, or the output itself if it has none.
+#[cfg(feature = "solc-backend")]
+fn solc_errors(output: &str) -> Vec<Diagnostic> {
+    let messages = serde_json::from_str::<Value>(output)
+        .ok()
+        .and_then(|output| output["errors"].as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|error| error["formattedMessage"].as_str())
+        .map(|message| format!("Yul compilation failed: {}", message.replace("\\\n", "\n")))
+        .collect::<Vec<_>>();
+    if messages.is_empty() {
+        vec![error(format!("Yul compilation failed: {}", output))]
+    } else {
+        messages.into_iter().map(error).collect()
+    }
+}
+
+/// Compiles a map of Yul objects to bytecode, printing solc's errors and
+/// panicking if compilation fails.
+#[cfg(feature = "solc-backend")]
+fn compile_yul(
+    yul_contracts: &IndexMap<String, String>,
+    optimize: bool,
+    evm_version: Option<EvmVersion>,
+) -> IndexMap<String, String> {
+    match fe_yulc::compile(
+        yul_contracts.clone(),
+        optimize,
+        evm_version.map(EvmVersion::name),
+    ) {
+        Err(error) => {
+            for error in serde_json::from_str::<Value>(&error.0)
+                .expect("unable to deserialize json output")["errors"]
+                .as_array()
+                .expect("errors not an array")
+            {
+                eprintln!(
+                    "Error: {}",
+                    error["formattedMessage"]
+                        .as_str()
+                        .expect("error value not a string")
+                        .replace("\\\n", "\n")
+                )
+            }
+            panic!("Yul compilation failed with the above errors")
+        }
+        Ok(contracts) => contracts,
+    }
+}
+
+/// Returns the errors found in a lowered module that was transformed by
+/// `plugins`, with a note that names them. Labels that point at code the
+/// plugins generated, which isn't part of any file, are removed.
+///
+/// If there are no plugins, an error in the lowered module is a bug in the
+/// compiler, and is reported as one.
+fn plugin_error(
+    files: &FileStore,
+    plugins: &[&dyn LoweringPlugin],
+    diagnostics: Vec<Diagnostic>,
+) -> CompileError {
+    let note = if plugins.is_empty() {
+        "Note: this is a bug in the compiler, please report it at \
+         https://github.com/ethereum/fe/issues"
+            .to_string()
+    } else {
+        format!(
+            "Note: the lowered code was transformed by the plugins {}",
+            plugins
+                .iter()
+                .map(|plugin| format!("`{}`", plugin.name()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    CompileError(
+        diagnostics
+            .into_iter()
+            .map(|mut diagnostic| {
+                if plugins.is_empty() {
+                    diagnostic.severity = Severity::Bug;
+                }
+                diagnostic
+                    .labels
+                    .retain(|label| files.get_file(label.span.file_id).is_some());
+                diagnostic.notes.push(note.clone());
+                diagnostic
+            })
+            .collect(),
+        CompilePhase::Codegen,
+    )
+}
+
+fn error(message: String) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        code: None,
+        message,
+        labels: vec![],
+        notes: vec![],
+    }
+}
+
+/// Checks that every contract in the lowered module has the same public
+/// functions as the source module, and that the selectors of its generated
+/// dispatcher are those of the functions in its ABI. The ABI is built from the
+/// source module, while the dispatcher is generated from the lowered module, so
+/// any difference would mean that a `pub fn` listed in the ABI can't be called,
+/// or that a function missing from the ABI can be. Lowering plugins can cause
+/// this.
+fn check_public_interface(
+    db: &Db,
+    module_id: ModuleId,
+    lowered_module_id: ModuleId,
+) -> Result<(), String> {
+    let abis = fe_abi::contracts(db, module_id).expect("failed to generate abi");
+    let lowered_contracts = lowered_module_id.all_contracts(db);
+    for contract in module_id.all_contracts(db).iter() {
+        let name = contract.name(db);
+        let lowered = match lowered_contracts
+            .iter()
+            .find(|lowered| lowered.name(db) == name)
+        {
+            Some(lowered) => lowered,
+            None => return Err(format!("contract `{}` is missing after lowering", name)),
+        };
+
+        let abi_functions = contract
+            .public_functions(db)
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let dispatched_functions = lowered
+            .public_functions(db)
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        if abi_functions != dispatched_functions {
+            return Err(format!(
+                "the dispatcher of contract `{}` doesn't match its ABI \
+                 (not dispatched: {:?}, not in the ABI: {:?})",
+                name,
+                abi_functions
+                    .difference(&dispatched_functions)
+                    .collect::<Vec<_>>(),
+                dispatched_functions
+                    .difference(&abi_functions)
+                    .collect::<Vec<_>>(),
+            ));
+        }
+
+        // The signatures of the ABI's functions, by their selectors.
+        let abi_selectors = abis[name.as_str()]
+            .functions()
+            .filter(|function| function.typ == FuncType::Function)
+            .map(|function| (function.selector(), function.signature()))
+            .collect::<BTreeMap<_, _>>();
+        let mut dispatched_selectors = BTreeSet::new();
+        for selector in fe_yulgen::dispatched_selectors(db, *lowered) {
+            if !dispatched_selectors.insert(selector.clone()) {
+                return Err(format!(
+                    "the dispatcher of contract `{}` has more than one arm for selector {}",
+                    name, selector
+                ));
+            }
+        }
+
+        let not_dispatched = abi_selectors
+            .iter()
+            .filter(|(selector, _)| !dispatched_selectors.contains(*selector))
+            .map(|(_, signature)| signature)
+            .collect::<Vec<_>>();
+        let not_in_abi = dispatched_selectors
+            .iter()
+            .filter(|selector| !abi_selectors.contains_key(*selector))
+            .collect::<Vec<_>>();
+        if !not_dispatched.is_empty() || !not_in_abi.is_empty() {
+            return Err(format!(
+                "the selectors of the dispatcher of contract `{}` don't match its ABI \
+                 (not dispatched: {:?}, not in the ABI: {:?})",
+                name, not_dispatched, not_in_abi
+            ));
+        }
+    }
+    Ok(())
 }
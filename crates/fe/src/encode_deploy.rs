@@ -0,0 +1,502 @@
+//! The `fe encode-deploy` subcommand, which builds the data of a transaction
+//! that deploys a compiled contract: its init code, followed by its
+//! constructor arguments encoded as the contract's ABI describes them.
+//!
+//! Given the address of a deployer and a salt, it also predicts the address
+//! that `CREATE2` deploys the contract to, i.e. the last 20 bytes of
+//! `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))`.
+
+use crate::summary::ExitCode;
+use clap::ArgMatches;
+use ethabi::{Address, Param, ParamType, Token, Uint};
+use fe_common::utils::keccak;
+use serde_json::Value;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+/// Prints the deployment data as hex and, if a deployer and salt are given,
+/// the `CREATE2` address on a second line.
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let artifact = Path::new(matches.value_of("artifact").unwrap());
+    let args = matches
+        .values_of("args")
+        .map(|values| values.collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let (init_code, constructor) = match load_artifact(artifact, !args.is_empty()) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::IoError;
+        }
+    };
+
+    let create2 = match (matches.value_of("deployer"), matches.value_of("salt")) {
+        (Some(deployer), Some(salt)) => match (parse_address(deployer), parse_uint(salt)) {
+            (Ok(deployer), Ok(salt)) => Some((deployer, salt)),
+            (Err(err), _) => {
+                eprintln!("Invalid deployer: {}", err);
+                return ExitCode::Failure;
+            }
+            (_, Err(err)) => {
+                eprintln!("Invalid salt: {}", err);
+                return ExitCode::Failure;
+            }
+        },
+        _ => None,
+    };
+
+    let data = match deployment_data(&init_code, &constructor, &args) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::Failure;
+        }
+    };
+
+    println!("0x{}", hex::encode(&data));
+    if let Some((deployer, salt)) = create2 {
+        println!("0x{}", hex::encode(create2_address(deployer, salt, &data)));
+    }
+    ExitCode::Success
+}
+
+/// Reads the init code of a contract and the parameters of its constructor
+/// from its output directory, e.g. `output/Token`, or its bytecode file, e.g.
+/// `output/Token/Token.bin`. The ABI next to the bytecode is only needed if
+/// there are arguments to encode.
+fn load_artifact(path: &Path, needs_abi: bool) -> Result<(Vec<u8>, Vec<Param>), String> {
+    let (bin_path, name) = if path.is_dir() {
+        let name = file_name(path, Path::file_name);
+        (path.join(format!("{}.bin", name)), name)
+    } else {
+        (path.to_path_buf(), file_name(path, Path::file_stem))
+    };
+    let abi_path = bin_path.with_file_name(format!("{}_abi.json", name));
+
+    let bin = fs::read_to_string(&bin_path)
+        .map_err(|err| format!("Unable to read `{}`: {}", bin_path.display(), err))?;
+    let init_code = decode_hex(bin.trim())
+        .map_err(|err| format!("`{}` isn't bytecode: {}", bin_path.display(), err))?;
+    if init_code.is_empty() {
+        return Err(format!(
+            "`{}` is empty; interfaces have no code to deploy",
+            bin_path.display()
+        ));
+    }
+
+    if !abi_path.exists() && !needs_abi {
+        return Ok((init_code, vec![]));
+    }
+    let abi = fs::read_to_string(&abi_path)
+        .map_err(|err| format!("Unable to read `{}`: {}", abi_path.display(), err))?;
+    let constructor = ethabi::Contract::load(abi.as_bytes())
+        .map_err(|err| format!("Invalid ABI in `{}`: {}", abi_path.display(), err))?
+        .constructor
+        .map(|constructor| constructor.inputs)
+        .unwrap_or_default();
+    Ok((init_code, constructor))
+}
+
+fn file_name(path: &Path, part: fn(&Path) -> Option<&OsStr>) -> String {
+    part(path)
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The init code followed by the encoded constructor arguments.
+fn deployment_data(
+    init_code: &[u8],
+    constructor: &[Param],
+    args: &[&str],
+) -> Result<Vec<u8>, String> {
+    if constructor.len() != args.len() {
+        let params = constructor
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.kind))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!(
+            "The constructor takes {} argument{} ({}), but {} {} given",
+            constructor.len(),
+            if constructor.len() == 1 { "" } else { "s" },
+            params,
+            args.len(),
+            if args.len() == 1 { "was" } else { "were" },
+        ));
+    }
+
+    let mut tokens = vec![];
+    for (param, arg) in constructor.iter().zip(args) {
+        // Arrays and tuples are given as JSON, everything else as is, so that
+        // strings and numbers don't need to be quoted twice.
+        let value = match param.kind {
+            ParamType::Array(_) | ParamType::FixedArray(..) | ParamType::Tuple(_) => {
+                serde_json::from_str(arg)
+                    .map_err(|err| format!("Invalid argument `{}`: {}", param.name, err))?
+            }
+            _ => Value::String(arg.to_string()),
+        };
+        tokens.push(
+            tokenize(&param.kind, &value)
+                .map_err(|err| format!("Invalid argument `{}`: {}", param.name, err))?,
+        );
+    }
+
+    let mut data = init_code.to_vec();
+    data.extend(ethabi::encode(&tokens));
+    Ok(data)
+}
+
+/// The address `CREATE2` deploys the init code to, i.e. the last 20 bytes of
+/// `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))`.
+fn create2_address(deployer: Address, salt: Uint, init_code: &[u8]) -> Address {
+    let mut preimage = vec![0xff];
+    preimage.extend(deployer.as_bytes());
+    preimage.extend(<[u8; 32]>::from(salt));
+    preimage.extend(keccak::full_as_bytes(init_code));
+    Address::from_slice(&keccak::full_as_bytes(&preimage)[12..])
+}
+
+/// The token of an argument of the given type. Numbers may also be given as
+/// JSON numbers inside arrays and tuples.
+fn tokenize(kind: &ParamType, value: &Value) -> Result<Token, String> {
+    let expected = |what: &str| format!("expected {} for `{}`, found `{}`", what, kind, value);
+    Ok(match kind {
+        ParamType::Uint(bits) => {
+            let text = scalar(value).ok_or_else(|| expected("a number"))?;
+            let uint = parse_uint(&text)?;
+            if uint.bits() > *bits {
+                return Err(format!("`{}` doesn't fit in `{}`", text, kind));
+            }
+            Token::Uint(uint)
+        }
+        ParamType::Int(bits) => {
+            let text = scalar(value).ok_or_else(|| expected("a number"))?;
+            let (negative, magnitude) = match text.strip_prefix('-') {
+                Some(magnitude) => (true, parse_uint(magnitude)?),
+                None => (false, parse_uint(&text)?),
+            };
+            // The smallest value's magnitude is one more than the largest's.
+            let min_magnitude = Uint::one() << (bits - 1);
+            if magnitude > min_magnitude || (magnitude == min_magnitude && !negative) {
+                return Err(format!("`{}` doesn't fit in `{}`", text, kind));
+            }
+            if negative {
+                // two's complement
+                Token::Int((!magnitude).overflowing_add(Uint::one()).0)
+            } else {
+                Token::Int(magnitude)
+            }
+        }
+        ParamType::Address => Token::Address(parse_address(
+            value.as_str().ok_or_else(|| expected("an address"))?,
+        )?),
+        ParamType::Bool => match value {
+            Value::Bool(truth) => Token::Bool(*truth),
+            Value::String(text) if text == "true" => Token::Bool(true),
+            Value::String(text) if text == "false" => Token::Bool(false),
+            _ => return Err(expected("`true` or `false`")),
+        },
+        ParamType::FixedBytes(size) => {
+            let bytes = decode_hex(value.as_str().ok_or_else(|| expected("hex bytes"))?)?;
+            if bytes.len() != *size {
+                return Err(format!(
+                    "expected {} bytes for `{}`, found {}",
+                    size,
+                    kind,
+                    bytes.len()
+                ));
+            }
+            Token::FixedBytes(bytes)
+        }
+        ParamType::Bytes => Token::Bytes(decode_hex(
+            value.as_str().ok_or_else(|| expected("hex bytes"))?,
+        )?),
+        ParamType::String => Token::String(
+            value
+                .as_str()
+                .ok_or_else(|| expected("a string"))?
+                .to_string(),
+        ),
+        ParamType::Array(inner) => Token::Array(
+            value
+                .as_array()
+                .ok_or_else(|| expected("an array"))?
+                .iter()
+                .map(|item| tokenize(inner, item))
+                .collect::<Result<_, _>>()?,
+        ),
+        ParamType::FixedArray(inner, len) => {
+            let items = value.as_array().ok_or_else(|| expected("an array"))?;
+            if items.len() != *len {
+                return Err(format!(
+                    "expected {} items for `{}`, found {}",
+                    len,
+                    kind,
+                    items.len()
+                ));
+            }
+            Token::FixedArray(
+                items
+                    .iter()
+                    .map(|item| tokenize(inner, item))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        ParamType::Tuple(kinds) => {
+            let items = value.as_array().ok_or_else(|| expected("an array"))?;
+            if items.len() != kinds.len() {
+                return Err(format!(
+                    "expected {} items for `{}`, found {}",
+                    kinds.len(),
+                    kind,
+                    items.len()
+                ));
+            }
+            Token::Tuple(
+                kinds
+                    .iter()
+                    .zip(items)
+                    .map(|(kind, item)| tokenize(kind, item))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+    })
+}
+
+/// The text of a number, which may be given as a JSON number inside an array
+/// or tuple.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) if number.is_u64() || number.is_i64() => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Parses a non-negative decimal number, or a hex number prefixed with `0x`.
+fn parse_uint(text: &str) -> Result<Uint, String> {
+    if let Some(digits) = text.strip_prefix("0x") {
+        if digits.is_empty() || digits.len() > 64 {
+            return Err(format!("`{}` isn't a 32 byte hex number", text));
+        }
+        let bytes = decode_hex(&format!("{:0>64}", digits))?;
+        return Ok(Uint::from_big_endian(&bytes));
+    }
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(format!("`{}` isn't a number", text));
+    }
+    Uint::from_dec_str(text).map_err(|_| format!("`{}` doesn't fit in 256 bits", text))
+}
+
+fn parse_address(text: &str) -> Result<Address, String> {
+    match text.strip_prefix("0x").map(decode_hex) {
+        Some(Ok(bytes)) if bytes.len() == 20 => Ok(Address::from_slice(&bytes)),
+        _ => Err(format!(
+            "`{}` isn't an address, e.g. `0x{}`",
+            text,
+            "00".repeat(20)
+        )),
+    }
+}
+
+/// Decodes hex, which may be prefixed with `0x`.
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .map_err(|err| format!("`{}` isn't hex: {}", text, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create2_address, deployment_data, parse_address, parse_uint};
+    use ethabi::Param;
+
+    /// The parameters of a constructor with the given JSON ABI inputs.
+    fn constructor(inputs: &str) -> Vec<Param> {
+        let abi = format!(r#"[{{"type": "constructor", "inputs": {}}}]"#, inputs);
+        ethabi::Contract::load(abi.as_bytes())
+            .unwrap()
+            .constructor
+            .unwrap()
+            .inputs
+    }
+
+    /// The constructor arguments encoded for a single parameter `x`, as hex.
+    fn encode(typ: &str, arg: &str) -> Result<String, String> {
+        let inputs = format!(r#"[{{"name": "x", "type": "{}"}}]"#, typ);
+        deployment_data(&[], &constructor(&inputs), &[arg]).map(hex::encode)
+    }
+
+    /// Hex numbers as words, padded on the left.
+    fn words(words: &[&str]) -> String {
+        words.iter().map(|word| format!("{:0>64}", word)).collect()
+    }
+
+    /// Hex bytes as a word, padded on the right.
+    fn padded(bytes: &str) -> String {
+        format!("{:0<64}", bytes)
+    }
+
+    #[test]
+    fn static_args() {
+        let constructor = constructor(
+            r#"[
+                {"name": "a", "type": "uint256"},
+                {"name": "b", "type": "bool"},
+                {"name": "c", "type": "address"},
+                {"name": "d", "type": "bytes2"}
+            ]"#,
+        );
+        let args = [
+            "0x10",
+            "true",
+            "0x2000000000000000000000000000000000000002",
+            "0xbeef",
+        ];
+        let data = deployment_data(&[0x60, 0x80], &constructor, &args).unwrap();
+        assert_eq!(
+            hex::encode(data),
+            "6080".to_string()
+                + &words(&["10", "1", "2000000000000000000000000000000000000002"])
+                + &padded("beef")
+        );
+
+        assert_eq!(encode("uint8", "255").unwrap(), words(&["ff"]));
+        assert_eq!(
+            encode("uint8", "256").unwrap_err(),
+            "Invalid argument `x`: `256` doesn't fit in `uint8`"
+        );
+        assert_eq!(
+            encode("bytes2", "0xbe").unwrap_err(),
+            "Invalid argument `x`: expected 2 bytes for `bytes2`, found 1"
+        );
+        assert_eq!(
+            encode("address", "0x20").unwrap_err(),
+            format!(
+                "Invalid argument `x`: `0x20` isn't an address, e.g. `0x{}`",
+                "00".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn negative_ints() {
+        assert_eq!(encode("int256", "-1").unwrap(), "f".repeat(64));
+        assert_eq!(encode("int8", "-128").unwrap(), "f".repeat(62) + "80");
+        assert_eq!(encode("int8", "127").unwrap(), words(&["7f"]));
+        assert_eq!(encode("int8", "-0").unwrap(), words(&["0"]));
+        assert_eq!(
+            encode("int8", "128").unwrap_err(),
+            "Invalid argument `x`: `128` doesn't fit in `int8`"
+        );
+        assert_eq!(
+            encode("int8", "-129").unwrap_err(),
+            "Invalid argument `x`: `-129` doesn't fit in `int8`"
+        );
+    }
+
+    #[test]
+    fn dynamic_args() {
+        let constructor = constructor(
+            r#"[
+                {"name": "a", "type": "string"},
+                {"name": "b", "type": "uint256"},
+                {"name": "c", "type": "bytes"}
+            ]"#,
+        );
+        let data = deployment_data(&[], &constructor, &["hi", "7", "0x0102"]).unwrap();
+        assert_eq!(
+            hex::encode(data),
+            // the offset of the string, the number and the offset of the bytes
+            words(&["60", "7", "a0"])
+                + &words(&["2"])
+                + &padded("6869")
+                + &words(&["2"])
+                + &padded("0102")
+        );
+    }
+
+    #[test]
+    fn array_args() {
+        assert_eq!(
+            encode("uint256[]", r#"[1, "0x2"]"#).unwrap(),
+            words(&["20", "2", "1", "2"])
+        );
+        assert_eq!(encode("uint256[]", "[]").unwrap(), words(&["20", "0"]));
+        assert_eq!(
+            encode("bool[2]", "[true, false]").unwrap(),
+            words(&["1", "0"])
+        );
+        assert_eq!(
+            encode("bool[2]", "[true]").unwrap_err(),
+            "Invalid argument `x`: expected 2 items for `bool[2]`, found 1"
+        );
+        assert!(encode("uint256[]", "1, 2")
+            .unwrap_err()
+            .starts_with("Invalid argument `x`: "));
+    }
+
+    #[test]
+    fn tuple_args() {
+        let tuple = |components: &str| {
+            let inputs = format!(
+                r#"[{{"name": "x", "type": "tuple", "components": {}}}]"#,
+                components
+            );
+            constructor(&inputs)
+        };
+
+        let static_tuple =
+            tuple(r#"[{"name": "a", "type": "uint256"}, {"name": "b", "type": "bool"}]"#);
+        assert_eq!(
+            deployment_data(&[], &static_tuple, &["[5, true]"]).map(hex::encode),
+            Ok(words(&["5", "1"]))
+        );
+        assert_eq!(
+            deployment_data(&[], &static_tuple, &["[5]"]).unwrap_err(),
+            "Invalid argument `x`: expected 2 items for `(uint256,bool)`, found 1"
+        );
+
+        let dynamic_tuple =
+            tuple(r#"[{"name": "a", "type": "uint256"}, {"name": "b", "type": "string"}]"#);
+        assert_eq!(
+            deployment_data(&[], &dynamic_tuple, &[r#"[5, "hi"]"#]).map(hex::encode),
+            // the offset of the tuple, then its number, the offset of its
+            // string and the string
+            Ok(words(&["20", "5", "40", "2"]) + &padded("6869"))
+        );
+    }
+
+    #[test]
+    fn arg_count() {
+        let constructor = constructor(r#"[{"name": "x", "type": "uint256"}]"#);
+        assert_eq!(
+            deployment_data(&[], &constructor, &[]).unwrap_err(),
+            "The constructor takes 1 argument (x: uint256), but 0 were given"
+        );
+    }
+
+    #[test]
+    fn eip_1014_create2_addresses() {
+        // examples 1 and 5 of EIP-1014
+        let address = |text| parse_address(text).unwrap();
+        assert_eq!(
+            create2_address(
+                address("0xdeadbeef00000000000000000000000000000000"),
+                parse_uint("0x00").unwrap(),
+                &[0x00],
+            ),
+            address("0xb928f69bb1d91cd65274e3c79d8986362984fda3")
+        );
+        assert_eq!(
+            create2_address(
+                address("0x00000000000000000000000000000000deadbeef"),
+                parse_uint("0xcafebabe").unwrap(),
+                &[0xde, 0xad, 0xbe, 0xef],
+            ),
+            address("0x60f3f640a8508fc6a86d45df051962668e1e8ac7")
+        );
+    }
+}
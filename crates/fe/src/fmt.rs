@@ -0,0 +1,69 @@
+//! The `fe fmt` subcommand, which formats `.fe` files in place, or with
+//! `--check`, lists the files that aren't formatted without changing them.
+
+use crate::summary::ExitCode;
+use clap::ArgMatches;
+use fe_common::diagnostics::print_diagnostics;
+use fe_common::files::FileStore;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Formats the given files, and the `.fe` files in the given directories.
+///
+/// A file that doesn't parse is left as it is, and its errors are printed.
+pub fn run(matches: &ArgMatches) -> ExitCode {
+    let check = matches.is_present("check");
+    let mut files = FileStore::new();
+    let mut exit_code = ExitCode::Success;
+
+    for path in matches.values_of("paths").unwrap().flat_map(source_files) {
+        let display = path.display();
+        let (content, file_id) = match files.load_file(&path.to_string_lossy()) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                eprintln!("Failed to read `{}`. Error: {}", display, err);
+                return ExitCode::IoError;
+            }
+        };
+
+        let formatted = match fe_fmt::format_module(file_id, &content) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                print_diagnostics(&diagnostics, &files);
+                exit_code = ExitCode::ParseError;
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+
+        if check {
+            println!("{} is not formatted", display);
+            if exit_code == ExitCode::Success {
+                exit_code = ExitCode::Failure;
+            }
+        } else if let Err(err) = fs::write(&path, formatted) {
+            eprintln!("Failed to write `{}`. Error: {}", display, err);
+            return ExitCode::IoError;
+        }
+    }
+    exit_code
+}
+
+/// The file at the path, or the `.fe` files in the directory at the path.
+fn source_files(path: &str) -> Vec<PathBuf> {
+    let path = Path::new(path);
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+    WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension() == Some(OsStr::new("fe")))
+        .map(|entry| entry.into_path())
+        .collect()
+}
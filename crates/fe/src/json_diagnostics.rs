@@ -0,0 +1,215 @@
+//! Diagnostics as JSON, for `--error-format=json`. Each diagnostic is printed
+//! to stderr as a JSON object on its own line, eg.
+//!
+//! ```json
+//! {
+//!   "schemaVersion": 1,
+//!   "severity": "error",
+//!   "code": null,
+//!   "message": "undefined function",
+//!   "file": "src/main.fe",
+//!   "span": { "start": 52, "end": 55, "line": 3, "column": 16 },
+//!   "labels": [
+//!     {
+//!       "style": "primary",
+//!       "file": "src/main.fe",
+//!       "span": { "start": 52, "end": 55, "line": 3, "column": 16 },
+//!       "message": "`foo` is undefined"
+//!     }
+//!   ],
+//!   "notes": []
+//! }
+//! ```
+//!
+//! `start` and `end` are byte offsets, and `line` and `column` are 1-based.
+//! The column counts characters, so a multi-byte character is one column.
+//! `file` and `span` are those of the primary label, and are null if the
+//! diagnostic has no labels.
+
+use fe_common::diagnostics::{Diagnostic, Label, LabelStyle, Severity};
+use fe_common::files::{ColumnUnit, FileStore};
+use fe_common::Span;
+use fe_driver::artifacts;
+use serde_json::{json, Value};
+
+/// Prints the diagnostics to stderr, one JSON object per line.
+pub fn print_diagnostics(diagnostics: &[Diagnostic], files: &FileStore) {
+    for diagnostic in diagnostics {
+        eprintln!(
+            "{}",
+            artifacts::versioned(diagnostic_json(diagnostic, files))
+        );
+    }
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic, files: &FileStore) -> Value {
+    let primary = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .or_else(|| diagnostic.labels.first());
+
+    json!({
+        "severity": match diagnostic.severity {
+            Severity::Help => "help",
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Bug => "bug",
+        },
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "file": primary.map(|label| file_name(label.span, files)),
+        "span": primary.map(|label| span_json(label.span, files)),
+        "labels": diagnostic
+            .labels
+            .iter()
+            .map(|label| label_json(label, files))
+            .collect::<Vec<_>>(),
+        "notes": diagnostic.notes,
+    })
+}
+
+fn label_json(label: &Label, files: &FileStore) -> Value {
+    json!({
+        "style": match label.style {
+            LabelStyle::Primary => "primary",
+            LabelStyle::Secondary => "secondary",
+        },
+        "file": file_name(label.span, files),
+        "span": span_json(label.span, files),
+        "message": label.message,
+    })
+}
+
+fn file_name(span: Span, files: &FileStore) -> Option<&str> {
+    files.get_file(span.file_id).map(|file| file.name.as_str())
+}
+
+fn span_json(span: Span, files: &FileStore) -> Value {
+    let mut value = json!({
+        "start": span.start,
+        "end": span.end,
+    });
+    if let Some(file) = files.get_file(span.file_id) {
+        let (line, column) = file.line_column(span.start, ColumnUnit::Char);
+        value["line"] = json!(line + 1);
+        value["column"] = json!(column + 1);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diagnostic_json;
+    use fe_common::diagnostics::{Diagnostic, Label, Severity};
+    use fe_common::files::{FileStore, SourceFileId};
+    use fe_common::Span;
+    use serde_json::json;
+
+    // `föo` is at bytes 40..44 of the third line, and the `ö` is two bytes.
+    const SRC: &str = "contract Foo:\n    pub fn bar():\n        föo()\n";
+
+    fn diagnostic(severity: Severity, labels: Vec<Label>) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: None,
+            message: "undefined function".into(),
+            labels,
+            notes: vec![],
+        }
+    }
+
+    #[test]
+    fn spans_and_labels() {
+        let mut files = FileStore::new();
+        let file_id = files.add_virtual_file("src/main.fe", SRC);
+        let diagnostic = Diagnostic {
+            code: Some("E0001".into()),
+            notes: vec!["Note: functions are declared with `fn`".into()],
+            // the secondary label comes first, but `file` and `span` are
+            // those of the primary label
+            ..diagnostic(
+                Severity::Error,
+                vec![
+                    Label::secondary(Span::new(file_id, 18, 31), "in this function"),
+                    Label::primary(Span::new(file_id, 43, 44), "here"),
+                ],
+            )
+        };
+
+        assert_eq!(
+            diagnostic_json(&diagnostic, &files),
+            json!({
+                "severity": "error",
+                "code": "E0001",
+                "message": "undefined function",
+                "file": "src/main.fe",
+                // the column counts the `ö` as one character
+                "span": { "start": 43, "end": 44, "line": 3, "column": 11 },
+                "labels": [
+                    {
+                        "style": "secondary",
+                        "file": "src/main.fe",
+                        "span": { "start": 18, "end": 31, "line": 2, "column": 5 },
+                        "message": "in this function",
+                    },
+                    {
+                        "style": "primary",
+                        "file": "src/main.fe",
+                        "span": { "start": 43, "end": 44, "line": 3, "column": 11 },
+                        "message": "here",
+                    },
+                ],
+                "notes": ["Note: functions are declared with `fn`"],
+            })
+        );
+    }
+
+    #[test]
+    fn without_primary_label() {
+        let mut files = FileStore::new();
+        let file_id = files.add_virtual_file("src/main.fe", SRC);
+
+        // the first label stands in for the primary label
+        let json = diagnostic_json(
+            &diagnostic(
+                Severity::Warning,
+                vec![Label::secondary(Span::new(file_id, 40, 44), "")],
+            ),
+            &files,
+        );
+        assert_eq!(
+            json["span"],
+            json!({ "start": 40, "end": 44, "line": 3, "column": 9 })
+        );
+
+        let json = diagnostic_json(&diagnostic(Severity::Bug, vec![]), &files);
+        assert_eq!(json["file"], json!(null));
+        assert_eq!(json["span"], json!(null));
+        assert_eq!(json["labels"], json!([]));
+
+        // a span in a file that isn't in the store has no line and column
+        let unknown = Span::new(SourceFileId(0), 1, 2);
+        let json = diagnostic_json(
+            &diagnostic(Severity::Error, vec![Label::primary(unknown, "")]),
+            &files,
+        );
+        assert_eq!(json["file"], json!(null));
+        assert_eq!(json["span"], json!({ "start": 1, "end": 2 }));
+    }
+
+    #[test]
+    fn severities() {
+        let files = FileStore::new();
+        let severities = [
+            Severity::Help,
+            Severity::Note,
+            Severity::Warning,
+            Severity::Error,
+            Severity::Bug,
+        ]
+        .map(|severity| diagnostic_json(&diagnostic(severity, vec![]), &files)["severity"].clone());
+        assert_eq!(severities, ["help", "note", "warning", "error", "bug"]);
+    }
+}
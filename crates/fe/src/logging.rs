@@ -0,0 +1,61 @@
+//! How much the CLI prints, as set with `-q` and `-v`. By default it prints
+//! its status messages, diagnostics and any warnings logged by the compiler.
+//! `-q` prints only errors, for use in scripts, and each `-v` logs more of
+//! what the compiler is doing to stderr, eg. the time spent in each phase
+//! with `-vv`, for bug reports.
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+    Trace,
+}
+
+impl Verbosity {
+    /// The verbosity given by the `-q` flag and the number of `-v` flags.
+    pub fn from_flags(quiet: bool, verbose: u64) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, 2) => Verbosity::Debug,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+
+    /// Returns true if status messages and warnings are left out.
+    pub fn is_quiet(self) -> bool {
+        self == Verbosity::Quiet
+    }
+
+    fn level(self) -> LevelFilter {
+        match self {
+            Verbosity::Quiet => LevelFilter::ERROR,
+            Verbosity::Normal => LevelFilter::WARN,
+            Verbosity::Verbose => LevelFilter::INFO,
+            Verbosity::Debug => LevelFilter::DEBUG,
+            Verbosity::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Logs the events of the compiler crates to stderr, up to the level of
+/// `verbosity`. From `-vv` on, the time spent in each span is logged when it
+/// closes.
+pub fn init(verbosity: Verbosity) {
+    let span_events = if verbosity >= Verbosity::Debug {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(verbosity.level())
+        .with_span_events(span_events)
+        .with_writer(std::io::stderr)
+        .init();
+}
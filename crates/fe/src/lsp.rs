@@ -0,0 +1,697 @@
+//! The `fe lsp` subcommand, a language server that speaks the Language Server
+//! Protocol over stdin and stdout.
+//!
+//! Whenever a `.fe` document is opened or changed, it's parsed and analyzed
+//! with the unsaved contents of the open documents, and the errors and
+//! warnings are sent to the editor. A document in the source directory of a
+//! project (see [`crate::manifest`]) is checked with the rest of the project's
+//! ingot, and any other document is checked on its own.
+//!
+//! The server also finds the references to a variable, parameter or function,
+//! and renames variables and parameters, within the module of the document.
+//! These requests are answered from the analyzer's name resolution, so they
+//! need the module to analyze without errors.
+//!
+//! The checks run on their own threads, so that the server keeps reading
+//! messages while a large project is checked. A check that is superseded by a
+//! newer one of the same project or document is cancelled, and its
+//! diagnostics are never published.
+
+use crate::manifest::Manifest;
+use fe_common::diagnostics::{Diagnostic, LabelStyle, Severity};
+use fe_common::files::{ColumnUnit, FileStore, SourceFile, SourceFileId};
+use fe_common::Span;
+use fe_driver::CancellationToken;
+use fe_parser::ast::SmolStr;
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use walkdir::WalkDir;
+
+/// The `textDocumentSync` kind in which every change sends the whole document.
+const FULL_SYNC: u64 = 1;
+/// The JSON-RPC error code of an unknown request.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The LSP error code of a valid request that couldn't be answered, e.g. a
+/// rename to a name that's taken.
+const REQUEST_FAILED: i64 = -32803;
+
+/// Runs the server until the client asks it to exit, or closes stdin.
+/// Returns false if the server wasn't shut down cleanly.
+pub fn run() -> bool {
+    let (events, received) = mpsc::channel();
+    let messages = events.clone();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        loop {
+            let message = read_message(&mut input);
+            let end = !matches!(message, Ok(Some(_)));
+            if messages.send(Event::Message(message)).is_err() || end {
+                return;
+            }
+        }
+    });
+
+    let stdout = io::stdout();
+    let mut server = Server {
+        documents: HashMap::new(),
+        checks: HashMap::new(),
+        events,
+        output: stdout.lock(),
+        shut_down: false,
+    };
+    for event in received {
+        let result = match event {
+            Event::Message(Ok(Some(message))) => server.handle(&message),
+            Event::Message(Ok(None)) => return false,
+            Event::Message(Err(err)) => {
+                eprintln!("Failed to read a message: {}", err);
+                return false;
+            }
+            Event::Checked { cancel, published } => server.publish_check(&cancel, published),
+        };
+        match result {
+            Ok(true) => {}
+            Ok(false) => return server.shut_down,
+            Err(err) => {
+                eprintln!("Failed to write a message: {}", err);
+                return false;
+            }
+        }
+    }
+    false
+}
+
+/// The events that the server handles, in the order they happen.
+enum Event {
+    /// A message from the client, or `None` at the end of the input.
+    Message(io::Result<Option<Value>>),
+    /// A check has finished, with the LSP diagnostics of each file that it
+    /// checked.
+    Checked {
+        cancel: CancellationToken,
+        published: Vec<(PathBuf, Vec<Value>)>,
+    },
+}
+
+struct Server<W> {
+    /// The contents of the open documents, which take precedence over the
+    /// files on disk.
+    documents: HashMap<PathBuf, String>,
+    /// The token of the latest check of each project, by its source
+    /// directory, and of each document outside of a project, by its path.
+    checks: HashMap<PathBuf, CancellationToken>,
+    events: Sender<Event>,
+    output: W,
+    shut_down: bool,
+}
+
+impl<W: Write> Server<W> {
+    /// Handles a request or notification. Returns false once the client has
+    /// asked the server to exit.
+    fn handle(&mut self, message: &Value) -> io::Result<bool> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id");
+
+        match method {
+            "initialize" => self.respond(
+                id,
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": FULL_SYNC,
+                        "referencesProvider": true,
+                        "renameProvider": true,
+                    },
+                    "serverInfo": { "name": "fe", "version": crate::VERSION },
+                }),
+            )?,
+            "shutdown" => {
+                self.shut_down = true;
+                self.respond(id, Value::Null)?
+            }
+            "exit" => return Ok(false),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let (Some(path), Some(text)) =
+                    (document_path(document), document["text"].as_str())
+                {
+                    self.documents.insert(path.clone(), text.to_string());
+                    self.check(&path);
+                }
+            }
+            "textDocument/didChange" => {
+                // With full sync, the last change holds the whole document.
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(path), Some(text)) = (document_path(&params["textDocument"]), text) {
+                    self.documents.insert(path.clone(), text.to_string());
+                    self.check(&path);
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(path) = document_path(&params["textDocument"]) {
+                    self.documents.remove(&path);
+                    if let Some(cancel) = self.checks.remove(&path) {
+                        cancel.cancel();
+                    }
+                    self.publish(&path, vec![])?;
+                }
+            }
+            "textDocument/references" => {
+                let locations = self.references(params);
+                self.respond(id, locations.unwrap_or(Value::Null))?
+            }
+            "textDocument/rename" => match self.rename(params) {
+                Ok(edit) => self.respond(id, edit)?,
+                Err(message) => self.respond_error(id, REQUEST_FAILED, &message)?,
+            },
+            _ => {
+                // Requests must be answered, but unknown notifications, like
+                // `initialized`, can be ignored.
+                if id.is_some() {
+                    let message = format!("unsupported method `{}`", method);
+                    self.respond_error(id, METHOD_NOT_FOUND, &message)?
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Starts a check of the document at `path`, with the rest of its
+    /// project if it's in one. The previous check of the same project or
+    /// document is cancelled.
+    fn check(&mut self, path: &Path) {
+        let project = project(path);
+        let key = project
+            .as_ref()
+            .map_or_else(|| path.to_path_buf(), |manifest| manifest.src_dir.clone());
+
+        let cancel = CancellationToken::new();
+        if let Some(previous) = self.checks.insert(key, cancel.clone()) {
+            previous.cancel();
+        }
+
+        let path = path.to_path_buf();
+        let documents = self.documents.clone();
+        let events = self.events.clone();
+        thread::spawn(move || {
+            if let Some(published) = check(&path, project, &documents, &cancel) {
+                // The server is gone if the event can't be sent.
+                let _ = events.send(Event::Checked { cancel, published });
+            }
+        });
+    }
+
+    /// Publishes the diagnostics of a finished check, unless a newer check
+    /// has superseded it.
+    fn publish_check(
+        &mut self,
+        cancel: &CancellationToken,
+        published: Vec<(PathBuf, Vec<Value>)>,
+    ) -> io::Result<bool> {
+        if !cancel.is_cancelled() {
+            for (path, diagnostics) in published {
+                self.publish(&path, diagnostics)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// The locations of the symbol at the position of a `references` request,
+    /// or `None` if there's no symbol there.
+    fn references(&self, params: &Value) -> Option<Value> {
+        let (input, span) = self.locate(params)?;
+        let symbol = fe_driver::find_references(&input.files, &input.deps, input.ingot(), span)?;
+
+        let mut spans = vec![];
+        if params["context"]["includeDeclaration"].as_bool() == Some(true) {
+            spans.push(symbol.definition);
+        }
+        spans.extend(symbol.references);
+        Some(Value::Array(
+            spans
+                .into_iter()
+                .filter_map(|span| {
+                    let (uri, file) = input.file(span.file_id)?;
+                    Some(json!({ "uri": uri, "range": range(file, span) }))
+                })
+                .collect(),
+        ))
+    }
+
+    /// The workspace edit of a `rename` request, or the reason the symbol at
+    /// its position can't be renamed.
+    fn rename(&self, params: &Value) -> Result<Value, String> {
+        let new_name = params["newName"].as_str().unwrap_or_default();
+        let (input, span) = self
+            .locate(params)
+            .ok_or_else(|| "the document couldn't be read".to_string())?;
+        let edits = fe_driver::rename(&input.files, &input.deps, input.ingot(), span, new_name)
+            .ok_or_else(|| "the module has errors".to_string())?
+            .map_err(|err| err.to_string())?;
+
+        let mut changes = serde_json::Map::new();
+        for edit in edits {
+            if let Some((uri, file)) = input.file(edit.span.file_id) {
+                let edit = json!({ "range": range(file, edit.span), "newText": edit.new_text });
+                match changes.entry(uri).or_insert_with(|| json!([])) {
+                    Value::Array(file_edits) => file_edits.push(edit),
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Ok(json!({ "changes": changes }))
+    }
+
+    /// Loads the document of a request, with the rest of its project, and
+    /// returns the empty span at the request's position.
+    fn locate(&self, params: &Value) -> Option<(Input, Span)> {
+        let path = document_path(&params["textDocument"])?;
+        let input = Input::load(&path, project(&path), &self.documents)?;
+        let id = *input.paths.iter().find(|(_, loaded)| **loaded == path)?.0;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let character = params["position"]["character"].as_u64()? as usize;
+        let offset = input
+            .files
+            .get_file(id)?
+            .byte_index(line, character, ColumnUnit::Utf16)?;
+        Some((input, Span::new(id, offset, offset)))
+    }
+
+    fn publish(&mut self, path: &Path, diagnostics: Vec<Value>) -> io::Result<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": path_uri(path),
+                "diagnostics": diagnostics,
+            },
+        }))
+    }
+
+    fn respond(&mut self, id: Option<&Value>, result: Value) -> io::Result<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id.cloned().unwrap_or(Value::Null),
+            "result": result,
+        }))
+    }
+
+    fn respond_error(&mut self, id: Option<&Value>, code: i64, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id.cloned().unwrap_or(Value::Null),
+            "error": { "code": code, "message": message },
+        }))
+    }
+
+    fn send(&mut self, message: Value) -> io::Result<()> {
+        write_message(&mut self.output, &message)
+    }
+}
+
+/// Checks the document at `path`, with the rest of its `project` if it's in
+/// one, and returns the LSP diagnostics of each checked file. Every checked
+/// file is returned, so that the diagnostics of the files that were fixed are
+/// cleared. Returns `None` if the check was cancelled.
+fn check(
+    path: &Path,
+    project: Option<Manifest>,
+    documents: &HashMap<PathBuf, String>,
+    cancel: &CancellationToken,
+) -> Option<Vec<(PathBuf, Vec<Value>)>> {
+    let mut input = Input::load(path, project, documents)?;
+    let diagnostics = match input.ingot() {
+        Some((name, file_ids)) => {
+            fe_driver::check_ingot(name, &input.files, file_ids, &input.deps, cancel)?
+        }
+        None => {
+            let (id, _) = input.paths.iter().next()?;
+            fe_driver::check_module(&input.files, *id, &input.deps, cancel)?
+        }
+    };
+
+    let mut by_file = input
+        .paths
+        .keys()
+        .map(|id| (*id, vec![]))
+        .collect::<HashMap<SourceFileId, Vec<Value>>>();
+    for diagnostic in &diagnostics {
+        if let Some((id, lsp_diagnostic)) = lsp_diagnostic(diagnostic, &input.files) {
+            if let Some(file_diagnostics) = by_file.get_mut(&id) {
+                file_diagnostics.push(lsp_diagnostic);
+            }
+        }
+    }
+    Some(
+        by_file
+            .into_iter()
+            .map(|(id, diagnostics)| {
+                let path = input.paths.remove(&id).expect("missing path");
+                (path, diagnostics)
+            })
+            .collect(),
+    )
+}
+
+/// The project whose source directory contains the document at `path`, if
+/// any.
+fn project(path: &Path) -> Option<Manifest> {
+    path.ancestors()
+        .skip(1)
+        .find_map(Manifest::find)
+        .and_then(|manifest| Manifest::load(&manifest).ok())
+        .filter(|manifest| path.starts_with(&manifest.src_dir))
+}
+
+/// The files that a document is analyzed with: the source files of its
+/// project, or just the document. Open documents are read from their unsaved
+/// contents.
+struct Input {
+    files: FileStore,
+    /// The path of each file of the project, or of the document.
+    paths: HashMap<SourceFileId, PathBuf>,
+    deps: IndexMap<SmolStr, Vec<SourceFileId>>,
+    /// The name and files of the project's ingot, if the document is in a
+    /// project.
+    ingot: Option<(String, Vec<SourceFileId>)>,
+}
+
+impl Input {
+    /// Loads the document at `path`, with the rest of its `project` if it's
+    /// in one. Returns `None` if the document couldn't be read.
+    fn load(
+        path: &Path,
+        project: Option<Manifest>,
+        documents: &HashMap<PathBuf, String>,
+    ) -> Option<Self> {
+        let mut files = FileStore::new();
+        for (path, text) in documents {
+            files.set_overlay(path.clone(), text);
+        }
+        let mut paths = HashMap::new();
+
+        let ingot = if let Some(manifest) = project {
+            let file_ids = WalkDir::new(&manifest.src_dir)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension() == Some(OsStr::new("fe")))
+                .filter_map(|entry| {
+                    let path = entry.into_path();
+                    let id = load_file(&mut files, &path)?;
+                    paths.insert(id, path);
+                    Some(id)
+                })
+                .collect::<Vec<_>>();
+            Some((manifest.name, file_ids))
+        } else {
+            let id = load_file(&mut files, path)?;
+            paths.insert(id, path.to_path_buf());
+            None
+        };
+        let deps = files.add_included_libraries();
+        Some(Input {
+            files,
+            paths,
+            deps,
+            ingot,
+        })
+    }
+
+    /// The name and files of the project's ingot, or `None` if the document
+    /// is analyzed on its own.
+    fn ingot(&self) -> Option<(&str, &[SourceFileId])> {
+        let (name, file_ids) = self.ingot.as_ref()?;
+        Some((name.as_str(), file_ids.as_slice()))
+    }
+
+    /// The URI of a file of the input, and the file.
+    fn file(&self, id: SourceFileId) -> Option<(String, &SourceFile)> {
+        Some((path_uri(self.paths.get(&id)?), self.files.get_file(id)?))
+    }
+}
+
+/// Loads the file at `path`, which is read from disk unless it's open.
+fn load_file(files: &mut FileStore, path: &Path) -> Option<SourceFileId> {
+    files
+        .load_file(&path.to_string_lossy())
+        .ok()
+        .map(|(_, id)| id)
+}
+
+/// Writes a message with its `Content-Length` header.
+pub(crate) fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+/// Reads a message, which is a JSON body preceded by a `Content-Length`
+/// header. Returns `None` at the end of the input.
+pub(crate) fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing `Content-Length` header",
+        )
+    })?;
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Converts a diagnostic to an LSP diagnostic, with the range of its primary
+/// label. Returns the file of the label too, or `None` if the diagnostic has
+/// no labels.
+fn lsp_diagnostic(diagnostic: &Diagnostic, files: &FileStore) -> Option<(SourceFileId, Value)> {
+    let label = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .or_else(|| diagnostic.labels.first())?;
+    let file = files.get_file(label.span.file_id)?;
+
+    let mut message = diagnostic.message.clone();
+    if !label.message.is_empty() {
+        message = format!("{}: {}", message, label.message);
+    }
+    for note in &diagnostic.notes {
+        message = format!("{}\n{}", message, note);
+    }
+
+    Some((
+        file.id,
+        json!({
+            "range": range(file, label.span),
+            "severity": match diagnostic.severity {
+                Severity::Error | Severity::Bug => 1,
+                Severity::Warning => 2,
+                Severity::Note => 3,
+                Severity::Help => 4,
+            },
+            "source": "fe",
+            "message": message,
+        }),
+    ))
+}
+
+/// The LSP range of a span in a file.
+fn range(file: &SourceFile, span: Span) -> Value {
+    json!({ "start": position(file, span.start), "end": position(file, span.end) })
+}
+
+/// The LSP position of a byte offset, whose character is counted in UTF-16
+/// code units.
+fn position(file: &SourceFile, offset: usize) -> Value {
+    let (line, character) = file.line_column(offset, ColumnUnit::Utf16);
+    json!({ "line": line, "character": character })
+}
+
+/// The path of a `file://` document.
+fn document_path(document: &Value) -> Option<PathBuf> {
+    let path = document["uri"].as_str()?.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| path.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// The `file://` URI of a path.
+fn path_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri += &format!("%{:02X}", byte);
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{path_uri, read_message, Server};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::mpsc;
+
+    const PATH: &str = "/fe-lsp-test/main.fe";
+    const SRC: &str = "contract Foo:
+    pub fn f(x: u256) -> u256:
+        let y: u256 = x + 1
+        return y * y
+";
+
+    /// Sends a request about the open document `SRC` to a server, and
+    /// returns the response.
+    fn request(method: &str, params: Value) -> Value {
+        let (events, _received) = mpsc::channel();
+        let mut server = Server {
+            documents: HashMap::new(),
+            checks: HashMap::new(),
+            events,
+            output: vec![],
+            shut_down: false,
+        };
+        server.documents.insert(PATH.into(), SRC.to_string());
+
+        let mut params = params;
+        params["textDocument"] = json!({ "uri": path_uri(Path::new(PATH)) });
+        server
+            .handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .unwrap();
+        read_message(&mut server.output.as_slice())
+            .unwrap()
+            .expect("no response")
+    }
+
+    /// The `(line, character)` of the start of each range.
+    fn starts<'a>(ranges: impl Iterator<Item = &'a Value>) -> Vec<(u64, u64)> {
+        ranges
+            .map(|range| {
+                let start = &range["start"];
+                (
+                    start["line"].as_u64().unwrap(),
+                    start["character"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn references() {
+        // the first `y` in `y * y`
+        let response = request(
+            "textDocument/references",
+            json!({
+                "position": { "line": 3, "character": 15 },
+                "context": { "includeDeclaration": true },
+            }),
+        );
+        let locations = response["result"].as_array().unwrap();
+        assert!(locations
+            .iter()
+            .all(|location| location["uri"] == "file:///fe-lsp-test/main.fe"));
+        assert_eq!(
+            starts(locations.iter().map(|location| &location["range"])),
+            vec![(2, 12), (3, 15), (3, 19)]
+        );
+
+        let response = request(
+            "textDocument/references",
+            json!({
+                "position": { "line": 2, "character": 12 },
+                "context": { "includeDeclaration": false },
+            }),
+        );
+        assert_eq!(response["result"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn references_without_symbol() {
+        // `contract`
+        let response = request(
+            "textDocument/references",
+            json!({
+                "position": { "line": 0, "character": 2 },
+                "context": { "includeDeclaration": true },
+            }),
+        );
+        assert_eq!(response["result"], Value::Null);
+    }
+
+    #[test]
+    fn rename() {
+        let response = request(
+            "textDocument/rename",
+            json!({ "position": { "line": 2, "character": 12 }, "newName": "z" }),
+        );
+        let edits = response["result"]["changes"]["file:///fe-lsp-test/main.fe"]
+            .as_array()
+            .unwrap();
+        assert!(edits.iter().all(|edit| edit["newText"] == "z"));
+        assert_eq!(
+            starts(edits.iter().map(|edit| &edit["range"])),
+            vec![(2, 12), (3, 15), (3, 19)]
+        );
+    }
+
+    #[test]
+    fn rename_conflict() {
+        let response = request(
+            "textDocument/rename",
+            json!({ "position": { "line": 2, "character": 12 }, "newName": "x" }),
+        );
+        assert_eq!(response["error"]["code"], -32803);
+        assert_eq!(response["error"]["message"], "`x` is already defined");
+    }
+}
@@ -1,44 +1,105 @@
 //! The `fe` command-line interface.
 
 use std::fs;
-use std::io::{Error, Write};
-use std::path::Path;
+use std::io::{Error, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-use clap::{arg_enum, values_t, App, Arg};
+use clap::{arg_enum, value_t, App, AppSettings, Arg, SubCommand};
 
-use fe_common::diagnostics::print_diagnostics;
+use fe_abi::solidity;
+use fe_abi::standards::{self, Standard};
+use fe_analyzer::lints::{self, Lint, LintLevel};
+use fe_common::diagnostics::{print_diagnostics, Diagnostic, Severity};
 use fe_common::files::{FileStore, SourceFileId};
 use fe_common::panic::install_panic_hook;
-use fe_driver::CompiledModule;
+use fe_driver::{
+    CancellationToken, ChainProfile, CompileOptions, CompiledContract, CompiledModule, DebugEvents,
+    EvmVersion, LoweringPlugin, RecordDbg,
+};
+use indexmap::IndexMap;
 use std::ffi::OsStr;
+use tracing::debug;
 use walkdir::WalkDir;
 
+mod encode_deploy;
+mod fmt;
+mod json_diagnostics;
+mod logging;
+mod lsp;
+mod manifest;
+mod output_selection;
+mod serve;
+mod staging;
+mod standard_json;
+mod summary;
+mod test_runner;
+
+use logging::Verbosity;
+use manifest::{Manifest, Profile};
+use output_selection::{OutputSelection, TARGET_NAMES};
+use staging::StagingDir;
+use summary::{DiagnosticCounts, ExitCode};
+
 const DEFAULT_OUTPUT_DIR_NAME: &str = "output";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// How often `--watch` checks the source files for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 arg_enum! {
     #[derive(PartialEq, Debug)]
     pub enum CompilationTarget {
         Abi,
         Ast,
+        Cst,
         LoweredAst,
+        Mir,
+        Resources,
+        SolidityInterface,
+        FeInterface,
         Bytecode,
+        RuntimeBytecode,
+        CodeSize,
         Tokens,
         Yul,
+        YulObject,
+        Wasm,
+    }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum ErrorFormat {
+        Human,
+        Json,
     }
 }
 
 pub fn main() {
     install_panic_hook();
 
+    let emit_long_help = format!(
+        "Comma separated compile targets e.g. -e=bytecode,yul. The targets of each contract can \
+         be selected with semicolon separated entries, e.g. -e='Token=abi,bytecode;*=abi' \
+         writes the ABI and bytecode of `Token` and only the ABI of the other contracts.\n\
+         [possible values: {}]",
+        TARGET_NAMES.join(", ")
+    );
+    let chain_names = ChainProfile::ALL
+        .iter()
+        .map(|chain| chain.name())
+        .collect::<Vec<_>>();
     let matches = App::new("Fe")
         .version(VERSION)
         .about("Compiler for the Fe language")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("input")
-                .help("The input source file to use e.g erc20.fe")
+                .help("The input source file, ingot directory or project to use e.g erc20.fe")
                 .index(1)
-                .required(true),
+                .required_unless("standard-json"),
         )
         .arg(
             Arg::with_name("output-dir")
@@ -52,16 +113,65 @@ pub fn main() {
             Arg::with_name("emit")
                 .short("e")
                 .long("emit")
-                .help("Comma separated compile targets e.g. -e=bytecode,yul")
-                .possible_values(&["abi", "bytecode", "ast", "tokens", "yul", "loweredAst"])
+                .help("Comma separated compile targets e.g. -e=bytecode,yul, or the targets of each contract e.g. -e='Token=abi,bytecode;*=abi'")
+                .long_help(&emit_long_help)
+                .validator(|value| OutputSelection::parse(&value).map(|_| ()))
                 .default_value("abi,bytecode")
-                .use_delimiter(true)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("error-format")
+                .long("error-format")
+                .help("How to print errors and warnings, as text for people or as a JSON object per line")
+                .possible_values(&["human", "json"])
+                .default_value("human")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .help("Prints only errors")
+                .conflicts_with("verbose")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Logs what the compiler is doing to stderr, in more detail with -vv and -vvv")
+                .multiple(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("json-summary")
+                .long("json-summary")
+                .help("Writes the exit status and the number of errors and warnings to a JSON file")
+                .value_name("FILE")
+                .takes_value(true)
+                .conflicts_with_all(&["standard-json", "watch"])
+                .global(true),
+        )
         .arg(
             Arg::with_name("overwrite")
                 .long("overwrite")
-                .help("Overwrite contents of output directory`"),
+                .help("Writes into a non-empty output directory, replacing files of the same name"),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .help("Recompiles the input whenever one of its source files changes"),
+        )
+        .arg(
+            Arg::with_name("debug-events")
+                .long("debug-events")
+                .help("Emits debug events on function entry, exit and revert, for use on development chains"),
+        )
+        .arg(
+            Arg::with_name("standard-json")
+                .long("standard-json")
+                .help("Reads a JSON description of the sources and outputs from stdin, and writes the results to stdout as JSON")
+                .conflicts_with_all(&["input", "watch"]),
         )
         .arg(
             Arg::with_name("optimize")
@@ -72,115 +182,749 @@ pub fn main() {
                 .use_delimiter(false)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("chain-profile")
+                .long("chain-profile")
+                .help("The chain to build for. Opcodes that it doesn't support are errors, and bytecode for L2 chains is compiled without `PUSH0`")
+                .possible_values(&chain_names)
+                .default_value("ethereum")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("erc20")
+                .long("erc20")
+                .help("Checks that the named contract implements the ERC-20 interface")
+                .value_name("CONTRACT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("erc721")
+                .long("erc721")
+                .help("Checks that the named contract implements the ERC-721 interface")
+                .value_name("CONTRACT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("erc1155")
+                .long("erc1155")
+                .help("Checks that the named contract implements the ERC-1155 interface")
+                .value_name("CONTRACT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .subcommand(
+            SubCommand::with_name("test")
+                .about("Runs the tests of the input's contracts, which are their public functions named `test*` that take no arguments")
+                .arg(
+                    Arg::with_name("input")
+                        .help("The input source file, ingot directory or project to test")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("gas-snapshot")
+                        .long("gas-snapshot")
+                        .help("Checks the gas used by each test against `.gas-snapshot`, then updates it"),
+                )
+                .arg(
+                    Arg::with_name("gas-threshold")
+                        .long("gas-threshold")
+                        .help("The percentage by which a test may use more gas than its snapshot")
+                        .value_name("PERCENT")
+                        .default_value("0")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("encode-deploy")
+                .about("Prints the data of a transaction that deploys a compiled contract, which is its init code followed by its encoded constructor arguments")
+                .arg(
+                    Arg::with_name("artifact")
+                        .help("The output directory of the contract, e.g. `output/Token`, or its bytecode file")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("args")
+                        .help("The constructor arguments, in order. Arrays and tuples are given as JSON, e.g. `[1, 2]`")
+                        .index(2)
+                        .multiple(true)
+                        .allow_hyphen_values(true),
+                )
+                .arg(
+                    Arg::with_name("deployer")
+                        .long("deployer")
+                        .help("The address that deploys the contract with CREATE2, whose address is printed on a second line")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .requires("salt"),
+                )
+                .arg(
+                    Arg::with_name("salt")
+                        .long("salt")
+                        .help("The CREATE2 salt, as a decimal or 0x-prefixed hex number")
+                        .value_name("SALT")
+                        .takes_value(true)
+                        .requires("deployer"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .about("Formats the given `.fe` files, and the `.fe` files in the given directories, in place")
+                .arg(
+                    Arg::with_name("paths")
+                        .help("The source files and directories to format")
+                        .index(1)
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .help("Lists the files that aren't formatted, and fails if there are any, without changing them"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lsp")
+                .about("Runs a language server on stdin and stdout, which reports errors and warnings as files are edited"),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Runs a compile server on stdin and stdout, which answers JSON-RPC requests to compile or check standard JSON inputs"),
+        )
         .get_matches();
 
-    let input_path = matches.value_of("input").unwrap();
-    let output_dir = matches.value_of("output-dir").unwrap();
+    // The flags may be given before or after a subcommand.
+    let flags = matches.subcommand().1.unwrap_or(&matches);
+    let verbosity =
+        Verbosity::from_flags(flags.is_present("quiet"), flags.occurrences_of("verbose"));
+    logging::init(verbosity);
+
+    if let Some(encoding) = matches.subcommand_matches("encode-deploy") {
+        encode_deploy::run(encoding).exit()
+    }
+
+    if let Some(formatting) = matches.subcommand_matches("fmt") {
+        fmt::run(formatting).exit()
+    }
+
+    if matches.subcommand_matches("lsp").is_some() {
+        if !lsp::run() {
+            ExitCode::Failure.exit()
+        }
+        return;
+    }
+
+    if matches.subcommand_matches("serve").is_some() {
+        if !serve::run() {
+            ExitCode::Failure.exit()
+        }
+        return;
+    }
+
+    if matches.is_present("standard-json") {
+        let mut input = String::new();
+        if let Err(err) = std::io::stdin().read_to_string(&mut input) {
+            eprintln!("Unable to read the standard JSON input: {}", err);
+            ExitCode::IoError.exit()
+        }
+        println!("{}", standard_json::compile(&input));
+        return;
+    }
+
+    let testing = matches.subcommand_matches("test");
+    let mut input_path = testing
+        .unwrap_or(&matches)
+        .value_of("input")
+        .unwrap()
+        .to_string();
+    let mut output_dir = matches.value_of("output-dir").unwrap().to_string();
+    let mut ingot_name = input_path.clone();
     let overwrite = matches.is_present("overwrite");
-    let optimize = matches.value_of("optimize") == Some("true");
-    let targets =
-        values_t!(matches.values_of("emit"), CompilationTarget).unwrap_or_else(|e| e.exit());
-    let with_bytecode = targets.contains(&CompilationTarget::Bytecode);
+    let mut optimize = matches.value_of("optimize") == Some("true");
+    let mut lint_levels = IndexMap::new();
+    let mut profiles = IndexMap::new();
+    let json_summary = flags.value_of("json-summary");
+    let diagnostic_counts = DiagnosticCounts::default();
+    // The gas snapshot is kept in the project directory, next to `fe.toml`,
+    // or in the ingot directory or the directory of the input file.
+    let mut project_dir = if Path::new(&input_path).is_dir() {
+        PathBuf::from(&input_path)
+    } else {
+        Path::new(&input_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    };
+    if let Some(path) = Manifest::find(Path::new(&input_path)) {
+        let manifest = Manifest::load(&path).unwrap_or_else(|(exit_code, err)| {
+            eprintln!("{}", err);
+            exit(exit_code, json_summary, &diagnostic_counts)
+        });
+        // Options given on the command line take precedence over the manifest.
+        if matches.occurrences_of("output-dir") == 0 {
+            output_dir = manifest.output_dir.to_string_lossy().into_owned();
+        }
+        // Tests are run once, without the build matrix.
+        if testing.is_none() {
+            profiles = manifest.profiles;
+        }
+        if matches.occurrences_of("optimize") == 0 {
+            optimize = manifest.optimize;
+        } else {
+            for profile in profiles.values_mut() {
+                profile.optimize = None;
+            }
+        }
+        project_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        input_path = manifest.src_dir.to_string_lossy().into_owned();
+        ingot_name = manifest.name;
+        lint_levels = manifest.lints;
+    }
+    let chain = ChainProfile::from_name(flags.value_of("chain-profile").unwrap())
+        .expect("validated by clap");
+    let latest_evm_version = EvmVersion::latest_for(chain);
+    if let Some(latest) = latest_evm_version {
+        for (name, profile) in profiles.iter_mut() {
+            match profile.evm_version {
+                Some(version) if version > latest => {
+                    eprintln!(
+                        "The profile `{}` targets the `{}` EVM, which {} doesn't support. The latest supported version is `{}`.",
+                        name,
+                        version.name(),
+                        chain.name(),
+                        latest.name()
+                    );
+                    exit(ExitCode::ConfigError, json_summary, &diagnostic_counts)
+                }
+                Some(_) => {}
+                None => profile.evm_version = Some(latest),
+            }
+        }
+    }
+    let error_format = value_t!(matches, "error-format", ErrorFormat).unwrap_or_else(|e| e.exit());
+    let selection = if testing.is_some() {
+        OutputSelection::new(vec![CompilationTarget::Bytecode])
+    } else {
+        OutputSelection::parse(matches.value_of("emit").unwrap()).expect("validated by clap")
+    };
+    let checks = [
+        ("erc20", Standard::Erc20),
+        ("erc721", Standard::Erc721),
+        ("erc1155", Standard::Erc1155),
+    ]
+    .iter()
+    .flat_map(|(arg, standard)| {
+        matches
+            .values_of(arg)
+            .into_iter()
+            .flatten()
+            .map(move |contract| (*standard, contract))
+    })
+    .collect::<Vec<_>>();
+    // `dbg` only records its values in the contracts that `fe test` runs,
+    // and compiles to nothing otherwise.
+    let plugins: &[&dyn LoweringPlugin] =
+        match (matches.is_present("debug-events"), testing.is_some()) {
+            (true, true) => &[&DebugEvents, &RecordDbg],
+            (true, false) => &[&DebugEvents],
+            (false, true) => &[&RecordDbg],
+            (false, false) => &[],
+        };
     #[cfg(not(feature = "solc-backend"))]
-    if with_bytecode {
+    if testing.is_none()
+        && !verbosity.is_quiet()
+        && (selection.contains(&CompilationTarget::Bytecode)
+            || selection.contains(&CompilationTarget::RuntimeBytecode)
+            || selection.contains(&CompilationTarget::CodeSize))
+    {
         eprintln!("Warning: bytecode output requires 'solc-backend' feature. Try `cargo build --release --features solc-backend`. Skipping.");
     }
+    #[cfg(not(feature = "wasm-backend"))]
+    if testing.is_none() && !verbosity.is_quiet() && selection.contains(&CompilationTarget::Wasm) {
+        eprintln!("Warning: WASM output requires 'wasm-backend' feature. Try `cargo build --release --features wasm-backend`. Skipping.");
+    }
 
-    let (content, compiled_module) = if Path::new(input_path).is_file() {
-        let mut files = FileStore::new();
-        let deps = files.add_included_libraries();
-        let file = files.load_file(input_path).map_err(ioerr_to_string);
+    let build = Build {
+        input_path: &input_path,
+        ingot_name: &ingot_name,
+        output_dir: &output_dir,
+        optimize,
+        // Tests run on an EVM of their own, which doesn't depend on the chain.
+        evm_version: latest_evm_version.filter(|_| testing.is_none()),
+        profiles: &profiles,
+        chain,
+        selection: &selection,
+        checks: &checks,
+        plugins,
+        error_format,
+        verbosity,
+        lint_levels: &lint_levels,
+        diagnostic_counts: &diagnostic_counts,
+    };
 
-        let (content, id) = match file {
-            Err(err) => {
-                eprintln!("Failed to load file: `{}`. Error: {}", input_path, err);
-                std::process::exit(1)
+    let gas_threshold = match testing {
+        Some(testing) if testing.is_present("gas-snapshot") => {
+            Some(value_t!(testing, "gas-threshold", f64).unwrap_or_else(|e| e.exit()))
+        }
+        _ => None,
+    };
+    staging::install_interrupt_handler();
+    if testing.is_none() && matches.is_present("watch") {
+        watch(&build, overwrite)
+    }
+    // A panic is reported by the panic hook, and still gets a summary.
+    let exit_code = panic::catch_unwind(AssertUnwindSafe(|| {
+        if testing.is_some() {
+            test_runner::run(&build, &project_dir, gas_threshold)
+        } else {
+            build.run(overwrite)
+        }
+    }))
+    .unwrap_or(ExitCode::InternalError);
+    exit(exit_code, json_summary, &diagnostic_counts)
+}
+
+/// Writes the JSON summary if one was requested, then exits with the given
+/// code, or with an IO error if the summary couldn't be written.
+fn exit(exit_code: ExitCode, json_summary: Option<&str>, counts: &DiagnosticCounts) -> ! {
+    if let Some(path) = json_summary {
+        if let Err(err) = summary::write(path, exit_code, counts) {
+            eprintln!("{}", err);
+            ExitCode::IoError.exit()
+        }
+    }
+    exit_code.exit()
+}
+
+/// A source file of the input.
+struct Source {
+    /// The directory that the outputs of the file are written to, relative to
+    /// the output directory. Empty for the input file and for `main.fe`.
+    output_dir: PathBuf,
+    /// The path of the file, as it's named in diagnostics.
+    path: String,
+    content: String,
+}
+
+/// The inputs and outputs of a compilation, as given on the command line or
+/// in a project manifest.
+struct Build<'a> {
+    input_path: &'a str,
+    /// The name of the ingot if the input is a directory.
+    ingot_name: &'a str,
+    output_dir: &'a str,
+    optimize: bool,
+    /// The EVM version of the bytecode when there are no profiles, or `None`
+    /// for solc's default.
+    evm_version: Option<EvmVersion>,
+    /// The build matrix of the project manifest. Without profiles, the input
+    /// is built once with `optimize` and `evm_version`.
+    profiles: &'a IndexMap<String, Profile>,
+    chain: ChainProfile,
+    selection: &'a OutputSelection,
+    checks: &'a [(Standard, &'a str)],
+    plugins: &'a [&'a dyn LoweringPlugin],
+    error_format: ErrorFormat,
+    verbosity: Verbosity,
+    /// The levels of lints set in the project manifest.
+    lint_levels: &'a IndexMap<Lint, LintLevel>,
+    /// The diagnostics found by the build, for the JSON summary.
+    diagnostic_counts: &'a DiagnosticCounts,
+}
+
+impl Build<'_> {
+    /// Compiles the input and writes the outputs, printing any diagnostics.
+    /// Returns the error code of the first step that failed: compilation,
+    /// writing the outputs or a standard check.
+    fn run(&self, overwrite: bool) -> ExitCode {
+        let (sources, compiled_module) = match self.compile() {
+            Ok(compiled) => compiled,
+            Err(exit_code) => return exit_code,
+        };
+        // The bytecode is compiled again for each output's EVM version.
+        #[cfg(feature = "solc-backend")]
+        let mut compiled_module = compiled_module;
+
+        let conforms = check_standards(&compiled_module, self.checks, self.verbosity);
+        if !self.verbosity.is_quiet() {
+            warn_unknown_selected_contracts(&compiled_module, self.selection);
+        }
+
+        for (output_dir, evm_version, optimize) in self.outputs() {
+            #[cfg(feature = "solc-backend")]
+            if self.compiles_bytecode_per_output() {
+                fe_driver::compile_bytecode(
+                    &mut compiled_module,
+                    self.selection.contains(&CompilationTarget::Bytecode),
+                    self.selection.contains(&CompilationTarget::RuntimeBytecode),
+                    optimize,
+                    evm_version,
+                );
             }
-            Ok(file) => file,
+
+            match write_compiled_module(
+                &compiled_module,
+                &sources,
+                self.selection,
+                &output_dir,
+                optimize,
+                evm_version,
+                overwrite,
+            ) {
+                Ok(_) => {
+                    if !self.verbosity.is_quiet() {
+                        println!("Compiled {}. Outputs in `{}`", self.input_path, output_dir)
+                    }
+                }
+                Err(err) => {
+                    eprintln!(
+                        "Failed to write output to directory: `{}`. Error: {}",
+                        output_dir, err
+                    );
+                    return ExitCode::IoError;
+                }
+            }
+        }
+
+        if conforms {
+            ExitCode::Success
+        } else {
+            ExitCode::Failure
+        }
+    }
+
+    /// The output directory, EVM version and optimizer setting of each build
+    /// of the input: one per profile, in a directory named after it, or just
+    /// the output directory if there are no profiles.
+    fn outputs(&self) -> Vec<(String, Option<EvmVersion>, bool)> {
+        if self.profiles.is_empty() {
+            return vec![(self.output_dir.to_string(), self.evm_version, self.optimize)];
+        }
+        self.profiles
+            .iter()
+            .map(|(name, profile)| {
+                let output_dir = Path::new(self.output_dir).join(name);
+                (
+                    output_dir.to_string_lossy().into_owned(),
+                    profile.evm_version,
+                    profile.optimize.unwrap_or(self.optimize),
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the bytecode is compiled for each output, with its own settings,
+    /// rather than along with the rest of the module with solc's defaults.
+    fn compiles_bytecode_per_output(&self) -> bool {
+        !self.profiles.is_empty() || self.evm_version.is_some()
+    }
+
+    /// Compiles the input, printing any diagnostics. Returns the sources of
+    /// the input and the compiled module, or the code to exit with if the
+    /// input couldn't be read or compiled.
+    fn compile(&self) -> Result<(Vec<Source>, CompiledModule), ExitCode> {
+        let input_path = self.input_path;
+        // Otherwise the bytecode of each output is compiled in `run`.
+        let with_bytecode = !self.compiles_bytecode_per_output()
+            && self.selection.contains(&CompilationTarget::Bytecode);
+        let with_runtime_bytecode = !self.compiles_bytecode_per_output()
+            && self.selection.contains(&CompilationTarget::RuntimeBytecode);
+        let options = CompileOptions {
+            with_bytecode,
+            with_runtime_bytecode,
+            with_mir: self.selection.contains(&CompilationTarget::Mir),
+            optimize: self.optimize,
+            chain: self.chain,
+            plugins: self.plugins,
+            cancel: CancellationToken::new(),
         };
 
-        let compiled_module =
-            match fe_driver::compile_module(&files, id, &deps, with_bytecode, optimize) {
+        let (sources, compiled_module) = if Path::new(input_path).is_file() {
+            let mut files = FileStore::new();
+            let deps = files.add_included_libraries();
+            let file = files.load_file(input_path).map_err(ioerr_to_string);
+
+            let (content, id) = match file {
+                Err(err) => {
+                    eprintln!("Failed to load file: `{}`. Error: {}", input_path, err);
+                    return Err(ExitCode::IoError);
+                }
+                Ok(file) => file,
+            };
+
+            let mut compiled_module = match fe_driver::compile_module(&files, id, &deps, &options) {
                 Ok(module) => module,
                 Err(error) => {
                     eprintln!("Unable to compile {}.", input_path);
-                    print_diagnostics(&error.0, &files);
-                    std::process::exit(1)
+                    self.print_diagnostics(&error.0, &files);
+                    return Err(error.1.into());
                 }
             };
-        (content, compiled_module)
-    } else {
-        let mut files = build_ingot_filestore_for_dir(input_path);
-        let ingot_files = files.all_files();
-        let deps = files.add_included_libraries();
+            compiled_module.warnings = self.report_warnings(compiled_module.warnings, &files)?;
+            let source = Source {
+                output_dir: PathBuf::new(),
+                path: input_path.to_string(),
+                content,
+            };
+            (vec![source], compiled_module)
+        } else {
+            if !Path::new(input_path).exists() {
+                eprintln!("Input directory does not exist: `{}`.", input_path);
+                return Err(ExitCode::IoError);
+            }
+
+            let mut files = match build_ingot_filestore_for_dir(input_path) {
+                Ok(files) => files,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return Err(ExitCode::IoError);
+                }
+            };
+            let ingot_files = files.all_files();
+            let deps = files.add_included_libraries();
+
+            let mut compiled_module = match fe_driver::compile_ingot(
+                self.ingot_name,
+                &files,
+                &ingot_files,
+                &deps,
+                &options,
+            ) {
+                Ok(module) => module,
+                Err(error) => {
+                    eprintln!("Unable to compile {}.", input_path);
+                    self.print_diagnostics(&error.0, &files);
+                    return Err(error.1.into());
+                }
+            };
+            // The outputs of each file are written to its path within the
+            // input directory.
+            compiled_module.module_contracts = compiled_module
+                .module_contracts
+                .into_iter()
+                .map(|(path, contracts)| {
+                    let relative = Path::new(&path)
+                        .strip_prefix(input_path)
+                        .map_or_else(|_| path.clone(), |relative| relative.display().to_string());
+                    (relative, contracts)
+                })
+                .collect();
 
-        if !Path::new(input_path).exists() {
-            eprintln!("Input directory does not exist: `{}`.", input_path);
-            std::process::exit(1)
+            compiled_module.warnings = self.report_warnings(compiled_module.warnings, &files)?;
+
+            // The outputs of `main.fe` are written to the output directory,
+            // and those of the other files to their path within the input
+            // directory, like their contracts.
+            let mut sources = ingot_files
+                .iter()
+                .filter_map(|id| files.get_file(*id))
+                .map(|file| {
+                    let relative = Path::new(&file.name)
+                        .strip_prefix(input_path)
+                        .unwrap_or_else(|_| Path::new(&file.name));
+                    let output_dir = if relative == Path::new("main.fe") {
+                        PathBuf::new()
+                    } else {
+                        relative.with_extension("")
+                    };
+                    Source {
+                        output_dir,
+                        path: file.name.clone(),
+                        content: file.content.clone(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            sources.sort_by(|a, b| a.output_dir.cmp(&b.output_dir));
+            (sources, compiled_module)
+        };
+
+        Ok((sources, compiled_module))
+    }
+
+    /// Applies the lint levels of the project to the warnings of a
+    /// compilation and prints them. Returns the remaining warnings, or the
+    /// code to exit with if a denied lint was found.
+    fn report_warnings(
+        &self,
+        warnings: Vec<Diagnostic>,
+        files: &FileStore,
+    ) -> Result<Vec<Diagnostic>, ExitCode> {
+        let diagnostics = lints::apply_levels(warnings, self.lint_levels);
+        if diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+        {
+            eprintln!("Unable to compile {}.", self.input_path);
+            self.print_diagnostics(&diagnostics, files);
+            return Err(ExitCode::TypeError);
+        }
+        self.print_warnings(&diagnostics, files);
+        Ok(diagnostics)
+    }
+
+    /// Prints the warnings of a compilation, unless the build is quiet.
+    fn print_warnings(&self, warnings: &[Diagnostic], files: &FileStore) {
+        if self.verbosity.is_quiet() {
+            self.diagnostic_counts.add(warnings);
+        } else {
+            self.print_diagnostics(warnings, files)
         }
+    }
 
-        let compiled_module = match fe_driver::compile_ingot(
-            input_path,
-            &files,
-            &ingot_files,
-            &deps,
-            with_bytecode,
-            optimize,
-        ) {
-            Ok(module) => module,
-            Err(error) => {
-                eprintln!("Unable to compile {}.", input_path);
-                print_diagnostics(&error.0, &files);
-                std::process::exit(1)
+    fn print_diagnostics(&self, diagnostics: &[Diagnostic], files: &FileStore) {
+        self.diagnostic_counts.add(diagnostics);
+        match self.error_format {
+            ErrorFormat::Human => print_diagnostics(diagnostics, files),
+            ErrorFormat::Json => json_diagnostics::print_diagnostics(diagnostics, files),
+        }
+    }
+
+    /// The source files that the build reads: the input file, or the `.fe`
+    /// files of the ingot directory.
+    fn source_files(&self) -> Vec<PathBuf> {
+        let path = Path::new(self.input_path);
+        if path.is_file() {
+            return vec![path.to_path_buf()];
+        }
+        WalkDir::new(path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension() == Some(OsStr::new("fe")))
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+}
+
+/// Builds the input, then rebuilds it whenever one of its source files is
+/// changed, added or removed. Runs until the process is interrupted.
+///
+/// Files are polled rather than watched with OS notifications, which keeps
+/// the CLI free of platform-specific dependencies. If the output directory
+/// is empty when watching starts, later builds overwrite the outputs of the
+/// earlier ones without `--overwrite`.
+fn watch(build: &Build, overwrite: bool) -> ! {
+    let mut state = source_state(build);
+    let overwrite = overwrite || verify_nonexistent_or_empty(Path::new(build.output_dir)).is_ok();
+    loop {
+        build.run(overwrite);
+        if !build.verbosity.is_quiet() {
+            println!("Watching for changes to {}...", build.input_path);
+        }
+
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            let new_state = source_state(build);
+            if new_state != state {
+                state = new_state;
+                break;
+            }
+        }
+        if !build.verbosity.is_quiet() {
+            println!();
+        }
+    }
+}
+
+/// The modification times of the source files of a build. Files that can't
+/// be read have no time, so that they're rebuilt once they're readable again.
+fn source_state(build: &Build) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut state = build
+        .source_files()
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+            (path, modified)
+        })
+        .collect::<Vec<_>>();
+    state.sort();
+    state
+}
+
+/// Checks the named contracts against standard interfaces, printing any
+/// mismatches. Returns false if any check failed.
+fn check_standards(
+    module: &CompiledModule,
+    checks: &[(Standard, &str)],
+    verbosity: Verbosity,
+) -> bool {
+    let mut conforms = true;
+    for (standard, name) in checks {
+        let contract = match module.contracts.get(*name) {
+            Some(contract) => contract,
+            None => {
+                eprintln!(
+                    "Unable to check {}: no contract named `{}`.",
+                    standard, name
+                );
+                conforms = false;
+                continue;
             }
         };
 
-        // no file content for ingots
-        ("".to_string(), compiled_module)
-    };
+        let mismatches = standards::check(&contract.abi, *standard);
+        if mismatches.is_empty() {
+            if !verbosity.is_quiet() {
+                println!("`{}` conforms to {}.", name, standard);
+            }
+        } else {
+            eprintln!("`{}` does not conform to {}:", name, standard);
+            for mismatch in mismatches {
+                eprintln!("  - {}", mismatch);
+            }
+            conforms = false;
+        }
+    }
+    conforms
+}
 
-    match write_compiled_module(compiled_module, &content, &targets, output_dir, overwrite) {
-        Ok(_) => println!("Compiled {}. Outputs in `{}`", input_path, output_dir),
-        Err(err) => {
-            eprintln!(
-                "Failed to write output to directory: `{}`. Error: {}",
-                output_dir, err
-            );
-            std::process::exit(1)
+/// Warns about the contracts that are selected by name with `--emit` but
+/// aren't in the module, which are likely misspelled.
+fn warn_unknown_selected_contracts(module: &CompiledModule, selection: &OutputSelection) {
+    for name in selection.contract_names() {
+        let exists = module.contracts.contains_key(name)
+            || module
+                .module_contracts
+                .values()
+                .any(|contracts| contracts.contains_key(name));
+        if !exists {
+            eprintln!("Warning: no contract named `{}` to emit outputs for.", name);
         }
     }
 }
 
-fn build_ingot_filestore_for_dir(path: &str) -> FileStore {
+fn build_ingot_filestore_for_dir(path: &str) -> Result<FileStore, String> {
     let path = Path::new(path);
     let walker = WalkDir::new(path);
     let mut files = FileStore::new();
 
     for entry in walker {
-        if entry.is_err() {
-            eprintln!("Error: {}", entry.unwrap_err());
-            std::process::exit(1)
-        }
-        let entry = entry.unwrap();
+        let entry = entry.map_err(|err| format!("Error: {}", err))?;
         let file_path = &entry.path().to_string_lossy().to_string();
 
         if entry.path().extension() == Some(OsStr::new("fe")) {
-            let file = files.load_file(file_path);
-            if let Err(err) = file {
-                eprintln!("Failed to load file: `{}`. Error: {}", &file_path, err);
-                std::process::exit(1)
-            }
+            files
+                .load_file(file_path)
+                .map_err(|err| format!("Failed to load file: `{}`. Error: {}", &file_path, err))?;
         }
     }
 
-    files
+    Ok(files)
 }
 
 fn write_compiled_module(
-    mut module: CompiledModule,
-    file_content: &str,
-    targets: &[CompilationTarget],
+    module: &CompiledModule,
+    sources: &[Source],
+    selection: &OutputSelection,
     output_dir: &str,
+    _optimize: bool,
+    _evm_version: Option<EvmVersion>,
     overwrite: bool,
 ) -> Result<(), String> {
     let output_dir = Path::new(output_dir);
@@ -195,49 +939,184 @@ fn write_compiled_module(
         verify_nonexistent_or_empty(output_dir)?;
     }
 
-    fs::create_dir_all(output_dir).map_err(ioerr_to_string)?;
+    let staging = StagingDir::create(output_dir)?;
+    let staging_dir = staging.path();
 
-    if targets.contains(&CompilationTarget::Ast) {
-        write_output(&output_dir.join("module.ast"), &module.src_ast)?;
+    if selection.contains(&CompilationTarget::Ast) {
+        write_output(&staging_dir.join("module.ast"), &module.src_ast)?;
     }
 
-    if targets.contains(&CompilationTarget::LoweredAst) {
-        write_output(&output_dir.join("lowered_module.ast"), &module.lowered_ast)?;
+    // The CST and the tokens are written for each source file of an ingot.
+    for source in sources {
+        let source_output_dir = staging_dir.join(&source.output_dir);
+        if selection.contains(&CompilationTarget::Cst)
+            || selection.contains(&CompilationTarget::Tokens)
+        {
+            fs::create_dir_all(&source_output_dir).map_err(ioerr_to_string)?;
+        }
+        if selection.contains(&CompilationTarget::Cst) {
+            let (ast, _) = fe_parser::parse_file(SourceFileId::default(), &source.content)
+                .map_err(|_| "Failed to parse the module".to_string())?;
+            let cst = fe_parser::cst::module_cst(SourceFileId::default(), &source.content, &ast);
+            write_output(&source_output_dir.join("module.cst"), &cst.to_string())?;
+        }
+
+        if selection.contains(&CompilationTarget::Tokens) {
+            let tokens = {
+                let lexer = fe_parser::lexer::Lexer::new(SourceFileId::default(), &source.content);
+                lexer.collect::<Vec<_>>()
+            };
+            write_output(
+                &source_output_dir.join("module.tokens"),
+                &format!("{:#?}", tokens),
+            )?;
+        }
     }
 
-    if targets.contains(&CompilationTarget::Tokens) {
-        let tokens = {
-            let lexer = fe_parser::lexer::Lexer::new(SourceFileId::default(), file_content);
-            lexer.collect::<Vec<_>>()
-        };
-        write_output(&output_dir.join("module.tokens"), &format!("{:#?}", tokens))?;
+    if selection.contains(&CompilationTarget::LoweredAst) {
+        write_output(&staging_dir.join("lowered_module.ast"), &module.lowered_ast)?;
     }
 
-    for (name, contract) in module.contracts.drain(0..) {
-        let contract_output_dir = output_dir.join(&name);
+    if selection.contains(&CompilationTarget::Mir) {
+        write_output(&staging_dir.join("module.mir"), &module.mir)?;
+    }
+
+    if selection.contains(&CompilationTarget::Abi) {
+        for (layout, table) in &module.diamonds {
+            let diamond_output_dir = staging_dir.join(layout);
+            fs::create_dir_all(&diamond_output_dir).map_err(ioerr_to_string)?;
+            let file_name = format!("{}_selectors.json", &layout);
+            write_output(&diamond_output_dir.join(file_name), table)?;
+        }
+    }
+
+    write_contracts(
+        &module.contracts,
+        selection,
+        staging_dir,
+        _optimize,
+        _evm_version,
+    )?;
+
+    // The contracts of the other source files of an ingot are written to a
+    // directory per file, eg. `tokens/erc20/` for `src/tokens/erc20.fe`.
+    for (path, contracts) in &module.module_contracts {
+        let module_output_dir = staging_dir.join(Path::new(path).with_extension(""));
+        write_contracts(
+            contracts,
+            selection,
+            &module_output_dir,
+            _optimize,
+            _evm_version,
+        )?;
+    }
+
+    staging.commit()
+}
+
+/// Writes the selected outputs of each contract to a directory named after it
+/// in `output_dir`. Contracts without selected outputs get no directory.
+fn write_contracts(
+    contracts: &IndexMap<String, CompiledContract>,
+    selection: &OutputSelection,
+    output_dir: &Path,
+    _optimize: bool,
+    _evm_version: Option<EvmVersion>,
+) -> Result<(), String> {
+    for (name, contract) in contracts {
+        let targets = selection.targets(name);
+        if targets.is_empty() {
+            continue;
+        }
+        let contract_output_dir = output_dir.join(name);
         fs::create_dir_all(&contract_output_dir).map_err(ioerr_to_string)?;
 
         if targets.contains(&CompilationTarget::Abi) {
-            let file_name = format!("{}_abi.json", &name);
+            let file_name = format!("{}_abi.json", name);
             write_output(&contract_output_dir.join(file_name), &contract.json_abi)?;
         }
 
+        if targets.contains(&CompilationTarget::SolidityInterface) {
+            let file_name = format!("{}.sol", name);
+            let interface = solidity::interface(name, &contract.abi).map_err(|err| {
+                format!(
+                    "Unable to generate the Solidity interface of `{}`: {:?}",
+                    name, err
+                )
+            })?;
+            write_output(&contract_output_dir.join(file_name), &interface)?;
+        }
+
+        if targets.contains(&CompilationTarget::FeInterface) {
+            let file_name = format!("{}_interface.fe", name);
+            write_output(&contract_output_dir.join(file_name), &contract.fe_interface)?;
+        }
+
+        if targets.contains(&CompilationTarget::Resources) {
+            let file_name = format!("{}_resources.txt", name);
+            write_output(&contract_output_dir.join(file_name), &contract.resources)?;
+        }
+
         if targets.contains(&CompilationTarget::Yul) {
-            let file_name = format!("{}_ir.yul", &name);
+            let file_name = format!("{}_deploy.yul", name);
             write_output(&contract_output_dir.join(file_name), &contract.yul)?;
+            let file_name = format!("{}_runtime.yul", name);
+            write_output(&contract_output_dir.join(file_name), &contract.runtime_yul)?;
+        }
+
+        if targets.contains(&CompilationTarget::YulObject) {
+            let file_name = format!("{}.yul", name);
+            write_output(&contract_output_dir.join(file_name), &contract.yul_object)?;
         }
 
         #[cfg(feature = "solc-backend")]
         if targets.contains(&CompilationTarget::Bytecode) {
-            let file_name = format!("{}.bin", &name);
+            let file_name = format!("{}.bin", name);
             write_output(&contract_output_dir.join(file_name), &contract.bytecode)?;
         }
+
+        #[cfg(feature = "solc-backend")]
+        if targets.contains(&CompilationTarget::RuntimeBytecode) {
+            let file_name = format!("{}_runtime.bin", name);
+            write_output(
+                &contract_output_dir.join(file_name),
+                &contract.runtime_bytecode,
+            )?;
+        }
+
+        #[cfg(feature = "solc-backend")]
+        if targets.contains(&CompilationTarget::CodeSize) {
+            let file_name = format!("{}_code_size.txt", name);
+            let code_size =
+                fe_driver::code_size(name, contract, _optimize, _evm_version).map_err(|error| {
+                    error
+                        .0
+                        .iter()
+                        .map(|diagnostic| diagnostic.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })?;
+            write_output(&contract_output_dir.join(file_name), &code_size.to_string())?;
+        }
+
+        #[cfg(feature = "wasm-backend")]
+        if targets.contains(&CompilationTarget::Wasm) {
+            // Interfaces have no code to write.
+            if let Some(wasm) = &contract.wasm {
+                let wasm = wasm
+                    .as_ref()
+                    .map_err(|err| format!("Unable to compile `{}` to WASM: {}", name, err))?;
+                let file_name = format!("{}.wat", name);
+                write_output(&contract_output_dir.join(file_name), wasm)?;
+            }
+        }
     }
 
     Ok(())
 }
 
 fn write_output(path: &Path, content: &str) -> Result<(), String> {
+    debug!("writing `{}`", path.display());
     let mut file = fs::OpenOptions::new()
         .write(true)
         .create(true)
@@ -0,0 +1,392 @@
+//! Projects, which are directories with an `fe.toml` manifest, eg.
+//!
+//! ```toml
+//! name = "token"
+//! src = "src"
+//! output = "build"
+//!
+//! [optimizer]
+//! enabled = false
+//!
+//! [lints]
+//! unused-variable = "allow"
+//! block-randomness = "deny"
+//!
+//! [profiles.l1]
+//! evm-version = "london"
+//!
+//! [profiles.l2]
+//! evm-version = "shanghai"
+//! optimizer = { enabled = false }
+//! ```
+//!
+//! Every key is optional. The name defaults to the name of the project
+//! directory, the sources to `src`, the output to `output` and the optimizer
+//! to enabled. Paths are relative to the project directory. The sources are
+//! compiled as an ingot, whose modules can `use` each other. The `[lints]`
+//! table sets the level of lints by id to `allow`, `warn` or `deny`, and
+//! lints without a level are warnings.
+//!
+//! The `[profiles]` table is a build matrix: the project is built once for
+//! each profile, with the profile's EVM version and optimizer setting, and the
+//! outputs of each profile are written to a directory named after it in the
+//! output directory. A profile's optimizer setting defaults to the project's,
+//! and its EVM version to solc's default.
+
+use crate::summary::ExitCode;
+use fe_analyzer::lints::{Lint, LintLevel};
+use fe_driver::EvmVersion;
+use indexmap::IndexMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+pub const MANIFEST_FILE_NAME: &str = "fe.toml";
+
+/// The settings of a project, as given in its manifest.
+#[derive(Debug)]
+pub struct Manifest {
+    pub name: String,
+    /// The directory of the project's `.fe` files.
+    pub src_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub optimize: bool,
+    pub lints: IndexMap<Lint, LintLevel>,
+    /// The build profiles, by name.
+    pub profiles: IndexMap<String, Profile>,
+}
+
+/// The settings of one build of a project's build matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    pub evm_version: Option<EvmVersion>,
+    /// Whether the optimizer is enabled, or `None` to use the project's
+    /// setting.
+    pub optimize: Option<bool>,
+}
+
+impl Manifest {
+    /// Returns the path of the manifest if `input` is one, or is a directory
+    /// that contains one.
+    pub fn find(input: &Path) -> Option<PathBuf> {
+        if input.is_dir() {
+            let path = input.join(MANIFEST_FILE_NAME);
+            if path.is_file() {
+                Some(path)
+            } else {
+                None
+            }
+        } else if input.file_name() == Some(MANIFEST_FILE_NAME.as_ref()) {
+            Some(input.to_path_buf())
+        } else {
+            None
+        }
+    }
+
+    /// Reads and parses a manifest. The error is the status to exit with,
+    /// which tells a manifest that can't be read from an invalid one, and a
+    /// message.
+    pub fn load(path: &Path) -> Result<Self, (ExitCode, String)> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            (
+                ExitCode::IoError,
+                format!("Failed to read `{}`. Error: {}", path.display(), err),
+            )
+        })?;
+        Self::parse(path, &content).map_err(|err| {
+            (
+                ExitCode::ConfigError,
+                format!("Invalid manifest `{}`: {}", path.display(), err),
+            )
+        })
+    }
+
+    fn parse(path: &Path, content: &str) -> Result<Self, String> {
+        let table = match content.parse::<Value>().map_err(|err| err.to_string())? {
+            Value::Table(table) => table,
+            _ => unreachable!("a TOML document is a table"),
+        };
+        let project_dir = match path.parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        };
+
+        let mut manifest = Manifest {
+            name: project_dir
+                .canonicalize()
+                .ok()
+                .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().into()))
+                .unwrap_or_else(|| "main".into()),
+            src_dir: project_dir.join("src"),
+            output_dir: project_dir.join("output"),
+            optimize: true,
+            lints: IndexMap::new(),
+            profiles: IndexMap::new(),
+        };
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("name", Value::String(name)) => manifest.name = name,
+                ("src", Value::String(dir)) => manifest.src_dir = project_dir.join(dir),
+                ("output", Value::String(dir)) => manifest.output_dir = project_dir.join(dir),
+                ("optimizer", Value::Table(optimizer)) => {
+                    for (key, value) in optimizer {
+                        match (key.as_str(), value) {
+                            ("enabled", Value::Boolean(enabled)) => manifest.optimize = enabled,
+                            ("enabled", _) => {
+                                return Err("`optimizer.enabled` must be a boolean".into())
+                            }
+                            (key, _) => return Err(format!("unknown key `optimizer.{}`", key)),
+                        }
+                    }
+                }
+                ("lints", Value::Table(lints)) => {
+                    for (id, value) in lints {
+                        let lint = Lint::from_id(&id)
+                            .ok_or_else(|| format!("unknown lint `{}` in `lints`", id))?;
+                        let level =
+                            value
+                                .as_str()
+                                .and_then(LintLevel::from_name)
+                                .ok_or_else(|| {
+                                    format!("`lints.{}` must be `allow`, `warn` or `deny`", id)
+                                })?;
+                        manifest.lints.insert(lint, level);
+                    }
+                }
+                ("profiles", Value::Table(profiles)) => {
+                    for (name, value) in profiles {
+                        let profile = match value {
+                            Value::Table(profile) => parse_profile(&name, profile)?,
+                            _ => return Err(format!("`profiles.{}` must be a table", name)),
+                        };
+                        manifest.profiles.insert(name, profile);
+                    }
+                }
+                ("name" | "src" | "output", _) => {
+                    return Err(format!("`{}` must be a string", key))
+                }
+                ("optimizer", _) => return Err("`optimizer` must be a table".into()),
+                ("lints", _) => return Err("`lints` must be a table".into()),
+                ("profiles", _) => return Err("`profiles` must be a table".into()),
+                (key, _) => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+fn parse_profile(name: &str, table: toml::value::Table) -> Result<Profile, String> {
+    let mut profile = Profile {
+        evm_version: None,
+        optimize: None,
+    };
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("evm-version", Value::String(version)) => {
+                let version = EvmVersion::from_name(&version).ok_or_else(|| {
+                    format!(
+                        "unknown EVM version `{}` in `profiles.{}`. Expected one of: {}",
+                        version,
+                        name,
+                        EvmVersion::ALL
+                            .iter()
+                            .map(|version| version.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+                profile.evm_version = Some(version);
+            }
+            ("evm-version", _) => {
+                return Err(format!("`profiles.{}.evm-version` must be a string", name))
+            }
+            ("optimizer", Value::Table(optimizer)) => {
+                for (key, value) in optimizer {
+                    match (key.as_str(), value) {
+                        ("enabled", Value::Boolean(enabled)) => profile.optimize = Some(enabled),
+                        ("enabled", _) => {
+                            return Err(format!(
+                                "`profiles.{}.optimizer.enabled` must be a boolean",
+                                name
+                            ))
+                        }
+                        (key, _) => {
+                            return Err(format!(
+                                "unknown key `profiles.{}.optimizer.{}`",
+                                name, key
+                            ))
+                        }
+                    }
+                }
+            }
+            ("optimizer", _) => {
+                return Err(format!("`profiles.{}.optimizer` must be a table", name))
+            }
+            (key, _) => return Err(format!("unknown key `profiles.{}.{}`", name, key)),
+        }
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, Profile};
+    use fe_analyzer::lints::{Lint, LintLevel};
+    use fe_driver::EvmVersion;
+    use std::path::Path;
+
+    /// The path of a manifest in a project directory that doesn't exist, so
+    /// that the project's name isn't taken from the directory.
+    const PATH: &str = "fe-manifest-test/token/fe.toml";
+
+    fn parse(content: &str) -> Result<Manifest, String> {
+        Manifest::parse(Path::new(PATH), content)
+    }
+
+    #[test]
+    fn defaults() {
+        let manifest = parse("").unwrap();
+        assert_eq!(manifest.name, "main");
+        assert_eq!(manifest.src_dir, Path::new("fe-manifest-test/token/src"));
+        assert_eq!(
+            manifest.output_dir,
+            Path::new("fe-manifest-test/token/output")
+        );
+        assert!(manifest.optimize);
+        assert!(manifest.lints.is_empty());
+        assert!(manifest.profiles.is_empty());
+
+        // a manifest in the current directory
+        let manifest = Manifest::parse(Path::new("fe.toml"), "").unwrap();
+        assert_eq!(manifest.src_dir, Path::new("./src"));
+    }
+
+    #[test]
+    fn relative_paths() {
+        let manifest = parse(
+            r#"
+name = "token"
+src = "contracts"
+output = "../build"
+
+[optimizer]
+enabled = false
+"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.name, "token");
+        assert_eq!(
+            manifest.src_dir,
+            Path::new("fe-manifest-test/token/contracts")
+        );
+        assert_eq!(
+            manifest.output_dir,
+            Path::new("fe-manifest-test/token/../build")
+        );
+        assert!(!manifest.optimize);
+    }
+
+    #[test]
+    fn lints() {
+        let manifest = parse(
+            r#"
+[lints]
+unused-variable = "allow"
+block-randomness = "deny"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.lints.into_iter().collect::<Vec<_>>(),
+            [
+                (Lint::BlockRandomness, LintLevel::Deny),
+                (Lint::UnusedVariable, LintLevel::Allow),
+            ]
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let manifest = parse(
+            r#"
+[profiles.l1]
+evm-version = "london"
+
+[profiles.l2]
+evm-version = "shanghai"
+optimizer = { enabled = false }
+
+[profiles.default]
+"#,
+        )
+        .unwrap();
+        // the keys of a TOML table are sorted
+        assert_eq!(
+            manifest.profiles.into_iter().collect::<Vec<_>>(),
+            [
+                (
+                    "default".to_string(),
+                    Profile {
+                        evm_version: None,
+                        optimize: None,
+                    }
+                ),
+                (
+                    "l1".to_string(),
+                    Profile {
+                        evm_version: Some(EvmVersion::London),
+                        optimize: None,
+                    }
+                ),
+                (
+                    "l2".to_string(),
+                    Profile {
+                        evm_version: Some(EvmVersion::Shanghai),
+                        optimize: Some(false),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |content| parse(content).unwrap_err();
+        assert_eq!(error("source = \"src\""), "unknown key `source`");
+        assert_eq!(error("src = 1"), "`src` must be a string");
+        assert_eq!(error("optimizer = true"), "`optimizer` must be a table");
+        assert_eq!(
+            error("[optimizer]\nenabled = \"yes\""),
+            "`optimizer.enabled` must be a boolean"
+        );
+        assert_eq!(
+            error("[optimizer]\nruns = 200"),
+            "unknown key `optimizer.runs`"
+        );
+        assert_eq!(
+            error("[lints]\nunused = \"allow\""),
+            "unknown lint `unused` in `lints`"
+        );
+        assert_eq!(
+            error("[lints]\nunused-variable = \"ignore\""),
+            "`lints.unused-variable` must be `allow`, `warn` or `deny`"
+        );
+        assert_eq!(
+            error("[profiles]\nl1 = \"london\""),
+            "`profiles.l1` must be a table"
+        );
+        assert_eq!(
+            error("[profiles.l1]\nevm-version = \"frontier\""),
+            "unknown EVM version `frontier` in `profiles.l1`. Expected one of: homestead, \
+             tangerineWhistle, spuriousDragon, byzantium, constantinople, petersburg, istanbul, \
+             berlin, london, paris, shanghai"
+        );
+        assert_eq!(
+            error("[profiles.l1]\nchain = \"optimism\""),
+            "unknown key `profiles.l1.chain`"
+        );
+        assert!(error("name = ").contains("line 1"));
+    }
+}
@@ -0,0 +1,190 @@
+//! The outputs to write for each contract, as given with `--emit`. The value
+//! is either a comma separated list of targets for every contract, eg.
+//! `abi,bytecode`, or a semicolon separated list of entries that select the
+//! targets of a contract by name, eg.
+//!
+//! ```text
+//! Token=abi,bytecode;*=abi
+//! ```
+//!
+//! which writes the ABI and bytecode of `Token` and only the ABI of the other
+//! contracts, like solc's `outputSelection`. The `*` entry selects the
+//! targets of the contracts that aren't named, and without it they have no
+//! outputs. Targets that aren't written per contract, like `ast`, are written
+//! if any entry selects them.
+
+use crate::CompilationTarget;
+use indexmap::IndexMap;
+use std::str::FromStr;
+
+/// The names of the targets, as they're given on the command line.
+pub const TARGET_NAMES: &[&str] = &[
+    "abi",
+    "bytecode",
+    "runtimeBytecode",
+    "codeSize",
+    "ast",
+    "cst",
+    "tokens",
+    "yul",
+    "yulObject",
+    "loweredAst",
+    "mir",
+    "resources",
+    "solidityInterface",
+    "feInterface",
+    "wasm",
+];
+
+const ANY_CONTRACT: &str = "*";
+
+#[derive(Debug, PartialEq)]
+pub struct OutputSelection {
+    /// The targets of the contracts that aren't named.
+    default: Vec<CompilationTarget>,
+    contracts: IndexMap<String, Vec<CompilationTarget>>,
+}
+
+impl OutputSelection {
+    /// Selects the same targets for every contract.
+    pub fn new(targets: Vec<CompilationTarget>) -> Self {
+        Self {
+            default: targets,
+            contracts: IndexMap::new(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if !value.contains('=') {
+            return parse_targets(value).map(Self::new);
+        }
+
+        let mut default = None;
+        let mut contracts = IndexMap::new();
+        for entry in value
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (name, targets) = entry.split_once('=').ok_or_else(|| {
+                format!(
+                    "invalid entry `{}`; expected a contract name and its targets, e.g. `Token=abi,bytecode`",
+                    entry
+                )
+            })?;
+            let name = name.trim();
+            let targets = parse_targets(targets)?;
+            if name.is_empty() {
+                return Err(format!("the entry `{}` has no contract name", entry));
+            }
+            let duplicate = if name == ANY_CONTRACT {
+                default.replace(targets).is_some()
+            } else {
+                contracts.insert(name.to_string(), targets).is_some()
+            };
+            if duplicate {
+                return Err(format!("`{}` is selected more than once", name));
+            }
+        }
+
+        Ok(Self {
+            default: default.unwrap_or_default(),
+            contracts,
+        })
+    }
+
+    /// The targets of the contract with the given name.
+    pub fn targets(&self, contract: &str) -> &[CompilationTarget] {
+        self.contracts.get(contract).unwrap_or(&self.default)
+    }
+
+    /// Returns true if the target is selected for any contract.
+    pub fn contains(&self, target: &CompilationTarget) -> bool {
+        self.default.contains(target)
+            || self
+                .contracts
+                .values()
+                .any(|targets| targets.contains(target))
+    }
+
+    /// The names of the contracts that have their own entry.
+    pub fn contract_names(&self) -> impl Iterator<Item = &str> {
+        self.contracts.keys().map(String::as_str)
+    }
+}
+
+fn parse_targets(targets: &str) -> Result<Vec<CompilationTarget>, String> {
+    targets
+        .split(',')
+        .map(str::trim)
+        .filter(|target| !target.is_empty())
+        .map(|target| {
+            CompilationTarget::from_str(target).map_err(|_| {
+                format!(
+                    "invalid target `{}`; the possible values are {}",
+                    target,
+                    TARGET_NAMES.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputSelection;
+    use crate::CompilationTarget::{Abi, Ast, Bytecode, Yul};
+
+    #[test]
+    fn same_targets_for_every_contract() {
+        let selection = OutputSelection::parse("abi, bytecode").unwrap();
+        assert_eq!(selection, OutputSelection::new(vec![Abi, Bytecode]));
+        assert_eq!(selection.targets("Token"), [Abi, Bytecode]);
+        assert_eq!(selection.contract_names().count(), 0);
+    }
+
+    #[test]
+    fn targets_by_contract() {
+        let selection = OutputSelection::parse("Token=abi,bytecode;*=abi").unwrap();
+        assert_eq!(selection.targets("Token"), [Abi, Bytecode]);
+        assert_eq!(selection.targets("Auction"), [Abi]);
+        assert_eq!(selection.contract_names().collect::<Vec<_>>(), ["Token"]);
+        assert!(selection.contains(&Bytecode));
+        assert!(!selection.contains(&Yul));
+    }
+
+    #[test]
+    fn named_contract_takes_precedence_over_wildcard() {
+        // regardless of the order of the entries
+        let selection = OutputSelection::parse("*=abi,yul;Token=bytecode").unwrap();
+        assert_eq!(selection.targets("Token"), [Bytecode]);
+        assert_eq!(selection.targets("Auction"), [Abi, Yul]);
+
+        // without the wildcard, the other contracts have no outputs
+        let selection = OutputSelection::parse("Token=abi;").unwrap();
+        assert!(selection.targets("Auction").is_empty());
+
+        // module targets are written if any entry selects them
+        let selection = OutputSelection::parse("Token=ast").unwrap();
+        assert!(selection.contains(&Ast));
+    }
+
+    #[test]
+    fn invalid_selections() {
+        let error = |value| OutputSelection::parse(value).unwrap_err();
+        assert!(error("abi,bin").starts_with(
+            "invalid target `bin`; the possible values are abi, bytecode, runtimeBytecode"
+        ));
+        assert!(error("Token=abi;*=bin").starts_with("invalid target `bin`"));
+        assert_eq!(
+            error("Token=abi;bytecode"),
+            "invalid entry `bytecode`; expected a contract name and its targets, e.g. `Token=abi,bytecode`"
+        );
+        assert_eq!(error("=abi"), "the entry `=abi` has no contract name");
+        assert_eq!(
+            error("Token=abi;Token=yul"),
+            "`Token` is selected more than once"
+        );
+        assert_eq!(error("*=abi;*=yul"), "`*` is selected more than once");
+    }
+}
@@ -0,0 +1,261 @@
+//! The `fe serve` subcommand, a compile server for web IDEs and build farms,
+//! which saves them from starting a compiler process for every compilation.
+//!
+//! The server speaks JSON-RPC 2.0 over stdin and stdout, with the same
+//! `Content-Length` framing as the language server. It answers these
+//! requests:
+//!
+//! - `compile`, whose params are a [standard JSON](crate::standard_json)
+//!   input and whose result is the standard JSON output.
+//! - `check`, which takes the same params, but only parses and analyzes the
+//!   sources, and returns an output with only the `errors`.
+//! - `shutdown`, after which the server exits once it gets an `exit`
+//!   notification.
+//!
+//! The outputs of recent requests are cached, so that compiling the same
+//! sources with the same settings again, e.g. for each worker of a build
+//! farm, doesn't compile them again.
+
+use crate::lsp::{read_message, write_message};
+use crate::standard_json;
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+
+/// The number of outputs that are cached. The oldest is dropped when a new
+/// output doesn't fit.
+const CACHE_SIZE: usize = 64;
+/// The JSON-RPC error code of an unknown request.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The JSON-RPC error code of a request that the server failed to handle.
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Runs the server until the client asks it to exit, or closes stdin.
+/// Returns false if the server wasn't shut down cleanly.
+pub fn run() -> bool {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut server = Server {
+        cache: IndexMap::new(),
+        output: stdout.lock(),
+        shut_down: false,
+    };
+
+    let mut input = stdin.lock();
+    loop {
+        match read_message(&mut input) {
+            Ok(Some(message)) => match server.handle(&message) {
+                Ok(true) => {}
+                Ok(false) => return server.shut_down,
+                Err(err) => {
+                    eprintln!("Failed to write a message: {}", err);
+                    return false;
+                }
+            },
+            Ok(None) => return false,
+            Err(err) => {
+                eprintln!("Failed to read a message: {}", err);
+                return false;
+            }
+        }
+    }
+}
+
+struct Server<W> {
+    /// The outputs of recent requests, by their method and params.
+    cache: IndexMap<(String, String), Value>,
+    output: W,
+    shut_down: bool,
+}
+
+impl<W: Write> Server<W> {
+    /// Handles a request or notification. Returns false once the client has
+    /// asked the server to exit.
+    fn handle(&mut self, message: &Value) -> io::Result<bool> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = match message.get("id") {
+            Some(id) => id,
+            // Notifications don't get a response.
+            None => return Ok(method != "exit"),
+        };
+
+        let result = match method {
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "compile" => self.cached(method, params, standard_json::compile_json),
+            "check" => self.cached(method, params, standard_json::check_json),
+            _ => Err(json!({
+                "code": METHOD_NOT_FOUND,
+                "message": format!("unsupported method `{}`", method),
+            })),
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        write_message(&mut self.output, &response)?;
+        Ok(true)
+    }
+
+    /// The output of `run` for the params of a request, from the cache if
+    /// the same request was handled recently. A panic of the compiler is
+    /// reported as an error, and the server keeps running.
+    fn cached(
+        &mut self,
+        method: &str,
+        params: &Value,
+        run: fn(&Value) -> Value,
+    ) -> Result<Value, Value> {
+        let key = (method.to_string(), params.to_string());
+        if let Some(output) = self.cache.get(&key) {
+            return Ok(output.clone());
+        }
+
+        let output = panic::catch_unwind(AssertUnwindSafe(|| run(params))).map_err(|_| {
+            json!({
+                "code": INTERNAL_ERROR,
+                "message": "the compiler panicked",
+            })
+        })?;
+        if self.cache.len() == CACHE_SIZE {
+            self.cache.shift_remove_index(0);
+        }
+        self.cache.insert(key, output.clone());
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Server, CACHE_SIZE, INTERNAL_ERROR, METHOD_NOT_FOUND};
+    use crate::lsp::read_message;
+    use indexmap::IndexMap;
+    use serde_json::{json, Value};
+
+    fn server() -> Server<Vec<u8>> {
+        Server {
+            cache: IndexMap::new(),
+            output: vec![],
+            shut_down: false,
+        }
+    }
+
+    fn params(src: &str) -> Value {
+        json!({
+            "language": "Fe",
+            "sources": { "main.fe": { "content": src } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } },
+        })
+    }
+
+    /// Sends a request to a server, and returns the response.
+    fn request(server: &mut Server<Vec<u8>>, method: &str, params: Value) -> Value {
+        server.output.clear();
+        let keep_running = server
+            .handle(&json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }))
+            .unwrap();
+        assert!(keep_running);
+        let response = read_message(&mut server.output.as_slice())
+            .unwrap()
+            .expect("no response");
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 7);
+        response
+    }
+
+    #[test]
+    fn compile() {
+        let mut server = server();
+        let response = request(
+            &mut server,
+            "compile",
+            params("contract Foo:\n    pub fn bar() -> u256:\n        return 1\n"),
+        );
+        let result = &response["result"];
+        assert_eq!(result["errors"], json!([]));
+        assert_eq!(
+            result["contracts"]["main.fe"]["Foo"]["abi"][0]["name"],
+            "bar"
+        );
+    }
+
+    #[test]
+    fn check() {
+        let mut server = server();
+        let response = request(
+            &mut server,
+            "check",
+            params("contract Foo:\n    pub fn bar() -> u256:\n        return true\n"),
+        );
+        let result = &response["result"];
+        // only the errors are reported
+        assert_eq!(result["contracts"], Value::Null);
+        assert_eq!(
+            result["errors"][0]["message"],
+            "expected function to return `u256` but was `bool`"
+        );
+    }
+
+    #[test]
+    fn cached_outputs() {
+        let mut server = server();
+        let src = "contract Foo:\n    pub fn bar() -> u256:\n        return 1\n";
+        let first = request(&mut server, "compile", params(src));
+        let second = request(&mut server, "compile", params(src));
+        assert_eq!(first, second);
+        assert_eq!(server.cache.len(), 1);
+
+        // the same params of another method are cached separately
+        request(&mut server, "check", params(src));
+        assert_eq!(server.cache.len(), 2);
+
+        // the oldest output is dropped once the cache is full
+        for index in 0..CACHE_SIZE {
+            let src = format!("contract Foo{}:\n    pub fn bar():\n        pass\n", index);
+            request(&mut server, "check", params(&src));
+        }
+        assert_eq!(server.cache.len(), CACHE_SIZE);
+        let keys = server
+            .cache
+            .keys()
+            .map(|(method, _)| method.as_str())
+            .collect::<Vec<_>>();
+        assert!(keys.iter().all(|method| *method == "check"));
+    }
+
+    #[test]
+    fn errors() {
+        let mut server = server();
+        let response = request(&mut server, "link", json!({}));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["error"]["message"], "unsupported method `link`");
+
+        fn panics(_params: &Value) -> Value {
+            panic!("a compiler bug")
+        }
+        let error = server.cached("compile", &json!({}), panics).unwrap_err();
+        assert_eq!(error["code"], INTERNAL_ERROR);
+        assert_eq!(error["message"], "the compiler panicked");
+        assert!(server.cache.is_empty());
+    }
+
+    #[test]
+    fn shutdown_and_exit() {
+        let mut server = server();
+        let response = request(&mut server, "shutdown", Value::Null);
+        assert_eq!(response["result"], Value::Null);
+        assert!(server.shut_down);
+
+        // notifications don't get a response
+        server.output.clear();
+        let keep_running = server
+            .handle(&json!({ "jsonrpc": "2.0", "method": "exit" }))
+            .unwrap();
+        assert!(!keep_running);
+        assert!(server.output.is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! The outputs of a build are written to a staging directory next to the
+//! output directory, and moved to the output directory once all of them were
+//! written. A build that fails, panics or is interrupted with Ctrl-C removes
+//! its staging directory and leaves the output directory as it was, so tools
+//! that read `output/` never see a partial build.
+//!
+//! If the output directory already exists, e.g. with `--overwrite`, the files
+//! of the new build replace the files of the same name, and any other files in
+//! the directory are kept: they are copied into the staging directory, the
+//! output directory is renamed aside, and the staging directory is renamed to
+//! it. If that rename fails, the old output directory is renamed back.
+
+use once_cell::sync::Lazy;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::debug;
+
+use crate::summary::ExitCode;
+
+/// The staging directories of the builds in progress, which are removed if the
+/// process is interrupted.
+static IN_PROGRESS: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Removes the staging directories of the builds in progress and exits when
+/// the process receives Ctrl-C. A staging directory that is being moved to its
+/// output directory is no longer in progress: the handler waits until it has
+/// been moved, since the lock is held for the whole move.
+pub fn install_interrupt_handler() {
+    let result = ctrlc::set_handler(|| {
+        for dir in IN_PROGRESS.lock().unwrap().drain(..) {
+            let _ = fs::remove_dir_all(dir);
+        }
+        ExitCode::Interrupted.exit()
+    });
+    if let Err(err) = result {
+        debug!("failed to install the Ctrl-C handler: {}", err);
+    }
+}
+
+/// A directory that the outputs are written to before they are moved to
+/// `target`. It is removed when dropped, unless it was committed.
+pub struct StagingDir {
+    path: PathBuf,
+    target: PathBuf,
+    /// Where an existing output directory is kept while it is replaced.
+    previous: PathBuf,
+    committed: bool,
+}
+
+impl StagingDir {
+    /// Creates an empty staging directory for the output directory `target`,
+    /// e.g. `.output.partial-1234` for `output`. It is created next to
+    /// `target` so that it can be renamed to it.
+    pub fn create(target: &Path) -> Result<Self, String> {
+        let name = target.file_name().ok_or_else(|| {
+            format!(
+                "`{}` can't be used as the output directory. Use the path of a subdirectory instead.",
+                target.display()
+            )
+        })?;
+        let parent = match target.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(parent).map_err(ioerr_to_string)?;
+
+        let mut staging_name = name.to_os_string();
+        staging_name.push(format!(".partial-{}", std::process::id()));
+        let path = parent.join(hidden(&staging_name));
+        let mut previous_name = name.to_os_string();
+        previous_name.push(format!(".previous-{}", std::process::id()));
+        let previous = parent.join(hidden(&previous_name));
+        // Left behind by an earlier process with the same id that was killed.
+        for dir in [&path, &previous] {
+            if dir.exists() {
+                fs::remove_dir_all(dir).map_err(ioerr_to_string)?;
+            }
+        }
+        fs::create_dir(&path).map_err(ioerr_to_string)?;
+        IN_PROGRESS.lock().unwrap().push(path.clone());
+
+        Ok(StagingDir {
+            path,
+            target: target.to_path_buf(),
+            previous,
+            committed: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Renames the staging directory to the output directory. If there is an
+    /// output directory already, its files that the build didn't write are
+    /// copied to the staging directory first, and it is only removed once the
+    /// staging directory has replaced it.
+    pub fn commit(mut self) -> Result<(), String> {
+        debug!(
+            "moving `{}` to `{}`",
+            self.path.display(),
+            self.target.display()
+        );
+        // Ctrl-C must neither remove the staging directory while its outputs
+        // are being moved, nor interrupt the move halfway.
+        let mut in_progress = IN_PROGRESS.lock().unwrap();
+        in_progress.retain(|dir| dir != &self.path);
+
+        if self.target.exists() {
+            copy_missing(&self.target, &self.path).map_err(|err| self.replace_error(err))?;
+            replace_dir(&self.path, &self.target, &self.previous)
+                .map_err(|err| self.replace_error(err))?;
+            if let Err(err) = fs::remove_dir_all(&self.previous) {
+                debug!("failed to remove `{}`: {}", self.previous.display(), err);
+            }
+        } else {
+            fs::rename(&self.path, &self.target).map_err(|err| self.replace_error(err))?;
+        }
+        self.committed = true;
+        Ok(())
+    }
+
+    fn replace_error(&self, err: io::Error) -> String {
+        format!(
+            "Failed to move the outputs to `{}`. Error: {}",
+            self.target.display(),
+            err
+        )
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        IN_PROGRESS.lock().unwrap().retain(|dir| dir != &self.path);
+        if !self.committed {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Copies the files in `from` to the same paths in `to`, unless there is a
+/// file of the same name in `to` already. Directories that exist in both are
+/// merged.
+fn copy_missing(from: &Path, to: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            if !target.exists() {
+                fs::create_dir(&target)?;
+            }
+            if target.is_dir() {
+                copy_missing(&entry.path(), &target)?;
+            }
+        } else if !target.exists() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renames `from` to the existing directory `to`, which is renamed to `aside`
+/// first. If `from` can't be renamed, `aside` is renamed back to `to`.
+fn replace_dir(from: &Path, to: &Path, aside: &Path) -> io::Result<()> {
+    fs::rename(to, aside)?;
+    if let Err(err) = fs::rename(from, to) {
+        if let Err(restore_err) = fs::rename(aside, to) {
+            debug!(
+                "failed to restore `{}` from `{}`: {}",
+                to.display(),
+                aside.display(),
+                restore_err
+            );
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn hidden(name: &std::ffi::OsStr) -> std::ffi::OsString {
+    let mut hidden = std::ffi::OsString::from(".");
+    hidden.push(name);
+    hidden
+}
+
+fn ioerr_to_string(error: io::Error) -> String {
+    format!("{}", error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_dir, StagingDir};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// An empty directory for a test, in the system's temporary directory.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fe-staging-{}-{}", name, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    /// The names of the entries in a directory, sorted.
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn commit_creates_output_dir() {
+        let dir = test_dir("create");
+        let output = dir.join("output");
+        let staging = StagingDir::create(&output).unwrap();
+        write(&staging.path().join("Foo/Foo.bin"), "6080");
+        staging.commit().unwrap();
+
+        assert_eq!(read(&output.join("Foo/Foo.bin")), "6080");
+        assert_eq!(entries(&dir), ["output"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn commit_replaces_outputs_and_keeps_other_files() {
+        let dir = test_dir("replace");
+        let output = dir.join("output");
+        write(&output.join("Foo/Foo.bin"), "old");
+        write(&output.join("Foo/notes.txt"), "notes");
+        write(&output.join("Bar/Bar.bin"), "bar");
+
+        let staging = StagingDir::create(&output).unwrap();
+        write(&staging.path().join("Foo/Foo.bin"), "new");
+        staging.commit().unwrap();
+
+        assert_eq!(read(&output.join("Foo/Foo.bin")), "new");
+        assert_eq!(read(&output.join("Foo/notes.txt")), "notes");
+        assert_eq!(read(&output.join("Bar/Bar.bin")), "bar");
+        // neither the staging directory nor the previous outputs are left
+        assert_eq!(entries(&dir), ["output"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dropped_staging_dir_leaves_output_dir() {
+        let dir = test_dir("drop");
+        let output = dir.join("output");
+        write(&output.join("Foo/Foo.bin"), "old");
+
+        let staging = StagingDir::create(&output).unwrap();
+        write(&staging.path().join("Foo/Foo.bin"), "new");
+        drop(staging);
+
+        assert_eq!(read(&output.join("Foo/Foo.bin")), "old");
+        assert_eq!(entries(&dir), ["output"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_replace_restores_output_dir() {
+        let dir = test_dir("restore");
+        let output = dir.join("output");
+        write(&output.join("Foo/Foo.bin"), "old");
+
+        // the staged directory doesn't exist, so it can't be renamed
+        let result = replace_dir(&dir.join("missing"), &output, &dir.join("aside"));
+        assert!(result.is_err());
+        assert_eq!(read(&output.join("Foo/Foo.bin")), "old");
+        assert_eq!(entries(&dir), ["output"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
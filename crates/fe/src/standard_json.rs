@@ -0,0 +1,500 @@
+//! The `--standard-json` mode, which lets build tools drive the compiler the
+//! way they drive solc with its standard JSON interface.
+//!
+//! The input, read from stdin, describes the sources, settings and requested
+//! outputs:
+//!
+//! ```json
+//! {
+//!   "language": "Fe",
+//!   "sources": {
+//!     "main.fe": { "content": "contract Foo:\n  pub fn bar() -> u256:\n    return 1\n" }
+//!   },
+//!   "settings": {
+//!     "optimizer": { "enabled": true },
+//!     "outputSelection": { "*": { "*": ["abi", "evm.bytecode.object"] } }
+//!   }
+//! }
+//! ```
+//!
+//! A single source is compiled as a module, and several sources are compiled
+//! as an ingot whose main module is the source named `main.fe`, eg.
+//! `src/main.fe`. The contracts of each source are reported under its name.
+//! The outputs that can be selected for each contract are
+//! `abi`, `ir` (the Yul code), `irRuntime`, `evm.bytecode.object` and
+//! `evm.deployedBytecode.object`. Selecting an output also selects the outputs
+//! nested inside of it, eg. `evm` selects both bytecode objects, and `*`
+//! selects everything. Every output is selected if `outputSelection` is
+//! missing. The result is written to stdout:
+//!
+//! ```json
+//! {
+//!   "schemaVersion": 1,
+//!   "errors": [],
+//!   "contracts": {
+//!     "main.fe": {
+//!       "Foo": {
+//!         "abi": [...],
+//!         "evm": { "bytecode": { "object": "..." } }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Errors and warnings, including invalid input, are reported in `errors`,
+//! each with a `severity`, a `message` and a `formattedMessage` that shows the
+//! source code.
+
+use fe_common::diagnostics::{diagnostics_string, Diagnostic, Severity};
+use fe_common::files::{FileStore, SourceFileId};
+use fe_driver::{artifacts, CancellationToken, CompileOptions, CompiledContract};
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// Compiles the sources described by the standard JSON `input`, and returns
+/// the standard JSON output.
+pub fn compile(input: &str) -> Value {
+    match serde_json::from_str(input) {
+        Ok(input) => compile_json(&input),
+        Err(err) => error_output(&format!("Invalid JSON input: {}", err)),
+    }
+}
+
+/// Like [`compile`], given the parsed input.
+pub fn compile_json(input: &Value) -> Value {
+    match Input::parse(input) {
+        Ok(input) => artifacts::versioned(input.compile()),
+        Err(message) => error_output(&message),
+    }
+}
+
+/// Parses and analyzes the sources of the standard JSON `input` without
+/// generating code, and returns an output with only the `errors`.
+pub fn check_json(input: &Value) -> Value {
+    match Input::parse(input) {
+        Ok(input) => artifacts::versioned(json!({ "errors": input.check() })),
+        Err(message) => error_output(&message),
+    }
+}
+
+fn error_output(message: &str) -> Value {
+    artifacts::versioned(json!({ "errors": [error_json("error", message, message)] }))
+}
+
+struct Input {
+    /// The path and content of each source.
+    sources: Vec<(String, String)>,
+    optimize: bool,
+    output_selection: Option<Value>,
+}
+
+impl Input {
+    fn parse(input: &Value) -> Result<Self, String> {
+        match input.get("language") {
+            None => {}
+            Some(Value::String(language)) if language == "Fe" => {}
+            Some(language) => {
+                return Err(format!(
+                    "Unsupported language {}. Only \"Fe\" is supported.",
+                    language
+                ))
+            }
+        }
+
+        let sources = input
+            .get("sources")
+            .and_then(Value::as_object)
+            .ok_or("`sources` must be an object that maps paths to sources")?
+            .iter()
+            .map(
+                |(path, source)| match source.get("content").and_then(Value::as_str) {
+                    Some(content) => Ok((path.clone(), content.to_string())),
+                    None => Err(format!("Source `{}` must have a string `content`", path)),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        if sources.is_empty() {
+            return Err("`sources` is empty".into());
+        }
+
+        let settings = input.get("settings");
+        let optimize = match settings.and_then(|settings| settings.pointer("/optimizer/enabled")) {
+            None => true,
+            Some(enabled) => enabled
+                .as_bool()
+                .ok_or("`settings.optimizer.enabled` must be a boolean")?,
+        };
+        let output_selection = settings
+            .and_then(|settings| settings.get("outputSelection"))
+            .cloned();
+
+        Ok(Input {
+            sources,
+            optimize,
+            output_selection,
+        })
+    }
+
+    fn files(&self) -> (FileStore, Vec<SourceFileId>) {
+        let mut files = FileStore::new();
+        let file_ids = self
+            .sources
+            .iter()
+            .map(|(path, content)| files.add_virtual_file(path, content))
+            .collect::<Vec<_>>();
+        (files, file_ids)
+    }
+
+    fn check(&self) -> Vec<Value> {
+        let (mut files, file_ids) = self.files();
+        let deps = files.add_included_libraries();
+        let cancel = CancellationToken::new();
+        let diagnostics = if let [file_id] = file_ids.as_slice() {
+            fe_driver::check_module(&files, *file_id, &deps, &cancel)
+        } else {
+            fe_driver::check_ingot("ingot", &files, &file_ids, &deps, &cancel)
+        }
+        .unwrap_or_default();
+        diagnostics_json(&diagnostics, &files)
+    }
+
+    fn compile(&self) -> Value {
+        let (mut files, file_ids) = self.files();
+        let deps = files.add_included_libraries();
+
+        let with_bytecode = self.selects_anywhere("evm.bytecode.object");
+        let with_runtime_bytecode = self.selects_anywhere("evm.deployedBytecode.object");
+
+        let mut errors = vec![];
+        #[cfg(not(feature = "solc-backend"))]
+        if with_bytecode || with_runtime_bytecode {
+            let message = "Bytecode output requires the `solc-backend` feature, and is skipped.";
+            errors.push(error_json("warning", message, message));
+        }
+
+        let options = CompileOptions {
+            with_bytecode,
+            with_runtime_bytecode,
+            optimize: self.optimize,
+            ..CompileOptions::default()
+        };
+        let (path, result) = if let [(path, _)] = self.sources.as_slice() {
+            let result = fe_driver::compile_module(&files, file_ids[0], &deps, &options);
+            (path.as_str(), result)
+        } else {
+            let main_path = self
+                .sources
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .find(|path| Path::new(path).file_stem() == Some("main".as_ref()))
+                .unwrap_or("main.fe");
+            let result = fe_driver::compile_ingot("ingot", &files, &file_ids, &deps, &options);
+            (main_path, result)
+        };
+
+        let contracts = match result {
+            Ok(module) => {
+                errors.extend(diagnostics_json(&module.warnings, &files));
+                let mut contracts = Map::new();
+                contracts.insert(
+                    path.to_string(),
+                    self.contracts_json(path, module.contracts),
+                );
+                for (source, source_contracts) in module.module_contracts {
+                    let json = self.contracts_json(&source, source_contracts);
+                    contracts.insert(source, json);
+                }
+                contracts
+            }
+            Err(error) => {
+                errors.extend(diagnostics_json(&error.0, &files));
+                Map::new()
+            }
+        };
+
+        json!({
+            "errors": errors,
+            "contracts": contracts,
+        })
+    }
+
+    /// The selected outputs of each of the contracts of the source `path`.
+    fn contracts_json(&self, path: &str, compiled: IndexMap<String, CompiledContract>) -> Value {
+        let mut contracts = Map::new();
+        for (name, contract) in compiled {
+            let mut outputs = json!({});
+            let mut add = |output: &str, value: Value| {
+                if self.selects(path, &name, output) {
+                    let pointer = format!("/{}", output.replace('.', "/"));
+                    insert_at(&mut outputs, &pointer, value);
+                }
+            };
+
+            add(
+                "abi",
+                serde_json::from_str(&contract.json_abi).expect("invalid ABI JSON"),
+            );
+            add("ir", Value::String(contract.yul));
+            add("irRuntime", Value::String(contract.runtime_yul));
+            #[cfg(feature = "solc-backend")]
+            {
+                add("evm.bytecode.object", Value::String(contract.bytecode));
+                add(
+                    "evm.deployedBytecode.object",
+                    Value::String(contract.runtime_bytecode),
+                );
+            }
+
+            contracts.insert(name, outputs);
+        }
+        Value::Object(contracts)
+    }
+
+    /// Whether `output` is selected for the contract named `contract` in the
+    /// source `file`.
+    fn selects(&self, file: &str, contract: &str, output: &str) -> bool {
+        let selection = match &self.output_selection {
+            Some(selection) => selection,
+            None => return true,
+        };
+        [file, "*"].into_iter().any(|file| {
+            [contract, "*"].into_iter().any(|contract| {
+                selection
+                    .get(file)
+                    .and_then(|contracts| contracts.get(contract))
+                    .and_then(Value::as_array)
+                    .map_or(false, |selected| {
+                        selected
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .any(|selected| matches_output(selected, output))
+                    })
+            })
+        })
+    }
+
+    /// Whether `output` is selected for any contract.
+    fn selects_anywhere(&self, output: &str) -> bool {
+        let selection = match &self.output_selection {
+            Some(selection) => selection,
+            None => return true,
+        };
+        selection
+            .as_object()
+            .into_iter()
+            .flat_map(|files| files.values())
+            .filter_map(Value::as_object)
+            .flat_map(|contracts| contracts.values())
+            .filter_map(Value::as_array)
+            .flatten()
+            .filter_map(Value::as_str)
+            .any(|selected| matches_output(selected, output))
+    }
+}
+
+/// Whether the `selected` output, eg. `evm.bytecode`, includes `output`.
+fn matches_output(selected: &str, output: &str) -> bool {
+    selected == "*"
+        || selected == output
+        || output
+            .strip_prefix(selected)
+            .map_or(false, |rest| rest.starts_with('.'))
+}
+
+/// Inserts `value` at the JSON `pointer`, creating the objects on the way.
+fn insert_at(target: &mut Value, pointer: &str, value: Value) {
+    let mut target = target;
+    let keys = pointer
+        .trim_start_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    let (last, parents) = keys.split_last().expect("empty JSON pointer");
+    for key in parents {
+        target = target
+            .as_object_mut()
+            .expect("not a JSON object")
+            .entry(*key)
+            .or_insert_with(|| json!({}));
+    }
+    target
+        .as_object_mut()
+        .expect("not a JSON object")
+        .insert(last.to_string(), value);
+}
+
+fn diagnostics_json(diagnostics: &[Diagnostic], files: &FileStore) -> Vec<Value> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let severity = match diagnostic.severity {
+                Severity::Error | Severity::Bug => "error",
+                Severity::Warning => "warning",
+                Severity::Note | Severity::Help => "info",
+            };
+            error_json(
+                severity,
+                &diagnostic.message,
+                &diagnostics_string(&[diagnostic.clone()], files),
+            )
+        })
+        .collect()
+}
+
+fn error_json(severity: &str, message: &str, formatted_message: &str) -> Value {
+    json!({
+        "severity": severity,
+        "message": message,
+        "formattedMessage": formatted_message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, matches_output};
+    use serde_json::{json, Value};
+
+    const SRC: &str = "contract Foo:
+    pub fn bar() -> u256:
+        return 1
+
+contract Baz:
+    pub fn qux() -> bool:
+        return true
+";
+
+    fn input(sources: Value, output_selection: Value) -> String {
+        json!({
+            "language": "Fe",
+            "sources": sources,
+            "settings": { "outputSelection": output_selection },
+        })
+        .to_string()
+    }
+
+    /// The messages of the errors in an output, which must all have the
+    /// given severity.
+    fn messages(output: &Value, severity: &str) -> Vec<String> {
+        output["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| {
+                assert_eq!(error["severity"], severity);
+                error["message"].as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn selected_outputs() {
+        let output = compile(&input(
+            json!({ "main.fe": { "content": SRC } }),
+            json!({ "*": { "Foo": ["abi"], "Baz": ["ir"] } }),
+        ));
+        assert_eq!(output["schemaVersion"], 1);
+        assert_eq!(output["errors"], json!([]));
+
+        let contracts = &output["contracts"]["main.fe"];
+        let foo = contracts["Foo"].as_object().unwrap();
+        assert_eq!(foo.keys().collect::<Vec<_>>(), ["abi"]);
+        assert_eq!(foo["abi"][0]["name"], "bar");
+        assert_eq!(foo["abi"][0]["outputs"][0]["type"], "uint256");
+
+        let baz = contracts["Baz"].as_object().unwrap();
+        assert_eq!(baz.keys().collect::<Vec<_>>(), ["ir"]);
+        assert!(baz["ir"].as_str().unwrap().contains("object \"Baz\""));
+    }
+
+    #[test]
+    fn selection_by_file_and_wildcard() {
+        let output = compile(&input(
+            json!({ "main.fe": { "content": SRC } }),
+            json!({ "main.fe": { "*": ["abi"] }, "*": { "Baz": ["irRuntime"] } }),
+        ));
+        let contracts = &output["contracts"]["main.fe"];
+        assert_eq!(
+            contracts["Foo"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["abi"]
+        );
+        assert_eq!(
+            contracts["Baz"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["abi", "irRuntime"]
+        );
+
+        // nothing is selected for another file
+        let output = compile(&input(
+            json!({ "main.fe": { "content": SRC } }),
+            json!({ "other.fe": { "*": ["*"] } }),
+        ));
+        assert_eq!(output["contracts"]["main.fe"]["Foo"], json!({}));
+    }
+
+    #[test]
+    fn nested_outputs() {
+        assert!(matches_output("*", "evm.bytecode.object"));
+        assert!(matches_output("evm", "evm.bytecode.object"));
+        assert!(matches_output("evm.bytecode", "evm.bytecode.object"));
+        assert!(matches_output("abi", "abi"));
+        assert!(!matches_output(
+            "evm.bytecode",
+            "evm.deployedBytecode.object"
+        ));
+        assert!(!matches_output("ir", "irRuntime"));
+    }
+
+    #[test]
+    fn compile_errors() {
+        let src = "contract Foo:\n    pub fn bar() -> u256:\n        return true\n";
+        let output = compile(&input(
+            json!({ "main.fe": { "content": src } }),
+            json!({ "*": { "*": ["abi"] } }),
+        ));
+        assert_eq!(output["contracts"], json!({}));
+        assert_eq!(
+            messages(&output, "error"),
+            ["expected function to return `u256` but was `bool`"]
+        );
+        let formatted = output["errors"][0]["formattedMessage"].as_str().unwrap();
+        assert!(formatted.contains("main.fe:3:9"), "{}", formatted);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let error = |input: &str| {
+            let output = compile(input);
+            assert_eq!(output["schemaVersion"], 1);
+            messages(&output, "error").join("\n")
+        };
+        assert!(error("{").starts_with("Invalid JSON input: "));
+        assert_eq!(
+            error(r#"{ "language": "Solidity", "sources": {} }"#),
+            r#"Unsupported language "Solidity". Only "Fe" is supported."#
+        );
+        assert_eq!(
+            error(r#"{ "language": "Fe" }"#),
+            "`sources` must be an object that maps paths to sources"
+        );
+        assert_eq!(error(r#"{ "sources": {} }"#), "`sources` is empty");
+        assert_eq!(
+            error(r#"{ "sources": { "main.fe": { "urls": [] } } }"#),
+            "Source `main.fe` must have a string `content`"
+        );
+        assert_eq!(
+            error(
+                r#"{ "sources": { "main.fe": { "content": "" } },
+                     "settings": { "optimizer": { "enabled": 1 } } }"#
+            ),
+            "`settings.optimizer.enabled` must be a boolean"
+        );
+    }
+}
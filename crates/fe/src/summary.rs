@@ -0,0 +1,236 @@
+//! The status that the CLI exits with, and the summary of a build that's
+//! written with `--json-summary`, eg.
+//!
+//! ```json
+//! {
+//!   "schemaVersion": 1,
+//!   "diagnostics": { "bug": 0, "error": 2, "help": 0, "note": 0, "warning": 1 },
+//!   "exit_code": 3,
+//!   "status": "type_error"
+//! }
+//! ```
+//!
+//! `diagnostics` counts the errors and warnings found in the input by
+//! severity, including those that weren't printed because of `-q`.
+
+use fe_common::diagnostics::{Diagnostic, Severity};
+use fe_driver::{artifacts, CompilePhase};
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// The status that `fe` exits with. Errors in the input, in reading or
+/// writing files and in the compiler itself each have their own code, so
+/// that scripts can tell a user's mistake from a compiler bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success,
+    /// A test or a standard check failed, or the language server stopped
+    /// before it was shut down.
+    Failure,
+    ParseError,
+    TypeError,
+    /// The input was analyzed but couldn't be compiled, eg. because of a
+    /// lowering plugin.
+    CodegenError,
+    /// An input file, the project manifest or an output couldn't be read or
+    /// written.
+    IoError,
+    /// The project manifest is invalid, or a profile in it can't be built.
+    ConfigError,
+    /// The compiler panicked, which is a bug in the compiler. This is the code
+    /// that Rust exits with after a panic.
+    InternalError,
+    /// The build was interrupted with Ctrl-C. This is the code that a shell
+    /// gives a process that was stopped by `SIGINT`.
+    Interrupted,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::Failure => 1,
+            ExitCode::ParseError => 2,
+            ExitCode::TypeError => 3,
+            ExitCode::CodegenError => 4,
+            ExitCode::IoError => 5,
+            ExitCode::ConfigError => 6,
+            ExitCode::InternalError => 101,
+            ExitCode::Interrupted => 130,
+        }
+    }
+
+    /// The name of the status in the JSON summary.
+    pub fn name(self) -> &'static str {
+        match self {
+            ExitCode::Success => "success",
+            ExitCode::Failure => "failure",
+            ExitCode::ParseError => "parse_error",
+            ExitCode::TypeError => "type_error",
+            ExitCode::CodegenError => "codegen_error",
+            ExitCode::IoError => "io_error",
+            ExitCode::ConfigError => "config_error",
+            ExitCode::InternalError => "internal_error",
+            ExitCode::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn exit(self) -> ! {
+        std::process::exit(self.code())
+    }
+}
+
+impl From<CompilePhase> for ExitCode {
+    fn from(phase: CompilePhase) -> Self {
+        match phase {
+            CompilePhase::Parse => ExitCode::ParseError,
+            CompilePhase::Analysis => ExitCode::TypeError,
+            CompilePhase::Codegen => ExitCode::CodegenError,
+            CompilePhase::Cancelled => ExitCode::Interrupted,
+        }
+    }
+}
+
+/// The number of diagnostics of each severity that a build found.
+#[derive(Default)]
+pub struct DiagnosticCounts(RefCell<BTreeMap<Severity, usize>>);
+
+impl DiagnosticCounts {
+    pub fn add(&self, diagnostics: &[Diagnostic]) {
+        let mut counts = self.0.borrow_mut();
+        for diagnostic in diagnostics {
+            *counts.entry(diagnostic.severity).or_default() += 1;
+        }
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.0.borrow().get(&severity).copied().unwrap_or(0)
+    }
+}
+
+/// Writes the JSON summary of a build that exited with `exit_code` to `path`.
+pub fn write(path: &str, exit_code: ExitCode, counts: &DiagnosticCounts) -> Result<(), String> {
+    let summary = artifacts::versioned(json!({
+        "status": exit_code.name(),
+        "exit_code": exit_code.code(),
+        "diagnostics": {
+            "bug": counts.count(Severity::Bug),
+            "error": counts.count(Severity::Error),
+            "help": counts.count(Severity::Help),
+            "note": counts.count(Severity::Note),
+            "warning": counts.count(Severity::Warning),
+        },
+    }));
+    let content = serde_json::to_string_pretty(&summary).expect("failed to serialize summary");
+    fs::write(path, content).map_err(|err| {
+        format!(
+            "Failed to write the JSON summary to `{}`. Error: {}",
+            path, err
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write, DiagnosticCounts, ExitCode};
+    use fe_common::diagnostics::{Diagnostic, Severity};
+    use fe_driver::CompilePhase;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn diagnostic(severity: Severity) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: None,
+            message: "message".into(),
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    #[test]
+    fn exit_codes() {
+        let codes = [
+            ExitCode::Success,
+            ExitCode::Failure,
+            ExitCode::ParseError,
+            ExitCode::TypeError,
+            ExitCode::CodegenError,
+            ExitCode::IoError,
+            ExitCode::ConfigError,
+            ExitCode::InternalError,
+            ExitCode::Interrupted,
+        ]
+        .map(|exit_code| (exit_code.code(), exit_code.name()));
+        assert_eq!(
+            codes,
+            [
+                (0, "success"),
+                (1, "failure"),
+                (2, "parse_error"),
+                (3, "type_error"),
+                (4, "codegen_error"),
+                (5, "io_error"),
+                (6, "config_error"),
+                (101, "internal_error"),
+                (130, "interrupted"),
+            ]
+        );
+    }
+
+    #[test]
+    fn exit_codes_of_compile_phases() {
+        assert_eq!(ExitCode::from(CompilePhase::Parse), ExitCode::ParseError);
+        assert_eq!(ExitCode::from(CompilePhase::Analysis), ExitCode::TypeError);
+        assert_eq!(
+            ExitCode::from(CompilePhase::Codegen),
+            ExitCode::CodegenError
+        );
+        assert_eq!(
+            ExitCode::from(CompilePhase::Cancelled),
+            ExitCode::Interrupted
+        );
+    }
+
+    #[test]
+    fn json_summary() {
+        let counts = DiagnosticCounts::default();
+        counts.add(&[diagnostic(Severity::Error), diagnostic(Severity::Warning)]);
+        counts.add(&[diagnostic(Severity::Error)]);
+
+        let path = std::env::temp_dir().join(format!("fe-summary-{}.json", std::process::id()));
+        write(path.to_str().unwrap(), ExitCode::TypeError, &counts).unwrap();
+        let summary: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            summary,
+            json!({
+                "schemaVersion": 1,
+                "status": "type_error",
+                "exit_code": 3,
+                "diagnostics": { "bug": 0, "error": 2, "help": 0, "note": 0, "warning": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn unwritable_json_summary() {
+        let dir = std::env::temp_dir().join(format!("fe-summary-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // the path is a directory
+        let error = write(
+            dir.to_str().unwrap(),
+            ExitCode::Success,
+            &DiagnosticCounts::default(),
+        )
+        .unwrap_err();
+        fs::remove_dir(&dir).unwrap();
+        assert!(
+            error.starts_with("Failed to write the JSON summary to "),
+            "{}",
+            error
+        );
+    }
+}
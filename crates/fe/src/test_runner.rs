@@ -0,0 +1,179 @@
+//! The `fe test` subcommand, which compiles the input and runs the tests of
+//! its contracts with [`fe_test`].
+
+use crate::summary::ExitCode;
+use crate::Build;
+#[cfg(feature = "solc-backend")]
+use fe_common::files::{ColumnUnit, SourceFile};
+#[cfg(feature = "solc-backend")]
+use fe_test::{DbgValue, GasSnapshot, TestResult, GAS_SNAPSHOT_FILE_NAME};
+#[cfg(feature = "solc-backend")]
+use std::fs;
+use std::path::Path;
+
+/// Compiles and tests the input, printing the outcome of each test and the
+/// values that it passed to `dbg`. Returns
+/// the code of the compilation error if the input didn't compile, and a
+/// failure if any test failed.
+///
+/// With a `gas_threshold`, the gas used by the tests is also checked against
+/// the `.gas-snapshot` file in `project_dir`, and a test that used more than
+/// `gas_threshold` percent more gas than its snapshot fails the run.
+/// The snapshot is written if every test passed without a regression, so that
+/// it tracks improvements and new tests.
+#[cfg(feature = "solc-backend")]
+pub fn run(build: &Build, project_dir: &Path, gas_threshold: Option<f64>) -> ExitCode {
+    let (sources, module) = match build.compile() {
+        Ok(compiled) => compiled,
+        Err(exit_code) => return exit_code,
+    };
+
+    // The contracts of the other modules of an ingot are named by the path of
+    // their module, eg. `tokens::erc20::TokenTest` for `src/tokens/erc20.fe`.
+    let mut names = vec![];
+    for (name, contract) in &module.contracts {
+        names.push((name.clone(), contract));
+    }
+    for (path, contracts) in &module.module_contracts {
+        let module_path = Path::new(path)
+            .with_extension("")
+            .iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("::");
+        for (name, contract) in contracts {
+            names.push((format!("{}::{}", module_path, name), contract));
+        }
+    }
+    let contracts = names
+        .iter()
+        .map(|(name, contract)| fe_test::TestContract {
+            name,
+            json_abi: &contract.json_abi,
+            bytecode: &contract.bytecode,
+            tests: &contract.tests,
+        })
+        .collect::<Vec<_>>();
+    let results = fe_test::run(&contracts);
+
+    println!(
+        "running {} test{}",
+        results.len(),
+        if results.len() == 1 { "" } else { "s" }
+    );
+    let files = sources
+        .iter()
+        .map(|source| SourceFile::new(&source.path, &source.content))
+        .collect::<Vec<_>>();
+    for result in &results {
+        println!(
+            "test {}::{} ... {}",
+            result.contract, result.name, result.outcome
+        );
+        for value in &result.dbg_values {
+            println!("    {}", format_dbg_value(value, &files));
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.outcome != fe_test::Outcome::Passed)
+        .count();
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        results.len() - failed,
+        failed
+    );
+    let passed = match gas_threshold {
+        Some(threshold) => {
+            let path = project_dir.join(GAS_SNAPSHOT_FILE_NAME);
+            check_gas_snapshot(&results, &path, threshold) && failed == 0
+        }
+        None => failed == 0,
+    };
+    if passed {
+        ExitCode::Success
+    } else {
+        ExitCode::Failure
+    }
+}
+
+/// Formats a `dbg` value like Rust's `dbg!`, eg. `[src/main.fe:12:20] x + 1 = 2`.
+/// The location is left out if the value's expression isn't in the input.
+#[cfg(feature = "solc-backend")]
+fn format_dbg_value(value: &DbgValue, files: &[SourceFile]) -> String {
+    let file = files.iter().find(|file| file.id.0 == value.file);
+    match file.and_then(|file| Some((file, file.content.get(value.start..value.end)?))) {
+        Some((file, expression)) => {
+            let (line, column) = file.line_column(value.start, ColumnUnit::Char);
+            format!(
+                "[{}:{}:{}] {} = {}",
+                file.name,
+                line + 1,
+                column + 1,
+                expression,
+                value.value
+            )
+        }
+        None => format!("[dbg] {}", value.value),
+    }
+}
+
+/// Checks the results against the gas snapshot and updates it if every test
+/// passed, printing any regressions. Returns false if there was a regression or the snapshot
+/// couldn't be read or written.
+#[cfg(feature = "solc-backend")]
+fn check_gas_snapshot(results: &[TestResult], path: &Path, threshold: f64) -> bool {
+    let previous = match fs::read_to_string(path) {
+        Ok(content) => match GasSnapshot::parse(&content) {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                eprintln!("Invalid gas snapshot `{}`: {}", path.display(), err);
+                return false;
+            }
+        },
+        Err(_) => GasSnapshot::default(),
+    };
+
+    let regressions = previous.regressions(results, threshold);
+    if !regressions.is_empty() {
+        eprintln!(
+            "\ngas regressions beyond {}% of `{}`:",
+            threshold,
+            path.display()
+        );
+        for regression in regressions {
+            eprintln!("  - {}", regression);
+        }
+        return false;
+    }
+
+    if results
+        .iter()
+        .any(|result| result.outcome != fe_test::Outcome::Passed)
+    {
+        println!(
+            "Gas snapshot `{}` not updated, as some tests failed",
+            path.display()
+        );
+        return true;
+    }
+    let snapshot = GasSnapshot::from_results(results);
+    if let Err(err) = fs::write(path, snapshot.to_string()) {
+        eprintln!(
+            "Failed to write the gas snapshot `{}`. Error: {}",
+            path.display(),
+            err
+        );
+        return false;
+    }
+    println!("Gas snapshot written to `{}`", path.display());
+    true
+}
+
+#[cfg(not(feature = "solc-backend"))]
+pub fn run(_build: &Build, _project_dir: &Path, _gas_threshold: Option<f64>) -> ExitCode {
+    eprintln!("Error: `fe test` requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.");
+    ExitCode::Failure
+}
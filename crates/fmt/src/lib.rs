@@ -0,0 +1,308 @@
+//! The Fe code formatter.
+//!
+//! The formatter works on the tokens of a module, including its comments and
+//! whitespace, rather than on its AST, so that comments, blank lines and the
+//! shape of the code are kept. It normalizes the layout of the code:
+//!
+//! - blocks are indented by four spaces, and lines that continue a bracketed
+//!   expression by one more level per bracket that's still open,
+//! - tokens on a line are separated by single spaces, except where the style
+//!   of the printed AST has none, eg. in `f(x=1)`, `a.b` and `x: u8`,
+//! - trailing whitespace and runs of blank lines are removed, and the file
+//!   ends with a single newline.
+//!
+//! Inline Yul blocks, and the spacing around `<` and `>`, which may delimit
+//! generic arguments, are kept as they are.
+
+use fe_common::diagnostics::{Diagnostic, Label, Severity};
+use fe_common::files::SourceFileId;
+use fe_common::Span;
+use fe_parser::lexer::Lexer;
+use fe_parser::{parse_file, Token, TokenKind};
+
+const INDENT: &str = "    ";
+
+/// Formats the source code of a module.
+///
+/// Returns the parser's diagnostics if the module doesn't parse without
+/// errors, since its layout may be the cause of the errors.
+pub fn format_module(file_id: SourceFileId, src: &str) -> Result<String, Vec<Diagnostic>> {
+    let (module, diagnostics) = parse_file(file_id, src)?;
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity >= Severity::Error)
+    {
+        return Err(diagnostics);
+    }
+
+    let formatted = format_tokens(Lexer::with_trivia(file_id, src));
+
+    // The formatter only changes the layout, so the formatted code must parse
+    // to the same module.
+    match parse_file(file_id, &formatted) {
+        Ok((formatted_module, _)) if formatted_module.to_string() == module.to_string() => {
+            Ok(formatted)
+        }
+        _ => Err(vec![Diagnostic {
+            severity: Severity::Bug,
+            code: None,
+            message: "formatting changed the meaning of the module".into(),
+            labels: vec![Label::primary(
+                Span::new(file_id, 0, src.len()),
+                "the module was left unformatted",
+            )],
+            notes: vec!["please report this at https://github.com/ethereum/fe/issues".into()],
+        }]),
+    }
+}
+
+/// The kind of bracket that a token opens or closes, if any, and whether it
+/// opens it.
+fn bracket(kind: TokenKind) -> Option<(TokenKind, bool)> {
+    match kind {
+        TokenKind::ParenOpen => Some((TokenKind::ParenOpen, true)),
+        TokenKind::BracketOpen => Some((TokenKind::BracketOpen, true)),
+        TokenKind::BraceOpen => Some((TokenKind::BraceOpen, true)),
+        TokenKind::ParenClose => Some((TokenKind::ParenOpen, false)),
+        TokenKind::BracketClose => Some((TokenKind::BracketOpen, false)),
+        TokenKind::BraceClose => Some((TokenKind::BraceOpen, false)),
+        _ => None,
+    }
+}
+
+/// Whether a token can end an operand, so that a `-` after it is a binary
+/// operator and a `(` or `[` after it is a call or an index.
+fn ends_operand(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Name
+            | TokenKind::SelfValue
+            | TokenKind::Int
+            | TokenKind::Hex
+            | TokenKind::Octal
+            | TokenKind::Binary
+            | TokenKind::Text
+            | TokenKind::True
+            | TokenKind::False
+            | TokenKind::ParenClose
+            | TokenKind::BracketClose
+    )
+}
+
+/// A token of a formatted line.
+struct LineToken<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    /// Whether the token was preceded by whitespace in the source.
+    spaced: bool,
+    /// The innermost bracket that the token is in, if any.
+    bracket: Option<TokenKind>,
+}
+
+/// Whether the formatted code has a space between two tokens on a line.
+///
+/// Inside braces, which only enclose inline Yul, and around `<`, `>`, `<<`
+/// and `>>`, which may also delimit generic arguments, the source decides.
+fn space_between(prev: &LineToken, next: &LineToken, prev_is_unary: bool) -> bool {
+    use TokenKind::*;
+    if next.bracket == Some(BraceOpen) || matches!(next.kind, BraceOpen | BraceClose) {
+        return next.spaced;
+    }
+    if matches!(prev.kind, Lt | Gt | LtLt | GtGt) || matches!(next.kind, Lt | Gt | LtLt | GtGt) {
+        return next.spaced;
+    }
+    if next.kind == Comment {
+        return true;
+    }
+    if matches!(
+        next.kind,
+        Comma | ParenClose | BracketClose | Dot | Question | Colon | ColonColon
+    ) || matches!(prev.kind, ParenOpen | BracketOpen | Dot | ColonColon)
+    {
+        return false;
+    }
+    match prev.kind {
+        // `x: u8` and `if x:`, but slices keep their spacing, eg. `a[1:3]`.
+        Colon if next.bracket == Some(BracketOpen) => return next.spaced,
+        Colon | Comma => return true,
+        _ => {}
+    }
+    if matches!(next.kind, ParenOpen | BracketOpen) && ends_operand(prev.kind) {
+        return false;
+    }
+    // Call arguments are labeled without spaces, eg. `f(x=1)`.
+    if prev.kind == Eq || next.kind == Eq {
+        return next.bracket != Some(ParenOpen);
+    }
+    !prev_is_unary
+}
+
+/// How the indentation of a line is decided.
+#[derive(Clone, Copy, PartialEq)]
+enum LineStart {
+    /// The line starts a statement, and is indented by the block it's in.
+    Statement,
+    /// The line continues a bracketed expression, and is indented by the
+    /// brackets that are open at its first token.
+    Continuation,
+    /// The line continues a bracketed expression, at the given level.
+    ContinuationAt(usize),
+}
+
+#[derive(Default)]
+struct Formatter<'a> {
+    out: String,
+    /// The source widths of the indentation of the enclosing blocks.
+    block_widths: Vec<usize>,
+    /// The open brackets, with the index of the line that each was opened on.
+    brackets: Vec<(TokenKind, usize)>,
+    line: Vec<LineToken<'a>>,
+    line_index: usize,
+    /// The source width of the indentation of the current line.
+    width: usize,
+    blank_lines: usize,
+    /// Whether the last line of code ended with a `:`, so that the lines
+    /// after it are in a new block.
+    prev_ends_block_header: bool,
+}
+
+/// Formats the tokens of a module, which must include the trivia.
+fn format_tokens<'a>(tokens: impl Iterator<Item = Token<'a>>) -> String {
+    let mut formatter = Formatter {
+        block_widths: vec![0],
+        ..Formatter::default()
+    };
+    let mut start = LineStart::Statement;
+    let mut spaced = false;
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Whitespace => spaced = true,
+            TokenKind::Newline => {
+                if formatter.line.is_empty() && formatter.brackets.is_empty() {
+                    formatter.blank_lines += 1;
+                }
+                formatter.flush_line(start);
+                formatter.line_index += 1;
+                spaced = false;
+                if formatter.brackets.is_empty() {
+                    formatter.width = token.text[1..].chars().count();
+                    start = LineStart::Statement;
+                } else {
+                    start = LineStart::Continuation;
+                }
+            }
+            kind => {
+                if start == LineStart::Continuation {
+                    start = LineStart::ContinuationAt(formatter.continuation_level(kind));
+                }
+                formatter.push(kind, token.text, spaced);
+                spaced = false;
+            }
+        }
+    }
+    formatter.flush_line(start);
+    formatter.out
+}
+
+impl<'a> Formatter<'a> {
+    fn push(&mut self, kind: TokenKind, text: &'a str, spaced: bool) {
+        if let Some((_, false)) = bracket(kind) {
+            self.brackets.pop();
+        }
+        self.line.push(LineToken {
+            kind,
+            text,
+            spaced,
+            bracket: self.brackets.last().map(|(kind, _)| *kind),
+        });
+        if let Some((open, true)) = bracket(kind) {
+            self.brackets.push((open, self.line_index));
+        }
+    }
+
+    /// The level of a line that continues a bracketed expression and starts
+    /// with a token of the given kind: one level deeper than its block for
+    /// each earlier line that opened a bracket that's still open. A closing
+    /// bracket at the start of the line is at the level of the line that
+    /// opened it.
+    fn continuation_level(&self, first: TokenKind) -> usize {
+        let mut open = self.brackets.as_slice();
+        if let Some((_, false)) = bracket(first) {
+            open = &open[..open.len() - 1];
+        }
+        let mut lines = open.iter().map(|(_, line)| *line).collect::<Vec<_>>();
+        lines.dedup();
+        self.block_widths.len() - 1 + lines.len()
+    }
+
+    /// The level of a line that starts a statement or a comment, updating the
+    /// enclosing blocks.
+    fn statement_level(&mut self) -> usize {
+        let innermost = *self.block_widths.last().unwrap();
+        if self.line[0].kind == TokenKind::Comment {
+            // A comment line is indented like the code of the block that it's
+            // in, which starts after it if it follows a block header.
+            return if self.prev_ends_block_header && self.width > innermost {
+                self.block_widths.len()
+            } else {
+                self.block_widths
+                    .iter()
+                    .filter(|w| **w <= self.width)
+                    .count()
+                    - 1
+            };
+        }
+        while self.width < *self.block_widths.last().unwrap() {
+            self.block_widths.pop();
+        }
+        if self.width > *self.block_widths.last().unwrap() {
+            self.block_widths.push(self.width);
+        }
+        self.block_widths.len() - 1
+    }
+
+    fn flush_line(&mut self, start: LineStart) {
+        if self.line.is_empty() {
+            return;
+        }
+        let level = match start {
+            LineStart::ContinuationAt(level) => level,
+            _ => self.statement_level(),
+        };
+        if !self.out.is_empty() && self.blank_lines > 0 && start == LineStart::Statement {
+            self.out.push('\n');
+        }
+        self.blank_lines = 0;
+        for _ in 0..level {
+            self.out.push_str(INDENT);
+        }
+
+        let mut prev_is_unary = false;
+        for (idx, token) in self.line.iter().enumerate() {
+            let is_unary_op = matches!(token.kind, TokenKind::Minus | TokenKind::Tilde);
+            if idx > 0 {
+                let prev = &self.line[idx - 1];
+                if space_between(prev, token, prev_is_unary) {
+                    self.out.push(' ');
+                }
+                prev_is_unary = is_unary_op && !ends_operand(prev.kind);
+            } else {
+                prev_is_unary = is_unary_op;
+            }
+            self.out.push_str(token.text.trim_end());
+        }
+        self.out.push('\n');
+
+        if self.line[0].kind != TokenKind::Comment {
+            let last_code = self
+                .line
+                .iter()
+                .rev()
+                .find(|token| token.kind != TokenKind::Comment)
+                .map(|token| token.kind);
+            self.prev_ends_block_header = last_code == Some(TokenKind::Colon);
+        }
+        self.line.clear();
+    }
+}
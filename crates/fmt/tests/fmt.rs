@@ -0,0 +1,42 @@
+use fe_common::files::SourceFileId;
+use fe_fmt::format_module;
+use fe_test_files::fixture;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn format(src: &str) -> String {
+    format_module(SourceFileId::default(), src).expect("failed to format source file")
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn formats_layout() {
+    pretty_assertions::assert_eq!(
+        fixture("formatting/formatted.fe"),
+        format(fixture("formatting/unformatted.fe"))
+    )
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn rejects_parse_errors() {
+    assert!(format_module(SourceFileId::default(), "contract Foo:\n  pub fn (:\n").is_err())
+}
+
+macro_rules! test_idempotent {
+    ($name:ident, $path:expr) => {
+        #[test]
+        #[wasm_bindgen_test]
+        fn $name() {
+            let once = format(fixture($path));
+            pretty_assertions::assert_eq!(once, format(&once))
+        }
+    };
+}
+
+test_idempotent! { formatted, "formatting/formatted.fe" }
+test_idempotent! { erc20, "demos/erc20_token.fe" }
+test_idempotent! { uniswap, "demos/uniswap.fe" }
+test_idempotent! { guest_book, "demos/guest_book.fe" }
+test_idempotent! { inline_yul, "printing/yul.fe" }
+test_idempotent! { generics, "printing/generics.fe" }
+test_idempotent! { slice_expr, "printing/slice_expr.fe" }
@@ -1,5 +1,8 @@
-use fe_analyzer::namespace::types::FixedSize;
-use fe_parser::ast::{BoolOperator, CallArg, Expr, FuncStmt, UnaryOperator, VarDeclTarget};
+use fe_analyzer::const_eval::ConstValue;
+use fe_analyzer::namespace::types::{Base, FeResult, FixedSize};
+use fe_parser::ast::{
+    BoolOperator, CallArg, Expr, FuncStmt, MatchArm, SmolStr, UnaryOperator, VarDeclTarget,
+};
 use fe_parser::node::{Node, NodeId};
 
 use crate::names;
@@ -47,7 +50,7 @@ where
 {
     match node {
         StmtOrExpr::Stmt(stmt) => {
-            let node = match stmt.kind {
+            let kind = match stmt.kind {
                 FuncStmt::Assert { test, msg } => FuncStmt::Assert {
                     test: map_ast_node(test.into(), map_fn).as_expr(),
                     msg: msg.map(|val| map_ast_node(val.into(), map_fn).as_expr()),
@@ -99,15 +102,37 @@ where
                     test: map_ast_node(test.into(), map_fn).as_expr(),
                     body: map_body(body, map_fn),
                 },
+                FuncStmt::Match { value, arms } => FuncStmt::Match {
+                    value: map_ast_node(value.into(), map_fn).as_expr(),
+                    arms: arms
+                        .into_iter()
+                        .map(|arm| {
+                            MatchArm {
+                                pat: arm.kind.pat,
+                                guard: arm
+                                    .kind
+                                    .guard
+                                    .map(|val| map_ast_node(val.into(), map_fn).as_expr()),
+                                body: map_body(arm.kind.body, map_fn),
+                            }
+                            .into_traceable_node(arm.original_id)
+                        })
+                        .collect(),
+                },
                 // See comment below for why no catch all should be used here
-                FuncStmt::Pass | FuncStmt::Break | FuncStmt::Continue => stmt.kind,
-            }
-            .into_traceable_node(stmt.original_id);
+                // Inline Yul contains no Fe expressions.
+                FuncStmt::Pass | FuncStmt::Break | FuncStmt::Continue | FuncStmt::Yul(_) => {
+                    stmt.kind
+                }
+            };
+            // The span is kept, so that the mapped code can still be traced to
+            // its source, e.g. by the `RecordDbg` plugin.
+            let node = Node::with_original_id(kind, stmt.span, stmt.original_id);
 
             map_fn(node.into())
         }
         StmtOrExpr::Expr(expr) => {
-            let expr = match expr.kind {
+            let kind = match expr.kind {
                 Expr::Attribute { value, attr } => Expr::Attribute {
                     value: Box::new(map_ast_node((*value).into(), map_fn).as_expr()),
                     attr,
@@ -146,6 +171,12 @@ where
                     value: Box::new(map_ast_node((*value).into(), map_fn).as_expr()),
                     index: Box::new(map_ast_node((*index).into(), map_fn).as_expr()),
                 },
+                Expr::Slice { value, start, end } => Expr::Slice {
+                    value: Box::new(map_ast_node((*value).into(), map_fn).as_expr()),
+                    start: start
+                        .map(|start| Box::new(map_ast_node((*start).into(), map_fn).as_expr())),
+                    end: end.map(|end| Box::new(map_ast_node((*end).into(), map_fn).as_expr())),
+                },
                 Expr::Ternary {
                     if_expr,
                     test,
@@ -165,6 +196,9 @@ where
                     op,
                     operand: Box::new(map_ast_node((*operand).into(), map_fn).as_expr()),
                 },
+                Expr::Try { value } => Expr::Try {
+                    value: Box::new(map_ast_node((*value).into(), map_fn).as_expr()),
+                },
                 // The following *could* be covered via catch all. However, that would turn into a footgun if we add
                 // more expressions in the future that need to be walked. It's better to not use a catch all here.
                 Expr::Bool(_)
@@ -173,8 +207,8 @@ where
                 | Expr::Path(_)
                 | Expr::Str(_)
                 | Expr::Unit => expr.kind,
-            }
-            .into_traceable_node(expr.original_id);
+            };
+            let expr = Node::with_original_id(kind, expr.span, expr.original_id);
 
             map_fn(expr.into())
         }
@@ -434,9 +468,32 @@ pub fn inject_before_expression(
                     transformed_body.push(stmt.clone())
                 }
             }
-            FuncStmt::Break | FuncStmt::Continue | FuncStmt::Pass => {
+            FuncStmt::Break | FuncStmt::Continue | FuncStmt::Pass | FuncStmt::Yul(_) => {
                 transformed_body.push(stmt.clone())
             }
+            // The value of a `match` that's left in the lowered module is
+            // always a variable, and its arms have no guards.
+            FuncStmt::Match { value, arms } => transformed_body.push(
+                FuncStmt::Match {
+                    value,
+                    arms: arms
+                        .into_iter()
+                        .map(|arm| {
+                            MatchArm {
+                                pat: arm.kind.pat,
+                                guard: arm.kind.guard,
+                                body: inject_before_expression(
+                                    &arm.kind.body,
+                                    expression,
+                                    injection,
+                                ),
+                            }
+                            .into_traceable_node(arm.original_id)
+                        })
+                        .collect(),
+                }
+                .into_traceable_node(stmt.original_id),
+            ),
         }
     }
     transformed_body
@@ -574,6 +631,135 @@ pub fn boolean_expr_to_if(
     unreachable!()
 }
 
+/// Turns a `?` expression into a set of statements that return early if the result is an error.
+/// Expects the return type of the function, the type of the unwrapped value and the variable
+/// result name to be provided as parameters.
+pub fn try_to_if(
+    fn_result: FeResult,
+    ok_type: FixedSize,
+    expr: &Node<Expr>,
+    result_name: &str,
+) -> Vec<Node<FuncStmt>> {
+    if let Expr::Try { value } = &expr.kind {
+        // from: value?
+        // into:
+        // res_value: Result<T, E> = value
+        // if not res_value.item0:
+        //     return Result<U, E>(item0=false, item1=0, item2=res_value.item2)
+        // res: T = res_value.item1
+        let ok = match ok_type {
+            FixedSize::Base(base) => base,
+            _ => unreachable!(),
+        };
+        let value_result = FeResult {
+            ok,
+            err: fn_result.err,
+        };
+        let value_name = format!("{}_value", result_name);
+        let value_field = |field: &str| {
+            Expr::Attribute {
+                value: Expr::Name(value_name.as_str().into()).into_boxed_node(),
+                attr: SmolStr::new(field).into_node(),
+            }
+            .into_node()
+        };
+
+        let value_decl = FuncStmt::VarDecl {
+            target: VarDeclTarget::Name(value_name.as_str().into()).into_node(),
+            typ: names::fixed_size_type_desc(&FixedSize::Result(value_result)).into_node(),
+            value: Some(value.kind.clone().into_traceable_node(value.original_id)),
+        }
+        .into_node();
+
+        let err_result = Expr::Call {
+            func: Expr::Name(names::tuple_struct_name(&names::result_tuple(&fn_result)))
+                .into_boxed_node(),
+            generic_args: None,
+            args: vec![
+                CallArg {
+                    label: Some(SmolStr::new("item0").into_node()),
+                    value: Expr::Bool(false).into_node(),
+                }
+                .into_node(),
+                CallArg {
+                    label: Some(SmolStr::new("item1").into_node()),
+                    value: zero_value(fn_result.ok).into_node(),
+                }
+                .into_node(),
+                CallArg {
+                    label: Some(SmolStr::new("item2").into_node()),
+                    value: value_field("item2"),
+                }
+                .into_node(),
+            ]
+            .into_node(),
+        };
+        let early_return = FuncStmt::If {
+            test: Expr::UnaryOperation {
+                op: UnaryOperator::Not.into_node(),
+                operand: Box::new(value_field("item0")),
+            }
+            .into_node(),
+            body: vec![FuncStmt::Return {
+                value: Some(err_result.into_node()),
+            }
+            .into_node()],
+            or_else: vec![],
+        }
+        .into_node();
+
+        let result_decl = FuncStmt::VarDecl {
+            target: VarDeclTarget::Name(result_name.into()).into_node(),
+            typ: names::fixed_size_type_desc(&FixedSize::Base(ok)).into_node(),
+            value: Some(value_field("item1")),
+        }
+        .into_node();
+
+        return vec![value_decl, early_return, result_decl];
+    }
+
+    unreachable!()
+}
+
+/// The zero value of a base type, e.g. the value stored alongside the flag of an
+/// empty `Option`.
+pub fn zero_value(typ: Base) -> Expr {
+    match typ {
+        Base::Numeric(_) => Expr::Num("0".into()),
+        Base::Bool => Expr::Bool(false),
+        Base::Address | Base::FixedBytes(_) => Expr::Call {
+            func: Expr::Name(typ.name()).into_boxed_node(),
+            generic_args: None,
+            args: vec![CallArg {
+                label: None,
+                value: Expr::Num("0".into()).into_node(),
+            }
+            .into_node()]
+            .into_node(),
+        },
+        Base::Unit => Expr::Unit,
+    }
+}
+
+/// The literal expression of a value that was computed at compile time.
+pub fn const_value_expr(value: &ConstValue) -> Expr {
+    match value {
+        ConstValue::Int(num) => {
+            let num = num.to_string();
+            match num.strip_prefix('-') {
+                Some(digits) => Expr::UnaryOperation {
+                    op: UnaryOperator::USub.into_node(),
+                    operand: Expr::Num(digits.into()).into_boxed_node(),
+                },
+                None => Expr::Num(num.into()),
+            }
+        }
+        ConstValue::Bool(val) => Expr::Bool(*val),
+        ConstValue::Str(val) => Expr::Str(val.clone()),
+        ConstValue::Unit => Expr::Unit,
+    }
+}
+
 /// Returns a vector of expressions with all ternary expressions that are
 /// contained within the given function statement. The last expression
 /// in the list is the outermost ternary expression found in the statement.
@@ -634,6 +820,36 @@ pub fn get_first_boolean_expressions(nodes: &[Node<FuncStmt>]) -> Vec<Node<Expr>
     vec![]
 }
 
+/// Returns a vector of expressions with all `?` expressions that are
+/// contained within the given function statement. The last expression
+/// in the list is the outermost `?` expression found in the statement.
+pub fn get_all_try_expressions(node: &Node<FuncStmt>) -> Vec<Node<Expr>> {
+    let mut expressions = vec![];
+    map_ast_node(node.clone().into(), &mut |exp| {
+        if let StmtOrExpr::Expr(expr) = &exp {
+            if let Expr::Try { .. } = expr.kind {
+                expressions.push(expr.clone())
+            }
+        }
+
+        exp
+    });
+
+    expressions
+}
+
+/// For a given set of nodes returns the first set of `?` expressions that can be found.
+/// The last expression in the list is the outermost `?` expression found in the statement.
+pub fn get_first_try_expressions(nodes: &[Node<FuncStmt>]) -> Vec<Node<Expr>> {
+    for node in nodes {
+        let result = get_all_try_expressions(node);
+        if !result.is_empty() {
+            return result;
+        }
+    }
+    vec![]
+}
+
 /// In a given set of `nodes replaces a node that matches the `node_id` with a name expression node.
 pub fn replace_node_with_name_expression(
     nodes: &[Node<FuncStmt>],
@@ -658,13 +874,15 @@ pub fn replace_node_with_name_expression(
 mod tests {
 
     use crate::ast_utils::get_first_ternary_expressions;
+    use crate::ast_utils::get_first_try_expressions;
     use crate::ast_utils::inject_before_expression;
     use crate::ast_utils::map_ast_node;
     use crate::ast_utils::replace_node_with_name_expression;
     use crate::ast_utils::ternary_to_if;
+    use crate::ast_utils::try_to_if;
     use crate::ast_utils::StmtOrExpr;
     use crate::utils::ZeroSpanNode;
-    use fe_analyzer::namespace::types::FixedSize;
+    use fe_analyzer::namespace::types::{Base, FeResult, FixedSize, Integer};
     use fe_parser::ast::BinOperator;
     use fe_parser::ast::CallArg;
     use fe_parser::ast::Expr;
@@ -965,4 +1183,56 @@ else:
 foo(outer)"#
         )
     }
+
+    #[test]
+    fn lower_try_expression() {
+        let try_expr = Expr::Try {
+            value: Expr::Call {
+                func: Expr::Name("foo".into()).into_boxed_node(),
+                generic_args: None,
+                args: vec![].into_node(),
+            }
+            .into_boxed_node(),
+        }
+        .into_node();
+
+        let call = Expr::Call {
+            func: Expr::Name("bar".into()).into_boxed_node(),
+            generic_args: None,
+            args: vec![CallArg {
+                label: None,
+                value: try_expr.clone(),
+            }
+            .into_node()]
+            .into_node(),
+        }
+        .into_node();
+
+        let original_body = vec![FuncStmt::Expr { value: call }.into_node()];
+        assert_eq!(to_code(&original_body), "bar(foo()?)");
+
+        let all_try = get_first_try_expressions(&original_body);
+        assert_eq!(all_try.len(), 1);
+        let found = all_try.last().unwrap();
+        assert_eq!(found.original_id, try_expr.original_id);
+
+        let fn_result = FeResult {
+            ok: Base::Bool,
+            err: Base::Numeric(Integer::U8),
+        };
+        let transformed = try_to_if(fn_result, FixedSize::u256(), found, "res");
+
+        let new_body = inject_before_expression(&original_body, found.original_id, &transformed);
+        let new_body = replace_node_with_name_expression(&new_body, found.original_id, "res");
+
+        assert_eq!(
+            to_code(&new_body),
+            "let res_value: $tuple_bool_u256_u8_ = foo()
+if not res_value.item0:
+    return $tuple_bool_bool_u8_(item0=false, item1=false, item2=res_value.item2)
+
+let res: u256 = res_value.item1
+bar(res)"
+        );
+    }
 }
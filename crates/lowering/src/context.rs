@@ -1,9 +1,11 @@
-use fe_analyzer::context::{ExpressionAttributes, FunctionBody};
-use fe_analyzer::namespace::items::{FunctionId, ModuleId};
-use fe_analyzer::namespace::types::{Array, FixedSize, Tuple};
+use crate::names;
+use fe_analyzer::context::{CallType, ExpressionAttributes, FunctionBody};
+use fe_analyzer::namespace::items::{FunctionId, ModuleId, TypeDef};
+use fe_analyzer::namespace::types::{Array, FeOption, FeResult, FixedSize, Tuple};
 use fe_analyzer::AnalyzerDb;
+use fe_parser::ast::SmolStr;
 use fe_parser::node::NodeId;
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use std::rc::Rc;
 
 pub struct ModuleContext<'db> {
@@ -15,15 +17,40 @@ pub struct ModuleContext<'db> {
 
     /// Tuples that are used in the module
     pub tuples: IndexSet<Tuple>,
+
+    /// Option types that are used in the module
+    pub options: IndexSet<FeOption>,
+
+    /// Result types that are used in the module
+    pub results: IndexSet<FeResult>,
+
+    /// The type parameters of the instance of a generic function or struct
+    /// that is being lowered, with the types they stand for
+    pub type_params: IndexMap<SmolStr, TypeDef>,
 }
 
 impl<'db> ModuleContext<'db> {
+    /// Registers an `Option` type, along with the tuple it is lowered to.
+    pub fn add_option(&mut self, option: FeOption) {
+        self.tuples.insert(names::option_tuple(&option));
+        self.options.insert(option);
+    }
+
+    /// Registers a `Result` type, along with the tuple it is lowered to.
+    pub fn add_result(&mut self, result: FeResult) {
+        self.tuples.insert(names::result_tuple(&result));
+        self.results.insert(result);
+    }
+
     pub fn new(db: &'db dyn AnalyzerDb, module: ModuleId) -> Self {
         Self {
             db,
             module,
             list_expressions: IndexSet::new(),
             tuples: IndexSet::new(),
+            options: IndexSet::new(),
+            results: IndexSet::new(),
+            type_params: IndexMap::new(),
         }
     }
 }
@@ -67,6 +94,12 @@ impl<'a, 'db> FnContext<'a, 'db> {
     pub fn var_decl_type<T: Into<NodeId>>(&self, node_id: T) -> Option<&FixedSize> {
         self.body.var_decl_types.get(&node_id.into())
     }
+    pub fn pattern_type<T: Into<NodeId>>(&self, node_id: T) -> Option<&FixedSize> {
+        self.body.pattern_types.get(&node_id.into())
+    }
+    pub fn call_type<T: Into<NodeId>>(&self, node_id: T) -> Option<&CallType> {
+        self.body.calls.get(&node_id.into())
+    }
 }
 
 impl<'a, 'db> AsMut<ModuleContext<'db>> for FnContext<'a, 'db> {
@@ -7,9 +7,11 @@ mod context;
 pub mod db;
 mod mappers;
 mod names;
+pub mod plugins;
 mod utils;
 
 pub use db::{LoweringDb, TestDb};
+pub use plugins::LoweringPlugin;
 
 /// Lower a Fe module
 ///
@@ -30,6 +30,12 @@ pub fn contract_def(context: &mut ModuleContext, contract: ContractId) -> Node<a
         functions.push(ast::ContractStmt::Function(functions::func_def(
             context, init_fn,
         )));
+    } else if let (Some(ownable_span), false) =
+        (contract.ownable_span(db), contract.is_upgradeable(db))
+    {
+        functions.push(ast::ContractStmt::Function(functions::owner_init_fn(
+            ownable_span,
+        )));
     }
 
     if let Some(call_fn) = contract.call_function(db) {
@@ -45,6 +51,15 @@ pub fn contract_def(context: &mut ModuleContext, contract: ContractId) -> Node<a
             fields,
             body: [events, functions].concat(),
             pub_qual: None,
+            layout: None,
+            upgradeable_qual: None,
+            // yulgen generates the `multicall` function from the lowered contract
+            multicall_qual: node.kind.multicall_qual,
+            // the members that these add are already part of the contract
+            ownable_qual: None,
+            pausable_qual: None,
+            roles_qual: None,
+            is_interface: node.kind.is_interface,
         },
         node.span,
     )
@@ -1,10 +1,20 @@
+use crate::ast_utils::{const_value_expr, zero_value};
 use crate::context::FnContext;
-use crate::names::{list_expr_generator_fn_name, tuple_struct_name};
+use crate::names::{
+    self, list_expr_generator_fn_name, option_tuple, option_unwrap_fn_name,
+    option_unwrap_or_fn_name, result_tuple, result_unwrap_fn_name, result_unwrap_or_fn_name,
+    tuple_struct_name,
+};
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::namespace::items::Item;
-use fe_analyzer::namespace::types::{Type, TypeDowncast};
+use fe_analyzer::builtins::{GlobalFunction, ValueMethod};
+use fe_analyzer::const_eval;
+use fe_analyzer::context::{CallType, Location};
+use fe_analyzer::namespace::items::{Class, FunctionId, Item, ModuleConstantId, ModuleId};
+use fe_analyzer::namespace::types::{Base, Struct, Tuple, Type, TypeDowncast};
+use fe_analyzer::AnalyzerDb;
 use fe_parser::ast as fe;
-use fe_parser::node::Node;
+use fe_parser::ast::SmolStr;
+use fe_parser::node::{Node, Span};
 
 /// Lowers an expression and all sub expressions.
 pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
@@ -13,13 +23,26 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
 
     let lowered_kind = match exp.kind {
         fe::Expr::Name(_) => expr_name(context, exp),
-        fe::Expr::Path(_) => exp.kind,
+        fe::Expr::Path(_) => expr_path(context, exp),
         fe::Expr::Num(_) => exp.kind,
         fe::Expr::Bool(_) => exp.kind,
         fe::Expr::Subscript { value, index } => fe::Expr::Subscript {
             value: boxed_expr(context, value),
             index: boxed_expr(context, index),
         },
+        fe::Expr::Slice { .. } => expr_slice(context, exp),
+        fe::Expr::Try { value } => {
+            // The `?` itself is lowered once the function body is complete; see `try_to_if`.
+            let typ = context
+                .expression_attributes(&value)
+                .expect("missing attributes")
+                .typ
+                .clone();
+            flagged_type_tuple(context, &typ);
+            fe::Expr::Try {
+                value: boxed_expr(context, value),
+            }
+        }
         fe::Expr::Attribute { value, attr } => fe::Expr::Attribute {
             value: boxed_expr(context, value),
             attr,
@@ -52,15 +75,7 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
             op,
             right: boxed_expr(context, right),
         },
-        fe::Expr::Call {
-            func,
-            generic_args,
-            args,
-        } => fe::Expr::Call {
-            func: boxed_expr(context, func),
-            generic_args,
-            args: call_args(context, args),
-        },
+        fe::Expr::Call { .. } => expr_call(context, exp),
         fe::Expr::List { .. } => expr_list(context, exp),
         fe::Expr::Tuple { .. } => expr_tuple(context, exp),
         fe::Expr::Str(_) => exp.kind,
@@ -113,18 +128,90 @@ fn expr_name(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
     };
 
     let db = context.db();
-    match context.id.module(db).resolve_name(db, name) {
-        Some(Item::Constant(val)) => {
-            assert!(
-                val.is_base_type(db),
-                "Should have been rejected at first analyzer pass"
-            );
-            val.value(db)
-        }
+    let item = match context.id.class(db) {
+        Some(Class::Contract(contract)) => contract.resolve_name(db, name),
+        _ => context.id.module(db).resolve_name(db, name),
+    };
+    match item {
+        Some(Item::Constant(val)) => constant_value(db, val),
         _ => exp.kind,
     }
 }
 
+/// Lowers a path expression. Paths to constants, eg. `limits::MAX`, are
+/// replaced by the constant's value like names are.
+fn expr_path(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
+    let path = match &exp.kind {
+        fe::Expr::Path(path) => path,
+        _ => unreachable!(),
+    };
+
+    let db = context.db();
+    match context.id.module(db).resolve_path_internal(db, path).value {
+        Some(Item::Constant(val)) => constant_value(db, val),
+        _ => exp.kind,
+    }
+}
+
+/// The value of a module or contract constant, as it replaces references to
+/// the constant.
+pub fn constant_value(db: &dyn AnalyzerDb, val: ModuleConstantId) -> fe::Expr {
+    assert!(
+        val.is_base_type(db),
+        "Should have been rejected at first analyzer pass"
+    );
+    match val.value(db) {
+        literal @ (fe::Expr::Bool(_) | fe::Expr::Num(_) | fe::Expr::Str(_) | fe::Expr::Unit) => {
+            literal
+        }
+        _ => const_value_expr(
+            &val.const_value(db)
+                .expect("Should have been rejected at first analyzer pass"),
+        ),
+    }
+}
+
+/// Lowers a slice expression. The bounds are constant, so they are lowered to
+/// literals, and omitted bounds are filled in.
+fn expr_slice(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
+    let size = match &context
+        .expression_attributes(&exp)
+        .expect("missing attributes")
+        .typ
+    {
+        Type::Array(array) => array.size,
+        Type::Base(Base::FixedBytes(size)) => *size,
+        _ => unreachable!(),
+    };
+
+    if let fe::Expr::Slice { value, start, .. } = exp.kind {
+        let start = match start {
+            Some(start) => {
+                let db = context.db();
+                match const_eval::eval_const_expr(
+                    db,
+                    context.id.module(db),
+                    context.id.class(db),
+                    &start,
+                ) {
+                    Ok(const_eval::ConstValue::Int(start)) => start,
+                    _ => panic!("slice bounds should be constant"),
+                }
+            }
+            None => 0.into(),
+        };
+        let end = start.clone() + size;
+
+        return fe::Expr::Slice {
+            value: boxed_expr(context, value),
+            start: Some(fe::Expr::Num(start.to_string().into()).into_boxed_node()),
+            end: Some(fe::Expr::Num(end.to_string().into()).into_boxed_node()),
+        };
+    }
+
+    unreachable!()
+}
+
 fn expr_tuple(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
     let typ = context
         .expression_attributes(&exp)
@@ -202,3 +289,313 @@ fn expr_list(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
 
     unreachable!()
 }
+
+fn expr_call(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
+    let node_id = exp.original_id;
+    let (func, generic_args, args) = match exp.kind {
+        fe::Expr::Call {
+            func,
+            generic_args,
+            args,
+        } => (func, generic_args, args),
+        _ => unreachable!(),
+    };
+
+    match context.call_type(&func).cloned() {
+        Some(CallType::Pure(function) | CallType::AssociatedFunction { function, .. })
+            if function.is_const(context.db()) =>
+        {
+            match const_call_value(context, function, &args, exp.span) {
+                Some(value) => const_value_expr(&value),
+                None => {
+                    let (func, generic_args) = call_func(context, func, generic_args);
+                    fe::Expr::Call {
+                        func,
+                        generic_args,
+                        args: call_args(context, args),
+                    }
+                }
+            }
+        }
+        Some(CallType::BuiltinFunction(
+            function @ (GlobalFunction::Some
+            | GlobalFunction::None
+            | GlobalFunction::Ok
+            | GlobalFunction::Err),
+        )) => {
+            let typ = context
+                .expression_attributes(node_id)
+                .expect("missing attributes")
+                .typ
+                .clone();
+            let tuple = flagged_type_tuple(context, &typ);
+            let mut values = args
+                .kind
+                .into_iter()
+                .map(|arg| expr(context, arg.kind.value));
+            let items = match (function, typ) {
+                (GlobalFunction::Some, _) => vec![
+                    fe::Expr::Bool(true).into_node(),
+                    values.next().expect("missing `some` argument"),
+                ],
+                (GlobalFunction::None, Type::Option(option)) => vec![
+                    fe::Expr::Bool(false).into_node(),
+                    zero_value(option.inner).into_node(),
+                ],
+                (GlobalFunction::Ok, Type::Result(result)) => vec![
+                    fe::Expr::Bool(true).into_node(),
+                    values.next().expect("missing `ok` argument"),
+                    zero_value(result.err).into_node(),
+                ],
+                (GlobalFunction::Err, Type::Result(result)) => vec![
+                    fe::Expr::Bool(false).into_node(),
+                    zero_value(result.ok).into_node(),
+                    values.next().expect("missing `err` argument"),
+                ],
+                _ => unreachable!(),
+            };
+            tuple_constructor(&tuple, items)
+        }
+        Some(CallType::BuiltinValueMethod {
+            method:
+                method @ (ValueMethod::IsSome
+                | ValueMethod::IsNone
+                | ValueMethod::IsOk
+                | ValueMethod::IsErr
+                | ValueMethod::Unwrap
+                | ValueMethod::UnwrapOr),
+            typ,
+        }) => {
+            let target = match func.kind {
+                fe::Expr::Attribute { value, .. } => value,
+                _ => unreachable!(),
+            };
+            flagged_type_tuple(context, &typ);
+            let in_storage = matches!(
+                context
+                    .expression_attributes(&target)
+                    .expect("missing attributes")
+                    .final_location(),
+                Location::Storage { .. }
+            );
+            let target = expr(context, *target);
+
+            match method {
+                ValueMethod::IsSome | ValueMethod::IsOk => flag(target),
+                ValueMethod::IsNone | ValueMethod::IsErr => fe::Expr::UnaryOperation {
+                    op: fe::UnaryOperator::Not.into_node(),
+                    operand: flag(target).into_boxed_node(),
+                },
+                _ => {
+                    // The helper functions take their argument by value, so copy it out of storage.
+                    let target = if in_storage {
+                        fe::Expr::Call {
+                            func: fe::Expr::Attribute {
+                                value: Box::new(target),
+                                attr: SmolStr::new("to_mem").into_node(),
+                            }
+                            .into_boxed_node(),
+                            generic_args: None,
+                            args: vec![].into_node(),
+                        }
+                        .into_node()
+                    } else {
+                        target
+                    };
+                    let fn_name = match (method, typ) {
+                        (ValueMethod::Unwrap, Type::Option(option)) => {
+                            option_unwrap_fn_name(&option)
+                        }
+                        (ValueMethod::UnwrapOr, Type::Option(option)) => {
+                            option_unwrap_or_fn_name(&option)
+                        }
+                        (ValueMethod::Unwrap, Type::Result(result)) => {
+                            result_unwrap_fn_name(&result)
+                        }
+                        (ValueMethod::UnwrapOr, Type::Result(result)) => {
+                            result_unwrap_or_fn_name(&result)
+                        }
+                        _ => unreachable!(),
+                    };
+                    let mut lowered_args = vec![fe::CallArg {
+                        label: None,
+                        value: target,
+                    }
+                    .into_node()];
+                    lowered_args.extend(call_args(context, args).kind);
+
+                    fe::Expr::Call {
+                        func: fe::Expr::Name(fn_name).into_boxed_node(),
+                        generic_args: None,
+                        args: lowered_args.into_node(),
+                    }
+                }
+            }
+        }
+        _ => {
+            let (func, generic_args) = call_func(context, func, generic_args);
+            fe::Expr::Call {
+                func,
+                generic_args,
+                args: call_args(context, args),
+            }
+        }
+    }
+}
+
+/// Lowers the function of a call. Instances of generic functions and structs
+/// are called by the names they are lowered to, and so are the types that type
+/// parameters stand for. Their type arguments are dropped.
+fn call_func(
+    context: &mut FnContext,
+    func: Box<Node<fe::Expr>>,
+    generic_args: Option<Node<Vec<fe::GenericArg>>>,
+) -> (Box<Node<fe::Expr>>, Option<Node<Vec<fe::GenericArg>>>) {
+    let db = context.db();
+    let is_type_param = |exp: &fe::Expr| matches!(exp, fe::Expr::Name(name) if context.module.type_params.contains_key(name));
+
+    let lowered = match context.call_type(&func).cloned() {
+        Some(CallType::Pure(function)) if !function.type_args(db).is_empty() => Some(item_expr(
+            context,
+            function.module(db),
+            names::generic_instance_name(&function.display_name(db)),
+        )),
+        Some(CallType::TypeConstructor(typ))
+            if is_type_param(&func.kind) || is_struct_instance(db, &typ) =>
+        {
+            Some(concrete_type_expr(context, &typ))
+        }
+        Some(CallType::AssociatedFunction {
+            class: Class::Struct(struct_),
+            ..
+        }) => match &func.kind {
+            fe::Expr::Attribute { value, attr }
+                if is_type_param(&value.kind) || !struct_.type_args(db).is_empty() =>
+            {
+                let strukt =
+                    concrete_type_expr(context, &Type::Struct(Struct::from_id(struct_, db)));
+                Some(fe::Expr::Attribute {
+                    value: Box::new(Node::with_original_id(
+                        strukt,
+                        value.span,
+                        value.original_id,
+                    )),
+                    attr: attr.clone(),
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match lowered {
+        Some(kind) => (
+            Box::new(Node::with_original_id(kind, func.span, func.original_id)),
+            None,
+        ),
+        None => (boxed_expr(context, func), generic_args),
+    }
+}
+
+fn is_struct_instance(db: &dyn AnalyzerDb, typ: &Type) -> bool {
+    matches!(typ, Type::Struct(struct_) if !struct_.id.type_args(db).is_empty())
+}
+
+/// The expression that refers to a type that a type parameter can stand for.
+fn concrete_type_expr(context: &FnContext, typ: &Type) -> fe::Expr {
+    let db = context.db();
+    match typ {
+        Type::Base(base) => fe::Expr::Name(base.name()),
+        Type::Struct(struct_) => item_expr(
+            context,
+            struct_.id.module(db),
+            names::generic_instance_name(&struct_.name),
+        ),
+        Type::Enum(enum_) => item_expr(context, enum_.id.module(db), enum_.name.clone()),
+        Type::Contract(contract) => {
+            item_expr(context, contract.id.module(db), contract.name.clone())
+        }
+        _ => unreachable!("only named types can be type arguments"),
+    }
+}
+
+/// The expression that refers to an item of the given module, which is its
+/// path if it's defined in another module.
+fn item_expr(context: &FnContext, module: ModuleId, name: SmolStr) -> fe::Expr {
+    if module == context.module.module {
+        fe::Expr::Name(name)
+    } else {
+        fe::Expr::Path(names::ingot_item_path(context.db(), module, name))
+    }
+}
+
+/// Evaluates a call to a `const` function at compile time, if all of its
+/// arguments are constant. Calls that fail to evaluate are left for runtime, so
+/// that they revert the same way they would if the arguments weren't constant.
+fn const_call_value(
+    context: &FnContext,
+    function: FunctionId,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    span: Span,
+) -> Option<const_eval::ConstValue> {
+    let db = context.db();
+    let args = args
+        .kind
+        .iter()
+        .map(|arg| {
+            const_eval::eval_const_expr(
+                db,
+                context.id.module(db),
+                context.id.class(db),
+                &arg.kind.value,
+            )
+            .ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    const_eval::eval_call(db, function, args, span).ok()
+}
+
+/// Registers an `Option` or `Result` type and returns the tuple it is lowered to.
+fn flagged_type_tuple(context: &mut FnContext, typ: &Type) -> Tuple {
+    match typ {
+        Type::Option(option) => {
+            context.module.add_option(*option);
+            option_tuple(option)
+        }
+        Type::Result(result) => {
+            context.module.add_result(*result);
+            result_tuple(result)
+        }
+        _ => panic!("expected `Option` or `Result` type"),
+    }
+}
+
+/// Builds the constructor call of a lowered tuple struct.
+/// Builds a call to the constructor of a lowered tuple struct.
+pub fn tuple_constructor(tuple: &Tuple, items: Vec<Node<fe::Expr>>) -> fe::Expr {
+    let args = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            fe::CallArg {
+                label: Some(SmolStr::new(format!("item{}", index)).into_node()),
+                value,
+            }
+            .into_node()
+        })
+        .collect::<Vec<_>>();
+
+    fe::Expr::Call {
+        func: fe::Expr::Name(tuple_struct_name(tuple)).into_boxed_node(),
+        generic_args: None,
+        args: args.into_node(),
+    }
+}
+
+/// The flag of a lowered `Option` or `Result`, which is set for `some` and `ok` values.
+fn flag(value: Node<fe::Expr>) -> fe::Expr {
+    fe::Expr::Attribute {
+        value: Box::new(value),
+        attr: SmolStr::new("item0").into_node(),
+    }
+}
@@ -1,32 +1,49 @@
 use crate::ast_utils::{
     boolean_expr_to_if, get_first_boolean_expressions, get_first_ternary_expressions,
+    get_first_try_expressions,
 };
 use crate::ast_utils::{
-    inject_before_expression, replace_node_with_name_expression, ternary_to_if,
+    inject_before_expression, map_ast_node, replace_node_with_name_expression, ternary_to_if,
+    try_to_if, StmtOrExpr,
 };
 use crate::context::{FnContext, ModuleContext};
 use crate::mappers::expressions;
 use crate::mappers::types;
+use crate::names;
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::namespace::items::FunctionId;
+use fe_analyzer::namespace::items::{Class, FunctionId};
 use fe_analyzer::namespace::types::{Base, Type};
 use fe_analyzer::namespace::types::{FixedSize, TypeDowncast};
-use fe_parser::ast::{self as fe, Expr, FuncStmt, RegularFunctionArg, SmolStr};
+use fe_analyzer::AnalyzerDb;
+use fe_common::utils::keccak;
+use fe_parser::ast::{self as fe, Expr, FuncStmt, RegularFunctionArg, SmolStr, VarDeclTarget};
 use fe_parser::node::Node;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+
+/// The keccak hash of this key is the storage slot of the flag that is set
+/// once an upgradeable contract has been initialized.
+const INITIALIZED_SLOT_KEY: &[u8] = b"fe.initializable.initialized";
 
 /// Lowers a function definition.
 pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::Function> {
     let node = &function.data(context.db).ast;
     let fe::Function {
         pub_,
+        const_,
         unsafe_,
+        test,
+        only_role,
         name,
+        generic_params: _,
         args,
         return_type: return_type_node,
+        return_names,
         body,
     } = &node.kind;
 
     let signature = function.signature(context.db);
+    let type_params = std::mem::replace(&mut context.type_params, function.type_params(context.db));
 
     let return_type = signature
         .return_type
@@ -37,6 +54,40 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
 
     let lowered_body = {
         let mut lowered_body = multiple_stmts(&mut fn_ctx, body.clone());
+        if !return_names.is_empty() {
+            // declare the named return values, and return them at the end
+            let declarations = function
+                .named_output_types(fn_ctx.db())
+                .into_iter()
+                .zip(return_names)
+                .map(|(typ, name)| {
+                    fe::FuncStmt::VarDecl {
+                        target: VarDeclTarget::Name(name.kind.clone()).into_node(),
+                        typ: names::fixed_size_type_desc(&typ).into_node(),
+                        value: None,
+                    }
+                    .into_node()
+                })
+                .collect::<Vec<_>>();
+            lowered_body = [declarations, lowered_body].concat();
+            if !is_last_statement_return(&lowered_body) {
+                lowered_body.push(
+                    fe::FuncStmt::Return {
+                        value: Some(named_outputs_expr(&mut fn_ctx).into_node()),
+                    }
+                    .into_node(),
+                );
+            }
+        }
+        if sets_owner(fn_ctx.db(), function) {
+            lowered_body.splice(0..0, owner_init());
+        }
+        if is_initialize_fn(fn_ctx.db(), function) {
+            lowered_body.insert(0, initializer_guard());
+        }
+        if let Some(role) = only_role {
+            lowered_body.insert(0, role_guard(&role.kind));
+        }
         // append `return ()` to the body if there is no return
         if return_type.is_unit() && !is_last_statement_return(&lowered_body) {
             lowered_body.push(
@@ -63,6 +114,20 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         &get_first_boolean_expressions,
         &boolean_expr_to_if,
     );
+    // `?` expressions are lowered last, so that they are only evaluated in the
+    // branches that ternary and boolean expressions were lowered into.
+    let lowered_body = if let FixedSize::Result(fn_result) = return_type {
+        lower_iteratively(
+            &mut fn_ctx,
+            lowered_body,
+            "try_result",
+            &get_first_try_expressions,
+            &|typ, expr, name| try_to_if(*fn_result, typ, expr, name),
+        )
+    } else {
+        lowered_body
+    };
+    let lowered_body = hoist_env_reads(&mut fn_ctx, lowered_body);
 
     let param_types = {
         let params = &signature.params;
@@ -84,7 +149,7 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         types
     };
 
-    let args = args
+    let mut args = args
         .iter()
         .zip(param_types)
         .map(|(pnode, ptype)| {
@@ -99,7 +164,10 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
                 pnode.to_owned()
             }
         })
-        .collect();
+        .collect::<Vec<_>>();
+    if sets_owner(fn_ctx.db(), function) && !function.takes_self(fn_ctx.db()) {
+        args.insert(0, fe::FunctionArg::Zelf.into_node());
+    }
 
     // The return type is lowered if it exists. If there is no return type, we set it to the unit type.
     let lowered_return_type = return_type_node
@@ -107,18 +175,93 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         .map(|type_desc| types::type_desc(fn_ctx.module, type_desc, &return_type.clone().into()))
         .unwrap_or_else(|| fe::TypeDesc::Unit.into_node());
 
+    fn_ctx.module.type_params = type_params;
+
     let lowered_function = fe::Function {
         pub_: *pub_,
+        const_: *const_,
         unsafe_: *unsafe_,
-        name: name.clone(),
+        test: *test,
+        // the guard is the first statement of the lowered body
+        only_role: None,
+        name: Node::new(
+            names::generic_instance_name(&function.display_name(fn_ctx.db())),
+            name.span,
+        ),
+        generic_params: vec![],
         args,
         return_type: Some(lowered_return_type),
+        return_names: vec![],
         body: lowered_body,
     };
 
     Node::new(lowered_function, node.span)
 }
 
+/// Reads each environment value that the function uses more than once, e.g.
+/// `msg.sender`, into a variable at the start of the body, and replaces the
+/// reads with that variable.
+///
+/// The analyzer only records reads of values that are constant during a call,
+/// so the value can be read before its first use.
+fn hoist_env_reads(
+    context: &mut FnContext,
+    statements: Vec<Node<FuncStmt>>,
+) -> Vec<Node<FuncStmt>> {
+    let mut reads: IndexMap<SmolStr, Vec<Node<Expr>>> = IndexMap::new();
+    for stmt in &statements {
+        map_ast_node(stmt.clone().into(), &mut |node| {
+            if let StmtOrExpr::Expr(expr) = &node {
+                if let Some(path) = context.body.env_reads.get(&expr.original_id) {
+                    reads.entry(path.clone()).or_default().push(expr.clone());
+                }
+            }
+            node
+        });
+    }
+
+    let mut declarations = vec![];
+    let mut hoisted_names = HashMap::new();
+    for (path, exprs) in reads.into_iter().filter(|(_, exprs)| exprs.len() > 1) {
+        let typ = context
+            .expression_attributes(exprs[0].original_id)
+            .expect("missing attributes")
+            .typ
+            .clone();
+        let typ = FixedSize::try_from(typ).expect("Not a fixed size");
+
+        let name: SmolStr = context.make_unique_name(&path.replace('.', "_")).into();
+        declarations.push(
+            FuncStmt::VarDecl {
+                target: VarDeclTarget::Name(name.clone()).into_node(),
+                typ: names::fixed_size_type_desc(&typ).into_node(),
+                value: Some(exprs[0].kind.clone().into_node()),
+            }
+            .into_node(),
+        );
+        for expr in exprs {
+            hoisted_names.insert(expr.original_id, name.clone());
+        }
+    }
+
+    if declarations.is_empty() {
+        return statements;
+    }
+
+    let statements = statements.into_iter().map(|stmt| {
+        map_ast_node(stmt.into(), &mut |node| match node {
+            StmtOrExpr::Expr(expr) if hoisted_names.contains_key(&expr.original_id) => {
+                Expr::Name(hoisted_names[&expr.original_id].clone())
+                    .into_node()
+                    .into()
+            }
+            _ => node,
+        })
+        .as_stmt()
+    });
+    declarations.into_iter().chain(statements).collect()
+}
+
 fn lower_iteratively(
     context: &mut FnContext,
     statements: Vec<Node<FuncStmt>>,
@@ -211,6 +354,7 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
             or_else: multiple_stmts(context, or_else),
         }],
         fe::FuncStmt::Unsafe(body) => vec![fe::FuncStmt::Unsafe(multiple_stmts(context, body))],
+        fe::FuncStmt::Match { value, arms } => lower_match(context, value, arms, stmt.span),
         fe::FuncStmt::Assert { test, msg } => vec![fe::FuncStmt::Assert {
             test: expressions::expr(context, test),
             msg: expressions::optional_expr(context, msg),
@@ -221,6 +365,7 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
         fe::FuncStmt::Pass => vec![stmt.kind],
         fe::FuncStmt::Break => vec![stmt.kind],
         fe::FuncStmt::Continue => vec![stmt.kind],
+        fe::FuncStmt::Yul(_) => vec![stmt.kind],
         fe::FuncStmt::Revert { error } => vec![fe::FuncStmt::Revert {
             error: error.map(|expr| expressions::expr(context, expr)),
         }],
@@ -276,6 +421,11 @@ fn stmt_return(context: &mut FnContext, value: Option<Node<fe::Expr>>) -> Vec<fe
         vec![fe::FuncStmt::Return {
             value: Some(expressions::expr(context, value)),
         }]
+    } else if context.id.has_named_outputs(context.db()) {
+        // lower a return statement with no value to a return of the named return values
+        vec![fe::FuncStmt::Return {
+            value: Some(named_outputs_expr(context).into_node()),
+        }]
     } else {
         // lower a return statement with no value to `return empty_tuple()`
         vec![fe::FuncStmt::Return {
@@ -284,6 +434,28 @@ fn stmt_return(context: &mut FnContext, value: Option<Node<fe::Expr>>) -> Vec<fe
     }
 }
 
+/// The value returned by a function with named return values: the single
+/// named value, or a tuple of all of them.
+fn named_outputs_expr(context: &mut FnContext) -> fe::Expr {
+    let db = context.db();
+    let names = context.id.return_names(db);
+    let return_type = context.id.signature(db).expect_return_type();
+
+    match return_type {
+        FixedSize::Tuple(tuple) if names.len() > 1 => {
+            context.module.tuples.insert(tuple.clone());
+            expressions::tuple_constructor(
+                &tuple,
+                names
+                    .into_iter()
+                    .map(|name| fe::Expr::Name(name).into_node())
+                    .collect(),
+            )
+        }
+        _ => fe::Expr::Name(names[0].clone()),
+    }
+}
+
 fn is_last_statement_return(stmts: &[Node<fe::FuncStmt>]) -> bool {
     if let Some(stmt) = stmts.last() {
         matches!(stmt.kind, fe::FuncStmt::Return { .. })
@@ -389,3 +561,486 @@ fn declare_tuple_items(
         }
     }
 }
+
+/// Lowers a `match` statement into a chain of `if` statements, or leaves it
+/// for yulgen to compile to a `switch` if it compares an enum or an integer
+/// against variants and literals (see `is_switch`).
+///
+/// e.g.
+/// ```fe
+/// match pair:
+///     (0, y) if y > 1:
+///         return y
+///     _:
+///         return 0
+/// ```
+/// will be lowered to
+/// ```fe
+/// let $match_value_0: $tuple_u256_u256_ = pair
+/// let $match_arm_1: bool = false
+/// if $match_value_0.item0 == 0:
+///     $match_arm_1 = $match_value_0.item1 > 1
+/// if $match_arm_1:
+///     let y: u256 = $match_value_0.item1
+///     return y
+/// else:
+///     return 0
+/// ```
+fn lower_match(
+    context: &mut FnContext,
+    value: Node<fe::Expr>,
+    arms: Vec<Node<fe::MatchArm>>,
+    span: fe_common::Span,
+) -> Vec<fe::FuncStmt> {
+    let value_type = context
+        .expression_attributes(&value)
+        .expect("missing attributes")
+        .typ
+        .clone();
+    let value_type = FixedSize::try_from(value_type).expect("Not a fixed size");
+
+    let match_value: SmolStr = context.make_unique_name("match_value").into();
+    let mut stmts = vec![fe::FuncStmt::VarDecl {
+        target: Node::new(fe::VarDeclTarget::Name(match_value.clone()), span),
+        typ: types::fixed_size_type_desc(context.module, &value_type),
+        value: Some(expressions::expr(context, value)),
+    }];
+    if is_switch(&value_type, &arms) {
+        let arms = arms
+            .into_iter()
+            .map(|arm| lower_switch_arm(context, &match_value, &value_type, arm))
+            .collect();
+        stmts.push(fe::FuncStmt::Match {
+            value: Expr::Name(match_value).into_node(),
+            arms,
+        });
+    } else {
+        stmts.extend(
+            lower_match_arms(context, &match_value, arms.into_iter())
+                .into_iter()
+                .map(|stmt| stmt.kind),
+        );
+    }
+    stmts
+}
+
+/// Returns true if the `match` compares an enum or integer value against enum
+/// variants and number literals, with an optional arm that matches any other
+/// value, and none of the arms have a guard.
+fn is_switch(value_type: &FixedSize, arms: &[Node<fe::MatchArm>]) -> bool {
+    let is_case = |pat: &fe::Pattern| {
+        matches!(
+            pat,
+            fe::Pattern::Path(_)
+                | fe::Pattern::Literal(Node {
+                    kind: Expr::Num(_),
+                    ..
+                })
+        )
+    };
+    matches!(
+        value_type,
+        FixedSize::Enum(_) | FixedSize::Base(Base::Numeric(_))
+    ) && arms.iter().any(|arm| is_case(&arm.kind.pat.kind))
+        && arms.iter().all(|arm| {
+            arm.kind.guard.is_none()
+                && (is_case(&arm.kind.pat.kind) || arm.kind.pat.kind.is_irrefutable())
+        })
+}
+
+/// Lowers the body of a `match` arm that becomes a case of a `switch`. An arm
+/// that binds the value to a name becomes the default case, which declares
+/// the name.
+fn lower_switch_arm(
+    context: &mut FnContext,
+    match_value: &str,
+    value_type: &FixedSize,
+    arm: Node<fe::MatchArm>,
+) -> Node<fe::MatchArm> {
+    let fe::MatchArm { pat, body, .. } = arm.kind;
+    let mut lowered_body = vec![];
+    let pat = match pat.kind {
+        fe::Pattern::Binding(name) => {
+            lowered_body.push(
+                FuncStmt::VarDecl {
+                    target: VarDeclTarget::Name(name).into_node(),
+                    typ: types::fixed_size_type_desc(context.module, value_type),
+                    value: Some(Expr::Name(match_value.into()).into_node()),
+                }
+                .into_node(),
+            );
+            Node::new(fe::Pattern::Wildcard, pat.span)
+        }
+        _ => pat,
+    };
+    lowered_body.extend(multiple_stmts(context, body));
+    fe::MatchArm {
+        pat,
+        guard: None,
+        body: lowered_body,
+    }
+    .into_traceable_node(arm.original_id)
+}
+
+fn lower_match_arms(
+    context: &mut FnContext,
+    match_value: &str,
+    mut arms: impl Iterator<Item = Node<fe::MatchArm>>,
+) -> Vec<Node<fe::FuncStmt>> {
+    let arm = match arms.next() {
+        Some(arm) => arm.kind,
+        None => return vec![],
+    };
+
+    let mut tests = vec![];
+    let mut bindings = vec![];
+    destructure_pattern(
+        context,
+        arm.pat,
+        Expr::Name(match_value.into()).into_node(),
+        &mut tests,
+        &mut bindings,
+    );
+
+    // The guard can't refer to the bindings, because they're only declared
+    // inside of the arm body, so we refer to the matched values directly.
+    if let Some(guard) = arm.guard {
+        let guard = expressions::expr(context, guard);
+        tests.push(replace_names(guard, &bindings));
+    }
+
+    let mut body = vec![];
+    for (name, value, typ) in bindings {
+        body.push(
+            FuncStmt::VarDecl {
+                target: fe::VarDeclTarget::Name(name).into_node(),
+                typ: types::fixed_size_type_desc(context.module, &typ),
+                value: Some(value),
+            }
+            .into_node(),
+        );
+    }
+    body.extend(multiple_stmts(context, arm.body));
+
+    if tests.is_empty() {
+        // This arm matches every value, so the remaining arms are unreachable.
+        return body;
+    }
+
+    let or_else = lower_match_arms(context, match_value, arms);
+    if tests.len() == 1 {
+        return vec![FuncStmt::If {
+            test: tests.remove(0),
+            body,
+            or_else,
+        }
+        .into_node()];
+    }
+
+    // Nested `if` statements evaluate the tests lazily, in order.
+    let arm_matched: SmolStr = context.make_unique_name("match_arm").into();
+    let mut check = vec![FuncStmt::Assign {
+        target: Expr::Name(arm_matched.clone()).into_node(),
+        value: tests.pop().expect("no tests"),
+    }
+    .into_node()];
+    for test in tests.into_iter().rev() {
+        check = vec![FuncStmt::If {
+            test,
+            body: check,
+            or_else: vec![],
+        }
+        .into_node()];
+    }
+
+    let mut stmts = vec![FuncStmt::VarDecl {
+        target: fe::VarDeclTarget::Name(arm_matched.clone()).into_node(),
+        typ: fe::TypeDesc::Base {
+            base: "bool".into(),
+        }
+        .into_node(),
+        value: Some(Expr::Bool(false).into_node()),
+    }
+    .into_node()];
+    stmts.extend(check);
+    stmts.push(
+        FuncStmt::If {
+            test: Expr::Name(arm_matched).into_node(),
+            body,
+            or_else,
+        }
+        .into_node(),
+    );
+    stmts
+}
+
+/// Collects the tests that decide whether `value` matches the pattern, and the
+/// variables that the pattern binds.
+fn destructure_pattern(
+    context: &mut FnContext,
+    pat: Node<fe::Pattern>,
+    value: Node<Expr>,
+    tests: &mut Vec<Node<Expr>>,
+    bindings: &mut Vec<(SmolStr, Node<Expr>, FixedSize)>,
+) {
+    match pat.kind {
+        fe::Pattern::Wildcard => {}
+        fe::Pattern::Binding(name) => {
+            let typ = context
+                .pattern_type(pat.id)
+                .expect("missing pattern type")
+                .clone();
+            bindings.push((name, value, typ))
+        }
+        fe::Pattern::Literal(literal) => {
+            let test = match expressions::expr(context, literal) {
+                Node {
+                    kind: Expr::Bool(true),
+                    ..
+                } => value,
+                Node {
+                    kind: Expr::Bool(false),
+                    ..
+                } => Expr::UnaryOperation {
+                    op: fe::UnaryOperator::Not.into_node(),
+                    operand: Box::new(value),
+                }
+                .into_node(),
+                literal => Expr::CompOperation {
+                    left: Box::new(value),
+                    op: fe::CompOperator::Eq.into_node(),
+                    right: Box::new(literal),
+                }
+                .into_node(),
+            };
+            tests.push(test)
+        }
+        fe::Pattern::Path(path) => tests.push(
+            Expr::CompOperation {
+                left: Box::new(value),
+                op: fe::CompOperator::Eq.into_node(),
+                right: Box::new(Expr::Path(path).into_node()),
+            }
+            .into_node(),
+        ),
+        fe::Pattern::Tuple(items) => {
+            for (index, item) in items.into_iter().enumerate() {
+                let item_value = Expr::Attribute {
+                    value: Box::new(value.clone()),
+                    attr: SmolStr::new(format!("item{}", index)).into_node(),
+                }
+                .into_node();
+                destructure_pattern(context, item, item_value, tests, bindings);
+            }
+        }
+        fe::Pattern::Struct { fields, .. } => {
+            for field in fields {
+                let fe::FieldPattern { label, pat } = field.kind;
+                let field_value = Expr::Attribute {
+                    value: Box::new(value.clone()),
+                    attr: label.kind.into_node(),
+                }
+                .into_node();
+                destructure_pattern(context, pat, field_value, tests, bindings);
+            }
+        }
+    }
+}
+
+/// Replaces the names of pattern bindings in `expr` with the values they're bound to.
+fn replace_names(expr: Node<Expr>, bindings: &[(SmolStr, Node<Expr>, FixedSize)]) -> Node<Expr> {
+    map_ast_node(expr.into(), &mut |val| {
+        if let StmtOrExpr::Expr(Node {
+            kind: Expr::Name(name),
+            ..
+        }) = &val
+        {
+            if let Some((_, value, _)) = bindings.iter().find(|(binding, ..)| binding == name) {
+                return value.clone().into();
+            }
+        }
+        val
+    })
+    .as_expr()
+}
+
+/// Whether the function is the `initialize` function of an upgradeable
+/// contract.
+fn is_initialize_fn(db: &dyn AnalyzerDb, function: FunctionId) -> bool {
+    match function.class(db) {
+        Some(Class::Contract(contract)) => contract.initialize_function(db) == Some(function),
+        _ => false,
+    }
+}
+
+/// Builds the guard that makes `initialize` callable only once:
+///
+/// ```fe
+/// unsafe:
+///   assert raw_sload(SLOT) == 0, "Initializable: contract is already initialized"
+///   raw_sstore(SLOT, 1)
+/// ```
+fn initializer_guard() -> Node<FuncStmt> {
+    let slot = || fe::Expr::Num(keccak::full(INITIALIZED_SLOT_KEY).into()).into_node();
+    let call = |name: &str, args: Vec<Node<Expr>>| {
+        fe::Expr::Call {
+            func: fe::Expr::Name(name.into()).into_boxed_node(),
+            generic_args: None,
+            args: args
+                .into_iter()
+                .map(|value| fe::CallArg { label: None, value }.into_node())
+                .collect::<Vec<_>>()
+                .into_node(),
+        }
+        .into_node()
+    };
+
+    FuncStmt::Unsafe(vec![
+        FuncStmt::Assert {
+            test: fe::Expr::CompOperation {
+                left: Box::new(call("raw_sload", vec![slot()])),
+                op: fe::CompOperator::Eq.into_node(),
+                right: fe::Expr::Num("0".into()).into_boxed_node(),
+            }
+            .into_node(),
+            msg: Some(
+                fe::Expr::Str("Initializable: contract is already initialized".into()).into_node(),
+            ),
+        }
+        .into_node(),
+        FuncStmt::Expr {
+            value: call(
+                "raw_sstore",
+                vec![slot(), fe::Expr::Num("1".into()).into_node()],
+            ),
+        }
+        .into_node(),
+    ])
+    .into_node()
+}
+
+/// Whether the function sets the owner of an `ownable` contract, which is
+/// `initialize` if the contract is `upgradeable`, and `__init__` otherwise.
+fn sets_owner(db: &dyn AnalyzerDb, function: FunctionId) -> bool {
+    match function.class(db) {
+        Some(Class::Contract(contract)) if contract.is_ownable(db) => {
+            if contract.is_upgradeable(db) {
+                contract.initialize_function(db) == Some(function)
+            } else {
+                contract.init_function(db) == Some(function)
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Builds the `__init__` function of an `ownable` contract that doesn't
+/// define one, which only sets the owner. `__init__` must be `pub`, so it's
+/// given the span of the `ownable` qualifier as its `pub` qualifier.
+pub fn owner_init_fn(ownable_span: fe_common::Span) -> Node<fe::Function> {
+    let mut body = owner_init();
+    body.push(
+        FuncStmt::Return {
+            value: Some(Expr::Unit.into_node()),
+        }
+        .into_node(),
+    );
+    fe::Function {
+        pub_: Some(ownable_span),
+        const_: None,
+        unsafe_: None,
+        test: None,
+        only_role: None,
+        name: SmolStr::new("__init__").into_node(),
+        generic_params: vec![],
+        args: vec![fe::FunctionArg::Zelf.into_node()],
+        return_type: Some(fe::TypeDesc::Unit.into_node()),
+        return_names: vec![],
+        body,
+    }
+    .into_node()
+}
+
+/// Builds the statements that make the deployer the owner of an `ownable`
+/// contract:
+///
+/// ```fe
+/// self._owner = msg.sender
+/// emit OwnershipTransferred(previous_owner=address(0), new_owner=msg.sender)
+/// ```
+fn owner_init() -> Vec<Node<FuncStmt>> {
+    let msg_sender = || {
+        Expr::Attribute {
+            value: Expr::Name("msg".into()).into_boxed_node(),
+            attr: SmolStr::new("sender").into_node(),
+        }
+        .into_node()
+    };
+    let arg = |label: &str, value: Node<Expr>| {
+        fe::CallArg {
+            label: Some(SmolStr::new(label).into_node()),
+            value,
+        }
+        .into_node()
+    };
+    let zero_address = Expr::Call {
+        func: Expr::Name("address".into()).into_boxed_node(),
+        generic_args: None,
+        args: vec![fe::CallArg {
+            label: None,
+            value: Expr::Num("0".into()).into_node(),
+        }
+        .into_node()]
+        .into_node(),
+    }
+    .into_node();
+
+    vec![
+        FuncStmt::Assign {
+            target: Expr::Attribute {
+                value: Expr::Name("self".into()).into_boxed_node(),
+                attr: SmolStr::new("_owner").into_node(),
+            }
+            .into_node(),
+            value: msg_sender(),
+        }
+        .into_node(),
+        FuncStmt::Emit {
+            name: SmolStr::new("OwnershipTransferred").into_node(),
+            args: vec![
+                arg("previous_owner", zero_address),
+                arg("new_owner", msg_sender()),
+            ]
+            .into_node(),
+        }
+        .into_node(),
+    ]
+}
+
+/// Builds the guard of an `only_role` function, which reverts unless the
+/// caller has been granted the role. The role is identified by the keccak256
+/// hash of its name:
+///
+/// ```fe
+/// self.check_role(<keccak256(role)>)
+/// ```
+fn role_guard(role: &str) -> Node<FuncStmt> {
+    FuncStmt::Expr {
+        value: Expr::Call {
+            func: Expr::Attribute {
+                value: Expr::Name("self".into()).into_boxed_node(),
+                attr: SmolStr::new("check_role").into_node(),
+            }
+            .into_boxed_node(),
+            generic_args: None,
+            args: vec![fe::CallArg {
+                label: None,
+                value: Expr::Num(keccak::full(role.as_bytes()).into()).into_node(),
+            }
+            .into_node()]
+            .into_node(),
+        }
+        .into_node(),
+    }
+    .into_node()
+}
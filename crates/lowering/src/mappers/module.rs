@@ -1,5 +1,5 @@
 use crate::context::ModuleContext;
-use crate::mappers::{contracts, functions, structs, types};
+use crate::mappers::{contracts, expressions, functions, structs, types};
 use crate::names;
 use crate::utils::ZeroSpanNode;
 use fe_analyzer::namespace::items::{Item, ModuleId, TypeDef};
@@ -42,34 +42,68 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
                     id.span(db),
                 )))
             }
+            // Generic structs and functions are kept as they are, so that `use`
+            // statements in other modules of the ingot still resolve. Only their
+            // instances are compiled.
+            TypeDef::Struct(id) if id.is_generic(db) => {
+                Some(ast::ModuleStmt::Struct(id.data(db).ast.clone()))
+            }
             TypeDef::Struct(id) => Some(ast::ModuleStmt::Struct(structs::struct_def(
                 &mut context,
                 *id,
             ))),
+            TypeDef::Enum(id) => Some(ast::ModuleStmt::Enum(id.data(db).ast.clone())),
             TypeDef::Contract(id) => Some(ast::ModuleStmt::Contract(contracts::contract_def(
                 &mut context,
                 *id,
             ))),
             TypeDef::Primitive(_) => unreachable!(),
         },
+        Item::Function(id) if id.is_generic(db) => {
+            Some(ast::ModuleStmt::Function(id.data(db).ast.clone()))
+        }
         Item::Function(id) => Some(ast::ModuleStmt::Function(functions::func_def(
             &mut context,
             *id,
         ))),
 
+        // The functions of trait implementations are lowered as part of their
+        // structs and contracts, so only the trait itself is kept, for `use`
+        // statements in other modules of the ingot.
+        Item::Trait(id) => Some(ast::ModuleStmt::Trait(id.data(db).ast.clone())),
+
         Item::GenericType(_) => todo!("generic types can't be defined in fe yet"),
         Item::Event(_) => todo!("events can't be defined at the module level yet"),
         Item::BuiltinFunction(_) | Item::Intrinsic(_) | Item::Object(_) => {
             unreachable!("special built-in stuff")
         }
 
-        // All name expressions referring to constants are handled at the time of lowering,
-        // which causes the constants to no longer serve a purpose.
-        Item::Constant(_) => None,
+        // Expressions referring to constants are replaced by their values, but
+        // the constants are kept so that `use` statements in other modules of the
+        // ingot still resolve.
+        Item::Constant(id) => {
+            let node = &id.data(db).ast;
+            Some(ast::ModuleStmt::Constant(Box::new(Node::new(
+                ast::ConstantDecl {
+                    name: node.kind.name.clone(),
+                    typ: node.kind.typ.clone(),
+                    value: Node::new(expressions::constant_value(db, *id), node.kind.value.span),
+                },
+                node.span,
+            ))))
+        }
         Item::Ingot(_) => unreachable!("ingots cannot be defined in a module"),
         Item::Module(_) => unreachable!("modules cannot be defined in modules (at least not yet)"),
     }));
 
+    lowered_body.extend(module.generic_instances(db).iter().map(|item| match item {
+        Item::Type(TypeDef::Struct(id)) => {
+            ast::ModuleStmt::Struct(structs::struct_def(&mut context, *id))
+        }
+        Item::Function(id) => ast::ModuleStmt::Function(functions::func_def(&mut context, *id)),
+        _ => unreachable!("only functions and structs can be generic"),
+    }));
+
     let struct_defs_from_tuples = context
         .tuples
         .iter()
@@ -82,10 +116,30 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
         .map(|expr| ast::ModuleStmt::Function(list_expr_to_fn_def(expr).into_node()))
         .collect::<Vec<_>>();
 
+    let func_defs_from_options = context.options.iter().flat_map(|option| {
+        let typ = FixedSize::Option(*option);
+        [
+            unwrap_fn_def(names::option_unwrap_fn_name(option), &typ, option.inner),
+            unwrap_or_fn_def(names::option_unwrap_or_fn_name(option), &typ, option.inner),
+        ]
+    });
+    let func_defs_from_results = context.results.iter().flat_map(|result| {
+        let typ = FixedSize::Result(*result);
+        [
+            unwrap_fn_def(names::result_unwrap_fn_name(result), &typ, result.ok),
+            unwrap_or_fn_def(names::result_unwrap_or_fn_name(result), &typ, result.ok),
+        ]
+    });
+    let func_defs_from_flagged_types = func_defs_from_options
+        .chain(func_defs_from_results)
+        .map(|func| ast::ModuleStmt::Function(func.into_node()))
+        .collect::<Vec<_>>();
+
     ast::Module {
         body: [
             struct_defs_from_tuples,
             func_defs_from_list_expr,
+            func_defs_from_flagged_types,
             lowered_body,
         ]
         .concat(),
@@ -104,9 +158,11 @@ fn build_tuple_struct(tuple: &Tuple) -> ast::Struct {
 
     ast::Struct {
         name: names::tuple_struct_name(tuple).into_node(),
+        generic_params: vec![],
         fields,
         functions: vec![],
         pub_qual: None,
+        is_error: false,
     }
 }
 
@@ -148,7 +204,16 @@ fn build_type_desc(typ: &FixedSize) -> ast::TypeDesc {
             base: contract.name.clone(),
         },
         FixedSize::Struct(strukt) => ast::TypeDesc::Base {
-            base: strukt.name.clone(),
+            base: names::generic_instance_name(&strukt.name),
+        },
+        FixedSize::Enum(enum_) => ast::TypeDesc::Base {
+            base: enum_.name.clone(),
+        },
+        FixedSize::Option(option) => ast::TypeDesc::Base {
+            base: names::tuple_struct_name(&names::option_tuple(option)),
+        },
+        FixedSize::Result(result) => ast::TypeDesc::Base {
+            base: names::tuple_struct_name(&names::result_tuple(result)),
         },
     }
 }
@@ -201,10 +266,100 @@ fn list_expr_to_fn_def(array: &Array) -> ast::Function {
     // Put it all together in one AST node that holds the entire function definition
     ast::Function {
         pub_: None,
+        const_: None,
         unsafe_: None,
+        test: None,
+        only_role: None,
         name: names::list_expr_generator_fn_name(array).into_node(),
+        generic_params: vec![],
         args,
         return_type,
+        return_names: vec![],
         body: [vec![var_decl], assignments, vec![return_stmt]].concat(),
     }
 }
+
+/// Builds the argument holding the `Option` or `Result` that is unwrapped.
+fn unwrap_target_arg(typ: &FixedSize) -> Node<ast::FunctionArg> {
+    ast::FunctionArg::Regular(ast::RegularFunctionArg {
+        name: SmolStr::new("value").into_node(),
+        typ: names::fixed_size_type_desc(typ).into_node(),
+    })
+    .into_node()
+}
+
+fn unwrap_target_field(name: &str) -> Node<ast::Expr> {
+    ast::Expr::Attribute {
+        value: ast::Expr::Name("value".into()).into_boxed_node(),
+        attr: SmolStr::new(name).into_node(),
+    }
+    .into_node()
+}
+
+/// Builds a function that returns the value of an `Option` or the `ok` value of
+/// a `Result`, and reverts if there is none.
+fn unwrap_fn_def(name: SmolStr, typ: &FixedSize, inner: Base) -> ast::Function {
+    let assert_stmt = ast::FuncStmt::Assert {
+        test: unwrap_target_field("item0"),
+        msg: None,
+    }
+    .into_node();
+
+    let return_stmt = ast::FuncStmt::Return {
+        value: Some(unwrap_target_field("item1")),
+    }
+    .into_node();
+
+    ast::Function {
+        pub_: None,
+        const_: None,
+        unsafe_: None,
+        test: None,
+        only_role: None,
+        name: name.into_node(),
+        generic_params: vec![],
+        args: vec![unwrap_target_arg(typ)],
+        return_type: Some(names::fixed_size_type_desc(&FixedSize::Base(inner)).into_node()),
+        return_names: vec![],
+        body: vec![assert_stmt, return_stmt],
+    }
+}
+
+/// Builds a function that returns the value of an `Option` or the `ok` value of
+/// a `Result`, and the given default if there is none.
+fn unwrap_or_fn_def(name: SmolStr, typ: &FixedSize, inner: Base) -> ast::Function {
+    let default_arg = ast::FunctionArg::Regular(ast::RegularFunctionArg {
+        name: SmolStr::new("default").into_node(),
+        typ: names::fixed_size_type_desc(&FixedSize::Base(inner)).into_node(),
+    })
+    .into_node();
+
+    let if_stmt = ast::FuncStmt::If {
+        test: unwrap_target_field("item0"),
+        body: vec![ast::FuncStmt::Return {
+            value: Some(unwrap_target_field("item1")),
+        }
+        .into_node()],
+        or_else: vec![],
+    }
+    .into_node();
+
+    let return_stmt = ast::FuncStmt::Return {
+        value: Some(ast::Expr::Name("default".into()).into_node()),
+    }
+    .into_node();
+
+    ast::Function {
+        pub_: None,
+        const_: None,
+        unsafe_: None,
+        test: None,
+        only_role: None,
+        name: name.into_node(),
+        generic_params: vec![],
+        args: vec![unwrap_target_arg(typ), default_arg],
+        return_type: Some(names::fixed_size_type_desc(&FixedSize::Base(inner)).into_node()),
+        return_names: vec![],
+        body: vec![if_stmt, return_stmt],
+    }
+}
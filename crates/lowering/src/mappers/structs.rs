@@ -1,11 +1,13 @@
 use crate::context::ModuleContext;
 use crate::mappers::{functions, types};
+use crate::names;
 use fe_analyzer::namespace::items::{StructFieldId, StructId};
 use fe_parser::ast;
 use fe_parser::node::Node;
 
 pub fn struct_def(context: &mut ModuleContext, struct_: StructId) -> Node<ast::Struct> {
     let db = context.db;
+    let type_params = std::mem::replace(&mut context.type_params, struct_.type_params(db));
 
     let fields = struct_
         .fields(db)
@@ -19,13 +21,20 @@ pub fn struct_def(context: &mut ModuleContext, struct_: StructId) -> Node<ast::S
         .map(|function| functions::func_def(context, *function))
         .collect();
 
+    context.type_params = type_params;
+
     let node = &struct_.data(context.db).ast;
     Node::new(
         ast::Struct {
-            name: node.kind.name.clone(),
+            name: Node::new(
+                names::generic_instance_name(&struct_.name(db)),
+                node.kind.name.span,
+            ),
+            generic_params: vec![],
             fields,
             functions,
             pub_qual: None,
+            is_error: node.kind.is_error,
         },
         node.span,
     )
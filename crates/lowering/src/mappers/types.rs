@@ -1,10 +1,40 @@
 use crate::context::ModuleContext;
 use crate::names;
-use fe_analyzer::namespace::types::{Type, TypeDowncast};
-use fe_parser::ast::{GenericArg, TypeDesc};
+use crate::utils::ZeroSpanNode;
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::namespace::types::{FixedSize, Type, TypeDowncast};
+use fe_parser::ast::{GenericArg, SmolStr, TypeDesc};
 use fe_parser::node::Node;
 
 pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type) -> Node<TypeDesc> {
+    if let Type::Option(option) = typ {
+        context.add_option(*option);
+        return Node::new(
+            names::fixed_size_type_desc(&FixedSize::Option(*option)),
+            desc.span,
+        );
+    }
+    if let Type::Result(result) = typ {
+        context.add_result(*result);
+        return Node::new(
+            names::fixed_size_type_desc(&FixedSize::Result(*result)),
+            desc.span,
+        );
+    }
+
+    // Type parameters and instances of generic structs are replaced by the
+    // types they stand for.
+    let is_type_param =
+        matches!(&desc.kind, TypeDesc::Base { base } if context.type_params.contains_key(base));
+    let is_struct_instance = matches!(
+        (&desc.kind, typ),
+        (TypeDesc::Generic { .. }, Type::Struct(struct_))
+            if !struct_.id.type_args(context.db).is_empty()
+    );
+    if is_type_param || is_struct_instance {
+        return Node::new(concrete_type_desc(context, typ), desc.span);
+    }
+
     match desc.kind {
         TypeDesc::Unit | TypeDesc::Base { .. } | TypeDesc::Path(_) => desc,
 
@@ -47,3 +77,50 @@ pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type)
         ),
     }
 }
+
+/// Creates the type description of a type that isn't written in the source,
+/// e.g. the type of a variable introduced during lowering.
+pub fn fixed_size_type_desc(context: &mut ModuleContext, typ: &FixedSize) -> Node<TypeDesc> {
+    if let FixedSize::Tuple(tuple) = typ {
+        for item in tuple.items.iter() {
+            fixed_size_type_desc(context, item);
+        }
+        context.tuples.insert(tuple.clone());
+    }
+    if let FixedSize::Option(option) = typ {
+        context.add_option(*option);
+    }
+    if let FixedSize::Result(result) = typ {
+        context.add_result(*result);
+    }
+    names::fixed_size_type_desc(typ).into_node()
+}
+
+/// The type description of a type that a type parameter stands for. Structs,
+/// enums and contracts of other modules are referred to by their paths.
+fn concrete_type_desc(context: &mut ModuleContext, typ: &Type) -> TypeDesc {
+    let db = context.db;
+    match typ {
+        Type::Struct(struct_) => item_type_desc(
+            context,
+            struct_.id.module(db),
+            names::generic_instance_name(&struct_.name),
+        ),
+        Type::Enum(enum_) => item_type_desc(context, enum_.id.module(db), enum_.name.clone()),
+        Type::Contract(contract) => {
+            item_type_desc(context, contract.id.module(db), contract.name.clone())
+        }
+        _ => {
+            let typ = FixedSize::try_from(typ.clone()).expect("expected fixed size type");
+            fixed_size_type_desc(context, &typ).kind
+        }
+    }
+}
+
+fn item_type_desc(context: &ModuleContext, module: ModuleId, name: SmolStr) -> TypeDesc {
+    if module == context.module {
+        TypeDesc::Base { base: name }
+    } else {
+        TypeDesc::Path(names::ingot_item_path(context.db, module, name))
+    }
+}
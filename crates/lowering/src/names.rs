@@ -1,7 +1,10 @@
 use crate::names;
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::namespace::types::{Array, Base, FixedSize, SafeNames, Tuple};
+use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::namespace::types::{Array, Base, FeOption, FeResult, FixedSize, SafeNames, Tuple};
+use fe_analyzer::AnalyzerDb;
 use fe_parser::ast::{self, SmolStr};
+use vec1::vec1;
 
 /// The name of a lowered list expression generator function.
 pub fn list_expr_generator_fn_name(list_expr_type: &Array) -> SmolStr {
@@ -13,6 +16,71 @@ pub fn tuple_struct_name(tuple: &Tuple) -> SmolStr {
     format!("${}", tuple.lower_snake()).into()
 }
 
+/// The name of a lowered instance of a generic function or struct, e.g.
+/// `max$u8$` for `max<u8>`. Other names are returned as they are.
+pub fn generic_instance_name(name: &str) -> SmolStr {
+    name.replace(", ", "$").replace(['<', '>'], "$").into()
+}
+
+/// The path of an item that is defined in the given module, starting at the
+/// ingot, e.g. `ingot::utils::max$u8$`.
+pub fn ingot_item_path(db: &dyn AnalyzerDb, module: ModuleId, name: SmolStr) -> ast::Path {
+    let mut segments = vec![name];
+    let mut current = module;
+    // The items of the root module are items of the ingot itself.
+    while let Some(parent) = current.parent_module(db) {
+        segments.push(current.name(db));
+        current = parent;
+    }
+    segments.push("ingot".into());
+    ast::Path {
+        segments: segments
+            .into_iter()
+            .rev()
+            .map(|segment| segment.into_node())
+            .collect(),
+    }
+}
+
+/// The tuple an `Option` is lowered to: a presence flag followed by the value.
+pub fn option_tuple(option: &FeOption) -> Tuple {
+    Tuple {
+        items: vec1![FixedSize::Base(Base::Bool), FixedSize::Base(option.inner)],
+    }
+}
+
+/// The name of the lowered `unwrap` function for an `Option` type.
+pub fn option_unwrap_fn_name(option: &FeOption) -> SmolStr {
+    format!("option_unwrap_{}", option.inner.lower_snake()).into()
+}
+
+/// The name of the lowered `unwrap_or` function for an `Option` type.
+pub fn option_unwrap_or_fn_name(option: &FeOption) -> SmolStr {
+    format!("option_unwrap_or_{}", option.inner.lower_snake()).into()
+}
+
+/// The tuple a `Result` is lowered to: a flag that is set for `ok` results,
+/// followed by the `ok` value and the `err` value.
+pub fn result_tuple(result: &FeResult) -> Tuple {
+    Tuple {
+        items: vec1![
+            FixedSize::Base(Base::Bool),
+            FixedSize::Base(result.ok),
+            FixedSize::Base(result.err)
+        ],
+    }
+}
+
+/// The name of the lowered `unwrap` function for a `Result` type.
+pub fn result_unwrap_fn_name(result: &FeResult) -> SmolStr {
+    format!("result_unwrap_{}", result.lower_snake()).into()
+}
+
+/// The name of the lowered `unwrap_or` function for a `Result` type.
+pub fn result_unwrap_or_fn_name(result: &FeResult) -> SmolStr {
+    format!("result_unwrap_or_{}", result.lower_snake()).into()
+}
+
 /// Maps a FixedSize type to its type description.
 pub fn fixed_size_type_desc(typ: &FixedSize) -> ast::TypeDesc {
     match typ {
@@ -39,7 +107,16 @@ pub fn fixed_size_type_desc(typ: &FixedSize) -> ast::TypeDesc {
             base: contract.name.clone(),
         },
         FixedSize::Struct(strukt) => ast::TypeDesc::Base {
-            base: strukt.name.clone(),
+            base: generic_instance_name(&strukt.name),
+        },
+        FixedSize::Enum(enum_) => ast::TypeDesc::Base {
+            base: enum_.name.clone(),
+        },
+        FixedSize::Option(option) => ast::TypeDesc::Base {
+            base: names::tuple_struct_name(&option_tuple(option)),
+        },
+        FixedSize::Result(result) => ast::TypeDesc::Base {
+            base: names::tuple_struct_name(&result_tuple(result)),
         },
     }
 }
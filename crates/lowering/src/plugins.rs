@@ -0,0 +1,148 @@
+//! Compile-time plugins, which transform the lowered AST of a module before
+//! Yul is generated from it.
+//!
+//! A plugin is a Rust type that implements [`LoweringPlugin`], and is passed
+//! to the compiler driver along with the input files. The lowered AST only
+//! uses the subset of Fe that yulgen supports (eg. no tuples, ternaries,
+//! augmented assignments or named return values), and the transformed module
+//! is analyzed again, so the code that a plugin adds must be valid in that
+//! subset.
+
+use crate::db::LoweringDb;
+use fe_analyzer::namespace::items::{IngotId, ModuleId};
+use fe_common::diagnostics::Diagnostic;
+use fe_common::files::SourceFileId;
+use fe_common::Span;
+use fe_parser::ast::{self, ContractStmt, FuncStmt, ModuleStmt};
+use fe_parser::node::Node;
+use std::rc::Rc;
+
+mod debug_events;
+mod record_dbg;
+
+pub use debug_events::DebugEvents;
+pub use record_dbg::RecordDbg;
+
+/// A compile-time plugin that transforms lowered modules.
+///
+/// Every hook has a default implementation, so a plugin only implements the
+/// ones it needs. By default, [`transform_module`](Self::transform_module)
+/// calls [`transform_contract`](Self::transform_contract) for each contract,
+/// which calls [`transform_function`](Self::transform_function) for each of
+/// the contract's functions.
+///
+/// The ABI of a contract is built from the source, so a plugin can't add,
+/// remove or rename public functions.
+pub trait LoweringPlugin {
+    /// The name of the plugin, which is shown in diagnostics about the code
+    /// that it changed.
+    fn name(&self) -> &str;
+
+    fn transform_module(&self, module: &mut ast::Module) {
+        for stmt in module.body.iter_mut() {
+            if let ModuleStmt::Contract(contract) = stmt {
+                self.transform_contract(&mut contract.kind);
+            }
+        }
+    }
+
+    fn transform_contract(&self, contract: &mut ast::Contract) {
+        let name = contract.name.kind.clone();
+        for stmt in contract.body.iter_mut() {
+            if let ContractStmt::Function(function) = stmt {
+                self.transform_function(&name, &mut function.kind);
+            }
+        }
+    }
+
+    /// Transforms a function of the contract named `contract`. The body of a
+    /// lowered function always ends with a `return` statement.
+    fn transform_function(&self, _contract: &str, _function: &mut ast::Function) {}
+}
+
+/// Applies the plugins to the lowered module, in order, and interns a module
+/// with the transformed AST. Returns the module as is if there are no plugins.
+pub fn apply(
+    db: &dyn LoweringDb,
+    module_id: ModuleId,
+    plugins: &[&dyn LoweringPlugin],
+) -> ModuleId {
+    if plugins.is_empty() {
+        return module_id;
+    }
+    let db = db.upcast();
+    let mut module = (*module_id.data(db)).clone();
+    transform(&mut module.ast, plugins);
+    db.intern_module(Rc::new(module))
+}
+
+/// Applies the plugins to each module of the lowered ingot, in order, and
+/// interns an ingot with the transformed ASTs. Returns the ingot as is if
+/// there are no plugins.
+pub fn apply_to_ingot(
+    db: &dyn LoweringDb,
+    ingot_id: IngotId,
+    plugins: &[&dyn LoweringPlugin],
+) -> IngotId {
+    if plugins.is_empty() {
+        return ingot_id;
+    }
+    let db = db.upcast();
+    let mut ingot = (*ingot_id.data(db)).clone();
+    for (_, module) in ingot.fe_files.values_mut() {
+        transform(module, plugins);
+    }
+    db.intern_ingot(Rc::new(ingot))
+}
+
+fn transform(module: &mut ast::Module, plugins: &[&dyn LoweringPlugin]) {
+    for plugin in plugins {
+        plugin.transform_module(module);
+    }
+}
+
+/// Parses the fields, events and functions of a contract definition, which a
+/// plugin can add to a contract, eg.
+///
+/// ```fe
+/// contract Members:
+///   calls: u256
+///
+///   fn count_call(self):
+///     self.calls = self.calls + 1
+///     return ()
+/// ```
+///
+/// The name of the contract is ignored. Every node has a span of zero.
+///
+/// # Panics
+/// Panics if `src` doesn't define a contract.
+pub fn parse_members(src: &str) -> Result<ast::Contract, Vec<Diagnostic>> {
+    let module = fe_parser::parse_generated(Span::zero(SourceFileId::default()), src)?;
+    let contract = module
+        .body
+        .into_iter()
+        .find_map(|stmt| match stmt {
+            ModuleStmt::Contract(contract) => Some(contract.kind),
+            _ => None,
+        })
+        .expect("plugin code doesn't define a contract");
+    Ok(contract)
+}
+
+/// Parses a sequence of statements, eg. to insert them at the start of a
+/// function body. Every node has a span of zero.
+pub fn parse_stmts(src: &str) -> Result<Vec<Node<FuncStmt>>, Vec<Diagnostic>> {
+    let body = src
+        .lines()
+        .map(|line| format!("  {}\n", line))
+        .collect::<String>();
+    let module = fe_parser::parse_generated(
+        Span::zero(SourceFileId::default()),
+        &format!("fn stmts():\n{}", body),
+    )?;
+    match module.body.into_iter().next() {
+        Some(ModuleStmt::Function(function)) => Ok(function.kind.body),
+        _ => unreachable!(),
+    }
+}
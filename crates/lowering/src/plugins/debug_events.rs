@@ -0,0 +1,164 @@
+use super::{parse_members, parse_stmts, LoweringPlugin};
+use crate::utils::ZeroSpanNode;
+use fe_parser::ast::{self, ContractStmt, Expr, FuncStmt, FunctionArg, TypeDesc, UnaryOperator};
+use fe_parser::node::Node;
+
+/// Instruments the functions of each contract with debug events, which is
+/// enabled by the `--debug-events` flag of the CLI.
+///
+/// A function emits `DebugEnter(function)` when it's called, followed by a
+/// `DebugValue(function, name, value)` for each of its `u256` parameters, and
+/// emits `DebugExit(function)` before each `return`. A failing `assert` or a
+/// `revert` emits `DebugRevert(function)` first. `function` is the name of the
+/// function, eg. `Foo.bar`, and the string fields are sized to fit the longest
+/// name in the contract.
+///
+/// The events are added to the lowered contract only, so they aren't part of
+/// its ABI.
+pub struct DebugEvents;
+
+impl LoweringPlugin for DebugEvents {
+    fn name(&self) -> &str {
+        "debug-events"
+    }
+
+    fn transform_contract(&self, contract: &mut ast::Contract) {
+        let contract_name = contract.name.kind.clone();
+        let functions = contract
+            .body
+            .iter_mut()
+            .filter_map(|stmt| match stmt {
+                ContractStmt::Function(function) => Some(&mut function.kind),
+                ContractStmt::Event(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let function_capacity = functions
+            .iter()
+            .map(|function| contract_name.len() + 1 + function.name.kind.len())
+            .max()
+            .unwrap_or(1);
+        let name_capacity = functions
+            .iter()
+            .flat_map(|function| u256_params(function))
+            .map(str::len)
+            .max()
+            .unwrap_or(1);
+
+        for function in functions {
+            let id = format!(
+                "String<{}>(\"{}.{}\")",
+                function_capacity, contract_name, function.name.kind
+            );
+
+            let mut enter = format!("emit DebugEnter(function={})\n", id);
+            for param in u256_params(function) {
+                enter += &format!(
+                    "emit DebugValue(function={}, name=String<{}>(\"{}\"), value={})\n",
+                    id, name_capacity, param, param
+                );
+            }
+            let enter = parse_stmts(&enter).expect("invalid debug events");
+            let exit = parse_stmts(&format!("emit DebugExit(function={})", id))
+                .expect("invalid debug events");
+            let revert = parse_stmts(&format!("emit DebugRevert(function={})", id))
+                .expect("invalid debug events");
+
+            instrument(&mut function.body, &exit, &revert);
+            function.body.splice(0..0, enter);
+        }
+
+        let events = parse_members(&format!(
+            "contract DebugEvents:
+  event DebugEnter:
+    function: String<{function}>
+
+  event DebugValue:
+    function: String<{function}>
+    name: String<{name}>
+    value: u256
+
+  event DebugExit:
+    function: String<{function}>
+
+  event DebugRevert:
+    function: String<{function}>
+",
+            function = function_capacity,
+            name = name_capacity,
+        ))
+        .expect("invalid debug events");
+        contract.body.extend(events.body);
+    }
+}
+
+/// The names of the `u256` parameters of `function`.
+fn u256_params(function: &ast::Function) -> impl Iterator<Item = &str> {
+    function.args.iter().filter_map(|arg| match &arg.kind {
+        FunctionArg::Regular(arg) => match &arg.typ.kind {
+            TypeDesc::Base { base } if base == "u256" => Some(arg.name.kind.as_str()),
+            _ => None,
+        },
+        FunctionArg::Zelf => None,
+    })
+}
+
+/// Inserts `exit` before each `return` in `body`, and `revert` before each
+/// revert site.
+fn instrument(body: &mut Vec<Node<FuncStmt>>, exit: &[Node<FuncStmt>], revert: &[Node<FuncStmt>]) {
+    let mut index = 0;
+    while index < body.len() {
+        let inserted = match &mut body[index].kind {
+            FuncStmt::Return { .. } => exit.to_vec(),
+            FuncStmt::Revert { .. } => revert.to_vec(),
+            FuncStmt::Assert { test, msg } => {
+                // `assert test, msg` becomes
+                //
+                // if not test:
+                //   emit DebugRevert(..)
+                //   assert false, msg
+                //
+                // so that `test` is still evaluated once.
+                let test = Expr::UnaryOperation {
+                    op: UnaryOperator::Not.into_node(),
+                    operand: Box::new(test.clone()),
+                }
+                .into_node();
+                let mut fail = revert.to_vec();
+                fail.push(
+                    FuncStmt::Assert {
+                        test: Expr::Bool(false).into_node(),
+                        msg: msg.take(),
+                    }
+                    .into_node(),
+                );
+                body[index] = FuncStmt::If {
+                    test,
+                    body: fail,
+                    or_else: vec![],
+                }
+                .into_node();
+                vec![]
+            }
+            FuncStmt::For { body, .. } | FuncStmt::While { body, .. } | FuncStmt::Unsafe(body) => {
+                instrument(body, exit, revert);
+                vec![]
+            }
+            FuncStmt::If { body, or_else, .. } => {
+                instrument(body, exit, revert);
+                instrument(or_else, exit, revert);
+                vec![]
+            }
+            FuncStmt::Match { arms, .. } => {
+                for arm in arms {
+                    instrument(&mut arm.kind.body, exit, revert);
+                }
+                vec![]
+            }
+            _ => vec![],
+        };
+        let count = inserted.len();
+        body.splice(index..index, inserted);
+        index += count + 1;
+    }
+}
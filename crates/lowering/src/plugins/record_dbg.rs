@@ -0,0 +1,98 @@
+use super::LoweringPlugin;
+use crate::ast_utils::{map_ast_node, StmtOrExpr};
+use crate::utils::ZeroSpanNode;
+use fe_parser::ast::{self, CallArg, ContractStmt, Expr, ModuleStmt};
+use fe_parser::node::Node;
+
+/// Records the values passed to `dbg` for the test runner, which `fe test`
+/// enables.
+///
+/// Each `dbg(value)` call becomes `__dbg_record(value, file, start, end)`,
+/// where `file` is the id of the source file of `value`, and `start` and `end`
+/// are its byte offsets in the file. Without this plugin, `dbg(value)`
+/// compiles to `value`.
+pub struct RecordDbg;
+
+impl LoweringPlugin for RecordDbg {
+    fn name(&self) -> &str {
+        "record-dbg"
+    }
+
+    fn transform_module(&self, module: &mut ast::Module) {
+        for stmt in module.body.iter_mut() {
+            match stmt {
+                ModuleStmt::Contract(contract) => self.transform_contract(&mut contract.kind),
+                ModuleStmt::Function(function) => record(&mut function.kind),
+                ModuleStmt::Struct(struct_) => {
+                    for function in struct_.kind.functions.iter_mut() {
+                        record(&mut function.kind);
+                    }
+                }
+                ModuleStmt::Impl(impl_) => {
+                    for function in impl_.kind.functions.iter_mut() {
+                        record(&mut function.kind);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn transform_contract(&self, contract: &mut ast::Contract) {
+        for stmt in contract.body.iter_mut() {
+            if let ContractStmt::Function(function) = stmt {
+                record(&mut function.kind);
+            }
+        }
+    }
+}
+
+/// Replaces the `dbg` calls in the body of `function`.
+fn record(function: &mut ast::Function) {
+    let body = std::mem::take(&mut function.body);
+    function.body = body
+        .into_iter()
+        .map(|stmt| map_ast_node(stmt.into(), &mut record_call).as_stmt())
+        .collect();
+}
+
+fn record_call(node: StmtOrExpr) -> StmtOrExpr {
+    let expr = match node {
+        StmtOrExpr::Expr(expr) => expr,
+        stmt => return stmt,
+    };
+    match expr.kind {
+        Expr::Call {
+            func,
+            generic_args,
+            mut args,
+        } if matches!(&func.kind, Expr::Name(name) if name == "dbg") && args.kind.len() == 1 => {
+            let span = args.kind[0].kind.value.span;
+            for location in [span.file_id.0, span.start as u128, span.end as u128] {
+                args.kind.push(
+                    CallArg {
+                        label: None,
+                        value: Expr::Num(location.to_string().into()).into_node(),
+                    }
+                    .into_node(),
+                );
+            }
+            let func = Node::with_original_id(
+                Expr::Name("__dbg_record".into()),
+                func.span,
+                func.original_id,
+            );
+            Node::with_original_id(
+                Expr::Call {
+                    func: Box::new(func),
+                    generic_args,
+                    args,
+                },
+                expr.span,
+                expr.original_id,
+            )
+            .into()
+        }
+        kind => Node::with_original_id(kind, expr.span, expr.original_id).into(),
+    }
+}
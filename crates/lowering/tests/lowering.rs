@@ -1,7 +1,8 @@
-use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent};
+use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent, ModuleId};
 use fe_analyzer::AnalyzerDb;
 use fe_common::diagnostics::print_diagnostics;
 use fe_common::files::{FileStore, SourceFileId};
+use fe_lowering::plugins::{self, LoweringPlugin};
 use fe_lowering::TestDb;
 use fe_parser::ast as fe;
 use insta::assert_snapshot;
@@ -9,9 +10,13 @@ use std::rc::Rc;
 use wasm_bindgen_test::wasm_bindgen_test;
 
 fn lower(src: &str, id: SourceFileId, files: &FileStore) -> fe::Module {
-    let ast = parse_file(src, id, files);
-
     let db = TestDb::default();
+    let module_id = intern_module(&db, src, id, files);
+    fe_lowering::lower_module(&db, module_id).ast(&db)
+}
+
+fn intern_module(db: &TestDb, src: &str, id: SourceFileId, files: &FileStore) -> ModuleId {
+    let ast = parse_file(src, id, files);
 
     let global = Global::default();
     let global_id = db.intern_global(Rc::new(global));
@@ -22,9 +27,7 @@ fn lower(src: &str, id: SourceFileId, files: &FileStore) -> fe::Module {
         file_content: ModuleFileContent::File { file: id },
         ast,
     };
-    let module_id = db.intern_module(Rc::new(module));
-
-    fe_lowering::lower_module(&db, module_id).ast(&db)
+    db.intern_module(Rc::new(module))
 }
 
 fn parse_file(src: &str, id: SourceFileId, files: &FileStore) -> fe::Module {
@@ -76,5 +79,83 @@ test_file! { module_fn, "lowering/module_fn.fe" }
 test_file! { struct_fn, "lowering/struct_fn.fe" }
 test_file! { ternary, "lowering/ternary.fe" }
 test_file! { and_or, "lowering/and_or.fe" }
+test_file! { env_reads, "lowering/env_reads.fe" }
 // TODO: the analyzer rejects lowered nested tuples.
 // test_file!(array_tuple, "lowering/array_tuple.fe");
+
+/// Counts the calls to the public functions of each contract.
+struct CallCounter;
+
+impl LoweringPlugin for CallCounter {
+    fn name(&self) -> &str {
+        "call-counter"
+    }
+
+    fn transform_contract(&self, contract: &mut fe::Contract) {
+        let members = plugins::parse_members("contract Members:\n  call_count: u256\n").unwrap();
+        contract.fields.extend(members.fields);
+        for stmt in contract.body.iter_mut() {
+            if let fe::ContractStmt::Function(function) = stmt {
+                if function.kind.pub_.is_some() && function.kind.name.kind != "__init__" {
+                    let count =
+                        plugins::parse_stmts("self.call_count = self.call_count + 1").unwrap();
+                    function.kind.body.splice(0..0, count);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lowering_plugin() {
+    let mut files = FileStore::new();
+    let src = test_files::fixture("lowering/plugin.fe");
+    let src_id = files.add_file("lowering/plugin.fe", src);
+
+    let db = TestDb::default();
+    let module_id = intern_module(&db, src, src_id, &files);
+    let lowered_id = fe_lowering::lower_module(&db, module_id);
+    let transformed_id = plugins::apply(&db, lowered_id, &[&CallCounter]);
+    fe_analyzer::analyze_module(&db, transformed_id).expect("plugin code has errors");
+
+    let transformed_code = format!("{}", transformed_id.ast(&db));
+    assert!(transformed_code.contains("call_count: u256"));
+    assert_eq!(
+        transformed_code
+            .matches("self.call_count = self.call_count + 1")
+            .count(),
+        2
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn debug_events() {
+    let mut files = FileStore::new();
+    let src = test_files::fixture("lowering/plugin.fe");
+    let src_id = files.add_file("lowering/plugin.fe", src);
+
+    let db = TestDb::default();
+    let module_id = intern_module(&db, src, src_id, &files);
+    let lowered_id = fe_lowering::lower_module(&db, module_id);
+    let transformed_id = plugins::apply(&db, lowered_id, &[&plugins::DebugEvents]);
+    fe_analyzer::analyze_module(&db, transformed_id).expect("debug events have errors");
+
+    let transformed_code = format!("{}", transformed_id.ast(&db));
+    for (event, count) in [
+        ("DebugEnter", 4),
+        ("DebugValue", 1),
+        ("DebugExit", 4),
+        ("DebugRevert", 1),
+    ] {
+        assert_eq!(
+            transformed_code
+                .matches(&format!("emit {}(", event))
+                .count(),
+            count,
+            "{}",
+            event
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::ir::FunctionBody;
+use fe_analyzer::namespace::items::{FunctionId, ModuleId};
+use fe_analyzer::AnalyzerDb;
+use fe_common::Upcast;
+use std::rc::Rc;
+
+mod queries;
+
+#[salsa::query_group(MirDbStorage)]
+pub trait MirDb: AnalyzerDb + Upcast<dyn AnalyzerDb> {
+    #[salsa::invoke(queries::function_body)]
+    fn mir_function_body(&self, function: FunctionId) -> Rc<FunctionBody>;
+
+    #[salsa::invoke(queries::module_bodies)]
+    fn mir_module_bodies(&self, module: ModuleId) -> Rc<Vec<Rc<FunctionBody>>>;
+}
+
+#[salsa::database(fe_analyzer::db::AnalyzerDbStorage, MirDbStorage)]
+#[derive(Default)]
+pub struct TestDb {
+    storage: salsa::Storage<TestDb>,
+}
+impl salsa::Database for TestDb {}
+
+impl Upcast<dyn MirDb> for TestDb {
+    fn upcast(&self) -> &(dyn MirDb + 'static) {
+        &*self
+    }
+}
+
+impl Upcast<dyn AnalyzerDb> for TestDb {
+    fn upcast(&self) -> &(dyn AnalyzerDb + 'static) {
+        &*self
+    }
+}
@@ -0,0 +1,30 @@
+use crate::db::MirDb;
+use crate::ir::FunctionBody;
+use crate::lower;
+use fe_analyzer::namespace::items::{FunctionId, Item, ModuleId, TypeDef};
+use std::rc::Rc;
+
+pub fn function_body(db: &dyn MirDb, function: FunctionId) -> Rc<FunctionBody> {
+    Rc::new(lower::lower_function(db.upcast(), function))
+}
+
+pub fn module_bodies(db: &dyn MirDb, module: ModuleId) -> Rc<Vec<Rc<FunctionBody>>> {
+    let adb = db.upcast();
+
+    let functions = module
+        .all_items(adb)
+        .iter()
+        .flat_map(|item| match item {
+            Item::Function(id) => vec![*id],
+            // interfaces aren't compiled
+            Item::Type(TypeDef::Contract(id)) if id.is_interface(adb) => vec![],
+            Item::Type(TypeDef::Contract(id)) => adb.contract_all_functions(*id).to_vec(),
+            Item::Type(TypeDef::Struct(id)) => id.functions(adb).values().copied().collect(),
+            _ => vec![],
+        })
+        // generic functions are only compiled through their instances
+        .filter(|function| !function.is_generic(adb))
+        .map(|function| db.mir_function_body(function))
+        .collect();
+    Rc::new(functions)
+}
@@ -0,0 +1,389 @@
+//! The Fe mid-level IR.
+//!
+//! A function body is a control flow graph of basic blocks. Every block holds a
+//! list of statements, which only ever operate on typed locals and places, and
+//! ends in a single terminator that transfers control to another block or
+//! leaves the function.
+
+use fe_analyzer::namespace::items::{EventId, FunctionId};
+use fe_analyzer::namespace::types::Type;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use num_bigint::BigInt;
+use smol_str::SmolStr;
+use std::fmt;
+
+/// Index of a local in [`FunctionBody::locals`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocalId(pub u32);
+
+/// Index of a basic block in [`FunctionBody::blocks`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockId(pub u32);
+
+/// A typed local variable.
+///
+/// Locals are either function parameters, user declared variables, or
+/// temporaries introduced while flattening nested expressions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Local {
+    pub name: Option<SmolStr>,
+    pub typ: Type,
+    pub kind: LocalKind,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LocalKind {
+    Param,
+    Var,
+    Temp,
+}
+
+/// A memory or storage location that can be read from or written to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Place {
+    pub base: PlaceBase,
+    pub projections: Vec<Projection>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PlaceBase {
+    Local(LocalId),
+    /// A field of the contract that is currently being executed.
+    ContractField {
+        name: SmolStr,
+        nonce: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Projection {
+    /// Struct field access.
+    Field(SmolStr),
+    /// Array element or map value access.
+    Index(Operand),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Place(Place),
+    Const(Constant),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Constant {
+    Int(BigInt),
+    Bool(bool),
+    Str(SmolStr),
+    Unit,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rvalue {
+    Use(Operand),
+    BinOp {
+        op: ast::BinOperator,
+        lhs: Operand,
+        rhs: Operand,
+    },
+    CompOp {
+        op: ast::CompOperator,
+        lhs: Operand,
+        rhs: Operand,
+    },
+    UnOp {
+        op: ast::UnaryOperator,
+        operand: Operand,
+    },
+    /// A copy of the elements of an array, or the bytes of a fixed-size bytes
+    /// value, from `start` up to `end`.
+    Slice {
+        value: Operand,
+        start: Operand,
+        end: Operand,
+    },
+    /// A read of a field of one of the global `block`, `chain`, `msg` or `tx`
+    /// objects, or of `self.address`.
+    Env {
+        object: SmolStr,
+        field: SmolStr,
+    },
+    Call {
+        callee: Callee,
+        args: Vec<Operand>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Callee {
+    /// A user defined function. Receivers of struct methods are passed as the
+    /// first argument.
+    Function {
+        id: FunctionId,
+        name: SmolStr,
+    },
+    /// A call to a function of another contract. The first argument is the
+    /// address of the contract.
+    External {
+        id: FunctionId,
+        name: SmolStr,
+    },
+    /// A builtin function, method or intrinsic.
+    Builtin(SmolStr),
+    TypeConstructor(Type),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Statement {
+    Assign {
+        place: Place,
+        value: Rvalue,
+    },
+    Eval(Rvalue),
+    Emit {
+        event: EventId,
+        name: SmolStr,
+        args: Vec<Operand>,
+    },
+    /// An inline Yul block, which is opaque to the MIR. It reads and writes
+    /// the function's variables by their Fe names.
+    InlineYul(Vec<Node<ast::YulStmt>>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Terminator {
+    Goto(BlockId),
+    Branch {
+        cond: Operand,
+        then_block: BlockId,
+        else_block: BlockId,
+    },
+    Return(Operand),
+    Revert(Option<Operand>),
+    /// A failed `assert`, optionally carrying the error message.
+    AssertFailure(Option<Operand>),
+}
+
+impl Terminator {
+    /// The blocks control may be transferred to.
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Goto(block) => vec![*block],
+            Terminator::Branch {
+                then_block,
+                else_block,
+                ..
+            } => vec![*then_block, *else_block],
+            Terminator::Return(_) | Terminator::Revert(_) | Terminator::AssertFailure(_) => {
+                vec![]
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BasicBlock {
+    pub statements: Vec<Statement>,
+    /// Only `None` while the body is being built.
+    pub terminator: Option<Terminator>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionBody {
+    pub name: SmolStr,
+    pub params: Vec<LocalId>,
+    pub return_type: Type,
+    pub locals: Vec<Local>,
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl FunctionBody {
+    pub const ENTRY: BlockId = BlockId(0);
+
+    pub fn local(&self, id: LocalId) -> &Local {
+        &self.locals[id.0 as usize]
+    }
+
+    pub fn block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id.0 as usize]
+    }
+}
+
+impl Place {
+    pub fn local(local: LocalId) -> Self {
+        Place {
+            base: PlaceBase::Local(local),
+            projections: vec![],
+        }
+    }
+
+    pub fn project(mut self, projection: Projection) -> Self {
+        self.projections.push(projection);
+        self
+    }
+}
+
+impl fmt::Display for LocalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "_{}", self.0)
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bb{}", self.0)
+    }
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.base {
+            PlaceBase::Local(local) => write!(f, "{}", local)?,
+            PlaceBase::ContractField { name, nonce } => write!(f, "self.{}#{}", name, nonce)?,
+        }
+        for projection in &self.projections {
+            match projection {
+                Projection::Field(name) => write!(f, ".{}", name)?,
+                Projection::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Place(place) => place.fmt(f),
+            Operand::Const(constant) => constant.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Int(num) => write!(f, "{}", num),
+            Constant::Bool(val) => write!(f, "{}", val),
+            Constant::Str(val) => write!(f, "{:?}", val),
+            Constant::Unit => write!(f, "()"),
+        }
+    }
+}
+
+impl fmt::Display for Callee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Callee::Function { name, .. } => write!(f, "{}", name),
+            Callee::External { name, .. } => write!(f, "external {}", name),
+            Callee::Builtin(name) => write!(f, "builtin {}", name),
+            Callee::TypeConstructor(typ) => write!(f, "{}", typ),
+        }
+    }
+}
+
+fn write_operands(f: &mut fmt::Formatter<'_>, operands: &[Operand]) -> fmt::Result {
+    let operands = operands
+        .iter()
+        .map(|operand| operand.to_string())
+        .collect::<Vec<_>>();
+    write!(f, "{}", operands.join(", "))
+}
+
+impl fmt::Display for Rvalue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rvalue::Use(operand) => operand.fmt(f),
+            Rvalue::BinOp { op, lhs, rhs } => write!(f, "{} {} {}", lhs, op, rhs),
+            Rvalue::CompOp { op, lhs, rhs } => write!(f, "{} {} {}", lhs, op, rhs),
+            Rvalue::UnOp { op, operand } => write!(f, "{}{}", op, operand),
+            Rvalue::Slice { value, start, end } => write!(f, "{}[{}:{}]", value, start, end),
+            Rvalue::Env { object, field } => write!(f, "{}.{}", object, field),
+            Rvalue::Call { callee, args } => {
+                write!(f, "{}(", callee)?;
+                write_operands(f, args)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Assign { place, value } => write!(f, "{} = {}", place, value),
+            Statement::Eval(value) => value.fmt(f),
+            Statement::Emit { name, args, .. } => {
+                write!(f, "emit {}(", name)?;
+                write_operands(f, args)?;
+                write!(f, ")")
+            }
+            Statement::InlineYul(body) => {
+                let stmts = body
+                    .iter()
+                    .map(|stmt| stmt.kind.to_string().replace('\n', " "))
+                    .collect::<Vec<_>>();
+                write!(f, "yul {{ {} }}", stmts.join(" "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Terminator::Goto(block) => write!(f, "goto {}", block),
+            Terminator::Branch {
+                cond,
+                then_block,
+                else_block,
+            } => write!(f, "branch {} ? {} : {}", cond, then_block, else_block),
+            Terminator::Return(value) => write!(f, "return {}", value),
+            Terminator::Revert(Some(value)) => write!(f, "revert {}", value),
+            Terminator::Revert(None) => write!(f, "revert"),
+            Terminator::AssertFailure(Some(msg)) => write!(f, "assert_failure {}", msg),
+            Terminator::AssertFailure(None) => write!(f, "assert_failure"),
+        }
+    }
+}
+
+impl fmt::Display for FunctionBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = self
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param, self.local(*param).typ))
+            .collect::<Vec<_>>();
+        writeln!(
+            f,
+            "fn {}({}) -> {} {{",
+            self.name,
+            params.join(", "),
+            self.return_type
+        )?;
+
+        for (idx, local) in self.locals.iter().enumerate() {
+            if local.kind == LocalKind::Param {
+                continue;
+            }
+            write!(f, "    let {}: {}", LocalId(idx as u32), local.typ)?;
+            match &local.name {
+                Some(name) => writeln!(f, " // {}", name)?,
+                None => writeln!(f)?,
+            }
+        }
+
+        for (idx, block) in self.blocks.iter().enumerate() {
+            writeln!(f)?;
+            writeln!(f, "    {}:", BlockId(idx as u32))?;
+            for stmt in &block.statements {
+                writeln!(f, "        {}", stmt)?;
+            }
+            match &block.terminator {
+                Some(terminator) => writeln!(f, "        {}", terminator)?,
+                None => writeln!(f, "        <unterminated>")?,
+            }
+        }
+        write!(f, "}}")
+    }
+}
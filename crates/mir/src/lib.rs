@@ -0,0 +1,36 @@
+//! Fe mid-level IR.
+//!
+//! The MIR is built from an analyzed (and usually lowered) module. Nested
+//! expressions are flattened into statements over typed locals, and structured
+//! control flow is replaced with basic blocks and explicit jumps, which makes
+//! the IR a simpler input for optimizations and alternate backends than the
+//! AST.
+
+use fe_analyzer::namespace::items::{FunctionId, ModuleId};
+use std::rc::Rc;
+
+pub mod db;
+pub mod ir;
+mod lower;
+
+pub use db::{MirDb, TestDb};
+
+/// Builds the MIR body of a function.
+pub fn lower_function(db: &dyn MirDb, function: FunctionId) -> Rc<ir::FunctionBody> {
+    db.mir_function_body(function)
+}
+
+/// Builds the MIR bodies of all functions defined in a module, including
+/// contract and struct member functions.
+pub fn lower_module(db: &dyn MirDb, module: ModuleId) -> Rc<Vec<Rc<ir::FunctionBody>>> {
+    db.mir_module_bodies(module)
+}
+
+/// Renders the MIR of a module in a human readable form.
+pub fn print_module(db: &dyn MirDb, module: ModuleId) -> String {
+    lower_module(db, module)
+        .iter()
+        .map(|body| body.to_string())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
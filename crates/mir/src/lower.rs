@@ -0,0 +1,779 @@
+use crate::ir::{
+    BasicBlock, BlockId, Callee, Constant, FunctionBody, Local, LocalId, LocalKind, Operand, Place,
+    PlaceBase, Projection, Rvalue, Statement, Terminator,
+};
+use fe_analyzer::builtins::GlobalObject;
+use fe_analyzer::context::{self, CallType, Location};
+use fe_analyzer::namespace::items::{Class, FunctionId, Item};
+use fe_analyzer::namespace::types::{Base, Struct, Type, U256};
+use fe_analyzer::AnalyzerDb;
+use fe_common::numeric;
+use fe_parser::ast as fe;
+use fe_parser::node::Node;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Builds the MIR body of an analyzed function.
+///
+/// # Panics
+///
+/// The function must have been analyzed without errors. Expressions that are
+/// removed by lowering (tuples, lists and paths) are not supported.
+pub fn lower_function(db: &dyn AnalyzerDb, function: FunctionId) -> FunctionBody {
+    let sig = function.signature(db);
+    let mut builder = BodyBuilder::new(db, function.body(db));
+
+    let mut params = vec![];
+    if sig.self_decl.is_some() {
+        if let Some(Class::Struct(id)) = function.class(db) {
+            let local = builder.declare("self".into(), Type::Struct(Struct::from_id(id, db)));
+            builder.locals[local.0 as usize].kind = LocalKind::Param;
+            params.push(local);
+        }
+    }
+    for param in &sig.params {
+        let typ = param.typ.clone().expect("param type error").into();
+        let local = builder.declare(param.name.clone(), typ);
+        builder.locals[local.0 as usize].kind = LocalKind::Param;
+        params.push(local);
+    }
+
+    // A single named return value is a variable that is returned at the end of
+    // the body. Multiple named values are returned as a tuple, which must be
+    // lowered.
+    match function.return_names(db).as_slice() {
+        [] => {}
+        [name] => {
+            let typ = sig.return_type.clone().expect("return type error").into();
+            builder.named_output = Some(builder.declare(name.clone(), typ));
+        }
+        _ => panic!("named return values should be lowered"),
+    }
+
+    builder.stmts(&function.data(db).ast.kind.body);
+    if builder.current_block().terminator.is_none() {
+        let value = builder.bare_return_value();
+        builder.terminate(Terminator::Return(value));
+    }
+
+    let mut body = FunctionBody {
+        name: Item::Function(function).path(db).join("::").into(),
+        params,
+        return_type: sig.return_type.clone().expect("return type error").into(),
+        locals: builder.locals,
+        blocks: builder.blocks,
+    };
+    remove_unreachable_blocks(&mut body);
+    body
+}
+
+struct LoopTargets {
+    continue_block: BlockId,
+    break_block: BlockId,
+}
+
+struct BodyBuilder<'a> {
+    db: &'a dyn AnalyzerDb,
+    fn_body: Rc<context::FunctionBody>,
+    locals: Vec<Local>,
+    blocks: Vec<BasicBlock>,
+    current: BlockId,
+    scopes: Vec<IndexMap<SmolStr, LocalId>>,
+    loops: Vec<LoopTargets>,
+    /// The variable of the named return value, if there is one.
+    named_output: Option<LocalId>,
+}
+
+impl<'a> BodyBuilder<'a> {
+    fn new(db: &'a dyn AnalyzerDb, fn_body: Rc<context::FunctionBody>) -> Self {
+        BodyBuilder {
+            db,
+            fn_body,
+            locals: vec![],
+            blocks: vec![BasicBlock::default()],
+            current: FunctionBody::ENTRY,
+            scopes: vec![IndexMap::new()],
+            loops: vec![],
+            named_output: None,
+        }
+    }
+
+    /// The value returned by a `return` without a value, or at the end of the
+    /// body.
+    fn bare_return_value(&self) -> Operand {
+        match self.named_output {
+            Some(local) => Operand::Place(Place::local(local)),
+            None => Operand::Const(Constant::Unit),
+        }
+    }
+
+    fn expr_type(&self, exp: &Node<fe::Expr>) -> Type {
+        self.fn_body
+            .expressions
+            .get(&exp.id)
+            .expect("missing expression attributes")
+            .typ
+            .clone()
+    }
+
+    fn call_type(&self, func: &Node<fe::Expr>) -> CallType {
+        self.fn_body
+            .calls
+            .get(&func.id)
+            .cloned()
+            .expect("missing call type")
+    }
+
+    fn new_local(&mut self, name: Option<SmolStr>, typ: Type, kind: LocalKind) -> LocalId {
+        self.locals.push(Local { name, typ, kind });
+        LocalId(self.locals.len() as u32 - 1)
+    }
+
+    fn new_temp(&mut self, typ: Type) -> LocalId {
+        self.new_local(None, typ, LocalKind::Temp)
+    }
+
+    /// Adds a named variable to the innermost scope.
+    fn declare(&mut self, name: SmolStr, typ: Type) -> LocalId {
+        let local = self.new_local(Some(name.clone()), typ, LocalKind::Var);
+        self.scopes
+            .last_mut()
+            .expect("missing scope")
+            .insert(name, local);
+        local
+    }
+
+    fn resolve(&self, name: &str) -> LocalId {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .unwrap_or_else(|| panic!("undefined variable `{}`", name))
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push(BasicBlock::default());
+        BlockId(self.blocks.len() as u32 - 1)
+    }
+
+    fn current_block(&mut self) -> &mut BasicBlock {
+        &mut self.blocks[self.current.0 as usize]
+    }
+
+    fn push(&mut self, stmt: Statement) {
+        self.current_block().statements.push(stmt)
+    }
+
+    fn assign(&mut self, place: Place, value: Rvalue) {
+        self.push(Statement::Assign { place, value })
+    }
+
+    /// Terminates the current block. Any statements that follow are unreachable,
+    /// and are collected in a fresh block that is removed once the body is built.
+    fn terminate(&mut self, terminator: Terminator) {
+        let block = self.current_block();
+        if block.terminator.is_none() {
+            block.terminator = Some(terminator);
+        }
+        self.current = self.new_block();
+    }
+
+    fn goto(&mut self, target: BlockId) {
+        self.terminate(Terminator::Goto(target));
+        self.current = target;
+    }
+
+    fn scoped(&mut self, stmts: &[Node<fe::FuncStmt>]) {
+        self.scopes.push(IndexMap::new());
+        self.stmts(stmts);
+        self.scopes.pop();
+    }
+
+    fn stmts(&mut self, stmts: &[Node<fe::FuncStmt>]) {
+        for stmt in stmts {
+            self.stmt(stmt)
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<fe::FuncStmt>) {
+        match &stmt.kind {
+            fe::FuncStmt::Return { value } => {
+                let value = match value {
+                    Some(value) => self.operand(value),
+                    None => self.bare_return_value(),
+                };
+                self.terminate(Terminator::Return(value))
+            }
+            fe::FuncStmt::VarDecl { target, typ, value } => {
+                let name = match &target.kind {
+                    fe::VarDeclTarget::Name(name) => name.clone(),
+                    fe::VarDeclTarget::Tuple(_) => {
+                        panic!("complex VarDeclTargets should be lowered to VarDeclTarget::Name")
+                    }
+                };
+                let typ = self
+                    .fn_body
+                    .var_decl_types
+                    .get(&typ.id)
+                    .cloned()
+                    .expect("missing declaration type")
+                    .into();
+                // The value has to be lowered before the name is in scope.
+                let value = value.as_ref().map(|value| self.rvalue(value));
+                let local = self.declare(name, typ);
+                if let Some(value) = value {
+                    self.assign(Place::local(local), value)
+                }
+            }
+            fe::FuncStmt::Assign { target, value } => {
+                let value = self.rvalue(value);
+                let place = self.place(target);
+                self.assign(place, value)
+            }
+            fe::FuncStmt::AugAssign { target, op, value } => {
+                let rhs = self.operand(value);
+                let place = self.place(target);
+                let value = Rvalue::BinOp {
+                    op: op.kind.clone(),
+                    lhs: Operand::Place(place.clone()),
+                    rhs,
+                };
+                self.assign(place, value)
+            }
+            fe::FuncStmt::For { target, iter, body } => self.for_loop(target, iter, body),
+            fe::FuncStmt::While { test, body } => {
+                let header = self.new_block();
+                let body_block = self.new_block();
+                let exit = self.new_block();
+
+                self.goto(header);
+                let cond = self.operand(test);
+                self.terminate(Terminator::Branch {
+                    cond,
+                    then_block: body_block,
+                    else_block: exit,
+                });
+
+                self.current = body_block;
+                self.loops.push(LoopTargets {
+                    continue_block: header,
+                    break_block: exit,
+                });
+                self.scoped(body);
+                self.loops.pop();
+                self.terminate(Terminator::Goto(header));
+                self.current = exit;
+            }
+            fe::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                let cond = self.operand(test);
+                let then_block = self.new_block();
+                let else_block = self.new_block();
+                let join = self.new_block();
+                self.terminate(Terminator::Branch {
+                    cond,
+                    then_block,
+                    else_block,
+                });
+
+                self.current = then_block;
+                self.scoped(body);
+                self.terminate(Terminator::Goto(join));
+
+                self.current = else_block;
+                self.scoped(or_else);
+                self.terminate(Terminator::Goto(join));
+                self.current = join;
+            }
+            fe::FuncStmt::Assert { test, msg } => {
+                let cond = self.operand(test);
+                let ok = self.new_block();
+                let failure = self.new_block();
+                self.terminate(Terminator::Branch {
+                    cond,
+                    then_block: ok,
+                    else_block: failure,
+                });
+
+                self.current = failure;
+                let msg = msg.as_ref().map(|msg| self.operand(msg));
+                self.terminate(Terminator::AssertFailure(msg));
+                self.current = ok;
+            }
+            fe::FuncStmt::Emit { name, args } => {
+                let event = *self
+                    .fn_body
+                    .emits
+                    .get(&stmt.id)
+                    .expect("missing emit event type");
+                let args = args
+                    .kind
+                    .iter()
+                    .map(|arg| self.operand(&arg.kind.value))
+                    .collect();
+                self.push(Statement::Emit {
+                    event,
+                    name: name.kind.clone(),
+                    args,
+                })
+            }
+            fe::FuncStmt::Expr { value } => {
+                let value = self.rvalue(value);
+                self.push(Statement::Eval(value))
+            }
+            fe::FuncStmt::Pass => {}
+            fe::FuncStmt::Break => {
+                let target = self
+                    .loops
+                    .last()
+                    .expect("break outside of loop")
+                    .break_block;
+                self.terminate(Terminator::Goto(target))
+            }
+            fe::FuncStmt::Continue => {
+                let target = self
+                    .loops
+                    .last()
+                    .expect("continue outside of loop")
+                    .continue_block;
+                self.terminate(Terminator::Goto(target))
+            }
+            fe::FuncStmt::Revert { error } => {
+                let error = error.as_ref().map(|error| self.operand(error));
+                self.terminate(Terminator::Revert(error))
+            }
+            fe::FuncStmt::Unsafe(body) => self.scoped(body),
+            fe::FuncStmt::Match { value, arms } => self.switch(value, arms),
+            fe::FuncStmt::Yul(body) => self.push(Statement::InlineYul(body.clone())),
+        }
+    }
+
+    /// Only the `match` statements that yulgen compiles to a `switch` are left
+    /// by lowering. Each case is tested in turn, until the default arm:
+    ///
+    /// ```text
+    /// test0: cond = value == case0; branch cond ? arm0 : test1
+    /// test1: cond = value == case1; branch cond ? arm1 : default
+    /// default: ...; goto join
+    /// ```
+    ///
+    /// Cases that an earlier arm already covers can't be reached and are left
+    /// out, like in the `switch`.
+    fn switch(&mut self, value: &Node<fe::Expr>, arms: &[Node<fe::MatchArm>]) {
+        let value_type = self.expr_type(value);
+        let value = self.operand(value);
+        let join = self.new_block();
+
+        let mut covered = vec![];
+        for arm in arms {
+            let case = match &arm.kind.pat.kind {
+                fe::Pattern::Wildcard => None,
+                fe::Pattern::Path(path) => {
+                    let variant = path.segments.last().expect("empty path");
+                    match &value_type {
+                        Type::Enum(val) => Some(BigInt::from(
+                            val.id
+                                .variant_index(self.db, &variant.kind)
+                                .expect("undefined enum variant"),
+                        )),
+                        _ => panic!("path patterns can only match enums"),
+                    }
+                }
+                fe::Pattern::Literal(Node {
+                    kind: fe::Expr::Num(num),
+                    ..
+                }) => Some(
+                    numeric::Literal::new(num)
+                        .parse::<BigInt>()
+                        .expect("invalid numeric literal"),
+                ),
+                _ => panic!("`match` patterns should be lowered"),
+            };
+
+            let case = match case {
+                Some(case) if covered.contains(&case) => continue,
+                Some(case) => case,
+                None => {
+                    self.scoped(&arm.kind.body);
+                    self.goto(join);
+                    return;
+                }
+            };
+            let cond = self.new_temp(Type::Base(Base::Bool));
+            self.assign(
+                Place::local(cond),
+                Rvalue::CompOp {
+                    op: fe::CompOperator::Eq,
+                    lhs: value.clone(),
+                    rhs: Operand::Const(Constant::Int(case.clone())),
+                },
+            );
+            let arm_block = self.new_block();
+            let next = self.new_block();
+            self.terminate(Terminator::Branch {
+                cond: Operand::Place(Place::local(cond)),
+                then_block: arm_block,
+                else_block: next,
+            });
+
+            self.current = arm_block;
+            self.scoped(&arm.kind.body);
+            self.terminate(Terminator::Goto(join));
+            self.current = next;
+            covered.push(case);
+        }
+        self.goto(join);
+    }
+
+    /// Arrays are iterated with an explicit index local:
+    ///
+    /// ```text
+    /// idx = 0
+    /// header: branch idx < size ? body : exit
+    /// body:   target = iter[idx]; ...; goto latch
+    /// latch:  idx = idx + 1; goto header
+    /// ```
+    fn for_loop(
+        &mut self,
+        target: &Node<SmolStr>,
+        iter: &Node<fe::Expr>,
+        body: &[Node<fe::FuncStmt>],
+    ) {
+        let array = match self.expr_type(iter) {
+            Type::Array(array) => array,
+            _ => panic!("invalid iter expression"),
+        };
+        let iter = self.operand(iter);
+        let iter = self.operand_to_place(iter, Type::Array(array.clone()));
+
+        let idx = self.new_temp(Type::Base(U256));
+        self.assign(
+            Place::local(idx),
+            Rvalue::Use(Operand::Const(Constant::Int(BigInt::from(0)))),
+        );
+
+        let header = self.new_block();
+        let body_block = self.new_block();
+        let latch = self.new_block();
+        let exit = self.new_block();
+
+        self.goto(header);
+        let cond = self.new_temp(Type::Base(Base::Bool));
+        self.assign(
+            Place::local(cond),
+            Rvalue::CompOp {
+                op: fe::CompOperator::Lt,
+                lhs: Operand::Place(Place::local(idx)),
+                rhs: Operand::Const(Constant::Int(BigInt::from(array.size))),
+            },
+        );
+        self.terminate(Terminator::Branch {
+            cond: Operand::Place(Place::local(cond)),
+            then_block: body_block,
+            else_block: exit,
+        });
+
+        self.current = body_block;
+        self.scopes.push(IndexMap::new());
+        let target = self.declare(target.kind.clone(), Type::Base(array.inner));
+        self.assign(
+            Place::local(target),
+            Rvalue::Use(Operand::Place(
+                iter.project(Projection::Index(Operand::Place(Place::local(idx)))),
+            )),
+        );
+        self.loops.push(LoopTargets {
+            continue_block: latch,
+            break_block: exit,
+        });
+        self.stmts(body);
+        self.loops.pop();
+        self.scopes.pop();
+        self.goto(latch);
+
+        self.assign(
+            Place::local(idx),
+            Rvalue::BinOp {
+                op: fe::BinOperator::Add,
+                lhs: Operand::Place(Place::local(idx)),
+                rhs: Operand::Const(Constant::Int(BigInt::from(1))),
+            },
+        );
+        self.terminate(Terminator::Goto(header));
+        self.current = exit;
+    }
+
+    /// Lowers an expression to an operand, introducing a temporary if the
+    /// expression isn't a constant or a place.
+    fn operand(&mut self, exp: &Node<fe::Expr>) -> Operand {
+        match self.rvalue(exp) {
+            Rvalue::Use(operand) => operand,
+            value => {
+                let temp = self.new_temp(self.expr_type(exp));
+                self.assign(Place::local(temp), value);
+                Operand::Place(Place::local(temp))
+            }
+        }
+    }
+
+    fn operand_to_place(&mut self, operand: Operand, typ: Type) -> Place {
+        match operand {
+            Operand::Place(place) => place,
+            Operand::Const(_) => {
+                let temp = self.new_temp(typ);
+                self.assign(Place::local(temp), Rvalue::Use(operand));
+                Place::local(temp)
+            }
+        }
+    }
+
+    fn rvalue(&mut self, exp: &Node<fe::Expr>) -> Rvalue {
+        match &exp.kind {
+            fe::Expr::Num(num) => {
+                let num = numeric::Literal::new(num)
+                    .parse::<BigInt>()
+                    .expect("invalid numeric literal");
+                Rvalue::Use(Operand::Const(Constant::Int(num)))
+            }
+            fe::Expr::Bool(val) => Rvalue::Use(Operand::Const(Constant::Bool(*val))),
+            fe::Expr::Str(val) => Rvalue::Use(Operand::Const(Constant::Str(val.clone()))),
+            fe::Expr::Unit => Rvalue::Use(Operand::Const(Constant::Unit)),
+            fe::Expr::Name(_) | fe::Expr::Subscript { .. } => {
+                Rvalue::Use(Operand::Place(self.place(exp)))
+            }
+            fe::Expr::Attribute { value, attr } => match self.env_read(value, attr) {
+                Some(env) => env,
+                None => Rvalue::Use(Operand::Place(self.place(exp))),
+            },
+            fe::Expr::BinOperation { left, op, right } => Rvalue::BinOp {
+                op: op.kind.clone(),
+                lhs: self.operand(left),
+                rhs: self.operand(right),
+            },
+            fe::Expr::CompOperation { left, op, right } => Rvalue::CompOp {
+                op: op.kind.clone(),
+                lhs: self.operand(left),
+                rhs: self.operand(right),
+            },
+            fe::Expr::UnaryOperation { op, operand } => Rvalue::UnOp {
+                op: op.kind.clone(),
+                operand: self.operand(operand),
+            },
+            fe::Expr::Slice { value, start, end } => Rvalue::Slice {
+                value: self.operand(value),
+                start: self.operand(start.as_ref().expect("slice bounds should be lowered")),
+                end: self.operand(end.as_ref().expect("slice bounds should be lowered")),
+            },
+            fe::Expr::BoolOperation { left, op, right } => {
+                // `a and b` only evaluates `b` if `a` is true, `a or b` only if `a` is false.
+                let result = self.new_temp(Type::Base(Base::Bool));
+                let lhs = self.rvalue(left);
+                self.assign(Place::local(result), lhs);
+
+                let rhs_block = self.new_block();
+                let join = self.new_block();
+                let (then_block, else_block) = match op.kind {
+                    fe::BoolOperator::And => (rhs_block, join),
+                    fe::BoolOperator::Or => (join, rhs_block),
+                };
+                self.terminate(Terminator::Branch {
+                    cond: Operand::Place(Place::local(result)),
+                    then_block,
+                    else_block,
+                });
+
+                self.current = rhs_block;
+                let rhs = self.rvalue(right);
+                self.assign(Place::local(result), rhs);
+                self.goto(join);
+                Rvalue::Use(Operand::Place(Place::local(result)))
+            }
+            fe::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                let result = self.new_temp(self.expr_type(exp));
+                let cond = self.operand(test);
+                let then_block = self.new_block();
+                let else_block = self.new_block();
+                let join = self.new_block();
+                self.terminate(Terminator::Branch {
+                    cond,
+                    then_block,
+                    else_block,
+                });
+
+                self.current = then_block;
+                let value = self.rvalue(if_expr);
+                self.assign(Place::local(result), value);
+                self.terminate(Terminator::Goto(join));
+
+                self.current = else_block;
+                let value = self.rvalue(else_expr);
+                self.assign(Place::local(result), value);
+                self.goto(join);
+                Rvalue::Use(Operand::Place(Place::local(result)))
+            }
+            fe::Expr::Call { func, args, .. } => self.call(func, args),
+            fe::Expr::List { .. } => panic!("list expressions should be lowered"),
+            fe::Expr::Tuple { .. } => panic!("tuple expressions should be lowered"),
+            fe::Expr::Try { .. } => panic!("`?` expressions should be lowered"),
+            fe::Expr::Path(_) => panic!("path expressions should be lowered or rejected"),
+        }
+    }
+
+    /// Reads of the global objects (`msg.sender`, `block.number`, ...) and of
+    /// `self.address` don't refer to a place.
+    fn env_read(&self, target: &Node<fe::Expr>, field: &Node<SmolStr>) -> Option<Rvalue> {
+        if let fe::Expr::Name(name) = &target.kind {
+            if GlobalObject::from_str(name).is_ok() {
+                return Some(Rvalue::Env {
+                    object: name.clone(),
+                    field: field.kind.clone(),
+                });
+            }
+        }
+        match self.expr_type(target) {
+            Type::SelfContract(_) if field.kind == "address" => Some(Rvalue::Env {
+                object: "self".into(),
+                field: field.kind.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn place(&mut self, exp: &Node<fe::Expr>) -> Place {
+        match &exp.kind {
+            fe::Expr::Name(name) => Place::local(self.resolve(name)),
+            fe::Expr::Attribute { value, attr } => match self.expr_type(value) {
+                Type::SelfContract(_) => {
+                    let nonce = match self.fn_body.expressions[&exp.id].location {
+                        Location::Storage { nonce: Some(nonce) } => nonce,
+                        _ => panic!("expected contract `self` field to be in storage"),
+                    };
+                    Place {
+                        base: PlaceBase::ContractField {
+                            name: attr.kind.clone(),
+                            nonce,
+                        },
+                        projections: vec![],
+                    }
+                }
+                typ => {
+                    let base = self.operand(value);
+                    self.operand_to_place(base, typ)
+                        .project(Projection::Field(attr.kind.clone()))
+                }
+            },
+            fe::Expr::Subscript { value, index } => {
+                let typ = self.expr_type(value);
+                let base = self.operand(value);
+                let base = self.operand_to_place(base, typ);
+                let index = self.operand(index);
+                base.project(Projection::Index(index))
+            }
+            _ => {
+                let operand = self.operand(exp);
+                self.operand_to_place(operand, self.expr_type(exp))
+            }
+        }
+    }
+
+    fn call(&mut self, func: &Node<fe::Expr>, args: &Node<Vec<Node<fe::CallArg>>>) -> Rvalue {
+        let call_type = self.call_type(func);
+        let receiver = match &func.kind {
+            fe::Expr::Attribute { value, .. } => Some(value.as_ref()),
+            _ => None,
+        };
+
+        let takes_receiver = match &call_type {
+            CallType::BuiltinValueMethod { .. } | CallType::External { .. } => true,
+            CallType::ValueMethod { class, .. } => matches!(class, Class::Struct(_)),
+            _ => false,
+        };
+        let mut operands = vec![];
+        if takes_receiver {
+            let receiver = receiver.expect("method call without receiver");
+            operands.push(self.operand(receiver));
+        }
+        operands.extend(args.kind.iter().map(|arg| self.operand(&arg.kind.value)));
+
+        let callee = match call_type {
+            CallType::BuiltinFunction(func) => Callee::Builtin(func.as_ref().into()),
+            CallType::Intrinsic(intrinsic) => Callee::Builtin(intrinsic.as_ref().into()),
+            CallType::BuiltinValueMethod { method, .. } => Callee::Builtin(method.as_ref().into()),
+            CallType::BuiltinAssociatedFunction { contract, function } => {
+                Callee::Builtin(format!("{}::{}", contract.name(self.db), function.as_ref()).into())
+            }
+            CallType::AssociatedFunction { function: id, .. }
+            | CallType::ValueMethod { method: id, .. }
+            | CallType::Pure(id) => Callee::Function {
+                id,
+                name: Item::Function(id).path(self.db).join("::").into(),
+            },
+            CallType::External { function: id, .. } => Callee::External {
+                id,
+                name: Item::Function(id).path(self.db).join("::").into(),
+            },
+            CallType::TypeConstructor(typ) => Callee::TypeConstructor(typ),
+        };
+
+        Rvalue::Call {
+            callee,
+            args: operands,
+        }
+    }
+}
+
+/// Removes blocks that can't be reached from the entry block, and renumbers the
+/// remaining blocks.
+fn remove_unreachable_blocks(body: &mut FunctionBody) {
+    let mut reachable = HashSet::new();
+    let mut stack = vec![FunctionBody::ENTRY];
+    while let Some(block) = stack.pop() {
+        if reachable.insert(block) {
+            if let Some(terminator) = &body.block(block).terminator {
+                stack.extend(terminator.successors());
+            }
+        }
+    }
+
+    let mut remap = IndexMap::new();
+    for idx in 0..body.blocks.len() {
+        let id = BlockId(idx as u32);
+        if reachable.contains(&id) {
+            remap.insert(id, BlockId(remap.len() as u32));
+        }
+    }
+
+    let blocks = std::mem::take(&mut body.blocks);
+    body.blocks = blocks
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| remap.contains_key(&BlockId(*idx as u32)))
+        .map(|(_, mut block)| {
+            block.terminator = block.terminator.map(|terminator| match terminator {
+                Terminator::Goto(target) => Terminator::Goto(remap[&target]),
+                Terminator::Branch {
+                    cond,
+                    then_block,
+                    else_block,
+                } => Terminator::Branch {
+                    cond,
+                    then_block: remap[&then_block],
+                    else_block: remap[&else_block],
+                },
+                terminator => terminator,
+            });
+            block
+        })
+        .collect();
+}
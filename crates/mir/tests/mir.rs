@@ -0,0 +1,100 @@
+use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent, ModuleId};
+use fe_analyzer::AnalyzerDb;
+use fe_common::diagnostics::print_diagnostics;
+use fe_common::files::FileStore;
+use fe_mir::ir::{FunctionBody, Terminator};
+use fe_mir::TestDb;
+use std::rc::Rc;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn analyzed_module(db: &TestDb, path: &str, files: &mut FileStore) -> ModuleId {
+    let src = test_files::fixture(path);
+    let id = files.add_file(path, src);
+    let ast = match fe_parser::parse_file(id, src) {
+        Ok((module, diags)) if diags.is_empty() => module,
+        Ok((_, diags)) | Err(diags) => {
+            print_diagnostics(&diags, files);
+            panic!("failed to parse file");
+        }
+    };
+
+    let global_id = db.intern_global(Rc::new(Global::default()));
+    let module_id = db.intern_module(Rc::new(Module {
+        name: "test_module".into(),
+        context: ModuleContext::Global(global_id),
+        file_content: ModuleFileContent::File { file: id },
+        ast,
+    }));
+
+    if let Err(diags) = fe_analyzer::analyze_module(db, module_id) {
+        print_diagnostics(&diags, files);
+        panic!("failed to analyze module");
+    }
+    module_id
+}
+
+fn assert_well_formed(body: &FunctionBody) {
+    for (idx, block) in body.blocks.iter().enumerate() {
+        let terminator = block
+            .terminator
+            .as_ref()
+            .unwrap_or_else(|| panic!("bb{} of `{}` is not terminated", idx, body.name));
+        for successor in terminator.successors() {
+            assert!(
+                (successor.0 as usize) < body.blocks.len(),
+                "bb{} of `{}` jumps to missing block {}",
+                idx,
+                body.name,
+                successor
+            );
+        }
+    }
+}
+
+macro_rules! test_file {
+    ($name:ident, $path:expr, $min_blocks:expr) => {
+        #[test]
+        #[wasm_bindgen_test]
+        fn $name() {
+            let mut files = FileStore::new();
+            let db = TestDb::default();
+            let module_id = analyzed_module(&db, $path, &mut files);
+
+            let bodies = fe_mir::lower_module(&db, module_id);
+            assert!(!bodies.is_empty());
+            for body in bodies.iter() {
+                assert_well_formed(body);
+            }
+            let max_blocks = bodies.iter().map(|body| body.blocks.len()).max();
+            assert!(max_blocks >= Some($min_blocks));
+            assert!(!fe_mir::print_module(&db, module_id).is_empty());
+        }
+    };
+}
+
+test_file! { return_u256, "features/return_u256.fe", 1 }
+test_file! { if_statement, "features/if_statement.fe", 3 }
+test_file! { while_loop, "features/while_loop.fe", 5 }
+test_file! { for_loop_with_break, "features/for_loop_with_break.fe", 5 }
+test_file! { events, "features/events.fe", 1 }
+test_file! { struct_fns, "features/struct_fns.fe", 1 }
+test_file! { revert, "features/revert.fe", 1 }
+test_file! { match_enum, "features/match_enum.fe", 3 }
+test_file! { inline_yul, "features/inline_yul.fe", 1 }
+
+#[test]
+#[wasm_bindgen_test]
+fn unreachable_blocks_are_removed() {
+    let mut files = FileStore::new();
+    let db = TestDb::default();
+    let module_id = analyzed_module(&db, "features/return_u256.fe", &mut files);
+
+    for body in fe_mir::lower_module(&db, module_id).iter() {
+        let returns = body
+            .blocks
+            .iter()
+            .filter(|block| matches!(block.terminator, Some(Terminator::Return(_))))
+            .count();
+        assert_eq!(returns, 1, "{}", body);
+    }
+}
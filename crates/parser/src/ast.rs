@@ -1,11 +1,12 @@
 use crate::node::Node;
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+use core::fmt;
+use core::fmt::Formatter;
+use core::fmt::Write;
 use fe_common::{Span, Spanned};
 use indenter::indented;
 use serde::{Deserialize, Serialize};
 pub use smol_str::SmolStr;
-use std::fmt;
-use std::fmt::Formatter;
-use std::fmt::Write;
 use vec1::Vec1;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -17,12 +18,16 @@ pub struct Module {
 pub enum ModuleStmt {
     Pragma(Node<Pragma>),
     Use(Node<Use>),
+    AbiImport(Node<AbiImport>),
     TypeAlias(Node<TypeAlias>),
     Contract(Node<Contract>),
     Constant(Box<Node<ConstantDecl>>),
     Struct(Node<Struct>),
+    Enum(Node<Enum>),
     Function(Node<Function>),
     Event(Node<Event>),
+    Trait(Node<Trait>),
+    Impl(Node<Impl>),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -40,6 +45,15 @@ pub struct Use {
     pub tree: Node<UseTree>,
 }
 
+/// An interface generated from the JSON ABI of a deployed contract, e.g.
+/// `use abi "./erc20_abi.json" as ERC20`. The path is relative to the file of
+/// the statement.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct AbiImport {
+    pub path: Node<SmolStr>,
+    pub name: Node<SmolStr>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum UseTree {
     Glob {
@@ -75,14 +89,69 @@ pub struct Contract {
     pub fields: Vec<Node<Field>>,
     pub body: Vec<ContractStmt>,
     pub pub_qual: Option<Span>,
+    /// The storage layout struct of a diamond facet, e.g. `AppStorage` in
+    /// `contract Counter for AppStorage:`.
+    pub layout: Option<Node<SmolStr>>,
+    /// The `upgradeable` qualifier of a contract that is meant to live behind
+    /// a proxy, and is set up by `initialize` instead of `__init__`.
+    pub upgradeable_qual: Option<Span>,
+    /// The `multicall` qualifier of a contract that gets a generated
+    /// `multicall(bytes[])` function, which batches calls to its public
+    /// functions.
+    pub multicall_qual: Option<Span>,
+    /// The `ownable` qualifier of a contract that gets an owner, which is set
+    /// to the deployer, and the functions to check and transfer ownership.
+    pub ownable_qual: Option<Span>,
+    /// The `pausable` qualifier of an `ownable` contract that the owner can
+    /// pause and unpause.
+    pub pausable_qual: Option<Span>,
+    /// The `roles` qualifier of a contract that grants roles to accounts, and
+    /// the functions to check, grant and revoke them.
+    pub roles_qual: Option<Span>,
+    /// `true` for an interface, e.g. `interface Token:`, which declares the
+    /// public functions of a contract that's deployed elsewhere. The functions
+    /// of an interface have no body.
+    pub is_interface: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Struct {
     pub name: Node<SmolStr>,
+    pub generic_params: Vec<Node<GenericParameter>>,
     pub fields: Vec<Node<Field>>,
     pub functions: Vec<Node<Function>>,
     pub pub_qual: Option<Span>,
+    /// `true` for a custom error, e.g. `error Unauthorized(caller: address)`,
+    /// which is a struct with public fields and no functions.
+    pub is_error: bool,
+}
+
+/// A trait, e.g. `trait Describe:`, which declares methods that are shared by
+/// the structs and contracts that implement it. A method without a body must
+/// be defined by each implementation. The others are default methods, which an
+/// implementation gets unless it defines a method of the same name.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Trait {
+    pub name: Node<SmolStr>,
+    pub functions: Vec<Node<Function>>,
+    pub pub_qual: Option<Span>,
+}
+
+/// The implementation of a trait for a struct or contract that's defined in
+/// the same module, e.g. `impl Describe for Point:`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Impl {
+    pub trait_name: Node<SmolStr>,
+    pub receiver: Node<SmolStr>,
+    pub functions: Vec<Node<Function>>,
+}
+
+/// An enum with unit variants, e.g. `enum State: Pending, Active, Closed`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Enum {
+    pub name: Node<SmolStr>,
+    pub variants: Vec<Node<SmolStr>>,
+    pub pub_qual: Option<Span>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -119,6 +188,14 @@ impl Spanned for GenericArg {
     }
 }
 
+/// A type parameter of a generic function or struct, with an optional bound,
+/// e.g. `T: Numeric` in `fn max<T: Numeric>(a: T, b: T) -> T`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct GenericParameter {
+    pub name: Node<SmolStr>,
+    pub bound: Option<Node<SmolStr>>,
+}
+
 /// struct or contract field, with optional 'pub' and 'const' qualifiers
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Field {
@@ -145,12 +222,25 @@ pub struct Event {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Function {
-    // qualifier order: `pub unsafe fn`
+    // qualifier order: `pub const unsafe fn`
     pub pub_: Option<Span>,
+    pub const_: Option<Span>,
     pub unsafe_: Option<Span>,
+    /// The `test` qualifier of a contract function that `fe test` runs, e.g.
+    /// `test pub fn increment(self):`.
+    pub test: Option<Span>,
+    /// The `only_role` qualifier of a contract function that only accounts
+    /// with the role can call, e.g. `only_role("MINTER") pub fn mint(self):`,
+    /// with the name of the role.
+    pub only_role: Option<Node<SmolStr>>,
     pub name: Node<SmolStr>,
+    pub generic_params: Vec<Node<GenericParameter>>,
     pub args: Vec<Node<FunctionArg>>,
     pub return_type: Option<Node<TypeDesc>>,
+    /// The names of the outputs of a function with named return values, e.g.
+    /// `amount` and `ok` in `fn f() -> (amount: u256, ok: bool)`. Empty if the
+    /// outputs are unnamed.
+    pub return_names: Vec<Node<SmolStr>>,
     pub body: Vec<Node<FuncStmt>>,
 }
 
@@ -226,6 +316,12 @@ pub enum FuncStmt {
         error: Option<Node<Expr>>,
     },
     Unsafe(Vec<Node<FuncStmt>>),
+    Match {
+        value: Node<Expr>,
+        arms: Vec<Node<MatchArm>>,
+    },
+    /// An inline Yul block, e.g. `yul { x := add(x, 1) }`.
+    Yul(Vec<Node<YulStmt>>),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -234,6 +330,86 @@ pub enum VarDeclTarget {
     Tuple(Vec<Node<VarDeclTarget>>),
 }
 
+/// A statement of an inline Yul block. Function definitions and `leave` aren't
+/// supported.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum YulStmt {
+    Block(Vec<Node<YulStmt>>),
+    Let {
+        names: Vec<Node<SmolStr>>,
+        value: Option<Node<YulExpr>>,
+    },
+    Assign {
+        names: Vec<Node<SmolStr>>,
+        value: Node<YulExpr>,
+    },
+    If {
+        test: Node<YulExpr>,
+        body: Vec<Node<YulStmt>>,
+    },
+    Switch {
+        value: Node<YulExpr>,
+        cases: Vec<Node<YulCase>>,
+    },
+    For {
+        init: Vec<Node<YulStmt>>,
+        test: Node<YulExpr>,
+        post: Vec<Node<YulStmt>>,
+        body: Vec<Node<YulStmt>>,
+    },
+    Break,
+    Continue,
+    Expr(Node<YulExpr>),
+}
+
+/// A `case` of a Yul `switch` statement. The `default` case has no value.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct YulCase {
+    pub value: Option<Node<SmolStr>>,
+    pub body: Vec<Node<YulStmt>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum YulExpr {
+    Name(SmolStr),
+    /// A number or boolean literal, as written.
+    Literal(SmolStr),
+    Call {
+        func: Node<SmolStr>,
+        args: Node<Vec<Node<YulExpr>>>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MatchArm {
+    pub pat: Node<Pattern>,
+    pub guard: Option<Node<Expr>>,
+    pub body: Vec<Node<FuncStmt>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Pattern {
+    /// `_`
+    Wildcard,
+    Binding(SmolStr),
+    /// A number, string or boolean literal.
+    Literal(Node<Expr>),
+    /// An enum variant, e.g. `State::Active`.
+    Path(Path),
+    Tuple(Vec<Node<Pattern>>),
+    /// `Point(x=0, y)`. Fields that aren't listed are ignored.
+    Struct {
+        name: Node<SmolStr>,
+        fields: Vec<Node<FieldPattern>>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct FieldPattern {
+    pub label: Node<SmolStr>,
+    pub pat: Node<Pattern>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Expr {
     Ternary {
@@ -268,6 +444,15 @@ pub enum Expr {
         value: Box<Node<Expr>>,
         index: Box<Node<Expr>>,
     },
+    /// `value[start:end]`. Either bound can be omitted.
+    Slice {
+        value: Box<Node<Expr>>,
+        start: Option<Box<Node<Expr>>>,
+        end: Option<Box<Node<Expr>>>,
+    },
+    Try {
+        value: Box<Node<Expr>>,
+    },
     Call {
         func: Box<Node<Expr>>,
         generic_args: Option<Node<Vec<GenericArg>>>,
@@ -343,6 +528,12 @@ impl Node<Struct> {
     }
 }
 
+impl Node<Trait> {
+    pub fn name(&self) -> &str {
+        &self.kind.name.kind
+    }
+}
+
 impl Node<Event> {
     pub fn name(&self) -> &str {
         &self.kind.name.kind
@@ -359,9 +550,45 @@ impl Function {
     pub fn is_pub(&self) -> bool {
         self.pub_.is_some()
     }
+    pub fn is_const(&self) -> bool {
+        self.const_.is_some()
+    }
     pub fn is_unsafe(&self) -> bool {
         self.unsafe_.is_some()
     }
+
+    /// The named return values of the function and their types, in order.
+    /// Empty if the outputs are unnamed.
+    pub fn named_outputs(&self) -> Vec<(&Node<SmolStr>, &Node<TypeDesc>)> {
+        let return_type = match &self.return_type {
+            Some(return_type) if !self.return_names.is_empty() => return_type,
+            _ => return vec![],
+        };
+        match &return_type.kind {
+            TypeDesc::Tuple { items } if self.return_names.len() > 1 => {
+                self.return_names.iter().zip(items.iter()).collect()
+            }
+            _ => self
+                .return_names
+                .iter()
+                .map(|name| (name, return_type))
+                .collect(),
+        }
+    }
+}
+
+impl Pattern {
+    /// Returns `true` if the pattern matches every (well-typed) value.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding(_) => true,
+            Pattern::Literal(_) | Pattern::Path(_) => false,
+            Pattern::Tuple(items) => items.iter().all(|item| item.kind.is_irrefutable()),
+            Pattern::Struct { fields, .. } => fields
+                .iter()
+                .all(|field| field.kind.pat.kind.is_irrefutable()),
+        }
+    }
 }
 
 impl Node<Function> {
@@ -397,12 +624,16 @@ impl Spanned for ModuleStmt {
         match self {
             ModuleStmt::Pragma(inner) => inner.span,
             ModuleStmt::Use(inner) => inner.span,
+            ModuleStmt::AbiImport(inner) => inner.span,
             ModuleStmt::TypeAlias(inner) => inner.span,
             ModuleStmt::Contract(inner) => inner.span,
             ModuleStmt::Constant(inner) => inner.span,
             ModuleStmt::Struct(inner) => inner.span,
+            ModuleStmt::Enum(inner) => inner.span,
             ModuleStmt::Function(inner) => inner.span,
             ModuleStmt::Event(inner) => inner.span,
+            ModuleStmt::Trait(inner) => inner.span,
+            ModuleStmt::Impl(inner) => inner.span,
         }
     }
 }
@@ -427,16 +658,26 @@ impl fmt::Display for ModuleStmt {
         match self {
             ModuleStmt::Pragma(node) => write!(f, "{}", node.kind),
             ModuleStmt::Use(node) => write!(f, "{}", node.kind),
+            ModuleStmt::AbiImport(node) => write!(f, "{}", node.kind),
             ModuleStmt::TypeAlias(node) => write!(f, "{}", node.kind),
             ModuleStmt::Contract(node) => write!(f, "{}", node.kind),
             ModuleStmt::Constant(node) => write!(f, "{}", node.kind),
             ModuleStmt::Struct(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Enum(node) => write!(f, "{}", node.kind),
             ModuleStmt::Function(node) => write!(f, "{}", node.kind),
             ModuleStmt::Event(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Trait(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Impl(node) => write!(f, "{}", node.kind),
         }
     }
 }
 
+impl fmt::Display for AbiImport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "use abi {:?} as {}", self.path.kind, self.name.kind)
+    }
+}
+
 impl fmt::Display for Pragma {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "pragma {}", self.version_requirement.kind)
@@ -499,7 +740,33 @@ impl fmt::Display for TypeAlias {
 
 impl fmt::Display for Contract {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "contract {}:", self.name.kind)?;
+        if self.is_interface {
+            writeln!(f, "interface {}:", self.name.kind)?;
+            if self.body.is_empty() {
+                return write!(indented(f), "pass");
+            }
+            return write!(indented(f), "{}", line_joined(&self.body));
+        }
+        if self.upgradeable_qual.is_some() {
+            write!(f, "upgradeable ")?;
+        }
+        if self.multicall_qual.is_some() {
+            write!(f, "multicall ")?;
+        }
+        if self.ownable_qual.is_some() {
+            write!(f, "ownable ")?;
+        }
+        if self.pausable_qual.is_some() {
+            write!(f, "pausable ")?;
+        }
+        if self.roles_qual.is_some() {
+            write!(f, "roles ")?;
+        }
+        write!(f, "contract {}", self.name.kind)?;
+        if let Some(layout) = &self.layout {
+            write!(f, " for {}", layout.kind)?;
+        }
+        writeln!(f, ":")?;
         if !self.fields.is_empty() {
             write!(indented(f), "{}\n\n", node_line_joined(&self.fields))?;
         }
@@ -513,7 +780,19 @@ impl fmt::Display for Contract {
 
 impl fmt::Display for Struct {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "struct {}:", self.name.kind)?;
+        if self.is_error {
+            let fields = self
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", field.kind.name.kind, field.kind.typ.kind))
+                .collect::<Vec<_>>();
+            return write!(f, "error {}({})", self.name.kind, fields.join(", "));
+        }
+        write!(f, "struct {}", self.name.kind)?;
+        if !self.generic_params.is_empty() {
+            write!(f, "<{}>", node_comma_joined(&self.generic_params))?;
+        }
+        writeln!(f, ":")?;
         if self.fields.is_empty() && self.functions.is_empty() {
             write!(indented(f), "pass")
         } else {
@@ -526,6 +805,46 @@ impl fmt::Display for Struct {
     }
 }
 
+impl fmt::Display for Trait {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.pub_qual.is_some() {
+            write!(f, "pub ")?;
+        }
+        writeln!(f, "trait {}:", self.name.kind)?;
+        if self.functions.is_empty() {
+            write!(indented(f), "pass")
+        } else {
+            write!(indented(f), "{}", double_line_joined(&self.functions))
+        }
+    }
+}
+
+impl fmt::Display for Impl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "impl {} for {}:",
+            self.trait_name.kind, self.receiver.kind
+        )?;
+        if self.functions.is_empty() {
+            write!(indented(f), "pass")
+        } else {
+            write!(indented(f), "{}", double_line_joined(&self.functions))
+        }
+    }
+}
+
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "enum {}: {}",
+            self.name.kind,
+            node_comma_joined(&self.variants)
+        )
+    }
+}
+
 impl fmt::Display for TypeDesc {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -549,6 +868,16 @@ impl fmt::Display for GenericArg {
     }
 }
 
+impl fmt::Display for GenericParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name.kind)?;
+        if let Some(bound) = &self.bound {
+            write!(f, ": {}", bound.kind)?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if self.is_pub {
@@ -589,20 +918,38 @@ impl fmt::Display for Node<Function> {
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.test.is_some() {
+            write!(f, "test ")?;
+        }
+        if let Some(role) = &self.only_role {
+            write!(f, "only_role({:?}) ", role.kind)?;
+        }
         if self.is_pub() {
             write!(f, "pub ")?;
         }
-        write!(
-            f,
-            "fn {}({})",
-            self.name.kind,
-            node_comma_joined(&self.args)
-        )?;
-        if let Some(return_type) = self.return_type.as_ref() {
-            writeln!(f, " -> {}:", return_type.kind)?;
-        } else {
-            writeln!(f, ":")?;
+        if self.is_const() {
+            write!(f, "const ")?;
+        }
+        write!(f, "fn {}", self.name.kind)?;
+        if !self.generic_params.is_empty() {
+            write!(f, "<{}>", node_comma_joined(&self.generic_params))?;
+        }
+        write!(f, "({})", node_comma_joined(&self.args))?;
+        let named_outputs = self.named_outputs();
+        if !named_outputs.is_empty() {
+            let outputs = named_outputs
+                .iter()
+                .map(|(name, typ)| format!("{}: {}", name.kind, typ.kind))
+                .collect::<Vec<_>>();
+            write!(f, " -> ({})", outputs.join(", "))?;
+        } else if let Some(return_type) = self.return_type.as_ref() {
+            write!(f, " -> {}", return_type.kind)?;
+        }
+        // The functions of an interface have no body.
+        if self.body.is_empty() {
+            return Ok(());
         }
+        writeln!(f, ":")?;
         write!(indented(f), "{}", node_line_joined(&self.body))
     }
 }
@@ -699,6 +1046,141 @@ impl fmt::Display for FuncStmt {
                 writeln!(f, "unsafe:")?;
                 writeln!(indented(f), "{}", node_line_joined(body))
             }
+            FuncStmt::Match { value, arms } => {
+                writeln!(f, "match {}:", value.kind)?;
+                write!(indented(f), "{}", node_line_joined(arms))
+            }
+            FuncStmt::Yul(body) => {
+                write!(f, "yul ")?;
+                fmt_yul_block(f, body)
+            }
+        }
+    }
+}
+
+fn fmt_yul_block(f: &mut Formatter<'_>, body: &[Node<YulStmt>]) -> fmt::Result {
+    if body.is_empty() {
+        return write!(f, "{{ }}");
+    }
+    writeln!(f, "{{")?;
+    writeln!(indented(f), "{}", node_line_joined(body))?;
+    write!(f, "}}")
+}
+
+/// Formats a Yul block on a single line, as the `init` and `post` blocks of a
+/// `for` loop usually are.
+fn yul_inline_block(body: &[Node<YulStmt>]) -> String {
+    if body.is_empty() {
+        return "{ }".into();
+    }
+    let stmts = body
+        .iter()
+        .map(|stmt| format!("{}", stmt.kind))
+        .collect::<Vec<_>>();
+    format!("{{ {} }}", stmts.join(" "))
+}
+
+impl fmt::Display for YulStmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            YulStmt::Block(body) => fmt_yul_block(f, body),
+            YulStmt::Let { names, value } => {
+                write!(f, "let {}", node_comma_joined(names))?;
+                if let Some(value) = value {
+                    write!(f, " := {}", value.kind)?;
+                }
+                Ok(())
+            }
+            YulStmt::Assign { names, value } => {
+                write!(f, "{} := {}", node_comma_joined(names), value.kind)
+            }
+            YulStmt::If { test, body } => {
+                write!(f, "if {} ", test.kind)?;
+                fmt_yul_block(f, body)
+            }
+            YulStmt::Switch { value, cases } => {
+                write!(f, "switch {}", value.kind)?;
+                for case in cases {
+                    write!(f, "\n{}", case.kind)?;
+                }
+                Ok(())
+            }
+            YulStmt::For {
+                init,
+                test,
+                post,
+                body,
+            } => {
+                write!(
+                    f,
+                    "for {} {} {} ",
+                    yul_inline_block(init),
+                    test.kind,
+                    yul_inline_block(post)
+                )?;
+                fmt_yul_block(f, body)
+            }
+            YulStmt::Break => write!(f, "break"),
+            YulStmt::Continue => write!(f, "continue"),
+            YulStmt::Expr(expr) => write!(f, "{}", expr.kind),
+        }
+    }
+}
+
+impl fmt::Display for YulCase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(value) = &self.value {
+            write!(f, "case {} ", value.kind)?;
+        } else {
+            write!(f, "default ")?;
+        }
+        fmt_yul_block(f, &self.body)
+    }
+}
+
+impl fmt::Display for YulExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            YulExpr::Name(name) | YulExpr::Literal(name) => write!(f, "{}", name),
+            YulExpr::Call { func, args } => {
+                write!(f, "{}({})", func.kind, node_comma_joined(&args.kind))
+            }
+        }
+    }
+}
+
+impl fmt::Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(guard) = &self.guard {
+            writeln!(f, "{} if {}:", self.pat.kind, guard.kind)?;
+        } else {
+            writeln!(f, "{}:", self.pat.kind)?;
+        }
+        write!(indented(f), "{}", node_line_joined(&self.body))
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(name) => write!(f, "{}", name),
+            Pattern::Literal(expr) => write!(f, "{}", expr.kind),
+            Pattern::Path(path) => write!(f, "{}", path),
+            Pattern::Tuple(elts) if elts.len() == 1 => write!(f, "({},)", elts[0].kind),
+            Pattern::Tuple(elts) => write!(f, "({})", node_comma_joined(elts)),
+            Pattern::Struct { name, fields } => {
+                write!(f, "{}({})", name.kind, node_comma_joined(fields))
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.pat.kind {
+            Pattern::Binding(name) if name == &self.label.kind => write!(f, "{}", name),
+            pat => write!(f, "{}={}", self.label.kind, pat),
         }
     }
 }
@@ -749,6 +1231,27 @@ impl fmt::Display for Expr {
             }
             Expr::Attribute { value, attr } => write!(f, "{}.{}", value.kind, attr.kind),
             Expr::Subscript { value, index } => write!(f, "{}[{}]", value.kind, index.kind),
+            Expr::Slice { value, start, end } => {
+                write!(f, "{}[", value.kind)?;
+                if let Some(start) = start {
+                    write!(f, "{}", start.kind)?;
+                }
+                write!(f, ":")?;
+                if let Some(end) = end {
+                    write!(f, "{}", end.kind)?;
+                }
+                write!(f, "]")
+            }
+            Expr::Try { value } => match value.kind {
+                Expr::Name(_)
+                | Expr::Path(_)
+                | Expr::Attribute { .. }
+                | Expr::Subscript { .. }
+                | Expr::Slice { .. }
+                | Expr::Call { .. }
+                | Expr::Try { .. } => write!(f, "{}?", value.kind),
+                _ => write!(f, "({})?", value.kind),
+            },
             Expr::Call {
                 func,
                 generic_args,
@@ -955,6 +1458,8 @@ fn expr_left_binding_power(expr: &Expr) -> u8 {
         Expr::CompOperation { op, .. } => op.kind.infix_binding_power().0,
         Expr::Attribute { .. } => max_power,
         Expr::Subscript { .. } => max_power,
+        Expr::Slice { .. } => max_power,
+        Expr::Try { .. } => max_power,
         Expr::Call { .. } => max_power,
         Expr::List { .. } => max_power,
         Expr::Tuple { .. } => max_power,
@@ -978,6 +1483,8 @@ fn expr_right_binding_power(expr: &Expr) -> u8 {
         Expr::CompOperation { op, .. } => op.kind.infix_binding_power().1,
         Expr::Attribute { .. } => max_power,
         Expr::Subscript { .. } => max_power,
+        Expr::Slice { .. } => max_power,
+        Expr::Try { .. } => max_power,
         Expr::Call { .. } => max_power,
         Expr::List { .. } => max_power,
         Expr::Tuple { .. } => max_power,
@@ -0,0 +1,640 @@
+//! A concrete syntax tree, which lays the source tokens (including comments
+//! and whitespace) over the structure of the AST. Printed, it's a tree-sitter
+//! style S-expression, e.g.
+//!
+//! ```text
+//! (source_file [0, 0] - [1, 0]
+//!   item: (constant [0, 0] - [0, 17]
+//!     "const"
+//!     (whitespace " ")
+//!     name: (name [0, 6] - [0, 7] "X")
+//!     ...
+//! ```
+
+use crate::ast::{
+    self, CallArg, ContractStmt, Event, Expr, Field, FuncStmt, Function, FunctionArg, GenericArg,
+    GenericParameter, MatchArm, ModuleStmt, Pattern, TypeDesc, UseTree, VarDeclTarget, YulCase,
+    YulExpr, YulStmt,
+};
+use crate::grammar::table;
+use crate::lexer::{Lexer, Token};
+use crate::node::{Node, Span};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::cmp::Reverse;
+use core::fmt;
+use core::iter::{self, Peekable};
+use fe_common::files::SourceFileId;
+use smol_str::SmolStr;
+
+/// A position in the source. Both the row and column count from zero, and the
+/// column is a byte offset into the row.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Point {
+    pub row: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode<'a> {
+    /// The kind of the node, e.g. `function` or `binary_operation`.
+    pub kind: &'static str,
+    /// The field of the parent node that holds this node, e.g. `name`.
+    pub field: Option<&'static str>,
+    pub span: Span,
+    pub start: Point,
+    pub end: Point,
+    /// The child nodes and tokens, in source order.
+    pub children: Vec<SyntaxElement<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement<'a> {
+    Node(SyntaxNode<'a>),
+    Token(Token<'a>),
+}
+
+impl<'a> SyntaxNode<'a> {
+    /// The tokens of the node in source order, including comments and
+    /// whitespace. The tokens of the root node cover the entire source.
+    pub fn tokens(&self) -> Vec<&Token<'a>> {
+        let mut tokens = vec![];
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    /// The source text of the node.
+    pub fn text(&self) -> String {
+        self.tokens().iter().map(|token| token.text).collect()
+    }
+
+    fn collect_tokens<'n>(&'n self, tokens: &mut Vec<&'n Token<'a>>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        if let Some(field) = self.field {
+            write!(f, "{}: ", field)?;
+        }
+        write!(
+            f,
+            "({} [{}, {}] - [{}, {}]",
+            self.kind, self.start.row, self.start.column, self.end.row, self.end.column
+        )?;
+        match self.children.as_slice() {
+            [SyntaxElement::Token(token)] => write!(f, " {:?}", token.text)?,
+            children => {
+                for child in children {
+                    write!(f, "\n{:indent$}", "", indent = (depth + 1) * 2)?;
+                    match child {
+                        SyntaxElement::Node(node) => node.write(f, depth + 1)?,
+                        SyntaxElement::Token(token) => write_token(f, token)?,
+                    }
+                }
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for SyntaxNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Keywords and symbols are written as their quoted text, like anonymous
+/// nodes in tree-sitter. Other tokens are written with their kind.
+fn write_token(f: &mut fmt::Formatter, token: &Token) -> fmt::Result {
+    match table::token_text(token.kind) {
+        Some(_) => write!(f, "{:?}", token.text),
+        None => {
+            let kind = format!("{:?}", token.kind).to_lowercase();
+            write!(f, "({} {:?})", kind, token.text)
+        }
+    }
+}
+
+/// Build the concrete syntax tree of `module`, which was parsed from `src`.
+pub fn module_cst<'a>(file_id: SourceFileId, src: &'a str, module: &ast::Module) -> SyntaxNode<'a> {
+    let root = Skeleton::new("source_file", Span::new(file_id, 0, src.len()))
+        .with_all("item", module.body.iter().map(module_stmt));
+    let line_starts = iter::once(0)
+        .chain(src.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect::<Vec<_>>();
+
+    build(
+        root,
+        &mut Lexer::with_trivia(file_id, src).peekable(),
+        &line_starts,
+    )
+}
+
+/// Places the tokens in the deepest node that contains them.
+fn build<'a>(
+    skeleton: Skeleton,
+    tokens: &mut Peekable<Lexer<'a>>,
+    line_starts: &[usize],
+) -> SyntaxNode<'a> {
+    let Skeleton {
+        kind,
+        field,
+        span,
+        children: mut skeletons,
+    } = skeleton;
+
+    // Some AST nodes overlap their siblings, e.g. the names of named return
+    // values lie within the return type. These become children of the
+    // sibling that contains them.
+    skeletons.sort_by_key(|child| (child.span.start, Reverse(child.span.end)));
+    let mut nested: Vec<Skeleton> = vec![];
+    for child in skeletons {
+        match nested.last_mut() {
+            Some(last)
+                if last.span.start <= child.span.start && child.span.end <= last.span.end =>
+            {
+                last.children.push(child)
+            }
+            _ => nested.push(child),
+        }
+    }
+
+    let mut nested = nested.into_iter().peekable();
+    let mut children = vec![];
+    loop {
+        let next_child = nested.peek().map(|child| child.span.start);
+        if let Some(token) = tokens.next_if(|token| {
+            token.span.end <= span.end && next_child.map_or(true, |start| token.span.start < start)
+        }) {
+            children.push(SyntaxElement::Token(token));
+        } else if let Some(child) = nested.next() {
+            children.push(SyntaxElement::Node(build(child, tokens, line_starts)));
+        } else {
+            break;
+        }
+    }
+
+    SyntaxNode {
+        kind,
+        field,
+        span,
+        start: point(line_starts, span.start),
+        end: point(line_starts, span.end),
+        children,
+    }
+}
+
+fn point(line_starts: &[usize], offset: usize) -> Point {
+    let row = line_starts.partition_point(|start| *start <= offset) - 1;
+    Point {
+        row,
+        column: offset - line_starts[row],
+    }
+}
+
+/// The nodes of the AST with their spans and fields, before the tokens are
+/// placed in them.
+struct Skeleton {
+    kind: &'static str,
+    field: Option<&'static str>,
+    span: Span,
+    children: Vec<Skeleton>,
+}
+
+impl Skeleton {
+    fn new(kind: &'static str, span: Span) -> Self {
+        Skeleton {
+            kind,
+            field: None,
+            span,
+            children: vec![],
+        }
+    }
+
+    fn with(self, field: &'static str, child: Skeleton) -> Self {
+        self.with_all(field, Some(child))
+    }
+
+    fn with_opt(self, field: &'static str, child: Option<Skeleton>) -> Self {
+        self.with_all(field, child)
+    }
+
+    fn with_all(
+        mut self,
+        field: &'static str,
+        children: impl IntoIterator<Item = Skeleton>,
+    ) -> Self {
+        self.children
+            .extend(children.into_iter().map(|child| Skeleton {
+                field: Some(field),
+                ..child
+            }));
+        self
+    }
+}
+
+fn module_stmt(stmt: &ModuleStmt) -> Skeleton {
+    match stmt {
+        ModuleStmt::Pragma(node) => Skeleton::new("pragma", node.span).with(
+            "version",
+            Skeleton::new("version_requirement", node.kind.version_requirement.span),
+        ),
+        ModuleStmt::Use(node) => {
+            Skeleton::new("use", node.span).with("tree", use_tree(&node.kind.tree))
+        }
+        ModuleStmt::AbiImport(node) => Skeleton::new("use_abi", node.span)
+            .with("path", Skeleton::new("string", node.kind.path.span))
+            .with("name", name(&node.kind.name)),
+        ModuleStmt::TypeAlias(node) => Skeleton::new("type_alias", node.span)
+            .with("name", name(&node.kind.name))
+            .with("type", type_desc(&node.kind.typ)),
+        ModuleStmt::Contract(node) => {
+            let contract = &node.kind;
+            let kind = if contract.is_interface {
+                "interface"
+            } else {
+                "contract"
+            };
+            Skeleton::new(kind, node.span)
+                .with("name", name(&contract.name))
+                .with_opt("layout", contract.layout.as_ref().map(name))
+                .with_all("field", contract.fields.iter().map(field))
+                .with_all(
+                    "body",
+                    contract.body.iter().map(|stmt| match stmt {
+                        ContractStmt::Event(node) => event(node),
+                        ContractStmt::Function(node) => function(node),
+                    }),
+                )
+        }
+        ModuleStmt::Constant(node) => Skeleton::new("constant", node.span)
+            .with("name", name(&node.kind.name))
+            .with("type", type_desc(&node.kind.typ))
+            .with("value", expr(&node.kind.value)),
+        ModuleStmt::Struct(node) if node.kind.is_error => Skeleton::new("error", node.span)
+            .with("name", name(&node.kind.name))
+            .with_all("field", node.kind.fields.iter().map(field)),
+        ModuleStmt::Struct(node) => Skeleton::new("struct", node.span)
+            .with("name", name(&node.kind.name))
+            .with_all(
+                "generic_parameter",
+                node.kind.generic_params.iter().map(generic_param),
+            )
+            .with_all("field", node.kind.fields.iter().map(field))
+            .with_all("body", node.kind.functions.iter().map(function)),
+        ModuleStmt::Enum(node) => Skeleton::new("enum", node.span)
+            .with("name", name(&node.kind.name))
+            .with_all("variant", node.kind.variants.iter().map(name)),
+        ModuleStmt::Function(node) => function(node),
+        ModuleStmt::Event(node) => event(node),
+        ModuleStmt::Trait(node) => Skeleton::new("trait", node.span)
+            .with("name", name(&node.kind.name))
+            .with_all("body", node.kind.functions.iter().map(function)),
+        ModuleStmt::Impl(node) => Skeleton::new("impl", node.span)
+            .with("trait", name(&node.kind.trait_name))
+            .with("type", name(&node.kind.receiver))
+            .with_all("body", node.kind.functions.iter().map(function)),
+    }
+}
+
+fn name(node: &Node<SmolStr>) -> Skeleton {
+    Skeleton::new("name", node.span)
+}
+
+/// A path, e.g. `std::evm`. Paths don't have a span of their own, so this is
+/// `None` for an empty path.
+fn path(path: &ast::Path) -> Option<Skeleton> {
+    let span = path.segments.first()?.span + path.segments.last()?.span;
+    Some(Skeleton::new("path", span).with_all("segment", path.segments.iter().map(name)))
+}
+
+fn use_tree(node: &Node<UseTree>) -> Skeleton {
+    match &node.kind {
+        UseTree::Glob { prefix } => {
+            Skeleton::new("use_glob", node.span).with_opt("path", path(prefix))
+        }
+        UseTree::Nested { prefix, children } => Skeleton::new("use_list", node.span)
+            .with_opt("path", path(prefix))
+            .with_all("tree", children.iter().map(use_tree)),
+        UseTree::Simple {
+            path: use_path,
+            rename,
+        } => Skeleton::new("use_path", node.span)
+            .with_opt("path", path(use_path))
+            .with_opt("rename", rename.as_ref().map(name)),
+    }
+}
+
+fn field(node: &Node<Field>) -> Skeleton {
+    Skeleton::new("field", node.span)
+        .with("name", name(&node.kind.name))
+        .with("type", type_desc(&node.kind.typ))
+        .with_opt("value", node.kind.value.as_ref().map(expr))
+}
+
+fn event(node: &Node<Event>) -> Skeleton {
+    Skeleton::new("event", node.span)
+        .with("name", name(&node.kind.name))
+        .with_all(
+            "field",
+            node.kind.fields.iter().map(|field| {
+                Skeleton::new("event_field", field.span)
+                    .with("name", name(&field.kind.name))
+                    .with("type", type_desc(&field.kind.typ))
+            }),
+        )
+}
+
+fn function(node: &Node<Function>) -> Skeleton {
+    let function = &node.kind;
+    Skeleton::new("function", node.span)
+        .with("name", name(&function.name))
+        .with_all(
+            "generic_parameter",
+            function.generic_params.iter().map(generic_param),
+        )
+        .with_all("parameter", function.args.iter().map(function_arg))
+        .with_opt("return_type", function.return_type.as_ref().map(type_desc))
+        .with_all("return_name", function.return_names.iter().map(name))
+        .with_all("body", function.body.iter().map(func_stmt))
+}
+
+fn generic_param(node: &Node<GenericParameter>) -> Skeleton {
+    Skeleton::new("generic_parameter", node.span)
+        .with("name", name(&node.kind.name))
+        .with_opt("bound", node.kind.bound.as_ref().map(name))
+}
+
+fn function_arg(node: &Node<FunctionArg>) -> Skeleton {
+    match &node.kind {
+        FunctionArg::Regular(arg) => Skeleton::new("parameter", node.span)
+            .with("name", name(&arg.name))
+            .with("type", type_desc(&arg.typ)),
+        FunctionArg::Zelf => Skeleton::new("self_parameter", node.span),
+    }
+}
+
+fn type_desc(node: &Node<TypeDesc>) -> Skeleton {
+    match &node.kind {
+        TypeDesc::Unit => Skeleton::new("unit_type", node.span),
+        TypeDesc::Base { .. } => Skeleton::new("type_name", node.span),
+        TypeDesc::Path(type_path) => {
+            path(type_path).unwrap_or_else(|| Skeleton::new("path", node.span))
+        }
+        TypeDesc::Tuple { items } => {
+            Skeleton::new("tuple_type", node.span).with_all("item", items.iter().map(type_desc))
+        }
+        TypeDesc::Generic { base, args } => Skeleton::new("generic_type", node.span)
+            .with("base", name(base))
+            .with("arguments", generic_args(args)),
+    }
+}
+
+fn generic_args(node: &Node<Vec<GenericArg>>) -> Skeleton {
+    Skeleton::new("generic_arguments", node.span).with_all(
+        "argument",
+        node.kind.iter().map(|arg| match arg {
+            GenericArg::TypeDesc(typ) => type_desc(typ),
+            GenericArg::Int(int) => Skeleton::new("number", int.span),
+        }),
+    )
+}
+
+fn func_stmt(node: &Node<FuncStmt>) -> Skeleton {
+    match &node.kind {
+        FuncStmt::Return { value } => {
+            Skeleton::new("return_statement", node.span).with_opt("value", value.as_ref().map(expr))
+        }
+        FuncStmt::VarDecl { target, typ, value } => Skeleton::new("let_statement", node.span)
+            .with("target", var_decl_target(target))
+            .with("type", type_desc(typ))
+            .with_opt("value", value.as_ref().map(expr)),
+        FuncStmt::Assign { target, value } => Skeleton::new("assignment", node.span)
+            .with("target", expr(target))
+            .with("value", expr(value)),
+        FuncStmt::AugAssign { target, value, .. } => {
+            Skeleton::new("augmented_assignment", node.span)
+                .with("target", expr(target))
+                .with("value", expr(value))
+        }
+        FuncStmt::For {
+            target,
+            iter,
+            body: stmts,
+        } => Skeleton::new("for_statement", node.span)
+            .with("target", name(target))
+            .with("iter", expr(iter))
+            .with_all("body", stmts.iter().map(func_stmt)),
+        FuncStmt::While { test, body: stmts } => Skeleton::new("while_statement", node.span)
+            .with("condition", expr(test))
+            .with_all("body", stmts.iter().map(func_stmt)),
+        FuncStmt::If {
+            test,
+            body: stmts,
+            or_else,
+        } => Skeleton::new("if_statement", node.span)
+            .with("condition", expr(test))
+            .with_all("body", stmts.iter().map(func_stmt))
+            .with_all("alternative", or_else.iter().map(func_stmt)),
+        FuncStmt::Assert { test, msg } => Skeleton::new("assert_statement", node.span)
+            .with("condition", expr(test))
+            .with_opt("message", msg.as_ref().map(expr)),
+        FuncStmt::Emit { name: event, args } => Skeleton::new("emit_statement", node.span)
+            .with("event", name(event))
+            .with("arguments", call_args(args)),
+        FuncStmt::Expr { value } => {
+            Skeleton::new("expression_statement", node.span).with("value", expr(value))
+        }
+        FuncStmt::Pass => Skeleton::new("pass_statement", node.span),
+        FuncStmt::Break => Skeleton::new("break_statement", node.span),
+        FuncStmt::Continue => Skeleton::new("continue_statement", node.span),
+        FuncStmt::Revert { error } => {
+            Skeleton::new("revert_statement", node.span).with_opt("error", error.as_ref().map(expr))
+        }
+        FuncStmt::Unsafe(stmts) => {
+            Skeleton::new("unsafe_block", node.span).with_all("body", stmts.iter().map(func_stmt))
+        }
+        FuncStmt::Match { value, arms } => Skeleton::new("match_statement", node.span)
+            .with("value", expr(value))
+            .with_all("arm", arms.iter().map(match_arm)),
+        FuncStmt::Yul(stmts) => {
+            Skeleton::new("yul_block", node.span).with_all("body", stmts.iter().map(yul_stmt))
+        }
+    }
+}
+
+fn yul_stmt(node: &Node<YulStmt>) -> Skeleton {
+    match &node.kind {
+        YulStmt::Block(stmts) => {
+            Skeleton::new("yul_block", node.span).with_all("body", stmts.iter().map(yul_stmt))
+        }
+        YulStmt::Let { names, value } => Skeleton::new("yul_let_statement", node.span)
+            .with_all("name", names.iter().map(name))
+            .with_opt("value", value.as_ref().map(yul_expr)),
+        YulStmt::Assign { names, value } => Skeleton::new("yul_assignment", node.span)
+            .with_all("name", names.iter().map(name))
+            .with("value", yul_expr(value)),
+        YulStmt::If { test, body } => Skeleton::new("yul_if_statement", node.span)
+            .with("condition", yul_expr(test))
+            .with_all("body", body.iter().map(yul_stmt)),
+        YulStmt::Switch { value, cases } => Skeleton::new("yul_switch_statement", node.span)
+            .with("value", yul_expr(value))
+            .with_all("case", cases.iter().map(yul_case)),
+        YulStmt::For {
+            init,
+            test,
+            post,
+            body,
+        } => Skeleton::new("yul_for_statement", node.span)
+            .with_all("init", init.iter().map(yul_stmt))
+            .with("condition", yul_expr(test))
+            .with_all("post", post.iter().map(yul_stmt))
+            .with_all("body", body.iter().map(yul_stmt)),
+        YulStmt::Break => Skeleton::new("yul_break_statement", node.span),
+        YulStmt::Continue => Skeleton::new("yul_continue_statement", node.span),
+        YulStmt::Expr(value) => {
+            Skeleton::new("yul_expression_statement", node.span).with("value", yul_expr(value))
+        }
+    }
+}
+
+fn yul_case(node: &Node<YulCase>) -> Skeleton {
+    Skeleton::new("yul_case", node.span)
+        .with_opt(
+            "value",
+            node.kind
+                .value
+                .as_ref()
+                .map(|value| Skeleton::new("yul_literal", value.span)),
+        )
+        .with_all("body", node.kind.body.iter().map(yul_stmt))
+}
+
+fn yul_expr(node: &Node<YulExpr>) -> Skeleton {
+    match &node.kind {
+        YulExpr::Name(_) => Skeleton::new("name", node.span),
+        YulExpr::Literal(_) => Skeleton::new("yul_literal", node.span),
+        YulExpr::Call { func, args } => Skeleton::new("yul_call", node.span)
+            .with("function", name(func))
+            .with_all("argument", args.kind.iter().map(yul_expr)),
+    }
+}
+
+fn var_decl_target(node: &Node<VarDeclTarget>) -> Skeleton {
+    match &node.kind {
+        VarDeclTarget::Name(_) => Skeleton::new("name", node.span),
+        VarDeclTarget::Tuple(items) => Skeleton::new("tuple_pattern", node.span)
+            .with_all("item", items.iter().map(var_decl_target)),
+    }
+}
+
+fn match_arm(node: &Node<MatchArm>) -> Skeleton {
+    Skeleton::new("match_arm", node.span)
+        .with("pattern", pattern(&node.kind.pat))
+        .with_opt("guard", node.kind.guard.as_ref().map(expr))
+        .with_all("body", node.kind.body.iter().map(func_stmt))
+}
+
+fn pattern(node: &Node<Pattern>) -> Skeleton {
+    match &node.kind {
+        Pattern::Wildcard => Skeleton::new("wildcard_pattern", node.span),
+        Pattern::Binding(_) => Skeleton::new("name", node.span),
+        Pattern::Literal(value) => {
+            Skeleton::new("literal_pattern", node.span).with("value", expr(value))
+        }
+        Pattern::Path(pattern_path) => {
+            path(pattern_path).unwrap_or_else(|| Skeleton::new("path", node.span))
+        }
+        Pattern::Tuple(items) => {
+            Skeleton::new("tuple_pattern", node.span).with_all("item", items.iter().map(pattern))
+        }
+        Pattern::Struct {
+            name: struct_name,
+            fields,
+        } => Skeleton::new("struct_pattern", node.span)
+            .with("name", name(struct_name))
+            .with_all(
+                "field",
+                fields.iter().map(|field| {
+                    Skeleton::new("field_pattern", field.span)
+                        .with("label", name(&field.kind.label))
+                        .with("pattern", pattern(&field.kind.pat))
+                }),
+            ),
+    }
+}
+
+fn expr(node: &Node<Expr>) -> Skeleton {
+    match &node.kind {
+        Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => Skeleton::new("conditional_expression", node.span)
+            .with("consequence", expr(if_expr))
+            .with("condition", expr(test))
+            .with("alternative", expr(else_expr)),
+        Expr::BoolOperation { left, right, .. } => Skeleton::new("boolean_operation", node.span)
+            .with("left", expr(left))
+            .with("right", expr(right)),
+        Expr::BinOperation { left, right, .. } => Skeleton::new("binary_operation", node.span)
+            .with("left", expr(left))
+            .with("right", expr(right)),
+        Expr::UnaryOperation { operand, .. } => {
+            Skeleton::new("unary_operation", node.span).with("operand", expr(operand))
+        }
+        Expr::CompOperation { left, right, .. } => Skeleton::new("comparison_operation", node.span)
+            .with("left", expr(left))
+            .with("right", expr(right)),
+        Expr::Attribute { value, attr } => Skeleton::new("attribute", node.span)
+            .with("value", expr(value))
+            .with("attribute", name(attr)),
+        Expr::Subscript { value, index } => Skeleton::new("subscript", node.span)
+            .with("value", expr(value))
+            .with("index", expr(index)),
+        Expr::Slice { value, start, end } => Skeleton::new("slice", node.span)
+            .with("value", expr(value))
+            .with_opt("start", start.as_deref().map(expr))
+            .with_opt("end", end.as_deref().map(expr)),
+        Expr::Try { value } => {
+            Skeleton::new("try_expression", node.span).with("value", expr(value))
+        }
+        Expr::Call {
+            func,
+            generic_args: generics,
+            args,
+        } => Skeleton::new("call", node.span)
+            .with("function", expr(func))
+            .with_opt("generic_arguments", generics.as_ref().map(generic_args))
+            .with("arguments", call_args(args)),
+        Expr::List { elts } => {
+            Skeleton::new("list", node.span).with_all("item", elts.iter().map(expr))
+        }
+        Expr::Tuple { elts } => {
+            Skeleton::new("tuple", node.span).with_all("item", elts.iter().map(expr))
+        }
+        Expr::Bool(_) => Skeleton::new("boolean", node.span),
+        Expr::Name(_) => Skeleton::new("name", node.span),
+        Expr::Path(expr_path) => {
+            path(expr_path).unwrap_or_else(|| Skeleton::new("path", node.span))
+        }
+        Expr::Num(_) => Skeleton::new("number", node.span),
+        Expr::Str(_) => Skeleton::new("string", node.span),
+        Expr::Unit => Skeleton::new("unit", node.span),
+    }
+}
+
+fn call_args(node: &Node<Vec<Node<CallArg>>>) -> Skeleton {
+    Skeleton::new("arguments", node.span).with_all(
+        "argument",
+        node.kind.iter().map(|arg| {
+            Skeleton::new("argument", arg.span)
+                .with_opt("label", arg.kind.label.as_ref().map(name))
+                .with("value", expr(&arg.kind.value))
+        }),
+    )
+}
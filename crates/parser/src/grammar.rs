@@ -2,4 +2,7 @@ pub mod contracts;
 pub mod expressions;
 pub mod functions;
 pub mod module;
+pub mod table;
+pub mod traits;
 pub mod types;
+pub mod yul;
@@ -1,10 +1,12 @@
-use super::functions::parse_fn_def;
+use super::expressions::unescape_string;
+use super::functions::{parse_fn_def, parse_fn_sig};
 use super::types::{parse_event_def, parse_field, parse_opt_qualifier};
 
-use crate::ast::{Contract, ContractStmt};
+use crate::ast::{Contract, ContractStmt, SmolStr};
 use crate::grammar::functions::parse_single_word_stmt;
 use crate::node::{Node, Span};
-use crate::{ParseFailed, ParseResult, Parser, TokenKind};
+use crate::{Label, ParseFailed, ParseResult, Parser, TokenKind};
+use alloc::{format, vec};
 
 // Rule: all "statement" level parse functions consume their trailing
 // newline(s), either directly or via a function they call.
@@ -12,12 +14,24 @@ use crate::{ParseFailed, ParseResult, Parser, TokenKind};
 // trailing newlines to check whether it's followed by an `else` block, and is
 // done for all statements for consistency.
 
+/// The contextual qualifiers of a contract definition, e.g. `upgradeable` in
+/// `upgradeable contract Token:`.
+#[derive(Default)]
+pub struct ContractQualifiers {
+    pub upgradeable: Option<Span>,
+    pub multicall: Option<Span>,
+    pub ownable: Option<Span>,
+    pub pausable: Option<Span>,
+    pub roles: Option<Span>,
+}
+
 /// Parse a contract definition.
 /// # Panics
 /// Panics if the next token isn't `contract`.
 pub fn parse_contract_def(
     par: &mut Parser,
     contract_pub_qual: Option<Span>,
+    qualifiers: ContractQualifiers,
 ) -> ParseResult<Node<Contract>> {
     let contract_tok = par.assert(TokenKind::Contract);
 
@@ -40,13 +54,36 @@ pub fn parse_contract_def(
         |_| vec!["Note: `contract` must be followed by a name, which must start with a letter and contain only letters, numbers, or underscores".into()],
     )?;
 
-    let header_span = contract_tok.span + contract_name.span;
+    // contract Counter for AppStorage:
+    let layout = if par.peek() == Some(TokenKind::For) {
+        par.next()?;
+        let name = par.expect_with_notes(
+            TokenKind::Name,
+            "failed to parse contract definition",
+            |_| vec!["Note: `for` must be followed by the name of the storage layout struct, e.g. `contract Counter for AppStorage:`".into()],
+        )?;
+        Some(Node::new(name.text.into(), name.span))
+    } else {
+        None
+    };
+
+    let header_span = contract_tok.span + contract_name.span + layout.as_ref();
     par.enter_block(header_span, "contract definition")?;
 
     let mut fields = vec![];
     let mut defs = vec![];
 
     loop {
+        let test_qual = if starts_test_qualifier(par) {
+            Some(par.assert(TokenKind::Name).span)
+        } else {
+            None
+        };
+        let role_guard = if starts_role_guard(par) {
+            Some(parse_role_guard(par)?)
+        } else {
+            None
+        };
         let mut pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
         let const_qual = parse_opt_qualifier(par, TokenKind::Const);
         if pub_qual.is_none() && const_qual.is_some() && par.peek() == Some(TokenKind::Pub) {
@@ -56,6 +93,14 @@ pub fn parse_contract_def(
                 "`const pub` should be written `pub const`",
             );
         }
+        if !matches!(par.peek(), Some(TokenKind::Fn | TokenKind::Unsafe)) {
+            if let Some(span) = test_qual {
+                par.error(span, "`test` can only qualify a function");
+            }
+            if let Some(role) = &role_guard {
+                par.error(role.span, "`only_role` can only qualify a function");
+            }
+        }
 
         match par.peek() {
             Some(TokenKind::Name) => {
@@ -66,13 +111,16 @@ pub fn parse_contract_def(
                 fields.push(field);
             }
             Some(TokenKind::Fn | TokenKind::Unsafe) => {
-                if let Some(span) = const_qual {
-                    par.error(
-                        span,
-                        "`const` qualifier can't be used with function definitions",
-                    );
+                let mut function = parse_fn_def(par, pub_qual, const_qual)?;
+                if let Some(role) = role_guard {
+                    function.span += role.span;
+                    function.kind.only_role = Some(role);
+                }
+                if let Some(span) = test_qual {
+                    function.span += span;
+                    function.kind.test = Some(span);
                 }
-                defs.push(ContractStmt::Function(parse_fn_def(par, pub_qual)?));
+                defs.push(ContractStmt::Function(function));
             }
             Some(TokenKind::Event) => {
                 if let Some(span) = pub_qual {
@@ -109,13 +157,190 @@ pub fn parse_contract_def(
         };
     }
 
-    let span = header_span + contract_pub_qual + fields.last() + defs.last();
+    let ContractQualifiers {
+        upgradeable: upgradeable_qual,
+        multicall: multicall_qual,
+        ownable: ownable_qual,
+        pausable: pausable_qual,
+        roles: roles_qual,
+    } = qualifiers;
+    let span = header_span
+        + contract_pub_qual
+        + upgradeable_qual
+        + multicall_qual
+        + ownable_qual
+        + pausable_qual
+        + roles_qual
+        + fields.last()
+        + defs.last();
     Ok(Node::new(
         Contract {
             name: Node::new(contract_name.text.into(), contract_name.span),
             fields,
             body: defs,
             pub_qual: contract_pub_qual,
+            layout,
+            upgradeable_qual,
+            multicall_qual,
+            ownable_qual,
+            pausable_qual,
+            roles_qual,
+            is_interface: false,
+        },
+        span,
+    ))
+}
+
+/// Returns `true` if the next token is the `test` qualifier of a function,
+/// e.g. in `test pub fn increment():`, rather than a field named `test`.
+fn starts_test_qualifier(par: &mut Parser) -> bool {
+    if par.peek() != Some(TokenKind::Name) || par.peeked_text() != "test" {
+        return false;
+    }
+    let mut bt_par = par.as_bt_parser();
+    if bt_par.next().is_err() {
+        return false;
+    }
+    match bt_par.peek() {
+        Some(TokenKind::Pub | TokenKind::Const | TokenKind::Unsafe | TokenKind::Fn) => true,
+        Some(TokenKind::Name) => bt_par.peeked_text() == "only_role",
+        _ => false,
+    }
+}
+
+/// Returns `true` if the next tokens are `only_role(`, which starts the
+/// `only_role` qualifier of a function rather than a field named `only_role`.
+fn starts_role_guard(par: &mut Parser) -> bool {
+    if par.peek() != Some(TokenKind::Name) || par.peeked_text() != "only_role" {
+        return false;
+    }
+    let mut bt_par = par.as_bt_parser();
+    bt_par.next().is_ok() && bt_par.peek() == Some(TokenKind::ParenOpen)
+}
+
+/// Parse the `only_role` qualifier of a function, e.g. `only_role("MINTER")`,
+/// into the name of the role.
+/// # Panics
+/// Panics if the next tokens aren't `only_role(`.
+fn parse_role_guard(par: &mut Parser) -> ParseResult<Node<SmolStr>> {
+    let only_role_tok = par.assert(TokenKind::Name);
+    par.assert(TokenKind::ParenOpen);
+    let role_tok = par.expect_with_notes(
+        TokenKind::Text,
+        "failed to parse `only_role` qualifier",
+        |_| vec!["Example: `only_role(\"MINTER\") pub fn mint(self):`".into()],
+    )?;
+    let close_tok = par.expect(
+        TokenKind::ParenClose,
+        "failed to parse `only_role` qualifier",
+    )?;
+    let role = match unescape_string(role_tok.text) {
+        Some(role) => role,
+        None => {
+            par.error(role_tok.span, "String contains an invalid escape sequence");
+            return Err(ParseFailed);
+        }
+    };
+    Ok(Node::new(role.into(), only_role_tok.span + close_tok.span))
+}
+
+/// Parse an interface definition, which declares the public functions of a
+/// contract that's deployed elsewhere, so that it can be called.
+/// # Panics
+/// Panics if the next token isn't the contextual keyword `interface`.
+pub fn parse_interface_def(
+    par: &mut Parser,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<Contract>> {
+    let interface_tok = par.assert(TokenKind::Name);
+    assert_eq!(interface_tok.text, "interface");
+
+    // interface Token:
+    //   pub fn balance_of(self, owner: address) -> u256
+    //   pub fn transfer(self, to: address, value: u256) -> bool
+    //
+
+    let name = par.expect_with_notes(
+        TokenKind::Name,
+        "failed to parse interface definition",
+        |_| vec!["Note: `interface` must be followed by a name, which must start with a letter and contain only letters, numbers, or underscores".into()],
+    )?;
+    let header_span = interface_tok.span + name.span;
+    par.enter_block(header_span, "interface definition")?;
+
+    let mut defs = vec![];
+    loop {
+        match par.peek() {
+            Some(TokenKind::Pub | TokenKind::Fn) => {
+                let fn_pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
+                let function = parse_fn_sig(par, fn_pub_qual, None)?;
+                if fn_pub_qual.is_none() {
+                    par.fancy_error(
+                        "interface functions must be public",
+                        vec![Label::primary(
+                            function.kind.name.span,
+                            "this function isn't `pub`",
+                        )],
+                        vec![format!(
+                            "Hint: add `pub`, e.g. `pub fn {}`",
+                            function.kind.name.kind
+                        )],
+                    );
+                }
+                if function.kind.name.kind == "__init__" {
+                    par.error(
+                        function.kind.name.span,
+                        "an interface can't have an `__init__` function",
+                    );
+                }
+                if par.peek() == Some(TokenKind::Colon) {
+                    let tok = par.next()?;
+                    par.fancy_error(
+                        "interface functions can't have a body",
+                        vec![Label::primary(tok.span, "unexpected `:`")],
+                        vec![
+                            "Note: an interface declares the signatures of functions that are run by a deployed contract".into(),
+                        ],
+                    );
+                    return Err(ParseFailed);
+                }
+                par.expect_newline("interface function")?;
+                defs.push(ContractStmt::Function(function));
+            }
+            Some(TokenKind::Pass) => {
+                parse_single_word_stmt(par)?;
+            }
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            None => break,
+            Some(_) => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse interface definition body",
+                    vec!["Note: an interface may only declare public functions, e.g. `pub fn total_supply(self) -> u256`".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+
+    let span = header_span + pub_qual + defs.last();
+    Ok(Node::new(
+        Contract {
+            name: Node::new(name.text.into(), name.span),
+            fields: vec![],
+            body: defs,
+            pub_qual,
+            layout: None,
+            upgradeable_qual: None,
+            multicall_qual: None,
+            ownable_qual: None,
+            pausable_qual: None,
+            roles_qual: None,
+            is_interface: true,
         },
         span,
     ))
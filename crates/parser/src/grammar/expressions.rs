@@ -1,7 +1,11 @@
 use crate::ast::{self, CallArg, Expr, Path};
 use crate::node::Node;
 use crate::{Label, ParseFailed, ParseResult, Parser, Token, TokenKind};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::{format, vec, vec::Vec};
 
+use super::table;
 use super::types::parse_generic_args;
 
 use if_chain::if_chain;
@@ -18,6 +22,10 @@ pub fn parse_expr(par: &mut Parser) -> ParseResult<Node<Expr>> {
 /// Parse an expression, stopping if/when we reach an operator that binds less
 /// tightly than given binding power.
 pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<Expr>> {
+    par.nested(|par| parse_expr_with_min_bp_unchecked(par, min_bp))
+}
+
+fn parse_expr_with_min_bp_unchecked(par: &mut Parser, min_bp: u8) -> ParseResult<Node<Expr>> {
     let mut expr_head = parse_expr_head(par)?;
 
     while let Some(op) = par.peek() {
@@ -41,19 +49,44 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
                 }
                 TokenKind::BracketOpen => {
                     par.next()?;
-                    let index = parse_expr(par)?;
-                    let rbracket = par.expect(
-                        TokenKind::BracketClose,
-                        "failed to parse subscript expression",
-                    )?;
-                    let span = expr_head.span + rbracket.span;
-                    Node::new(
-                        Expr::Subscript {
-                            value: Box::new(expr_head),
-                            index: Box::new(index),
-                        },
-                        span,
-                    )
+                    let start = if par.peek() == Some(TokenKind::Colon) {
+                        None
+                    } else {
+                        Some(parse_expr(par)?)
+                    };
+
+                    if par.peek() == Some(TokenKind::Colon) {
+                        par.next()?;
+                        let end = if par.peek() == Some(TokenKind::BracketClose) {
+                            None
+                        } else {
+                            Some(parse_expr(par)?)
+                        };
+                        let rbracket = par
+                            .expect(TokenKind::BracketClose, "failed to parse slice expression")?;
+                        let span = expr_head.span + rbracket.span;
+                        Node::new(
+                            Expr::Slice {
+                                value: Box::new(expr_head),
+                                start: start.map(Box::new),
+                                end: end.map(Box::new),
+                            },
+                            span,
+                        )
+                    } else {
+                        let rbracket = par.expect(
+                            TokenKind::BracketClose,
+                            "failed to parse subscript expression",
+                        )?;
+                        let span = expr_head.span + rbracket.span;
+                        Node::new(
+                            Expr::Subscript {
+                                value: Box::new(expr_head),
+                                index: Box::new(start.expect("subscript without index")),
+                            },
+                            span,
+                        )
+                    }
                 }
                 TokenKind::If => {
                     par.next()?;
@@ -73,6 +106,16 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
                         span,
                     )
                 }
+                TokenKind::Question => {
+                    let question = par.next()?;
+                    let span = expr_head.span + question.span;
+                    Node::new(
+                        Expr::Try {
+                            value: Box::new(expr_head),
+                        },
+                        span,
+                    )
+                }
                 _ => unreachable!(), // patterns above must match those in `postfix_binding_power`
             };
             continue;
@@ -185,11 +228,11 @@ fn parse_expr_head(par: &mut Parser) -> ParseResult<Node<Expr>> {
     use TokenKind::*;
 
     match par.peek_or_err()? {
-        Name | SelfValue | Int | Hex | Octal | Binary | Text | True | False => {
+        kind if table::ATOMS.contains(&kind) => {
             let tok = par.next()?;
             Ok(atom(par, &tok))
         }
-        Plus | Minus | Not | Tilde => {
+        kind if table::prefix_operator(kind).is_some() => {
             let op = par.next()?;
             let operand = parse_expr_with_min_bp(par, prefix_binding_power(op.kind))?;
             unary_op(par, &op, operand)
@@ -210,57 +253,23 @@ fn parse_expr_head(par: &mut Parser) -> ParseResult<Node<Expr>> {
 
 /// Specifies how tightly a prefix unary operator binds to its operand.
 fn prefix_binding_power(op: TokenKind) -> u8 {
-    use TokenKind::*;
-    match op {
-        Not => 65,
-        Plus | Minus | Tilde => 135,
-        _ => panic!("Unexpected unary op token: {:?}", op),
+    match table::prefix_operator(op) {
+        Some(group) => group.binding_power,
+        None => panic!("Unexpected unary op token: {:?}", op),
     }
 }
 
 /// Specifies how tightly does an infix operator bind to its left and right
-/// operands. See https://docs.python.org/3/reference/expressions.html#operator-precedence
+/// operands.
 fn infix_binding_power(op: TokenKind) -> Option<(u8, u8)> {
-    use TokenKind::*;
-
-    let bp = match op {
-        // assignment expr `:=`?
-        // lambda?
-        // Comma => (40, 41),
-        Or => (50, 51),
-        And => (60, 61),
-        // prefix Not => 65
-
-        // all comparisons are the same
-        Lt | LtEq | Gt | GtEq | NotEq | EqEq => (70, 71),
-
-        Pipe => (80, 81),
-        Hat => (90, 91),
-        Amper => (100, 101),
-        LtLt | GtGt => (110, 111),
-        Plus | Minus => (120, 121),
-        Star | Slash | Percent => (130, 131),
-        // Prefix Plus | Minus | Tilde => 135
-        StarStar => (141, 140),
-        Dot => (150, 151),
-        ColonColon => (160, 161),
-        _ => return None,
-    };
-    Some(bp)
+    table::infix_operator(op)?.infix_binding_power()
 }
 
 /// Specifies how tightly a postfix operator binds to its operand.
-/// We don't have any "real" postfix operators (like `?` in rust),
-/// but we treat `[`, `(`, and ternary `if` as though they're postfix
-/// operators.
+/// The only "real" postfix operator is `?`, but we treat `[`, `(`, and
+/// ternary `if` as though they're postfix operators.
 fn postfix_binding_power(op: TokenKind) -> Option<u8> {
-    use TokenKind::*;
-    match op {
-        If => Some(35), // ternary
-        BracketOpen => Some(150),
-        ParenOpen => Some(150),
-        _ => None,
-    }
+    table::postfix_operator(op).map(|group| group.binding_power)
 }
 
 /// Parse a square-bracket list expression, eg. `[1, 2, x]`
@@ -371,9 +380,57 @@ fn atom(par: &mut Parser, tok: &Token) -> Node<Expr> {
     Node::new(expr, tok.span)
 }
 
-fn unescape_string(quoted_string: &str) -> Option<String> {
+/// Removes the quotes around a string literal and replaces its escape
+/// sequences. Returns `None` if the string contains an invalid escape sequence.
+pub(crate) fn unescape_string(quoted_string: &str) -> Option<String> {
     let inner = &quoted_string[1..quoted_string.len() - 1];
-    unescape::unescape(inner)
+    let mut chars = inner.chars();
+    let mut unescaped = String::with_capacity(inner.len());
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let escaped = match chars.next()? {
+            'b' => '\u{0008}',
+            'f' => '\u{000C}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '\'' => '\'',
+            '"' => '"',
+            '\\' => '\\',
+            'u' => unescape_code_point(&mut chars, 4, 16)?,
+            'x' => unescape_code_point(&mut chars, 2, 16)?,
+            digit @ '0'..='7' => {
+                // up to three octal digits
+                let mut code = digit.to_digit(8)?;
+                for _ in 0..2 {
+                    match chars.clone().next().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                char::from_u32(code)?
+            }
+            _ => return None,
+        };
+        unescaped.push(escaped);
+    }
+    Some(unescaped)
+}
+
+/// Parses the code point of a `\u` or `\x` escape sequence, which has exactly
+/// `len` digits.
+fn unescape_code_point(chars: &mut core::str::Chars, len: usize, radix: u32) -> Option<char> {
+    let rest = chars.as_str();
+    let digits = rest.get(..len)?;
+    *chars = rest[len..].chars();
+    char::from_u32(u32::from_str_radix(digits, radix).ok()?)
 }
 
 /// Create an expr from the given infix operator and operands.
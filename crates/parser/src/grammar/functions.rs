@@ -1,16 +1,54 @@
-use super::expressions::{parse_call_args, parse_expr};
-use super::types::parse_type_desc;
+use super::expressions::{parse_call_args, parse_expr, parse_expr_with_min_bp};
+use super::types::{parse_generic_params, parse_path_tail, parse_type_desc};
+use super::yul::parse_yul_block;
 
 use crate::ast::{
-    BinOperator, Expr, FuncStmt, Function, FunctionArg, RegularFunctionArg, VarDeclTarget,
+    BinOperator, Expr, FieldPattern, FuncStmt, Function, FunctionArg, MatchArm, Pattern,
+    RegularFunctionArg, TypeDesc, UnaryOperator, VarDeclTarget,
 };
 use crate::lexer::TokenKind;
 use crate::node::{Node, Span};
 use crate::{Label, ParseFailed, ParseResult, Parser};
+use alloc::{format, vec, vec::Vec};
+use smol_str::SmolStr;
+use vec1::Vec1;
+
+/// Parse a function definition. The optional `pub` and `const` qualifiers must
+/// be parsed by the caller, and passed in. Next token must be `unsafe` or `fn`.
+pub fn parse_fn_def(
+    par: &mut Parser,
+    pub_qual: Option<Span>,
+    const_qual: Option<Span>,
+) -> ParseResult<Node<Function>> {
+    let sig = parse_fn_sig(par, pub_qual, const_qual)?;
+    parse_fn_body(par, sig)
+}
+
+/// Parse the body of a function whose signature was parsed by
+/// [`parse_fn_sig`]. Next token must be the `:` that starts the body.
+pub fn parse_fn_body(par: &mut Parser, sig: Node<Function>) -> ParseResult<Node<Function>> {
+    let Node {
+        kind: mut function,
+        mut span,
+    } = sig;
+
+    // TODO: allow multi-line return type? `fn f()\n ->\n u8`
+    // TODO: allow single-line fn defs?
+    par.enter_block(span, "function definition")?;
+    function.body = parse_block_stmts(par)?;
+    span += function.body.last();
+    Ok(Node::new(function, span))
+}
 
-/// Parse a function definition. The optional `pub` qualifier must be parsed by
-/// the caller, and passed in. Next token must be `unsafe` or `fn`.
-pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult<Node<Function>> {
+/// Parse the signature of a function, up to and excluding the `:` of its body,
+/// e.g. `pub fn transfer(self, to: address, value: u256) -> bool`. The
+/// function's body is empty. The optional `pub` and `const` qualifiers must be
+/// parsed by the caller, and passed in. Next token must be `unsafe` or `fn`.
+pub fn parse_fn_sig(
+    par: &mut Parser,
+    mut pub_qual: Option<Span>,
+    const_qual: Option<Span>,
+) -> ParseResult<Node<Function>> {
     let unsafe_qual = par.optional(TokenKind::Unsafe).map(|tok| tok.span);
     if let Some(pub_) = par.optional(TokenKind::Pub) {
         let unsafe_span =
@@ -28,7 +66,15 @@ pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult
     }
     let fn_tok = par.expect(TokenKind::Fn, "failed to parse function definition")?;
     let name = par.expect(TokenKind::Name, "failed to parse function definition")?;
-    let mut span = fn_tok.span + unsafe_qual + pub_qual + name.span;
+    let mut span = fn_tok.span + unsafe_qual + const_qual + pub_qual + name.span;
+
+    let generic_params = if par.peek() == Some(TokenKind::Lt) {
+        let node = parse_generic_params(par)?;
+        span += node.span;
+        node.kind
+    } else {
+        vec![]
+    };
 
     let args = match par.peek_or_err()? {
         TokenKind::ParenOpen => {
@@ -68,32 +114,99 @@ pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult
             return Err(ParseFailed);
         }
     };
-    let return_type = if par.peek() == Some(TokenKind::Arrow) {
+    let (return_type, return_names) = if par.peek() == Some(TokenKind::Arrow) {
         par.next()?;
-        Some(parse_type_desc(par)?)
+        if starts_named_outputs(par) {
+            let (typ, names) = parse_named_outputs(par)?;
+            (Some(typ), names)
+        } else {
+            (Some(parse_type_desc(par)?), vec![])
+        }
     } else {
-        None
+        (None, vec![])
     };
     span += return_type.as_ref();
 
-    // TODO: allow multi-line return type? `fn f()\n ->\n u8`
-    // TODO: allow single-line fn defs?
-    par.enter_block(span, "function definition")?;
-    let body = parse_block_stmts(par)?;
-    span += body.last();
     Ok(Node::new(
         Function {
             pub_: pub_qual,
+            const_: const_qual,
             unsafe_: unsafe_qual,
+            test: None,
+            only_role: None,
             name: name.into(),
+            generic_params,
             args,
             return_type,
-            body,
+            return_names,
+            body: vec![],
         },
         span,
     ))
 }
 
+/// Returns `true` if the next tokens are the start of a list of named return
+/// values, e.g. `(amount: u256, ok: bool)`, rather than a tuple type.
+fn starts_named_outputs(par: &mut Parser) -> bool {
+    if par.peek() != Some(TokenKind::ParenOpen) {
+        return false;
+    }
+    let mut bt_par = par.as_bt_parser();
+    bt_par.next().is_ok()
+        && bt_par.peek() == Some(TokenKind::Name)
+        && bt_par.next().is_ok()
+        && bt_par.peek() == Some(TokenKind::Colon)
+}
+
+/// Parse a list of named return values, e.g. `(amount: u256, ok: bool)`.
+/// Returns the return type, which is a tuple if there is more than one value,
+/// and the names of the values.
+fn parse_named_outputs(par: &mut Parser) -> ParseResult<(Node<TypeDesc>, Vec<Node<SmolStr>>)> {
+    let mut span = par.assert(TokenKind::ParenOpen).span;
+    let mut names = vec![];
+    let mut types = vec![];
+    loop {
+        let name = par.expect(TokenKind::Name, "failed to parse named return values")?;
+        par.expect_with_notes(
+            TokenKind::Colon,
+            "failed to parse named return values",
+            |_| {
+                vec![
+                    "Note: each named return value must have a name and a type".into(),
+                    format!("Example: `{}: u256`", name.text),
+                ]
+            },
+        )?;
+        names.push(Node::new(name.text.into(), name.span));
+        types.push(parse_type_desc(par)?);
+
+        if par.peek() == Some(TokenKind::Comma) {
+            par.next()?;
+            if par.peek() == Some(TokenKind::ParenClose) {
+                span += par.next()?.span;
+                break;
+            }
+        } else {
+            span += par
+                .expect(TokenKind::ParenClose, "failed to parse named return values")?
+                .span;
+            break;
+        }
+    }
+
+    let typ = if types.len() == 1 {
+        types.pop().expect("missing return type")
+    } else {
+        Node::new(
+            TypeDesc::Tuple {
+                items: Vec1::try_from_vec(types).expect("couldn't convert vec to vec1"),
+            },
+            span,
+        )
+    };
+    Ok((typ, names))
+}
+
 fn parse_fn_param_list(par: &mut Parser) -> ParseResult<Node<Vec<Node<FunctionArg>>>> {
     let mut span = par.assert(TokenKind::ParenOpen).span;
     let mut params = vec![];
@@ -215,9 +328,10 @@ pub fn parse_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
     use TokenKind::*;
 
     // rule: stmt parsing fns eat the trailing separator (newline, semi, eof)
-    match par.peek_or_err()? {
+    par.nested(|par| match par.peek_or_err()? {
         For => parse_for_stmt(par),
         If => parse_if_stmt(par),
+        Match => parse_match_stmt(par),
         While => parse_while_stmt(par),
         Return => parse_return_stmt(par),
         Assert => parse_assert_stmt(par),
@@ -226,8 +340,9 @@ pub fn parse_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
         Emit => parse_emit_statement(par),
         Let => parse_var_decl(par),
         Unsafe => parse_unsafe_block(par),
+        Name if par.peeked_text() == "yul" && is_yul_block_start(par) => parse_inline_yul(par),
         _ => parse_expr_stmt(par),
-    }
+    })
 }
 
 fn parse_var_decl(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
@@ -509,3 +624,211 @@ pub fn parse_unsafe_block(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
 
     Ok(Node::new(FuncStmt::Unsafe(body), span))
 }
+
+/// `yul` is only a keyword when it's followed by a `{`, so it can still be
+/// used as a name.
+fn is_yul_block_start(par: &mut Parser) -> bool {
+    let mut bt_par = par.as_bt_parser();
+    bt_par.next().is_ok() && bt_par.peek() == Some(TokenKind::BraceOpen)
+}
+
+/// Parse an inline Yul block, e.g. `yul { x := add(x, 1) }`.
+///
+/// # Panics
+/// Panics if the next token isn't `yul`.
+pub fn parse_inline_yul(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let yul_tok = par.assert(TokenKind::Name);
+    assert_eq!(yul_tok.text, "yul");
+    let body = parse_yul_block(par)?;
+    par.expect_newline("`yul` block")?;
+    Ok(Node::new(
+        FuncStmt::Yul(body.kind),
+        yul_tok.span + body.span,
+    ))
+}
+
+/// Parse a `match` statement.
+///
+/// # Panics
+/// Panics if the next token isn't `match`.
+pub fn parse_match_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let match_tok = par.assert(TokenKind::Match);
+    let value = parse_expr(par)?;
+    par.enter_block(match_tok.span + value.span, "`match` statement")?;
+
+    let mut arms = vec![];
+    loop {
+        match par.peek() {
+            None => break,
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            Some(_) => arms.push(parse_match_arm(par)?),
+        }
+    }
+    let span = match_tok.span + value.span + arms.last();
+    Ok(Node::new(FuncStmt::Match { value, arms }, span))
+}
+
+fn parse_match_arm(par: &mut Parser) -> ParseResult<Node<MatchArm>> {
+    let pat = parse_pattern(par)?;
+    let guard = if par.peek() == Some(TokenKind::If) {
+        par.next()?;
+        Some(parse_expr(par)?)
+    } else {
+        None
+    };
+    par.enter_block(pat.span + guard.as_ref(), "`match` arm")?;
+    let body = parse_block_stmts(par)?;
+    let span = pat.span + guard.as_ref() + body.last();
+    Ok(Node::new(MatchArm { pat, guard, body }, span))
+}
+
+/// Parse a `match` arm pattern, eg. `_`, `x`, `(1, y)`, `Point(x=0, y)` or
+/// `State::Active`.
+pub fn parse_pattern(par: &mut Parser) -> ParseResult<Node<Pattern>> {
+    use TokenKind::*;
+
+    match par.peek_or_err()? {
+        Name => {
+            let name = par.next()?;
+            if par.peek() == Some(ParenOpen) {
+                parse_struct_pattern(par, name.into())
+            } else if par.peek() == Some(ColonColon) {
+                let (path, span, trailing_delim) = parse_path_tail(par, name.into());
+                if let Some(colons) = trailing_delim {
+                    let next = par.next()?;
+                    par.fancy_error(
+                        "failed to parse `match` pattern",
+                        vec![
+                            Label::secondary(colons.span, "path delimiter"),
+                            Label::primary(next.span, "expected a name"),
+                        ],
+                        vec![],
+                    );
+                    return Err(ParseFailed);
+                }
+                Ok(Node::new(Pattern::Path(path), span))
+            } else if name.text == "_" {
+                Ok(Node::new(Pattern::Wildcard, name.span))
+            } else {
+                Ok(Node::new(Pattern::Binding(name.text.into()), name.span))
+            }
+        }
+        ParenOpen => {
+            let lparen = par.next()?;
+            let mut elts = vec![];
+            let mut trailing_comma = false;
+            loop {
+                if par.peek_or_err()? == ParenClose {
+                    break;
+                }
+                elts.push(parse_pattern(par)?);
+                trailing_comma = par.peek() == Some(Comma);
+                if trailing_comma {
+                    par.next()?;
+                } else {
+                    break;
+                }
+            }
+            let rparen = par.expect(ParenClose, "failed to parse tuple pattern")?;
+            let span = lparen.span + rparen.span;
+            match elts.len() {
+                0 => {
+                    par.error(span, "empty tuple patterns are not supported");
+                    Err(ParseFailed)
+                }
+                // pattern wrapped in parens
+                1 if !trailing_comma => Ok(Node::new(elts.remove(0).kind, span)),
+                _ => Ok(Node::new(Pattern::Tuple(elts), span)),
+            }
+        }
+        Int | Hex | Octal | Binary | Text | True | False | Minus => {
+            // Stop before a ternary `if`, which is the arm guard.
+            let expr = parse_expr_with_min_bp(par, 36)?;
+            if !is_literal(&expr.kind) {
+                par.fancy_error(
+                    "failed to parse `match` pattern",
+                    vec![Label::primary(expr.span, "expected a literal")],
+                    vec![
+                        "Note: patterns may only contain literals, names, enum variants, tuples and structs"
+                            .into(),
+                    ],
+                );
+                return Err(ParseFailed);
+            }
+            let span = expr.span;
+            Ok(Node::new(Pattern::Literal(expr), span))
+        }
+        _ => {
+            let tok = par.next()?;
+            par.unexpected_token_error(
+                tok.span,
+                "failed to parse `match` pattern",
+                vec!["Example: `(x, 0)`, `Point(x=0, y)`, `State::Active` or `_`".into()],
+            );
+            Err(ParseFailed)
+        }
+    }
+}
+
+fn parse_struct_pattern(par: &mut Parser, name: Node<SmolStr>) -> ParseResult<Node<Pattern>> {
+    use TokenKind::*;
+
+    par.assert(ParenOpen);
+    let mut fields = vec![];
+    loop {
+        if par.peek_or_err()? == ParenClose {
+            break;
+        }
+        let pat = parse_pattern(par)?;
+        let field = if par.peek() == Some(Eq) {
+            par.next()?;
+            let label = match pat.kind {
+                Pattern::Binding(label) => Node::new(label, pat.span),
+                _ => {
+                    par.error(pat.span, "struct pattern field labels must be names");
+                    return Err(ParseFailed);
+                }
+            };
+            let pat = parse_pattern(par)?;
+            let span = label.span + pat.span;
+            Node::new(FieldPattern { label, pat }, span)
+        } else if let Pattern::Binding(label) = &pat.kind {
+            // `Point(x)` is shorthand for `Point(x=x)`
+            let label = Node::new(label.clone(), pat.span);
+            let span = pat.span;
+            Node::new(FieldPattern { label, pat }, span)
+        } else {
+            par.fancy_error(
+                "failed to parse struct pattern",
+                vec![Label::primary(
+                    pat.span,
+                    "this field pattern must be labeled",
+                )],
+                vec![format!("Example: `{}(x={})`", name.kind, pat.kind)],
+            );
+            return Err(ParseFailed);
+        };
+        fields.push(field);
+        if par.peek() == Some(Comma) {
+            par.next()?;
+        } else {
+            break;
+        }
+    }
+    let rparen = par.expect(ParenClose, "failed to parse struct pattern")?;
+    let span = name.span + rparen.span;
+    Ok(Node::new(Pattern::Struct { name, fields }, span))
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Num(_) | Expr::Bool(_) | Expr::Str(_) => true,
+        Expr::UnaryOperation { op, operand } => {
+            op.kind == UnaryOperator::USub && matches!(operand.kind, Expr::Num(_))
+        }
+        _ => false,
+    }
+}
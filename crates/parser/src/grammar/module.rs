@@ -1,12 +1,17 @@
-use super::contracts::parse_contract_def;
-use super::expressions::parse_expr;
+use super::contracts::{parse_contract_def, parse_interface_def, ContractQualifiers};
+use super::expressions::{parse_expr, unescape_string};
 use super::functions::parse_fn_def;
+use super::traits::{parse_impl_def, parse_trait_def};
 use super::types::{
-    parse_event_def, parse_path_tail, parse_struct_def, parse_type_alias, parse_type_desc,
+    parse_enum_def, parse_error_def, parse_event_def, parse_path_tail, parse_struct_def,
+    parse_type_alias, parse_type_desc,
 };
-use crate::ast::{ConstantDecl, Module, ModuleStmt, Pragma, Use, UseTree};
+use crate::ast::{AbiImport, ConstantDecl, Contract, Module, ModuleStmt, Pragma, Use, UseTree};
 use crate::node::{Node, Span};
 use crate::{Label, ParseFailed, ParseResult, Parser, TokenKind};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::{format, vec};
 
 use semver::VersionReq;
 
@@ -35,11 +40,38 @@ pub fn parse_module(par: &mut Parser) -> ParseResult<Node<Module>> {
 pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
     let stmt = match par.peek_or_err()? {
         TokenKind::Pragma => ModuleStmt::Pragma(parse_pragma(par)?),
+        TokenKind::Use if is_abi_import(par) => ModuleStmt::AbiImport(parse_abi_import(par)?),
         TokenKind::Use => ModuleStmt::Use(parse_use(par)?),
-        TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(par, None)?),
+        TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(
+            par,
+            None,
+            ContractQualifiers::default(),
+        )?),
+        TokenKind::Name if is_contract_qualifier(par.peeked_text()) => {
+            ModuleStmt::Contract(parse_qualified_contract_def(par, None)?)
+        }
+        TokenKind::Name if par.peeked_text() == "interface" => {
+            ModuleStmt::Contract(parse_interface_def(par, None)?)
+        }
         TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, None)?),
+        TokenKind::Name if par.peeked_text() == "error" => {
+            ModuleStmt::Struct(parse_error_def(par, None)?)
+        }
+        TokenKind::Name if par.peeked_text() == "trait" => {
+            ModuleStmt::Trait(parse_trait_def(par, None)?)
+        }
+        TokenKind::Name if par.peeked_text() == "impl" => ModuleStmt::Impl(parse_impl_def(par)?),
+        TokenKind::Enum => ModuleStmt::Enum(parse_enum_def(par, None)?),
         TokenKind::Type => ModuleStmt::TypeAlias(parse_type_alias(par, None)?),
-        TokenKind::Const => ModuleStmt::Constant(Box::new(parse_constant(par)?)),
+        TokenKind::Const => {
+            let const_tok = par.next()?;
+            match par.peek_or_err()? {
+                TokenKind::Fn | TokenKind::Unsafe => {
+                    ModuleStmt::Function(parse_fn_def(par, None, Some(const_tok.span))?)
+                }
+                _ => ModuleStmt::Constant(Box::new(parse_constant(par, const_tok.span)?)),
+            }
+        }
 
         // Let these be parse errors for now:
         TokenKind::Event => ModuleStmt::Event(parse_event_def(par, None)?),
@@ -49,12 +81,31 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
             match par.peek_or_err()? {
                 TokenKind::Event => ModuleStmt::Event(parse_event_def(par, Some(pub_span))?),
                 TokenKind::Fn | TokenKind::Unsafe => {
-                    ModuleStmt::Function(parse_fn_def(par, Some(pub_span))?)
+                    ModuleStmt::Function(parse_fn_def(par, Some(pub_span), None)?)
+                }
+                TokenKind::Const => {
+                    let const_span = par.next()?.span;
+                    ModuleStmt::Function(parse_fn_def(par, Some(pub_span), Some(const_span))?)
                 }
                 TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, Some(pub_span))?),
+                TokenKind::Name if par.peeked_text() == "error" => {
+                    ModuleStmt::Struct(parse_error_def(par, Some(pub_span))?)
+                }
+                TokenKind::Name if par.peeked_text() == "trait" => {
+                    ModuleStmt::Trait(parse_trait_def(par, Some(pub_span))?)
+                }
+                TokenKind::Enum => ModuleStmt::Enum(parse_enum_def(par, Some(pub_span))?),
                 TokenKind::Type => ModuleStmt::TypeAlias(parse_type_alias(par, Some(pub_span))?),
-                TokenKind::Contract => {
-                    ModuleStmt::Contract(parse_contract_def(par, Some(pub_span))?)
+                TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(
+                    par,
+                    Some(pub_span),
+                    ContractQualifiers::default(),
+                )?),
+                TokenKind::Name if is_contract_qualifier(par.peeked_text()) => {
+                    ModuleStmt::Contract(parse_qualified_contract_def(par, Some(pub_span))?)
+                }
+                TokenKind::Name if par.peeked_text() == "interface" => {
+                    ModuleStmt::Contract(parse_interface_def(par, Some(pub_span))?)
                 }
                 _ => {
                     let tok = par.next()?;
@@ -67,13 +118,16 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
                 }
             }
         }
-        TokenKind::Fn | TokenKind::Unsafe => ModuleStmt::Function(parse_fn_def(par, None)?),
+        TokenKind::Fn | TokenKind::Unsafe => ModuleStmt::Function(parse_fn_def(par, None, None)?),
         _ => {
             let tok = par.next()?;
             par.unexpected_token_error(
                 tok.span,
                 "failed to parse module",
-                vec!["Note: expected import, contract, struct, type, const or event".into()],
+                vec![
+                    "Note: expected import, contract, interface, struct, error, enum, trait, impl, type, const or event"
+                        .into(),
+                ],
             );
             return Err(ParseFailed);
         }
@@ -81,11 +135,53 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
     Ok(stmt)
 }
 
-/// Parse a constant, e.g. `const MAGIC_NUMBER: u256 = 4711`.
-/// # Panics
-/// Panics if the next token isn't `const`.
-pub fn parse_constant(par: &mut Parser) -> ParseResult<Node<ConstantDecl>> {
-    let const_tok = par.assert(TokenKind::Const);
+/// Whether `text` is a contextual keyword that qualifies a contract
+/// definition.
+fn is_contract_qualifier(text: &str) -> bool {
+    matches!(
+        text,
+        "upgradeable" | "multicall" | "ownable" | "pausable" | "roles"
+    )
+}
+
+/// Parse a contract definition with the `upgradeable`, `multicall`, `ownable`,
+/// `pausable` or `roles` qualifiers, in any order, e.g.
+/// `ownable pausable contract Token:`.
+fn parse_qualified_contract_def(
+    par: &mut Parser,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<Contract>> {
+    let mut qualifiers = ContractQualifiers::default();
+    while par.peek() == Some(TokenKind::Name) && is_contract_qualifier(par.peeked_text()) {
+        let tok = par.next()?;
+        let qual = match tok.text {
+            "upgradeable" => &mut qualifiers.upgradeable,
+            "multicall" => &mut qualifiers.multicall,
+            "ownable" => &mut qualifiers.ownable,
+            "pausable" => &mut qualifiers.pausable,
+            _ => &mut qualifiers.roles,
+        };
+        if qual.is_some() {
+            par.error(tok.span, format!("duplicate `{}` qualifier", tok.text));
+        }
+        *qual = Some(tok.span);
+    }
+
+    if par.peek() != Some(TokenKind::Contract) {
+        let tok = par.next()?;
+        par.unexpected_token_error(
+            tok.span,
+            "failed to parse module",
+            vec!["Note: contract qualifiers like `upgradeable` must be followed by a contract definition, e.g. `upgradeable contract Token:`".into()],
+        );
+        return Err(ParseFailed);
+    }
+    parse_contract_def(par, pub_qual, qualifiers)
+}
+
+/// Parse a constant, e.g. `const MAGIC_NUMBER: u256 = 4711`. The `const`
+/// keyword must be parsed by the caller, and its span passed in.
+pub fn parse_constant(par: &mut Parser, const_span: Span) -> ParseResult<Node<ConstantDecl>> {
     let name = par.expect(TokenKind::Name, "failed to parse constant declaration")?;
     par.expect_with_notes(
         TokenKind::Colon,
@@ -115,7 +211,7 @@ pub fn parse_constant(par: &mut Parser) -> ParseResult<Node<ConstantDecl>> {
 
     let exp = parse_expr(par)?;
 
-    let span = const_tok.span + exp.span;
+    let span = const_span + exp.span;
     Ok(Node::new(
         ConstantDecl {
             name: name.into(),
@@ -138,6 +234,50 @@ pub fn parse_use(par: &mut Parser) -> ParseResult<Node<Use>> {
     Ok(Node::new(Use { tree }, use_tok.span + tree_span))
 }
 
+/// Returns true if the next tokens are `use abi` followed by a string, which
+/// starts an ABI import rather than a `use` of a module named `abi`.
+fn is_abi_import(par: &mut Parser) -> bool {
+    let mut bt_par = par.as_bt_parser();
+    bt_par.next().is_ok()
+        && bt_par.peek() == Some(TokenKind::Name)
+        && bt_par.peeked_text() == "abi"
+        && bt_par.next().is_ok()
+        && bt_par.peek() == Some(TokenKind::Text)
+}
+
+/// Parse a `use abi "<path>" as <name>` statement.
+/// # Panics
+/// Panics if the next tokens aren't `use abi` followed by a string.
+pub fn parse_abi_import(par: &mut Parser) -> ParseResult<Node<AbiImport>> {
+    let use_tok = par.assert(TokenKind::Use);
+    par.assert(TokenKind::Name);
+    let path_tok = par.assert(TokenKind::Text);
+    let path = match unescape_string(path_tok.text) {
+        Some(path) => path,
+        None => {
+            par.error(path_tok.span, "String contains an invalid escape sequence");
+            return Err(ParseFailed);
+        }
+    };
+
+    par.expect_with_notes(TokenKind::As, "failed to parse `use abi` statement", |_| {
+        vec![
+            "Note: the interface generated from an ABI needs a name".into(),
+            "Example: `use abi \"./erc20_abi.json\" as ERC20`".into(),
+        ]
+    })?;
+    let name = par.expect(TokenKind::Name, "failed to parse `use abi` statement")?;
+    let span = use_tok.span + name.span;
+
+    Ok(Node::new(
+        AbiImport {
+            path: Node::new(path.into(), path_tok.span),
+            name: name.into(),
+        },
+        span,
+    ))
+}
+
 /// Parse a `use` tree.
 pub fn parse_use_tree(par: &mut Parser) -> ParseResult<Node<UseTree>> {
     let (path, path_span, trailing_delim) = {
@@ -0,0 +1,289 @@
+//! Declarative tables of the expression grammar. The expression parser looks
+//! up operator precedences here, and the grammar generator (`cargo xtask
+//! grammar`) derives the EBNF and tree-sitter grammars from the same tables,
+//! so that editor syntax definitions stay in sync with the parser.
+
+use crate::lexer::TokenKind;
+use TokenKind::*;
+
+/// How an infix operator groups with operators of the same precedence.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// A part of the syntax that follows a postfix operator's operand.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Part {
+    Token(TokenKind),
+    Expr,
+    /// A parenthesized list of call arguments, e.g. `(1, x=2)`.
+    CallArgs,
+    Optional(&'static [Part]),
+}
+
+/// An operator that follows its operand. `suffix` is the syntax of everything
+/// after the operand, starting with `token`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Postfix {
+    pub token: TokenKind,
+    pub suffix: &'static [Part],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Operators {
+    Prefix(&'static [TokenKind]),
+    Infix(Assoc, &'static [TokenKind]),
+    Postfix(&'static [Postfix]),
+}
+
+/// Operators of the same precedence. Higher binding powers bind more tightly.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OperatorGroup {
+    pub name: &'static str,
+    pub binding_power: u8,
+    pub operators: Operators,
+}
+
+impl OperatorGroup {
+    /// The left and right binding powers of the group's infix operators.
+    pub fn infix_binding_power(&self) -> Option<(u8, u8)> {
+        match self.operators {
+            Operators::Infix(Assoc::Left, _) => Some((self.binding_power, self.binding_power + 1)),
+            Operators::Infix(Assoc::Right, _) => Some((self.binding_power + 1, self.binding_power)),
+            _ => None,
+        }
+    }
+}
+
+/// The operators of the expression grammar, from the loosest to the tightest
+/// binding. See <https://docs.python.org/3/reference/expressions.html#operator-precedence>
+pub const OPERATORS: &[OperatorGroup] = &[
+    OperatorGroup {
+        name: "ternary",
+        binding_power: 35,
+        operators: Operators::Postfix(&[Postfix {
+            token: If,
+            suffix: &[Part::Token(If), Part::Expr, Part::Token(Else), Part::Expr],
+        }]),
+    },
+    OperatorGroup {
+        name: "or",
+        binding_power: 50,
+        operators: Operators::Infix(Assoc::Left, &[Or]),
+    },
+    OperatorGroup {
+        name: "and",
+        binding_power: 60,
+        operators: Operators::Infix(Assoc::Left, &[And]),
+    },
+    OperatorGroup {
+        name: "not",
+        binding_power: 65,
+        operators: Operators::Prefix(&[Not]),
+    },
+    OperatorGroup {
+        name: "comparison",
+        binding_power: 70,
+        operators: Operators::Infix(Assoc::Left, &[Lt, LtEq, Gt, GtEq, NotEq, EqEq]),
+    },
+    OperatorGroup {
+        name: "bitwise_or",
+        binding_power: 80,
+        operators: Operators::Infix(Assoc::Left, &[Pipe]),
+    },
+    OperatorGroup {
+        name: "bitwise_xor",
+        binding_power: 90,
+        operators: Operators::Infix(Assoc::Left, &[Hat]),
+    },
+    OperatorGroup {
+        name: "bitwise_and",
+        binding_power: 100,
+        operators: Operators::Infix(Assoc::Left, &[Amper]),
+    },
+    OperatorGroup {
+        name: "shift",
+        binding_power: 110,
+        operators: Operators::Infix(Assoc::Left, &[LtLt, GtGt]),
+    },
+    OperatorGroup {
+        name: "additive",
+        binding_power: 120,
+        operators: Operators::Infix(Assoc::Left, &[Plus, Minus]),
+    },
+    OperatorGroup {
+        name: "multiplicative",
+        binding_power: 130,
+        operators: Operators::Infix(Assoc::Left, &[Star, Slash, Percent]),
+    },
+    OperatorGroup {
+        name: "unary",
+        binding_power: 135,
+        operators: Operators::Prefix(&[Plus, Minus, Tilde]),
+    },
+    OperatorGroup {
+        name: "power",
+        binding_power: 140,
+        operators: Operators::Infix(Assoc::Right, &[StarStar]),
+    },
+    OperatorGroup {
+        name: "attribute",
+        binding_power: 150,
+        operators: Operators::Infix(Assoc::Left, &[Dot]),
+    },
+    OperatorGroup {
+        name: "postfix",
+        binding_power: 150,
+        operators: Operators::Postfix(&[
+            Postfix {
+                token: ParenOpen,
+                suffix: &[Part::CallArgs],
+            },
+            Postfix {
+                token: BracketOpen,
+                suffix: &[
+                    Part::Token(BracketOpen),
+                    Part::Optional(&[Part::Expr]),
+                    Part::Optional(&[Part::Token(Colon), Part::Optional(&[Part::Expr])]),
+                    Part::Token(BracketClose),
+                ],
+            },
+            Postfix {
+                token: Question,
+                suffix: &[Part::Token(Question)],
+            },
+        ]),
+    },
+    OperatorGroup {
+        name: "path",
+        binding_power: 160,
+        operators: Operators::Infix(Assoc::Left, &[ColonColon]),
+    },
+];
+
+/// The tokens that are an expression on their own.
+pub const ATOMS: &[TokenKind] = &[Name, SelfValue, Int, Hex, Octal, Binary, Text, True, False];
+
+/// The source text of the keyword and symbol tokens.
+pub const TOKEN_TEXT: &[(TokenKind, &str)] = &[
+    (True, "true"),
+    (False, "false"),
+    (Assert, "assert"),
+    (Break, "break"),
+    (Continue, "continue"),
+    (Contract, "contract"),
+    (Fn, "fn"),
+    (Const, "const"),
+    (Elif, "elif"),
+    (Else, "else"),
+    (Emit, "emit"),
+    (Enum, "enum"),
+    (Event, "event"),
+    (Idx, "idx"),
+    (If, "if"),
+    (Match, "match"),
+    (Pragma, "pragma"),
+    (Pass, "pass"),
+    (For, "for"),
+    (Pub, "pub"),
+    (Return, "return"),
+    (Revert, "revert"),
+    (SelfValue, "self"),
+    (Struct, "struct"),
+    (Type, "type"),
+    (Unsafe, "unsafe"),
+    (While, "while"),
+    (And, "and"),
+    (As, "as"),
+    (In, "in"),
+    (Not, "not"),
+    (Or, "or"),
+    (Let, "let"),
+    (Use, "use"),
+    (ParenOpen, "("),
+    (ParenClose, ")"),
+    (BracketOpen, "["),
+    (BracketClose, "]"),
+    (BraceOpen, "{"),
+    (BraceClose, "}"),
+    (Colon, ":"),
+    (ColonColon, "::"),
+    (Comma, ","),
+    (Semi, ";"),
+    (Question, "?"),
+    (Plus, "+"),
+    (Minus, "-"),
+    (Star, "*"),
+    (Slash, "/"),
+    (Pipe, "|"),
+    (Amper, "&"),
+    (Lt, "<"),
+    (LtLt, "<<"),
+    (Gt, ">"),
+    (GtGt, ">>"),
+    (Eq, "="),
+    (Dot, "."),
+    (Percent, "%"),
+    (EqEq, "=="),
+    (NotEq, "!="),
+    (LtEq, "<="),
+    (GtEq, ">="),
+    (Tilde, "~"),
+    (Hat, "^"),
+    (StarStar, "**"),
+    (StarStarEq, "**="),
+    (PlusEq, "+="),
+    (MinusEq, "-="),
+    (StarEq, "*="),
+    (SlashEq, "/="),
+    (PercentEq, "%="),
+    (AmperEq, "&="),
+    (PipeEq, "|="),
+    (HatEq, "^="),
+    (LtLtEq, "<<="),
+    (GtGtEq, ">>="),
+    (Arrow, "->"),
+];
+
+/// The regular expressions of the tokens whose text varies. These must match
+/// the `#[regex]` attributes of [`TokenKind`].
+pub const TOKEN_PATTERNS: &[(TokenKind, &str)] = &[
+    (Name, r"[a-zA-Z_][a-zA-Z0-9_]*"),
+    (Int, r"[0-9]+"),
+    (Hex, r"0[xX][0-9a-fA-F]+"),
+    (Octal, r"0[oO][0-7]+"),
+    (Binary, r"0[bB][0-1]+"),
+    (Text, r#""([^"\\]|\\.)*"|'([^'\\]|\\.)*'"#),
+    (Comment, r"#[^\n]*"),
+];
+
+/// The source text of a keyword or symbol token.
+pub fn token_text(kind: TokenKind) -> Option<&'static str> {
+    TOKEN_TEXT
+        .iter()
+        .find(|(token, _)| *token == kind)
+        .map(|(_, text)| *text)
+}
+
+/// The group of the prefix operator `op`.
+pub fn prefix_operator(op: TokenKind) -> Option<&'static OperatorGroup> {
+    OPERATORS
+        .iter()
+        .find(|group| matches!(group.operators, Operators::Prefix(ops) if ops.contains(&op)))
+}
+
+/// The group of the infix operator `op`.
+pub fn infix_operator(op: TokenKind) -> Option<&'static OperatorGroup> {
+    OPERATORS
+        .iter()
+        .find(|group| matches!(group.operators, Operators::Infix(_, ops) if ops.contains(&op)))
+}
+
+/// The group of the postfix operator that starts with `op`.
+pub fn postfix_operator(op: TokenKind) -> Option<&'static OperatorGroup> {
+    OPERATORS.iter().find(|group| {
+        matches!(group.operators, Operators::Postfix(ops) if ops.iter().any(|postfix| postfix.token == op))
+    })
+}
@@ -0,0 +1,137 @@
+use super::functions::{parse_fn_body, parse_fn_def, parse_fn_sig, parse_single_word_stmt};
+use super::types::parse_opt_qualifier;
+
+use crate::ast::{Impl, Trait};
+use crate::node::{Node, Span};
+use crate::{ParseFailed, ParseResult, Parser, TokenKind};
+use alloc::{format, vec};
+
+/// Parse a trait definition.
+/// # Panics
+/// Panics if the next token isn't the contextual keyword `trait`.
+pub fn parse_trait_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<Trait>> {
+    let trait_tok = par.assert(TokenKind::Name);
+    assert_eq!(trait_tok.text, "trait");
+
+    // trait Describe:
+    //   fn id(self) -> u256
+    //
+    //   fn describe(self) -> u256:
+    //     return self.id() * 10
+    //
+
+    let name = par.expect_with_notes(TokenKind::Name, "failed to parse trait definition", |_| {
+        vec!["Note: `trait` must be followed by a name, which must start with a letter and contain only letters, numbers, or underscores".into()]
+    })?;
+    let header_span = trait_tok.span + name.span;
+    par.enter_block(header_span, "trait definition")?;
+
+    let mut functions = vec![];
+    loop {
+        match par.peek() {
+            Some(TokenKind::Pub | TokenKind::Fn | TokenKind::Unsafe) => {
+                let fn_pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
+                let sig = parse_fn_sig(par, fn_pub_qual, None)?;
+                if par.peek() == Some(TokenKind::Colon) {
+                    functions.push(parse_fn_body(par, sig)?);
+                } else {
+                    par.expect_newline("trait function")?;
+                    functions.push(sig);
+                }
+            }
+            Some(TokenKind::Pass) => {
+                parse_single_word_stmt(par)?;
+            }
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            None => break,
+            Some(_) => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse trait definition body",
+                    vec!["Note: a trait may only declare functions, e.g. `fn balance(self) -> u256`, or define default functions".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+
+    let span = header_span + pub_qual + functions.last();
+    Ok(Node::new(
+        Trait {
+            name: name.into(),
+            functions,
+            pub_qual,
+        },
+        span,
+    ))
+}
+
+/// Parse the implementation of a trait, e.g. `impl Describe for Point:`.
+/// # Panics
+/// Panics if the next token isn't the contextual keyword `impl`.
+pub fn parse_impl_def(par: &mut Parser) -> ParseResult<Node<Impl>> {
+    let impl_tok = par.assert(TokenKind::Name);
+    assert_eq!(impl_tok.text, "impl");
+
+    let trait_name = par.expect_with_notes(TokenKind::Name, "failed to parse trait implementation", |_| {
+        vec!["Note: `impl` must be followed by the name of a trait, e.g. `impl Describe for Point:`".into()]
+    })?;
+    par.expect_with_notes(
+        TokenKind::For,
+        "failed to parse trait implementation",
+        |_| {
+            vec![format!(
+                "Note: the trait must be followed by `for` and the name of a struct or contract, e.g. `impl {} for Point:`",
+                trait_name.text
+            )]
+        },
+    )?;
+    let receiver = par.expect_with_notes(
+        TokenKind::Name,
+        "failed to parse trait implementation",
+        |_| vec!["Note: `for` must be followed by the name of a struct or contract".into()],
+    )?;
+    let header_span = impl_tok.span + receiver.span;
+    par.enter_block(header_span, "trait implementation")?;
+
+    let mut functions = vec![];
+    loop {
+        match par.peek() {
+            Some(TokenKind::Pub | TokenKind::Fn | TokenKind::Unsafe) => {
+                let fn_pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
+                functions.push(parse_fn_def(par, fn_pub_qual, None)?);
+            }
+            Some(TokenKind::Pass) => {
+                parse_single_word_stmt(par)?;
+            }
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            None => break,
+            Some(_) => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse trait implementation body",
+                    vec!["Note: a trait implementation may only define functions".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+
+    let span = header_span + functions.last();
+    Ok(Node::new(
+        Impl {
+            trait_name: trait_name.into(),
+            receiver: receiver.into(),
+            functions,
+        },
+        span,
+    ))
+}
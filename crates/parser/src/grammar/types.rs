@@ -1,9 +1,12 @@
-use crate::ast::{self, EventField, Field, GenericArg, Path, TypeAlias, TypeDesc};
+use crate::ast::{
+    self, EventField, Field, GenericArg, GenericParameter, Path, TypeAlias, TypeDesc,
+};
 use crate::grammar::expressions::parse_expr;
 use crate::grammar::functions::{parse_fn_def, parse_single_word_stmt};
 use crate::node::{Node, Span};
 use crate::Token;
 use crate::{ParseFailed, ParseResult, Parser, TokenKind};
+use alloc::{format, vec, vec::Vec};
 use fe_common::diagnostics::Label;
 use if_chain::if_chain;
 use smol_str::SmolStr;
@@ -20,12 +23,23 @@ pub fn parse_struct_def(
     let name = par.expect_with_notes(TokenKind::Name, "failed to parse struct definition", |_| {
         vec!["Note: a struct name must start with a letter or underscore, and contain letters, numbers, or underscores".into()]
     })?;
+    let generic_params = if par.peek() == Some(TokenKind::Lt) {
+        Some(parse_generic_params(par)?)
+    } else {
+        None
+    };
 
     let mut fields = vec![];
     let mut functions = vec![];
     par.enter_block(struct_tok.span + name.span, "struct definition")?;
     loop {
         let pub_qual = par.optional(TokenKind::Pub).map(|tok| tok.span);
+        let const_qual = par.optional(TokenKind::Const).map(|tok| tok.span);
+        if let Some(span) = const_qual {
+            if par.peek() == Some(TokenKind::Name) {
+                par.error(span, "`const` qualifier can't be used with struct fields");
+            }
+        }
         match par.peek() {
             Some(TokenKind::Name) => {
                 let field = parse_field(par, pub_qual, None)?;
@@ -38,7 +52,7 @@ pub fn parse_struct_def(
                 fields.push(field);
             }
             Some(TokenKind::Fn | TokenKind::Unsafe) => {
-                functions.push(parse_fn_def(par, pub_qual)?);
+                functions.push(parse_fn_def(par, pub_qual, const_qual)?);
             }
             Some(TokenKind::Dedent) => {
                 par.next()?;
@@ -55,13 +69,129 @@ pub fn parse_struct_def(
             }
         }
     }
-    let span = struct_tok.span + struct_pub_qual + name.span + fields.last();
+    let span =
+        struct_tok.span + struct_pub_qual + name.span + generic_params.as_ref() + fields.last();
     Ok(Node::new(
         ast::Struct {
             name: name.into(),
+            generic_params: generic_params.map(|node| node.kind).unwrap_or_default(),
             fields,
             functions,
             pub_qual: struct_pub_qual,
+            is_error: false,
+        },
+        span,
+    ))
+}
+
+/// Parse a custom error definition, e.g.
+/// `error InsufficientBalance(available: u256, required: u256)`.
+/// # Panics
+/// Panics if the next token isn't the name `error`.
+pub fn parse_error_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<ast::Struct>> {
+    let error_tok = par.assert(TokenKind::Name);
+    let name = par.expect_with_notes(TokenKind::Name, "failed to parse error definition", |_| {
+        vec!["Note: an error name must start with a letter or underscore, and contain letters, numbers, or underscores".into()]
+    })?;
+    par.expect_with_notes(
+        TokenKind::ParenOpen,
+        "failed to parse error definition",
+        |_| {
+            vec![
+                "Note: an error name must be followed by a list of fields in parentheses".into(),
+                format!(
+                    "Example: `error {}(available: u256, required: u256)`",
+                    name.text
+                ),
+            ]
+        },
+    )?;
+
+    let mut fields = vec![];
+    loop {
+        if par.peek_or_err()? == TokenKind::ParenClose {
+            break;
+        }
+        let field_name = par.expect(TokenKind::Name, "failed to parse error field")?;
+        par.expect_with_notes(TokenKind::Colon, "failed to parse error field", |_| {
+            vec![format!(
+                "Note: an error field is a name and a type, e.g. `{}: u256`",
+                field_name.text
+            )]
+        })?;
+        let typ = parse_type_desc(par)?;
+        let span = field_name.span + typ.span;
+        fields.push(Node::new(
+            Field {
+                is_pub: true,
+                is_const: false,
+                name: field_name.into(),
+                typ,
+                value: None,
+            },
+            span,
+        ));
+        if par.optional(TokenKind::Comma).is_none() {
+            break;
+        }
+    }
+    let rparen = par.expect(TokenKind::ParenClose, "failed to parse error definition")?;
+    par.expect_newline("error definition")?;
+
+    let span = error_tok.span + pub_qual + rparen.span;
+    Ok(Node::new(
+        ast::Struct {
+            name: name.into(),
+            generic_params: vec![],
+            fields,
+            functions: vec![],
+            pub_qual,
+            is_error: true,
+        },
+        span,
+    ))
+}
+
+/// Parse an enum definition, e.g. `enum State: Pending, Active, Closed`.
+/// # Panics
+/// Panics if the next token isn't `enum`.
+pub fn parse_enum_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<ast::Enum>> {
+    let enum_tok = par.assert(TokenKind::Enum);
+    let name = par.expect_with_notes(TokenKind::Name, "failed to parse enum definition", |_| {
+        vec!["Note: an enum name must start with a letter or underscore, and contain letters, numbers, or underscores".into()]
+    })?;
+    par.expect_with_notes(TokenKind::Colon, "failed to parse enum definition", |_| {
+        vec![
+            "Note: an enum name must be followed by a colon and a list of variants".into(),
+            format!("Example: `enum {}: Pending, Active, Closed`", name.text),
+        ]
+    })?;
+
+    let mut variants = vec![];
+    loop {
+        let variant = par.expect_with_notes(TokenKind::Name, "failed to parse enum definition", |_| {
+            vec![format!(
+                "Note: the variants of an enum are names separated by commas, e.g. `enum {}: Pending, Active, Closed`",
+                name.text
+            )]
+        })?;
+        variants.push(Node::from(variant));
+        if par.optional(TokenKind::Comma).is_none() {
+            break;
+        }
+        // A trailing comma is allowed.
+        if matches!(par.peek(), Some(TokenKind::Newline) | None) {
+            break;
+        }
+    }
+    par.expect_newline("enum definition")?;
+
+    let span = enum_tok.span + pub_qual + name.span + variants.last();
+    Ok(Node::new(
+        ast::Enum {
+            name: name.into(),
+            variants,
+            pub_qual,
         },
         span,
     ))
@@ -475,3 +605,44 @@ pub fn parse_type_desc(par: &mut Parser) -> ParseResult<Node<TypeDesc>> {
 
     Ok(typ)
 }
+
+/// Parse the type parameters of a generic function or struct, e.g.
+/// `<T, U: Numeric>`.
+/// # Panics
+/// Panics if the next token isn't `<`.
+pub fn parse_generic_params(par: &mut Parser) -> ParseResult<Node<Vec<Node<GenericParameter>>>> {
+    let mut span = par.assert(TokenKind::Lt).span;
+
+    let mut params = vec![];
+    loop {
+        if par.peek_or_err()? == TokenKind::Gt {
+            break;
+        }
+        let name = par.expect_with_notes(
+            TokenKind::Name,
+            "failed to parse generic type parameter list",
+            |_| vec!["Example: `<T, U: Numeric>`".into()],
+        )?;
+        let bound = if par.optional(TokenKind::Colon).is_some() {
+            let bound = par.expect(TokenKind::Name, "failed to parse type parameter bound")?;
+            Some(Node::from(bound))
+        } else {
+            None
+        };
+        let param_span = name.span + bound.as_ref();
+        params.push(Node::new(
+            GenericParameter {
+                name: name.into(),
+                bound,
+            },
+            param_span,
+        ));
+        if par.optional(TokenKind::Comma).is_none() {
+            break;
+        }
+    }
+    span += par
+        .expect(TokenKind::Gt, "failed to parse generic type parameter list")?
+        .span;
+    Ok(Node::new(params, span))
+}
@@ -0,0 +1,277 @@
+use crate::ast::{YulCase, YulExpr, YulStmt};
+use crate::lexer::{Token, TokenKind};
+use crate::node::Node;
+use crate::{Label, ParseFailed, ParseResult, Parser};
+use alloc::{format, vec, vec::Vec};
+use smol_str::SmolStr;
+
+/// Words that are keywords in Yul, but not in Fe.
+const YUL_KEYWORDS: [&str; 5] = ["switch", "case", "default", "function", "leave"];
+
+/// Parse a braced block of Yul statements. Returns the statements, spanning the
+/// braces.
+pub fn parse_yul_block(par: &mut Parser) -> ParseResult<Node<Vec<Node<YulStmt>>>> {
+    let open = par.expect(TokenKind::BraceOpen, "failed to parse Yul block")?;
+    let mut body = vec![];
+    loop {
+        if par.peek_or_err()? == TokenKind::BraceClose {
+            let close = par.next()?;
+            return Ok(Node::new(body, open.span + close.span));
+        }
+        body.push(parse_yul_stmt(par)?);
+    }
+}
+
+/// Parse a Yul statement. Yul statements don't need to be separated by
+/// newlines, so a statement ends where the next one begins.
+pub fn parse_yul_stmt(par: &mut Parser) -> ParseResult<Node<YulStmt>> {
+    use TokenKind::*;
+
+    par.nested(|par| match par.peek_or_err()? {
+        BraceOpen => {
+            let block = parse_yul_block(par)?;
+            Ok(Node::new(YulStmt::Block(block.kind), block.span))
+        }
+        Let => parse_yul_let(par),
+        If => {
+            let if_tok = par.assert(If);
+            let test = parse_yul_expr(par)?;
+            let body = parse_yul_block(par)?;
+            let span = if_tok.span + body.span;
+            Ok(Node::new(
+                YulStmt::If {
+                    test,
+                    body: body.kind,
+                },
+                span,
+            ))
+        }
+        For => {
+            let for_tok = par.assert(For);
+            let init = parse_yul_block(par)?;
+            let test = parse_yul_expr(par)?;
+            let post = parse_yul_block(par)?;
+            let body = parse_yul_block(par)?;
+            let span = for_tok.span + body.span;
+            Ok(Node::new(
+                YulStmt::For {
+                    init: init.kind,
+                    test,
+                    post: post.kind,
+                    body: body.kind,
+                },
+                span,
+            ))
+        }
+        Break => Ok(Node::new(YulStmt::Break, par.next()?.span)),
+        Continue => Ok(Node::new(YulStmt::Continue, par.next()?.span)),
+        Name if par.peeked_text() == "switch" => parse_yul_switch(par),
+        Name if matches!(par.peeked_text(), "function" | "leave") => {
+            let tok = par.next()?;
+            par.fancy_error(
+                format!("`{}` is not supported in inline Yul", tok.text),
+                vec![Label::primary(tok.span, "not supported")],
+                vec![],
+            );
+            Err(ParseFailed)
+        }
+        _ => parse_yul_assign_or_call(par),
+    })
+}
+
+fn parse_yul_let(par: &mut Parser) -> ParseResult<Node<YulStmt>> {
+    let let_tok = par.assert(TokenKind::Let);
+    let names = parse_yul_names(par)?;
+    let value = if par.peek() == Some(TokenKind::Colon) {
+        expect_yul_assign_op(par)?;
+        Some(parse_yul_expr(par)?)
+    } else {
+        None
+    };
+    let span = let_tok.span + names.last() + value.as_ref();
+    Ok(Node::new(YulStmt::Let { names, value }, span))
+}
+
+fn parse_yul_assign_or_call(par: &mut Parser) -> ParseResult<Node<YulStmt>> {
+    use TokenKind::*;
+
+    let name = parse_yul_identifier(par)?;
+    match par.peek() {
+        Some(ParenOpen) => {
+            let call = parse_yul_call_tail(par, name)?;
+            let span = call.span;
+            Ok(Node::new(YulStmt::Expr(call), span))
+        }
+        Some(Comma | Colon) => {
+            let mut names = vec![name];
+            while par.peek() == Some(Comma) {
+                par.next()?;
+                names.push(parse_yul_identifier(par)?);
+            }
+            expect_yul_assign_op(par)?;
+            let value = parse_yul_expr(par)?;
+            let span = names[0].span + value.span;
+            Ok(Node::new(YulStmt::Assign { names, value }, span))
+        }
+        _ => {
+            par.fancy_error(
+                "failed to parse Yul statement",
+                vec![Label::primary(
+                    name.span,
+                    "expected an assignment or a function call",
+                )],
+                vec![],
+            );
+            Err(ParseFailed)
+        }
+    }
+}
+
+fn parse_yul_switch(par: &mut Parser) -> ParseResult<Node<YulStmt>> {
+    use TokenKind::*;
+
+    let switch_tok = par.next()?;
+    let value = parse_yul_expr(par)?;
+    let mut cases = vec![];
+    loop {
+        let (case_tok, case_value) = match par.peek() {
+            Some(Name) if par.peeked_text() == "case" => {
+                let case_tok = par.next()?;
+                let lit = par.next()?;
+                if !is_yul_literal(&lit) {
+                    par.unexpected_token_error(
+                        lit.span,
+                        "failed to parse Yul `case`",
+                        vec!["Note: the value of a `case` must be a literal".into()],
+                    );
+                    return Err(ParseFailed);
+                }
+                (case_tok, Some(Node::new(lit.text.into(), lit.span)))
+            }
+            Some(Name) if par.peeked_text() == "default" => (par.next()?, None),
+            _ => break,
+        };
+        let body = parse_yul_block(par)?;
+        let span = case_tok.span + body.span;
+        cases.push(Node::new(
+            YulCase {
+                value: case_value,
+                body: body.kind,
+            },
+            span,
+        ));
+    }
+
+    if cases.is_empty() {
+        par.fancy_error(
+            "failed to parse Yul `switch` statement",
+            vec![Label::primary(
+                switch_tok.span + value.span,
+                "expected at least one `case` or `default` after this",
+            )],
+            vec![],
+        );
+        return Err(ParseFailed);
+    }
+    let span = switch_tok.span + cases.last();
+    Ok(Node::new(YulStmt::Switch { value, cases }, span))
+}
+
+/// Parse a Yul expression: a literal, an identifier or a function call.
+pub fn parse_yul_expr(par: &mut Parser) -> ParseResult<Node<YulExpr>> {
+    par.nested(|par| {
+        if matches!(
+            par.peek_or_err()?,
+            TokenKind::Int | TokenKind::Hex | TokenKind::True | TokenKind::False
+        ) {
+            let tok = par.next()?;
+            return Ok(Node::new(YulExpr::Literal(tok.text.into()), tok.span));
+        }
+
+        let name = parse_yul_identifier(par)?;
+        if par.peek() == Some(TokenKind::ParenOpen) {
+            parse_yul_call_tail(par, name)
+        } else {
+            let span = name.span;
+            Ok(Node::new(YulExpr::Name(name.kind), span))
+        }
+    })
+}
+
+/// Parse the parenthesized arguments of a call to the function `func`.
+fn parse_yul_call_tail(par: &mut Parser, func: Node<SmolStr>) -> ParseResult<Node<YulExpr>> {
+    let open = par.assert(TokenKind::ParenOpen);
+    let mut args = vec![];
+    if par.peek_or_err()? != TokenKind::ParenClose {
+        loop {
+            args.push(parse_yul_expr(par)?);
+            if par.peek() == Some(TokenKind::Comma) {
+                par.next()?;
+            } else {
+                break;
+            }
+        }
+    }
+    let close = par.expect(TokenKind::ParenClose, "failed to parse Yul function call")?;
+    let args = Node::new(args, open.span + close.span);
+    let span = func.span + args.span;
+    Ok(Node::new(YulExpr::Call { func, args }, span))
+}
+
+fn parse_yul_names(par: &mut Parser) -> ParseResult<Vec<Node<SmolStr>>> {
+    let mut names = vec![parse_yul_identifier(par)?];
+    while par.peek() == Some(TokenKind::Comma) {
+        par.next()?;
+        names.push(parse_yul_identifier(par)?);
+    }
+    Ok(names)
+}
+
+fn parse_yul_identifier(par: &mut Parser) -> ParseResult<Node<SmolStr>> {
+    let tok = par.next()?;
+    if is_yul_identifier(&tok) {
+        Ok(Node::new(tok.text.into(), tok.span))
+    } else {
+        par.unexpected_token_error(tok.span, "failed to parse Yul identifier", vec![]);
+        Err(ParseFailed)
+    }
+}
+
+/// `:=` is lexed as a `:` followed by a `=`, which must be adjacent.
+fn expect_yul_assign_op(par: &mut Parser) -> ParseResult<()> {
+    let colon = par.expect(TokenKind::Colon, "failed to parse Yul assignment")?;
+    let eq = par.expect(TokenKind::Eq, "failed to parse Yul assignment")?;
+    if colon.span.end != eq.span.start {
+        par.fancy_error(
+            "failed to parse Yul assignment",
+            vec![Label::primary(colon.span + eq.span, "expected `:=`")],
+            vec![],
+        );
+        return Err(ParseFailed);
+    }
+    Ok(())
+}
+
+fn is_yul_literal(tok: &Token) -> bool {
+    matches!(
+        tok.kind,
+        TokenKind::Int | TokenKind::Hex | TokenKind::True | TokenKind::False
+    )
+}
+
+/// Some Yul builtins, like `and`, `not` and `return`, are keywords in Fe, so
+/// any word is accepted here except for the keywords of Yul itself.
+fn is_yul_identifier(tok: &Token) -> bool {
+    use TokenKind::*;
+
+    let is_word = tok
+        .text
+        .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && tok
+            .text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_word
+        && !matches!(tok.kind, Let | If | For | Break | Continue | True | False)
+        && !YUL_KEYWORDS.contains(&tok.text)
+}
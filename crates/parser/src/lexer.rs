@@ -4,10 +4,17 @@ use fe_common::files::SourceFileId;
 use logos::Logos;
 pub use token::{Token, TokenKind};
 
+/// An iterator over the tokens of a source code string. Tokens borrow their
+/// text from the source, so lexing doesn't allocate.
 #[derive(Clone)]
 pub struct Lexer<'a> {
     file_id: SourceFileId,
     inner: logos::Lexer<'a, TokenKind>,
+    /// Whether comments and whitespace are yielded, rather than skipped.
+    trivia: bool,
+    /// The span given to every token, instead of its position in the source,
+    /// when lexing source code that is generated by the compiler.
+    span: Option<Span>,
 }
 
 impl<'a> Lexer<'a> {
@@ -16,6 +23,28 @@ impl<'a> Lexer<'a> {
         Lexer {
             file_id,
             inner: TokenKind::lexer(src),
+            trivia: false,
+            span: None,
+        }
+    }
+
+    /// Create a new lexer for source code that is generated by the compiler,
+    /// that gives every token the span `span`, eg. of the code that caused the
+    /// generated code to be added.
+    pub fn with_span(span: Span, src: &'a str) -> Lexer {
+        Lexer {
+            span: Some(span),
+            ..Lexer::new(span.file_id, src)
+        }
+    }
+
+    /// Create a new lexer that also yields comment and whitespace tokens, so
+    /// that the yielded tokens cover the entire source code string (eg. for
+    /// syntax highlighting).
+    pub fn with_trivia(file_id: SourceFileId, src: &'a str) -> Lexer {
+        Lexer {
+            trivia: true,
+            ..Lexer::new(file_id, src)
         }
     }
 
@@ -29,25 +58,93 @@ impl<'a> Iterator for Lexer<'a> {
     type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let kind = self.inner.next()?;
+        let mut kind = self.inner.next()?;
+        while !self.trivia && kind.is_trivia() {
+            kind = self.inner.next()?;
+        }
         let text = self.inner.slice();
         let span = self.inner.span();
 
         Some(Token {
             kind,
             text,
-            span: Span {
+            span: self.span.unwrap_or(Span {
                 file_id: self.file_id,
                 start: span.start,
                 end: span.end,
-            },
+            }),
         })
     }
 }
 
+/// The ASCII character that a non-ASCII character is easily mistaken for, if
+/// any, e.g. `a` for the Cyrillic `а`. Identifiers can only contain ASCII
+/// characters, so that names that look the same are the same name, and this is
+/// used to explain why an identifier that looks fine is rejected.
+pub fn confusable_ascii(c: char) -> Option<char> {
+    match c {
+        // fullwidth forms
+        '\u{FF10}'..='\u{FF19}' => char::from_u32(c as u32 - 0xFF10 + '0' as u32),
+        '\u{FF21}'..='\u{FF3A}' => char::from_u32(c as u32 - 0xFF21 + 'A' as u32),
+        '\u{FF41}'..='\u{FF5A}' => char::from_u32(c as u32 - 0xFF41 + 'a' as u32),
+        '\u{FF3F}' => Some('_'),
+        // Cyrillic
+        'а' => Some('a'),
+        'в' | 'В' => Some('B'),
+        'е' => Some('e'),
+        'і' => Some('i'),
+        'ј' => Some('j'),
+        'к' | 'К' => Some('K'),
+        'м' | 'М' => Some('M'),
+        'н' | 'Н' => Some('H'),
+        'о' => Some('o'),
+        'р' => Some('p'),
+        'с' => Some('c'),
+        'т' | 'Т' => Some('T'),
+        'у' => Some('y'),
+        'х' => Some('x'),
+        'ѕ' => Some('s'),
+        'ԁ' => Some('d'),
+        'ԛ' => Some('q'),
+        'ԝ' => Some('w'),
+        'А' => Some('A'),
+        'Е' => Some('E'),
+        'І' => Some('I'),
+        'Ј' => Some('J'),
+        'О' => Some('O'),
+        'Р' => Some('P'),
+        'С' => Some('C'),
+        'Ѕ' => Some('S'),
+        'Х' => Some('X'),
+        'Ү' => Some('Y'),
+        // Greek
+        'α' => Some('a'),
+        'ι' => Some('i'),
+        'ν' => Some('v'),
+        'ο' => Some('o'),
+        'ρ' => Some('p'),
+        'Α' => Some('A'),
+        'Β' => Some('B'),
+        'Ε' => Some('E'),
+        'Ζ' => Some('Z'),
+        'Η' => Some('H'),
+        'Ι' => Some('I'),
+        'Κ' => Some('K'),
+        'Μ' => Some('M'),
+        'Ν' => Some('N'),
+        'Ο' => Some('O'),
+        'Ρ' => Some('P'),
+        'Τ' => Some('T'),
+        'Υ' => Some('Y'),
+        'Χ' => Some('X'),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::lexer::{Lexer, TokenKind};
+    use crate::node::Span;
     use fe_common::files::SourceFileId;
     use TokenKind::*;
 
@@ -95,6 +192,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn non_ascii_names() {
+        // rejected by the parser, but lexed as whole names
+        check("café = \u{430}mount", &[Name, Eq, Name]);
+        assert_eq!(super::confusable_ascii('\u{430}'), Some('a'));
+        assert_eq!(super::confusable_ascii('\u{FF41}'), Some('a'));
+        assert_eq!(super::confusable_ascii('é'), None);
+    }
+
     #[test]
     fn tabs_and_comment() {
         check(
@@ -102,4 +208,37 @@ mod tests {
             &[Newline, Contract, Newline, Name, Newline],
         );
     }
+
+    #[test]
+    fn trivia() {
+        let src = "contract Foo: # hi mom!\n  x: u8";
+        let tokens = Lexer::with_trivia(SourceFileId::default(), src).collect::<Vec<_>>();
+
+        let kinds = tokens.iter().map(|t| t.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                Contract, Whitespace, Name, Colon, Whitespace, Comment, Newline, Name, Colon,
+                Whitespace, Name,
+            ]
+        );
+        assert_eq!(tokens[5].text, "# hi mom!");
+        assert_eq!(tokens.iter().map(|t| t.text).collect::<String>(), src);
+    }
+
+    #[test]
+    fn fixed_span() {
+        let span = Span::new(SourceFileId::default(), 4, 11);
+        let tokens = Lexer::with_span(span, "fn owner(self):").collect::<Vec<_>>();
+        assert_eq!(tokens.len(), 6);
+        assert!(tokens.iter().all(|t| t.span == span));
+        assert_eq!(tokens[1].text, "owner");
+    }
+
+    #[test]
+    fn grammar_table_token_text() {
+        for (kind, text) in crate::grammar::table::TOKEN_TEXT {
+            check(text, &[*kind]);
+        }
+    }
 }
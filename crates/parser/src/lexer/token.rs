@@ -1,8 +1,8 @@
 use crate::node::Node;
 use crate::node::Span;
+use core::ops::Add;
 use logos::Logos;
 use smol_str::SmolStr;
-use std::ops::Add;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token<'a> {
@@ -27,13 +27,17 @@ impl<'a> Add<&Token<'a>> for Span {
 
 #[derive(Debug, Copy, Clone, PartialEq, Logos)]
 pub enum TokenKind {
-    // Ignoring comments and spaces/tabs for now.
-    // If we implement an auto-formatting tool, we'll probably want to change this.
-    #[regex(r"#[^\n]*", logos::skip)]
-    #[regex("[ \t]+", logos::skip)]
     #[error]
     Error,
 
+    /// Trivia tokens. These are only yielded by a lexer created with
+    /// [`Lexer::with_trivia`](crate::lexer::Lexer::with_trivia), and are
+    /// otherwise skipped.
+    #[regex(r"#[^\n]*")]
+    Comment,
+    #[regex("[ \t]+")]
+    Whitespace,
+
     #[regex(r"\n[ \t]*")]
     Newline,
 
@@ -41,7 +45,9 @@ pub enum TokenKind {
     Indent,
     Dedent,
 
-    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    /// Non-ASCII letters are lexed as part of a name so that the parser can
+    /// reject them with a clear error; see [`crate::lexer::confusable_ascii`].
+    #[regex(r"[a-zA-Z_\p{XID_Start}][a-zA-Z0-9_\p{XID_Continue}]*")]
     Name,
     #[regex("[0-9]+")]
     Int,
@@ -79,12 +85,16 @@ pub enum TokenKind {
     Else,
     #[token("emit")]
     Emit,
+    #[token("enum")]
+    Enum,
     #[token("event")]
     Event,
     #[token("idx")]
     Idx,
     #[token("if")]
     If,
+    #[token("match")]
+    Match,
     #[token("pragma")]
     Pragma,
     #[token("pass")]
@@ -143,6 +153,8 @@ pub enum TokenKind {
     Comma,
     #[token(";")]
     Semi,
+    #[token("?")]
+    Question,
     #[token("+")]
     Plus,
     #[token("-")]
@@ -210,6 +222,11 @@ pub enum TokenKind {
 }
 
 impl TokenKind {
+    /// Returns `true` for comments and whitespace, which the parser ignores.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, TokenKind::Comment | TokenKind::Whitespace)
+    }
+
     /// Return a user-friendly description of the token kind. E.g.
     /// TokenKind::Newline => "a newline"
     /// TokenKind::Colon => "`:`"
@@ -217,6 +234,8 @@ impl TokenKind {
         use TokenKind::*;
         match self {
             Newline => "a newline",
+            Comment => "a comment",
+            Whitespace => "whitespace",
             Dedent => "a dedent",
             Indent => "an indentation",
             Name => "a name",
@@ -238,9 +257,11 @@ impl TokenKind {
             Elif => "keyword `elif`",
             Else => "keyword `else`",
             Emit => "keyword `emit`",
+            Enum => "keyword `enum`",
             Event => "keyword `event`",
             Idx => "keyword `idx`",
             If => "keyword `if`",
+            Match => "keyword `match`",
             Pragma => "keyword `pragma`",
             Pass => "keyword `pass`",
             For => "keyword `for`",
@@ -268,6 +289,7 @@ impl TokenKind {
             ColonColon => "symbol `::`",
             Comma => "symbol `,`",
             Semi => "symbol ``",
+            Question => "symbol `?`",
             Plus => "symbol `+`",
             Minus => "symbol `-`",
             Star => "symbol `*`",
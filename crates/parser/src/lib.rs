@@ -1,4 +1,14 @@
+//! The Fe lexer and parser.
+//!
+//! With the default `std` feature disabled, the crate is `no_std` and only
+//! needs `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod ast;
+pub mod cst;
 pub mod grammar;
 pub mod lexer;
 pub use lexer::{Token, TokenKind};
@@ -6,9 +16,11 @@ mod parser;
 pub use parser::{Label, ParseFailed, ParseResult, Parser};
 pub mod node;
 
+use alloc::vec::Vec;
 use ast::Module;
 use fe_common::diagnostics::Diagnostic;
 use fe_common::files::SourceFileId;
+use node::Span;
 
 /// Parse a [`Module`] from the file content string.
 ///
@@ -32,9 +44,22 @@ pub fn parse_file(
     }
 }
 
+/// Parse a [`Module`] from source code that is generated by the compiler, eg.
+/// the members that a contract qualifier adds to the contract. Every node is
+/// given the span `span`, so that diagnostics point at the code that caused
+/// the generated code to be added.
+pub fn parse_generated(span: Span, src: &str) -> Result<Module, Vec<Diagnostic>> {
+    let mut parser = Parser::with_span(span, src);
+    match crate::grammar::module::parse_module(&mut parser) {
+        Ok(node) if parser.diagnostics.is_empty() => Ok(node.kind),
+        _ => Err(parser.diagnostics),
+    }
+}
+
 /// Apply the given parsing function to the code string, returning the result.
 /// If the parsing fails, the parser's diagnostics will be printed.
 /// This function is provided for easy testing of later compiler stages.
+#[cfg(feature = "std")]
 pub fn parse_code_chunk<F, T>(mut parse_fn: F, src: &str) -> ParseResult<T>
 where
     F: FnMut(&mut Parser) -> ParseResult<T>,
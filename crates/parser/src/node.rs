@@ -1,6 +1,7 @@
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicU32, Ordering};
 pub use fe_common::{Span, Spanned};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU32, Ordering};
 
 #[derive(Debug, PartialEq, Copy, Clone, Hash, Eq, Default, PartialOrd, Ord)]
 pub struct NodeId(u32);
@@ -5,9 +5,11 @@ use fe_common::diagnostics::{Diagnostic, Severity};
 use fe_common::files::SourceFileId;
 
 use crate::ast::Module;
-use crate::lexer::{Lexer, Token, TokenKind};
+use crate::lexer::{self, Lexer, Token, TokenKind};
 use crate::node::Span;
-use std::{error, fmt};
+use alloc::string::{String, ToString};
+use alloc::{format, vec, vec::Vec};
+use core::fmt;
 
 #[derive(Debug)]
 pub struct ParseFailed;
@@ -16,10 +18,16 @@ impl fmt::Display for ParseFailed {
         write!(fmt, "ParseFailed")
     }
 }
-impl error::Error for ParseFailed {}
+#[cfg(feature = "std")]
+impl std::error::Error for ParseFailed {}
 
 pub type ParseResult<T> = Result<T, ParseFailed>;
 
+/// The maximum number of nested expressions and blocks the parser will accept.
+/// Deeper input is rejected with an error rather than risking a stack overflow
+/// here or in the later compiler stages that walk the AST recursively.
+pub const MAX_NESTING_DEPTH: usize = 128;
+
 /// `Parser` maintains the parsing state, such as the token stream,
 /// indent stack, paren stack, diagnostics, etc.
 /// Syntax parsing logic is in the [`crate::grammar`] module.
@@ -38,6 +46,9 @@ pub struct Parser<'a> {
     indent_stack: Vec<BlockIndent<'a>>,
     indent_style: Option<char>,
 
+    /// The current number of nested expressions and blocks being parsed.
+    depth: usize,
+
     /// The diagnostics (errors and warnings) emitted during parsing.
     pub diagnostics: Vec<Diagnostic>,
 }
@@ -45,9 +56,19 @@ pub struct Parser<'a> {
 impl<'a> Parser<'a> {
     /// Create a new parser for a source code string and associated file id.
     pub fn new(file_id: SourceFileId, content: &'a str) -> Self {
+        Self::with_lexer(file_id, Lexer::new(file_id, content))
+    }
+
+    /// Create a new parser for source code that is generated by the compiler.
+    /// Every node is given the span `span`. See [`Lexer::with_span`].
+    pub fn with_span(span: Span, content: &'a str) -> Self {
+        Self::with_lexer(span.file_id, Lexer::with_span(span, content))
+    }
+
+    fn with_lexer(file_id: SourceFileId, lexer: Lexer<'a>) -> Self {
         Parser {
             file_id,
-            lexer: Lexer::new(file_id, content),
+            lexer,
             buffered: vec![],
             enclosure_stack: vec![],
             indent_stack: vec![BlockIndent {
@@ -57,6 +78,7 @@ impl<'a> Parser<'a> {
                 indent_span: Span::zero(file_id),
             }],
             indent_style: None,
+            depth: 0,
             diagnostics: vec![],
         }
     }
@@ -113,7 +135,43 @@ impl<'a> Parser<'a> {
     }
 
     fn next_raw(&mut self) -> Option<Token<'a>> {
-        self.buffered.pop().or_else(|| self.lexer.next())
+        self.buffered.pop().or_else(|| self.lex())
+    }
+
+    /// Takes the next token from the lexer. Names that contain non-ASCII
+    /// characters are reported here, once per token, and are still parsed as
+    /// names so that parsing can go on.
+    fn lex(&mut self) -> Option<Token<'a>> {
+        let tok = self.lexer.next()?;
+        if tok.kind == TokenKind::Name && !tok.text.is_ascii() {
+            self.non_ascii_name_error(&tok);
+        }
+        Some(tok)
+    }
+
+    fn non_ascii_name_error(&mut self, tok: &Token<'a>) {
+        let (offset, c) = tok
+            .text
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii())
+            .expect("name is ASCII");
+        let start = tok.span.start + offset;
+        let mut notes =
+            vec!["Note: identifiers can only contain ASCII letters, digits and `_`".to_string()];
+        if let Some(ascii) = lexer::confusable_ascii(c) {
+            notes.push(format!(
+                "Hint: `{}` looks like the ASCII character `{}`, but it's a different character",
+                c, ascii
+            ));
+        }
+        self.fancy_error(
+            format!("non-ASCII character in identifier `{}`", tok.text),
+            vec![Label::primary(
+                Span::new(self.file_id, start, start + c.len_utf8()),
+                format!("`{}` (U+{:04X}) isn't an ASCII character", c, c as u32),
+            )],
+            notes,
+        );
     }
 
     /// Take a peek at the next token kind without consuming it, or return an
@@ -158,7 +216,7 @@ impl<'a> Parser<'a> {
 
     fn peek_raw(&mut self) -> Option<TokenKind> {
         if self.buffered.is_empty() {
-            if let Some(tok) = self.lexer.next() {
+            if let Some(tok) = self.lex() {
                 self.buffered.push(tok);
             } else {
                 return None;
@@ -192,6 +250,32 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Run the given parsing function one nesting level deeper. If this would
+    /// exceed [`MAX_NESTING_DEPTH`], an error is emitted at the next token and
+    /// parsing fails.
+    pub fn nested<T>(
+        &mut self,
+        parse_fn: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            self.peek_or_err()?;
+            let span = self.buffered.last().unwrap().span;
+            self.fancy_error(
+                "expression too deeply nested",
+                vec![Label::primary(span, "nesting limit reached here")],
+                vec![format!(
+                    "Note: expressions and blocks may be nested at most {} levels deep",
+                    MAX_NESTING_DEPTH
+                )],
+            );
+            return Err(ParseFailed);
+        }
+        self.depth += 1;
+        let result = parse_fn(self);
+        self.depth -= 1;
+        result
+    }
+
     /// Returns `true` if the parser has reached the end of the file.
     pub fn done(&mut self) -> bool {
         self.peek_raw() == None
@@ -426,6 +510,7 @@ impl<'a> Parser<'a> {
     pub fn error<S: Into<String>>(&mut self, span: Span, message: S) {
         self.diagnostics.push(Diagnostic {
             severity: Severity::Error,
+            code: None,
             message: message.into(),
             labels: vec![Label::primary(span, "")],
             notes: vec![],
@@ -442,6 +527,7 @@ impl<'a> Parser<'a> {
     ) {
         self.diagnostics.push(Diagnostic {
             severity: Severity::Error,
+            code: None,
             message: message.into(),
             labels,
             notes,
@@ -496,6 +582,7 @@ impl<'a, 'b> BTParser<'a, 'b> {
             enclosure_stack: snapshot.enclosure_stack.clone(),
             indent_stack: snapshot.indent_stack.clone(),
             indent_style: snapshot.indent_style,
+            depth: snapshot.depth,
             diagnostics: Vec::new(),
         };
         Self { snapshot, parser }
@@ -511,7 +598,7 @@ impl<'a, 'b> BTParser<'a, 'b> {
     }
 }
 
-impl<'a, 'b> std::ops::Deref for BTParser<'a, 'b> {
+impl<'a, 'b> core::ops::Deref for BTParser<'a, 'b> {
     type Target = Parser<'a>;
 
     fn deref(&self) -> &Self::Target {
@@ -519,7 +606,7 @@ impl<'a, 'b> std::ops::Deref for BTParser<'a, 'b> {
     }
 }
 
-impl<'a, 'b> std::ops::DerefMut for BTParser<'a, 'b> {
+impl<'a, 'b> core::ops::DerefMut for BTParser<'a, 'b> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.parser
     }
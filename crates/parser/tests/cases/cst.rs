@@ -0,0 +1,59 @@
+use fe_common::files::SourceFileId;
+use fe_parser::cst::module_cst;
+use fe_parser::parse_file;
+use fe_test_files::fixture;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn print_cst(src: &str) -> String {
+    let (module, _) =
+        parse_file(SourceFileId::default(), src).expect("failed to parse source file");
+    module_cst(SourceFileId::default(), src, &module).to_string()
+}
+
+macro_rules! test_lossless {
+    ($name:ident, $path:expr) => {
+        #[test]
+        #[wasm_bindgen_test]
+        fn $name() {
+            let src = fixture($path);
+            let (module, _) =
+                parse_file(SourceFileId::default(), src).expect("failed to parse source file");
+            let cst = module_cst(SourceFileId::default(), src, &module);
+            pretty_assertions::assert_eq!(src, cst.text())
+        }
+    };
+}
+
+test_lossless! { erc20, "demos/erc20_token.fe" }
+test_lossless! { guest_book, "demos/guest_book.fe" }
+test_lossless! { match_stmt, "printing/match.fe" }
+test_lossless! { slice_expr, "printing/slice_expr.fe" }
+test_lossless! { inline_yul, "printing/yul.fe" }
+test_lossless! { generics, "printing/generics.fe" }
+test_lossless! { traits, "printing/traits.fe" }
+
+#[test]
+#[wasm_bindgen_test]
+fn constant() {
+    let cst = print_cst("const X: u256 = 1 # one\n");
+    for line in [
+        "(source_file [0, 0] - [1, 0]",
+        "  item: (constant [0, 0] - [0, 17]",
+        "    \"const\"",
+        "    (whitespace \" \")",
+        "    name: (name [0, 6] - [0, 7] \"X\")",
+        "    \":\"",
+        "    type: (type_name [0, 9] - [0, 13] \"u256\")",
+        "    value: (number [0, 16] - [0, 17] \"1\"))",
+        "  (whitespace \" \")",
+        "  (comment \"# one\")",
+        "  (newline \"\\n\"))",
+    ] {
+        assert!(
+            cst.lines().any(|printed| printed == line),
+            "missing `{}` in:\n{}",
+            line,
+            cst
+        );
+    }
+}
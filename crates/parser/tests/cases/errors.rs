@@ -79,7 +79,6 @@ test_parse_err! { type_desc_path_number, module::parse_module, true, "type Foo =
 test_parse_err! { module_pub_event, module::parse_module, false, "pub event E:\n  x: u8" }
 test_parse_err! { contract_pub_event, module::parse_module, false, "contract C:\n pub event E:\n  x: u8" }
 test_parse_err! { contract_const_pub, module::parse_module, false, "contract C:\n const pub x: u8" }
-test_parse_err! { contract_const_fn, module::parse_module, false, "contract C:\n const fn f():\n  pass" }
 test_parse_err! { emit_no_args, functions::parse_stmt, true, "emit x" }
 test_parse_err! { emit_expr, functions::parse_stmt, true, "emit x + 1" }
 test_parse_err! { emit_bad_call, functions::parse_stmt, true, "emit MyEvent(1)()" }
@@ -90,12 +89,16 @@ test_parse_err! { expr_dotted_number, expressions::parse_expr, true, "3.14" }
 test_parse_err! { for_no_in, functions::parse_stmt, true, "for x:\n pass" }
 test_parse_err! { fn_no_args, module::parse_module, false, "fn f:\n  return 5" }
 test_parse_err! { fn_unsafe_pub, module::parse_module, false, "unsafe pub fn f():\n  return 5" }
+test_parse_err! { fn_generic_param_not_a_name, module::parse_module, true, "fn f<1>():\n  return 5" }
+test_parse_err! { non_ascii_name, module::parse_module, false, "fn café():\n  return 5" }
+test_parse_err! { confusable_name, module::parse_module, false, "fn f(\u{430}mount: u256):\n  return 5" }
 test_parse_err! { fn_def_kw, module::parse_module, true, "contract C:\n pub def f(x: u8):\n  return x" }
 test_parse_err! { if_no_body, functions::parse_stmt, true, "if x:\nelse:\n x" }
 test_parse_err! { use_bad_name, module::parse_use, true, "use x as 123" }
 test_parse_err! { module_bad_stmt, module::parse_module, true, "if x:\n y" }
 test_parse_err! { module_nonsense, module::parse_module, true, "))" }
 test_parse_err! { struct_bad_field_name, module::parse_module, true, "struct f:\n pub event" }
+test_parse_err! { struct_const_field, module::parse_module, false, "struct S:\n const x: u8" }
 test_parse_err! { stmt_vardecl_attr, functions::parse_stmt, true, "f.s : u" }
 test_parse_err! { stmt_vardecl_tuple, functions::parse_stmt, true, "(a, x+1) : u256" }
 test_parse_err! { stmt_vardecl_tuple_empty, functions::parse_stmt, true, "(a, ()) : u256" }
@@ -126,3 +129,17 @@ fn string_invalid_escape() {
     );
     assert_snapshot!(err);
 }
+
+#[test]
+fn expr_too_deeply_nested() {
+    let nested = |depth| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+
+    err_string("expr_nested", expressions::parse_expr, false, &nested(100));
+    let err = err_string(
+        "expr_too_deeply_nested",
+        expressions::parse_expr,
+        true,
+        &nested(200),
+    );
+    assert!(err.contains("expression too deeply nested"));
+}
@@ -1,3 +1,4 @@
+mod cst;
 mod errors;
 mod parse_ast;
 mod print_ast;
@@ -62,6 +62,8 @@ test_parse! { expr_call1, expressions::parse_expr, "foo()" }
 test_parse! { expr_call2, expressions::parse_expr, "foo(1,2,x=3)" }
 test_parse! { expr_attr1, expressions::parse_expr, "foo.bar[0][y]" }
 test_parse! { expr_attr2, expressions::parse_expr, "a[x].b[y](1)" }
+test_parse! { expr_slice1, expressions::parse_expr, "x[1:n]" }
+test_parse! { expr_slice2, expressions::parse_expr, "x[:2][0]" }
 test_parse! { expr_num1, expressions::parse_expr, "12345" }
 test_parse! { expr_num2, expressions::parse_expr, "00001" }
 test_parse! { expr_hex1, expressions::parse_expr, "0xbeefbeef" }
@@ -117,6 +119,7 @@ test_parse! { stmt_for, functions::parse_stmt, "for a in b[0]:\n pass" }
 test_parse! { stmt_var_decl_name, functions::parse_stmt, "let foo: u256 = 1" }
 test_parse! { stmt_var_decl_tuple, functions::parse_stmt, "let (foo, bar): (u256, u256) = (10, 10)" }
 test_parse! { stmt_var_decl_tuples, functions::parse_stmt, "let (a, (b, (c, d))): x" }
+test_parse! { stmt_yul, functions::parse_stmt, "yul { x := add(x, 1) }" }
 test_parse! { type_def, module::parse_module, "type X = Map<address, u256>" }
 test_parse! { pub_type_def, module::parse_module, "pub type X = Map<address, u256>" }
 test_parse! { type_name, types::parse_type_desc, "MyType" }
@@ -136,6 +139,9 @@ test_parse! { fn_def, module::parse_module, "fn foo21(x: bool, y: address,) -> b
 test_parse! { fn_def_pub, module::parse_module, "pub fn foo21(x: bool, y: address,) -> bool:\n x"}
 test_parse! { fn_def_unsafe, module::parse_module, "unsafe fn foo21(x: bool, y: address,) -> bool:\n x"}
 test_parse! { fn_def_pub_unsafe, module::parse_module, "pub unsafe fn foo21(x: bool, y: address,) -> bool:\n x"}
+test_parse! { fn_def_pub_const, module::parse_module, "pub const fn foo21(x: bool, y: address,) -> bool:\n x"}
+test_parse! { fn_def_named_outputs, module::parse_module, "fn f() -> (amount: u256, ok: bool):\n pass"}
+test_parse! { fn_def_generic, module::parse_module, "fn f<T, U: Numeric>(x: T):\n pass"}
 test_parse! { event_def, module::parse_module, "event Foo:\n  x: address\n  idx y: u8" }
 test_parse! { empty_event_def, module::parse_module, "event Foo:\n  pass" }
 test_parse! { pub_event_def, module::parse_module, "event Foo:\n  x: address\n  idx y: u8" }
@@ -146,6 +152,7 @@ test_parse! { pragma3, module::parse_pragma, "pragma >= 1.2, < 1.5" }
 test_parse! { use_simple1, module::parse_use, "use foo::bar" }
 test_parse! { use_simple2, module::parse_use, "use foo::bar as baz" }
 test_parse! { use_glob, module::parse_use, "use foo::bar::*" }
+test_parse! { use_abi, module::parse_abi_import, r#"use abi "./erc20_abi.json" as ERC20"# }
 test_parse! { use_nested1, module::parse_use, "use foo::bar::{bing::*, bang::big, bass as fish, bong::{hello as hi, goodbye}}" }
 test_parse! { use_nested2, module::parse_use, r#"use std::bar::{
     bing::*,
@@ -167,6 +174,13 @@ test_parse! { struct_def, module::parse_module, r#"struct S:
 test_parse! { empty_struct_def, module::parse_module, r#"struct S:
   pass
 "# }
+test_parse! { struct_def_generic, module::parse_module, r#"struct Pair<T>:
+  a: T
+  b: T
+"# }
+
+test_parse! { enum_def, module::parse_module, "enum State: Pending, Active, Closed" }
+test_parse! { error_def, module::parse_module, "error Unauthorized(caller: address)" }
 
 test_parse! { contract_def, module::parse_module, r#"contract Foo:
   x: address
@@ -187,6 +201,37 @@ test_parse! { pub_contract_def, module::parse_module, r#"pub contract Foo:
       return 10
 "# }
 
+test_parse! { facet_contract_def, module::parse_module, r#"contract Counter for AppStorage:
+    pass
+"# }
+
+test_parse! { upgradeable_contract_def, module::parse_module, r#"pub upgradeable contract Token:
+    pass
+"# }
+
+test_parse! { multicall_contract_def, module::parse_module, r#"multicall upgradeable contract Token:
+    pass
+"# }
+
+test_parse! { ownable_contract_def, module::parse_module, r#"pub ownable pausable contract Token:
+    pass
+"# }
+
+test_parse! { role_guard_contract_def, module::parse_module, r#"roles contract Token:
+    only_role("MINTER") pub fn mint(self):
+        pass
+"# }
+
+test_parse! { test_fn_contract_def, module::parse_module, r#"contract Foo:
+    test: u256
+    test pub fn count(self):
+        pass
+"# }
+
+test_parse! { interface_def, module::parse_module, r#"interface Token:
+    pub fn total_supply(self) -> u256
+"# }
+
 test_parse! { module_stmts, module::parse_module, r#"
 pragma 0.5.0
 
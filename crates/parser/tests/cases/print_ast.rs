@@ -24,3 +24,9 @@ test_print! { erc20, "demos/erc20_token.fe" }
 test_print! { guest_book, "printing/guest_book_no_comments.fe" }
 test_print! { expr_parens, "printing/expr_parens.fe" }
 test_print! { defs, "printing/defs.fe" }
+test_print! { match_stmt, "printing/match.fe" }
+test_print! { try_expr, "printing/try_expr.fe" }
+test_print! { slice_expr, "printing/slice_expr.fe" }
+test_print! { inline_yul, "printing/yul.fe" }
+test_print! { generics, "printing/generics.fe" }
+test_print! { traits, "printing/traits.fe" }
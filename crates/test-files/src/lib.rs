@@ -1,5 +1,6 @@
 use fe_common::files::{FileLoader, FileStore};
 use include_dir::{include_dir, Dir};
+use std::io;
 use std::path::Path;
 
 const FIXTURES: Dir = include_dir!("$CARGO_MANIFEST_DIR/fixtures");
@@ -23,17 +24,27 @@ struct FixtureLoader {}
 
 impl FileLoader for FixtureLoader {
     fn load_file(&self, path: &Path) -> std::io::Result<String> {
-        Ok(FIXTURES
-            .get_file(path)
-            .unwrap_or_else(|| panic!("bad fixture file path {:?}", path))
+        let file = FIXTURES.get_file(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no fixture file at {}", path.display()),
+            )
+        })?;
+        Ok(file
             .contents_utf8()
             .expect("fixture file isn't utf8")
             .to_string())
     }
 }
 
+/// An empty file store that loads files from the fixtures, e.g. the ABI that
+/// a fixture imports with `use abi`.
+pub fn new_filestore() -> FileStore {
+    FileStore::with_loader(Box::new(FixtureLoader {}))
+}
+
 pub fn build_filestore(path: &str) -> FileStore {
-    let mut files = FileStore::with_loader(Box::new(FixtureLoader {}));
+    let mut files = new_filestore();
 
     for path in all_file_paths_in_fixture_dir(path) {
         files.load_file(&path).unwrap();
@@ -0,0 +1,169 @@
+//! Gas snapshots, which record the gas used by each passing test so that
+//! later runs can check it for regressions. A snapshot has a line per test,
+//! sorted by name, eg.
+//!
+//! ```text
+//! CounterTest::increment (gas: 45219)
+//! CounterTest::reset (gas: 21874)
+//! ```
+
+use crate::{Outcome, TestResult};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The name of the snapshot file that `fe test --gas-snapshot` maintains.
+pub const GAS_SNAPSHOT_FILE_NAME: &str = ".gas-snapshot";
+
+/// The gas used by each test, keyed by `Contract::test`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GasSnapshot(pub BTreeMap<String, u64>);
+
+/// A test that used more gas than its snapshot allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasRegression {
+    pub test: String,
+    pub snapshot: u64,
+    pub gas_used: u64,
+}
+
+impl GasSnapshot {
+    /// The snapshot of the tests that passed. Failed tests are left out, as
+    /// the gas they used until failing says little about the code.
+    pub fn from_results(results: &[TestResult]) -> Self {
+        Self(
+            results
+                .iter()
+                .filter(|result| result.outcome == Outcome::Passed)
+                .map(|result| (test_key(result), result.gas_used))
+                .collect(),
+        )
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut snapshot = BTreeMap::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = line
+                .strip_suffix(')')
+                .and_then(|line| line.split_once(" (gas: "))
+                .and_then(|(test, gas)| Some((test, gas.parse::<u64>().ok()?)));
+            match entry {
+                Some((test, gas)) => {
+                    snapshot.insert(test.to_string(), gas);
+                }
+                None => return Err(format!("invalid entry on line {}: `{}`", number + 1, line)),
+            }
+        }
+        Ok(Self(snapshot))
+    }
+
+    /// The tests in `results` that used more than `threshold` percent more
+    /// gas than recorded in the snapshot. Tests that aren't in the snapshot
+    /// or that failed aren't checked.
+    pub fn regressions(&self, results: &[TestResult], threshold: f64) -> Vec<GasRegression> {
+        results
+            .iter()
+            .filter(|result| result.outcome == Outcome::Passed)
+            .filter_map(|result| {
+                let test = test_key(result);
+                let snapshot = *self.0.get(&test)?;
+                let allowed = snapshot as f64 * (1.0 + threshold / 100.0);
+                if result.gas_used as f64 > allowed {
+                    Some(GasRegression {
+                        test,
+                        snapshot,
+                        gas_used: result.gas_used,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for GasSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (test, gas) in &self.0 {
+            writeln!(f, "{} (gas: {})", test, gas)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GasRegression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let increase = (self.gas_used - self.snapshot) as f64 * 100.0 / self.snapshot.max(1) as f64;
+        write!(
+            f,
+            "{} used {} gas, up from {} (+{:.2}%)",
+            self.test, self.gas_used, self.snapshot, increase
+        )
+    }
+}
+
+fn test_key(result: &TestResult) -> String {
+    format!("{}::{}", result.contract, result.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GasRegression, GasSnapshot};
+    use crate::{Outcome, TestResult};
+
+    fn result(name: &str, outcome: Outcome, gas_used: u64) -> TestResult {
+        TestResult {
+            contract: "CounterTest".into(),
+            name: name.into(),
+            outcome,
+            gas_used,
+            dbg_values: vec![],
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let results = [
+            result("reset", Outcome::Passed, 21874),
+            result("increment", Outcome::Passed, 45219),
+            result("overflow", Outcome::Failed("reverted".into()), 100),
+        ];
+        let snapshot = GasSnapshot::from_results(&results);
+        let content = snapshot.to_string();
+        assert_eq!(
+            content,
+            "CounterTest::increment (gas: 45219)\nCounterTest::reset (gas: 21874)\n"
+        );
+        assert_eq!(GasSnapshot::parse(&content), Ok(snapshot));
+        assert!(GasSnapshot::parse("CounterTest::reset 21874").is_err());
+    }
+
+    #[test]
+    fn regressions() {
+        let snapshot = GasSnapshot::parse(
+            "CounterTest::test_a (gas: 1000)\nCounterTest::test_b (gas: 1000)\n",
+        )
+        .unwrap();
+        let results = [
+            result("test_a", Outcome::Passed, 1050),
+            result("test_b", Outcome::Passed, 1200),
+            result("test_c", Outcome::Passed, 5000),
+        ];
+
+        assert_eq!(
+            snapshot.regressions(&results, 10.0),
+            vec![GasRegression {
+                test: "CounterTest::test_b".into(),
+                snapshot: 1000,
+                gas_used: 1200,
+            }]
+        );
+        assert_eq!(snapshot.regressions(&results, 0.0).len(), 2);
+        assert_eq!(
+            snapshot.regressions(&results, 0.0)[1].to_string(),
+            "CounterTest::test_b used 1200 gas, up from 1000 (+20.00%)"
+        );
+    }
+}
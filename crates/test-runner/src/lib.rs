@@ -0,0 +1,537 @@
+//! Runs the tests of compiled Fe contracts in an in-memory EVM.
+//!
+//! A test is a public function of a contract with the `test` qualifier,
+//! which takes no arguments, eg.
+//!
+//! ```fe
+//! contract CounterTest:
+//!   test pub fn increment():
+//!     let counter: Counter = Counter.create(0)
+//!     counter.increment()
+//!     assert counter.get() == 1, "count should be 1"
+//! ```
+//!
+//! Other functions are never run as tests, whatever their names.
+//!
+//! The tests of a contract are a suite. The contract is deployed once per
+//! suite, by [`DEFAULT_CALLER`] and without constructor arguments, and the
+//! state of the chain after the deployment is snapshotted. Each test starts
+//! from that snapshot, so expensive setup in the constructor, like deploying
+//! a protocol, is done once, and the tests don't see each other's changes.
+//! A test passes if the call succeeds, and fails if deploying the contract or
+//! calling the test reverts. The gas used by the tests can be checked against
+//! a [`GasSnapshot`].
+//!
+//! The values that a test passes to `dbg` are recorded in its [`TestResult`],
+//! if the contract was compiled with the `RecordDbg` plugin of the driver,
+//! which makes `dbg` call the [`DBG_RECORDER`] address. The runner's EVM
+//! intercepts these calls.
+//!
+//! Tests can change the chain with the cheatcodes of `std::cheats`, which
+//! call the [`CHEATCODES`] address:
+//!
+//! - `warp(timestamp)` and `roll(number)` set `block.timestamp` and
+//!   `block.number`.
+//! - After `expect_revert()`, the test passes if it reverts, and fails if it
+//!   doesn't.
+//! - After `expect_emit()`, the next event that the test emits itself is
+//!   expected to be emitted by another contract afterwards. `expect_emit`
+//!   marks the event with a log whose only topic is [`CHEATCODES`].
+//!
+//! The EVM's precompile hook, which intercepts the calls, can't change the
+//! caller of a call or the balance of an account that the test already
+//! touched, or snapshot and restore the state within a call, so there's no
+//! cheatcode to do any of these.
+
+use evm::backend::{
+    ApplyBackend, Backend, Basic, Log, MemoryAccount, MemoryBackend, MemoryVicinity,
+};
+use evm::executor::{MemoryStackState, StackExecutor, StackSubstateMetadata};
+use evm_runtime::{Capture, Context, CreateScheme, ExitError, ExitReason, ExitSucceed, Handler};
+use primitive_types::{H160, H256, U256};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+
+mod gas_snapshot;
+
+pub use gas_snapshot::{GasRegression, GasSnapshot, GAS_SNAPSHOT_FILE_NAME};
+
+/// The address that deploys test contracts and calls their tests.
+pub const DEFAULT_CALLER: &str = "1000000000000000000000000000000000000001";
+
+/// The address that `dbg` calls with the values it records.
+pub const DBG_RECORDER: u64 = 0xfe0db;
+/// The address that the cheatcodes of `std::cheats` call.
+pub const CHEATCODES: u64 = 0xfec0de;
+
+/// The codes of the cheatcodes, which are the first word of their input.
+const WARP: u64 = 1;
+const ROLL: u64 = 2;
+const EXPECT_REVERT: u64 = 3;
+
+thread_local! {
+    /// The state of the running test that the intercepted calls change.
+    static STATE: RefCell<TestState> = RefCell::new(TestState::default());
+}
+
+#[derive(Default)]
+struct TestState {
+    dbg_values: Vec<DbgValue>,
+    /// The block timestamp and number set by `warp` and `roll`.
+    block_timestamp: U256,
+    block_number: U256,
+    /// Whether the test called `expect_revert`.
+    expects_revert: bool,
+}
+
+/// The selector of `Error(string)`, which `assert` and `revert` use for
+/// messages.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// The selector of `Panic(uint256)`, which failed checks like overflows use.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// A compiled contract that may contain tests.
+pub struct TestContract<'a> {
+    pub name: &'a str,
+    pub json_abi: &'a str,
+    /// The deployment bytecode, as hex.
+    pub bytecode: &'a str,
+    /// The names of the contract's tests, which the compiler reports for each
+    /// contract.
+    pub tests: &'a [String],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub contract: String,
+    pub name: String,
+    pub outcome: Outcome,
+    /// The gas used by the call to the test, which excludes deploying the
+    /// contract.
+    pub gas_used: u64,
+    /// The values passed to `dbg` while deploying the contract of the suite
+    /// and running the test, in the order they were recorded.
+    pub dbg_values: Vec<DbgValue>,
+}
+
+/// A value passed to `dbg`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbgValue {
+    /// The id of the source file of the value's expression, as in
+    /// `fe_common::files::SourceFileId`.
+    pub file: u128,
+    /// The byte offsets of the value's expression in the file.
+    pub start: usize,
+    pub end: usize,
+    /// The value, formatted for its type, eg. `-1`, `true` or
+    /// `0x1000000000000000000000000000000000000001`.
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The test failed, for the given reason.
+    Failed(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "ok"),
+            Outcome::Failed(reason) => write!(f, "FAILED: {}", reason),
+        }
+    }
+}
+
+/// Loads a JSON ABI without its custom errors, which ethabi doesn't support yet.
+fn load_abi(json_abi: &str) -> Result<ethabi::Contract, String> {
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(json_abi).map_err(|err| err.to_string())?;
+    let entries = entries
+        .into_iter()
+        .filter(|entry| entry["type"] != "error")
+        .collect::<Vec<_>>();
+    let json_abi = serde_json::Value::Array(entries).to_string();
+    ethabi::Contract::load(json_abi.as_bytes()).map_err(|err| err.to_string())
+}
+
+/// Runs every test of the contracts, in the order they're declared.
+/// Contracts without tests are skipped.
+pub fn run(contracts: &[TestContract]) -> Vec<TestResult> {
+    let vicinity = vicinity();
+    let mut results = vec![];
+    for contract in contracts
+        .iter()
+        .filter(|contract| !contract.tests.is_empty())
+    {
+        STATE.with(|state| *state.borrow_mut() = TestState::default());
+        let suite = deploy(contract, &vicinity);
+        let setup_dbg_values = STATE.with(|state| state.take()).dbg_values;
+
+        for name in contract.tests {
+            STATE.with(|state| {
+                *state.borrow_mut() = TestState {
+                    dbg_values: setup_dbg_values.clone(),
+                    ..TestState::default()
+                }
+            });
+            let (outcome, gas_used) = match &suite {
+                Ok(suite) => run_test(suite, &vicinity, name),
+                Err(reason) => (Outcome::Failed(reason.clone()), 0),
+            };
+            results.push(TestResult {
+                contract: contract.name.to_string(),
+                name: name.clone(),
+                outcome,
+                gas_used,
+                dbg_values: STATE.with(|state| state.take()).dbg_values,
+            });
+        }
+    }
+    results
+}
+
+/// A deployed test contract, and the snapshot of the chain that its tests
+/// start from.
+struct Suite {
+    abi: ethabi::Contract,
+    address: H160,
+    state: BTreeMap<H160, MemoryAccount>,
+}
+
+fn vicinity() -> MemoryVicinity {
+    MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: DEFAULT_CALLER.parse().expect("invalid caller"),
+        chain_id: U256::one(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::zero(),
+        block_difficulty: U256::zero(),
+        block_gas_limit: U256::MAX,
+    }
+}
+
+/// Deploys the contract on an empty chain and snapshots the chain, or returns
+/// why every test of the contract fails.
+fn deploy(contract: &TestContract, vicinity: &MemoryVicinity) -> Result<Suite, String> {
+    let abi = load_abi(contract.json_abi).expect("invalid ABI");
+    if abi
+        .constructor
+        .as_ref()
+        .map_or(false, |constructor| !constructor.inputs.is_empty())
+    {
+        return Err("test contracts can't take constructor arguments".into());
+    }
+    let bytecode = hex::decode(contract.bytecode).expect("invalid bytecode");
+    let caller = vicinity.origin;
+
+    let mut backend = TestBackend(MemoryBackend::new(vicinity, BTreeMap::new()));
+    let config = evm::Config::istanbul();
+    let state = MemoryStackState::new(StackSubstateMetadata::new(u64::MAX, &config), &backend);
+    let mut executor = StackExecutor::new_with_precompile(state, &config, intercept);
+
+    let address = match executor.create(
+        caller,
+        CreateScheme::Legacy { caller },
+        U256::zero(),
+        bytecode,
+        None,
+    ) {
+        Capture::Exit((ExitReason::Succeed(_), Some(address), _)) => address,
+        Capture::Exit((reason, _, output)) => {
+            return Err(format!(
+                "deploying `{}` {}",
+                contract.name,
+                failure(&reason, &output)
+            ))
+        }
+        Capture::Trap(_) => unreachable!("the executor doesn't trap"),
+    };
+
+    let (values, logs) = executor.into_state().deconstruct();
+    backend.0.apply(values, logs, false);
+    Ok(Suite {
+        abi,
+        address,
+        state: backend.0.state().clone(),
+    })
+}
+
+/// Runs a test on a copy of the snapshot of its suite.
+fn run_test(suite: &Suite, vicinity: &MemoryVicinity, name: &str) -> (Outcome, u64) {
+    let backend = TestBackend(MemoryBackend::new(vicinity, suite.state.clone()));
+    let config = evm::Config::istanbul();
+    let state = MemoryStackState::new(StackSubstateMetadata::new(u64::MAX, &config), &backend);
+    let mut executor = StackExecutor::new_with_precompile(state, &config, intercept);
+
+    let input = suite.abi.functions[name][0]
+        .encode_input(&[])
+        .expect("failed to encode the test call");
+    let context = Context {
+        address: suite.address,
+        caller: vicinity.origin,
+        apparent_value: U256::zero(),
+    };
+    let gas_before = executor.used_gas();
+    let capture = executor.call(suite.address, None, input, None, false, context);
+    let gas_used = executor.used_gas() - gas_before;
+
+    let expects_revert = STATE.with(|state| state.borrow().expects_revert);
+    let outcome = match capture {
+        Capture::Exit((ExitReason::Succeed(_), _)) if expects_revert => {
+            Outcome::Failed("expected a revert".into())
+        }
+        Capture::Exit((ExitReason::Succeed(_), _)) => {
+            let (_, logs) = executor.into_state().deconstruct();
+            match check_expected_emits(&logs.into_iter().collect::<Vec<_>>(), suite.address) {
+                Ok(()) => Outcome::Passed,
+                Err(reason) => Outcome::Failed(reason),
+            }
+        }
+        Capture::Exit((ExitReason::Revert(_), _)) if expects_revert => Outcome::Passed,
+        Capture::Exit((reason, output)) => Outcome::Failed(failure(&reason, &output)),
+        Capture::Trap(_) => unreachable!("the executor doesn't trap"),
+    };
+    (outcome, gas_used)
+}
+
+/// Checks that each event marked by `expect_emit` in the logs of the test
+/// contract at `address` was emitted by another contract afterwards.
+fn check_expected_emits(logs: &[Log], address: H160) -> Result<(), String> {
+    let marker = vec![H256::from_low_u64_be(CHEATCODES)];
+    let mut logs = logs.iter();
+    while let Some(log) = logs.next() {
+        if log.address != address || log.topics != marker {
+            continue;
+        }
+        let expected = logs
+            .find(|log| log.address == address)
+            .ok_or("`expect_emit` isn't followed by an expected event")?;
+        match logs.find(|log| log.address != address) {
+            Some(log) if log.topics == expected.topics && log.data == expected.data => {}
+            Some(_) => return Err("emitted a different event than expected".into()),
+            None => return Err("didn't emit the expected event".into()),
+        }
+    }
+    Ok(())
+}
+
+/// The backend of the test EVM, with the block timestamp and number set by
+/// the cheatcodes.
+struct TestBackend<'vicinity>(MemoryBackend<'vicinity>);
+
+impl Backend for TestBackend<'_> {
+    fn gas_price(&self) -> U256 {
+        self.0.gas_price()
+    }
+    fn origin(&self) -> H160 {
+        self.0.origin()
+    }
+    fn block_hash(&self, number: U256) -> H256 {
+        self.0.block_hash(number)
+    }
+    fn block_number(&self) -> U256 {
+        STATE.with(|state| state.borrow().block_number)
+    }
+    fn block_coinbase(&self) -> H160 {
+        self.0.block_coinbase()
+    }
+    fn block_timestamp(&self) -> U256 {
+        STATE.with(|state| state.borrow().block_timestamp)
+    }
+    fn block_difficulty(&self) -> U256 {
+        self.0.block_difficulty()
+    }
+    fn block_gas_limit(&self) -> U256 {
+        self.0.block_gas_limit()
+    }
+    fn chain_id(&self) -> U256 {
+        self.0.chain_id()
+    }
+    fn exists(&self, address: H160) -> bool {
+        self.0.exists(address)
+    }
+    fn basic(&self, address: H160) -> Basic {
+        self.0.basic(address)
+    }
+    fn code(&self, address: H160) -> Vec<u8> {
+        self.0.code(address)
+    }
+    fn storage(&self, address: H160, index: H256) -> H256 {
+        self.0.storage(address, index)
+    }
+    fn original_storage(&self, address: H160, index: H256) -> Option<H256> {
+        self.0.original_storage(address, index)
+    }
+}
+
+/// Intercepts the calls to [`DBG_RECORDER`] and [`CHEATCODES`].
+///
+/// The input of a `dbg` record is the kind of the value, the value itself and
+/// its location, as five words. The input of a cheatcode is its code and its
+/// argument, as two words.
+fn intercept(
+    address: H160,
+    input: &[u8],
+    _target_gas: Option<u64>,
+    _context: &Context,
+) -> Option<Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
+    let word = |index: usize| U256::from_big_endian(&input[index * 32..(index + 1) * 32]);
+    let recorded = if address == H160::from_low_u64_be(DBG_RECORDER) {
+        if input.len() != 160 {
+            return Some(Err(ExitError::Other("invalid `dbg` record".into())));
+        }
+        let value = DbgValue {
+            file: word(2).low_u128(),
+            start: word(3).low_u64() as usize,
+            end: word(4).low_u64() as usize,
+            value: format_dbg_value(word(0).low_u64(), word(1)),
+        };
+        STATE.with(|state| state.borrow_mut().dbg_values.push(value));
+        Ok(())
+    } else if address == H160::from_low_u64_be(CHEATCODES) {
+        if input.len() != 64 {
+            return Some(Err(ExitError::Other("invalid cheatcode".into())));
+        }
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            match word(0).low_u64() {
+                WARP => state.block_timestamp = word(1),
+                ROLL => state.block_number = word(1),
+                EXPECT_REVERT => state.expects_revert = true,
+                _ => return Err(ExitError::Other("unknown cheatcode".into())),
+            }
+            Ok(())
+        })
+    } else {
+        return None;
+    };
+    Some(recorded.map(|()| (ExitSucceed::Returned, vec![], 0)))
+}
+
+/// Formats a `dbg` value of the given kind: unsigned, signed, `bool` or
+/// `address`.
+fn format_dbg_value(kind: u64, value: U256) -> String {
+    match kind {
+        1 if value.bit(255) => format!("-{}", (!value).overflowing_add(U256::one()).0),
+        2 => (!value.is_zero()).to_string(),
+        3 => {
+            let mut bytes = [0; 32];
+            value.to_big_endian(&mut bytes);
+            format!("0x{}", hex::encode(&bytes[12..]))
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Describes why a call failed, decoding the revert data if possible.
+fn failure(reason: &ExitReason, output: &[u8]) -> String {
+    match reason {
+        ExitReason::Revert(_) => format!("reverted{}", revert_reason(output)),
+        reason => format!("failed with {:?}", reason),
+    }
+}
+
+/// The reason in the data of a revert, eg. `: "count should be 1"`, or an
+/// empty string if there's no data.
+fn revert_reason(data: &[u8]) -> String {
+    if data.is_empty() {
+        return String::new();
+    }
+    let (selector, args) = data.split_at(data.len().min(4));
+    if selector == ERROR_SELECTOR {
+        if let Ok(tokens) = ethabi::decode(&[ethabi::ParamType::String], args) {
+            if let Some(ethabi::Token::String(message)) = tokens.into_iter().next() {
+                return format!(": {:?}", message);
+            }
+        }
+    } else if selector == PANIC_SELECTOR && args.len() == 32 {
+        return format!(": panic 0x{:02x}", U256::from_big_endian(args).low_u64());
+    }
+    format!(" with 0x{}", hex::encode(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_expected_emits, format_dbg_value, revert_reason, CHEATCODES};
+    use evm::backend::Log;
+    use primitive_types::{H160, H256, U256};
+
+    #[test]
+    fn revert_reasons() {
+        assert_eq!(revert_reason(&[]), "");
+
+        let error = ethabi::encode(&[ethabi::Token::String("count should be 1".into())]);
+        assert_eq!(
+            revert_reason(&[&[0x08, 0xc3, 0x79, 0xa0][..], &error[..]].concat()),
+            ": \"count should be 1\""
+        );
+
+        let panic = ethabi::encode(&[ethabi::Token::Uint(0x11u64.into())]);
+        assert_eq!(
+            revert_reason(&[&[0x4e, 0x48, 0x7b, 0x71][..], &panic[..]].concat()),
+            ": panic 0x11"
+        );
+
+        assert_eq!(revert_reason(&[0xde, 0xad]), " with 0xdead");
+    }
+
+    #[test]
+    fn dbg_values() {
+        assert_eq!(format_dbg_value(0, U256::from(42)), "42");
+        assert_eq!(format_dbg_value(1, U256::from(42)), "42");
+        assert_eq!(format_dbg_value(1, U256::MAX), "-1");
+        assert_eq!(format_dbg_value(2, U256::one()), "true");
+        assert_eq!(format_dbg_value(2, U256::zero()), "false");
+        assert_eq!(
+            format_dbg_value(3, U256::from(0xfe0db)),
+            "0x00000000000000000000000000000000000fe0db"
+        );
+    }
+
+    #[test]
+    fn expected_emits() {
+        let test = H160::from_low_u64_be(1);
+        let token = H160::from_low_u64_be(2);
+        let log = |address: H160, topic: u64, data: &[u8]| Log {
+            address,
+            topics: vec![H256::from_low_u64_be(topic)],
+            data: data.to_vec(),
+        };
+        let marker = log(test, CHEATCODES, &[]);
+
+        assert_eq!(check_expected_emits(&[log(token, 7, &[1])], test), Ok(()));
+        assert_eq!(
+            check_expected_emits(
+                &[
+                    log(token, 7, &[0]),
+                    marker.clone(),
+                    log(test, 7, &[1]),
+                    log(test, 8, &[]),
+                    log(token, 7, &[1]),
+                ],
+                test
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_expected_emits(
+                &[marker.clone(), log(test, 7, &[1]), log(token, 7, &[2])],
+                test
+            ),
+            Err("emitted a different event than expected".into())
+        );
+        assert_eq!(
+            check_expected_emits(&[marker.clone(), log(test, 7, &[1])], test),
+            Err("didn't emit the expected event".into())
+        );
+        assert_eq!(
+            check_expected_emits(&[marker], test),
+            Err("`expect_emit` isn't followed by an expected event".into())
+        );
+    }
+}
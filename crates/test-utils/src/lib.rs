@@ -1,6 +1,5 @@
 use evm_runtime::{ExitReason, Handler};
 use fe_common::diagnostics::print_diagnostics;
-use fe_common::files::FileStore;
 use fe_common::utils::keccak;
 use fe_driver as driver;
 use fe_yulgen::runtime::functions;
@@ -258,10 +257,18 @@ pub fn encoded_over_or_underflow() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x11)])
 }
 
+pub fn encoded_panic_invalid_enum_value() -> Vec<u8> {
+    encode_revert("Panic(uint256)", &[uint_token(0x21)])
+}
+
 pub fn encoded_panic_out_of_bounds() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x32)])
 }
 
+pub fn encoded_panic_empty_array_pop() -> Vec<u8> {
+    encode_revert("Panic(uint256)", &[uint_token(0x31)])
+}
+
 pub fn encoded_div_or_mod_by_zero() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x12)])
 }
@@ -279,11 +286,19 @@ pub fn deploy_contract(
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
     let src = test_files::fixture(fixture);
-    let mut files = FileStore::new();
+    let mut files = test_files::new_filestore();
     let id = files.add_file(fixture, src);
     let deps = files.add_included_libraries();
 
-    let compiled_module = match driver::compile_module(&files, id, &deps, true, true) {
+    let compiled_module = match driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &driver::CompileOptions {
+            with_runtime_bytecode: false,
+            ..driver::CompileOptions::default()
+        },
+    ) {
         Ok(module) => module,
         Err(error) => {
             fe_common::diagnostics::print_diagnostics(&error.0, &files);
@@ -316,8 +331,16 @@ pub fn deploy_contract_from_ingot(
     let ingot_files = files.all_files();
     let deps = files.add_included_libraries();
 
-    let compiled_module = match driver::compile_ingot(path, &files, &ingot_files, &deps, true, true)
-    {
+    let compiled_module = match driver::compile_ingot(
+        path,
+        &files,
+        &ingot_files,
+        &deps,
+        &driver::CompileOptions {
+            with_runtime_bytecode: false,
+            ..driver::CompileOptions::default()
+        },
+    ) {
         Ok(module) => module,
         Err(error) => {
             fe_common::diagnostics::print_diagnostics(&error.0, &files);
@@ -325,9 +348,16 @@ pub fn deploy_contract_from_ingot(
         }
     };
 
+    // The contract may be in `main.fe` or in any of the other files.
     let compiled_contract = compiled_module
         .contracts
         .get(contract_name)
+        .or_else(|| {
+            compiled_module
+                .module_contracts
+                .values()
+                .find_map(|contracts| contracts.get(contract_name))
+        })
         .expect("could not find contract in fixture");
 
     _deploy_contract(
@@ -370,6 +400,7 @@ pub fn encode_revert(selector: &str, input: &[ethabi::Token]) -> Vec<u8> {
             ethabi::Token::Uint(val) => format!("{:0>64}", format!("{:x}", val)),
             ethabi::Token::Int(val) => format!("{:0>64}", format!("{:x}", val)),
             ethabi::Token::Bool(val) => format!("{:0>64x}", *val as i32),
+            ethabi::Token::Address(val) => format!("{:0>64}", hex::encode(val.as_bytes())),
             ethabi::Token::String(val) => {
                 const DATA_OFFSET: &str =
                     "0000000000000000000000000000000000000000000000000000000000000020";
@@ -401,13 +432,25 @@ fn get_function_selector(signature: &str) -> String {
     hex::encode(&keccak::full_as_bytes(signature.as_bytes())[..4])
 }
 
+/// Loads a JSON ABI, leaving out the custom errors that ethabi can't parse yet.
+fn load_abi(abi: &str) -> ethabi::Contract {
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(abi).expect("unable to parse the ABI");
+    let entries = entries
+        .into_iter()
+        .filter(|entry| entry["type"] != "error")
+        .collect::<Vec<_>>();
+    let abi = serde_json::Value::Array(entries).to_string();
+    ethabi::Contract::load(abi.as_bytes()).expect("unable to load the ABI")
+}
+
 fn _deploy_contract(
     executor: &mut Executor,
     bytecode: &str,
     abi: &str,
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
-    let abi = ethabi::Contract::load(abi.as_bytes()).expect("unable to load the ABI");
+    let abi = load_abi(abi);
 
     let mut bytecode = hex::decode(bytecode).expect("failed to decode bytecode");
 
@@ -522,11 +565,19 @@ pub fn compile_solidity_contract(
 
 #[allow(dead_code)]
 pub fn load_contract(address: H160, fixture: &str, contract_name: &str) -> ContractHarness {
-    let mut files = FileStore::new();
+    let mut files = test_files::new_filestore();
     let deps = files.add_included_libraries();
     let src = test_files::fixture(fixture);
     let id = files.add_file(fixture, src);
-    let compiled_module = match driver::compile_module(&files, id, &deps, true, true) {
+    let compiled_module = match driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &driver::CompileOptions {
+            with_runtime_bytecode: false,
+            ..driver::CompileOptions::default()
+        },
+    ) {
         Ok(module) => module,
         Err(err) => {
             print_diagnostics(&err.0, &files);
@@ -537,8 +588,7 @@ pub fn load_contract(address: H160, fixture: &str, contract_name: &str) -> Contr
         .contracts
         .get(contract_name)
         .expect("could not find contract in fixture");
-    let abi = ethabi::Contract::load(compiled_contract.json_abi.as_bytes())
-        .expect("unable to load the ABI");
+    let abi = load_abi(&compiled_contract.json_abi);
 
     ContractHarness::new(address, abi)
 }
@@ -652,7 +702,7 @@ impl ExecutionOutput {
 #[cfg(feature = "solc-backend")]
 fn execute_runtime_functions(executor: &mut Executor, runtime: &Runtime) -> (ExitReason, Vec<u8>) {
     let yul_code = runtime.to_yul().to_string().replace("\"", "\\\"");
-    let bytecode = fe_yulc::compile_single_contract("Contract", yul_code, false)
+    let bytecode = fe_yulc::compile_single_contract("Contract", yul_code, false, None)
         .expect("failed to compile Yul");
     let bytecode = hex::decode(&bytecode).expect("failed to decode bytecode");
 
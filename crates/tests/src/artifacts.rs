@@ -0,0 +1,46 @@
+use fe_driver::artifacts::{self, ArtifactError, SCHEMA_VERSION};
+
+#[test]
+fn unversioned_artifacts_are_migrated() {
+    let summary = artifacts::load(
+        r#"{ "status": "success", "exit_code": 0, "diagnostics": { "error": 0 } }"#,
+    )
+    .expect("failed to load summary");
+
+    assert_eq!(summary["schemaVersion"], SCHEMA_VERSION);
+    assert_eq!(summary["status"], "success");
+    assert_eq!(summary["diagnostics"]["error"], 0);
+}
+
+#[test]
+fn current_artifacts_are_loaded_unchanged() {
+    let content = format!(
+        r#"{{ "schemaVersion": {}, "errors": [], "contracts": {{}} }}"#,
+        SCHEMA_VERSION
+    );
+    let output = artifacts::load(&content).expect("failed to load output");
+
+    assert_eq!(artifacts::schema_version(&output), Ok(SCHEMA_VERSION));
+    assert_eq!(output, artifacts::load(&output.to_string()).unwrap());
+}
+
+#[test]
+fn invalid_artifacts_are_rejected() {
+    let newer = format!(r#"{{ "schemaVersion": {} }}"#, SCHEMA_VERSION + 1);
+    assert_eq!(
+        artifacts::load(&newer),
+        Err(ArtifactError::UnsupportedVersion(SCHEMA_VERSION + 1))
+    );
+    assert_eq!(
+        artifacts::load(r#"[{ "type": "function" }]"#),
+        Err(ArtifactError::NotAnObject)
+    );
+    assert!(matches!(
+        artifacts::load(r#"{ "schemaVersion": "1" }"#),
+        Err(ArtifactError::InvalidVersion(_))
+    ));
+    assert!(matches!(
+        artifacts::load("{"),
+        Err(ArtifactError::InvalidJson(_))
+    ));
+}
@@ -0,0 +1,46 @@
+use fe_common::diagnostics::Severity;
+use fe_common::files::FileStore;
+use fe_driver::{CancellationToken, CompileOptions, CompilePhase};
+
+#[test]
+fn cancelled_compilation_stops() {
+    let path = "demos/erc20_token.fe";
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, test_files::fixture(path));
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let error = fe_driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &CompileOptions {
+            with_bytecode: false,
+            with_runtime_bytecode: false,
+            optimize: false,
+            cancel: cancel.clone(),
+            ..CompileOptions::default()
+        },
+    )
+    .err()
+    .expect("the compilation wasn't cancelled");
+    assert_eq!(error.1, CompilePhase::Cancelled);
+    assert!(error.0.is_empty());
+
+    assert!(fe_driver::check_module(&files, id, &deps, &cancel).is_none());
+}
+
+#[test]
+fn uncancelled_check_finishes() {
+    let path = "demos/erc20_token.fe";
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, test_files::fixture(path));
+
+    let diagnostics = fe_driver::check_module(&files, id, &deps, &CancellationToken::new())
+        .expect("the check was cancelled");
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity != Severity::Error));
+}
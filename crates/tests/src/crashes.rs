@@ -11,7 +11,16 @@ macro_rules! test_file {
             let mut files = FileStore::new();
             let deps = files.add_included_libraries();
             let id = files.add_file(path, src);
-            fe_driver::compile_module(&files, id, &deps, true, true).ok();
+            fe_driver::compile_module(
+                &files,
+                id,
+                &deps,
+                &fe_driver::CompileOptions {
+                    with_runtime_bytecode: false,
+                    ..fe_driver::CompileOptions::default()
+                },
+            )
+            .ok();
         }
     };
 }
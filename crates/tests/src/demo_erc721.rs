@@ -0,0 +1,152 @@
+#![cfg(feature = "solc-backend")]
+
+use fe_compiler_test_utils::*;
+
+#[test]
+fn erc721_token() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "demos/erc721_token.fe", "ERC721", &[]);
+
+        let alice = DEFAULT_CALLER;
+        let bob = "2000000000000000000000000000000000000002";
+        let james = "3000000000000000000000000000000000000003";
+
+        // alice mints two tokens to herself
+        harness.test_function(
+            &mut executor,
+            "mint",
+            &[address_token(alice), uint_token(1)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "mint",
+            &[address_token(alice), uint_token(2)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "balanceOf",
+            &[address_token(alice)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(
+            &mut executor,
+            "ownerOf",
+            &[uint_token(1)],
+            Some(&address_token(alice)),
+        );
+
+        // a token can't be minted twice, and tokens that don't exist have no owner
+        harness.test_function_reverts(
+            &mut executor,
+            "mint",
+            &[address_token(bob), uint_token(1)],
+            &encoded_panic_assert(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "ownerOf",
+            &[uint_token(3)],
+            &encoded_panic_assert(),
+        );
+
+        // alice approves bob to transfer token 1
+        harness.test_function(
+            &mut executor,
+            "approve",
+            &[address_token(bob), uint_token(1)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "getApproved",
+            &[uint_token(1)],
+            Some(&address_token(bob)),
+        );
+
+        // bob transfers token 1 to james, but can't transfer token 2
+        harness.set_caller(address(bob));
+        harness.test_function(
+            &mut executor,
+            "transferFrom",
+            &[address_token(alice), address_token(james), uint_token(1)],
+            None,
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "transferFrom",
+            &[address_token(alice), address_token(james), uint_token(2)],
+            &encoded_panic_assert(),
+        );
+        harness.test_function(
+            &mut executor,
+            "ownerOf",
+            &[uint_token(1)],
+            Some(&address_token(james)),
+        );
+        harness.test_function(
+            &mut executor,
+            "getApproved",
+            &[uint_token(1)],
+            Some(&address_token("0")),
+        );
+        harness.test_function(
+            &mut executor,
+            "balanceOf",
+            &[address_token(alice)],
+            Some(&uint_token(1)),
+        );
+
+        // alice makes bob an operator for all of her tokens
+        harness.set_caller(address(alice));
+        harness.test_function(
+            &mut executor,
+            "setApprovalForAll",
+            &[address_token(bob), bool_token(true)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "isApprovedForAll",
+            &[address_token(alice), address_token(bob)],
+            Some(&bool_token(true)),
+        );
+        harness.set_caller(address(bob));
+        harness.test_function(
+            &mut executor,
+            "transferFrom",
+            &[address_token(alice), address_token(bob), uint_token(2)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "balanceOf",
+            &[address_token(bob)],
+            Some(&uint_token(1)),
+        );
+
+        // validate events
+        harness.events_emitted(
+            executor,
+            &[
+                (
+                    "Transfer",
+                    &[address_token("0"), address_token(alice), uint_token(1)],
+                ),
+                (
+                    "Approval",
+                    &[address_token(alice), address_token(bob), uint_token(1)],
+                ),
+                (
+                    "Transfer",
+                    &[address_token(alice), address_token(james), uint_token(1)],
+                ),
+                (
+                    "ApprovalForAll",
+                    &[address_token(alice), address_token(bob), bool_token(true)],
+                ),
+            ],
+        );
+    });
+}
@@ -138,6 +138,48 @@ fn test_revert() {
     })
 }
 
+#[test]
+fn custom_errors() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "custom_errors.fe", "Foo", &[]);
+        let stranger = "2000000000000000000000000000000000000002";
+
+        harness.test_function_reverts(
+            &mut executor,
+            "withdraw",
+            &[uint_token(5)],
+            &encode_revert(
+                "InsufficientBalance(uint256,uint256)",
+                &[uint_token(0), uint_token(5)],
+            ),
+        );
+        harness.test_function(&mut executor, "deposit", &[uint_token(3)], None);
+        harness.test_function_reverts(
+            &mut executor,
+            "withdraw",
+            &[uint_token(5)],
+            &encode_revert(
+                "InsufficientBalance(uint256,uint256)",
+                &[uint_token(3), uint_token(5)],
+            ),
+        );
+        harness.test_function(
+            &mut executor,
+            "withdraw",
+            &[uint_token(2)],
+            Some(&uint_token(1)),
+        );
+
+        harness.set_caller(address(stranger));
+        harness.test_function_reverts(
+            &mut executor,
+            "reset",
+            &[],
+            &encode_revert("Unauthorized(address)", &[address_token(stranger)]),
+        );
+    })
+}
+
 #[test]
 fn test_balances() {
     with_executor(&|mut executor| {
@@ -259,6 +301,366 @@ fn test_arrays() {
     })
 }
 
+#[test]
+fn string_operations() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "string_operations.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "len",
+            &[string_token("fox")],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "len",
+            &[string_token("")],
+            Some(&uint_token(0)),
+        );
+        harness.test_function(
+            &mut executor,
+            "concat",
+            &[string_token("brown "), string_token("fox")],
+            Some(&string_token("brown fox")),
+        );
+        harness.test_function(
+            &mut executor,
+            "concat",
+            &[string_token(""), string_token("fox")],
+            Some(&string_token("fox")),
+        );
+        harness.test_function(
+            &mut executor,
+            "slice",
+            &[
+                string_token("The quick brown fox jumps over the lazy dog"),
+                uint_token(4),
+                uint_token(19),
+            ],
+            Some(&string_token("quick brown fox")),
+        );
+        harness.test_function(
+            &mut executor,
+            "slice",
+            &[string_token("fox"), uint_token(3), uint_token(3)],
+            Some(&string_token("")),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "slice",
+            &[string_token("fox"), uint_token(1), uint_token(4)],
+            &encoded_panic_out_of_bounds(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "slice",
+            &[string_token("fox"), uint_token(2), uint_token(1)],
+            &encoded_panic_out_of_bounds(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "eq",
+            &[string_token("fox"), string_token("fox")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "eq",
+            &[string_token("fox"), string_token("foxes")],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "eq",
+            &[string_token("fox"), string_token("dog")],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "not_eq",
+            &[string_token("fox"), string_token("dog")],
+            Some(&bool_token(true)),
+        );
+
+        harness.test_function(&mut executor, "set_name", &[string_token("Alice")], None);
+        harness.test_function(&mut executor, "name_len", &[], Some(&uint_token(5)));
+        harness.test_function(
+            &mut executor,
+            "greet",
+            &[],
+            Some(&string_token("Hello, Alice")),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_name",
+            &[string_token("Alice")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_name",
+            &[string_token("Bob")],
+            Some(&bool_token(false)),
+        );
+    })
+}
+
+#[test]
+fn array_copy_semantics() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "array_copy_semantics.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "from_literal",
+            &[uint_token(7)],
+            Some(&uint_array_token(&[7, 8, 2])),
+        );
+        harness.test_function(&mut executor, "sum_literal", &[], Some(&uint_token(6)));
+
+        // Memory arrays are passed and assigned by reference.
+        harness.test_function(
+            &mut executor,
+            "alias_on_declaration",
+            &[],
+            Some(&uint_token(10)),
+        );
+        harness.test_function(
+            &mut executor,
+            "alias_on_assignment",
+            &[],
+            Some(&uint_token(10)),
+        );
+        harness.test_function(&mut executor, "alias_argument", &[], Some(&uint_token(10)));
+
+        // `clone`, storage writes and `to_mem` copy.
+        harness.test_function(&mut executor, "copy_with_clone", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "copy_to_storage", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "copy_to_memory", &[], Some(&uint_token(1)));
+    })
+}
+
+#[test]
+fn array_methods() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "array_methods.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "contains",
+            &[uint_token(7)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "contains",
+            &[uint_token(4)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "index_of",
+            &[uint_token(5)],
+            Some(&uint_token(1)),
+        );
+        // The array length is returned if there is no such item.
+        harness.test_function(
+            &mut executor,
+            "index_of",
+            &[uint_token(4)],
+            Some(&uint_token(4)),
+        );
+        harness.test_function(
+            &mut executor,
+            "index_of_signed",
+            &[int_token(-3)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fill",
+            &[int_token(-2)],
+            Some(&int_array_token(&[-2, -2, -2])),
+        );
+        harness.test_function(
+            &mut executor,
+            "copy_from",
+            &[],
+            Some(&uint_array_token(&[4, 5, 6])),
+        );
+        harness.test_function(
+            &mut executor,
+            "storage_methods",
+            &[uint_token(9)],
+            Some(&uint_token(11)),
+        );
+        harness.test_function(
+            &mut executor,
+            "storage_copy_from",
+            &[],
+            Some(&uint_array_token(&[1, 2, 3, 4])),
+        );
+        harness.test_function(
+            &mut executor,
+            "copied_index_of",
+            &[uint_token(2)],
+            Some(&uint_token(2)),
+        );
+    })
+}
+
+#[test]
+fn dynamic_array() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "dynamic_array.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "len", &[], Some(&uint_token(0)));
+        harness.test_function_reverts(
+            &mut executor,
+            "get",
+            &[uint_token(0)],
+            &encoded_panic_out_of_bounds(),
+        );
+        harness.test_function_reverts(&mut executor, "pop", &[], &encoded_panic_empty_array_pop());
+
+        harness.test_function(
+            &mut executor,
+            "push",
+            &[uint_token(3)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "push",
+            &[uint_token(5)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(
+            &mut executor,
+            "push",
+            &[uint_token(7)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(&mut executor, "get", &[uint_token(1)], Some(&uint_token(5)));
+        harness.test_function(&mut executor, "set", &[uint_token(1), uint_token(6)], None);
+        harness.test_function(&mut executor, "sum", &[], Some(&uint_token(16)));
+        harness.test_function_reverts(
+            &mut executor,
+            "get",
+            &[uint_token(3)],
+            &encoded_panic_out_of_bounds(),
+        );
+
+        harness.test_function(&mut executor, "pop", &[], Some(&uint_token(7)));
+        harness.test_function(&mut executor, "len", &[], Some(&uint_token(2)));
+        harness.test_function_reverts(
+            &mut executor,
+            "get",
+            &[uint_token(2)],
+            &encoded_panic_out_of_bounds(),
+        );
+
+        harness.test_function(&mut executor, "packed", &[], Some(&bool_token(true)));
+        harness.test_function(
+            &mut executor,
+            "signed",
+            &[int_token(-3)],
+            Some(&int_token(-3)),
+        );
+
+        let holder = address_token(SOME_ADDRESS);
+        harness.test_function(
+            &mut executor,
+            "add_holder",
+            &[uint_token(1), holder.clone()],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "holder",
+            &[uint_token(1), uint_token(0)],
+            Some(&holder),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "holder",
+            &[uint_token(2), uint_token(0)],
+            &encoded_panic_out_of_bounds(),
+        );
+    })
+}
+
+#[test]
+fn array_sort() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "array_sort.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "sort",
+            &[uint_array_token(&[5, 1, 4, 1, 3])],
+            Some(&uint_array_token(&[1, 1, 3, 4, 5])),
+        );
+        harness.test_function(
+            &mut executor,
+            "sort_signed",
+            &[int_array_token(&[3, -128, 127, -1])],
+            Some(&int_array_token(&[-128, -1, 3, 127])),
+        );
+        harness.test_function(
+            &mut executor,
+            "binary_search",
+            &[uint_token(13)],
+            Some(&uint_token(4)),
+        );
+        harness.test_function(
+            &mut executor,
+            "binary_search",
+            &[uint_token(1)],
+            Some(&uint_token(0)),
+        );
+        // The array length is returned if there is no such item.
+        harness.test_function(
+            &mut executor,
+            "binary_search",
+            &[uint_token(4)],
+            Some(&uint_token(6)),
+        );
+        harness.test_function(
+            &mut executor,
+            "binary_search_signed",
+            &[int_token(-3)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "binary_search_signed",
+            &[int_token(-4)],
+            Some(&uint_token(4)),
+        );
+        harness.test_function(
+            &mut executor,
+            "median",
+            &[
+                uint_token(30),
+                uint_token(10),
+                uint_token(50),
+                uint_token(20),
+                uint_token(40),
+            ],
+            Some(&uint_token(30)),
+        );
+        harness.test_function(
+            &mut executor,
+            "stored_binary_search",
+            &[uint_token(50)],
+            Some(&uint_token(2)),
+        );
+    })
+}
+
 #[rstest(fixture_file, input, expected,
     case("for_loop_with_static_array.fe", &[], uint_token(30)),
     case("for_loop_with_static_array_from_sto.fe", &[], uint_token(6)),
@@ -274,6 +676,25 @@ fn test_arrays() {
     case("if_statement_test_from_sto.fe", &[], uint_token(42)),
     case("if_statement_2.fe", &[uint_token(6)], uint_token(1)),
     case("if_statement_with_block_declaration.fe", &[], uint_token(1)),
+    case("match_statement.fe", &[uint_token(120)], uint_token(12)),
+    case("match_statement.fe", &[uint_token(11)], uint_token(1)),
+    case("match_statement.fe", &[uint_token(57)], uint_token(2)),
+    case("match_statement.fe", &[uint_token(32)], uint_token(30)),
+    case("match_statement.fe", &[uint_token(53)], uint_token(3)),
+    case("match_statement.fe", &[uint_token(4)], uint_token(4)),
+    case("enums.fe", &[uint_token(1)], uint_token(1)),
+    case("match_enum.fe", &[uint_token(0)], uint_token(16)),
+    case("match_enum.fe", &[uint_token(1)], uint_token(8)),
+    case("match_enum.fe", &[uint_token(2)], uint_token(48)),
+    case("match_enum.fe", &[uint_token(3)], uint_token(3)),
+    case("inline_yul.fe", &[uint_token(5)], uint_token(6)),
+    case("inline_yul.fe", &[uint_token(30)], uint_token(100)),
+    case("option_map.fe", &[uint_token(1)], uint_token(1)),
+    case("option_map.fe", &[uint_token(5)], uint_token(12)),
+    case("result_try.fe", &[uint_token(5)], uint_token(23)),
+    case("result_try.fe", &[uint_token(0)], uint_token(1000)),
+    case("const_fn.fe", &[uint_token(3)], uint_token(77)),
+    case("constants.fe", &[uint_token(42)], uint_token(5_000_042)),
     case("ternary_expression.fe", &[uint_token(6)], uint_token(1)),
     case("ternary_expression.fe", &[uint_token(4)], uint_token(0)),
     case("call_statement_without_args.fe", &[], uint_token(100)),
@@ -432,6 +853,7 @@ fn test_arrays() {
     case::associated_fns("associated_fns.fe", &[uint_token(12)], uint_token(144)),
     case::struct_fns("struct_fns.fe", &[uint_token(10), uint_token(20)], uint_token(100)),
     case::cast_address_to_u256("cast_address_to_u256.fe", &[address_token(SOME_ADDRESS)], address_token(SOME_ADDRESS)),
+    case::dbg("dbg.fe", &[int_token(-3)], int_token(-2)),
 )]
 fn test_method_return(fixture_file: &str, input: &[ethabi::Token], expected: ethabi::Token) {
     with_executor(&|mut executor| {
@@ -1241,27 +1663,322 @@ fn structs() {
 }
 
 #[test]
-fn keccak() {
+fn struct_hash() {
     with_executor(&|mut executor| {
-        let harness = deploy_contract(&mut executor, "keccak.fe", "Keccak", &[]);
-        // The expected value in clear text is on purpose for added clarity. All other
-        // tests use get_keccak256 to calculate the expected results on the fly.
+        let harness = deploy_contract(&mut executor, "struct_hash.fe", "Foo", &[]);
+        let maker = "2012301230123012301230123012301230123002";
+        let args = [
+            address_token(maker),
+            uint_token(1000),
+            int_token(-5),
+            bool_token(true),
+            ethabi::Token::FixedBytes(vec![0xde, 0xad, 0xbe, 0xef]),
+        ];
+
+        // The fields are hashed with their packed encoding, like
+        // `keccak256(abi.encodePacked(...))` in Solidity.
+        let mut packed = address(maker).as_bytes().to_vec();
+        packed.extend_from_slice(&1000u128.to_be_bytes());
+        packed.extend_from_slice(&(-5i16).to_be_bytes());
+        packed.push(1);
+        packed.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        let hash = ethabi::Token::Uint(keccak::full_as_bytes(&packed).into());
+
+        harness.test_function(&mut executor, "hash_order", &args, Some(&hash));
+        harness.test_function(&mut executor, "hash_stored_order", &args, Some(&hash));
+        harness.test_function(&mut executor, "hash_modified_order", &args, Some(&hash));
         harness.test_function(
             &mut executor,
-            "return_hash_from_u256",
-            &[ethabi::Token::Bytes(U256::from(1).to_be_bytes().to_vec())],
-            Some(&uint_token_from_dec_str(
-                "80084422859880547211683076133703299733277748156566366325829078699459944778998",
-            )),
+            "hash_empty",
+            &[],
+            Some(&ethabi::Token::Uint(keccak::full_as_bytes(&[]).into())),
+        );
+    })
+}
+
+#[test]
+fn proportion() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "proportion.fe", "Foo", &[]);
+        let u256_max = uint_token_from_dec_str(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
         );
 
         harness.test_function(
             &mut executor,
-            "return_hash_from_u256",
-            &[ethabi::Token::Bytes(U256::from(1).to_be_bytes().to_vec())],
-            Some(&ethabi::Token::Uint(
-                keccak::full_as_bytes(&U256::from(1).to_be_bytes()).into(),
-            )),
+            "scale",
+            &[uint_token(6), uint_token(7), uint_token(3)],
+            Some(&uint_token(14)),
+        );
+        harness.test_function(
+            &mut executor,
+            "scale",
+            &[uint_token(7), uint_token(1), uint_token(2)],
+            Some(&uint_token(3)),
+        );
+
+        // the intermediate product doesn't fit in 256 bits
+        harness.test_function(
+            &mut executor,
+            "scale",
+            &[u256_max.clone(), uint_token(2), uint_token(4)],
+            Some(&uint_token_from_dec_str(
+                "57896044618658097711785492504343953926634992332820282019728792003956564819967",
+            )),
+        );
+        harness.test_function(
+            &mut executor,
+            "scale",
+            &[u256_max.clone(), u256_max.clone(), u256_max.clone()],
+            Some(&u256_max),
+        );
+
+        harness.test_function_reverts(
+            &mut executor,
+            "scale",
+            &[uint_token(1), uint_token(1), uint_token(0)],
+            &encoded_div_or_mod_by_zero(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "scale",
+            &[u256_max.clone(), uint_token(2), uint_token(1)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(1000), uint_token(250)],
+            Some(&uint_token(25)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[u256_max.clone(), uint_token(10000)],
+            Some(&u256_max),
+        );
+    });
+}
+
+#[rstest(
+    value,
+    numerator,
+    denominator,
+    down,
+    up,
+    nearest,
+    // exact division
+    case(6, 7, 3, 14, 14, 14),
+    case(0, 7, 3, 0, 0, 0),
+    // half-way
+    case(7, 1, 2, 3, 4, 4),
+    case(1, 1, 2, 0, 1, 1),
+    // below and above half-way
+    case(7, 1, 3, 2, 3, 2),
+    case(5, 1, 3, 1, 2, 2),
+    case(1, 1, 3, 0, 1, 0)
+)]
+fn proportion_rounding(
+    value: u64,
+    numerator: u64,
+    denominator: u64,
+    down: u64,
+    up: u64,
+    nearest: u64,
+) {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "proportion.fe", "Foo", &[]);
+        let args = [
+            uint_token(value),
+            uint_token(numerator),
+            uint_token(denominator),
+        ];
+
+        harness.test_function(&mut executor, "scale", &args, Some(&uint_token(down)));
+        harness.test_function(&mut executor, "scale_ceil", &args, Some(&uint_token(up)));
+        harness.test_function(
+            &mut executor,
+            "scale_round",
+            &args,
+            Some(&uint_token(nearest)),
+        );
+    });
+}
+
+#[test]
+fn proportion_rounding_full_precision() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "proportion.fe", "Foo", &[]);
+        let u256_max = uint_token_from_dec_str(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        );
+        let half_max_down = uint_token_from_dec_str(
+            "57896044618658097711785492504343953926634992332820282019728792003956564819967",
+        );
+        let half_max_up = uint_token_from_dec_str(
+            "57896044618658097711785492504343953926634992332820282019728792003956564819968",
+        );
+
+        // `u256_max * 2 / 4` is half-way between two integers, and the
+        // intermediate product doesn't fit in 256 bits
+        let args = [u256_max.clone(), uint_token(2), uint_token(4)];
+        harness.test_function(&mut executor, "scale", &args, Some(&half_max_down));
+        harness.test_function(&mut executor, "scale_ceil", &args, Some(&half_max_up));
+        harness.test_function(&mut executor, "scale_round", &args, Some(&half_max_up));
+
+        // an exact quotient of `u256_max` isn't rounded up
+        let args = [u256_max.clone(), u256_max.clone(), u256_max.clone()];
+        harness.test_function(&mut executor, "scale_ceil", &args, Some(&u256_max));
+        harness.test_function(&mut executor, "scale_round", &args, Some(&u256_max));
+
+        // the quotient rounds down to `u256_max`, so rounding it up overflows
+        let args = [
+            uint_token_from_dec_str(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639934",
+            ),
+            uint_token_from_dec_str(
+                "57896044618658097711785492504343953926634992332820282019728792003956564819970",
+            ),
+            uint_token_from_dec_str(
+                "57896044618658097711785492504343953926634992332820282019728792003956564819969",
+            ),
+        ];
+        harness.test_function(&mut executor, "scale", &args, Some(&u256_max));
+        for function in ["scale_ceil", "scale_round"] {
+            harness.test_function_reverts(
+                &mut executor,
+                function,
+                &args,
+                &encoded_over_or_underflow(),
+            );
+        }
+
+        for function in ["scale_ceil", "scale_round"] {
+            harness.test_function_reverts(
+                &mut executor,
+                function,
+                &[uint_token(1), uint_token(1), uint_token(0)],
+                &encoded_div_or_mod_by_zero(),
+            );
+        }
+
+        // 0.3% of 1005 is 3.015
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(1005), uint_token(30)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee_ceil",
+            &[uint_token(1005), uint_token(30)],
+            Some(&uint_token(4)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee_round",
+            &[uint_token(1005), uint_token(30)],
+            Some(&uint_token(3)),
+        );
+        // 0.5% of 100 is exactly 0.5
+        harness.test_function(
+            &mut executor,
+            "fee_round",
+            &[uint_token(100), uint_token(50)],
+            Some(&uint_token(1)),
+        );
+    });
+}
+
+#[rstest(method, args, expected,
+    case("floor_u256", &[uint_token(7), uint_token(2)], uint_token(3)),
+    case("ceil_u256", &[uint_token(7), uint_token(2)], uint_token(4)),
+    case("round_u256", &[uint_token(7), uint_token(2)], uint_token(4)),
+    case("floor_u256", &[uint_token(5), uint_token(3)], uint_token(1)),
+    case("ceil_u256", &[uint_token(5), uint_token(3)], uint_token(2)),
+    case("round_u256", &[uint_token(5), uint_token(3)], uint_token(2)),
+    case("floor_u256", &[uint_token(4), uint_token(3)], uint_token(1)),
+    case("ceil_u256", &[uint_token(4), uint_token(3)], uint_token(2)),
+    case("round_u256", &[uint_token(4), uint_token(3)], uint_token(1)),
+    case("floor_u256", &[uint_token(6), uint_token(3)], uint_token(2)),
+    case("ceil_u256", &[uint_token(6), uint_token(3)], uint_token(2)),
+    case("round_u256", &[uint_token(6), uint_token(3)], uint_token(2)),
+    case("floor_i256", &[int_token(-7), int_token(2)], int_token(-4)),
+    case("ceil_i256", &[int_token(-7), int_token(2)], int_token(-3)),
+    case("round_i256", &[int_token(-7), int_token(2)], int_token(-4)),
+    case("floor_i256", &[int_token(7), int_token(-2)], int_token(-4)),
+    case("ceil_i256", &[int_token(7), int_token(-2)], int_token(-3)),
+    case("round_i256", &[int_token(7), int_token(-2)], int_token(-4)),
+    case("floor_i256", &[int_token(-7), int_token(-2)], int_token(3)),
+    case("ceil_i256", &[int_token(-7), int_token(-2)], int_token(4)),
+    case("round_i256", &[int_token(-7), int_token(-2)], int_token(4)),
+    case("floor_i256", &[int_token(-5), int_token(3)], int_token(-2)),
+    case("ceil_i256", &[int_token(-5), int_token(3)], int_token(-1)),
+    case("round_i256", &[int_token(-5), int_token(3)], int_token(-2)),
+    case("floor_i256", &[int_token(-4), int_token(3)], int_token(-2)),
+    case("ceil_i256", &[int_token(-4), int_token(3)], int_token(-1)),
+    case("round_i256", &[int_token(-4), int_token(3)], int_token(-1)),
+    case("floor_i256", &[int_token(-6), int_token(3)], int_token(-2)),
+    case("ceil_i256", &[int_token(-6), int_token(3)], int_token(-2)),
+    case("round_i256", &[int_token(-6), int_token(3)], int_token(-2)),
+)]
+fn rounding_div(method: &str, args: &[ethabi::Token], expected: ethabi::Token) {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "rounding_div.fe", "Foo", &[]);
+        harness.test_function(&mut executor, method, args, Some(&expected));
+    })
+}
+
+#[test]
+fn rounding_div_reverts() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "rounding_div.fe", "Foo", &[]);
+
+        harness.test_function_reverts(
+            &mut executor,
+            "ceil_u256",
+            &[uint_token(1), uint_token(0)],
+            &encoded_div_or_mod_by_zero(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "round_i256",
+            &[int_token(1), int_token(0)],
+            &encoded_div_or_mod_by_zero(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "ceil_i8",
+            &[int_token(-128), int_token(-1)],
+            &encoded_over_or_underflow(),
+        );
+    })
+}
+
+#[test]
+fn keccak() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "keccak.fe", "Keccak", &[]);
+        // The expected value in clear text is on purpose for added clarity. All other
+        // tests use get_keccak256 to calculate the expected results on the fly.
+        harness.test_function(
+            &mut executor,
+            "return_hash_from_u256",
+            &[ethabi::Token::Bytes(U256::from(1).to_be_bytes().to_vec())],
+            Some(&uint_token_from_dec_str(
+                "80084422859880547211683076133703299733277748156566366325829078699459944778998",
+            )),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "return_hash_from_u256",
+            &[ethabi::Token::Bytes(U256::from(1).to_be_bytes().to_vec())],
+            Some(&ethabi::Token::Uint(
+                keccak::full_as_bytes(&U256::from(1).to_be_bytes()).into(),
+            )),
         );
 
         harness.test_function(
@@ -1293,6 +2010,416 @@ fn keccak() {
     });
 }
 
+#[test]
+fn merkle_proof() {
+    let hash_pair = |a: [u8; 32], b: [u8; 32]| {
+        let (a, b) = if a < b { (a, b) } else { (b, a) };
+        keccak::full_as_bytes(&[a, b].concat())
+    };
+    let leaves = ["alice", "bob", "carol", "dave"]
+        .iter()
+        .map(|name| keccak::full_as_bytes(name.as_bytes()))
+        .collect::<Vec<_>>();
+    let left = hash_pair(leaves[0], leaves[1]);
+    let right = hash_pair(leaves[2], leaves[3]);
+    let root = hash_pair(left, right);
+
+    // Proofs are padded with zeros to 32 hashes.
+    let proof = |nodes: &[[u8; 32]]| {
+        let mut tokens = nodes
+            .iter()
+            .map(|node| ethabi::Token::Uint((*node).into()))
+            .collect::<Vec<_>>();
+        tokens.resize(32, uint_token(0));
+        ethabi::Token::FixedArray(tokens)
+    };
+    let leaf = |node: [u8; 32]| ethabi::Token::Uint(node.into());
+
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(
+            &mut executor,
+            "merkle.fe",
+            "Allowlist",
+            &[ethabi::Token::Uint(root.into())],
+        );
+
+        harness.test_function(
+            &mut executor,
+            "is_allowed",
+            &[proof(&[leaves[3], left]), leaf(leaves[2])],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_allowed",
+            &[proof(&[leaves[0], right]), leaf(leaves[1])],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_allowed",
+            &[proof(&[leaves[3], left]), leaf(leaves[1])],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_allowed",
+            &[proof(&[]), leaf(root)],
+            Some(&bool_token(true)),
+        );
+    })
+}
+
+#[test]
+fn ecdsa_recover() {
+    // A signature of `keccak256("Fe")` by the key
+    // 0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318.
+    let signer = "2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+    let hash = "3f49c5dd50968296457b049b58ede4d27286ebc3e1f0821713d05b71a1d59839";
+    let r = "bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d";
+    let s = "16c4bc848f8778852fde1251074d7925e4c47d5638cb5b58df42770b1eb82be2";
+    // The same signature with `s` in the upper half of the curve order.
+    let high_s = "e93b437b7078877ad021edaef8b286d8d5ea5f90767d44e2e08fe781b17e155f";
+
+    // The test EVM has no precompiles, so a stand-in for `ecrecover` is
+    // deployed at address 1. It returns the signer if its input is exactly
+    // `(hash, 28, r, s)`, whose keccak256 hash is pushed below, and nothing
+    // otherwise, like the precompile does for invalid signatures.
+    let ecrecover = hex::decode(format!(
+        "366000600037366000207f{}14603057005b73{}60005260206000f3",
+        "460b260d09ea43f6cfd5fccf532202cd126101f2ca164f346c57d44302a67fc3", signer
+    ))
+    .unwrap();
+
+    let vicinity = evm::backend::MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: H160::zero(),
+        chain_id: U256::zero(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::zero(),
+        block_difficulty: U256::zero(),
+        block_gas_limit: primitive_types::U256::MAX,
+    };
+    let mut state = BTreeMap::new();
+    state.insert(
+        H160::from_low_u64_be(1),
+        evm::backend::MemoryAccount {
+            nonce: U256::zero(),
+            balance: U256::zero(),
+            storage: BTreeMap::new(),
+            code: ecrecover,
+        },
+    );
+    let backend = evm::backend::MemoryBackend::new(&vicinity, state);
+
+    with_executor_backend(backend, &|mut executor| {
+        let harness = deploy_contract(&mut executor, "ecdsa.fe", "Verifier", &[]);
+        let uint = |hex: &str| ethabi::Token::Uint(U256::from_str_radix(hex, 16).unwrap());
+
+        harness.test_function(
+            &mut executor,
+            "recover",
+            &[uint(hash), uint_token(28), uint(r), uint(s)],
+            Some(&address_token(signer)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "recover",
+            &[uint(hash), uint_token(27), uint(r), uint(high_s)],
+            &encode_error_reason("ECDSA: invalid signature 's' value"),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "recover",
+            &[uint(hash), uint_token(1), uint(r), uint(s)],
+            &encode_error_reason("ECDSA: invalid signature 'v' value"),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "recover",
+            &[uint(hash), uint_token(27), uint(r), uint(s)],
+            &encode_error_reason("ECDSA: invalid signature"),
+        );
+    })
+}
+
+#[test]
+fn permit() {
+    let owner = "1000000000000000000000000000000000000001";
+    let spender = "2000000000000000000000000000000000000002";
+
+    // A stand-in for `ecrecover` that returns the `r` value of the signature
+    // as the signer, so that the digest is checked separately below.
+    let ecrecover = hex::decode("60403560005260206000f3").unwrap();
+
+    let vicinity = evm::backend::MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: H160::zero(),
+        chain_id: U256::zero(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::from(100),
+        block_difficulty: U256::zero(),
+        block_gas_limit: primitive_types::U256::MAX,
+    };
+    let mut state = BTreeMap::new();
+    state.insert(
+        H160::from_low_u64_be(1),
+        evm::backend::MemoryAccount {
+            nonce: U256::zero(),
+            balance: U256::zero(),
+            storage: BTreeMap::new(),
+            code: ecrecover,
+        },
+    );
+    let backend = evm::backend::MemoryBackend::new(&vicinity, state);
+
+    with_executor_backend(backend, &|mut executor| {
+        let harness = deploy_contract(&mut executor, "permit.fe", "Token", &[]);
+        let hash = |tokens: &[ethabi::Token]| keccak::full_as_bytes(&ethabi::encode(tokens));
+        let word = |bytes: [u8; 32]| ethabi::Token::Uint(bytes.into());
+        let signed_by = |signer: &str| {
+            ethabi::Token::Uint(U256::from_big_endian(&hex::decode(signer).unwrap()))
+        };
+
+        let domain_separator = hash(&[
+            word(keccak::full_as_bytes(
+                b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            )),
+            word(keccak::full_as_bytes(b"Fe Token")),
+            word(keccak::full_as_bytes(b"1")),
+            uint_token(0),
+            ethabi::Token::Address(harness.address),
+        ]);
+        harness.test_function(
+            &mut executor,
+            "DOMAIN_SEPARATOR",
+            &[],
+            Some(&word(domain_separator)),
+        );
+
+        let struct_hash = hash(&[
+            word(keccak::full_as_bytes(
+                b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+            )),
+            address_token(owner),
+            address_token(spender),
+            uint_token(500),
+            uint_token(0),
+            uint_token(200),
+        ]);
+        let digest = keccak::full_as_bytes(
+            &[&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat(),
+        );
+        harness.test_function(
+            &mut executor,
+            "permit_hash",
+            &[
+                address_token(owner),
+                address_token(spender),
+                uint_token(500),
+                uint_token(0),
+                uint_token(200),
+            ],
+            Some(&word(digest)),
+        );
+
+        let permit = |signer: &str, deadline: u64| {
+            [
+                address_token(owner),
+                address_token(spender),
+                uint_token(500),
+                uint_token(deadline),
+                uint_token(27),
+                signed_by(signer),
+                uint_token(1),
+            ]
+        };
+
+        harness.test_function_reverts(
+            &mut executor,
+            "permit",
+            &permit(owner, 99),
+            &encode_error_reason("ERC20Permit: expired deadline"),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "permit",
+            &permit(spender, 200),
+            &encode_error_reason("ERC20Permit: invalid signature"),
+        );
+
+        harness.test_function(&mut executor, "permit", &permit(owner, 200), None);
+        harness.test_function(
+            &mut executor,
+            "allowance",
+            &[address_token(owner), address_token(spender)],
+            Some(&uint_token(500)),
+        );
+        harness.test_function(
+            &mut executor,
+            "nonces",
+            &[address_token(owner)],
+            Some(&uint_token(1)),
+        );
+    })
+}
+
+#[test]
+fn roles() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "roles.fe", "Token", &[]);
+        let minter = "2000000000000000000000000000000000000002";
+        let minter_role = keccak::full_as_bytes(b"MINTER");
+
+        harness.test_function(
+            &mut executor,
+            "minter_role",
+            &[],
+            Some(&ethabi::Token::Uint(minter_role.into())),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_minter",
+            &[address_token(minter)],
+            Some(&bool_token(false)),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "grant_minter",
+            &[address_token(minter)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "is_minter",
+            &[address_token(minter)],
+            Some(&bool_token(true)),
+        );
+
+        // Only admins can grant roles, and only minters can mint.
+        harness.test_function_reverts(
+            &mut executor,
+            "mint",
+            &[uint_token(100)],
+            &encode_error_reason("AccessControl: missing role"),
+        );
+        harness.set_caller(address(minter));
+        harness.test_function_reverts(
+            &mut executor,
+            "grant_minter",
+            &[address_token(minter)],
+            &encode_error_reason("AccessControl: missing role"),
+        );
+        harness.test_function(&mut executor, "mint", &[uint_token(100)], None);
+        harness.test_function(&mut executor, "supply", &[], Some(&uint_token(100)));
+
+        harness.set_caller(address(DEFAULT_CALLER));
+        harness.test_function(
+            &mut executor,
+            "revoke_minter",
+            &[address_token(minter)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "is_minter",
+            &[address_token(minter)],
+            Some(&bool_token(false)),
+        );
+
+        let role = ethabi::Token::FixedBytes(minter_role.to_vec());
+        harness.events_emitted(
+            executor,
+            &[
+                (
+                    "RoleGranted",
+                    &[
+                        ethabi::Token::FixedBytes(vec![0; 32]),
+                        address_token(DEFAULT_CALLER),
+                        address_token(DEFAULT_CALLER),
+                    ],
+                ),
+                (
+                    "RoleGranted",
+                    &[
+                        role.clone(),
+                        address_token(minter),
+                        address_token(DEFAULT_CALLER),
+                    ],
+                ),
+                (
+                    "RoleRevoked",
+                    &[role, address_token(minter), address_token(DEFAULT_CALLER)],
+                ),
+            ],
+        );
+    })
+}
+
+#[test]
+fn raw_storage() {
+    with_executor(&|mut executor| {
+        let implementation = "2000000000000000000000000000000000000002";
+        let harness = deploy_contract(
+            &mut executor,
+            "raw_storage.fe",
+            "Proxy",
+            &[address_token(implementation)],
+        );
+        let slot = U256::from_str_radix(
+            "360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc",
+            16,
+        )
+        .unwrap();
+
+        harness.test_function(
+            &mut executor,
+            "implementation",
+            &[],
+            Some(&address_token(implementation)),
+        );
+
+        let mut slot_bytes = [0; 32];
+        slot.to_big_endian(&mut slot_bytes);
+        let stored = executor.storage(harness.address, slot_bytes.into());
+        assert_eq!(H160::from(stored), address(implementation));
+
+        let upgraded = "3000000000000000000000000000000000000003";
+        harness.test_function(
+            &mut executor,
+            "upgrade_to",
+            &[address_token(upgraded)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "implementation",
+            &[],
+            Some(&address_token(upgraded)),
+        );
+        harness.test_function(
+            &mut executor,
+            "load",
+            &[ethabi::Token::Uint(slot)],
+            Some(&ethabi::Token::Uint(U256::from_big_endian(
+                address(upgraded).as_bytes(),
+            ))),
+        );
+        harness.test_function(&mut executor, "upgrade_count", &[], Some(&uint_token(1)));
+        harness.test_function(
+            &mut executor,
+            "load",
+            &[uint_token(1234)],
+            Some(&uint_token(0)),
+        );
+    })
+}
+
 #[test]
 fn short_circuit() {
     with_executor(&|mut executor| {
@@ -1347,6 +2474,51 @@ fn two_contracts() {
     })
 }
 
+#[test]
+fn interface() {
+    with_executor(&|mut executor| {
+        let counter_harness = deploy_contract(&mut executor, "interface.fe", "SimpleCounter", &[]);
+        let harness = deploy_contract(&mut executor, "interface.fe", "Foo", &[]);
+        let counter_address = ethabi::Token::Address(counter_harness.address);
+
+        harness.test_function(
+            &mut executor,
+            "add_twice",
+            &[counter_address.clone(), uint_token(3)],
+            Some(&uint_token(6)),
+        );
+        counter_harness.test_function(&mut executor, "count", &[], Some(&uint_token(6)));
+        harness.test_function(
+            &mut executor,
+            "reset_and_count",
+            &[counter_address],
+            Some(&uint_token(0)),
+        );
+    })
+}
+
+#[test]
+fn abi_import() {
+    with_executor(&|mut executor| {
+        let counter_harness = deploy_contract(&mut executor, "abi_import.fe", "SimpleCounter", &[]);
+        let harness = deploy_contract(&mut executor, "abi_import.fe", "Foo", &[]);
+        let counter_address = ethabi::Token::Address(counter_harness.address);
+
+        harness.test_function(
+            &mut executor,
+            "add_twice",
+            &[counter_address.clone(), uint_token(4)],
+            Some(&uint_token(8)),
+        );
+        harness.test_function(
+            &mut executor,
+            "reset_and_count",
+            &[counter_address],
+            Some(&uint_token(0)),
+        );
+    })
+}
+
 #[test]
 fn external_contract() {
     with_executor(&|mut executor| {
@@ -1443,6 +2615,92 @@ fn create_contract_from_init() {
     })
 }
 
+#[rstest(function_name, case("create_token"), case("create2_token"))]
+fn create_contract_with_args(function_name: &str) {
+    with_executor(&|mut executor| {
+        let factory_harness = deploy_contract(
+            &mut executor,
+            "create_contract_with_args.fe",
+            "TokenFactory",
+            &[],
+        );
+
+        let token_address = factory_harness
+            .call_function(&mut executor, function_name, &[uint_token(1000)])
+            .expect("factory did not return an address")
+            .into_address()
+            .expect("not an address");
+
+        let token_harness = load_contract(token_address, "create_contract_with_args.fe", "Token");
+
+        token_harness.test_function(
+            &mut executor,
+            "get_owner",
+            &[],
+            Some(&address_token(DEFAULT_CALLER)),
+        );
+        token_harness.test_function(&mut executor, "get_supply", &[], Some(&uint_token(1000)));
+        token_harness.test_function(&mut executor, "get_symbol", &[], Some(&string_token("FE")));
+    })
+}
+
+#[test]
+fn low_level_calls() {
+    with_executor(&|mut executor| {
+        let callee = deploy_contract(&mut executor, "low_level_calls.fe", "Callee", &[]);
+        let caller = deploy_contract(&mut executor, "low_level_calls.fe", "Caller", &[]);
+        let target = ethabi::Token::Address(callee.address);
+
+        caller.test_function(
+            &mut executor,
+            "call_number",
+            &[target.clone()],
+            Some(&uint_token(42)),
+        );
+        caller.test_function(
+            &mut executor,
+            "call_fails",
+            &[target.clone()],
+            Some(&bool_token(false)),
+        );
+        caller.test_function(
+            &mut executor,
+            "static_call_number",
+            &[target.clone()],
+            Some(&uint_token(42)),
+        );
+        caller.test_function(
+            &mut executor,
+            "static_call_writes",
+            &[target.clone()],
+            Some(&bool_token(false)),
+        );
+
+        // The callee's code writes the caller's storage.
+        caller.test_function(
+            &mut executor,
+            "delegate_set_value",
+            &[target],
+            Some(&uint_token(7)),
+        );
+
+        // Sending more wei than the contract has reverts with Error(0x100).
+        let bob = "2000000000000000000000000000000000000002";
+        validate_revert(
+            caller.capture_call(&mut executor, "pay", &[address_token(bob), uint_token(3)]),
+            &encode_revert("Error(uint256)", &[uint_token(0x100)]),
+        );
+        executor.state_mut().deposit(caller.address, U256::from(10));
+        caller.test_function(
+            &mut executor,
+            "pay",
+            &[address_token(bob), uint_token(3)],
+            Some(&bool_token(true)),
+        );
+        assert_eq!(executor.balance(address(bob)), U256::from(3));
+    })
+}
+
 #[rstest(
     fixture_file,
     contract_name,
@@ -1692,57 +2950,319 @@ fn abi_decode_checks() {
             // tampered_data[byte_index] = 32;
             harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
 
-            // place non-zero byte in padded region of the string
-            let mut tampered_data = data.clone();
-            // last byte in string encoding
-            let byte_index = 4 + head_size + string_data_size - 1;
-            // set last bit to 1
-            tampered_data[byte_index] = 1;
-            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+            // place non-zero byte in padded region of the string
+            let mut tampered_data = data.clone();
+            // last byte in string encoding
+            let byte_index = 4 + head_size + string_data_size - 1;
+            // set last bit to 1
+            tampered_data[byte_index] = 1;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
+            // place non-zero byte in padded region of the string
+            let mut tampered_data = data.clone();
+            // first byte in padded section of string encoding
+            let byte_index = 4 + head_size + 32 + string_size;
+            // set first bit to 1
+            tampered_data[byte_index] = 128;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
+            // place non-zero byte in padded region of the bytes
+            let mut tampered_data = data.clone();
+            // last byte in bytes encoding
+            let byte_index = 4 + head_size + string_data_size + bytes_data_size - 1;
+            // set last bit to 1
+            tampered_data[byte_index] = 1;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
+            // place non-zero byte in padded region of the bytes
+            let mut tampered_data = data;
+            // first byte in padded section of string encoding
+            let byte_index = 4 + head_size + string_data_size + 32 + bytes_size;
+            // set first bit to 1
+            tampered_data[byte_index] = 128;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
+            // invalid since bytes has size 990 instead of 1000
+            let invalid_input = [
+                string_token("hello Fe"),
+                address_token("baddad"),
+                bytes_token(&"ten bytes.".repeat(99)),
+                bool_token(true),
+            ];
+            harness.test_function_reverts(&mut executor, func_name, &invalid_input, &revert_data);
+
+            // invalid since string has size 100, which is greater than 80
+            let invalid_input = [
+                string_token(&"hello Fe..".repeat(10)),
+                address_token("baddad"),
+                bytes_token(&"ten bytes.".repeat(100)),
+                bool_token(true),
+            ];
+            harness.test_function_reverts(&mut executor, func_name, &invalid_input, &revert_data);
+        }
+    });
+}
+
+#[test]
+fn enums() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "enums.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "is_closed", &[], Some(&bool_token(false)));
+        harness.test_function(
+            &mut executor,
+            "from_index",
+            &[uint_token(2)],
+            Some(&uint_token(2)),
+        );
+        validate_revert(
+            harness.capture_call(&mut executor, "from_index", &[uint_token(3)]),
+            &encoded_panic_invalid_enum_value(),
+        );
+
+        // Arguments that aren't variant indices are rejected when decoded.
+        harness.test_function(
+            &mut executor,
+            "to_index",
+            &[uint_token(1)],
+            Some(&uint_token(1)),
+        );
+        let data = harness.build_calldata("to_index", &[uint_token(3)]);
+        harness.test_call_reverts(&mut executor, data, &encoded_invalid_abi_data());
+
+        harness.test_function(&mut executor, "bar", &[uint_token(2)], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "is_closed", &[], Some(&bool_token(true)));
+        // There's no variant after `State::Closed`.
+        validate_revert(
+            harness.capture_call(&mut executor, "advance", &[]),
+            &encoded_panic_invalid_enum_value(),
+        );
+    })
+}
+
+#[test]
+fn match_enum() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "match_enum.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "safe_apply",
+            &[uint_token(3), uint_token(12), uint_token(0)],
+            Some(&uint_token(0)),
+        );
+        harness.test_function(
+            &mut executor,
+            "safe_apply",
+            &[uint_token(0), uint_token(12), uint_token(0)],
+            Some(&uint_token(12)),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(0)],
+            Some(&uint_token(30)),
+        );
+        harness.test_function(&mut executor, "fee", &[uint_token(1)], Some(&uint_token(5)));
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(2)],
+            Some(&uint_token(200)),
+        );
+    })
+}
+
+#[test]
+fn bool_normalization() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "bool_normalization.fe", "Foo", &[]);
+        let true_data = ethabi::encode(&[bool_token(true)]);
+
+        // `flag` is the first byte of the word derived from its nonce, `0`.
+        let mut flag_ptr = keccak::full_as_bytes(b"0");
+        flag_ptr[31] = 0;
+        let flag_word = U256::from_big_endian(&flag_ptr) / 32;
+
+        // a `true` with other bits set in its byte and word
+        let dirty_true = (U256::from(0x82) << 248) | U256::from(1);
+        harness.test_function(
+            &mut executor,
+            "set_word",
+            &[
+                ethabi::Token::Uint(flag_word),
+                ethabi::Token::Uint(dirty_true),
+            ],
+            None,
+        );
+        let get_flag = harness.build_calldata("get_flag", &[]);
+        harness.test_call_returns(&mut executor, get_flag, &true_data);
+
+        // a `true` with high bits set in its ABI encoding
+        let mut echo = harness.build_calldata("echo", &[bool_token(true)]);
+        echo[4] = 0x80;
+        echo[34] = 0x01;
+        harness.test_call_returns(&mut executor, echo, &true_data);
+
+        let mut echo = harness.build_calldata("echo", &[bool_token(false)]);
+        echo[35] = 0x02;
+        harness.test_call_returns(&mut executor, echo, &true_data);
+    });
+}
+
+#[test]
+fn fixed_bytes() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "fixed_bytes.fe", "Foo", &[]);
+        let bytes = |value: &[u8]| ethabi::Token::FixedBytes(value.to_vec());
+        let value = bytes(&[0x12, 0x34, 0x56, 0x78]);
+
+        harness.test_function(
+            &mut executor,
+            "from_u32",
+            &[uint_token(0x12345678)],
+            Some(&value),
+        );
+        harness.test_function(
+            &mut executor,
+            "from_u8",
+            &[uint_token(0xff)],
+            Some(&bytes(&[0, 0, 0, 0xff])),
+        );
+        harness.test_function(&mut executor, "from_literal", &[], Some(&value));
+        harness.test_function(
+            &mut executor,
+            "to_u32",
+            &[value.clone()],
+            Some(&uint_token(0x12345678)),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "get_byte",
+            &[value.clone(), uint_token(1)],
+            Some(&uint_token(0x34)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "get_byte",
+            &[value.clone(), uint_token(4)],
+            &encoded_panic_out_of_bounds(),
+        );
 
-            // place non-zero byte in padded region of the string
-            let mut tampered_data = data.clone();
-            // first byte in padded section of string encoding
-            let byte_index = 4 + head_size + 32 + string_size;
-            // set first bit to 1
-            tampered_data[byte_index] = 128;
-            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+        harness.test_function(
+            &mut executor,
+            "shift_left",
+            &[value.clone(), uint_token(8)],
+            Some(&bytes(&[0x34, 0x56, 0x78, 0])),
+        );
+        harness.test_function(
+            &mut executor,
+            "shift_right",
+            &[value.clone(), uint_token(8)],
+            Some(&bytes(&[0, 0x12, 0x34, 0x56])),
+        );
+        harness.test_function(
+            &mut executor,
+            "invert",
+            &[value.clone()],
+            Some(&bytes(&[0xed, 0xcb, 0xa9, 0x87])),
+        );
+        harness.test_function(
+            &mut executor,
+            "xor",
+            &[value.clone(), bytes(&[0xff, 0, 0xff, 0])],
+            Some(&bytes(&[0xed, 0x34, 0xa9, 0x78])),
+        );
+        harness.test_function(
+            &mut executor,
+            "concat",
+            &[bytes(&[1, 2]), bytes(&[3]), bytes(&[4, 5, 6])],
+            Some(&bytes(&[1, 2, 3, 4, 5, 6])),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_less",
+            &[bytes(&[0x12, 0x34, 0, 0]), value.clone()],
+            Some(&bool_token(true)),
+        );
 
-            // place non-zero byte in padded region of the bytes
-            let mut tampered_data = data.clone();
-            // last byte in bytes encoding
-            let byte_index = 4 + head_size + string_data_size + bytes_data_size - 1;
-            // set last bit to 1
-            tampered_data[byte_index] = 1;
-            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+        harness.test_function(&mut executor, "store", &[value.clone()], None);
+        harness.test_function(&mut executor, "load", &[], Some(&value));
+        harness.test_function(
+            &mut executor,
+            "stored_byte",
+            &[uint_token(3)],
+            Some(&uint_token(0x78)),
+        );
 
-            // place non-zero byte in padded region of the bytes
-            let mut tampered_data = data;
-            // first byte in padded section of string encoding
-            let byte_index = 4 + head_size + string_data_size + 32 + bytes_size;
-            // set first bit to 1
-            tampered_data[byte_index] = 128;
-            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+        // place a non-zero byte after the 4 bytes of the value
+        let mut tampered_data = harness.build_calldata("to_u32", &[value]);
+        tampered_data[4 + 4] = 1;
+        harness.test_call_reverts(&mut executor, tampered_data, &encoded_invalid_abi_data());
+    });
+}
 
-            // invalid since bytes has size 990 instead of 1000
-            let invalid_input = [
-                string_token("hello Fe"),
-                address_token("baddad"),
-                bytes_token(&"ten bytes.".repeat(99)),
-                bool_token(true),
-            ];
-            harness.test_function_reverts(&mut executor, func_name, &invalid_input, &revert_data);
+#[test]
+fn slices() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "slices.fe", "Foo", &[]);
+        let values = uint_array_token(&[1, 2, 3, 4, 5]);
+        let bytes = |value: &[u8]| ethabi::Token::FixedBytes(value.to_vec());
+        let data = bytes(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]);
 
-            // invalid since string has size 100, which is greater than 80
-            let invalid_input = [
-                string_token(&"hello Fe..".repeat(10)),
-                address_token("baddad"),
-                bytes_token(&"ten bytes.".repeat(100)),
-                bool_token(true),
-            ];
-            harness.test_function_reverts(&mut executor, func_name, &invalid_input, &revert_data);
-        }
-    });
+        harness.test_function(
+            &mut executor,
+            "head",
+            &[values.clone()],
+            Some(&uint_array_token(&[1, 2])),
+        );
+        harness.test_function(
+            &mut executor,
+            "tail",
+            &[values.clone()],
+            Some(&uint_array_token(&[3, 4, 5])),
+        );
+        harness.test_function(
+            &mut executor,
+            "middle_sum",
+            &[values.clone()],
+            Some(&uint_token(9)),
+        );
+        // Slices are copies, so writing to them doesn't change the original array.
+        harness.test_function(
+            &mut executor,
+            "copy_first",
+            &[values.clone()],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "stored_tail",
+            &[values],
+            Some(&uint_array_token(&[4, 5])),
+        );
+        harness.test_function(
+            &mut executor,
+            "small_items",
+            &[uint_array_token(&[10, 20, 30, 40])],
+            Some(&uint_array_token(&[20, 30])),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "selector",
+            &[data.clone()],
+            Some(&bytes(&[0x12, 0x34, 0x56, 0x78])),
+        );
+        harness.test_function(
+            &mut executor,
+            "middle_bytes",
+            &[data],
+            Some(&bytes(&[0x56, 0x78])),
+        );
+    })
 }
 
 #[test]
@@ -1825,3 +3345,355 @@ fn signext_int_array2(method: &str, value: ethabi::Token) {
         harness.test_function(&mut executor, method, &[value.clone()], Some(&value));
     })
 }
+
+#[test]
+fn upgradeable() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "upgradeable.fe", "Token", &[]);
+        let owner = "1000000000000000000000000000000000000001";
+
+        harness.test_function(
+            &mut executor,
+            "initialize",
+            &[address_token(owner), uint_token(100)],
+            None,
+        );
+        harness.test_function(&mut executor, "owner", &[], Some(&address_token(owner)));
+        harness.test_function(&mut executor, "supply", &[], Some(&uint_token(100)));
+
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "initialize",
+                &[address_token(owner), uint_token(200)],
+            ),
+            &encode_error_reason("Initializable: contract is already initialized"),
+        );
+        harness.test_function(&mut executor, "supply", &[], Some(&uint_token(100)));
+    })
+}
+
+#[test]
+fn multicall() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "multicall.fe", "Counter", &[]);
+        let call = |name, input: &[ethabi::Token]| {
+            ethabi::Token::Bytes(harness.build_calldata(name, input))
+        };
+        let result = |n| ethabi::Token::Bytes(ethabi::encode(&[uint_token(n)]));
+
+        harness.test_function(
+            &mut executor,
+            "multicall",
+            &[ethabi::Token::Array(vec![
+                call("add", &[uint_token(2)]),
+                call("add", &[uint_token(3)]),
+                call("fail", &[uint_token(0)]),
+            ])],
+            Some(&ethabi::Token::Array(vec![
+                result(2),
+                result(5),
+                ethabi::Token::Bytes(vec![]),
+            ])),
+        );
+
+        // A failing call reverts the whole batch with its own revert data.
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "multicall",
+                &[ethabi::Token::Array(vec![
+                    call("add", &[uint_token(3)]),
+                    call("fail", &[uint_token(1)]),
+                ])],
+            ),
+            &encode_error_reason("Not zero"),
+        );
+        harness.test_function(&mut executor, "get", &[], Some(&uint_token(5)));
+    })
+}
+
+#[test]
+fn fallback_receive() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "fallback_receive.fe", "Wallet", &[]);
+
+        // Empty calldata goes to `receive`, and an unknown selector to `fallback`.
+        harness.test_call_returns(&mut executor, vec![], &[]);
+        harness.test_call_returns(&mut executor, vec![0xde, 0xad, 0xbe, 0xef], &[]);
+        harness.test_call_returns(&mut executor, vec![0xde, 0xad, 0xbe, 0xef], &[]);
+
+        harness.test_function(&mut executor, "get_receives", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "get_fallbacks", &[], Some(&uint_token(2)));
+    })
+}
+
+#[test]
+fn diamond_facets() {
+    with_executor(&|mut executor| {
+        let ownership = deploy_contract(&mut executor, "diamond.fe", "Ownership", &[]);
+        let counter = deploy_contract(&mut executor, "diamond.fe", "Counter", &[]);
+        let diamond = deploy_contract(&mut executor, "diamond.fe", "Diamond", &[]);
+
+        // Register each selector of the compiled selector table with the
+        // facet that implements it.
+        let path = "features/diamond.fe";
+        let mut files = test_files::new_filestore();
+        let id = files.add_file(path, test_files::fixture(path));
+        let deps = files.add_included_libraries();
+        let options = fe_driver::CompileOptions {
+            with_bytecode: false,
+            with_runtime_bytecode: false,
+            ..fe_driver::CompileOptions::default()
+        };
+        let module = fe_driver::compile_module(&files, id, &deps, &options)
+            .unwrap_or_else(|_| panic!("failed to compile `{}`", path));
+        let table = fe_driver::artifacts::load(&module.diamonds["AppStorage"])
+            .expect("failed to load the selector table");
+        let entries = table["selectors"].as_array().expect("missing selectors");
+        assert_eq!(entries.len(), 5);
+        for entry in entries {
+            let facet = match entry["facet"].as_str() {
+                Some("Ownership") => &ownership,
+                Some("Counter") => &counter,
+                facet => panic!("unexpected facet {:?}", facet),
+            };
+            let selector = entry["selector"].as_str().expect("missing selector");
+            let selector = U256::from_str_radix(&selector[2..], 16).unwrap();
+            diamond.test_function(
+                &mut executor,
+                "set_facet",
+                &[
+                    ethabi::Token::Uint(selector),
+                    ethabi::Token::Address(facet.address),
+                ],
+                None,
+            );
+        }
+
+        // Calls to the diamond are routed to each facet, and run on the
+        // diamond's storage.
+        let through_diamond = |facet: &ContractHarness| ContractHarness {
+            address: diamond.address,
+            abi: facet.abi.clone(),
+            caller: diamond.caller,
+            value: U256::zero(),
+        };
+        let diamond_ownership = through_diamond(&ownership);
+        let diamond_counter = through_diamond(&counter);
+        let owner = address_token(SOME_ADDRESS);
+
+        diamond_ownership.test_function(&mut executor, "set_owner", &[owner.clone()], None);
+        diamond_ownership.test_function(&mut executor, "owner", &[], Some(&owner));
+        diamond_counter.test_function(&mut executor, "increment", &[], None);
+        diamond_counter.test_function(&mut executor, "increment", &[], None);
+        diamond_counter.test_function(&mut executor, "get_count", &[], Some(&uint_token(2)));
+        diamond_counter.test_function(&mut executor, "get_admin", &[], Some(&owner));
+
+        // The facets' own storage is untouched.
+        let zero = address_token("0000000000000000000000000000000000000000");
+        ownership.test_function(&mut executor, "owner", &[], Some(&zero));
+        counter.test_function(&mut executor, "get_count", &[], Some(&uint_token(0)));
+
+        // A selector that no facet implements reverts.
+        diamond.test_call_reverts(&mut executor, vec![0xde, 0xad, 0xbe, 0xef], &[]);
+    })
+}
+
+#[test]
+fn ownable_pausable() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(
+            &mut executor,
+            "ownable_pausable.fe",
+            "Vault",
+            &[uint_token(10)],
+        );
+        let zero = "0000000000000000000000000000000000000000";
+        let new_owner = "2000000000000000000000000000000000000002";
+        let not_owner = encode_error_reason("Ownable: caller is not the owner");
+
+        harness.test_function(
+            &mut executor,
+            "owner",
+            &[],
+            Some(&address_token(DEFAULT_CALLER)),
+        );
+        harness.test_function(&mut executor, "withdraw", &[uint_token(4)], None);
+
+        // Deposits are blocked while the contract is paused.
+        harness.test_function(&mut executor, "pause", &[], None);
+        harness.test_function(&mut executor, "paused", &[], Some(&bool_token(true)));
+        harness.test_function_reverts(
+            &mut executor,
+            "deposit",
+            &[uint_token(1)],
+            &encode_error_reason("Pausable: paused"),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "pause",
+            &[],
+            &encode_error_reason("Pausable: paused"),
+        );
+        harness.test_function(&mut executor, "unpause", &[], None);
+        harness.test_function(&mut executor, "deposit", &[uint_token(5)], None);
+        harness.test_function(&mut executor, "get_total", &[], Some(&uint_token(11)));
+
+        harness.test_function_reverts(
+            &mut executor,
+            "transfer_ownership",
+            &[address_token(zero)],
+            &encode_error_reason("Ownable: new owner is the zero address"),
+        );
+        harness.test_function(
+            &mut executor,
+            "transfer_ownership",
+            &[address_token(new_owner)],
+            None,
+        );
+        harness.test_function_reverts(&mut executor, "pause", &[], &not_owner);
+        harness.test_function_reverts(&mut executor, "withdraw", &[uint_token(1)], &not_owner);
+
+        harness.set_caller(address(new_owner));
+        harness.test_function(&mut executor, "renounce_ownership", &[], None);
+        harness.test_function(&mut executor, "owner", &[], Some(&address_token(zero)));
+
+        harness.events_emitted(
+            executor,
+            &[
+                (
+                    "OwnershipTransferred",
+                    &[address_token(zero), address_token(DEFAULT_CALLER)],
+                ),
+                (
+                    "OwnershipTransferred",
+                    &[address_token(DEFAULT_CALLER), address_token(new_owner)],
+                ),
+                (
+                    "OwnershipTransferred",
+                    &[address_token(new_owner), address_token(zero)],
+                ),
+                ("Paused", &[address_token(DEFAULT_CALLER)]),
+                ("Unpaused", &[address_token(DEFAULT_CALLER)]),
+            ],
+        );
+    })
+}
+
+#[test]
+fn named_outputs() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "named_outputs.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "split",
+            &[uint_token(42)],
+            Some(&tuple_token(&[uint_token(4), uint_token(2)])),
+        );
+        harness.test_function(
+            &mut executor,
+            "checked_sub",
+            &[uint_token(5), uint_token(3)],
+            Some(&tuple_token(&[uint_token(2), bool_token(true)])),
+        );
+        harness.test_function(
+            &mut executor,
+            "checked_sub",
+            &[uint_token(3), uint_token(5)],
+            Some(&tuple_token(&[uint_token(0), bool_token(false)])),
+        );
+        harness.test_function(
+            &mut executor,
+            "doubled",
+            &[uint_token(21)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "explicit",
+            &[uint_token(0)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "explicit",
+            &[uint_token(7)],
+            Some(&uint_token(7)),
+        );
+    })
+}
+
+#[test]
+fn generics() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "generics.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "max_u8",
+            &[uint_token(3), uint_token(7)],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "max_i256",
+            &[int_token(-3), int_token(-7)],
+            Some(&int_token(-3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "swapped",
+            &[uint_token(1), uint_token(2)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(
+            &mut executor,
+            "larger",
+            &[uint_token(5), uint_token(9)],
+            Some(&uint_token(9)),
+        );
+    })
+}
+
+#[test]
+fn traits() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "traits.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "rect_area",
+            &[uint_token(3), uint_token(4)],
+            Some(&uint_token(12)),
+        );
+        // the default `is_larger` of `Shape`
+        harness.test_function(
+            &mut executor,
+            "rect_larger",
+            &[uint_token(3), uint_token(4), uint_token(12)],
+            Some(&bool_token(false)),
+        );
+        // overridden by `Square`
+        harness.test_function(
+            &mut executor,
+            "square_larger",
+            &[uint_token(3), uint_token(9)],
+            Some(&bool_token(true)),
+        );
+        // the default `is_owner` of `Owned` is a public function of `Foo`
+        harness.test_function(
+            &mut executor,
+            "is_owner",
+            &[address_token(DEFAULT_CALLER)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_owner",
+            &[address_token("2000000000000000000000000000000000000002")],
+            Some(&bool_token(false)),
+        );
+    })
+}
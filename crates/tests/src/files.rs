@@ -0,0 +1,82 @@
+use fe_common::diagnostics::Severity;
+use fe_common::files::{FileLoader, FileStore};
+use fe_driver::CancellationToken;
+use std::cell::Cell;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+const FOO: &str = "const X: u256 = 1\n";
+
+/// Loads `foo.fe`, and counts how often it's read.
+struct CountingLoader {
+    reads: Rc<Cell<usize>>,
+}
+
+impl FileLoader for CountingLoader {
+    fn load_file(&self, path: &Path) -> io::Result<String> {
+        self.reads.set(self.reads.get() + 1);
+        if path == Path::new("foo.fe") {
+            Ok(FOO.to_string())
+        } else {
+            Err(io::Error::new(io::ErrorKind::NotFound, "no such file"))
+        }
+    }
+}
+
+fn counting_filestore() -> (FileStore, Rc<Cell<usize>>) {
+    let reads = Rc::new(Cell::new(0));
+    let loader = CountingLoader {
+        reads: reads.clone(),
+    };
+    (FileStore::with_loader(Box::new(loader)), reads)
+}
+
+#[test]
+fn files_are_loaded_once() {
+    let (mut files, reads) = counting_filestore();
+    let (content, id) = files.load_file("foo.fe").unwrap();
+    assert_eq!(content, FOO);
+    assert_eq!(files.load_file("foo.fe").unwrap().1, id);
+    assert_eq!(files.file_id(Path::new("foo.fe")), Some(id));
+    assert_eq!(reads.get(), 1);
+}
+
+#[test]
+fn overlays_replace_loaded_files() {
+    let (mut files, reads) = counting_filestore();
+    let (_, disk_id) = files.load_file("foo.fe").unwrap();
+
+    let unsaved = "const X: u256 = 2\n";
+    files.set_overlay("foo.fe", unsaved);
+    let (content, overlay_id) = files.load_file("foo.fe").unwrap();
+    assert_eq!(content, unsaved);
+    assert_ne!(overlay_id, disk_id);
+
+    assert_eq!(
+        files.remove_overlay(Path::new("foo.fe")).as_deref(),
+        Some(unsaved)
+    );
+    assert_eq!(files.load_file("foo.fe").unwrap().1, disk_id);
+    assert_eq!(reads.get(), 2);
+}
+
+#[test]
+fn virtual_files_can_import_virtual_abis() {
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    files.add_virtual_file(
+        "virtual/counter_abi.json",
+        test_files::fixture("features/counter_abi.json"),
+    );
+    let id = files.add_virtual_file(
+        "virtual/abi_import.fe",
+        test_files::fixture("features/abi_import.fe"),
+    );
+
+    let diagnostics = fe_driver::check_module(&files, id, &deps, &CancellationToken::new())
+        .expect("the check was cancelled");
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity != Severity::Error));
+}
@@ -61,3 +61,35 @@ fn test_basic_ingot() {
         );
     })
 }
+
+#[test]
+fn test_ingot_module_contract() {
+    with_executor(&|mut executor| {
+        // `BingContract` is defined in `bing.fe` rather than `main.fe`
+        let harness = deploy_ingot(&mut executor, "basic_ingot", "BingContract", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "add",
+            &[uint_token(40), uint_token(2)],
+            Some(&uint_token(42)),
+        );
+    })
+}
+
+#[test]
+fn test_ingot_imports() {
+    with_executor(&|mut executor| {
+        let harness = deploy_ingot(&mut executor, "import_ingot", "Foo", &[]);
+
+        harness.test_function(&mut executor, "max_supply", &[], Some(&uint_token(1000)));
+        harness.test_function(&mut executor, "decimals", &[], Some(&uint_token(18)));
+        harness.test_function(&mut executor, "point_sum", &[], Some(&uint_token(1018)));
+        harness.test_function(
+            &mut executor,
+            "double_max_supply",
+            &[],
+            Some(&uint_token(2000)),
+        );
+    })
+}
@@ -1,8 +1,14 @@
 #[cfg(test)]
+mod artifacts;
+#[cfg(test)]
+mod cancel;
+#[cfg(test)]
 mod crashes;
 #[cfg(test)]
 mod demo_erc20;
 #[cfg(test)]
+mod demo_erc721;
+#[cfg(test)]
 mod demo_guestbook;
 #[cfg(test)]
 mod demo_uniswap;
@@ -11,10 +17,18 @@ mod differential;
 #[cfg(test)]
 mod features;
 #[cfg(test)]
+mod files;
+#[cfg(test)]
 mod ingots;
 #[cfg(test)]
+mod mir;
+#[cfg(test)]
 mod runtime;
 #[cfg(test)]
 mod solidity;
 #[cfg(test)]
 mod stress;
+#[cfg(test)]
+mod test_runner;
+#[cfg(test)]
+mod yul_object;
@@ -0,0 +1,67 @@
+use fe_common::files::FileStore;
+use fe_driver::{CompileOptions, CompiledModule};
+use rstest::rstest;
+
+fn compile(path: &str, with_mir: bool) -> CompiledModule {
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, test_files::fixture(path));
+    fe_driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &CompileOptions {
+            with_bytecode: false,
+            with_runtime_bytecode: false,
+            with_mir,
+            optimize: false,
+            ..CompileOptions::default()
+        },
+    )
+    .unwrap_or_else(|_| panic!("failed to compile `{}`", path))
+}
+
+// The lowered module keeps `match` statements over enums and integers, and
+// inline Yul, which the MIR has to represent.
+#[rstest(
+    path,
+    case("features/match_enum.fe"),
+    case("features/match_statement.fe"),
+    case("features/inline_yul.fe")
+)]
+fn mir_is_built_for_lowered_module(path: &str) {
+    let module = compile(path, true);
+    assert!(!module.mir.is_empty());
+}
+
+#[test]
+fn mir_is_only_built_on_request() {
+    let module = compile("features/match_enum.fe", false);
+    assert!(module.mir.is_empty());
+}
+
+// Functions that only compute with values are compiled to Yul from their MIR.
+// A body with more than one block dispatches on the next block to execute.
+#[rstest(
+    path,
+    contract,
+    has_blocks,
+    case("features/pure_fn.fe", "Foo", false),
+    case("features/if_statement.fe", "Foo", true),
+    case("features/math.fe", "Math", true)
+)]
+fn yul_is_generated_from_mir(path: &str, contract: &str, has_blocks: bool) {
+    let module = compile(path, false);
+    let yul = &module.contracts[contract].runtime_yul;
+    assert!(yul.contains("mir$"));
+    assert_eq!(yul.contains("mir$block"), has_blocks);
+}
+
+// Member functions that take `self` are still mapped from the lowered AST,
+// even if they branch.
+#[test]
+fn yul_falls_back_to_lowered_ast() {
+    let module = compile("features/pure_fn_standalone.fe", false);
+    let yul = &module.contracts["Foo"].runtime_yul;
+    assert!(!yul.contains("mir$block"));
+}
@@ -338,3 +338,49 @@ fn external_calls_stress() {
         );
     })
 }
+
+#[test]
+fn small_int_math_stress() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "small_int_math.fe", "SmallIntMath", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "checksum",
+            &[uint_token(20)],
+            Some(&uint_token(871696090)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "checksum",
+            &[uint_token(42)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "walk",
+            &[int_token(10), int_token(-7)],
+            Some(&int_token(-105)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "walk",
+            &[int_token(200), int_token(300)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "mix",
+            &[uint_token(300), int_token(-5)],
+            Some(&int_token(180010)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "mix",
+            &[uint_token(70000), int_token(0)],
+            &encoded_over_or_underflow(),
+        );
+    })
+}
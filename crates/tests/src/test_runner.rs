@@ -0,0 +1,129 @@
+#![cfg(feature = "solc-backend")]
+use fe_common::files::FileStore;
+use fe_test::{Outcome, TestContract};
+
+#[test]
+fn test_runner() {
+    let path = "features/test_runner.fe";
+    let src = test_files::fixture(path);
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, src);
+    let module = fe_driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &fe_driver::CompileOptions {
+            with_runtime_bytecode: false,
+            plugins: &[&fe_driver::RecordDbg],
+            ..fe_driver::CompileOptions::default()
+        },
+    )
+    .expect("failed to compile");
+
+    let contracts = module
+        .contracts
+        .iter()
+        .map(|(name, contract)| TestContract {
+            name,
+            json_abi: &contract.json_abi,
+            bytecode: &contract.bytecode,
+            tests: &contract.tests,
+        })
+        .collect::<Vec<_>>();
+    let results = fe_test::run(&contracts);
+
+    // The values passed to `dbg`, with the expressions at their locations.
+    let dbg_values = results
+        .iter()
+        .flat_map(|result| &result.dbg_values)
+        .map(|value| {
+            assert_eq!(value.file, id.0);
+            (&src[value.start..value.end], value.value.as_str())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        dbg_values,
+        vec![("-2", "-2"), ("counter.get()", "1"), ("x < 0", "true")]
+    );
+
+    let results = results
+        .into_iter()
+        .map(|result| {
+            (
+                format!("{}::{}", result.contract, result.name),
+                result.outcome,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        results,
+        vec![
+            ("CounterTest::increment".to_string(), Outcome::Passed),
+            (
+                "CounterTest::without_increment".to_string(),
+                Outcome::Failed("reverted: \"count should be 1\"".into())
+            ),
+            (
+                "CounterTest::overflow".to_string(),
+                Outcome::Failed("reverted: panic 0x11".into())
+            ),
+            ("CounterTest::debug".to_string(), Outcome::Passed),
+            ("SetupTest::increment".to_string(), Outcome::Passed),
+            ("SetupTest::increment_again".to_string(), Outcome::Passed),
+        ]
+    );
+}
+
+#[test]
+fn cheatcodes() {
+    let path = "features/cheatcodes.fe";
+    let src = test_files::fixture(path);
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, src);
+    let module = fe_driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &fe_driver::CompileOptions {
+            with_runtime_bytecode: false,
+            ..fe_driver::CompileOptions::default()
+        },
+    )
+    .expect("failed to compile");
+
+    let contracts = module
+        .contracts
+        .iter()
+        .map(|(name, contract)| TestContract {
+            name,
+            json_abi: &contract.json_abi,
+            bytecode: &contract.bytecode,
+            tests: &contract.tests,
+        })
+        .collect::<Vec<_>>();
+    let results = fe_test::run(&contracts)
+        .into_iter()
+        .map(|result| (result.name, result.outcome))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        results,
+        vec![
+            ("warp".to_string(), Outcome::Passed),
+            ("roll".to_string(), Outcome::Passed),
+            ("expect_revert".to_string(), Outcome::Passed),
+            (
+                "expect_revert_without_revert".to_string(),
+                Outcome::Failed("expected a revert".into())
+            ),
+            ("expect_emit".to_string(), Outcome::Passed),
+            (
+                "expect_emit_with_other_value".to_string(),
+                Outcome::Failed("emitted a different event than expected".into())
+            ),
+        ]
+    );
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "solc-backend")]
+use fe_common::files::FileStore;
+
+#[test]
+fn yul_objects_compile_on_their_own() {
+    let path = "demos/erc20_token.fe";
+    let src = test_files::fixture(path);
+    let mut files = FileStore::new();
+    let deps = files.add_included_libraries();
+    let id = files.add_file(path, src);
+    let module = fe_driver::compile_module(
+        &files,
+        id,
+        &deps,
+        &fe_driver::CompileOptions {
+            with_runtime_bytecode: false,
+            ..fe_driver::CompileOptions::default()
+        },
+    )
+    .expect("failed to compile");
+
+    for (name, contract) in module.contracts {
+        // Escape the object the way solc's JSON input expects it, like a
+        // build tool would, and check that solc compiles it to the same
+        // bytecode.
+        let yul_src = contract
+            .yul_object
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        let bytecode = fe_yulc::compile_single_contract(&name, yul_src, true, None)
+            .unwrap_or_else(|err| panic!("solc rejected the Yul object of `{}`: {}", name, err.0));
+        assert_eq!(bytecode, contract.bytecode, "bytecode of `{}`", name);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::types::Scalar;
+use crate::{function_name, unsupported, WasmError};
+use fe_abi::utils::func_selector;
+use fe_analyzer::namespace::items::{ContractId, FunctionId};
+use fe_mir::MirDb;
+use std::fmt::Write;
+
+/// The functions that `main` calls.
+pub fn entry_points(db: &dyn MirDb, contract: ContractId) -> Vec<FunctionId> {
+    let adb = db.upcast();
+    contract
+        .public_functions(adb)
+        .values()
+        .copied()
+        .chain(contract.fallback_function(adb))
+        .chain(contract.receive_function(adb))
+        .collect()
+}
+
+/// Builds the exported `main` function, which calls the receive function if
+/// there is no call data, the public function with the selector of the call
+/// data, or the fallback function otherwise.
+pub fn main(db: &dyn MirDb, contract: ContractId) -> Result<String, WasmError> {
+    let adb = db.upcast();
+    let name = contract.name(adb);
+    if contract.init_function(adb).is_some() {
+        return Err(unsupported(&name, "a constructor"));
+    }
+    if contract.call_function(adb).is_some() {
+        return Err(unsupported(&name, "a `__call__` function"));
+    }
+    if contract.is_multicall(adb) {
+        return Err(unsupported(&name, "`multicall`"));
+    }
+
+    let mut wat = String::new();
+    wat.push_str("  (func $main\n");
+    wat.push_str("    (local $selector i32)\n");
+    if let Some(receive) = contract.receive_function(adb) {
+        let body = db.mir_function_body(receive);
+        writeln!(
+            wat,
+            "    (if (i32.eqz (call $getCallDataSize))\n      (then\n        (drop (call {}))\n        (call $finish (i32.const 0) (i32.const 0))\n        (return)))",
+            function_name(&body.name)
+        )
+        .unwrap();
+    }
+
+    wat.push_str("    (if (i32.ge_u (call $getCallDataSize) (i32.const 4))\n      (then\n");
+    wat.push_str("        (call $callDataCopy (i32.const 0) (i32.const 0) (i32.const 4))\n");
+    wat.push_str("        (local.set $selector (i32.wrap_i64 (i64.shr_u (call $bswap64 (i64.extend_i32_u (i32.load (i32.const 0)))) (i64.const 32))))\n");
+    for (fn_name, id) in contract.public_functions(adb).iter() {
+        let body = db.mir_function_body(*id);
+        let params = body
+            .params
+            .iter()
+            .map(|param| {
+                let typ = &body.local(*param).typ;
+                Scalar::of(typ)
+                    .filter(|scalar| *scalar != Scalar::Unit)
+                    .ok_or_else(|| unsupported(&body.name, format!("the type `{}`", typ)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let returns = Scalar::of(&body.return_type)
+            .ok_or_else(|| unsupported(&body.name, format!("the type `{}`", body.return_type)))?;
+
+        let selector = func_selector(
+            fn_name,
+            &params
+                .iter()
+                .map(|scalar| scalar.abi_name())
+                .collect::<Vec<_>>(),
+        );
+        let args = params
+            .iter()
+            .enumerate()
+            .map(|(idx, scalar)| {
+                let word = format!(
+                    "(call $load_word (i32.const {}) {})",
+                    64 + 32 * idx,
+                    scalar.signed_flag()
+                );
+                let (min, max) = scalar.range();
+                match scalar.bits() {
+                    64 => format!(" {}", word),
+                    _ if scalar.is_signed() => format!(
+                        " (call $arg_s {} (i64.const {}) (i64.const {}))",
+                        word, min, max
+                    ),
+                    _ => format!(" (call $arg_u {} (i64.const {}))", word, max),
+                }
+            })
+            .collect::<String>();
+        let call = format!("(call {}{})", function_name(&body.name), args);
+
+        writeln!(
+            wat,
+            "        (if (i32.eq (local.get $selector) (i32.const {}))\n          (then\n            (call $copy_args (i32.const {}))",
+            selector,
+            params.len()
+        )
+        .unwrap();
+        if returns == Scalar::Unit {
+            writeln!(wat, "            (drop {})", call).unwrap();
+            wat.push_str("            (call $finish (i32.const 0) (i32.const 0))\n");
+        } else {
+            writeln!(
+                wat,
+                "            (call $store_word (i32.const 0) {} {})",
+                call,
+                returns.signed_flag()
+            )
+            .unwrap();
+            wat.push_str("            (call $finish (i32.const 0) (i32.const 32))\n");
+        }
+        wat.push_str("            (return)))\n");
+    }
+    wat.push_str("      ))\n");
+
+    if let Some(fallback) = contract.fallback_function(adb) {
+        let body = db.mir_function_body(fallback);
+        writeln!(wat, "    (drop (call {}))", function_name(&body.name)).unwrap();
+    }
+    wat.push_str("    (call $finish (i32.const 0) (i32.const 0)))\n");
+    Ok(wat)
+}
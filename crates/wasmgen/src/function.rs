@@ -0,0 +1,382 @@
+use crate::types::Scalar;
+use crate::{function_name, unsupported, WasmError};
+use fe_analyzer::namespace::items::{ContractId, FunctionId};
+use fe_analyzer::AnalyzerDb;
+use fe_mir::ir::{
+    BlockId, Callee, Constant, FunctionBody, LocalId, Operand, Place, PlaceBase, Rvalue, Statement,
+    Terminator,
+};
+use fe_parser::ast::{BinOperator, CompOperator, UnaryOperator};
+use std::fmt::Write;
+
+/// The user defined functions that a function calls.
+pub fn callees(body: &FunctionBody) -> Vec<FunctionId> {
+    let mut callees = vec![];
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            let value = match stmt {
+                Statement::Assign { value, .. } | Statement::Eval(value) => value,
+                Statement::Emit { .. } | Statement::InlineYul(_) => continue,
+            };
+            if let Rvalue::Call {
+                callee: Callee::Function { id, .. },
+                ..
+            } = value
+            {
+                callees.push(*id);
+            }
+        }
+    }
+    callees
+}
+
+/// Compiles the body of a function, which is a control flow graph, to a loop
+/// around a `br_table` that jumps to the code of the current block. Each
+/// block sets the next block and continues the loop, or leaves the function.
+pub fn function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    body: &FunctionBody,
+) -> Result<String, WasmError> {
+    let builder = FunctionBuilder { db, contract, body };
+
+    let mut wat = String::new();
+    write!(wat, "  (func {}", function_name(&body.name)).unwrap();
+    for param in &body.params {
+        builder.local_scalar(*param)?;
+        write!(wat, " (param ${} i64)", param).unwrap();
+    }
+    builder.scalar(&body.return_type)?;
+    wat.push_str(" (result i64)\n");
+    for idx in 0..body.locals.len() {
+        let local = LocalId(idx as u32);
+        if !body.params.contains(&local) {
+            builder.local_scalar(local)?;
+            writeln!(wat, "    (local ${} i64)", local).unwrap();
+        }
+    }
+    wat.push_str("    (local $bb i32)\n");
+    wat.push_str("    (loop $dispatch\n");
+
+    let blocks = (0..body.blocks.len())
+        .map(|idx| format!("${}", BlockId(idx as u32)))
+        .collect::<Vec<_>>();
+    for label in blocks.iter().rev() {
+        writeln!(wat, "      (block {}", label).unwrap();
+    }
+    writeln!(
+        wat,
+        "        (br_table {} (local.get $bb)))",
+        blocks.join(" ")
+    )
+    .unwrap();
+
+    for (idx, block) in body.blocks.iter().enumerate() {
+        writeln!(wat, "      ;; {}", BlockId(idx as u32)).unwrap();
+        for stmt in &block.statements {
+            writeln!(wat, "      {}", builder.statement(stmt)?).unwrap();
+        }
+        let terminator = block.terminator.as_ref().expect("unterminated block");
+        writeln!(wat, "      {}", builder.terminator(terminator)?).unwrap();
+        if idx + 1 < body.blocks.len() {
+            wat.push_str("      )\n");
+        }
+    }
+    wat.push_str("    )\n");
+    wat.push_str("    (unreachable))\n");
+    Ok(wat)
+}
+
+struct FunctionBuilder<'a> {
+    db: &'a dyn AnalyzerDb,
+    contract: ContractId,
+    body: &'a FunctionBody,
+}
+
+impl<'a> FunctionBuilder<'a> {
+    fn unsupported(&self, feature: impl Into<String>) -> WasmError {
+        unsupported(&self.body.name, feature)
+    }
+
+    fn scalar(&self, typ: &fe_analyzer::namespace::types::Type) -> Result<Scalar, WasmError> {
+        Scalar::of(typ).ok_or_else(|| self.unsupported(format!("the type `{}`", typ)))
+    }
+
+    fn local_scalar(&self, local: LocalId) -> Result<Scalar, WasmError> {
+        self.scalar(&self.body.local(local).typ)
+    }
+
+    fn place_scalar(&self, place: &Place) -> Result<Scalar, WasmError> {
+        if !place.projections.is_empty() {
+            return Err(self.unsupported("struct fields, array elements or map values"));
+        }
+        match &place.base {
+            PlaceBase::Local(local) => self.local_scalar(*local),
+            PlaceBase::ContractField { name, .. } => {
+                match self.contract.field_type(self.db, name) {
+                    Some((Ok(typ), _)) => self.scalar(&typ),
+                    _ => panic!("missing type of contract field `{}`", name),
+                }
+            }
+        }
+    }
+
+    /// The type of an operand, or `None` for a number literal, whose type
+    /// depends on where it's used.
+    fn operand_scalar(&self, operand: &Operand) -> Result<Option<Scalar>, WasmError> {
+        match operand {
+            Operand::Place(place) => self.place_scalar(place).map(Some),
+            Operand::Const(Constant::Bool(_)) => Ok(Some(Scalar::Bool)),
+            Operand::Const(Constant::Unit) => Ok(Some(Scalar::Unit)),
+            Operand::Const(_) => Ok(None),
+        }
+    }
+
+    fn read(&self, place: &Place) -> Result<String, WasmError> {
+        let scalar = self.place_scalar(place)?;
+        Ok(match &place.base {
+            PlaceBase::Local(local) => format!("(local.get ${})", local),
+            PlaceBase::ContractField { nonce, .. } => format!(
+                "(call $load_field (i64.const {}) {})",
+                nonce,
+                scalar.signed_flag()
+            ),
+        })
+    }
+
+    fn write(&self, place: &Place, value: String) -> Result<String, WasmError> {
+        let scalar = self.place_scalar(place)?;
+        Ok(match &place.base {
+            PlaceBase::Local(local) => format!("(local.set ${} {})", local, value),
+            PlaceBase::ContractField { nonce, .. } => format!(
+                "(call $store_field (i64.const {}) {} {})",
+                nonce,
+                value,
+                scalar.signed_flag()
+            ),
+        })
+    }
+
+    fn operand(&self, operand: &Operand) -> Result<String, WasmError> {
+        match operand {
+            Operand::Place(place) => self.read(place),
+            Operand::Const(Constant::Int(num)) if num.bits() <= 64 => {
+                Ok(format!("(i64.const {})", num))
+            }
+            Operand::Const(Constant::Int(num)) => {
+                Err(self.unsupported(format!("the number `{}`", num)))
+            }
+            Operand::Const(Constant::Bool(val)) => Ok(format!("(i64.const {})", *val as u8)),
+            Operand::Const(Constant::Unit) => Ok("(i64.const 0)".to_string()),
+            Operand::Const(Constant::Str(_)) => Err(self.unsupported("strings")),
+        }
+    }
+
+    fn statement(&self, stmt: &Statement) -> Result<String, WasmError> {
+        match stmt {
+            Statement::Assign { place, value } => {
+                let scalar = self.place_scalar(place)?;
+                let value = self.rvalue(value, Some(scalar))?;
+                self.write(place, value)
+            }
+            Statement::Eval(value) => Ok(format!("(drop {})", self.rvalue(value, None)?)),
+            Statement::Emit { .. } => Err(self.unsupported("events")),
+            Statement::InlineYul(_) => Err(self.unsupported("inline Yul")),
+        }
+    }
+
+    fn terminator(&self, terminator: &Terminator) -> Result<String, WasmError> {
+        Ok(match terminator {
+            Terminator::Goto(target) => {
+                format!("(local.set $bb (i32.const {})) (br $dispatch)", target.0)
+            }
+            Terminator::Branch {
+                cond,
+                then_block,
+                else_block,
+            } => format!(
+                "(local.set $bb (select (i32.const {}) (i32.const {}) (i32.wrap_i64 {}))) (br $dispatch)",
+                then_block.0,
+                else_block.0,
+                self.operand(cond)?
+            ),
+            Terminator::Return(value) => format!("(return {})", self.operand(value)?),
+            Terminator::Revert(None) => "(call $fail) (unreachable)".to_string(),
+            Terminator::Revert(Some(_)) => return Err(self.unsupported("`revert` with an error")),
+            Terminator::AssertFailure(None) => {
+                "(call $panic (i64.const 0x01)) (unreachable)".to_string()
+            }
+            Terminator::AssertFailure(Some(_)) => {
+                return Err(self.unsupported("`assert` with a message"))
+            }
+        })
+    }
+
+    /// Compiles a value. `typ` is the type of the place it's assigned to, if
+    /// there is one.
+    fn rvalue(&self, value: &Rvalue, typ: Option<Scalar>) -> Result<String, WasmError> {
+        match value {
+            Rvalue::Use(operand) => self.operand(operand),
+            Rvalue::BinOp { op, lhs, rhs } => {
+                let scalar = match typ {
+                    Some(scalar) => scalar,
+                    None => self
+                        .operand_scalar(lhs)?
+                        .or(self.operand_scalar(rhs)?)
+                        .ok_or_else(|| self.unsupported("an operation on number literals"))?,
+                };
+                self.bin_op(op, scalar, self.operand(lhs)?, self.operand(rhs)?)
+            }
+            Rvalue::CompOp { op, lhs, rhs } => {
+                let signed = self
+                    .operand_scalar(lhs)?
+                    .or(self.operand_scalar(rhs)?)
+                    .map_or(false, Scalar::is_signed);
+                let sign = if signed { "s" } else { "u" };
+                let instr = match op {
+                    CompOperator::Eq => "i64.eq".to_string(),
+                    CompOperator::NotEq => "i64.ne".to_string(),
+                    CompOperator::Lt => format!("i64.lt_{}", sign),
+                    CompOperator::LtE => format!("i64.le_{}", sign),
+                    CompOperator::Gt => format!("i64.gt_{}", sign),
+                    CompOperator::GtE => format!("i64.ge_{}", sign),
+                };
+                Ok(format!(
+                    "(i64.extend_i32_u ({} {} {}))",
+                    instr,
+                    self.operand(lhs)?,
+                    self.operand(rhs)?
+                ))
+            }
+            Rvalue::UnOp { op, operand } => {
+                let scalar = match typ {
+                    Some(scalar) => Some(scalar),
+                    None => self.operand_scalar(operand)?,
+                };
+                let value = self.operand(operand)?;
+                match (op, scalar) {
+                    (UnaryOperator::Not, _) => {
+                        Ok(format!("(i64.extend_i32_u (i64.eqz {}))", value))
+                    }
+                    (UnaryOperator::USub, Some(scalar)) => self.bin_op(
+                        &BinOperator::Sub,
+                        scalar,
+                        "(i64.const 0)".to_string(),
+                        value,
+                    ),
+                    (UnaryOperator::Invert, Some(scalar)) => {
+                        Ok(scalar.truncate(format!("(i64.xor {} (i64.const -1))", value)))
+                    }
+                    _ => Err(self.unsupported("an operation on number literals")),
+                }
+            }
+            Rvalue::Slice { .. } => Err(self.unsupported("slices")),
+            Rvalue::Env { object, field } => {
+                Err(self.unsupported(format!("`{}.{}`", object, field)))
+            }
+            Rvalue::Call { callee, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.operand(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match callee {
+                    Callee::Function { name, .. } => Ok(format!(
+                        "(call {}{})",
+                        function_name(name),
+                        args.iter()
+                            .map(|arg| format!(" {}", arg))
+                            .collect::<String>()
+                    )),
+                    Callee::TypeConstructor(to) => match (self.scalar(to)?, args.as_slice()) {
+                        (scalar @ Scalar::Int(_), [value]) => Ok(scalar.truncate(value.clone())),
+                        _ => Err(self.unsupported(format!("the `{}` constructor", to))),
+                    },
+                    Callee::External { .. } => Err(self.unsupported("calls to other contracts")),
+                    Callee::Builtin(name) => {
+                        Err(self.unsupported(format!("the builtin `{}`", name)))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Arithmetic is checked, and panics on overflow like the EVM backend.
+    fn bin_op(
+        &self,
+        op: &BinOperator,
+        scalar: Scalar,
+        lhs: String,
+        rhs: String,
+    ) -> Result<String, WasmError> {
+        let int = match scalar {
+            Scalar::Int(int) => int,
+            _ => return Err(self.unsupported(format!("`{}` on `{:?}` values", op, scalar))),
+        };
+        let signed = int.is_signed();
+        let sign = if signed { "s" } else { "u" };
+        let wide = scalar.bits() == 64;
+        let (min, max) = scalar.range();
+        let checked = |value: String| {
+            if signed {
+                format!(
+                    "(call $check_s {} (i64.const {}) (i64.const {}))",
+                    value, min, max
+                )
+            } else {
+                format!("(call $check_u {} (i64.const {}))", value, max)
+            }
+        };
+
+        Ok(match op {
+            BinOperator::Add | BinOperator::Sub | BinOperator::Mult => {
+                let name = match op {
+                    BinOperator::Add => "add",
+                    BinOperator::Sub => "sub",
+                    _ => "mul",
+                };
+                if wide {
+                    format!(
+                        "(call ${}_{}64 {} {})",
+                        name,
+                        if signed { "i" } else { "u" },
+                        lhs,
+                        rhs
+                    )
+                } else {
+                    checked(format!("(i64.{} {} {})", name, lhs, rhs))
+                }
+            }
+            BinOperator::Div | BinOperator::Mod => {
+                let name = if *op == BinOperator::Div {
+                    "div"
+                } else {
+                    "rem"
+                };
+                let value = format!("(i64.{}_{} {} (call $nonzero {}))", name, sign, lhs, rhs);
+                // The quotient of the smallest value by -1 doesn't fit.
+                if signed && !wide && name == "div" {
+                    checked(value)
+                } else {
+                    value
+                }
+            }
+            BinOperator::BitAnd => format!("(i64.and {} {})", lhs, rhs),
+            BinOperator::BitOr => format!("(i64.or {} {})", lhs, rhs),
+            BinOperator::BitXor => format!("(i64.xor {} {})", lhs, rhs),
+            // Shifting by the width of the type or more shifts all bits out.
+            BinOperator::LShift => scalar.truncate(format!(
+                "(select (i64.const 0) (i64.shl {} {}) (i64.ge_u {} (i64.const 64)))",
+                lhs, rhs, rhs
+            )),
+            BinOperator::RShift if signed => format!(
+                "(i64.shr_s {} (select (i64.const 63) {} (i64.ge_u {} (i64.const 64))))",
+                lhs, rhs, rhs
+            ),
+            BinOperator::RShift => format!(
+                "(select (i64.const 0) (i64.shr_u {} {}) (i64.ge_u {} (i64.const 64)))",
+                lhs, rhs, rhs
+            ),
+            BinOperator::Pow => return Err(self.unsupported("`**`")),
+        })
+    }
+}
@@ -0,0 +1,109 @@
+//! An experimental backend that compiles the Fe mid-level IR to WebAssembly,
+//! for eWASM-style execution environments.
+//!
+//! Each contract is compiled to a module in the WebAssembly text format. The
+//! module imports the host functions of the Ethereum Environment Interface
+//! from the `ethereum` namespace, and exports a `main` function that
+//! dispatches calls by the selectors of the contract's ABI, like the Yul
+//! backend does on the EVM.
+//!
+//! The backend only supports a small part of the language. Every value is an
+//! `i64`, so the only types are `bool`, `()` and the integers of at most 64
+//! bits, and contracts can only use plain storage fields of these types,
+//! arithmetic, comparisons, control flow, `assert` without a message and
+//! `revert` without an error. Contracts that use anything else, e.g. `u256`
+//! or a builtin function, aren't compiled, with a [`WasmError`] that names
+//! the first unsupported feature.
+
+use fe_analyzer::namespace::items::{ContractId, ModuleId};
+use fe_mir::MirDb;
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+use std::fmt;
+
+mod dispatcher;
+mod function;
+mod types;
+
+/// The host functions and helpers that every module includes.
+const RUNTIME: &str = include_str!("runtime.wat");
+
+/// The reason that a contract can't be compiled to WASM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmError {
+    /// The path of the function that uses the feature, e.g. `Foo::bar`.
+    pub function: SmolStr,
+    /// The feature, e.g. "the type `u256`".
+    pub feature: String,
+}
+
+impl fmt::Display for WasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` uses {}, which the WASM backend doesn't support",
+            self.function, self.feature
+        )
+    }
+}
+
+impl std::error::Error for WasmError {}
+
+/// Compiles the contracts of a lowered module to WASM text, by name.
+/// Interfaces have no code and are skipped.
+pub fn compile(db: &dyn MirDb, module: ModuleId) -> IndexMap<String, Result<String, WasmError>> {
+    let adb = db.upcast();
+    module
+        .all_contracts(adb)
+        .iter()
+        .filter(|contract| !contract.is_interface(adb))
+        .map(|contract| {
+            (
+                contract.name(adb).to_string(),
+                compile_contract(db, *contract),
+            )
+        })
+        .collect()
+}
+
+fn compile_contract(db: &dyn MirDb, contract: ContractId) -> Result<String, WasmError> {
+    let adb = db.upcast();
+    let main = dispatcher::main(db, contract)?;
+
+    let mut functions = vec![];
+    let mut queue = dispatcher::entry_points(db, contract);
+    while let Some(id) = queue.pop() {
+        if functions.contains(&id) {
+            continue;
+        }
+        functions.push(id);
+        queue.extend(function::callees(&db.mir_function_body(id)));
+    }
+
+    let mut module = String::from("(module\n");
+    module.push_str(RUNTIME);
+    for id in functions {
+        module.push('\n');
+        module.push_str(&function::function(
+            adb,
+            contract,
+            &db.mir_function_body(id),
+        )?);
+    }
+    module.push('\n');
+    module.push_str(&main);
+    module.push_str(")\n");
+    Ok(module)
+}
+
+/// The WASM name of a function, which is its MIR name.
+fn function_name(body_name: &str) -> String {
+    format!("${}", body_name)
+}
+
+fn unsupported(function: &str, feature: impl Into<String>) -> WasmError {
+    WasmError {
+        function: function.into(),
+        feature: feature.into(),
+    }
+}
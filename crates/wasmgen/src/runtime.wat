@@ -0,0 +1,177 @@
+  (import "ethereum" "getCallDataSize" (func $getCallDataSize (result i32)))
+  (import "ethereum" "callDataCopy" (func $callDataCopy (param i32 i32 i32)))
+  (import "ethereum" "storageLoad" (func $storageLoad (param i32 i32)))
+  (import "ethereum" "storageStore" (func $storageStore (param i32 i32)))
+  (import "ethereum" "finish" (func $finish (param i32 i32)))
+  (import "ethereum" "revert" (func $revert (param i32 i32)))
+
+  ;; The first 64 bytes of memory hold storage keys and values, return data
+  ;; and revert data. The arguments of a call are copied to the memory after
+  ;; them.
+  (memory 1)
+  (export "memory" (memory 0))
+  (export "main" (func $main))
+
+  ;; Reverses the bytes of a value, which converts between the big endian ABI
+  ;; encoding and the little endian loads and stores of WASM.
+  (func $bswap64 (param $x i64) (result i64)
+    (local $result i64)
+    (local $i i32)
+    (loop $next
+      (local.set $result
+        (i64.or
+          (i64.shl (local.get $result) (i64.const 8))
+          (i64.and (local.get $x) (i64.const 0xff))))
+      (local.set $x (i64.shr_u (local.get $x) (i64.const 8)))
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $next (i32.lt_u (local.get $i) (i32.const 8))))
+    (local.get $result))
+
+  ;; Writes a value as a 32 byte ABI word, which is sign extended if the value
+  ;; is signed.
+  (func $store_word (param $ptr i32) (param $value i64) (param $signed i32)
+    (local $fill i64)
+    (if (i32.and (local.get $signed) (i64.lt_s (local.get $value) (i64.const 0)))
+      (then (local.set $fill (i64.const -1))))
+    (i64.store (local.get $ptr) (local.get $fill))
+    (i64.store offset=8 (local.get $ptr) (local.get $fill))
+    (i64.store offset=16 (local.get $ptr) (local.get $fill))
+    (i64.store offset=24 (local.get $ptr) (call $bswap64 (local.get $value))))
+
+  ;; Reads a 32 byte ABI word, whose value must fit in 64 bits.
+  (func $load_word (param $ptr i32) (param $signed i32) (result i64)
+    (local $value i64)
+    (local $fill i64)
+    (local.set $value (call $bswap64 (i64.load offset=24 (local.get $ptr))))
+    (if (i32.and (local.get $signed) (i64.lt_s (local.get $value) (i64.const 0)))
+      (then (local.set $fill (i64.const -1))))
+    (if (i32.or
+          (i64.ne (i64.load (local.get $ptr)) (local.get $fill))
+          (i32.or
+            (i64.ne (i64.load offset=8 (local.get $ptr)) (local.get $fill))
+            (i64.ne (i64.load offset=16 (local.get $ptr)) (local.get $fill))))
+      (then (call $fail)))
+    (local.get $value))
+
+  (func $load_field (param $slot i64) (param $signed i32) (result i64)
+    (call $store_word (i32.const 0) (local.get $slot) (i32.const 0))
+    (call $storageLoad (i32.const 0) (i32.const 32))
+    (call $load_word (i32.const 32) (local.get $signed)))
+
+  (func $store_field (param $slot i64) (param $value i64) (param $signed i32)
+    (call $store_word (i32.const 0) (local.get $slot) (i32.const 0))
+    (call $store_word (i32.const 32) (local.get $value) (local.get $signed))
+    (call $storageStore (i32.const 0) (i32.const 32)))
+
+  ;; Copies the arguments of a call to memory, or reverts if the calldata is
+  ;; too short.
+  (func $copy_args (param $count i32)
+    (local $size i32)
+    (local.set $size (i32.mul (local.get $count) (i32.const 32)))
+    (if (i32.lt_u (call $getCallDataSize) (i32.add (local.get $size) (i32.const 4)))
+      (then (call $fail)))
+    (call $callDataCopy (i32.const 64) (i32.const 4) (local.get $size)))
+
+  (func $arg_u (param $value i64) (param $max i64) (result i64)
+    (if (i64.gt_u (local.get $value) (local.get $max))
+      (then (call $fail)))
+    (local.get $value))
+
+  (func $arg_s (param $value i64) (param $min i64) (param $max i64) (result i64)
+    (if (i32.or
+          (i64.lt_s (local.get $value) (local.get $min))
+          (i64.gt_s (local.get $value) (local.get $max)))
+      (then (call $fail)))
+    (local.get $value))
+
+  (func $fail
+    (call $revert (i32.const 0) (i32.const 0))
+    (unreachable))
+
+  ;; Reverts with `Panic(uint256)`, with the same codes as the EVM backend.
+  (func $panic (param $code i64)
+    (i32.store (i32.const 0) (i32.const 0x717b484e))
+    (call $store_word (i32.const 4) (local.get $code) (i32.const 0))
+    (call $revert (i32.const 0) (i32.const 36))
+    (unreachable))
+
+  ;; Integers narrower than 64 bits are added, subtracted and multiplied
+  ;; without overflowing 64 bits, and the result is checked against the range
+  ;; of their type.
+  (func $check_u (param $value i64) (param $max i64) (result i64)
+    (if (i64.gt_u (local.get $value) (local.get $max))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $value))
+
+  (func $check_s (param $value i64) (param $min i64) (param $max i64) (result i64)
+    (if (i32.or
+          (i64.lt_s (local.get $value) (local.get $min))
+          (i64.gt_s (local.get $value) (local.get $max)))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $value))
+
+  (func $add_u64 (param $a i64) (param $b i64) (result i64)
+    (local $sum i64)
+    (local.set $sum (i64.add (local.get $a) (local.get $b)))
+    (if (i64.lt_u (local.get $sum) (local.get $a))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $sum))
+
+  (func $sub_u64 (param $a i64) (param $b i64) (result i64)
+    (if (i64.lt_u (local.get $a) (local.get $b))
+      (then (call $panic (i64.const 0x11))))
+    (i64.sub (local.get $a) (local.get $b)))
+
+  (func $mul_u64 (param $a i64) (param $b i64) (result i64)
+    (local $product i64)
+    (local.set $product (i64.mul (local.get $a) (local.get $b)))
+    (if (i32.and
+          (i64.ne (local.get $b) (i64.const 0))
+          (i64.ne (i64.div_u (local.get $product) (local.get $b)) (local.get $a)))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $product))
+
+  (func $add_i64 (param $a i64) (param $b i64) (result i64)
+    (local $sum i64)
+    (local.set $sum (i64.add (local.get $a) (local.get $b)))
+    (if (i64.lt_s
+          (i64.and
+            (i64.xor (local.get $a) (local.get $sum))
+            (i64.xor (local.get $b) (local.get $sum)))
+          (i64.const 0))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $sum))
+
+  (func $sub_i64 (param $a i64) (param $b i64) (result i64)
+    (local $difference i64)
+    (local.set $difference (i64.sub (local.get $a) (local.get $b)))
+    (if (i64.lt_s
+          (i64.and
+            (i64.xor (local.get $a) (local.get $b))
+            (i64.xor (local.get $a) (local.get $difference)))
+          (i64.const 0))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $difference))
+
+  (func $mul_i64 (param $a i64) (param $b i64) (result i64)
+    (local $product i64)
+    (if (i32.or
+          (i32.and
+            (i64.eq (local.get $a) (i64.const -1))
+            (i64.eq (local.get $b) (i64.const -9223372036854775808)))
+          (i32.and
+            (i64.eq (local.get $b) (i64.const -1))
+            (i64.eq (local.get $a) (i64.const -9223372036854775808))))
+      (then (call $panic (i64.const 0x11))))
+    (local.set $product (i64.mul (local.get $a) (local.get $b)))
+    (if (i32.and
+          (i64.ne (local.get $b) (i64.const 0))
+          (i64.ne (i64.div_s (local.get $product) (local.get $b)) (local.get $a)))
+      (then (call $panic (i64.const 0x11))))
+    (local.get $product))
+
+  ;; Division by zero panics rather than trapping.
+  (func $nonzero (param $value i64) (result i64)
+    (if (i64.eqz (local.get $value))
+      (then (call $panic (i64.const 0x12))))
+    (local.get $value))
@@ -0,0 +1,83 @@
+use fe_analyzer::namespace::types::{Base, Integer, Type};
+
+/// A type whose values are represented as an `i64`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Scalar {
+    Unit,
+    Bool,
+    Int(Integer),
+}
+
+impl Scalar {
+    /// The scalar of a Fe type, or `None` if it doesn't fit in 64 bits.
+    pub fn of(typ: &Type) -> Option<Self> {
+        match typ {
+            Type::Base(Base::Unit) => Some(Scalar::Unit),
+            Type::Base(Base::Bool) => Some(Scalar::Bool),
+            Type::Base(Base::Numeric(int)) if int.size() <= 8 => Some(Scalar::Int(*int)),
+            _ => None,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, Scalar::Int(int) if int.is_signed())
+    }
+
+    /// The `i32` flag of the runtime functions that take the signedness of a
+    /// value.
+    pub fn signed_flag(self) -> &'static str {
+        if self.is_signed() {
+            "(i32.const 1)"
+        } else {
+            "(i32.const 0)"
+        }
+    }
+
+    /// The name of the type in the Solidity ABI, which selectors are computed
+    /// from.
+    pub fn abi_name(self) -> String {
+        match self {
+            Scalar::Unit => "()".to_string(),
+            Scalar::Bool => "bool".to_string(),
+            Scalar::Int(int) if int.is_signed() => format!("int{}", self.bits()),
+            Scalar::Int(_) => format!("uint{}", self.bits()),
+        }
+    }
+
+    /// The number of bits of the values, which are zero or sign extended to 64
+    /// bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            Scalar::Unit | Scalar::Bool => 1,
+            Scalar::Int(int) => int.size() as u32 * 8,
+        }
+    }
+
+    /// The smallest and largest value, as `i64` literals.
+    pub fn range(self) -> (String, String) {
+        let bits = self.bits();
+        if self.is_signed() {
+            let max = (1i128 << (bits - 1)) - 1;
+            ((-max - 1).to_string(), max.to_string())
+        } else {
+            ("0".to_string(), ((1u128 << bits) - 1).to_string())
+        }
+    }
+
+    /// Wraps a 64 bit value to the range of the type, which is how explicit
+    /// conversions and the bitwise operators treat the bits that don't fit.
+    pub fn truncate(self, value: String) -> String {
+        let bits = self.bits();
+        if bits == 64 {
+            value
+        } else if self.is_signed() {
+            let shift = 64 - bits;
+            format!(
+                "(i64.shr_s (i64.shl {} (i64.const {})) (i64.const {}))",
+                value, shift, shift
+            )
+        } else {
+            format!("(i64.and {} (i64.const {}))", value, (1u64 << bits) - 1)
+        }
+    }
+}
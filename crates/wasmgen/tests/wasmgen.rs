@@ -0,0 +1,91 @@
+use fe_analyzer::namespace::items::{Global, Module, ModuleContext, ModuleFileContent, ModuleId};
+use fe_analyzer::AnalyzerDb;
+use fe_common::diagnostics::print_diagnostics;
+use fe_common::files::FileStore;
+use fe_mir::TestDb;
+use fe_wasmgen::WasmError;
+use std::rc::Rc;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn analyzed_module(db: &TestDb, path: &str, files: &mut FileStore) -> ModuleId {
+    let src = test_files::fixture(path);
+    let id = files.add_file(path, src);
+    let ast = match fe_parser::parse_file(id, src) {
+        Ok((module, diags)) if diags.is_empty() => module,
+        Ok((_, diags)) | Err(diags) => {
+            print_diagnostics(&diags, files);
+            panic!("failed to parse file");
+        }
+    };
+
+    let global_id = db.intern_global(Rc::new(Global::default()));
+    let module_id = db.intern_module(Rc::new(Module {
+        name: "test_module".into(),
+        context: ModuleContext::Global(global_id),
+        file_content: ModuleFileContent::File { file: id },
+        ast,
+    }));
+
+    if let Err(diags) = fe_analyzer::analyze_module(db, module_id) {
+        print_diagnostics(&diags, files);
+        panic!("failed to analyze module");
+    }
+    module_id
+}
+
+fn compile(path: &str, contract: &str) -> Result<String, WasmError> {
+    let mut files = FileStore::new();
+    let db = TestDb::default();
+    let module_id = analyzed_module(&db, path, &mut files);
+    fe_wasmgen::compile(&db, module_id)
+        .remove(contract)
+        .expect("missing contract")
+}
+
+/// Asserts that each opened s-expression of the module is closed.
+fn assert_balanced(wat: &str) {
+    let mut depth = 0i32;
+    for c in wat.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        assert!(depth >= 0, "unbalanced module:\n{}", wat);
+    }
+    assert_eq!(depth, 0, "unbalanced module:\n{}", wat);
+}
+
+macro_rules! test_file {
+    ($name:ident, $path:expr, $contract:expr, [$($function:expr),*]) => {
+        #[test]
+        #[wasm_bindgen_test]
+        fn $name() {
+            let wat = compile($path, $contract).unwrap_or_else(|err| panic!("{}", err));
+            assert_balanced(&wat);
+            assert!(wat.contains("(func $main"));
+            $(assert!(wat.contains(&format!("(func ${}", $function)), "{}", wat);)*
+        }
+    };
+}
+
+test_file! { return_identity_u8, "features/return_identity_u8.fe", "Foo", ["Foo::bar"] }
+test_file! { return_identity_u64, "features/return_identity_u64.fe", "Foo", ["Foo::bar"] }
+test_file! { return_invert_i64, "features/return_invert_i64.fe", "Foo", ["Foo::bar"] }
+test_file! { counter_u64, "features/counter_u64.fe", "Counter", ["Counter::increment", "Counter::get"] }
+
+#[test]
+#[wasm_bindgen_test]
+fn unsupported_type() {
+    let err = compile("features/if_statement.fe", "Foo").unwrap_err();
+    assert_eq!(err.function, "Foo::bar");
+    assert_eq!(err.feature, "the type `u256`");
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn storage_fields_use_their_nonce() {
+    let wat = compile("features/counter_u64.fe", "Counter").unwrap();
+    assert!(wat.contains("(call $load_field (i64.const 0) (i32.const 0))"));
+    assert!(wat.contains("(call $add_u64"));
+}
@@ -0,0 +1,323 @@
+//! Generates the expression grammar from the tables in
+//! `fe_parser::grammar::table`. Every operator group becomes one precedence
+//! level; statements and items aren't table-driven yet, so they aren't
+//! included.
+
+use fe_parser::grammar::table::{
+    self, Assoc, OperatorGroup, Operators, Part, ATOMS, OPERATORS, TOKEN_PATTERNS,
+};
+use fe_parser::TokenKind;
+use std::fmt::Write;
+
+/// The operator groups from the loosest to the tightest binding. Groups with
+/// the same binding power keep their order in the table.
+fn precedence_levels() -> Vec<&'static OperatorGroup> {
+    let mut groups = OPERATORS.iter().collect::<Vec<_>>();
+    groups.sort_by_key(|group| group.binding_power);
+    groups
+}
+
+/// The name of a token without fixed text, e.g. `Name`.
+fn pattern_name(kind: TokenKind) -> String {
+    format!("{:?}", kind)
+}
+
+/// Prints the expression grammar in EBNF.
+pub fn ebnf() -> String {
+    let levels = precedence_levels();
+    let rule = |index: usize| match levels.get(index) {
+        Some(group) => format!("{}_expr", group.name),
+        None => "primary_expr".to_string(),
+    };
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "(* Generated by `cargo xtask grammar` from `fe_parser::grammar::table`. *)\n"
+    )
+    .unwrap();
+    writeln!(out, "expr ::= {}", rule(0)).unwrap();
+
+    for (index, group) in levels.iter().enumerate() {
+        let this = rule(index);
+        let next = rule(index + 1);
+        let body = match group.operators {
+            Operators::Infix(Assoc::Left, ops) => {
+                format!("{} ({} {})*", next, ebnf_choice(ops), next)
+            }
+            Operators::Infix(Assoc::Right, ops) => {
+                // As in Python's `power ::= primary ["**" u_expr]`, the right
+                // operand may start with a looser prefix operator.
+                let operand = levels[..index]
+                    .iter()
+                    .rposition(|group| matches!(group.operators, Operators::Prefix(_)))
+                    .map_or_else(|| this.clone(), rule);
+                format!("{} ({} {})?", next, ebnf_choice(ops), operand)
+            }
+            Operators::Prefix(ops) => format!("{} {} | {}", ebnf_choice(ops), this, next),
+            Operators::Postfix(ops) => {
+                let suffixes = ops
+                    .iter()
+                    .map(|op| ebnf_parts(op.suffix))
+                    .collect::<Vec<_>>();
+                format!("{} ({})*", next, suffixes.join(" | "))
+            }
+        };
+        writeln!(out, "{} ::= {}", this, body).unwrap();
+    }
+
+    let mut primary = ATOMS
+        .iter()
+        .map(|kind| ebnf_token(*kind))
+        .collect::<Vec<_>>();
+    primary.push(r#""(" (expr ("," expr)* ","?)? ")""#.to_string());
+    primary.push(r#""[" (expr ("," expr)* ","?)? "]""#.to_string());
+    writeln!(out, "primary_expr ::= {}", primary.join("\n    | ")).unwrap();
+    writeln!(
+        out,
+        r#"call_args ::= "(" (call_arg ("," call_arg)* ","?)? ")""#
+    )
+    .unwrap();
+    writeln!(out, r#"call_arg ::= (NAME "=")? expr"#).unwrap();
+
+    writeln!(out).unwrap();
+    for (kind, pattern) in TOKEN_PATTERNS {
+        writeln!(
+            out,
+            "{} ::= /{}/",
+            pattern_name(*kind).to_uppercase(),
+            pattern
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn ebnf_token(kind: TokenKind) -> String {
+    match table::token_text(kind) {
+        Some(text) => format!("\"{}\"", text),
+        None => pattern_name(kind).to_uppercase(),
+    }
+}
+
+fn ebnf_choice(ops: &[TokenKind]) -> String {
+    let tokens = ops.iter().map(|op| ebnf_token(*op)).collect::<Vec<_>>();
+    if tokens.len() == 1 {
+        tokens.join("")
+    } else {
+        format!("({})", tokens.join(" | "))
+    }
+}
+
+fn ebnf_parts(parts: &[Part]) -> String {
+    parts.iter().map(ebnf_part).collect::<Vec<_>>().join(" ")
+}
+
+fn ebnf_part(part: &Part) -> String {
+    match part {
+        Part::Token(kind) => ebnf_token(*kind),
+        Part::Expr => "expr".to_string(),
+        Part::CallArgs => "call_args".to_string(),
+        Part::Optional([part]) => format!("{}?", ebnf_part(part)),
+        Part::Optional(parts) => format!("({})?", ebnf_parts(parts)),
+    }
+}
+
+/// Prints a skeleton tree-sitter `grammar.js` with the expression rules.
+pub fn tree_sitter() -> String {
+    let levels = precedence_levels();
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated by `cargo xtask grammar --format tree-sitter` from
+// `fe_parser::grammar::table`. Only the expression rules are generated; the
+// rules for statements and items have to be added by hand.
+"
+    )
+    .unwrap();
+
+    writeln!(out, "const PREC = {{").unwrap();
+    for group in &levels {
+        writeln!(out, "  {}: {},", group.name, group.binding_power).unwrap();
+    }
+    writeln!(out, "}};\n").unwrap();
+
+    writeln!(
+        out,
+        "function commaSep1(rule) {{
+  return seq(rule, repeat(seq(',', rule)), optional(','));
+}}
+
+module.exports = grammar({{
+  name: 'fe',
+
+  extras: $ => [/\\s/, $.comment],
+
+  word: $ => $.name,
+
+  rules: {{
+    source_file: $ => repeat($._expression),
+"
+    )
+    .unwrap();
+
+    let expressions = levels
+        .iter()
+        .map(|group| format!("$.{}_expression", group.name))
+        .chain(["$._primary_expression".to_string()])
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "    _expression: $ => choice(\n      {},\n    ),\n",
+        expressions.join(",\n      ")
+    )
+    .unwrap();
+
+    for group in &levels {
+        let body = match group.operators {
+            Operators::Infix(assoc, ops) => format!(
+                "prec.{}(PREC.{}, seq(
+      field('left', $._expression),
+      field('operator', {}),
+      field('right', $._expression),
+    ))",
+                match assoc {
+                    Assoc::Left => "left",
+                    Assoc::Right => "right",
+                },
+                group.name,
+                ts_choice(ops)
+            ),
+            Operators::Prefix(ops) => format!(
+                "prec(PREC.{}, seq(
+      field('operator', {}),
+      field('operand', $._expression),
+    ))",
+                group.name,
+                ts_choice(ops)
+            ),
+            Operators::Postfix(ops) => {
+                let suffixes = ops.iter().map(|op| ts_parts(op.suffix)).collect::<Vec<_>>();
+                let suffix = if suffixes.len() == 1 {
+                    suffixes.join("")
+                } else {
+                    format!(
+                        "choice(\n        {},\n      )",
+                        suffixes.join(",\n        ")
+                    )
+                };
+                format!(
+                    "prec.left(PREC.{}, seq(
+      field('operand', $._expression),
+      {},
+    ))",
+                    group.name, suffix
+                )
+            }
+        };
+        writeln!(out, "    {}_expression: $ => {},\n", group.name, body).unwrap();
+    }
+
+    let primary = ATOMS
+        .iter()
+        .map(|kind| ts_token(*kind))
+        .chain(["$.tuple".to_string(), "$.list".to_string()])
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "    _primary_expression: $ => choice(\n      {},\n    ),
+",
+        primary.join(",\n      ")
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    tuple: $ => seq('(', optional(commaSep1($._expression)), ')'),
+
+    list: $ => seq('[', optional(commaSep1($._expression)), ']'),
+
+    call_args: $ => seq('(', optional(commaSep1($.call_arg)), ')'),
+
+    call_arg: $ => seq(
+      optional(seq(field('label', $.name), '=')),
+      field('value', $._expression),
+    ),
+"
+    )
+    .unwrap();
+
+    for (kind, pattern) in TOKEN_PATTERNS {
+        writeln!(
+            out,
+            "    {}: $ => /{}/,",
+            pattern_name(*kind).to_lowercase(),
+            pattern
+        )
+        .unwrap();
+    }
+    writeln!(out, "  }},\n}});").unwrap();
+    out
+}
+
+fn ts_token(kind: TokenKind) -> String {
+    match table::token_text(kind) {
+        Some(text) => format!("'{}'", text),
+        None => format!("$.{}", pattern_name(kind).to_lowercase()),
+    }
+}
+
+fn ts_choice(ops: &[TokenKind]) -> String {
+    let tokens = ops.iter().map(|op| ts_token(*op)).collect::<Vec<_>>();
+    if tokens.len() == 1 {
+        tokens.join("")
+    } else {
+        format!("choice({})", tokens.join(", "))
+    }
+}
+
+fn ts_parts(parts: &[Part]) -> String {
+    match parts {
+        [part] => ts_part(part),
+        _ => format!(
+            "seq({})",
+            parts.iter().map(ts_part).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn ts_part(part: &Part) -> String {
+    match part {
+        Part::Token(kind) => ts_token(*kind),
+        Part::Expr => "$._expression".to_string(),
+        Part::CallArgs => "$.call_args".to_string(),
+        Part::Optional(parts) => format!("optional({})", ts_parts(parts)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn ebnf_precedence() {
+        let ebnf = super::ebnf();
+        for rule in [
+            "expr ::= ternary_expr",
+            r#"ternary_expr ::= or_expr ("if" expr "else" expr)*"#,
+            r#"not_expr ::= "not" not_expr | comparison_expr"#,
+            r#"additive_expr ::= multiplicative_expr (("+" | "-") multiplicative_expr)*"#,
+            r#"power_expr ::= attribute_expr ("**" unary_expr)?"#,
+            r#"postfix_expr ::= path_expr (call_args | "[" expr? (":" expr?)? "]" | "?")*"#,
+            "NAME ::= /[a-zA-Z_][a-zA-Z0-9_]*/",
+        ] {
+            assert!(ebnf.contains(rule), "missing `{}` in:\n{}", rule, ebnf);
+        }
+    }
+
+    #[test]
+    fn tree_sitter_precedence() {
+        let grammar = super::tree_sitter();
+        assert!(grammar.contains("  additive: 120,"));
+        assert!(grammar.contains("    power_expression: $ => prec.right(PREC.power, seq("));
+        assert!(grammar.contains("      field('operator', choice('+', '-')),"));
+    }
+}
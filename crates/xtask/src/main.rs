@@ -0,0 +1,26 @@
+//! Development tasks for the Fe repository, run with `cargo xtask <task>`.
+//!
+//! - `grammar [--format ebnf|tree-sitter]` prints the expression grammar,
+//!   derived from the tables the parser uses. The tree-sitter output is a
+//!   skeleton `grammar.js` for editor syntax definitions.
+
+mod grammar;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: cargo xtask grammar [--format ebnf|tree-sitter]";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["grammar"] | ["grammar", "--format", "ebnf"] => print!("{}", grammar::ebnf()),
+        ["grammar", "--format", "tree-sitter"] => print!("{}", grammar::tree_sitter()),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Reports of the bytes of a contract's runtime code that each Yul function
+//! takes up, to help with staying under the contract size limit.
+//!
+//! The sizes come from solc's `functionDebugData`, which gives the entry point
+//! of each function that wasn't inlined. A function is credited with the bytes
+//! from its entry point to the next one, and the code before the first entry
+//! point, which includes the inlined functions, is reported on its own. The
+//! last function is also credited with any data and nested objects that follow
+//! the code.
+
+use std::fmt;
+
+/// The maximum size of a contract's runtime code, from EIP-170.
+pub const CODE_SIZE_LIMIT: usize = 24_576;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSize {
+    pub contract: String,
+    /// The size of the runtime code, in bytes.
+    pub total: usize,
+    /// The bytes before the first function, which are the top-level code and
+    /// any inlined functions.
+    pub top_level: usize,
+    /// The bytes of each function, largest first.
+    pub functions: Vec<(String, usize)>,
+}
+
+impl CodeSize {
+    /// Attributes `total` bytes of code to the functions at the given entry
+    /// points.
+    pub fn new(contract: &str, total: usize, mut entry_points: Vec<(String, usize)>) -> Self {
+        entry_points.sort_by_key(|(_, entry_point)| *entry_point);
+        let top_level = entry_points
+            .first()
+            .map_or(total, |(_, entry_point)| *entry_point);
+        let mut functions = entry_points
+            .iter()
+            .enumerate()
+            .map(|(index, (name, entry_point))| {
+                let end = entry_points.get(index + 1).map_or(total, |(_, next)| *next);
+                (name.clone(), end.saturating_sub(*entry_point))
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|(a_name, a_size), (b_name, b_size)| {
+            b_size.cmp(a_size).then_with(|| a_name.cmp(b_name))
+        });
+
+        CodeSize {
+            contract: contract.to_string(),
+            total,
+            top_level,
+            functions,
+        }
+    }
+}
+
+impl fmt::Display for CodeSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "runtime code of `{}`: {} bytes, {:.1}% of the {} byte limit",
+            self.contract,
+            self.total,
+            self.total as f64 * 100.0 / CODE_SIZE_LIMIT as f64,
+            CODE_SIZE_LIMIT
+        )?;
+        writeln!(
+            f,
+            "  {:>6} bytes  [top-level code and inlined functions]",
+            self.top_level
+        )?;
+        for (name, size) in &self.functions {
+            // Yulgen prefixes the functions of Fe code with `$$`, and the rest
+            // are helpers of the compiler.
+            match name.strip_prefix("$$") {
+                Some(name) => writeln!(f, "  {:>6} bytes  {}", size, name)?,
+                None => writeln!(f, "  {:>6} bytes  {} (compiler helper)", size, name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeSize;
+
+    #[test]
+    fn attributes_bytes_to_functions() {
+        let size = CodeSize::new(
+            "Foo",
+            100,
+            vec![
+                ("checked_add_u256".into(), 70),
+                ("$$bar".into(), 40),
+                ("$$baz".into(), 50),
+            ],
+        );
+        assert_eq!(size.top_level, 40);
+        assert_eq!(
+            size.functions,
+            vec![
+                ("checked_add_u256".into(), 30),
+                ("$$baz".into(), 20),
+                ("$$bar".into(), 10),
+            ]
+        );
+        assert_eq!(
+            size.to_string(),
+            "runtime code of `Foo`: 100 bytes, 0.4% of the 24576 byte limit
+      40 bytes  [top-level code and inlined functions]
+      30 bytes  checked_add_u256 (compiler helper)
+      20 bytes  baz
+      10 bytes  bar
+"
+        );
+    }
+}
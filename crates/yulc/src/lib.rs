@@ -1,19 +1,27 @@
 use indexmap::map::IndexMap;
 
+mod code_size;
+
+pub use code_size::{CodeSize, CODE_SIZE_LIMIT};
+
 #[derive(Debug)]
 pub struct YulcError(pub String);
 
 /// Compile a map of Yul contracts to a map of bytecode contracts.
 ///
-/// Returns a `contract_name -> hex_encoded_bytecode` map.
+/// Returns a `contract_name -> hex_encoded_bytecode` map. The bytecode is
+/// compiled for `evm_version`, by its name in solc, or for solc's default EVM
+/// version if it's `None`.
 pub fn compile(
     mut contracts: IndexMap<String, String>,
     optimize: bool,
+    evm_version: Option<&str>,
 ) -> Result<IndexMap<String, String>, YulcError> {
     contracts
         .drain(0..)
         .map(|(name, yul_src)| {
-            compile_single_contract(&name, yul_src, optimize).map(|bytecode| (name, bytecode))
+            compile_single_contract(&name, yul_src, optimize, evm_version)
+                .map(|bytecode| (name, bytecode))
         })
         .collect()
 }
@@ -24,14 +32,9 @@ pub fn compile_single_contract(
     name: &str,
     yul_src: String,
     optimize: bool,
+    evm_version: Option<&str>,
 ) -> Result<String, YulcError> {
-    let solc_temp = include_str!("solc_temp.json");
-    let input = solc_temp
-        .replace("{optimizer_enabled}", &optimize.to_string())
-        .replace("{src}", &yul_src);
-    let raw_output = solc::compile(&input);
-    let output: serde_json::Value = serde_json::from_str(&raw_output)
-        .map_err(|_| YulcError("JSON serialization error".into()))?;
+    let output = solc_output(&yul_src, optimize, evm_version)?;
 
     let bytecode = output["contracts"]["input.yul"][name]["evm"]["bytecode"]["object"]
         .to_string()
@@ -44,18 +47,80 @@ pub fn compile_single_contract(
     Ok(bytecode)
 }
 
+#[cfg(feature = "solc-backend")]
+/// Compiles a contract's runtime Yul object, and reports the bytes of code
+/// that each of its functions takes up. See [`CodeSize`].
+pub fn code_size(
+    name: &str,
+    runtime_yul_src: &str,
+    optimize: bool,
+    evm_version: Option<&str>,
+) -> Result<CodeSize, YulcError> {
+    let output = solc_output(runtime_yul_src, optimize, evm_version)?;
+
+    let bytecode = &output["contracts"]["input.yul"][name]["evm"]["bytecode"];
+    let total = match bytecode["object"].as_str() {
+        Some(object) => object.len() / 2,
+        None => return Err(YulcError(output.to_string())),
+    };
+    let entry_points = bytecode["functionDebugData"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(function, data)| {
+            let entry_point = data["entryPoint"].as_u64()?;
+            Some((function.clone(), entry_point as usize))
+        })
+        .collect();
+
+    Ok(CodeSize::new(name, total, entry_points))
+}
+
+#[cfg(feature = "solc-backend")]
+fn solc_output(
+    yul_src: &str,
+    optimize: bool,
+    evm_version: Option<&str>,
+) -> Result<serde_json::Value, YulcError> {
+    let solc_temp = include_str!("solc_temp.json");
+    let mut input = solc_temp
+        .replace("{optimizer_enabled}", &optimize.to_string())
+        .replace("{src}", yul_src);
+    if let Some(evm_version) = evm_version {
+        let mut settings: serde_json::Value = serde_json::from_str(&input)
+            .map_err(|_| YulcError("JSON serialization error".into()))?;
+        settings["settings"]["evmVersion"] = evm_version.into();
+        input = settings.to_string();
+    }
+    let raw_output = solc::compile(&input);
+    serde_json::from_str(&raw_output).map_err(|_| YulcError("JSON serialization error".into()))
+}
+
 #[cfg(not(feature = "solc-backend"))]
 /// Compiles a single Yul contract to bytecode.
 pub fn compile_single_contract(
     _name: &str,
     _yul_src: String,
     _optimize: bool,
+    _evm_version: Option<&str>,
 ) -> Result<String, YulcError> {
     // This is ugly, but required (as far as I can tell) to make
     // `cargo test --workspace` work without solc.
     panic!("fe-yulc requires 'solc-backend' feature")
 }
 
+#[cfg(not(feature = "solc-backend"))]
+/// Compiles a contract's runtime Yul object, and reports the bytes of code
+/// that each of its functions takes up.
+pub fn code_size(
+    _name: &str,
+    _runtime_yul_src: &str,
+    _optimize: bool,
+    _evm_version: Option<&str>,
+) -> Result<CodeSize, YulcError> {
+    panic!("fe-yulc requires 'solc-backend' feature")
+}
+
 #[cfg(feature = "solc-backend")]
 #[test]
 fn test_solc_sanity() {
@@ -3,6 +3,7 @@ use fe_analyzer::namespace::items::{ContractId, EventId, FunctionId, ModuleId, S
 use fe_analyzer::AnalyzerDb;
 use fe_common::Upcast;
 use fe_lowering::LoweringDb;
+use fe_mir::MirDb;
 use indexmap::{IndexMap, IndexSet};
 use smol_str::SmolStr;
 use std::rc::Rc;
@@ -12,10 +13,19 @@ mod queries;
 
 #[salsa::query_group(YulgenDbStorage)]
 pub trait YulgenDb:
-    AnalyzerDb + LoweringDb + Upcast<dyn AnalyzerDb> + Upcast<dyn LoweringDb>
+    AnalyzerDb
+    + LoweringDb
+    + MirDb
+    + Upcast<dyn AnalyzerDb>
+    + Upcast<dyn LoweringDb>
+    + Upcast<dyn MirDb>
 {
     #[salsa::invoke(queries::compile_module)]
     fn compile_module(&self, module_id: ModuleId) -> IndexMap<String, String>;
+    #[salsa::invoke(queries::compile_module_runtime)]
+    fn compile_module_runtime(&self, module_id: ModuleId) -> IndexMap<String, String>;
+    #[salsa::invoke(queries::compile_module_objects)]
+    fn compile_module_objects(&self, module_id: ModuleId) -> IndexMap<String, String>;
 
     #[salsa::invoke(queries::contracts::contract_object)]
     fn contract_object(&self, contract: ContractId) -> yul::Object;
@@ -54,6 +64,10 @@ pub trait YulgenDb:
     fn struct_init_name(&self, id: StructId) -> SmolStr;
     #[salsa::invoke(queries::structs::struct_init_fn)]
     fn struct_init_fn(&self, id: StructId) -> yul::Statement;
+    #[salsa::invoke(queries::structs::struct_hash_name)]
+    fn struct_hash_name(&self, id: StructId) -> SmolStr;
+    #[salsa::invoke(queries::structs::struct_hash_fn)]
+    fn struct_hash_fn(&self, id: StructId) -> Option<yul::Statement>;
     #[salsa::invoke(queries::structs::struct_api_fns)]
     fn struct_api_fns(&self, id: StructId) -> Vec<yul::Statement>;
 }
@@ -61,6 +75,7 @@ pub trait YulgenDb:
 #[salsa::database(
     fe_analyzer::db::AnalyzerDbStorage,
     fe_lowering::db::LoweringDbStorage,
+    fe_mir::db::MirDbStorage,
     YulgenDbStorage
 )]
 #[derive(Default)]
@@ -75,6 +90,12 @@ impl Upcast<dyn LoweringDb> for Db {
     }
 }
 
+impl Upcast<dyn MirDb> for Db {
+    fn upcast(&self) -> &(dyn MirDb + 'static) {
+        &*self
+    }
+}
+
 impl Upcast<dyn AnalyzerDb> for Db {
     fn upcast(&self) -> &(dyn AnalyzerDb + 'static) {
         &*self
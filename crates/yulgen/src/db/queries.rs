@@ -2,7 +2,7 @@ use crate::db::YulgenDb;
 use crate::mappers;
 use fe_analyzer::namespace::items::ModuleId;
 use indexmap::map::IndexMap;
-use yultsur::yul;
+use yultsur::*;
 
 pub mod contracts;
 pub mod events;
@@ -16,23 +16,61 @@ pub fn compile_module(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, S
         .collect()
 }
 
+pub fn compile_module_runtime(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
+    mappers::module::module(db, module)
+        .drain()
+        .map(|(name, object)| {
+            let runtime = object
+                .objects
+                .into_iter()
+                .find(|object| object.name == identifier! { runtime })
+                .expect("contract object has no runtime object");
+            // The runtime object is compiled on its own, so it takes the
+            // contract's name.
+            let runtime = yul::Object {
+                name: object.name,
+                ..runtime
+            };
+            (name, to_safe_json(runtime))
+        })
+        .collect()
+}
+
+pub fn compile_module_objects(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
+    mappers::module::module(db, module)
+        .drain()
+        .map(|(name, object)| (name, normalize_object(object, false).to_string()))
+        .collect()
+}
+
 fn to_safe_json(obj: yul::Object) -> String {
-    normalize_object(obj).to_string().replace("\"", "\\\"")
+    normalize_object(obj, true)
+        .to_string()
+        .replace("\"", "\\\"")
 }
 
-fn normalize_object(obj: yul::Object) -> yul::Object {
+/// Escapes the values of the object's data as Yul string literals, whose
+/// backslashes are escaped once more if the object is to be embedded in JSON.
+fn normalize_object(obj: yul::Object, for_json: bool) -> yul::Object {
     let data = obj
         .data
         .into_iter()
-        .map(|data| yul::Data {
-            name: data.name,
-            value: data
+        .map(|data| {
+            let value = data
                 .value
-                .replace('\\', "\\\\\\\\")
-                .replace('\n', "\\\\n")
-                .replace("\"", "\\\\\"")
-                .replace('\r', "\\\\r")
-                .replace('\t', "\\\\t"),
+                .replace('\\', "\\\\")
+                .replace('\n', "\\n")
+                .replace("\"", "\\\"")
+                .replace('\r', "\\r")
+                .replace('\t', "\\t");
+            yul::Data {
+                name: data.name,
+                value: if for_json {
+                    value.replace('\\', "\\\\")
+                } else {
+                    value
+                },
+            }
         })
         .collect::<Vec<_>>();
     yul::Object {
@@ -41,7 +79,7 @@ fn normalize_object(obj: yul::Object) -> yul::Object {
         objects: obj
             .objects
             .into_iter()
-            .map(normalize_object)
+            .map(|obj| normalize_object(obj, for_json))
             .collect::<Vec<_>>(),
         data,
     }
@@ -123,7 +123,22 @@ pub fn contract_abi_dispatcher(db: &dyn YulgenDb, contract: ContractId) -> Vec<y
                 }
                 fns
             });
-    fns.push(abi_dispatcher::dispatcher(&public_functions));
+    let multicall = contract.is_multicall(adb);
+    if multicall {
+        fns.push(functions::contracts::multicall());
+    }
+    let fallback = contract
+        .fallback_function(adb)
+        .map(|id| db.function_yul_name(id));
+    let receive = contract
+        .receive_function(adb)
+        .map(|id| db.function_yul_name(id));
+    fns.push(abi_dispatcher::dispatcher(
+        &public_functions,
+        multicall,
+        fallback.as_deref(),
+        receive.as_deref(),
+    ));
     fns.sort();
     fns.dedup();
     fns
@@ -164,8 +179,19 @@ fn build_dependency_graph(
                                 .expect("abi_encode non-fixedsize type");
                             yulfns.push(functions::abi::encode(&[typ.as_abi_type(adb)]));
                         }
-                        CallType::BuiltinAssociatedFunction { contract, .. } => {
+                        CallType::BuiltinAssociatedFunction { contract, function } => {
                             created_contracts.insert(*contract);
+                            if let Some(init_fn) = contract.init_function(adb) {
+                                let (params, _) = db.function_sig_abi_types(init_fn);
+                                if !params.is_empty() {
+                                    yulfns.push(functions::abi::encode(&params));
+                                    yulfns.push(functions::contracts::create_with_args(
+                                        &contract.name(adb),
+                                        *function,
+                                        &params,
+                                    ));
+                                }
+                            }
                         }
                         _ => {}
                     }
@@ -1,6 +1,7 @@
 use crate::context::FnContext;
 use crate::db::YulgenDb;
 use crate::mappers::functions::multiple_func_stmt;
+use crate::mir;
 use crate::names;
 use crate::operations::abi as abi_operations;
 use crate::runtime::functions;
@@ -33,9 +34,10 @@ pub fn function_def(db: &dyn YulgenDb, function: FunctionId) -> yul::Statement {
     };
     param_names.extend(sig.params.iter().map(|param| names::var_name(&param.name)));
 
-    let mut fn_context = FnContext::new(db, function.body(analyzer_db));
-    let function_statements =
-        multiple_func_stmt(&mut fn_context, &function.data(analyzer_db).ast.kind.body);
+    let function_statements = mir::function_statements(db, function).unwrap_or_else(|| {
+        let mut fn_context = FnContext::new(db, function.body(analyzer_db));
+        multiple_func_stmt(&mut fn_context, &function.data(analyzer_db).ast.kind.body)
+    });
 
     let function_name = identifier! { (db.function_yul_name(function)) };
     // all user-defined functions are given a return value during lowering
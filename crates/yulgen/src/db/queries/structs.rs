@@ -1,7 +1,7 @@
 use crate::db::YulgenDb;
 use crate::types::{AbiType, AsAbiType, EvmSized};
 use fe_analyzer::namespace::items::{Item, StructId, TypeDef};
-use fe_analyzer::namespace::types::FixedSize;
+use fe_analyzer::namespace::types::{Base, FixedSize};
 use smol_str::SmolStr;
 use std::rc::Rc;
 use yultsur::*;
@@ -107,7 +107,7 @@ pub fn struct_getter_fn(
         //                   that might be of type Array<u256, 2> with an entirely new array e.g. val.foo = [100, 200]
         //                   In that case, we don't want to follow the stored reference because we want to override
         //                   it entirely.
-        FixedSize::Base(_) => normal_getter,
+        FixedSize::Base(_) | FixedSize::Enum(_) => normal_getter,
         _ => {
             if deref {
                 deref_getter
@@ -175,9 +175,70 @@ pub fn struct_init_fn(db: &dyn YulgenDb, struct_: StructId) -> yul::Statement {
     }
 }
 
+pub fn struct_hash_name(db: &dyn YulgenDb, struct_: StructId) -> SmolStr {
+    format!("{}.hash", db.struct_qualified_name(struct_)).into()
+}
+
+/// The function that hashes a struct in memory with keccak256.
+///
+/// The fields are hashed in order, using their packed encoding (like
+/// Solidity's `abi.encodePacked`): each field occupies only the bytes of its
+/// type, e.g. 1 byte for a `u8` or `bool` and 20 bytes for an `address`.
+///
+/// Returns `None` if a field isn't of a base type, since there's no packed
+/// encoding for it.
+pub fn struct_hash_fn(db: &dyn YulgenDb, struct_: StructId) -> Option<yul::Statement> {
+    let function_name = identifier! { (db.struct_hash_name(struct_)) };
+    let fields = struct_.fields(db.upcast());
+
+    let mut offset = 0;
+    let mut body = vec![];
+    for (index, field) in fields.values().enumerate() {
+        let base = match field.typ(db.upcast()).expect("struct field error") {
+            FixedSize::Base(base) => base,
+            _ => return None,
+        };
+        let size = packed_size(&base);
+        // Every field occupies a word in memory. Fixed-size bytes values are
+        // left-aligned in it, all other values are right-aligned.
+        let field_offset = literal_expression! { (index * 32) };
+        let word = expression! { mload((add(ptr, [field_offset]))) };
+        let value = match base {
+            Base::FixedBytes(_) => word,
+            _ => {
+                let shift = literal_expression! { (256 - size * 8) };
+                expression! { shl([shift], [word]) }
+            }
+        };
+        // The bytes after the value are overwritten by the next field.
+        let buf_offset = literal_expression! { (offset) };
+        body.push(statement! { mstore((add(buf, [buf_offset])), [value]) });
+        offset += size;
+    }
+    let size = literal_expression! { (offset) };
+
+    Some(function_definition! {
+        function [function_name](ptr) -> return_val {
+            (let buf := avail())
+            [body...]
+            (return_val := keccak256(buf, [size]))
+        }
+    })
+}
+
+/// The number of bytes used by a value in the packed encoding.
+fn packed_size(base: &Base) -> usize {
+    match base {
+        Base::Address => 20,
+        Base::FixedBytes(size) => *size,
+        _ => base.size(),
+    }
+}
+
 pub fn struct_api_fns(db: &dyn YulgenDb, struct_: StructId) -> Vec<yul::Statement> {
     [
         vec![db.struct_init_fn(struct_)],
+        db.struct_hash_fn(struct_).into_iter().collect(),
         struct_
             .fields(db.upcast())
             .keys()
@@ -1,17 +1,21 @@
 //! Fe to Yul compiler.
 
 pub use crate::db::{Db, YulgenDb};
-use fe_analyzer::namespace::items::ModuleId;
+use crate::resources::ContractResources;
+use fe_analyzer::namespace::items::{ContractId, ModuleId};
 use fe_analyzer::AnalyzerDb;
 use indexmap::map::IndexMap;
+use yultsur::*;
 
 pub mod constants;
 pub mod constructor;
 mod context;
 mod db;
 mod mappers;
+mod mir;
 pub mod names;
 pub mod operations;
+pub mod resources;
 pub mod runtime;
 pub mod types;
 mod utils;
@@ -27,3 +31,59 @@ mod utils;
 pub fn compile(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
     db.compile_module(module)
 }
+
+/// Compiles the runtime objects of a lowered Fe module's contracts to Yul.
+///
+/// Each runtime object is named after its contract and can be compiled on its
+/// own, e.g. to measure the size of the deployed code.
+///
+/// # Panics
+///
+/// See [`compile`].
+pub fn compile_runtime(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
+    db.compile_module_runtime(module)
+}
+
+/// Compiles a lowered Fe module to standalone Yul objects, which are the
+/// objects of [`compile`] in the form that `solc --strict-assembly` reads.
+/// The objects of [`compile`] are escaped to be embedded in solc's JSON input.
+///
+/// # Panics
+///
+/// See [`compile`].
+pub fn compile_objects(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
+    db.compile_module_objects(module)
+}
+
+/// The selectors that the dispatcher of a lowered contract routes calls by,
+/// including `multicall(bytes[])` for `multicall` contracts.
+pub fn dispatched_selectors(db: &dyn YulgenDb, contract: ContractId) -> Vec<String> {
+    db.contract_abi_dispatcher(contract)
+        .iter()
+        .flat_map(runtime::abi_dispatcher::dispatched_selectors)
+        .collect()
+}
+
+/// Estimates the stack and memory used by the functions of each contract's
+/// deploy and runtime code. See [`resources`].
+pub fn estimate_resources(
+    db: &dyn YulgenDb,
+    module: ModuleId,
+) -> IndexMap<String, ContractResources> {
+    mappers::module::module(db, module)
+        .into_iter()
+        .map(|(name, object)| {
+            let runtime = object
+                .objects
+                .iter()
+                .find(|object| object.name == identifier! { runtime })
+                .expect("contract object has no runtime object");
+            let resources = ContractResources::new(
+                &name,
+                &object.code.block.to_string(),
+                &runtime.code.block.to_string(),
+            );
+            (name, resources)
+        })
+        .collect()
+}
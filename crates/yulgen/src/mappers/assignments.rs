@@ -1,8 +1,10 @@
 use crate::context::FnContext;
 use crate::mappers::expressions;
 use crate::operations::data as data_operations;
+use crate::operations::math as math_operations;
+use crate::types::EvmSized;
 use fe_analyzer::context::Location;
-use fe_analyzer::namespace::types::{FixedSize, Type};
+use fe_analyzer::namespace::types::{Base, FixedSize, Type};
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 use yultsur::yul::FunctionCall;
@@ -54,13 +56,24 @@ pub fn assign(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statem
             }
             (Location::Storage { .. }, Location::Value) => {
                 let target = expr_as_ident(target);
-                let value = data_operations::sload(typ, value);
+                let value = if typ == FixedSize::Base(Base::Bool) {
+                    math_operations::normalize_bool(data_operations::sload(typ, value))
+                } else {
+                    data_operations::sload(typ, value)
+                };
                 statement! { [target] := [value] }
             }
             (Location::Storage { .. }, Location::Memory) => {
                 unreachable!("raw sto to mem assign")
             }
             (Location::Value, Location::Memory) => data_operations::mstore(typ, target, value),
+            // A contract field starts a storage word of its own, so a full
+            // word value doesn't need to preserve any other bytes.
+            (Location::Value, Location::Storage { .. })
+                if typ.size() == 32 && is_contract_field(context, target_node) =>
+            {
+                data_operations::sstore_word(target, value)
+            }
             (Location::Value, Location::Storage { .. }) => {
                 data_operations::sstore(typ, target, value)
             }
@@ -74,6 +87,19 @@ pub fn assign(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statem
     unreachable!()
 }
 
+/// True if the expression is a field of the contract itself, like
+/// `self.total`. Members of storage structs and map values are not.
+fn is_contract_field(context: &FnContext, expr: &Node<fe::Expr>) -> bool {
+    if let fe::Expr::Attribute { value, .. } = &expr.kind {
+        matches!(
+            context.expression_attributes(value).typ,
+            Type::SelfContract(_)
+        )
+    } else {
+        false
+    }
+}
+
 fn expr_as_ident(expr: yul::Expression) -> yul::Identifier {
     if let yul::Expression::Identifier(ident) = expr {
         ident
@@ -19,7 +19,7 @@ pub fn var_decl(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stat
             statement! { let [target] := [value] }
         } else {
             match decl_type {
-                FixedSize::Base(_) => statement! { let [target] := 0 },
+                FixedSize::Base(_) | FixedSize::Enum(_) => statement! { let [target] := 0 },
                 typ => {
                     let size = literal_expression! { (typ.size()) };
                     statement! { let [target] := alloc([size]) }
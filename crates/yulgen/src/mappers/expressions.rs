@@ -25,10 +25,11 @@ use yultsur::*;
 pub fn expr(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     let expression = match &exp.kind {
         fe::Expr::Name(_) => expr_name(exp),
-        fe::Expr::Path(_) => panic!("path expressions should be lowered or rejected"),
+        fe::Expr::Path(_) => expr_enum_variant(context, exp),
         fe::Expr::Num(_) => expr_num(exp),
         fe::Expr::Bool(_) => expr_bool(exp),
         fe::Expr::Subscript { .. } => expr_subscript(context, exp),
+        fe::Expr::Slice { .. } => expr_slice(context, exp),
         fe::Expr::Attribute { .. } => expr_attribute(context, exp),
         fe::Expr::Ternary { .. } => panic!("ternary expressions should be lowered"),
         fe::Expr::BoolOperation { .. } => panic!("bool operation expressions should be lowered"),
@@ -38,6 +39,7 @@ pub fn expr(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
         fe::Expr::Call { .. } => expr_call(context, exp),
         fe::Expr::List { .. } => panic!("list expressions should be lowered"),
         fe::Expr::Tuple { .. } => panic!("tuple expressions should be lowered"),
+        fe::Expr::Try { .. } => panic!("`?` expressions should be lowered"),
         fe::Expr::Str(_) => expr_str(exp),
         fe::Expr::Unit => expression! { 0x0 },
     };
@@ -67,6 +69,8 @@ fn move_expression(
                     &integer,
                     data_operations::sload(fixed_size, val),
                 )
+            } else if typ == Type::Base(Base::Bool) {
+                math_operations::normalize_bool(data_operations::sload(fixed_size, val))
             } else {
                 data_operations::sload(fixed_size, val)
             }
@@ -118,6 +122,73 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
             GlobalFunction::BalanceOf => {
                 expression! { balance([yul_args[0].to_owned()]) }
             }
+            GlobalFunction::Proportion => {
+                expression! { mul_div([yul_args[0].to_owned()], [yul_args[1].to_owned()], [yul_args[2].to_owned()]) }
+            }
+            GlobalFunction::ProportionCeil => {
+                expression! { mul_div_ceil([yul_args[0].to_owned()], [yul_args[1].to_owned()], [yul_args[2].to_owned()]) }
+            }
+            GlobalFunction::ProportionRound => {
+                expression! { mul_div_round([yul_args[0].to_owned()], [yul_args[1].to_owned()], [yul_args[2].to_owned()]) }
+            }
+            GlobalFunction::Bps => {
+                expression! { mul_div([yul_args[0].to_owned()], [yul_args[1].to_owned()], 10000) }
+            }
+            GlobalFunction::BpsCeil => {
+                expression! { mul_div_ceil([yul_args[0].to_owned()], [yul_args[1].to_owned()], 10000) }
+            }
+            GlobalFunction::BpsRound => {
+                expression! { mul_div_round([yul_args[0].to_owned()], [yul_args[1].to_owned()], 10000) }
+            }
+            GlobalFunction::RawSload => {
+                expression! { sload([yul_args[0].to_owned()]) }
+            }
+            GlobalFunction::RawSstore => {
+                expression! { raw_sstore([yul_args[0].to_owned()], [yul_args[1].to_owned()]) }
+            }
+            GlobalFunction::RawCall | GlobalFunction::Delegatecall | GlobalFunction::Staticcall => {
+                let data_arg = &args.kind.get(1).expect("Missing argument").kind.value;
+                let data_size =
+                    FixedSize::try_from(context.expression_attributes(data_arg).typ.clone())
+                        .expect("Invalid type")
+                        .size();
+                let output_size = builtins::call_return_data_size(
+                    context.adb,
+                    &context.expression_attributes(exp).typ,
+                )
+                .expect("invalid low-level call result type");
+                let func_name = match func {
+                    GlobalFunction::RawCall => identifier! { raw_call },
+                    GlobalFunction::Delegatecall => identifier! { raw_delegatecall },
+                    _ => identifier! { raw_staticcall },
+                };
+                let data_size = literal_expression! { (data_size) };
+                let output_size = literal_expression! { (output_size) };
+                let mut call_args = vec![yul_args[0].to_owned(), yul_args[1].to_owned(), data_size];
+                call_args.extend(yul_args[2..].iter().cloned());
+                call_args.push(output_size);
+                expression! { [func_name]([call_args...]) }
+            }
+            // `dbg` compiles to its value, unless the `RecordDbg` plugin
+            // turned it into `__dbg_record`.
+            GlobalFunction::Dbg => yul_args[0].to_owned(),
+            GlobalFunction::DbgRecord => {
+                let value = &args.kind.first().expect("Missing argument").kind.value;
+                let kind = match &context.expression_attributes(value).typ {
+                    Type::Base(Base::Numeric(integer)) if integer.is_signed() => 1,
+                    Type::Base(Base::Bool) => 2,
+                    Type::Base(Base::Address) => 3,
+                    _ => 0,
+                };
+                let kind = literal_expression! { (kind) };
+                expression! { dbg_record([yul_args[0].to_owned()], [yul_args[1].to_owned()], [yul_args[2].to_owned()], [yul_args[3].to_owned()], [kind]) }
+            }
+            GlobalFunction::Some | GlobalFunction::None => {
+                panic!("`Option` constructors should be lowered")
+            }
+            GlobalFunction::Ok | GlobalFunction::Err => {
+                panic!("`Result` constructors should be lowered")
+            }
         },
         CallType::Intrinsic(func) => {
             let yul_name = identifier! { (func.as_ref().strip_prefix("__").unwrap()) };
@@ -141,13 +212,133 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                     ),
                     _ => panic!("invalid attributes"),
                 },
+                builtins::ValueMethod::IsSome
+                | builtins::ValueMethod::IsNone
+                | builtins::ValueMethod::Unwrap
+                | builtins::ValueMethod::UnwrapOr => panic!("`Option` methods should be lowered"),
+                builtins::ValueMethod::IsOk | builtins::ValueMethod::IsErr => {
+                    panic!("`Result` methods should be lowered")
+                }
+                builtins::ValueMethod::DivFloor
+                | builtins::ValueMethod::DivCeil
+                | builtins::ValueMethod::DivRound => match typ {
+                    Type::Base(Base::Numeric(integer)) => math_operations::rounding_div(
+                        method,
+                        &integer,
+                        expr(context, target),
+                        yul_args[0].to_owned(),
+                    ),
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Contains
+                | builtins::ValueMethod::IndexOf
+                | builtins::ValueMethod::Fill
+                | builtins::ValueMethod::CopyFrom => match typ {
+                    Type::Array(array) => {
+                        let location = context.expression_attributes(target).final_location();
+                        let target = expr(context, target);
+                        let arg = yul_args[0].to_owned();
+                        match method {
+                            builtins::ValueMethod::Contains => {
+                                data_operations::array_contains(array, location, target, arg)
+                            }
+                            builtins::ValueMethod::IndexOf => {
+                                data_operations::array_index_of(array, location, target, arg)
+                            }
+                            builtins::ValueMethod::Fill => {
+                                data_operations::array_fill(array, location, target, arg)
+                            }
+                            _ => data_operations::array_copy_from(array, location, target, arg),
+                        }
+                    }
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Sort => match typ {
+                    Type::Array(array) => data_operations::array_sort(array, expr(context, target)),
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Hash => match typ {
+                    Type::Struct(struct_) => {
+                        struct_operations::hash(context.db, struct_.id, expr(context, target))
+                    }
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Push => match typ {
+                    Type::DynamicArray(array) => data_operations::dynamic_array_push(
+                        array,
+                        expr(context, target),
+                        yul_args[0].to_owned(),
+                    ),
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Pop => match typ {
+                    Type::DynamicArray(array) => {
+                        let value =
+                            data_operations::dynamic_array_pop(array, expr(context, target));
+                        match array.inner {
+                            Base::Numeric(integer) => {
+                                math_operations::adjust_numeric_size(&integer, value)
+                            }
+                            Base::Bool => math_operations::normalize_bool(value),
+                            _ => value,
+                        }
+                    }
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Len => match typ {
+                    Type::DynamicArray(_) => {
+                        data_operations::dynamic_array_len(expr(context, target))
+                    }
+                    Type::String(_) => {
+                        let location = context.expression_attributes(target).final_location();
+                        data_operations::string_len(location, expr(context, target))
+                    }
+                    _ => panic!("invalid attributes"),
+                },
+                builtins::ValueMethod::Concat => {
+                    data_operations::string_concat(expr(context, target), yul_args[0].to_owned())
+                }
+                builtins::ValueMethod::Slice => data_operations::string_slice(
+                    expr(context, target),
+                    yul_args[0].to_owned(),
+                    yul_args[1].to_owned(),
+                ),
+                builtins::ValueMethod::BinarySearch => match typ {
+                    Type::Array(array) => data_operations::array_binary_search(
+                        array,
+                        expr(context, target),
+                        yul_args[0].to_owned(),
+                    ),
+                    _ => panic!("invalid attributes"),
+                },
             }
         }
         CallType::TypeConstructor(Type::Struct(val)) => {
             struct_operations::init(context.db, val.id, yul_args)
         }
         CallType::TypeConstructor(Type::Base(Base::Numeric(integer))) => {
-            math_operations::adjust_numeric_size(&integer, yul_args[0].to_owned())
+            let arg = &args.kind.first().expect("Missing argument").kind.value;
+            let value = match context.expression_attributes(arg).typ {
+                Type::Base(Base::FixedBytes(size)) => {
+                    math_operations::fixed_bytes_to_int(size, yul_args[0].to_owned())
+                }
+                _ => yul_args[0].to_owned(),
+            };
+            math_operations::adjust_numeric_size(&integer, value)
+        }
+        CallType::TypeConstructor(Type::Base(Base::FixedBytes(size))) => {
+            fixed_bytes_constructor(context, size, args, yul_args)
+        }
+        CallType::TypeConstructor(Type::Enum(val)) => {
+            let arg = &args.kind.first().expect("Missing argument").kind.value;
+            // Literals are checked to be variant indices by the analyzer.
+            let value = yul_args[0].to_owned();
+            if let fe::Expr::Num(_) = arg.kind {
+                value
+            } else {
+                let variant_count = literal_expression! { (val.variant_count) };
+                expression! { to_enum([value], [variant_count]) }
+            }
         }
         CallType::TypeConstructor(_) => yul_args[0].to_owned(),
         CallType::Pure(func) => {
@@ -156,14 +347,19 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
         }
         CallType::BuiltinAssociatedFunction { contract, function } => {
             let contract_name = contract.name(context.adb);
-            match function {
-                ContractTypeMethod::Create2 => contract_operations::create2(
-                    &contract_name,
-                    yul_args[0].to_owned(),
-                    yul_args[1].to_owned(),
-                ),
-                ContractTypeMethod::Create => {
-                    contract_operations::create(&contract_name, yul_args[0].to_owned())
+            if yul_args.len() > function.arg_count() {
+                // The contract's `__init__` function takes arguments.
+                contract_operations::create_with_args(&contract_name, function, yul_args)
+            } else {
+                match function {
+                    ContractTypeMethod::Create2 => contract_operations::create2(
+                        &contract_name,
+                        yul_args[0].to_owned(),
+                        yul_args[1].to_owned(),
+                    ),
+                    ContractTypeMethod::Create => {
+                        contract_operations::create(&contract_name, yul_args[0].to_owned())
+                    }
                 }
             }
         }
@@ -213,6 +409,35 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     };
 }
 
+/// Builds a fixed-size bytes value from an unsigned integer, or by
+/// concatenating fixed-size bytes values.
+fn fixed_bytes_constructor(
+    context: &mut FnContext,
+    size: usize,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    yul_args: Vec<yul::Expression>,
+) -> yul::Expression {
+    let mut value = None;
+    let mut offset = 0;
+    for (arg, yul_arg) in args.kind.iter().zip(yul_args) {
+        let arg_size = match context.expression_attributes(&arg.kind.value).typ {
+            Type::Base(Base::FixedBytes(arg_size)) => arg_size,
+            _ => return math_operations::int_to_fixed_bytes(size, yul_arg),
+        };
+        let shifted = if offset == 0 {
+            yul_arg
+        } else {
+            expression! { shr([literal_expression! { (offset * 8) }], [yul_arg]) }
+        };
+        value = Some(match value {
+            Some(value) => expression! { or([value], [shifted]) },
+            None => shifted,
+        });
+        offset += arg_size;
+    }
+    value.expect("Missing argument")
+}
+
 pub fn expr_comp_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     if let fe::Expr::CompOperation { left, op, right } = &exp.kind {
         let yul_left = expr(context, left);
@@ -220,6 +445,15 @@ pub fn expr_comp_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul
 
         let typ = &context.expression_attributes(left).typ;
 
+        if let Type::String(_) = typ {
+            let is_equal = data_operations::string_eq(yul_left, yul_right);
+            return match op.kind {
+                fe::CompOperator::Eq => is_equal,
+                fe::CompOperator::NotEq => expression! { iszero([is_equal]) },
+                _ => panic!("invalid attributes"),
+            };
+        }
+
         return match op.kind {
             fe::CompOperator::Eq => expression! { eq([yul_left], [yul_right]) },
             fe::CompOperator::NotEq => expression! { iszero((eq([yul_left], [yul_right]))) },
@@ -285,11 +519,17 @@ pub fn expr_bin_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul:
                     integer,
                     expression! { shl([yul_right], [math_operations::adjust_numeric_size(integer, yul_left)]) },
                 ),
+                // The bytes are aligned to the left, so bits shifted out of the value are gone.
+                Type::Base(Base::FixedBytes(_)) => expression! { shl([yul_right], [yul_left]) },
                 _ => unreachable!(),
             },
-            fe::BinOperator::RShift => match typ.is_signed_integer() {
-                true => expression! { sar([yul_right], [yul_left]) },
-                false => expression! { shr([yul_right], [yul_left]) },
+            fe::BinOperator::RShift => match typ {
+                Type::Base(Base::FixedBytes(size)) => math_operations::clean_fixed_bytes(
+                    *size,
+                    expression! { shr([yul_right], [yul_left]) },
+                ),
+                _ if typ.is_signed_integer() => expression! { sar([yul_right], [yul_left]) },
+                _ => expression! { shr([yul_right], [yul_left]) },
             },
             fe::BinOperator::Mod => match typ {
                 Type::Base(Base::Numeric(integer)) => {
@@ -336,6 +576,9 @@ pub fn expr_unary_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yu
                 Type::Base(Base::Numeric(integer)) => {
                     math_operations::adjust_numeric_size(integer, expression! { not([yul_operand])})
                 }
+                Type::Base(Base::FixedBytes(size)) => {
+                    math_operations::clean_fixed_bytes(*size, expression! { not([yul_operand]) })
+                }
                 _ => unreachable!(),
             },
         };
@@ -359,6 +602,25 @@ fn expr_name(exp: &Node<fe::Expr>) -> yul::Expression {
     identifier_expression! { [names::var_name(&name)] }
 }
 
+/// An enum variant, eg. `State::Active`, is the literal index of the variant.
+/// Other paths are lowered to their values.
+fn expr_enum_variant(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
+    let variant = match &exp.kind {
+        fe::Expr::Path(path) => path.segments.last().expect("empty path"),
+        _ => unreachable!(),
+    };
+    match &context.expression_attributes(exp).typ {
+        Type::Enum(val) => {
+            let index = val
+                .id
+                .variant_index(context.adb, &variant.kind)
+                .expect("undefined enum variant");
+            literal_expression! { (index) }
+        }
+        _ => panic!("path expressions should be lowered or rejected"),
+    }
+}
+
 fn expr_num(exp: &Node<fe::Expr>) -> yul::Expression {
     if let fe::Expr::Num(num) = &exp.kind {
         let literal = numeric::Literal::new(num);
@@ -410,6 +672,12 @@ fn expr_subscript(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Express
         return match &value_attributes.typ {
             Type::Map(_) => data_operations::keyed_map(value, index),
             Type::Array(array) => data_operations::indexed_array(array.clone(), value, index),
+            Type::DynamicArray(array) => {
+                data_operations::indexed_dynamic_array(*array, value, index)
+            }
+            Type::Base(Base::FixedBytes(size)) => {
+                expression! { get_fixed_byte([value], [literal_expression! { (size) }], [index]) }
+            }
             _ => panic!("invalid attributes"),
         };
     }
@@ -417,6 +685,39 @@ fn expr_subscript(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Express
     unreachable!()
 }
 
+fn expr_slice(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
+    if let fe::Expr::Slice {
+        value: value_node,
+        start,
+        end,
+    } = &exp.kind
+    {
+        let value = expr(context, value_node);
+        let start = slice_bound(start);
+        let end = slice_bound(end);
+
+        return match &context.expression_attributes(value_node).typ {
+            Type::Array(array) => data_operations::sliced_array(array.clone(), value, start, end),
+            Type::Base(Base::FixedBytes(_)) => {
+                math_operations::sliced_fixed_bytes(value, start, end)
+            }
+            _ => panic!("invalid attributes"),
+        };
+    }
+
+    unreachable!()
+}
+
+/// Slice bounds are lowered to literals.
+fn slice_bound(bound: &Option<Box<Node<fe::Expr>>>) -> usize {
+    match bound.as_ref().map(|bound| &bound.kind) {
+        Some(fe::Expr::Num(num)) => numeric::Literal::new(num)
+            .parse::<usize>()
+            .expect("invalid slice bound"),
+        _ => panic!("slice bounds should be lowered"),
+    }
+}
+
 fn expr_attribute(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     let (target, field) = match &exp.kind {
         fe::Expr::Attribute { value, attr } => (value, attr),
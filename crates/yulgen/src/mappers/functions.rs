@@ -1,15 +1,17 @@
 use crate::constants::PANIC_FAILED_ASSERTION;
 use crate::context::FnContext;
-use crate::mappers::{assignments, declarations, expressions};
+use crate::mappers::{assignments, declarations, expressions, inline_yul};
 use crate::names;
 use crate::operations::data as data_operations;
 use crate::operations::revert as revert_operations;
 use crate::types::{AbiType, AsAbiType, EvmSized};
 use fe_analyzer::context::{CallType, ExpressionAttributes};
 use fe_analyzer::namespace::types::{Base, Type};
+use fe_common::numeric;
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 use if_chain::if_chain;
+use num_bigint::BigInt;
 use yultsur::*;
 
 pub fn multiple_func_stmt(
@@ -44,6 +46,8 @@ fn func_stmt(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stateme
         fe::FuncStmt::Break => break_statement(context, stmt),
         fe::FuncStmt::Continue => continue_statement(context, stmt),
         fe::FuncStmt::Revert { .. } => revert(context, stmt),
+        fe::FuncStmt::Match { .. } => match_statement(context, stmt),
+        fe::FuncStmt::Yul(body) => yul::Statement::Block(inline_yul::block(body)),
     }
 }
 
@@ -95,6 +99,74 @@ fn if_statement(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stat
     unreachable!()
 }
 
+/// Compiles a `match` that compares an enum or an integer against variants and
+/// literals to a `switch`. Other `match` statements are lowered to `if`
+/// statements.
+///
+/// Yul rejects duplicate cases and cases after the default, so the arms that
+/// can't be reached are left out.
+fn match_statement(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
+    if let fe::FuncStmt::Match { value, arms } = &stmt.kind {
+        let expression = expressions::expr(context, value);
+        let value_type = context.expression_attributes(value).typ.clone();
+
+        let mut covered = vec![];
+        let mut cases = vec![];
+        for arm in arms {
+            let case_value = match &arm.kind.pat.kind {
+                fe::Pattern::Wildcard => None,
+                fe::Pattern::Path(path) => {
+                    let variant = path.segments.last().expect("empty path");
+                    match &value_type {
+                        Type::Enum(val) => Some(BigInt::from(
+                            val.id
+                                .variant_index(context.adb, &variant.kind)
+                                .expect("undefined enum variant"),
+                        )),
+                        _ => panic!("path patterns can only match enums"),
+                    }
+                }
+                fe::Pattern::Literal(Node {
+                    kind: fe::Expr::Num(num),
+                    ..
+                }) => Some(
+                    numeric::Literal::new(num)
+                        .parse::<BigInt>()
+                        .expect("Invalid numeric literal"),
+                ),
+                _ => panic!("`match` patterns should be lowered"),
+            };
+            if matches!(&case_value, Some(case_value) if covered.contains(case_value)) {
+                continue;
+            }
+
+            let block = yul::Block {
+                statements: multiple_func_stmt(context, &arm.kind.body),
+            };
+            match case_value {
+                Some(case_value) => {
+                    cases.push(yul::Case {
+                        literal: Some(literal! { (case_value.to_string()) }),
+                        block,
+                    });
+                    covered.push(case_value);
+                }
+                None => {
+                    cases.push(yul::Case {
+                        literal: None,
+                        block,
+                    });
+                    break;
+                }
+            }
+        }
+
+        return yul::Statement::Switch(yul::Switch { expression, cases });
+    }
+
+    unreachable!()
+}
+
 fn expr(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
     if let fe::FuncStmt::Expr { value } = &stmt.kind {
         let expr = expressions::expr(context, value);
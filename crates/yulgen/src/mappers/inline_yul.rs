@@ -0,0 +1,125 @@
+use crate::names;
+use fe_parser::ast as fe;
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+use yultsur::*;
+
+/// Builds the Yul of an inline Yul block. The analyzer has checked that the
+/// names that aren't defined in the block refer to Fe variables.
+pub fn block(body: &[Node<fe::YulStmt>]) -> yul::Block {
+    YulMapper::default().block(body)
+}
+
+#[derive(Default)]
+struct YulMapper {
+    /// The variables defined in the Yul block, by Yul scope.
+    locals: Vec<Vec<SmolStr>>,
+}
+
+impl YulMapper {
+    fn block(&mut self, body: &[Node<fe::YulStmt>]) -> yul::Block {
+        self.locals.push(vec![]);
+        let block = yul::Block {
+            statements: self.stmts(body),
+        };
+        self.locals.pop();
+        block
+    }
+
+    fn stmts(&mut self, body: &[Node<fe::YulStmt>]) -> Vec<yul::Statement> {
+        body.iter().map(|stmt| self.stmt(stmt)).collect()
+    }
+
+    fn stmt(&mut self, stmt: &Node<fe::YulStmt>) -> yul::Statement {
+        match &stmt.kind {
+            fe::YulStmt::Block(body) => yul::Statement::Block(self.block(body)),
+            fe::YulStmt::Let { names, value } => {
+                let expression = value.as_ref().map(|value| self.expr(value));
+                let scope = self.locals.last_mut().expect("missing Yul scope");
+                scope.extend(names.iter().map(|name| name.kind.clone()));
+                yul::Statement::VariableDeclaration(yul::VariableDeclaration {
+                    identifiers: self.names(names),
+                    expression,
+                })
+            }
+            fe::YulStmt::Assign { names, value } => yul::Statement::Assignment(yul::Assignment {
+                identifiers: self.names(names),
+                expression: self.expr(value),
+            }),
+            fe::YulStmt::If { test, body } => yul::Statement::If(yul::If {
+                expression: self.expr(test),
+                block: self.block(body),
+            }),
+            fe::YulStmt::Switch { value, cases } => yul::Statement::Switch(yul::Switch {
+                expression: self.expr(value),
+                cases: cases
+                    .iter()
+                    .map(|case| yul::Case {
+                        literal: case
+                            .kind
+                            .value
+                            .as_ref()
+                            .map(|value| literal! { (value.kind.to_string()) }),
+                        block: self.block(&case.kind.body),
+                    })
+                    .collect(),
+            }),
+            fe::YulStmt::For {
+                init,
+                test,
+                post,
+                body,
+            } => {
+                // The variables defined in `init` are visible in the rest of
+                // the loop.
+                let pre = self.block(init);
+                self.locals.push(init_locals(init));
+                let for_loop = yul::ForLoop {
+                    pre,
+                    condition: self.expr(test),
+                    post: self.block(post),
+                    body: self.block(body),
+                };
+                self.locals.pop();
+                yul::Statement::ForLoop(for_loop)
+            }
+            fe::YulStmt::Break => yul::Statement::Break,
+            fe::YulStmt::Continue => yul::Statement::Continue,
+            fe::YulStmt::Expr(value) => yul::Statement::Expression(self.expr(value)),
+        }
+    }
+
+    fn expr(&self, expr: &Node<fe::YulExpr>) -> yul::Expression {
+        match &expr.kind {
+            fe::YulExpr::Literal(value) => literal_expression! { (value.to_string()) },
+            fe::YulExpr::Name(name) => yul::Expression::Identifier(self.name(name)),
+            fe::YulExpr::Call { func, args } => yul::Expression::FunctionCall(yul::FunctionCall {
+                identifier: identifier! { (func.kind.to_string()) },
+                arguments: args.kind.iter().map(|arg| self.expr(arg)).collect(),
+            }),
+        }
+    }
+
+    fn names(&self, names: &[Node<SmolStr>]) -> Vec<yul::Identifier> {
+        names.iter().map(|name| self.name(&name.kind)).collect()
+    }
+
+    fn name(&self, name: &str) -> yul::Identifier {
+        if self.locals.iter().flatten().any(|local| local == name) {
+            names::inline_yul_var_name(name)
+        } else {
+            names::var_name(name)
+        }
+    }
+}
+
+/// The variables defined at the top level of a `for` loop's `init` block.
+fn init_locals(init: &[Node<fe::YulStmt>]) -> Vec<SmolStr> {
+    init.iter()
+        .filter_map(|stmt| match &stmt.kind {
+            fe::YulStmt::Let { names, .. } => Some(names.iter().map(|name| name.kind.clone())),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
@@ -2,4 +2,5 @@ mod assignments;
 mod declarations;
 mod expressions;
 pub mod functions;
+mod inline_yul;
 pub mod module;
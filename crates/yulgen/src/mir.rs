@@ -0,0 +1,347 @@
+//! Yul generation from the MIR.
+//!
+//! The body of a function that only computes with values (integers, booleans
+//! and addresses) is generated from its MIR. Any other function, e.g. one that
+//! takes `self`, reads memory or storage, or calls a builtin, is still mapped
+//! from the lowered AST by [`crate::mappers`].
+
+use crate::constants::PANIC_FAILED_ASSERTION;
+use crate::db::YulgenDb;
+use crate::names;
+use crate::operations::math as math_operations;
+use crate::operations::revert as revert_operations;
+use fe_analyzer::context::Location;
+use fe_analyzer::namespace::items::FunctionId;
+use fe_analyzer::namespace::types::{Base, FixedSize, Type};
+use fe_analyzer::AnalyzerDb;
+use fe_mir::ir::{
+    BasicBlock, BlockId, Callee, Constant, FunctionBody, LocalId, LocalKind, Operand, Place,
+    PlaceBase, Rvalue, Statement, Terminator,
+};
+use fe_mir::MirDb;
+use fe_parser::ast as fe;
+use num_bigint::BigInt;
+use yultsur::*;
+
+/// The variable holding the block that is executed next, if the body has more
+/// than one block.
+const NEXT_BLOCK: &str = "mir$block";
+
+/// Generates the statements of a function's body from its MIR.
+///
+/// Returns `None` if the function uses anything that isn't supported yet.
+pub fn function_statements(db: &dyn YulgenDb, function: FunctionId) -> Option<Vec<yul::Statement>> {
+    if !computes_with_values(db.upcast(), function) {
+        return None;
+    }
+
+    let mir_db: &dyn MirDb = db.upcast();
+    let body = mir_db.mir_function_body(function);
+    FnGen { db, body: &body }.body()
+}
+
+/// Checks that a function only has value type parameters, variables and
+/// expressions. The MIR of such a function never has projections or contract
+/// fields, and building it can't run into expressions that lowering should
+/// have removed.
+fn computes_with_values(db: &dyn AnalyzerDb, function: FunctionId) -> bool {
+    let sig = function.signature(db);
+    let body = function.body(db);
+
+    let is_value_type =
+        |typ: &FixedSize| matches!(typ, FixedSize::Base(base) if is_value_base(base));
+    sig.self_decl.is_none()
+        && function.return_names(db).len() <= 1
+        && sig.return_type.as_ref().map_or(false, is_value_type)
+        && sig
+            .params
+            .iter()
+            .all(|param| param.typ.as_ref().map_or(false, is_value_type))
+        && body.var_decl_types.values().all(is_value_type)
+        && body.expressions.values().all(|attr| {
+            matches!(&attr.typ, Type::Base(base) if is_value_base(base))
+                && matches!(attr.location, Location::Value)
+                && attr.move_location.is_none()
+        })
+}
+
+fn is_value_base(base: &Base) -> bool {
+    matches!(
+        base,
+        Base::Numeric(_) | Base::Bool | Base::Address | Base::Unit
+    )
+}
+
+struct FnGen<'a> {
+    db: &'a dyn YulgenDb,
+    body: &'a FunctionBody,
+}
+
+impl FnGen<'_> {
+    /// A body with a single block is generated as straight-line code. Otherwise
+    /// the blocks are the cases of a `switch` over the next block, which is
+    /// repeated until a block leaves the function.
+    fn body(&self) -> Option<Vec<yul::Statement>> {
+        let mut statements = vec![];
+        for (idx, local) in self.body.locals.iter().enumerate() {
+            if local.kind != LocalKind::Param {
+                let name = self.local_name(LocalId(idx as u32));
+                statements.push(statement! { let [name] := 0 })
+            }
+        }
+
+        if let [block] = self.body.blocks.as_slice() {
+            statements.extend(self.block(block)?);
+            return Some(statements);
+        }
+
+        let mut cases = vec![];
+        for (idx, block) in self.body.blocks.iter().enumerate() {
+            cases.push(yul::Case {
+                literal: Some(literal! { (idx.to_string()) }),
+                block: yul::Block {
+                    statements: self.block(block)?,
+                },
+            });
+        }
+        let next_block = identifier! { (NEXT_BLOCK) };
+        statements.push(statement! { let [next_block.clone()] := 0 });
+        statements.push(yul::Statement::ForLoop(yul::ForLoop {
+            pre: yul::Block { statements: vec![] },
+            condition: literal_expression! { 1 },
+            post: yul::Block { statements: vec![] },
+            body: yul::Block {
+                statements: vec![yul::Statement::Switch(yul::Switch {
+                    expression: identifier_expression! { [next_block] },
+                    cases,
+                })],
+            },
+        }));
+        Some(statements)
+    }
+
+    fn block(&self, block: &BasicBlock) -> Option<Vec<yul::Statement>> {
+        let mut statements = vec![];
+        for stmt in &block.statements {
+            statements.push(self.stmt(stmt)?);
+        }
+        let terminator = block.terminator.as_ref().expect("unterminated block");
+        statements.extend(self.terminator(terminator)?);
+        Some(statements)
+    }
+
+    fn stmt(&self, stmt: &Statement) -> Option<yul::Statement> {
+        match stmt {
+            Statement::Assign { place, value } => {
+                let local = self.place_local(place)?;
+                let target = self.local_name(local);
+                let value = self.rvalue(value, &self.body.local(local).typ)?;
+                Some(statement! { [target] := [value] })
+            }
+            Statement::Eval(value) => {
+                let value = self.rvalue(value, &Type::unit())?;
+                Some(statement! { pop([value]) })
+            }
+            Statement::Emit { .. } | Statement::InlineYul(_) => None,
+        }
+    }
+
+    fn terminator(&self, terminator: &Terminator) -> Option<Vec<yul::Statement>> {
+        let next_block = identifier! { (NEXT_BLOCK) };
+        match terminator {
+            Terminator::Goto(target) => {
+                let target = block_literal(*target);
+                Some(vec![statement! { [next_block] := [target] }])
+            }
+            Terminator::Branch {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                let then_block = block_literal(*then_block);
+                let else_block = block_literal(*else_block);
+                Some(vec![yul::Statement::Switch(yul::Switch {
+                    expression: self.operand(cond)?,
+                    cases: vec![
+                        yul::Case {
+                            literal: Some(literal! { (0) }),
+                            block: yul::Block {
+                                statements: vec![
+                                    statement! { [next_block.clone()] := [else_block] },
+                                ],
+                            },
+                        },
+                        yul::Case {
+                            literal: None,
+                            block: yul::Block {
+                                statements: vec![statement! { [next_block] := [then_block] }],
+                            },
+                        },
+                    ],
+                })])
+            }
+            Terminator::Return(value) => {
+                let value = self.operand(value)?;
+                Some(statements! {
+                    (return_val := [value])
+                    (leave)
+                })
+            }
+            Terminator::Revert(None) => Some(vec![statement! { revert(0, 0) }]),
+            Terminator::AssertFailure(None) => Some(vec![revert_operations::panic_revert(
+                PANIC_FAILED_ASSERTION,
+            )]),
+            Terminator::Revert(Some(_)) | Terminator::AssertFailure(Some(_)) => None,
+        }
+    }
+
+    /// Generates an rvalue that is assigned to a local of type `typ`.
+    fn rvalue(&self, value: &Rvalue, typ: &Type) -> Option<yul::Expression> {
+        match value {
+            Rvalue::Use(operand) => self.operand(operand),
+            Rvalue::BinOp { op, lhs, rhs } => {
+                let integer = match typ {
+                    Type::Base(Base::Numeric(integer)) => integer,
+                    _ => return None,
+                };
+                let lhs = self.operand(lhs)?;
+                let rhs = self.operand(rhs)?;
+                Some(match op {
+                    fe::BinOperator::Add => {
+                        expression! { [names::checked_add(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::Sub => {
+                        expression! { [names::checked_sub(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::Mult => {
+                        expression! { [names::checked_mul(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::Div => {
+                        expression! { [names::checked_div(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::Mod => {
+                        expression! { [names::checked_mod(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::Pow => {
+                        expression! { [names::checked_exp(integer)]([lhs], [rhs]) }
+                    }
+                    fe::BinOperator::BitAnd => expression! { and([lhs], [rhs]) },
+                    fe::BinOperator::BitOr => expression! { or([lhs], [rhs]) },
+                    fe::BinOperator::BitXor => expression! { xor([lhs], [rhs]) },
+                    fe::BinOperator::LShift => math_operations::adjust_numeric_size(
+                        integer,
+                        expression! { shl([rhs], [math_operations::adjust_numeric_size(integer, lhs)]) },
+                    ),
+                    fe::BinOperator::RShift if integer.is_signed() => {
+                        expression! { sar([rhs], [lhs]) }
+                    }
+                    fe::BinOperator::RShift => expression! { shr([rhs], [lhs]) },
+                })
+            }
+            Rvalue::CompOp { op, lhs, rhs } => {
+                let signed = self.is_signed(lhs) || self.is_signed(rhs);
+                let lhs = self.operand(lhs)?;
+                let rhs = self.operand(rhs)?;
+                Some(match (op, signed) {
+                    (fe::CompOperator::Eq, _) => expression! { eq([lhs], [rhs]) },
+                    (fe::CompOperator::NotEq, _) => expression! { iszero((eq([lhs], [rhs]))) },
+                    (fe::CompOperator::Lt, true) => expression! { slt([lhs], [rhs]) },
+                    (fe::CompOperator::Lt, false) => expression! { lt([lhs], [rhs]) },
+                    (fe::CompOperator::LtE, true) => expression! { iszero((sgt([lhs], [rhs]))) },
+                    (fe::CompOperator::LtE, false) => expression! { iszero((gt([lhs], [rhs]))) },
+                    (fe::CompOperator::Gt, true) => expression! { sgt([lhs], [rhs]) },
+                    (fe::CompOperator::Gt, false) => expression! { gt([lhs], [rhs]) },
+                    (fe::CompOperator::GtE, true) => expression! { iszero((slt([lhs], [rhs]))) },
+                    (fe::CompOperator::GtE, false) => expression! { iszero((lt([lhs], [rhs]))) },
+                })
+            }
+            Rvalue::UnOp { op, operand } => {
+                let is_const = matches!(operand, Operand::Const(_));
+                let value = self.operand(operand)?;
+                match (op, typ) {
+                    // Literals are checked at compile time, like in the AST mappers.
+                    (fe::UnaryOperator::USub, Type::Base(Base::Numeric(_))) if is_const => {
+                        Some(expression! { sub(0, [value]) })
+                    }
+                    (fe::UnaryOperator::USub, Type::Base(Base::Numeric(integer))) => {
+                        Some(expression! { [names::checked_neg(integer)]([value]) })
+                    }
+                    (fe::UnaryOperator::Not, _) => Some(expression! { iszero([value]) }),
+                    (fe::UnaryOperator::Invert, Type::Base(Base::Numeric(integer))) => Some(
+                        math_operations::adjust_numeric_size(integer, expression! { not([value]) }),
+                    ),
+                    _ => None,
+                }
+            }
+            Rvalue::Call {
+                callee: Callee::Function { id, .. },
+                args,
+            } => {
+                // Receivers are passed as the first argument, which the values
+                // of this function can't be.
+                if id.signature(self.db.upcast()).self_decl.is_some() {
+                    return None;
+                }
+                let name = identifier! { (self.db.function_yul_name(*id)) };
+                let args = args
+                    .iter()
+                    .map(|arg| self.operand(arg))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(expression! { [name]([args...]) })
+            }
+            Rvalue::Call { .. } | Rvalue::Slice { .. } | Rvalue::Env { .. } => None,
+        }
+    }
+
+    fn operand(&self, operand: &Operand) -> Option<yul::Expression> {
+        match operand {
+            Operand::Place(place) => {
+                let name = self.local_name(self.place_local(place)?);
+                Some(identifier_expression! { [name] })
+            }
+            Operand::Const(Constant::Int(num)) => {
+                // Negative numbers are written in two's complement.
+                let num = if num < &BigInt::from(0) {
+                    (BigInt::from(1) << 256) + num
+                } else {
+                    num.clone()
+                };
+                Some(literal_expression! { (num) })
+            }
+            Operand::Const(Constant::Bool(val)) => Some(literal_expression! { (val) }),
+            Operand::Const(Constant::Unit) => Some(literal_expression! { 0 }),
+            Operand::Const(Constant::Str(_)) => None,
+        }
+    }
+
+    fn place_local(&self, place: &Place) -> Option<LocalId> {
+        match place.base {
+            PlaceBase::Local(local) if place.projections.is_empty() => Some(local),
+            _ => None,
+        }
+    }
+
+    fn is_signed(&self, operand: &Operand) -> bool {
+        match operand {
+            Operand::Place(Place {
+                base: PlaceBase::Local(local),
+                ..
+            }) => self.body.local(*local).typ.is_signed_integer(),
+            _ => false,
+        }
+    }
+
+    /// Parameters keep the names of the AST mappers, which are used for the
+    /// function's signature.
+    fn local_name(&self, local: LocalId) -> yul::Identifier {
+        let data = self.body.local(local);
+        match (&data.kind, &data.name) {
+            (LocalKind::Param, Some(name)) => names::var_name(name),
+            _ => identifier! { (format!("mir${}", local.0)) },
+        }
+    }
+}
+
+fn block_literal(block: BlockId) -> yul::Expression {
+    literal_expression! { (block.0) }
+}
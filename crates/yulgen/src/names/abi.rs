@@ -22,6 +22,8 @@ pub fn decode_component(typ: &AbiType, location: AbiDecodeLocation) -> yul::Iden
     match typ {
         AbiType::Address => decode_component_address(location),
         AbiType::Bool => decode_component_bool(location),
+        AbiType::Enum { variant_count } => decode_component_enum(*variant_count, location),
+        AbiType::FixedBytes { size } => decode_component_fixed_bytes(*size, location),
         AbiType::Uint { size } => decode_component_uint(*size, location),
         AbiType::Int { size } => decode_component_int(*size, location),
         AbiType::StaticArray { inner, size } => {
@@ -43,6 +45,16 @@ pub fn decode_component_uint(size: usize, location: AbiDecodeLocation) -> yul::I
     identifier! { (name) }
 }
 
+pub fn decode_component_enum(variant_count: usize, location: AbiDecodeLocation) -> yul::Identifier {
+    let name = format!(
+        "abi_decode_component_enum{}_{}",
+        variant_count,
+        decode_location(location)
+    );
+
+    identifier! { (name) }
+}
+
 pub fn decode_component_int(size: usize, location: AbiDecodeLocation) -> yul::Identifier {
     let name = format!(
         "abi_decode_component_int{}_{}",
@@ -53,6 +65,16 @@ pub fn decode_component_int(size: usize, location: AbiDecodeLocation) -> yul::Id
     identifier! { (name) }
 }
 
+pub fn decode_component_fixed_bytes(size: usize, location: AbiDecodeLocation) -> yul::Identifier {
+    let name = format!(
+        "abi_decode_component_fixed_bytes{}_{}",
+        size,
+        decode_location(location)
+    );
+
+    identifier! { (name) }
+}
+
 pub fn decode_component_bool(location: AbiDecodeLocation) -> yul::Identifier {
     let name = format!("abi_decode_component_bool_{}", decode_location(location));
 
@@ -128,7 +150,9 @@ pub fn typ(_typ: &AbiType) -> String {
         AbiType::Uint { size } => format!("uint{}", size * 8),
         AbiType::Int { size } => format!("int{}", size * 8),
         AbiType::Bool => "bool".to_string(),
+        AbiType::Enum { variant_count } => format!("enum{}", variant_count),
         AbiType::Address => "address".to_string(),
+        AbiType::FixedBytes { size } => format!("fixed_bytes{}", size),
         AbiType::StaticArray { size, inner } => format!("array_{}_{}", size, typ(inner)),
         AbiType::Tuple { components } => format!("tuple_{}", types(components)),
         AbiType::String { max_size } => format!("string_{}", max_size),
@@ -1,5 +1,6 @@
 use crate::names::abi as abi_names;
 use crate::types::AbiType;
+use fe_analyzer::builtins::{ContractTypeMethod, ValueMethod};
 use fe_analyzer::namespace::types::Integer;
 use yultsur::*;
 
@@ -25,6 +26,17 @@ pub fn checked_div(size: &Integer) -> yul::Identifier {
     identifier! {(format!("checked_div_{}", size.to_lowercase()))}
 }
 
+/// Generate a function name to perform division with the rounding of the
+/// given `div_*` method
+pub fn rounding_div(method: ValueMethod, size: &Integer) -> yul::Identifier {
+    let sign: &str = if size.is_signed() {
+        "signed"
+    } else {
+        "unsigned"
+    };
+    identifier! {(format!("{}_{}", method.as_ref(), sign))}
+}
+
 /// Generate a function name to perform checked modulo
 pub fn checked_mod(size: &Integer) -> yul::Identifier {
     let sign: &str = if size.is_signed() {
@@ -65,9 +77,21 @@ pub fn var_name(name: &str) -> yul::Identifier {
     identifier! { (format!("${}", name)) }
 }
 
+/// Generate a name for a variable defined in an inline Yul block that doesn't
+/// collide with Fe variables or with the variables of the generated code
+pub fn inline_yul_var_name(name: &str) -> yul::Identifier {
+    identifier! { (format!("yul${}", name)) }
+}
+
 /// Generates a revert function name for a given name and types
 pub fn revert(name: &str, typ: &AbiType) -> yul::Identifier {
     let name = format!("revert_with_{}_{}", name, abi_names::typ(typ));
 
     identifier! { (name) }
 }
+
+/// Generates the name of the function that creates the given contract with
+/// the arguments of its `__init__` function
+pub fn contract_create(contract_name: &str, method: ContractTypeMethod) -> yul::Identifier {
+    identifier! { (format!("contract_{}_{}", method.as_ref(), contract_name)) }
+}
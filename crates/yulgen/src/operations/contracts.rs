@@ -1,3 +1,5 @@
+use crate::names;
+use fe_analyzer::builtins::ContractTypeMethod;
 use yultsur::*;
 
 /// Executes the `create2` operation for a given contract with the given value
@@ -25,3 +27,14 @@ pub fn create(name: &str, value: yul::Expression) -> yul::Expression {
         )
     }
 }
+
+/// Creates a contract whose `__init__` function takes arguments. The value
+/// (and salt) are followed by the arguments.
+pub fn create_with_args(
+    name: &str,
+    method: ContractTypeMethod,
+    args: Vec<yul::Expression>,
+) -> yul::Expression {
+    let func_name = names::contract_create(name, method);
+    expression! { [func_name]([args...]) }
+}
@@ -1,6 +1,8 @@
 use crate::operations::abi as abi_operations;
 use crate::types::{AbiType, EvmSized};
-use fe_analyzer::namespace::types::Array;
+use fe_analyzer::context::Location;
+use fe_analyzer::namespace::types::{Array, Base, DynamicArray};
+use num_bigint::BigUint;
 use yultsur::*;
 
 /// Loads a value of the given type from storage.
@@ -19,6 +21,33 @@ pub fn sstore<T: EvmSized>(
     statement! { bytes_sstoren([sptr], [size], [value]) }
 }
 
+/// Stores a 32 byte value in a storage word of its own, like a `u256`
+/// contract field.
+///
+/// Unlike `sstore`, the word isn't loaded to preserve the rest of its bytes,
+/// since the value overwrites all of them. If the pointer is a literal, the
+/// word's address is computed at compile time.
+pub fn sstore_word(sptr: yul::Expression, value: yul::Expression) -> yul::Statement {
+    let word_ptr = match literal_value(&sptr) {
+        Some(ptr) => literal_expression! { (format!("{:#x}", ptr >> 5u8)) },
+        None => expression! { div([sptr], 32) },
+    };
+    statement! { sstore([word_ptr], [value]) }
+}
+
+/// The value of a numeric literal expression.
+fn literal_value(expr: &yul::Expression) -> Option<BigUint> {
+    if let yul::Expression::Literal(literal) = expr {
+        let literal = literal.to_string();
+        match literal.strip_prefix("0x") {
+            Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+            None => literal.parse().ok(),
+        }
+    } else {
+        None
+    }
+}
+
 /// Loads a value of the given type from memory.
 pub fn mload<T: EvmSized>(typ: T, mptr: yul::Expression) -> yul::Expression {
     let size = literal_expression! { (typ.size()) };
@@ -142,3 +171,172 @@ pub fn indexed_array(
     let array_length = literal_expression! { (typ.size) };
     expression! { get_array_item([array], [array_length], [index], [inner_size] ) }
 }
+
+/// Finds the location of an item of a dynamically-sized array in storage.
+pub fn indexed_dynamic_array(
+    typ: DynamicArray,
+    array: yul::Expression,
+    index: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { get_dyn_array_item([array], [index], [inner_size]) }
+}
+
+/// Returns the number of items of a dynamically-sized array in storage, which
+/// is stored at the array's word.
+pub fn dynamic_array_len(array: yul::Expression) -> yul::Expression {
+    expression! { sload((div([array], 32))) }
+}
+
+/// Appends a value to a dynamically-sized array in storage.
+pub fn dynamic_array_push(
+    typ: DynamicArray,
+    array: yul::Expression,
+    value: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { dyn_array_push([array], [inner_size], [value]) }
+}
+
+/// Removes the last item of a dynamically-sized array in storage and returns
+/// it.
+pub fn dynamic_array_pop(typ: DynamicArray, array: yul::Expression) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { dyn_array_pop([array], [inner_size]) }
+}
+
+/// Returns the number of bytes of a string, which is stored in its first word.
+pub fn string_len(location: Location, string: yul::Expression) -> yul::Expression {
+    match location {
+        Location::Storage { .. } => expression! { bytes_sloadn([string], 32) },
+        _ => expression! { mload([string]) },
+    }
+}
+
+/// Copies two strings in memory into a new string.
+pub fn string_concat(a: yul::Expression, b: yul::Expression) -> yul::Expression {
+    expression! { string_concat([a], [b]) }
+}
+
+/// Copies the bytes of a string in memory from `start` up to `end` into a new
+/// string.
+pub fn string_slice(
+    string: yul::Expression,
+    start: yul::Expression,
+    end: yul::Expression,
+) -> yul::Expression {
+    expression! { string_slice([string], [start], [end]) }
+}
+
+/// Returns 1 if two strings in memory are equal, otherwise 0.
+pub fn string_eq(a: yul::Expression, b: yul::Expression) -> yul::Expression {
+    expression! { string_eq([a], [b]) }
+}
+
+/// Copies the elements of an array in memory from `start` up to `end` to a new
+/// segment of memory.
+pub fn sliced_array(
+    typ: Array,
+    array: yul::Expression,
+    start: usize,
+    end: usize,
+) -> yul::Expression {
+    let offset = literal_expression! { (start * typ.inner.size()) };
+    let size = literal_expression! { ((end - start) * typ.inner.size()) };
+    expression! { mcopym((add([array], [offset])), [size]) }
+}
+
+/// Returns the index of the first item of an array that is equal to `value`,
+/// or the array length if there is no such item.
+pub fn array_index_of(
+    typ: Array,
+    location: Location,
+    array: yul::Expression,
+    value: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    let array_length = literal_expression! { (typ.size) };
+    match location {
+        Location::Storage { .. } => {
+            expression! { sindex_of([array], [array_length], [inner_size], [value]) }
+        }
+        _ => expression! { mindex_of([array], [array_length], [inner_size], [value]) },
+    }
+}
+
+/// Returns 1 if an item of the array is equal to `value`, otherwise 0.
+pub fn array_contains(
+    typ: Array,
+    location: Location,
+    array: yul::Expression,
+    value: yul::Expression,
+) -> yul::Expression {
+    let array_length = literal_expression! { (typ.size) };
+    let index = array_index_of(typ, location, array, value);
+    expression! { lt([index], [array_length]) }
+}
+
+/// Sets every item of an array to `value`.
+pub fn array_fill(
+    typ: Array,
+    location: Location,
+    array: yul::Expression,
+    value: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    let array_length = literal_expression! { (typ.size) };
+    match location {
+        Location::Storage { .. } => {
+            expression! { sfill([array], [array_length], [inner_size], [value]) }
+        }
+        _ => expression! { mfill([array], [array_length], [inner_size], [value]) },
+    }
+}
+
+/// Copies the items of an array in memory into an existing array of the same
+/// type.
+pub fn array_copy_from(
+    typ: Array,
+    location: Location,
+    array: yul::Expression,
+    source: yul::Expression,
+) -> yul::Expression {
+    let size = literal_expression! { (typ.size()) };
+    match location {
+        Location::Storage { .. } => expression! { scopy_from([array], [source], [size]) },
+        _ => expression! { mcopy_from([array], [source], [size]) },
+    }
+}
+
+/// The bit that is flipped to compare items of the array as unsigned numbers;
+/// the sign bit for signed integers, otherwise 0.
+fn sign_bit(typ: &Array) -> yul::Expression {
+    match typ.inner {
+        Base::Numeric(integer) if integer.is_signed() => {
+            let bit = BigUint::from(1u8) << (typ.inner.size() * 8 - 1);
+            literal_expression! { (format!("{:#x}", bit)) }
+        }
+        _ => literal_expression! { 0 },
+    }
+}
+
+/// Sorts an array in memory in ascending order.
+pub fn array_sort(typ: Array, array: yul::Expression) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    let array_length = literal_expression! { (typ.size) };
+    let sign_bit = sign_bit(&typ);
+    expression! { msort([array], [array_length], [inner_size], [sign_bit]) }
+}
+
+/// Returns the index of an item of a sorted array in memory that is equal to
+/// `value`, or the array length if there is no such item.
+pub fn array_binary_search(
+    typ: Array,
+    array: yul::Expression,
+    value: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    let array_length = literal_expression! { (typ.size) };
+    let sign_bit = sign_bit(&typ);
+    expression! { mbinary_search([array], [array_length], [inner_size], [sign_bit], [value]) }
+}
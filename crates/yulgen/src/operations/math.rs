@@ -1,6 +1,9 @@
+use fe_analyzer::builtins::ValueMethod;
 use fe_analyzer::namespace::types::Integer;
+use num_bigint::BigUint;
 use yultsur::*;
 
+use crate::constants::numeric_min_max;
 use crate::names;
 
 /// Loads a value of the given type from storage.
@@ -11,3 +14,68 @@ pub fn adjust_numeric_size(integer: &Integer, value: yul::Expression) -> yul::Ex
         value
     }
 }
+
+/// Normalizes a boolean to 0 or 1. A byte written outside of Fe code, e.g. by
+/// an older version of a contract, may hold any nonzero value for `true`.
+pub fn normalize_bool(value: yul::Expression) -> yul::Expression {
+    expression! { iszero((iszero([value]))) }
+}
+
+/// Clears the bytes of a word after its first `size` bytes, which are unused
+/// by a `bytes<size>` value.
+pub fn clean_fixed_bytes(size: usize, value: yul::Expression) -> yul::Expression {
+    if size < 32 {
+        let ones = (BigUint::from(1u8) << (size * 8)) - 1u8;
+        let mask = literal_expression! { (format!("{:#x}", ones << ((32 - size) * 8))) };
+        expression! { and([value], [mask]) }
+    } else {
+        value
+    }
+}
+
+/// Returns the bytes of a fixed-size bytes value from `start` up to `end`, as a
+/// `bytes<end - start>` value.
+pub fn sliced_fixed_bytes(value: yul::Expression, start: usize, end: usize) -> yul::Expression {
+    let shifted = if start > 0 {
+        expression! { shl([literal_expression! { (start * 8) }], [value]) }
+    } else {
+        value
+    };
+    clean_fixed_bytes(end - start, shifted)
+}
+
+/// Converts an unsigned integer to a `bytes<size>` value. Integers are aligned
+/// to the right of a word, and fixed-size bytes to the left.
+pub fn int_to_fixed_bytes(size: usize, value: yul::Expression) -> yul::Expression {
+    if size < 32 {
+        expression! { shl([literal_expression! { ((32 - size) * 8) }], [value]) }
+    } else {
+        value
+    }
+}
+
+/// Converts a `bytes<size>` value to an unsigned integer.
+pub fn fixed_bytes_to_int(size: usize, value: yul::Expression) -> yul::Expression {
+    if size < 32 {
+        expression! { shr([literal_expression! { ((32 - size) * 8) }], [value]) }
+    } else {
+        value
+    }
+}
+
+/// Divides `dividend` by `divisor`, rounding the quotient as specified by the
+/// given `div_*` method.
+pub fn rounding_div(
+    method: ValueMethod,
+    integer: &Integer,
+    dividend: yul::Expression,
+    divisor: yul::Expression,
+) -> yul::Expression {
+    let func_name = names::rounding_div(method, integer);
+    if integer.is_signed() {
+        let (min_value, _) = numeric_min_max()[integer].clone();
+        expression! { [func_name]([dividend], [divisor], [min_value]) }
+    } else {
+        expression! { [func_name]([dividend], [divisor]) }
+    }
+}
@@ -16,3 +16,9 @@ pub fn get_attribute(
     let function_name = identifier! { (db.struct_getter_name(struct_, field_name.into(), true)) };
     expression! { [function_name]([val]) }
 }
+
+/// Hashes a struct in memory with keccak256.
+pub fn hash(db: &dyn YulgenDb, struct_: StructId, val: yul::Expression) -> yul::Expression {
+    let function_name = identifier! { (db.struct_hash_name(struct_)) };
+    expression! { [function_name]([val]) }
+}
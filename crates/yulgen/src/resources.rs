@@ -0,0 +1,778 @@
+//! Static estimates of the stack and memory used by the functions of a
+//! contract's Yul code.
+//!
+//! A function whose variables don't fit in the stack slots the EVM can reach
+//! fails to compile with solc's "stack too deep" error, which doesn't say much
+//! about the cause. Memory is never freed, so a call that allocates a lot of
+//! it can also run out of gas. The estimates are made on the unoptimized Yul
+//! text and are deliberately pessimistic: temporaries are counted as if
+//! nothing is inlined, and every call is assumed to happen on the deepest
+//! path.
+
+use fe_common::diagnostics::{Diagnostic, Severity};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The number of stack slots an instruction can reach, with `DUP16` and
+/// `SWAP16`.
+pub const STACK_REACH: usize = 16;
+/// The maximum number of items on the EVM stack.
+pub const STACK_LIMIT: usize = 1024;
+/// The cost of memory expansion, in gas, above which a warning is emitted.
+/// This is a tenth of the block gas limit.
+pub const MEMORY_GAS_WARNING: u64 = 3_000_000;
+
+/// Functions that allocate memory, with the index of their size argument.
+const ALLOCATORS: &[(&str, usize)] = &[("alloc", 0), ("alloc_mstoren", 1)];
+
+/// The estimated resources of a Yul function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionResources {
+    /// The name of the Yul function, or `None` for the object's top-level
+    /// code.
+    pub name: Option<String>,
+    /// The stack slots used by the function's parameters, return values,
+    /// local variables and temporaries.
+    pub stack_slots: usize,
+    /// The stack depth while the function runs, including the functions it
+    /// calls. `None` if it's recursive, or calls a recursive function.
+    pub max_stack_depth: Option<usize>,
+    /// The bytes of memory allocated by the function and the functions it
+    /// calls. `None` if the size of an allocation isn't a constant, if it
+    /// happens in a loop, or if the function is recursive.
+    pub memory: Option<u64>,
+}
+
+impl FunctionResources {
+    fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("[top-level code]")
+    }
+}
+
+/// The estimated resources of a contract's deployment and runtime code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractResources {
+    pub contract: String,
+    pub deploy: Vec<FunctionResources>,
+    pub runtime: Vec<FunctionResources>,
+}
+
+impl ContractResources {
+    /// Estimates the resources of a contract, given the code blocks of its
+    /// deploy and runtime objects.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either block isn't valid Yul.
+    pub fn new(contract: &str, deploy: &str, runtime: &str) -> Self {
+        ContractResources {
+            contract: contract.to_string(),
+            deploy: estimate(deploy),
+            runtime: estimate(runtime),
+        }
+    }
+
+    /// Warnings for the functions that are likely to exceed the EVM's limits.
+    pub fn warnings(&self) -> Vec<Diagnostic> {
+        let mut warnings = vec![];
+        for function in self.deploy.iter().chain(&self.runtime) {
+            let name = function.display_name();
+            if function.stack_slots > STACK_REACH {
+                warnings.push(warning(
+                    "stack-too-deep",
+                    format!(
+                        "`{}` of contract `{}` needs about {} stack slots, but only {} can be reached",
+                        name, self.contract, function.stack_slots, STACK_REACH
+                    ),
+                    "Note: solc is likely to fail with a \"stack too deep\" error. Try using fewer local variables and parameters, e.g. by grouping them in a struct, or splitting the function up.",
+                ));
+            }
+            if matches!(function.max_stack_depth, Some(depth) if depth > STACK_LIMIT) {
+                warnings.push(warning(
+                    "stack-overflow",
+                    format!(
+                        "calls from `{}` of contract `{}` may use more than {} stack slots",
+                        name, self.contract, STACK_LIMIT
+                    ),
+                    "Note: The EVM stack can hold at most 1024 values, and the call fails if it overflows.",
+                ));
+            }
+            if let Some(bytes) = function.memory {
+                if memory_gas(bytes) > MEMORY_GAS_WARNING {
+                    warnings.push(warning(
+                        "memory-cost",
+                        format!(
+                            "`{}` of contract `{}` allocates {} bytes of memory, which costs {} gas",
+                            name,
+                            self.contract,
+                            bytes,
+                            memory_gas(bytes)
+                        ),
+                        "Note: The cost of memory grows quadratically, and memory isn't freed until the call ends.",
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for ContractResources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (object, functions) in [("deploy", &self.deploy), ("runtime", &self.runtime)] {
+            writeln!(f, "{} code of `{}`:", object, self.contract)?;
+            for function in functions {
+                write!(
+                    f,
+                    "  {}: {} stack slots, max stack depth ",
+                    function.display_name(),
+                    function.stack_slots
+                )?;
+                match function.max_stack_depth {
+                    Some(depth) => write!(f, "{}", depth)?,
+                    None => write!(f, "unbounded (recursive)")?,
+                }
+                match function.memory {
+                    Some(bytes) => writeln!(f, ", allocates {} bytes", bytes)?,
+                    None => writeln!(f, ", allocates an unknown amount of memory")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn warning(code: &str, message: String, note: &str) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        code: Some(code.to_string()),
+        message,
+        labels: vec![],
+        notes: vec![note.to_string()],
+    }
+}
+
+/// The gas cost of expanding memory to `bytes`.
+fn memory_gas(bytes: u64) -> u64 {
+    let words = (bytes + 31) / 32;
+    3 * words + words * words / 512
+}
+
+/// Estimates the resources of the top-level code and each function defined in
+/// a Yul block, in that order.
+fn estimate(code: &str) -> Vec<FunctionResources> {
+    let mut parser = Parser {
+        tokens: tokenize(code),
+        pos: 0,
+    };
+    let body = parser.block();
+    assert!(
+        parser.pos == parser.tokens.len(),
+        "unexpected Yul after block"
+    );
+
+    let mut functions = HashMap::new();
+    collect_functions(&body, &mut functions);
+    let top_level = Function {
+        name: None,
+        params: 0,
+        returns: 0,
+        body,
+    };
+
+    let mut estimator = Estimator {
+        functions: &functions,
+        depths: HashMap::new(),
+        memory: HashMap::new(),
+    };
+    let mut names = functions.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    std::iter::once(&top_level)
+        .chain(names.iter().map(|name| &functions[name]))
+        .map(|function| FunctionResources {
+            name: function.name.clone(),
+            stack_slots: function.stack_slots(&functions),
+            max_stack_depth: estimator.depth(function),
+            memory: estimator.memory(function),
+        })
+        .collect()
+}
+
+fn collect_functions(stmts: &[Stmt], functions: &mut HashMap<String, Function>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Function(function) => {
+                collect_functions(&function.body, functions);
+                functions.insert(function.name.clone().unwrap(), function.clone());
+            }
+            Stmt::Block(body) | Stmt::If(_, body) => collect_functions(body, functions),
+            Stmt::Switch(_, cases) => {
+                for case in cases {
+                    collect_functions(case, functions)
+                }
+            }
+            Stmt::For(pre, _, post, body) => {
+                collect_functions(pre, functions);
+                collect_functions(post, functions);
+                collect_functions(body, functions);
+            }
+            Stmt::Let(..) | Stmt::Expr(_) | Stmt::Other => {}
+        }
+    }
+}
+
+/// Computes the memory and stack depth of functions and their callees,
+/// remembering the results. A function that's still being computed is
+/// part of a cycle.
+struct Estimator<'a> {
+    functions: &'a HashMap<String, Function>,
+    depths: HashMap<String, Option<Option<usize>>>,
+    memory: HashMap<String, Option<Option<u64>>>,
+}
+
+impl Estimator<'_> {
+    fn depth(&mut self, function: &Function) -> Option<usize> {
+        let functions = self.functions;
+        if let Some(name) = &function.name {
+            match self.depths.get(name) {
+                Some(Some(depth)) => return *depth,
+                Some(None) => return None,
+                None => self.depths.insert(name.clone(), None),
+            };
+        }
+
+        let mut callees = vec![];
+        function.calls(&mut |call, _| {
+            if let Some(callee) = functions.get(call.0) {
+                callees.push(callee)
+            }
+        });
+        let mut deepest = Some(0);
+        for callee in callees {
+            deepest = match (deepest, self.depth(callee)) {
+                // the callee's frame sits on top of the caller's frame, along
+                // with the return address
+                (Some(deepest), Some(depth)) => Some(deepest.max(depth + 1)),
+                _ => None,
+            };
+        }
+        let depth = deepest.map(|deepest| function.stack_slots(functions) + deepest);
+
+        if let Some(name) = &function.name {
+            self.depths.insert(name.clone(), Some(depth));
+        }
+        depth
+    }
+
+    fn memory(&mut self, function: &Function) -> Option<u64> {
+        let functions = self.functions;
+        if let Some(name) = &function.name {
+            match self.memory.get(name) {
+                Some(Some(memory)) => return *memory,
+                Some(None) => return None,
+                None => self.memory.insert(name.clone(), None),
+            };
+        }
+
+        let mut calls = vec![];
+        function.calls(&mut |(name, args), in_loop| {
+            calls.push((name.to_string(), args.to_vec(), in_loop))
+        });
+        let mut total = Some(0u64);
+        for (name, args, in_loop) in calls {
+            let allocated =
+                if let Some((_, size)) = ALLOCATORS.iter().find(|(alloc, _)| *alloc == name) {
+                    match args.get(*size) {
+                        Some(Expr::Value(size)) => *size,
+                        _ => None,
+                    }
+                } else if let Some(callee) = functions.get(&name) {
+                    self.memory(callee)
+                } else {
+                    Some(0)
+                };
+            total = match (total, allocated) {
+                (Some(total), Some(0)) => Some(total),
+                (Some(_), Some(_)) if in_loop => None,
+                (Some(total), Some(allocated)) => total.checked_add(allocated),
+                _ => None,
+            };
+        }
+
+        if let Some(name) = &function.name {
+            self.memory.insert(name.clone(), Some(total));
+        }
+        total
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Function {
+    name: Option<String>,
+    params: usize,
+    returns: usize,
+    body: Vec<Stmt>,
+}
+
+impl Function {
+    /// The stack slots used by the function's own frame.
+    fn stack_slots(&self, functions: &HashMap<String, Function>) -> usize {
+        let live = self.params + self.returns;
+        live.max(block_slots(&self.body, live, functions))
+    }
+
+    /// Calls `f` with each function call in the body, and whether it's in a
+    /// loop.
+    fn calls(&self, f: &mut dyn FnMut((&str, &[Expr]), bool)) {
+        stmts_calls(&self.body, false, f)
+    }
+}
+
+/// The peak number of stack slots used by a block, given the number of
+/// variables that are live when it starts.
+fn block_slots(stmts: &[Stmt], mut live: usize, functions: &HashMap<String, Function>) -> usize {
+    let temps = |expr: &Expr| expr_slots(expr, functions);
+    let mut peak = live;
+    for stmt in stmts {
+        let used = match stmt {
+            Stmt::Let(count, value) => {
+                let used = live + value.as_ref().map_or(0, temps);
+                live += count;
+                used.max(live)
+            }
+            Stmt::Expr(value) => live + temps(value),
+            Stmt::If(test, body) => (live + temps(test)).max(block_slots(body, live, functions)),
+            // the switch value stays on the stack while the cases run
+            Stmt::Switch(value, cases) => cases
+                .iter()
+                .map(|case| block_slots(case, live + 1, functions))
+                .fold(live + temps(value), usize::max),
+            Stmt::For(pre, test, post, body) => {
+                let pre_live = live + pre.iter().map(Stmt::declared).sum::<usize>();
+                block_slots(pre, live, functions)
+                    .max(pre_live + temps(test))
+                    .max(block_slots(post, pre_live, functions))
+                    .max(block_slots(body, pre_live, functions))
+            }
+            Stmt::Block(body) => block_slots(body, live, functions),
+            Stmt::Function(_) | Stmt::Other => live,
+        };
+        peak = peak.max(used);
+    }
+    peak
+}
+
+/// The peak number of stack slots used to evaluate an expression. Arguments
+/// are evaluated from right to left, and a call to a Yul function first
+/// pushes its return address.
+fn expr_slots(expr: &Expr, functions: &HashMap<String, Function>) -> usize {
+    match expr {
+        Expr::Value(_) => 1,
+        Expr::Call(name, args) => {
+            let base = usize::from(functions.contains_key(name));
+            args.iter()
+                .rev()
+                .enumerate()
+                .map(|(pushed, arg)| base + pushed + expr_slots(arg, functions))
+                .fold((base + args.len()).max(1), usize::max)
+        }
+    }
+}
+
+fn stmts_calls(stmts: &[Stmt], in_loop: bool, f: &mut dyn FnMut((&str, &[Expr]), bool)) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Let(_, Some(value)) | Stmt::Expr(value) => expr_calls(value, in_loop, f),
+            Stmt::If(test, body) => {
+                expr_calls(test, in_loop, f);
+                stmts_calls(body, in_loop, f);
+            }
+            Stmt::Switch(value, cases) => {
+                expr_calls(value, in_loop, f);
+                for case in cases {
+                    stmts_calls(case, in_loop, f)
+                }
+            }
+            Stmt::For(pre, test, post, body) => {
+                stmts_calls(pre, in_loop, f);
+                expr_calls(test, true, f);
+                stmts_calls(post, true, f);
+                stmts_calls(body, true, f);
+            }
+            Stmt::Block(body) => stmts_calls(body, in_loop, f),
+            Stmt::Let(_, None) | Stmt::Function(_) | Stmt::Other => {}
+        }
+    }
+}
+
+fn expr_calls(expr: &Expr, in_loop: bool, f: &mut dyn FnMut((&str, &[Expr]), bool)) {
+    if let Expr::Call(name, args) = expr {
+        for arg in args {
+            expr_calls(arg, in_loop, f)
+        }
+        f((name, args), in_loop)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    /// A variable or a literal, with its value if it's a number.
+    Value(Option<u64>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Block(Vec<Stmt>),
+    Function(Function),
+    /// A declaration of a number of variables.
+    Let(usize, Option<Expr>),
+    /// An assignment or an expression statement.
+    Expr(Expr),
+    If(Expr, Vec<Stmt>),
+    Switch(Expr, Vec<Vec<Stmt>>),
+    For(Vec<Stmt>, Expr, Vec<Stmt>, Vec<Stmt>),
+    /// `break`, `continue` or `leave`.
+    Other,
+}
+
+impl Stmt {
+    /// The number of variables the statement declares in its block.
+    fn declared(&self) -> usize {
+        match self {
+            Stmt::Let(count, _) => *count,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    /// A literal, with its value if it's a number.
+    Literal(Option<u64>),
+    Symbol(&'static str),
+}
+
+fn tokenize(code: &str) -> Vec<Token> {
+    let chars = code.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    let take_while = |pos: &mut usize, pred: &dyn Fn(char) -> bool| {
+        let start = *pos;
+        while *pos < chars.len() && pred(chars[*pos]) {
+            *pos += 1;
+        }
+        chars[start..*pos].iter().collect::<String>()
+    };
+    let skip_string = |pos: &mut usize| {
+        *pos += 1;
+        while *pos < chars.len() && chars[*pos] != '"' {
+            *pos += if chars[*pos] == '\\' { 2 } else { 1 };
+        }
+        *pos += 1;
+    };
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).copied();
+        match c {
+            _ if c.is_whitespace() => pos += 1,
+            '/' if next == Some('/') => {
+                take_while(&mut pos, &|c| c != '\n');
+            }
+            '/' if next == Some('*') => {
+                pos += 2;
+                while pos + 1 < chars.len() && !(chars[pos] == '*' && chars[pos + 1] == '/') {
+                    pos += 1;
+                }
+                pos += 2;
+            }
+            '"' => {
+                skip_string(&mut pos);
+                tokens.push(Token::Literal(None));
+            }
+            '0'..='9' => {
+                let num = take_while(&mut pos, &|c| c.is_ascii_alphanumeric());
+                let value = match num.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                    None => num.parse().ok(),
+                };
+                tokens.push(Token::Literal(value));
+            }
+            'a'..='z' | 'A'..='Z' | '_' | '$' => {
+                let name = take_while(&mut pos, &|c| {
+                    c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')
+                });
+                if name == "hex" && chars.get(pos) == Some(&'"') {
+                    skip_string(&mut pos);
+                    tokens.push(Token::Literal(None));
+                } else {
+                    tokens.push(Token::Name(name));
+                }
+            }
+            ':' if next == Some('=') => {
+                tokens.push(Token::Symbol(":="));
+                pos += 2;
+            }
+            '-' if next == Some('>') => {
+                tokens.push(Token::Symbol("->"));
+                pos += 2;
+            }
+            '{' | '}' | '(' | ')' | ',' | ':' => {
+                tokens.push(Token::Symbol(match c {
+                    '{' => "{",
+                    '}' => "}",
+                    '(' => "(",
+                    ')' => ")",
+                    ',' => ",",
+                    _ => ":",
+                }));
+                pos += 1;
+            }
+            _ => panic!("unexpected character `{}` in Yul", c),
+        }
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .expect("unexpected end of Yul");
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &'static str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &'static str) {
+        assert!(self.eat(symbol), "expected `{}` in Yul", symbol)
+    }
+
+    fn name(&mut self) -> String {
+        match self.next() {
+            Token::Name(name) => name,
+            token => panic!("expected a name in Yul, found {:?}", token),
+        }
+    }
+
+    /// A comma separated list of names, optionally with types, e.g.
+    /// `a, b:u256`.
+    fn names(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(Token::Name(_)) = self.peek() {
+            self.pos += 1;
+            if self.eat(":") {
+                self.name();
+            }
+            count += 1;
+            if !self.eat(",") {
+                break;
+            }
+        }
+        count
+    }
+
+    fn block(&mut self) -> Vec<Stmt> {
+        self.expect("{");
+        let mut stmts = vec![];
+        while !self.eat("}") {
+            stmts.push(self.stmt())
+        }
+        stmts
+    }
+
+    fn stmt(&mut self) -> Stmt {
+        if self.peek() == Some(&Token::Symbol("{")) {
+            return Stmt::Block(self.block());
+        }
+        let keyword = match self.peek() {
+            Some(Token::Name(name)) => name.clone(),
+            _ => return Stmt::Expr(self.expr()),
+        };
+        match keyword.as_str() {
+            "function" => {
+                self.pos += 1;
+                let name = self.name();
+                self.expect("(");
+                let params = self.names();
+                self.expect(")");
+                let returns = if self.eat("->") { self.names() } else { 0 };
+                let body = self.block();
+                Stmt::Function(Function {
+                    name: Some(name),
+                    params,
+                    returns,
+                    body,
+                })
+            }
+            "let" => {
+                self.pos += 1;
+                let count = self.names();
+                let value = self.eat(":=").then(|| self.expr());
+                Stmt::Let(count, value)
+            }
+            "if" => {
+                self.pos += 1;
+                let test = self.expr();
+                Stmt::If(test, self.block())
+            }
+            "switch" => {
+                self.pos += 1;
+                let value = self.expr();
+                let mut cases = vec![];
+                loop {
+                    match self.peek() {
+                        Some(Token::Name(name)) if name == "case" => {
+                            self.pos += 1;
+                            self.expr();
+                        }
+                        Some(Token::Name(name)) if name == "default" => self.pos += 1,
+                        _ => break,
+                    }
+                    cases.push(self.block());
+                }
+                Stmt::Switch(value, cases)
+            }
+            "for" => {
+                self.pos += 1;
+                let pre = self.block();
+                let test = self.expr();
+                let post = self.block();
+                let body = self.block();
+                Stmt::For(pre, test, post, body)
+            }
+            "break" | "continue" | "leave" => {
+                self.pos += 1;
+                Stmt::Other
+            }
+            _ => {
+                // an assignment, or an expression
+                let start = self.pos;
+                self.names();
+                if self.eat(":=") {
+                    Stmt::Expr(self.expr())
+                } else {
+                    self.pos = start;
+                    Stmt::Expr(self.expr())
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Expr {
+        match self.next() {
+            Token::Literal(value) => Expr::Value(value),
+            Token::Name(name) => {
+                if !self.eat("(") {
+                    return Expr::Value(None);
+                }
+                let mut args = vec![];
+                while !self.eat(")") {
+                    args.push(self.expr());
+                    self.eat(",");
+                }
+                Expr::Call(name, args)
+            }
+            token => panic!("expected an expression in Yul, found {:?}", token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate, ContractResources, FunctionResources};
+
+    const CODE: &str = r#"{
+        function alloc(size) -> ptr {
+            ptr := mload(0x00)
+            if eq(ptr, 0x00) { ptr := 0x20 }
+            mstore(0x00, add(ptr, size))
+        }
+        function $$store(a, b) -> ptr {
+            ptr := alloc(64)
+            mstore(ptr, a)
+            mstore(add(ptr, 32), b)
+        }
+        function $$copy(len) -> ptr {
+            ptr := alloc(len)
+            for { let i := 0 } lt(i, len) { i := add(i, 32) } {
+                mstore(add(ptr, i), 0)
+            }
+        }
+        function $$loop(n) {
+            for { let i := 0 } lt(i, n) { i := add(i, 1) } { pop($$store(i, i)) }
+        }
+        function $$countdown(n) -> r {
+            switch n
+            case 0 { r := 0 }
+            default { r := $$countdown(sub(n, 1)) }
+        }
+        pop($$store(1, 2))
+        pop($$store(3, "four"))
+    }"#;
+
+    fn find<'a>(functions: &'a [FunctionResources], name: &str) -> &'a FunctionResources {
+        functions
+            .iter()
+            .find(|function| function.name.as_deref() == Some(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn function_resources() {
+        let functions = estimate(CODE);
+        assert_eq!(functions[0].name, None);
+        assert_eq!(functions[0].memory, Some(128));
+
+        let store = find(&functions, "$$store");
+        // `a`, `b` and `ptr`, with `b` and the two arguments of `add` on top
+        assert_eq!(store.stack_slots, 6);
+        assert_eq!(store.memory, Some(64));
+        assert_eq!(
+            store.max_stack_depth,
+            Some(store.stack_slots + 1 + find(&functions, "alloc").stack_slots)
+        );
+
+        assert_eq!(find(&functions, "$$copy").memory, None);
+        assert_eq!(find(&functions, "$$loop").memory, None);
+        assert_eq!(find(&functions, "$$countdown").max_stack_depth, None);
+        assert_eq!(find(&functions, "$$countdown").memory, None);
+    }
+
+    #[test]
+    fn stack_too_deep() {
+        let params = (0..16).map(|i| format!("p{}", i)).collect::<Vec<_>>();
+        let code = format!(
+            "{{ function $$wide({}) -> r {{ r := add(p0, p15) }} }}",
+            params.join(", ")
+        );
+        let resources = ContractResources::new("Foo", "{}", &code);
+        assert_eq!(find(&resources.runtime, "$$wide").stack_slots, 19);
+
+        let warnings = resources.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code.as_deref(), Some("stack-too-deep"));
+        assert!(warnings[0].message.contains("`$$wide` of contract `Foo`"));
+    }
+}
@@ -3,33 +3,120 @@ use crate::operations::abi as abi_operations;
 use crate::types::{to_abi_selector_names, AbiDecodeLocation, AbiType};
 use fe_abi::utils as abi_utils;
 use smol_str::SmolStr;
+use std::collections::HashMap;
 use yultsur::*;
 
 /// Builds a switch statement that dispatches calls to the contract and wraps it in
-/// a `$$__call__` function.
+/// a `$$__call__` function. If `multicall` is set, calls to
+/// `multicall(bytes[])` are dispatched to `$$__multicall`.
+///
+/// `fallback` and `receive` are the Yul names of the contract's `fallback` and
+/// `receive` functions. Calls with empty calldata go to `receive`, and calls
+/// that match no selector go to `fallback`. Without them, these calls return
+/// nothing.
 pub fn dispatcher(
     functions: &[(SmolStr, SmolStr, impl AsRef<[AbiType]>, Option<AbiType>)],
+    multicall: bool,
+    fallback: Option<&str>,
+    receive: Option<&str>,
 ) -> yul::Statement {
-    let arms = functions
+    assert_unique_selectors(functions, multicall);
+
+    let mut arms = functions
         .iter()
         .map(|(name, qname, params, ret)| dispatch_arm(name, qname, params.as_ref(), ret))
         .collect::<Vec<_>>();
+    if multicall {
+        arms.push(multicall_arm());
+    }
 
-    let dispatcher = if arms.is_empty() {
-        statement! { return(0, 0) }
+    let fallback = call_and_return(fallback);
+    let mut dispatcher = if arms.is_empty() {
+        fallback
     } else {
-        switch! {
+        vec![switch! {
             switch (cloadn(0, 4))
             [arms...]
-            (default { (return(0, 0)) })
-        }
+            (default { [fallback...] })
+        }]
     };
+    if let Some(receive) = receive {
+        let receive = call_and_return(Some(receive));
+        dispatcher.insert(
+            0,
+            statement! { if (iszero((calldatasize()))) { [receive...] } },
+        );
+    }
 
     let call_fn_ident = identifier! { ("$$__call__") };
 
     function_definition! {
         function [call_fn_ident]() {
-            [dispatcher]
+            [dispatcher...]
+        }
+    }
+}
+
+/// The selectors of the arms of a dispatcher built by [`dispatcher`], in
+/// order. Returns nothing for any other statement.
+pub fn dispatched_selectors(dispatcher: &yul::Statement) -> Vec<String> {
+    let definition = match dispatcher {
+        yul::Statement::FunctionDefinition(definition)
+            if definition.name.identifier == "$$__call__" =>
+        {
+            definition
+        }
+        _ => return vec![],
+    };
+    definition
+        .block
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            yul::Statement::Switch(switch) => Some(switch),
+            _ => None,
+        })
+        .flat_map(|switch| &switch.cases)
+        .filter_map(|case| case.literal.as_ref())
+        .map(|literal| literal.literal.clone())
+        .collect()
+}
+
+/// Calls a function that takes no arguments, if there is one, and returns
+/// nothing.
+fn call_and_return(function: Option<&str>) -> Vec<yul::Statement> {
+    match function {
+        Some(name) => {
+            let name = identifier! { (name) };
+            statements! {
+                (pop([name]()))
+                (return(0, 0))
+            }
+        }
+        None => statements! { (return(0, 0)) },
+    }
+}
+
+/// Every selector must resolve to exactly one function; two arms with the same
+/// selector would make all but one of the functions unreachable.
+///
+/// # Panics
+/// Panics if two functions have the same selector.
+fn assert_unique_selectors(
+    functions: &[(SmolStr, SmolStr, impl AsRef<[AbiType]>, Option<AbiType>)],
+    multicall: bool,
+) {
+    let mut seen = HashMap::new();
+    if multicall {
+        seen.insert(multicall_selector().to_string(), "multicall");
+    }
+    for (name, _, params, _) in functions {
+        let selector = selector(name, params.as_ref()).to_string();
+        if let Some(other) = seen.insert(selector.clone(), name.as_str()) {
+            panic!(
+                "internal consistency error: functions `{}` and `{}` have the same selector {}",
+                other, name, selector
+            )
         }
     }
 }
@@ -88,13 +175,30 @@ fn dispatch_arm(
     }
 }
 
+fn multicall_arm() -> yul::Case {
+    let selector = multicall_selector();
+    let multicall_fn_ident = identifier! { ("$$__multicall") };
+
+    case! {
+        case [selector] {
+            ([multicall_fn_ident]())
+        }
+    }
+}
+
 fn selector(name: &str, params: &[AbiType]) -> yul::Literal {
     literal! { (abi_utils::func_selector(name, &to_abi_selector_names(params))) }
 }
 
+fn multicall_selector() -> yul::Literal {
+    literal! { (abi_utils::func_selector("multicall", &["bytes[]".to_string()])) }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::runtime::abi_dispatcher::selector;
+    use crate::runtime::abi_dispatcher::{
+        dispatched_selectors, dispatcher, multicall_selector, selector,
+    };
     use crate::types::AbiType;
 
     #[test]
@@ -102,6 +206,33 @@ mod tests {
         assert_eq!(selector("foo", &[]).to_string(), String::from("0xc2985578"),)
     }
 
+    #[test]
+    #[should_panic(expected = "have the same selector")]
+    fn test_duplicate_selector() {
+        let functions = [
+            ("foo".into(), "$$foo".into(), Vec::<AbiType>::new(), None),
+            ("foo".into(), "$$foo_2".into(), Vec::<AbiType>::new(), None),
+        ];
+        dispatcher(&functions, false, None, None);
+    }
+
+    #[test]
+    fn test_dispatched_selectors() {
+        let functions = [
+            ("foo".into(), "$$foo".into(), Vec::<AbiType>::new(), None),
+            (
+                "bar".into(),
+                "$$bar".into(),
+                vec![AbiType::Uint { size: 32 }],
+                None,
+            ),
+        ];
+        assert_eq!(
+            dispatched_selectors(&dispatcher(&functions, true, None, None)),
+            vec!["0xc2985578", "0x0423a132", "0xac9650d8"]
+        )
+    }
+
     #[test]
     fn test_selector_literal() {
         assert_eq!(
@@ -109,4 +240,9 @@ mod tests {
             String::from("0x0423a132"),
         )
     }
+
+    #[test]
+    fn test_multicall_selector_literal() {
+        assert_eq!(multicall_selector().to_string(), String::from("0xac9650d8"),)
+    }
 }
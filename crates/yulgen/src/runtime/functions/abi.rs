@@ -2,6 +2,7 @@ use crate::constants::ERROR_INVALID_ABI_DATA;
 use crate::names::abi as abi_names;
 use crate::operations::abi as abi_operations;
 use crate::operations::abi::EncodingSize;
+use crate::operations::math as math_operations;
 use crate::operations::revert as revert_operations;
 use crate::types::{AbiDecodeLocation, AbiType};
 use crate::utils::ceil_32;
@@ -218,7 +219,9 @@ pub fn decode_component(typ: &AbiType, location: AbiDecodeLocation) -> yul::Stat
         AbiType::Uint { size } => decode_component_uint(*size, location),
         AbiType::Int { size } => decode_component_int(*size, location),
         AbiType::Bool => decode_component_bool(location),
+        AbiType::Enum { variant_count } => decode_component_enum(*variant_count, location),
         AbiType::Address => decode_component_address(location),
+        AbiType::FixedBytes { size } => decode_component_fixed_bytes(*size, location),
         AbiType::String { max_size } => decode_component_string(*max_size, location),
         AbiType::Bytes { size } => decode_component_bytes(*size, location),
     }
@@ -255,10 +258,43 @@ pub fn decode_component_int(size: usize, location: AbiDecodeLocation) -> yul::St
     }
 }
 
+/// Decodes a boolean. Any nonzero word is decoded as `true`, since some
+/// encoders, e.g. of contracts written in assembly, don't clean the high bits.
 pub fn decode_component_bool(location: AbiDecodeLocation) -> yul::Statement {
     let func_name = abi_names::decode_component_bool(location);
+    let decode_expr = math_operations::normalize_bool(load_word(expression! { ptr }, location));
+
+    function_definition! {
+         function [func_name](head_start, offset) -> return_val {
+            (let ptr := add(head_start, offset))
+            (return_val := [decode_expr])
+         }
+    }
+}
+
+/// Decodes an enum, which must be the index of one of its variants.
+pub fn decode_component_enum(variant_count: usize, location: AbiDecodeLocation) -> yul::Statement {
+    let func_name = abi_names::decode_component_enum(variant_count, location);
+    let decode_expr = load_word(expression! { ptr }, location);
+    let variant_count = literal_expression! { (variant_count) };
+
+    function_definition! {
+         function [func_name](head_start, offset) -> return_val {
+            (let ptr := add(head_start, offset))
+            (return_val := [decode_expr])
+            (if (iszero((lt(return_val, [variant_count])))) { [revert_with_invalid_abi_data()] })
+         }
+    }
+}
+
+/// Decodes a `bytes<size>` value, which is left-aligned in its word.
+pub fn decode_component_fixed_bytes(size: usize, location: AbiDecodeLocation) -> yul::Statement {
+    let func_name = abi_names::decode_component_fixed_bytes(size, location);
     let decode_expr = load_word(expression! { ptr }, location);
-    let check_padding = check_left_padding(expression! { 255 }, expression! { return_val });
+    let check_padding = check_right_padding(
+        literal_expression! { ((32 - size) * 8) },
+        expression! { return_val },
+    );
 
     function_definition! {
          function [func_name](head_start, offset) -> return_val {
@@ -518,7 +554,9 @@ pub fn encode(types: &[AbiType]) -> yul::Statement {
             AbiType::Uint { .. } => encode_uint(param),
             AbiType::Int { .. } => encode_uint(param),
             AbiType::Bool => encode_uint(param),
+            AbiType::Enum { .. } => encode_uint(param),
             AbiType::Address => encode_uint(param),
+            AbiType::FixedBytes { .. } => encode_uint(param),
             AbiType::String { .. } => encode_string_head(param),
             AbiType::Bytes { size } => encode_bytes_head(*size),
         })
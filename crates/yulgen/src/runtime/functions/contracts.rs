@@ -1,10 +1,24 @@
 use crate::constants::{ERROR_FAILED_SEND_VALUE, ERROR_INSUFFICIENT_FUNDS_TO_SEND_VALUE};
+use crate::names;
+use crate::names::abi as abi_names;
+use crate::operations::abi as abi_operations;
 use crate::operations::revert as revert_operations;
+use crate::types::AbiType;
+use fe_analyzer::builtins::ContractTypeMethod;
 use yultsur::*;
 
 /// Return all contacts runtime functions
 pub fn all() -> Vec<yul::Statement> {
-    vec![create2(), create(), send_value()]
+    vec![
+        create2(),
+        create(),
+        send_value(),
+        raw_call(),
+        raw_delegatecall(),
+        raw_staticcall(),
+        call_result(),
+        dbg_record(),
+    ]
 }
 
 /// Function that executes the `create2` operation.
@@ -29,6 +43,45 @@ pub fn create() -> yul::Statement {
     }
 }
 
+/// Function that creates the given contract with the arguments of its
+/// `__init__` function, which are ABI encoded and appended to the contract's
+/// creation code.
+pub fn create_with_args(
+    contract_name: &str,
+    method: ContractTypeMethod,
+    params: &[AbiType],
+) -> yul::Statement {
+    let func_name = names::contract_create(contract_name, method);
+    let (param_idents, param_exprs) = abi_names::vals("init", params.len());
+    let encoding_size = abi_operations::encoding_size(params, &param_exprs);
+    let encoding_operation = abi_operations::encode(params, param_exprs);
+    let name = literal_expression! { (format!("\"{}\"", contract_name)) };
+
+    let (mut func_params, create) = match method {
+        ContractTypeMethod::Create => (
+            vec![identifier! { value }],
+            expression! { create(value, mptr, size) },
+        ),
+        ContractTypeMethod::Create2 => (
+            vec![identifier! { value }, identifier! { salt }],
+            expression! { create2(value, mptr, size, salt) },
+        ),
+    };
+    func_params.extend(param_idents);
+
+    function_definition! {
+        function [func_name]([func_params...]) -> return_address {
+            (let code_size := datasize([name.clone()]))
+            (let mptr := alloc(code_size))
+            (datacopy(mptr, (dataoffset([name])), code_size))
+            // the arguments are encoded right after the code
+            (let size := add(code_size, [encoding_size]))
+            (pop([encoding_operation]))
+            (return_address := [create])
+        }
+    }
+}
+
 /// Function that sends wei from the contract to another address
 pub fn send_value() -> yul::Statement {
     function_definition! {
@@ -42,3 +95,122 @@ pub fn send_value() -> yul::Statement {
         }
     }
 }
+
+/// Function that calls `to_address` with the `data_size` bytes of call data at
+/// `data_ptr`, sending it `value` wei. Like `send_value`, it reverts if the
+/// contract's balance is too low, but a failing call doesn't revert.
+pub fn raw_call() -> yul::Statement {
+    function_definition! {
+        function raw_call(to_address, data_ptr, data_size, gas_limit, value, output_size) -> result {
+            (if (lt((selfbalance()), value)) { [revert_operations::error_revert_numeric(ERROR_INSUFFICIENT_FUNDS_TO_SEND_VALUE)] })
+            (let success := call(gas_limit, to_address, value, data_ptr, data_size, 0, 0))
+            (result := call_result(success, output_size))
+        }
+    }
+}
+
+/// Function that runs the code of `to_address` with the `data_size` bytes of
+/// call data at `data_ptr`, in the context of this contract.
+pub fn raw_delegatecall() -> yul::Statement {
+    function_definition! {
+        function raw_delegatecall(to_address, data_ptr, data_size, gas_limit, output_size) -> result {
+            (let success := delegatecall(gas_limit, to_address, data_ptr, data_size, 0, 0))
+            (result := call_result(success, output_size))
+        }
+    }
+}
+
+/// Function that calls `to_address` with the `data_size` bytes of call data at
+/// `data_ptr`, without allowing it to modify any state.
+pub fn raw_staticcall() -> yul::Statement {
+    function_definition! {
+        function raw_staticcall(to_address, data_ptr, data_size, gas_limit, output_size) -> result {
+            (let success := staticcall(gas_limit, to_address, data_ptr, data_size, 0, 0))
+            (result := call_result(success, output_size))
+        }
+    }
+}
+
+/// Function that builds the `(bool, Array<u8, N>)` result of a low-level call
+/// from its success flag. Up to `output_size` bytes of the return data are
+/// copied to a new array, whose remaining bytes are zero.
+pub fn call_result() -> yul::Statement {
+    function_definition! {
+        function call_result(success, output_size) -> result {
+            (let output := alloc(output_size))
+            // Memory above the free memory pointer may have been used as
+            // scratch space, so the array is zeroed by copying from beyond
+            // the end of the call data.
+            (calldatacopy(output, (calldatasize()), output_size))
+            (let copy_size := returndatasize())
+            (if (gt(copy_size, output_size)) { (copy_size := output_size) })
+            (returndatacopy(output, 0, copy_size))
+            (result := alloc(64))
+            (mstore(result, success))
+            (mstore((add(result, 32)), output))
+        }
+    }
+}
+
+/// Function that records a `dbg` value for the test runner, by calling the
+/// recorder address with the kind of the value, the value itself and the file
+/// id and offsets of its source. The call is only made in code compiled by
+/// `fe test`, whose EVM intercepts it, and its failure is ignored.
+pub fn dbg_record() -> yul::Statement {
+    function_definition! {
+        function dbg_record(value, file, start, end, kind) -> result {
+            (let ptr := alloc(160))
+            (mstore(ptr, kind))
+            (mstore((add(ptr, 32)), value))
+            (mstore((add(ptr, 64)), file))
+            (mstore((add(ptr, 96)), start))
+            (mstore((add(ptr, 128)), end))
+            (pop((staticcall((gas()), 0x00000000000000000000000000000000000fe0db, ptr, 160, 0, 0))))
+            (result := value)
+        }
+    }
+}
+
+/// The generated `multicall(bytes[])` function of a `multicall` contract.
+///
+/// Each item of the batch is calldata for a call to the contract itself,
+/// which is made with `delegatecall` so that `msg.sender` is preserved. The
+/// results are returned as `bytes[]`, and the first failing call reverts the
+/// batch with its own revert data. This isn't part of `all()`, as it's only
+/// included in the runtime of `multicall` contracts.
+pub fn multicall() -> yul::Statement {
+    let multicall_fn_ident = identifier! { ("$$__multicall") };
+
+    function_definition! {
+        function [multicall_fn_ident]() {
+            // the `bytes[]` argument, which starts with its length
+            (let data := add(4, (calldataload(4))))
+            (let count := calldataload(data))
+            (let heads := add(data, 32))
+            (let out := alloc((add(64, (mul(count, 32))))))
+            (mstore(out, 32))
+            (mstore((add(out, 32)), count))
+            (let offsets := add(out, 64))
+            (let i := 0)
+            (for {} (lt(i, count)) {(i := add(i, 1))}
+            {
+                (let item := add(heads, (calldataload((add(heads, (mul(i, 32))))))))
+                (let size := calldataload(item))
+                // the input is overwritten by the result, so it isn't allocated
+                (let input := avail())
+                (calldatacopy(input, (add(item, 32)), size))
+                (if (iszero((delegatecall((gas()), (address()), input, size, 0, 0)))) {
+                    (returndatacopy(input, 0, (returndatasize())))
+                    (revert(input, (returndatasize())))
+                })
+                (let result := alloc((add(32, (ceil32((returndatasize())))))))
+                (mstore((add(offsets, (mul(i, 32)))), (sub(result, offsets))))
+                // zero the padding of the last word before copying the result
+                (mstore((add(result, (ceil32((returndatasize()))))), 0))
+                (mstore(result, (returndatasize())))
+                (returndatacopy((add(result, 32)), 0, (returndatasize())))
+            })
+            (return(out, (sub((avail()), out))))
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::constants::PANIC_OUT_OF_BOUNDS;
+use crate::constants::{PANIC_EMPTY_ARRAY_POP, PANIC_INVALID_ENUM_VALUE, PANIC_OUT_OF_BOUNDS};
 use crate::operations::revert as revert_operations;
 
 use yultsur::*;
@@ -17,19 +17,37 @@ pub fn all() -> Vec<yul::Statement> {
         ccopym(),
         ceil32(),
         cloadn(),
+        dyn_array_data_ptr(),
+        dyn_array_pop(),
+        dyn_array_push(),
         free(),
+        mfill(),
+        mindex_of(),
         get_array_item(),
+        get_dyn_array_item(),
+        get_fixed_byte(),
         load_data_string(),
         map_value_ptr(),
+        mbinary_search(),
+        mcopy_from(),
         mcopym(),
         mcopys(),
         mloadn(),
+        msort(),
         mstoren(),
+        raw_sstore(),
+        scopy_from(),
         scopym(),
         scopys(),
         set_zero(),
+        sfill(),
+        sindex_of(),
         sloadn(),
         sstoren(),
+        string_concat(),
+        string_eq(),
+        string_slice(),
+        to_enum(),
     ]
 }
 
@@ -54,6 +72,17 @@ pub fn alloc() -> yul::Statement {
     }
 }
 
+/// Stores a word at the given storage slot.
+pub fn raw_sstore() -> yul::Statement {
+    function_definition! {
+        function raw_sstore(slot, value) -> result {
+            (sstore(slot, value))
+            // raw_sstore returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
 /// Set the highest available pointer.
 pub fn free() -> yul::Statement {
     function_definition! {
@@ -371,6 +400,76 @@ pub fn load_data_string() -> yul::Statement {
     }
 }
 
+/// Concatenates two strings in memory into a newly allocated string.
+pub fn string_concat() -> yul::Statement {
+    function_definition! {
+        function string_concat(a, b) -> mptr {
+            (let a_size := mload(a))
+            (let b_size := mload(b))
+            (mptr := alloc(32))
+            (mstore(mptr, (add(a_size, b_size))))
+            // The contents are allocated right after the size.
+            (pop((mcopym((add(a, 32)), a_size))))
+            (pop((mcopym((add(b, 32)), b_size))))
+        }
+    }
+}
+
+/// Copies the bytes of a string in memory from `start` up to `end` into a
+/// newly allocated string.
+/// Reverts with a panic if the bounds are out of order or past the end of the
+/// string.
+pub fn string_slice() -> yul::Statement {
+    function_definition! {
+        function string_slice(ptr, start, end) -> mptr {
+            (if (or((gt(start, end)), (gt(end, (mload(ptr)))))) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            } )
+            (mptr := alloc(32))
+            (mstore(mptr, (sub(end, start))))
+            (pop((mcopym((add((add(ptr, 32)), start)), (sub(end, start))))))
+        }
+    }
+}
+
+/// Returns `1` if two strings in memory have the same size and bytes.
+pub fn string_eq() -> yul::Statement {
+    function_definition! {
+        function string_eq(a, b) -> result {
+            (let size := mload(a))
+            (if (eq(size, (mload(b)))) {
+                (result := eq((keccak256((add(a, 32)), size)), (keccak256((add(b, 32)), size))))
+            })
+        }
+    }
+}
+
+/// Returns the byte of a fixed-size bytes value at the given index.
+/// Reverts with a panic if the index is out of bounds.
+pub fn get_fixed_byte() -> yul::Statement {
+    function_definition! {
+        function get_fixed_byte(value, size, index) -> return_val {
+            (if (iszero((lt(index, size)))) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            } )
+            (return_val := byte(index, value))
+        }
+    }
+}
+
+/// Converts an integer to an enum value, which is its variant index.
+/// Reverts with a panic if there is no variant with the index.
+pub fn to_enum() -> yul::Statement {
+    function_definition! {
+        function to_enum(value, variant_count) -> return_val {
+            (if (iszero((lt(value, variant_count)))) {
+                [revert_operations::panic_revert(PANIC_INVALID_ENUM_VALUE)]
+            } )
+            (return_val := value)
+        }
+    }
+}
+
 /// Returns a pointer to the array item at the requested index.
 /// Reverts with a panic if the index is out of bounds.
 pub fn get_array_item() -> yul::Statement {
@@ -383,3 +482,223 @@ pub fn get_array_item() -> yul::Statement {
         }
     }
 }
+
+/// Derives the byte address of the first item of a dynamically-sized array in
+/// storage, whose length is stored at the given address.
+///
+/// Like in Solidity, the items start at the word whose address is the hash of
+/// the address of the length. The address is always divisible by 32.
+pub fn dyn_array_data_ptr() -> yul::Statement {
+    function_definition! {
+        function dyn_array_data_ptr(array_ptr) -> ptr {
+            (let mptr := avail())
+            (mstore(mptr, (div(array_ptr, 32))))
+            (ptr := set_zero(248, 256, (keccak256(mptr, 32))))
+        }
+    }
+}
+
+/// Returns a pointer to the item of a dynamically-sized array in storage at
+/// the requested index.
+/// Reverts with a panic if the index is out of bounds.
+pub fn get_dyn_array_item() -> yul::Statement {
+    function_definition! {
+        function get_dyn_array_item(array_ptr, index, inner_size) -> ptr {
+            (if (iszero((lt(index, (sload((div(array_ptr, 32)))))))) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            } )
+            (ptr := add((dyn_array_data_ptr(array_ptr)), (mul(index, inner_size))))
+        }
+    }
+}
+
+/// Appends a value to a dynamically-sized array in storage.
+///
+/// The value is truncated to the item size, so that negative numbers don't
+/// overwrite the items stored before them in the same word.
+pub fn dyn_array_push() -> yul::Statement {
+    function_definition! {
+        function dyn_array_push(array_ptr, inner_size, val) -> result {
+            (let item := and(val, (sub((shl((mul(8, inner_size)), 1)), 1))))
+            (let length_ptr := div(array_ptr, 32))
+            (let length := sload(length_ptr))
+            (sstore(length_ptr, (add(length, 1))))
+            (let item_ptr := add((dyn_array_data_ptr(array_ptr)), (mul(length, inner_size))))
+            (bytes_sstoren(item_ptr, inner_size, item))
+            // `push` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Removes the last item of a dynamically-sized array in storage and returns
+/// it. The storage of the item is cleared.
+/// Reverts with a panic if the array is empty.
+pub fn dyn_array_pop() -> yul::Statement {
+    function_definition! {
+        function dyn_array_pop(array_ptr, inner_size) -> val {
+            (let length_ptr := div(array_ptr, 32))
+            (let length := sload(length_ptr))
+            (if (iszero(length)) {
+                [revert_operations::panic_revert(PANIC_EMPTY_ARRAY_POP)]
+            } )
+            (length := sub(length, 1))
+            (let item_ptr := add((dyn_array_data_ptr(array_ptr)), (mul(length, inner_size))))
+            (val := bytes_sloadn(item_ptr, inner_size))
+            (bytes_sstoren(item_ptr, inner_size, 0))
+            (sstore(length_ptr, length))
+        }
+    }
+}
+
+/// Returns the index of the first item of an array in memory that is equal to
+/// the given value, or the array length if there is no such item.
+///
+/// The value is truncated to the item size, so that negative numbers match
+/// the items they were stored as.
+pub fn mindex_of() -> yul::Statement {
+    function_definition! {
+        function mindex_of(array_ptr, array_length, inner_size, val) -> index {
+            (let item := and(val, (sub((shl((mul(8, inner_size)), 1)), 1))))
+            (for {} (lt(index, array_length)) {(index := add(index, 1))}
+            {
+                (if (eq((mloadn((add(array_ptr, (mul(index, inner_size)))), inner_size)), item)) { (leave) })
+            })
+        }
+    }
+}
+
+/// Returns the index of the first item of an array in storage that is equal to
+/// the given value, or the array length if there is no such item.
+///
+/// The array pointer addresses a byte.
+pub fn sindex_of() -> yul::Statement {
+    function_definition! {
+        function sindex_of(array_ptr, array_length, inner_size, val) -> index {
+            (let item := and(val, (sub((shl((mul(8, inner_size)), 1)), 1))))
+            (for {} (lt(index, array_length)) {(index := add(index, 1))}
+            {
+                (if (eq((bytes_sloadn((add(array_ptr, (mul(index, inner_size)))), inner_size)), item)) { (leave) })
+            })
+        }
+    }
+}
+
+/// Sets every item of an array in memory to the given value.
+pub fn mfill() -> yul::Statement {
+    function_definition! {
+        function mfill(array_ptr, array_length, inner_size, val) -> result {
+            (for {(let index := 0)} (lt(index, array_length)) {(index := add(index, 1))}
+            {
+                (mstoren((add(array_ptr, (mul(index, inner_size)))), inner_size, val))
+            })
+            // `fill` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Sets every item of an array in storage to the given value.
+///
+/// The array pointer addresses a byte.
+pub fn sfill() -> yul::Statement {
+    function_definition! {
+        function sfill(array_ptr, array_length, inner_size, val) -> result {
+            (for {(let index := 0)} (lt(index, array_length)) {(index := add(index, 1))}
+            {
+                (bytes_sstoren((add(array_ptr, (mul(index, inner_size)))), inner_size, val))
+            })
+            // `fill` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Copies an array in memory into an existing array in memory.
+pub fn mcopy_from() -> yul::Statement {
+    function_definition! {
+        function mcopy_from(array_ptr, source_ptr, size) -> result {
+            (let offset := 0)
+            (for {} (lt((add(offset, 32)), size)) {}
+            {
+                (mstore((add(array_ptr, offset)), (mload((add(source_ptr, offset))))))
+                (offset := add(offset, 32))
+            })
+
+            (let rem := sub(size, offset))
+            (if (gt(rem, 0)) {
+                (mstoren((add(array_ptr, offset)), rem, (mloadn((add(source_ptr, offset)), rem))))
+            })
+            // `copy_from` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Copies an array in memory into an array in storage.
+///
+/// The array pointer addresses a byte.
+pub fn scopy_from() -> yul::Statement {
+    function_definition! {
+        function scopy_from(array_ptr, source_ptr, size) -> result {
+            (bytes_mcopys(source_ptr, array_ptr, size))
+            // `copy_from` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Sorts an array in memory in ascending order, in place.
+///
+/// Items are compared as unsigned numbers after flipping `sign_bit`, which is
+/// the highest bit of an item for signed integers and 0 otherwise.
+pub fn msort() -> yul::Statement {
+    function_definition! {
+        function msort(array_ptr, array_length, inner_size, sign_bit) -> result {
+            (for {(let i := 1)} (lt(i, array_length)) {(i := add(i, 1))}
+            {
+                (let item := mloadn((add(array_ptr, (mul(i, inner_size)))), inner_size))
+                (let key := xor(item, sign_bit))
+                // shift the larger items before `i` to the right, then insert
+                // the item in the gap
+                (let j := i)
+                (for {} (gt(j, 0)) {(j := sub(j, 1))}
+                {
+                    (let prev_ptr := add(array_ptr, (mul((sub(j, 1)), inner_size))))
+                    (let prev := mloadn(prev_ptr, inner_size))
+                    (if (iszero((gt((xor(prev, sign_bit)), key)))) { (break) })
+                    (mstoren((add(prev_ptr, inner_size)), inner_size, prev))
+                })
+                (mstoren((add(array_ptr, (mul(j, inner_size)))), inner_size, item))
+            })
+            // `sort` returns the unit type but we need to return something.
+            (result := 0x0)
+        }
+    }
+}
+
+/// Returns the index of an item of a sorted array in memory that is equal to
+/// the given value, or the array length if there is no such item.
+///
+/// Items are compared like in `msort`.
+pub fn mbinary_search() -> yul::Statement {
+    function_definition! {
+        function mbinary_search(array_ptr, array_length, inner_size, sign_bit, val) -> index {
+            (let key := xor((and(val, (sub((shl((mul(8, inner_size)), 1)), 1)))), sign_bit))
+            (let low := 0)
+            (let high := array_length)
+            (for {} (lt(low, high)) {}
+            {
+                (let mid := shr(1, (add(low, high))))
+                (let item := xor((mloadn((add(array_ptr, (mul(mid, inner_size)))), inner_size)), sign_bit))
+                (if (eq(item, key)) {
+                    (index := mid)
+                    (leave)
+                })
+                (if (lt(item, key)) { (low := add(mid, 1)) })
+                (if (gt(item, key)) { (high := mid) })
+            })
+            (index := array_length)
+        }
+    }
+}
@@ -50,6 +50,18 @@ pub fn checked_div_fns() -> Vec<yul::Statement> {
     ]
 }
 
+/// Return a vector of runtime functions for divisions with explicit rounding
+pub fn rounding_div_fns() -> Vec<yul::Statement> {
+    vec![
+        div_floor_unsigned(),
+        div_ceil_unsigned(),
+        div_round_unsigned(),
+        div_floor_signed(),
+        div_ceil_signed(),
+        div_round_signed(),
+    ]
+}
+
 /// Return a vector of runtime functions for exponentiation with over-/underflow
 /// protection
 pub fn checked_exp_fns() -> Vec<yul::Statement> {
@@ -118,11 +130,11 @@ pub fn adjust_numeric_size_fns() -> Vec<yul::Statement> {
         adjust_numeric_unsigned(Integer::U32),
         adjust_numeric_unsigned(Integer::U16),
         adjust_numeric_unsigned(Integer::U8),
-        adjust_numeric_signed(Integer::I128, 15),
-        adjust_numeric_signed(Integer::I64, 7),
-        adjust_numeric_signed(Integer::I32, 3),
-        adjust_numeric_signed(Integer::I16, 1),
-        adjust_numeric_signed(Integer::I8, 0),
+        adjust_numeric_signed(Integer::I128),
+        adjust_numeric_signed(Integer::I64),
+        adjust_numeric_signed(Integer::I32),
+        adjust_numeric_signed(Integer::I16),
+        adjust_numeric_signed(Integer::I8),
     ]
 }
 
@@ -131,12 +143,14 @@ pub fn all() -> Vec<yul::Statement> {
     [
         checked_add_fns(),
         checked_div_fns(),
+        rounding_div_fns(),
         checked_exp_fns(),
         checked_mod_fns(),
         checked_mul_fns(),
         checked_sub_fns(),
         checked_neg_fns(),
         adjust_numeric_size_fns(),
+        vec![mul_div(), mul_div_ceil(), mul_div_round()],
     ]
     .concat()
 }
@@ -189,6 +203,16 @@ fn checked_mul_unsigned(size: Integer) -> yul::Statement {
     let fn_name = names::checked_mul(&size);
     let max_value = get_max(size);
 
+    if size.size() <= 16 {
+        // the product of two values of at most 128 bits can't overflow 256 bits
+        return function_definition! {
+            function [fn_name](val1, val2) -> product {
+                (product := mul(val1, val2))
+                (if (gt(product, [max_value])) { [revert_with_over_or_under_flow()] })
+            }
+        };
+    }
+
     function_definition! {
         function [fn_name](val1, val2) -> product {
             // overflow, if val1 != 0 and val2 > (max_value / val1)
@@ -205,6 +229,15 @@ fn checked_mul_signed(size: Integer) -> yul::Statement {
     let fn_name = names::checked_mul(&size);
     let (min_value, max_value) = get_min_max(size);
 
+    if size != Integer::I256 {
+        return function_definition! {
+            function [fn_name](val1, val2) -> product {
+                (product := mul(val1, val2))
+                ([revert_unless_sign_extended(size, "product")])
+            }
+        };
+    }
+
     function_definition! {
         function [fn_name](val1, val2) -> product {
             // overflow, if val1 > 0, val2 > 0 and val1 > (max_value / val2)
@@ -227,6 +260,15 @@ fn checked_add_unsigned(size: Integer) -> yul::Statement {
     let fn_name = names::checked_add(&size);
     let max_value = get_max(size);
 
+    if size != Integer::U256 {
+        return function_definition! {
+            function [fn_name](val1, val2) -> sum {
+                (sum := add(val1, val2))
+                (if (gt(sum, [max_value])) { [revert_with_over_or_under_flow()] })
+            }
+        };
+    }
+
     function_definition! {
         function [fn_name](val1, val2) -> sum {
             // overflow, if val1 > (max_value - val2)
@@ -242,6 +284,16 @@ fn checked_add_signed(size: Integer) -> yul::Statement {
     }
     let (min_value, max_value) = get_min_max(size);
     let fn_name = names::checked_add(&size);
+
+    if size != Integer::I256 {
+        return function_definition! {
+            function [fn_name](val1, val2) -> sum {
+                (sum := add(val1, val2))
+                ([revert_unless_sign_extended(size, "sum")])
+            }
+        };
+    }
+
     function_definition! {
         function [fn_name](val1, val2) -> sum {
             // overflow, if val1 >= 0 and val2 > (max_value - val1)
@@ -262,6 +314,176 @@ fn checked_div_unsigned() -> yul::Statement {
     }
 }
 
+fn div_floor_unsigned() -> yul::Statement {
+    function_definition! {
+        function div_floor_unsigned(val1, val2) -> result {
+            (result := checked_div_unsigned(val1, val2))
+        }
+    }
+}
+
+fn div_ceil_unsigned() -> yul::Statement {
+    function_definition! {
+        function div_ceil_unsigned(val1, val2) -> result {
+            (result := checked_div_unsigned(val1, val2))
+            (if (mod(val1, val2)) { (result := add(result, 1)) })
+        }
+    }
+}
+
+fn div_round_unsigned() -> yul::Statement {
+    function_definition! {
+        function div_round_unsigned(val1, val2) -> result {
+            (result := checked_div_unsigned(val1, val2))
+            // round half up: `rem >= val2 - rem`
+            (let rem := mod(val1, val2))
+            (if (iszero((lt(rem, (sub(val2, rem)))))) { (result := add(result, 1)) })
+        }
+    }
+}
+
+// The signed rounding functions take the minimum value of the integer type to
+// detect the `min / -1` overflow. `sdiv` truncates towards zero, so the
+// quotient is adjusted by one depending on the signs of the remainder (which
+// has the sign of the dividend) and the divisor.
+
+fn div_floor_signed() -> yul::Statement {
+    function_definition! {
+        function div_floor_signed(val1, val2, min) -> result {
+            (if (iszero(val2)) { [revert_with_div_or_mod_by_zero()] })
+            (if (and((eq(val1, min)), (eq(val2, (sub(0, 1)))))) { [revert_with_over_or_under_flow()] })
+            (result := sdiv(val1, val2))
+            (let rem := smod(val1, val2))
+            // the exact quotient is negative and was rounded up
+            (if (and((iszero((iszero(rem)))), (xor((slt(rem, 0)), (slt(val2, 0)))))) {
+                (result := sub(result, 1))
+            })
+        }
+    }
+}
+
+fn div_ceil_signed() -> yul::Statement {
+    function_definition! {
+        function div_ceil_signed(val1, val2, min) -> result {
+            (if (iszero(val2)) { [revert_with_div_or_mod_by_zero()] })
+            (if (and((eq(val1, min)), (eq(val2, (sub(0, 1)))))) { [revert_with_over_or_under_flow()] })
+            (result := sdiv(val1, val2))
+            (let rem := smod(val1, val2))
+            // the exact quotient is positive and was rounded down
+            (if (and((iszero((iszero(rem)))), (eq((slt(rem, 0)), (slt(val2, 0)))))) {
+                (result := add(result, 1))
+            })
+        }
+    }
+}
+
+fn div_round_signed() -> yul::Statement {
+    function_definition! {
+        function div_round_signed(val1, val2, min) -> result {
+            (if (iszero(val2)) { [revert_with_div_or_mod_by_zero()] })
+            (if (and((eq(val1, min)), (eq(val2, (sub(0, 1)))))) { [revert_with_over_or_under_flow()] })
+            (result := sdiv(val1, val2))
+            (let rem := smod(val1, val2))
+            // round half away from zero: `|rem| >= |val2| - |rem|`, compared as
+            // unsigned values so that `|min|` doesn't overflow
+            (let abs_rem := rem)
+            (if (slt(rem, 0)) { (abs_rem := sub(0, rem)) })
+            (let abs_val2 := val2)
+            (if (slt(val2, 0)) { (abs_val2 := sub(0, val2)) })
+            (if (iszero((lt(abs_rem, (sub(abs_val2, abs_rem)))))) {
+                ([switch! {
+                    switch (xor((slt(rem, 0)), (slt(val2, 0))))
+                    (case 0 { (result := add(result, 1)) })
+                    (case 1 { (result := sub(result, 1)) })
+                }])
+            })
+        }
+    }
+}
+
+/// Computes `floor(a * b / denominator)` without overflowing in the
+/// intermediate product, by computing the full 512 bit product and dividing it
+/// by `denominator`. Reverts if `denominator` is zero or the result doesn't fit
+/// in 256 bits.
+///
+/// Based on Remco Bloemen's `mulDiv` (<https://xn--2-umb.com/21/muldiv>).
+fn mul_div() -> yul::Statement {
+    function_definition! {
+        function mul_div(a, b, denominator) -> result {
+            (if (iszero(denominator)) { [revert_with_div_or_mod_by_zero()] })
+
+            // 512 bit product of `a * b`, as `prod1 * 2**256 + prod0`
+            (let mm := mulmod(a, b, (not(0))))
+            (let prod0 := mul(a, b))
+            (let prod1 := sub((sub(mm, prod0)), (lt(mm, prod0))))
+
+            // the product fits in 256 bits, so a plain division is exact
+            (if (iszero(prod1)) {
+                (result := div(prod0, denominator))
+                (leave)
+            })
+
+            // the result must fit in 256 bits
+            (if (iszero((gt(denominator, prod1)))) { [revert_with_over_or_under_flow()] })
+
+            // make the division exact by subtracting the remainder
+            (let remainder := mulmod(a, b, denominator))
+            (prod1 := sub(prod1, (gt(remainder, prod0))))
+            (prod0 := sub(prod0, remainder))
+
+            // divide out the largest power of two factor of the denominator
+            (let twos := and((sub(0, denominator)), denominator))
+            (let odd_denominator := div(denominator, twos))
+            (prod0 := div(prod0, twos))
+            (twos := add((div((sub(0, twos)), twos)), 1))
+            (prod0 := or(prod0, (mul(prod1, twos))))
+
+            // the odd denominator is invertible modulo 2**256; start with an
+            // inverse correct to 4 bits and double the precision with each
+            // Newton-Raphson step until it is correct to 256 bits
+            (let inv := xor((mul(3, odd_denominator)), 2))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+            (inv := mul(inv, (sub(2, (mul(odd_denominator, inv))))))
+
+            (result := mul(prod0, inv))
+        }
+    }
+}
+
+// `mul_div` rounds down. These round the quotient up, or to the nearest
+// integer with halves rounded up, like `div_ceil_unsigned` and
+// `div_round_unsigned`, and revert if rounding up overflows.
+
+fn mul_div_ceil() -> yul::Statement {
+    function_definition! {
+        function mul_div_ceil(a, b, denominator) -> result {
+            (result := mul_div(a, b, denominator))
+            (if (mulmod(a, b, denominator)) {
+                (if (iszero((not(result)))) { [revert_with_over_or_under_flow()] })
+                (result := add(result, 1))
+            })
+        }
+    }
+}
+
+fn mul_div_round() -> yul::Statement {
+    function_definition! {
+        function mul_div_round(a, b, denominator) -> result {
+            (result := mul_div(a, b, denominator))
+            // round half up: `rem >= denominator - rem`
+            (let rem := mulmod(a, b, denominator))
+            (if (iszero((lt(rem, (sub(denominator, rem)))))) {
+                (if (iszero((not(result)))) { [revert_with_over_or_under_flow()] })
+                (result := add(result, 1))
+            })
+        }
+    }
+}
+
 fn checked_div_signed(size: Integer) -> yul::Statement {
     if !size.is_signed() {
         panic!("Expected signed integer")
@@ -296,6 +518,15 @@ fn checked_sub_signed(size: Integer) -> yul::Statement {
     let fn_name = names::checked_sub(&size);
     let (min_value, max_value) = get_min_max(size);
 
+    if size != Integer::I256 {
+        return function_definition! {
+            function [fn_name](val1, val2) -> diff {
+                (diff := sub(val1, val2))
+                ([revert_unless_sign_extended(size, "diff")])
+            }
+        };
+    }
+
     function_definition! {
         function [fn_name](val1, val2) -> diff {
             // underflow, if val2 >= 0 and val1 < (min_value + val2)
@@ -346,14 +577,34 @@ fn adjust_numeric_unsigned(size: Integer) -> yul::Statement {
     }
 }
 
-fn adjust_numeric_signed(size: Integer, base: usize) -> yul::Statement {
+fn adjust_numeric_signed(size: Integer) -> yul::Statement {
     if !size.is_signed() {
         panic!("Expected signed integer")
     }
     let fn_name = names::adjust_numeric_size(&size);
     function_definition! {
         function [fn_name](value) -> cleaned {
-            (cleaned := signextend([literal_expression! { (base) }], value))
+            (cleaned := signextend([sign_byte(size)], value))
+        }
+    }
+}
+
+/// The index of the byte holding the sign bit of a signed integer that is
+/// narrower than 256 bits, as used by `signextend`.
+fn sign_byte(size: Integer) -> yul::Expression {
+    literal_expression! { (size.size() - 1) }
+}
+
+/// Reverts if `value`, the full-width result of an operation on two values of
+/// the given signed type, doesn't fit in the type. The operands of `add`,
+/// `sub` and `mul` on integers narrower than 256 bits can't overflow 256 bits,
+/// so the result fits iff sign extending it from the type's sign bit doesn't
+/// change it.
+fn revert_unless_sign_extended(size: Integer, value: &str) -> yul::Statement {
+    let value = identifier_expression! { (value) };
+    statement! {
+        if (iszero((eq([value.clone()], (signextend([sign_byte(size)], [value])))))) {
+            [revert_with_over_or_under_flow()]
         }
     }
 }
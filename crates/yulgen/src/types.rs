@@ -1,5 +1,5 @@
 use fe_analyzer::namespace::types::{
-    Array, Base, Contract, FeString, FixedSize, Integer, Struct, Tuple,
+    Array, Base, Contract, Enum, FeString, FixedSize, Integer, Struct, Tuple,
 };
 use fe_analyzer::AnalyzerDb;
 
@@ -16,7 +16,10 @@ impl EvmSized for FixedSize {
             FixedSize::Tuple(tuple) => tuple.size(),
             FixedSize::String(string) => string.size(),
             FixedSize::Contract(contract) => contract.size(),
+            FixedSize::Enum(val) => val.size(),
             FixedSize::Struct(val) => val.size(),
+            FixedSize::Option(_) => panic!("`Option` types should be lowered"),
+            FixedSize::Result(_) => panic!("`Result` types should be lowered"),
         }
     }
 }
@@ -46,11 +49,18 @@ impl EvmSized for Base {
             Base::Numeric(integer) => integer.size(),
             Base::Bool => 1,
             Base::Address => 32,
+            Base::FixedBytes(_) => 32,
             Base::Unit => 0,
         }
     }
 }
 
+impl EvmSized for Enum {
+    fn size(&self) -> usize {
+        1
+    }
+}
+
 impl EvmSized for Array {
     fn size(&self) -> usize {
         self.size * self.inner.size()
@@ -84,14 +94,35 @@ impl EvmSized for Contract {
 /// Solidity ABI type with extra information needed for generation encoding/decoding functions.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
 pub enum AbiType {
-    StaticArray { inner: Box<AbiType>, size: usize },
-    Tuple { components: Vec<AbiType> },
-    Uint { size: usize },
-    Int { size: usize },
+    StaticArray {
+        inner: Box<AbiType>,
+        size: usize,
+    },
+    Tuple {
+        components: Vec<AbiType>,
+    },
+    Uint {
+        size: usize,
+    },
+    Int {
+        size: usize,
+    },
     Bool,
     Address,
-    String { max_size: usize },
-    Bytes { size: usize },
+    /// An enum, encoded as the `uint8` index of its variant.
+    Enum {
+        variant_count: usize,
+    },
+    /// `bytes1` to `bytes32`
+    FixedBytes {
+        size: usize,
+    },
+    String {
+        max_size: usize,
+    },
+    Bytes {
+        size: usize,
+    },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
@@ -118,6 +149,8 @@ impl AbiType {
             AbiType::Int { .. } => 32,
             AbiType::Bool => 32,
             AbiType::Address => 32,
+            AbiType::Enum { .. } => 32,
+            AbiType::FixedBytes { .. } => 32,
             AbiType::String { .. } => 32,
             AbiType::Bytes { .. } => 32,
         }
@@ -130,7 +163,9 @@ impl AbiType {
             AbiType::Uint { size } => size,
             AbiType::Int { size } => size,
             AbiType::Bool => 1,
+            AbiType::Enum { .. } => 1,
             AbiType::Address => 32,
+            AbiType::FixedBytes { .. } => 32,
             _ => todo!("recursive encoding"),
         }
     }
@@ -143,7 +178,9 @@ impl AbiType {
             AbiType::Tuple { .. } => false,
             AbiType::Int { .. } => false,
             AbiType::Bool => false,
+            AbiType::Enum { .. } => false,
             AbiType::Address => false,
+            AbiType::FixedBytes { .. } => false,
             AbiType::String { .. } => true,
             AbiType::Bytes { .. } => true,
         }
@@ -164,6 +201,8 @@ impl AbiType {
             AbiType::Int { size } => format!("int{}", 8 * size),
             AbiType::Bool => "bool".to_string(),
             AbiType::Address => "address".to_string(),
+            AbiType::Enum { .. } => "uint8".to_string(),
+            AbiType::FixedBytes { size } => format!("bytes{}", size),
             AbiType::String { .. } => "string".to_string(),
             AbiType::Bytes { .. } => "bytes".to_string(),
         }
@@ -182,7 +221,12 @@ impl AsAbiType for FixedSize {
             FixedSize::Tuple(tuple) => tuple.as_abi_type(db),
             FixedSize::String(string) => string.as_abi_type(db),
             FixedSize::Contract(_) => AbiType::Address,
+            FixedSize::Enum(val) => AbiType::Enum {
+                variant_count: val.variant_count,
+            },
             FixedSize::Struct(val) => val.as_abi_type(db),
+            FixedSize::Option(_) => panic!("`Option` types should be lowered"),
+            FixedSize::Result(_) => panic!("`Result` types should be lowered"),
         }
     }
 }
@@ -200,6 +244,7 @@ impl AsAbiType for Base {
             }
             Base::Address => AbiType::Address,
             Base::Bool => AbiType::Bool,
+            Base::FixedBytes(size) => AbiType::FixedBytes { size: *size },
             Base::Unit => panic!("unit type is not abi encodable"),
         }
     }
@@ -47,7 +47,11 @@ fn functions() -> Vec<(SmolStr, SmolStr, Vec<AbiType>, Option<AbiType>)> {
 }
 
 // ABI dispatcher
-test_yulgen! { abi_dispatcher,  abi_dispatcher::dispatcher(&functions()) }
+test_yulgen! { abi_dispatcher,  abi_dispatcher::dispatcher(&functions(), false, None, None) }
+test_yulgen! {
+    abi_dispatcher_fallback_receive,
+    abi_dispatcher::dispatcher(&functions(), false, Some("$$somemod$fallback"), Some("$$somemod$receive"))
+}
 
 // ABI encoding functions
 test_yulgen! {
@@ -120,6 +124,14 @@ test_yulgen! {
     sum_operation,
     data_operations::sum(expressions! { 42 26 99 })
 }
+test_yulgen! {
+    sstore_word_operation,
+    data_operations::sstore_word(expression! { ptr }, expression! { val })
+}
+test_yulgen! {
+    sstore_word_literal_ptr_operation,
+    data_operations::sstore_word(expression! { 0x40 }, expression! { 255 })
+}
 
 // ABI operations
 test_yulgen! {